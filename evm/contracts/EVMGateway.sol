@@ -38,6 +38,13 @@ contract EVMGateway is ReentrancyGuard, Pausable, AccessControl {
     // ============ State Variables ============
     
     address public coordinator;
+    
+    // Coordinator key rotated out by setCoordinator, accepted until
+    // previousCoordinatorValidUntil so in-flight authorizations still execute
+    address public previousCoordinator;
+    uint256 public previousCoordinatorValidUntil;
+    uint256 public coordinatorOverlap = 1 hours;
+    
     uint256 public depositNonce;
     uint256 public withdrawalNonce;
     uint256 public bridgeFee = 30; // 0.3% in basis points
@@ -70,6 +77,7 @@ contract EVMGateway is ReentrancyGuard, Pausable, AccessControl {
         bytes32 zcashAddress;
         uint256 timestamp;
         bool processed;
+        bool refunded;
     }
     
     struct WithdrawalRequestInfo {
@@ -114,6 +122,14 @@ contract EVMGateway is ReentrancyGuard, Pausable, AccessControl {
         uint256 timestamp
     );
     
+    event DepositRefunded(
+        bytes32 indexed depositId,
+        address indexed sender,
+        address indexed token,
+        uint256 amount,
+        uint256 timestamp
+    );
+    
     event LiquidityAdded(
         address indexed provider,
         address indexed token,
@@ -134,6 +150,11 @@ contract EVMGateway is ReentrancyGuard, Pausable, AccessControl {
         uint256 timestamp
     );
     
+    event CoordinatorOverlapUpdated(
+        uint256 oldOverlap,
+        uint256 newOverlap
+    );
+    
     event BridgeFeeUpdated(
         uint256 oldFee,
         uint256 newFee
@@ -235,7 +256,8 @@ contract EVMGateway is ReentrancyGuard, Pausable, AccessControl {
             recipient: recipient,
             zcashAddress: zcashAddress,
             timestamp: block.timestamp,
-            processed: false
+            processed: false,
+            refunded: false
         });
         
         // Update balances
@@ -328,11 +350,13 @@ contract EVMGateway is ReentrancyGuard, Pausable, AccessControl {
      * @notice Execute withdrawal with coordinator authorization
      * @dev Called by relayer with coordinator's signature
      * @param withdrawalId Withdrawal identifier from requestWithdrawal
+     * @param deadline Last timestamp the authorization is valid at, signed with it
      * @param coordinatorSignature Coordinator's signature authorizing withdrawal
      * @return success Whether withdrawal succeeded
      */
     function executeWithdrawal(
         bytes32 withdrawalId,
+        uint256 deadline,
         bytes calldata coordinatorSignature
     )
         external
@@ -340,8 +364,51 @@ contract EVMGateway is ReentrancyGuard, Pausable, AccessControl {
         whenNotPaused
         returns (bool success)
     {
+        _executeWithdrawal(withdrawalId, deadline, coordinatorSignature);
+        return true;
+    }
+    
+    /**
+     * @notice Execute several authorized withdrawals in one transaction
+     * @dev Reverts as a whole if any withdrawal fails verification
+     * @param withdrawalIds Withdrawal identifiers from requestWithdrawal
+     * @param deadlines Authorization deadline for each withdrawal
+     * @param coordinatorSignatures Coordinator signature for each withdrawal
+     * @return success Whether all withdrawals succeeded
+     */
+    function executeWithdrawalBatch(
+        bytes32[] calldata withdrawalIds,
+        uint256[] calldata deadlines,
+        bytes[] calldata coordinatorSignatures
+    )
+        external
+        nonReentrant
+        whenNotPaused
+        returns (bool success)
+    {
+        require(withdrawalIds.length > 0, "Empty batch");
+        require(
+            withdrawalIds.length == deadlines.length &&
+                withdrawalIds.length == coordinatorSignatures.length,
+            "Length mismatch"
+        );
+        
+        for (uint256 i = 0; i < withdrawalIds.length; i++) {
+            _executeWithdrawal(withdrawalIds[i], deadlines[i], coordinatorSignatures[i]);
+        }
+        
+        return true;
+    }
+    
+    function _executeWithdrawal(
+        bytes32 withdrawalId,
+        uint256 deadline,
+        bytes calldata coordinatorSignature
+    ) internal {
         WithdrawalRequestInfo storage request = withdrawalRequests[withdrawalId];
         
+        // Past it the coordinator releases the liquidity this withdrawal held
+        require(block.timestamp <= deadline, "Authorization expired");
         require(request.timestamp > 0, "Withdrawal not found");
         require(!request.executed, "Already executed");
         require(!usedNullifiers[request.nullifier], "Nullifier already used");
@@ -357,13 +424,14 @@ contract EVMGateway is ReentrancyGuard, Pausable, AccessControl {
             request.token,
             request.amount,
             request.nullifier,
+            deadline,
             block.chainid // Prevent replay attacks across chains
         ));
         
         bytes32 ethSignedMessageHash = messageHash.toEthSignedMessageHash();
         address signer = ethSignedMessageHash.recover(coordinatorSignature);
         
-        require(signer == coordinator, "Invalid coordinator signature");
+        require(_isCoordinator(signer), "Invalid coordinator signature");
         
         // Mark as executed
         request.executed = true;
@@ -389,10 +457,85 @@ contract EVMGateway is ReentrancyGuard, Pausable, AccessControl {
             request.nullifier,
             block.timestamp
         );
+    }
+    
+    // ============ DEPOSIT REFUND ============
+    
+    /**
+     * @notice Return a deposit the coordinator could not route to its sender
+     * @dev Called by relayer with the coordinator's refund authorization,
+     *      issued only for deposits that never received a Zcash note
+     * @param depositId Deposit identifier from deposit
+     * @param coordinatorSignature Coordinator's signature authorizing the refund
+     * @return success Whether the refund succeeded
+     */
+    function refundDeposit(
+        bytes32 depositId,
+        bytes calldata coordinatorSignature
+    )
+        external
+        nonReentrant
+        whenNotPaused
+        returns (bool success)
+    {
+        DepositInfo storage info = deposits[depositId];
+        
+        require(info.timestamp > 0, "Deposit not found");
+        require(!info.refunded, "Already refunded");
+        require(
+            lockedBalances[info.token] >= info.amount,
+            "Insufficient locked balance"
+        );
+        
+        // Verify coordinator signature; the prefix keeps refund and
+        // withdrawal authorizations from being interchangeable
+        bytes32 messageHash = keccak256(abi.encodePacked(
+            "REFUND",
+            depositId,
+            info.sender,
+            info.token,
+            info.amount,
+            block.chainid
+        ));
+        
+        bytes32 ethSignedMessageHash = messageHash.toEthSignedMessageHash();
+        address signer = ethSignedMessageHash.recover(coordinatorSignature);
+        
+        require(_isCoordinator(signer), "Invalid coordinator signature");
+        
+        info.refunded = true;
+        lockedBalances[info.token] -= info.amount;
+        
+        if (info.token == NATIVE_ASSET) {
+            (bool sent, ) = payable(info.sender).call{value: info.amount}("");
+            require(sent, "ETH transfer failed");
+        } else {
+            IERC20(info.token).safeTransfer(info.sender, info.amount);
+        }
+        
+        emit DepositRefunded(
+            depositId,
+            info.sender,
+            info.token,
+            info.amount,
+            block.timestamp
+        );
         
         return true;
     }
     
+    /**
+     * @dev Current coordinator, or the previous one inside its overlap window
+     */
+    function _isCoordinator(address signer) internal view returns (bool) {
+        if (signer == coordinator) {
+            return true;
+        }
+        return signer != address(0)
+            && signer == previousCoordinator
+            && block.timestamp < previousCoordinatorValidUntil;
+    }
+    
     // ============ LIQUIDITY MANAGEMENT ============
     
     /**
@@ -588,6 +731,7 @@ contract EVMGateway is ReentrancyGuard, Pausable, AccessControl {
     
     /**
      * @notice Update coordinator address
+     * @dev The old coordinator's signatures stay valid for coordinatorOverlap
      */
     function setCoordinator(address newCoordinator)
         external
@@ -597,10 +741,27 @@ contract EVMGateway is ReentrancyGuard, Pausable, AccessControl {
         
         address oldCoordinator = coordinator;
         coordinator = newCoordinator;
+        previousCoordinator = oldCoordinator;
+        previousCoordinatorValidUntil = block.timestamp + coordinatorOverlap;
         
         emit CoordinatorUpdated(oldCoordinator, newCoordinator, block.timestamp);
     }
     
+    /**
+     * @notice Set how long a rotated-out coordinator key stays valid
+     */
+    function setCoordinatorOverlap(uint256 newOverlap)
+        external
+        onlyRole(DEFAULT_ADMIN_ROLE)
+    {
+        require(newOverlap <= 7 days, "Overlap too long");
+        
+        uint256 oldOverlap = coordinatorOverlap;
+        coordinatorOverlap = newOverlap;
+        
+        emit CoordinatorOverlapUpdated(oldOverlap, newOverlap);
+    }
+    
     /**
      * @notice Set bridge fee
      */
@@ -231,9 +231,10 @@ export declare class MinaGateway extends SmartContract {
      */
     requestWithdrawal(amount: UInt64, nullifier: Field, zcashProof: Field, merkleRoot: Field, signature: Signature): Promise<void>;
     /**
-     * Execute withdrawal - Step 2 (with coordinator signature)
+     * Execute withdrawal - Step 2 (with coordinator signature, valid until
+     * `deadline` in unix seconds)
      */
-    executeWithdrawal(withdrawalId: Field, recipient: PublicKey, amount: UInt64, nullifier: Field, coordinatorSignature: Signature): Promise<void>;
+    executeWithdrawal(withdrawalId: Field, recipient: PublicKey, amount: UInt64, nullifier: Field, deadline: UInt64, coordinatorSignature: Signature): Promise<void>;
     /**
      * Set paused state
      */
@@ -165,14 +165,18 @@ export class MinaGateway extends SmartContract {
         }));
     }
     /**
-     * Execute withdrawal - Step 2 (with coordinator signature)
+     * Execute withdrawal - Step 2 (with coordinator signature, valid until
+     * `deadline` in unix seconds)
      */
-    async executeWithdrawal(withdrawalId, recipient, amount, nullifier, coordinatorSignature) {
+    async executeWithdrawal(withdrawalId, recipient, amount, nullifier, deadline, coordinatorSignature) {
         const isPaused = this.paused.getAndRequireEquals();
         isPaused.assertFalse('Gateway is paused');
         // Verify this matches a pending withdrawal
         const storedWithdrawalId = this.lastWithdrawalId.getAndRequireEquals();
         withdrawalId.assertEquals(storedWithdrawalId, 'Invalid withdrawal ID');
+        // Past the deadline the coordinator releases the liquidity this
+        // withdrawal held; network timestamps are in milliseconds
+        this.network.timestamp.requireBetween(UInt64.zero, deadline.mul(1000));
         // Verify coordinator signature
         const coordinator = this.coordinator.getAndRequireEquals();
         coordinatorSignature.verify(coordinator, [
@@ -180,6 +184,7 @@ export class MinaGateway extends SmartContract {
             ...recipient.toFields(),
             amount.value,
             nullifier,
+            deadline.value,
         ]).assertTrue('Invalid coordinator signature');
         // Update balances
         const currentWithdrawn = this.totalWithdrawn.getAndRequireEquals();
@@ -282,6 +287,7 @@ __decorate([
         PublicKey,
         UInt64,
         Field,
+        UInt64,
         Signature]),
     __metadata("design:returntype", Promise)
 ], MinaGateway.prototype, "executeWithdrawal", null);
@@ -211,13 +211,15 @@ export class MinaGateway extends SmartContract {
     }
 
     /**
-     * Execute withdrawal - Step 2 (with coordinator signature)
+     * Execute withdrawal - Step 2 (with coordinator signature, valid until
+     * `deadline` in unix seconds)
      */
     @method async executeWithdrawal(
         withdrawalId: Field,
         recipient: PublicKey,
         amount: UInt64,
         nullifier: Field,
+        deadline: UInt64,
         coordinatorSignature: Signature
     ): Promise<void> {
         const isPaused = this.paused.getAndRequireEquals();
@@ -227,6 +229,10 @@ export class MinaGateway extends SmartContract {
         const storedWithdrawalId = this.lastWithdrawalId.getAndRequireEquals(); 
         withdrawalId.assertEquals(storedWithdrawalId, 'Invalid withdrawal ID');
 
+        // Past the deadline the coordinator releases the liquidity this
+        // withdrawal held; network timestamps are in milliseconds
+        this.network.timestamp.requireBetween(UInt64.zero, deadline.mul(1000));
+
         // Verify coordinator signature
         const coordinator = this.coordinator.getAndRequireEquals();
         coordinatorSignature.verify(coordinator, [
@@ -234,6 +240,7 @@ export class MinaGateway extends SmartContract {
             ...recipient.toFields(),
             amount.value,
             nullifier,
+            deadline.value,
         ]).assertTrue('Invalid coordinator signature');
 
         // Update balances
@@ -2,17 +2,28 @@
 // FIXED: Two-step withdrawal with coordinator signature verification
 
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::LookupMap;
+use near_sdk::collections::{LookupMap, LookupSet};
 use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::{
-    env, near_bindgen, AccountId, NearToken, PanicOnDefault, Promise,
+    env, near_bindgen, AccountId, NearToken, PanicOnDefault, Promise, PromiseOrValue,
     BorshStorageKey, require,
 };
 use near_sdk::NearSchema;
 
 const MIN_DEPOSIT: u128 = 100_000_000_000_000_000_000_000; // 0.1 NEAR
 const NEAR_TOKEN: &str = "near";
+/// Delay before a coordinator change takes effect, unless reconfigured
+const DEFAULT_COORDINATOR_TIMELOCK_SECS: u64 = 2 * 24 * 60 * 60;
+/// Floor for `set_coordinator_timelock`, so a compromised owner key still
+/// can't swap the coordinator unnoticed
+const MIN_COORDINATOR_TIMELOCK_SECS: u64 = 60 * 60;
+/// How long a replaced coordinator keeps verifying after activation, so
+/// authorizations it already signed still execute, unless reconfigured
+const DEFAULT_COORDINATOR_OVERLAP_SECS: u64 = 60 * 60;
+/// Ceiling for `set_coordinator_overlap`
+const MAX_COORDINATOR_OVERLAP_SECS: u64 = 7 * 24 * 60 * 60;
+const NANOS_PER_SEC: u64 = 1_000_000_000;
 
 #[derive(BorshSerialize, BorshStorageKey)]
 enum StorageKey {
@@ -20,19 +31,36 @@ enum StorageKey {
     Deposits,
     WithdrawalRequests,
     Nullifiers,
+    AllowedTokens,
+    TokenMinDeposits,
 }
 
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct NEARGateway {
     pub owner: AccountId,
+    /// Proposed owner; ownership moves once they call `accept_owner`
+    pub pending_owner: Option<AccountId>,
+    /// Ethereum-implicit account (`0x` + address) of the coordinator's
+    /// secp256k1 signing key; withdrawals must be signed by it
     pub coordinator: AccountId,
+    /// Coordinator change waiting out the timelock
+    pub pending_coordinator: Option<PendingCoordinator>,
+    pub coordinator_timelock_secs: u64,
+    /// Coordinator replaced by the last activation, still accepted until
+    /// its `valid_until`
+    pub previous_coordinator: Option<PreviousCoordinator>,
+    pub coordinator_overlap_secs: u64,
     pub paused: bool,
     
     pub locked_balances: LookupMap<AccountId, u128>,
     pub deposits: LookupMap<String, DepositInfo>,
     pub withdrawal_requests: LookupMap<String, WithdrawalRequestInfo>,
     pub nullifiers: LookupMap<Vec<u8>, bool>,
+    /// NEP-141 token contracts accepted through `ft_on_transfer`
+    pub allowed_tokens: LookupSet<AccountId>,
+    /// Smallest `ft_on_transfer` deposit per token, in its base units
+    pub token_min_deposits: LookupMap<AccountId, u128>,
     
     pub total_deposits: u128,
     pub total_withdrawals: u128,
@@ -42,6 +70,27 @@ pub struct NEARGateway {
     pub bridge_fee: u16, // basis points
 }
 
+/// State written by gateways deployed before the token allowlist, two-step
+/// ownership and the coordinator timelock; read once by `migrate`
+#[derive(BorshDeserialize, BorshSerialize)]
+struct OldNEARGateway {
+    owner: AccountId,
+    coordinator: AccountId,
+    paused: bool,
+    
+    locked_balances: LookupMap<AccountId, u128>,
+    deposits: LookupMap<String, DepositInfo>,
+    withdrawal_requests: LookupMap<String, WithdrawalRequestInfo>,
+    nullifiers: LookupMap<Vec<u8>, bool>,
+    
+    total_deposits: u128,
+    total_withdrawals: u128,
+    deposit_count: u64,
+    withdrawal_count: u64,
+    
+    bridge_fee: u16,
+}
+
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, NearSchema, Clone)]
 #[abi(borsh, json)]
 #[serde(crate = "near_sdk::serde")]
@@ -70,6 +119,35 @@ pub struct WithdrawalRequestInfo {
     pub executed: bool,
 }
 
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, NearSchema, Clone)]
+#[abi(borsh, json)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PendingCoordinator {
+    pub coordinator: AccountId,
+    /// Block timestamp (nanoseconds) from which `activate_coordinator` applies it
+    pub activates_at: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, NearSchema, Clone)]
+#[abi(borsh, json)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PreviousCoordinator {
+    pub coordinator: AccountId,
+    /// Block timestamp (nanoseconds) from which its signatures are rejected
+    pub valid_until: u64,
+}
+
+/// `msg` payload of an `ft_transfer_call` into the gateway
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FtDepositMsg {
+    pub target_chain_id: u64,
+    /// Hex-encoded 32-byte recipient
+    pub recipient: String,
+    /// Hex-encoded 32-byte Zcash address
+    pub zcash_address: String,
+}
+
 #[derive(Serialize, Deserialize, NearSchema)]
 #[abi(json)]
 #[serde(crate = "near_sdk::serde")]
@@ -88,12 +166,19 @@ impl NEARGateway {
         
         Self {
             owner: env::predecessor_account_id(),
+            pending_owner: None,
             coordinator,
+            pending_coordinator: None,
+            coordinator_timelock_secs: DEFAULT_COORDINATOR_TIMELOCK_SECS,
+            previous_coordinator: None,
+            coordinator_overlap_secs: DEFAULT_COORDINATOR_OVERLAP_SECS,
             paused: false,
             locked_balances: LookupMap::new(StorageKey::LockedBalances),
             deposits: LookupMap::new(StorageKey::Deposits),
             withdrawal_requests: LookupMap::new(StorageKey::WithdrawalRequests),
             nullifiers: LookupMap::new(StorageKey::Nullifiers),
+            allowed_tokens: LookupSet::new(StorageKey::AllowedTokens),
+            token_min_deposits: LookupMap::new(StorageKey::TokenMinDeposits),
             total_deposits: 0,
             total_withdrawals: 0,
             deposit_count: 0,
@@ -102,6 +187,39 @@ impl NEARGateway {
         }
     }
 
+    /// Upgrade an `OldNEARGateway` in place after deploying this code to it.
+    /// Balances, deposits, withdrawals and nullifiers carry over; no token
+    /// is allowlisted yet and the coordinator timelock and overlap start at
+    /// their defaults.
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let old: OldNEARGateway = env::state_read()
+            .unwrap_or_else(|| env::panic_str("No state to migrate"));
+        
+        Self {
+            owner: old.owner,
+            pending_owner: None,
+            coordinator: old.coordinator,
+            pending_coordinator: None,
+            coordinator_timelock_secs: DEFAULT_COORDINATOR_TIMELOCK_SECS,
+            previous_coordinator: None,
+            coordinator_overlap_secs: DEFAULT_COORDINATOR_OVERLAP_SECS,
+            paused: old.paused,
+            locked_balances: old.locked_balances,
+            deposits: old.deposits,
+            withdrawal_requests: old.withdrawal_requests,
+            nullifiers: old.nullifiers,
+            allowed_tokens: LookupSet::new(StorageKey::AllowedTokens),
+            token_min_deposits: LookupMap::new(StorageKey::TokenMinDeposits),
+            total_deposits: old.total_deposits,
+            total_withdrawals: old.total_withdrawals,
+            deposit_count: old.deposit_count,
+            withdrawal_count: old.withdrawal_count,
+            bridge_fee: old.bridge_fee,
+        }
+    }
+
     // ============ DEPOSIT ============
 
     #[payable]
@@ -117,50 +235,55 @@ impl NEARGateway {
         let amount = env::attached_deposit().as_yoctonear();
         
         require!(amount >= MIN_DEPOSIT, "Amount below minimum");
-        require!(recipient.len() == 32, "Invalid recipient");
-        require!(zcash_address.len() == 32, "Invalid Zcash address");
         
-        let fee = self.calculate_fee(amount);
-        let net_amount = amount - fee;
-        
-        let deposit_id = self.generate_deposit_id(
-            &sender,
-            &NEAR_TOKEN.parse().unwrap(),
+        self.record_deposit(
+            sender,
+            NEAR_TOKEN.parse().unwrap(),
             amount,
             target_chain_id,
-        );
-        
-        let deposit = DepositInfo {
-            deposit_id: deposit_id.clone(),
-            sender: sender.clone(),
-            token: NEAR_TOKEN.parse().unwrap(),
-            amount: U128(net_amount),
-            target_chain_id,
-            recipient: hex::encode(&recipient),
-            zcash_address: hex::encode(&zcash_address),
-            timestamp: env::block_timestamp(),
-            processed: false,
-        };
-        
-        self.deposits.insert(&deposit_id, &deposit);
+            recipient,
+            zcash_address,
+        )
+    }
+
+    /// NEP-141 receiver: deposits arrive via `ft_transfer_call` on the token
+    /// contract with an `FtDepositMsg` as `msg`. Only allowlisted tokens are
+    /// accepted, so funds can't be stranded in a token the coordinator
+    /// doesn't support, and only from the token's minimum deposit up.
+    pub fn ft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        self.assert_not_paused();
         
-        let token_id: AccountId = NEAR_TOKEN.parse().unwrap();
-        let current = self.locked_balances.get(&token_id).unwrap_or(0);
-        self.locked_balances.insert(&token_id, &(current + net_amount));
+        let token = env::predecessor_account_id();
+        require!(self.allowed_tokens.contains(&token), "Token not allowed");
+        require!(amount.0 > 0, "Invalid amount");
+        let min_deposit = self
+            .token_min_deposits
+            .get(&token)
+            .unwrap_or_else(|| env::panic_str("No minimum deposit set for token"));
+        require!(amount.0 >= min_deposit, "Amount below minimum");
         
-        self.total_deposits += net_amount;
-        self.deposit_count += 1;
+        let deposit_msg: FtDepositMsg =
+            near_sdk::serde_json::from_str(&msg).expect("Invalid deposit message");
+        let recipient = hex::decode(&deposit_msg.recipient).expect("Invalid recipient");
+        let zcash_address = hex::decode(&deposit_msg.zcash_address)
+            .expect("Invalid Zcash address");
         
-        env::log_str(&format!(
-            "EVENT_JSON:{{\"standard\":\"zerobridge\",\"version\":\"1.0.0\",\
-            \"event\":\"tokens_locked\",\"data\":{{\"deposit_id\":\"{}\",\
-            \"sender\":\"{}\",\"amount\":\"{}\",\"target_chain_id\":{},\
-            \"recipient\":\"{}\",\"zcash_address\":\"{}\"}}}}",
-            deposit_id, sender, net_amount, target_chain_id,
-            hex::encode(&recipient), hex::encode(&zcash_address)
-        ));
+        self.record_deposit(
+            sender_id,
+            token,
+            amount.0,
+            deposit_msg.target_chain_id,
+            recipient,
+            zcash_address,
+        );
         
-        deposit_id
+        // Keep the full amount
+        PromiseOrValue::Value(U128(0))
     }
 
     // ============ WITHDRAWAL REQUEST (Step 1) ============
@@ -221,9 +344,12 @@ impl NEARGateway {
 
     // ============ WITHDRAWAL EXECUTION (Step 2) ============
 
+    /// `deadline` (unix seconds) is signed with the withdrawal; past it the
+    /// coordinator releases the liquidity it held, so it no longer executes
     pub fn execute_withdrawal(
         &mut self,
         withdrawal_id: String,
+        deadline: u64,
         coordinator_signature: Vec<u8>,
     ) -> Promise {
         self.assert_not_paused();
@@ -245,6 +371,7 @@ impl NEARGateway {
         self.verify_coordinator_signature(
             &withdrawal_id,
             &withdrawal_request,
+            deadline,
             &coordinator_signature,
         );
         
@@ -284,26 +411,33 @@ impl NEARGateway {
         &self,
         withdrawal_id: &str,
         request: &WithdrawalRequestInfo,
+        deadline: u64,
         signature: &[u8],
     ) {
-        use near_sdk::env::keccak256;
-        
-        // Construct message hash (same format as EVM)
-        let mut message = Vec::new();
-        message.extend_from_slice(withdrawal_id.as_bytes());
-        message.extend_from_slice(request.recipient.as_bytes());
-        message.extend_from_slice(&request.amount.0.to_le_bytes());
-        message.extend_from_slice(request.nullifier.as_bytes());
-        
-        let message_hash = keccak256(&message);
-        
-        // In production, verify ECDSA signature here using ed25519 or secp256k1
-        // For now, simplified check
+        require!(env::block_timestamp() / NANOS_PER_SEC <= deadline, "Authorization expired");
         require!(signature.len() == 65, "Invalid signature length");
         
-        // TODO: Actual signature verification
-        // let is_valid = env::ecrecover(&message_hash, signature, 0, false);
-        // require!(is_valid.is_some(), "Invalid signature");
+        // r ‖ s ‖ recovery id (0 or 1)
+        let public_key = env::ecrecover(
+            &withdrawal_message_hash(withdrawal_id, request, deadline),
+            &signature[..64],
+            signature[64],
+            true,
+        )
+        .unwrap_or_else(|| env::panic_str("Invalid signature"));
+        
+        require!(
+            self.accepts_coordinator(&eth_implicit_account(&public_key)),
+            "Invalid coordinator"
+        );
+    }
+
+    /// The coordinator, or the previous one while its overlap lasts
+    fn accepts_coordinator(&self, signer: &AccountId) -> bool {
+        *signer == self.coordinator
+            || self.previous_coordinator.as_ref().is_some_and(|previous| {
+                previous.coordinator == *signer && env::block_timestamp() < previous.valid_until
+            })
     }
 
     // ============ VIEW FUNCTIONS ============
@@ -312,6 +446,16 @@ impl NEARGateway {
         U128(self.locked_balances.get(&token).unwrap_or(0))
     }
 
+    /// Native NEAR is always accepted; NEP-141 tokens must be allowlisted
+    pub fn is_token_allowed(&self, token: AccountId) -> bool {
+        token.as_str() == NEAR_TOKEN || self.allowed_tokens.contains(&token)
+    }
+
+    /// Minimum `ft_on_transfer` deposit of `token`, if one is set
+    pub fn get_token_min_deposit(&self, token: AccountId) -> Option<U128> {
+        self.token_min_deposits.get(&token).map(U128)
+    }
+
     pub fn is_nullifier_used(&self, nullifier: Vec<u8>) -> bool {
         self.nullifiers.get(&nullifier).unwrap_or(false)
     }
@@ -324,6 +468,18 @@ impl NEARGateway {
         self.withdrawal_requests.get(&withdrawal_id)
     }
 
+    pub fn get_pending_coordinator(&self) -> Option<PendingCoordinator> {
+        self.pending_coordinator.clone()
+    }
+
+    pub fn get_previous_coordinator(&self) -> Option<PreviousCoordinator> {
+        self.previous_coordinator.clone()
+    }
+
+    pub fn get_pending_owner(&self) -> Option<AccountId> {
+        self.pending_owner.clone()
+    }
+
     pub fn get_stats(&self) -> BridgeStats {
         BridgeStats {
             total_deposits: U128(self.total_deposits),
@@ -335,17 +491,114 @@ impl NEARGateway {
 
     // ============ ADMIN FUNCTIONS ============
 
+    /// Schedule a coordinator change. It takes effect once the timelock has
+    /// passed and `activate_coordinator` is called; a newer proposal
+    /// replaces this one.
     pub fn set_coordinator(&mut self, new_coordinator: AccountId) {
         self.assert_owner();
         
-        let old_coordinator = self.coordinator.clone();
-        self.coordinator = new_coordinator.clone();
+        let activates_at = env::block_timestamp() + self.coordinator_timelock_secs * NANOS_PER_SEC;
+        self.pending_coordinator = Some(PendingCoordinator {
+            coordinator: new_coordinator.clone(),
+            activates_at,
+        });
+        
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"zerobridge\",\"version\":\"1.0.0\",\
+            \"event\":\"coordinator_change_proposed\",\"data\":{{\"current_coordinator\":\"{}\",\
+            \"new_coordinator\":\"{}\",\"activates_at\":{}}}}}",
+            self.coordinator, new_coordinator, activates_at
+        ));
+    }
+
+    /// Apply the pending coordinator change once its timelock has passed.
+    /// Anyone may call this; the owner already decided the change. The
+    /// replaced coordinator stays accepted for `coordinator_overlap_secs`
+    /// from now, so authorizations it signed before the switch still execute.
+    pub fn activate_coordinator(&mut self) {
+        let pending = self
+            .pending_coordinator
+            .take()
+            .unwrap_or_else(|| env::panic_str("No pending coordinator change"));
+        require!(
+            env::block_timestamp() >= pending.activates_at,
+            "Coordinator timelock not expired"
+        );
+        
+        let old_coordinator = std::mem::replace(&mut self.coordinator, pending.coordinator);
+        let valid_until = env::block_timestamp() + self.coordinator_overlap_secs * NANOS_PER_SEC;
+        self.previous_coordinator = Some(PreviousCoordinator {
+            coordinator: old_coordinator.clone(),
+            valid_until,
+        });
         
         env::log_str(&format!(
             "EVENT_JSON:{{\"standard\":\"zerobridge\",\"version\":\"1.0.0\",\
             \"event\":\"coordinator_updated\",\"data\":{{\"old_coordinator\":\"{}\",\
-            \"new_coordinator\":\"{}\"}}}}",
-            old_coordinator, new_coordinator
+            \"old_coordinator_valid_until\":{},\"new_coordinator\":\"{}\"}}}}",
+            old_coordinator, valid_until, self.coordinator
+        ));
+    }
+
+    pub fn cancel_coordinator_change(&mut self) {
+        self.assert_owner();
+        
+        let pending = self
+            .pending_coordinator
+            .take()
+            .unwrap_or_else(|| env::panic_str("No pending coordinator change"));
+        
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"zerobridge\",\"version\":\"1.0.0\",\
+            \"event\":\"coordinator_change_cancelled\",\"data\":{{\"new_coordinator\":\"{}\"}}}}",
+            pending.coordinator
+        ));
+    }
+
+    /// Applies to changes proposed from now on
+    pub fn set_coordinator_timelock(&mut self, seconds: u64) {
+        self.assert_owner();
+        require!(seconds >= MIN_COORDINATOR_TIMELOCK_SECS, "Timelock too short");
+        self.coordinator_timelock_secs = seconds;
+    }
+
+    /// Applies to changes activated from now on
+    pub fn set_coordinator_overlap(&mut self, seconds: u64) {
+        self.assert_owner();
+        require!(seconds <= MAX_COORDINATOR_OVERLAP_SECS, "Overlap too long");
+        self.coordinator_overlap_secs = seconds;
+    }
+
+    /// First step of an ownership transfer; replaces any earlier proposal
+    pub fn propose_owner(&mut self, new_owner: AccountId) {
+        self.assert_owner();
+        
+        self.pending_owner = Some(new_owner.clone());
+        
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"zerobridge\",\"version\":\"1.0.0\",\
+            \"event\":\"owner_proposed\",\"data\":{{\"owner\":\"{}\",\
+            \"pending_owner\":\"{}\"}}}}",
+            self.owner, new_owner
+        ));
+    }
+
+    /// Second step: the proposed account takes ownership
+    pub fn accept_owner(&mut self) {
+        let caller = env::predecessor_account_id();
+        require!(
+            self.pending_owner.as_ref() == Some(&caller),
+            "Only pending owner"
+        );
+        
+        let old_owner = std::mem::replace(&mut self.owner, caller);
+        self.pending_owner = None;
+        
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"zerobridge\",\"version\":\"1.0.0\",\
+            \"event\":\"owner_transferred\",\"data\":{{\"old_owner\":\"{}\",\
+            \"new_owner\":\"{}\"}}}}",
+            old_owner, self.owner
         ));
     }
 
@@ -368,6 +621,42 @@ impl NEARGateway {
         self.bridge_fee = fee_bps;
     }
 
+    /// Allowlisting alone doesn't open deposits of a token: they are refused
+    /// until `set_token_min_deposit` gives it a minimum.
+    pub fn set_token_allowed(&mut self, token: AccountId, allowed: bool) {
+        self.assert_owner();
+        
+        if allowed {
+            self.allowed_tokens.insert(&token);
+        } else {
+            self.allowed_tokens.remove(&token);
+        }
+        
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"zerobridge\",\"version\":\"1.0.0\",\
+            \"event\":\"token_allowlist_updated\",\"data\":{{\"token\":\"{}\",\
+            \"allowed\":{}}}}}",
+            token, allowed
+        ));
+    }
+
+    /// Smallest deposit of an allowlisted token, in its base units. Tokens
+    /// differ in decimals, so each needs its own; deposits of a token
+    /// without one are refused.
+    pub fn set_token_min_deposit(&mut self, token: AccountId, min_deposit: U128) {
+        self.assert_owner();
+        require!(min_deposit.0 > 0, "Invalid amount");
+        
+        let old_min_deposit = self.token_min_deposits.insert(&token, &min_deposit.0);
+        
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"zerobridge\",\"version\":\"1.0.0\",\
+            \"event\":\"token_min_deposit_updated\",\"data\":{{\"token\":\"{}\",\
+            \"old_min_deposit\":\"{}\",\"new_min_deposit\":\"{}\"}}}}",
+            token, old_min_deposit.unwrap_or(0), min_deposit.0
+        ));
+    }
+
     #[payable]
     pub fn add_liquidity(&mut self) {
         self.assert_not_paused();
@@ -396,6 +685,60 @@ impl NEARGateway {
         );
     }
 
+    fn record_deposit(
+        &mut self,
+        sender: AccountId,
+        token: AccountId,
+        amount: u128,
+        target_chain_id: u64,
+        recipient: Vec<u8>,
+        zcash_address: Vec<u8>,
+    ) -> String {
+        require!(recipient.len() == 32, "Invalid recipient");
+        require!(zcash_address.len() == 32, "Invalid Zcash address");
+        
+        let fee = self.calculate_fee(amount);
+        let net_amount = amount - fee;
+        
+        let deposit_id = self.generate_deposit_id(
+            &sender,
+            &token,
+            amount,
+            target_chain_id,
+        );
+        
+        let deposit = DepositInfo {
+            deposit_id: deposit_id.clone(),
+            sender: sender.clone(),
+            token: token.clone(),
+            amount: U128(net_amount),
+            target_chain_id,
+            recipient: hex::encode(&recipient),
+            zcash_address: hex::encode(&zcash_address),
+            timestamp: env::block_timestamp(),
+            processed: false,
+        };
+        
+        self.deposits.insert(&deposit_id, &deposit);
+        
+        let current = self.locked_balances.get(&token).unwrap_or(0);
+        self.locked_balances.insert(&token, &(current + net_amount));
+        
+        self.total_deposits += net_amount;
+        self.deposit_count += 1;
+        
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"zerobridge\",\"version\":\"1.0.0\",\
+            \"event\":\"tokens_locked\",\"data\":{{\"deposit_id\":\"{}\",\
+            \"sender\":\"{}\",\"token\":\"{}\",\"amount\":\"{}\",\"target_chain_id\":{},\
+            \"recipient\":\"{}\",\"zcash_address\":\"{}\"}}}}",
+            deposit_id, sender, token, net_amount, target_chain_id,
+            hex::encode(&recipient), hex::encode(&zcash_address)
+        ));
+        
+        deposit_id
+    }
+
     fn calculate_fee(&self, amount: u128) -> u128 {
         (amount * self.bridge_fee as u128) / 10000
     }
@@ -442,11 +785,33 @@ impl NEARGateway {
     }
 }
 
+/// Hash the coordinator signs to authorize a withdrawal
+fn withdrawal_message_hash(withdrawal_id: &str, request: &WithdrawalRequestInfo, deadline: u64) -> [u8; 32] {
+    let mut message = Vec::new();
+    message.extend_from_slice(withdrawal_id.as_bytes());
+    message.extend_from_slice(request.recipient.as_str().as_bytes());
+    message.extend_from_slice(&request.amount.0.to_le_bytes());
+    message.extend_from_slice(request.nullifier.as_bytes());
+    message.extend_from_slice(&deadline.to_le_bytes());
+    
+    env::keccak256_array(&message)
+}
+
+/// Ethereum-implicit account of an uncompressed secp256k1 public key
+/// (x ‖ y): `0x` and the last 20 bytes of its keccak256, in hex
+fn eth_implicit_account(public_key: &[u8; 64]) -> AccountId {
+    format!("0x{}", hex::encode(&env::keccak256_array(public_key)[12..]))
+        .parse()
+        .unwrap_or_else(|_| env::panic_str("Invalid coordinator"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use near_sdk::test_utils::{accounts, VMContextBuilder};
     use near_sdk::{testing_env, VMContext};
+    use k256::ecdsa::SigningKey;
+    use k256::elliptic_curve::sec1::ToEncodedPoint;
 
     fn get_context(predecessor: AccountId) -> VMContext {
         VMContextBuilder::new()
@@ -466,6 +831,44 @@ mod tests {
         assert_eq!(contract.paused, false);
     }
 
+    #[test]
+    fn test_migrate_from_state_before_allowlist() {
+        testing_env!(get_context(accounts(0)));
+        
+        let mut locked_balances: LookupMap<AccountId, u128> = LookupMap::new(StorageKey::LockedBalances);
+        locked_balances.insert(&NEAR_TOKEN.parse().unwrap(), &9_970);
+        let mut nullifiers: LookupMap<Vec<u8>, bool> = LookupMap::new(StorageKey::Nullifiers);
+        nullifiers.insert(&vec![7u8; 32], &true);
+        env::state_write(&OldNEARGateway {
+            owner: accounts(0),
+            coordinator: accounts(1),
+            paused: true,
+            locked_balances,
+            deposits: LookupMap::new(StorageKey::Deposits),
+            withdrawal_requests: LookupMap::new(StorageKey::WithdrawalRequests),
+            nullifiers,
+            total_deposits: 9_970,
+            total_withdrawals: 0,
+            deposit_count: 1,
+            withdrawal_count: 0,
+            bridge_fee: 50,
+        });
+        
+        let contract = NEARGateway::migrate();
+        
+        assert_eq!((contract.owner, contract.coordinator), (accounts(0), accounts(1)));
+        assert!(contract.paused);
+        assert_eq!((contract.deposit_count, contract.bridge_fee), (1, 50));
+        assert_eq!(contract.get_locked_balance(NEAR_TOKEN.parse().unwrap()), U128(9_970));
+        assert!(contract.is_nullifier_used(vec![7u8; 32]));
+        assert_eq!(contract.get_pending_owner(), None);
+        assert!(contract.get_pending_coordinator().is_none());
+        assert_eq!(contract.coordinator_timelock_secs, DEFAULT_COORDINATOR_TIMELOCK_SECS);
+        assert!(contract.get_previous_coordinator().is_none());
+        assert_eq!(contract.coordinator_overlap_secs, DEFAULT_COORDINATOR_OVERLAP_SECS);
+        assert!(!contract.is_token_allowed(accounts(3)));
+    }
+
     #[test]
     fn test_deposit() {
         let mut context = get_context(accounts(0));
@@ -502,4 +905,356 @@ mod tests {
         assert!(!withdrawal_id.is_empty());
         assert_eq!(contract.withdrawal_count, 1);
     }
-}
\ No newline at end of file
+
+    fn ft_deposit_msg() -> String {
+        near_sdk::serde_json::json!({
+            "target_chain_id": 1,
+            "recipient": hex::encode([1u8; 32]),
+            "zcash_address": hex::encode([2u8; 32]),
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_ft_deposit_of_allowed_token() {
+        testing_env!(get_context(accounts(0)));
+        let mut contract = NEARGateway::new(accounts(1));
+        contract.set_token_allowed(accounts(3), true);
+        contract.set_token_min_deposit(accounts(3), U128(1_000));
+        assert!(contract.is_token_allowed(accounts(3)));
+        
+        // The token contract calls back on behalf of the depositor
+        testing_env!(get_context(accounts(3)));
+        let unused = contract.ft_on_transfer(accounts(2), U128(10_000), ft_deposit_msg());
+        
+        assert!(matches!(unused, PromiseOrValue::Value(U128(0))));
+        assert_eq!(contract.deposit_count, 1);
+        // 0.3% fee withheld
+        assert_eq!(contract.get_locked_balance(accounts(3)), U128(9_970));
+        assert_eq!(contract.get_locked_balance(NEAR_TOKEN.parse().unwrap()), U128(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Token not allowed")]
+    fn test_ft_deposit_of_disallowed_token() {
+        testing_env!(get_context(accounts(0)));
+        let mut contract = NEARGateway::new(accounts(1));
+        contract.set_token_allowed(accounts(3), true);
+        contract.set_token_allowed(accounts(3), false);
+        assert!(!contract.is_token_allowed(accounts(3)));
+        
+        testing_env!(get_context(accounts(3)));
+        contract.ft_on_transfer(accounts(2), U128(10_000), ft_deposit_msg());
+    }
+
+    #[test]
+    #[should_panic(expected = "Amount below minimum")]
+    fn test_ft_deposit_below_token_minimum() {
+        testing_env!(get_context(accounts(0)));
+        let mut contract = NEARGateway::new(accounts(1));
+        contract.set_token_allowed(accounts(3), true);
+        contract.set_token_min_deposit(accounts(3), U128(1_000));
+        assert_eq!(contract.get_token_min_deposit(accounts(3)), Some(U128(1_000)));
+        
+        testing_env!(get_context(accounts(3)));
+        contract.ft_on_transfer(accounts(2), U128(999), ft_deposit_msg());
+    }
+
+    #[test]
+    #[should_panic(expected = "No minimum deposit set for token")]
+    fn test_ft_deposit_needs_token_minimum() {
+        testing_env!(get_context(accounts(0)));
+        let mut contract = NEARGateway::new(accounts(1));
+        contract.set_token_allowed(accounts(3), true);
+        
+        // Dust of an allowlisted token isn't let through by default
+        testing_env!(get_context(accounts(3)));
+        contract.ft_on_transfer(accounts(2), U128(1), ft_deposit_msg());
+    }
+
+    #[test]
+    fn test_allowlisted_token_opens_with_minimum() {
+        testing_env!(get_context(accounts(0)));
+        let mut contract = NEARGateway::new(accounts(1));
+        contract.set_token_allowed(accounts(3), true);
+        assert!(contract.is_token_allowed(accounts(3)));
+        assert_eq!(contract.get_token_min_deposit(accounts(3)), None);
+        
+        contract.set_token_min_deposit(accounts(3), U128(1_000));
+        
+        testing_env!(get_context(accounts(3)));
+        contract.ft_on_transfer(accounts(2), U128(1_000), ft_deposit_msg());
+        assert_eq!(contract.deposit_count, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only owner")]
+    fn test_only_owner_manages_allowlist() {
+        testing_env!(get_context(accounts(0)));
+        let mut contract = NEARGateway::new(accounts(1));
+        
+        testing_env!(get_context(accounts(2)));
+        contract.set_token_allowed(accounts(3), true);
+    }
+
+    #[test]
+    fn test_propose_owner_keeps_current_owner() {
+        testing_env!(get_context(accounts(0)));
+        let mut contract = NEARGateway::new(accounts(1));
+        
+        contract.propose_owner(accounts(2));
+        
+        assert_eq!(contract.owner, accounts(0));
+        assert_eq!(contract.get_pending_owner(), Some(accounts(2)));
+        assert!(near_sdk::test_utils::get_logs()
+            .iter()
+            .any(|log| log.contains("\"event\":\"owner_proposed\"")));
+    }
+
+    #[test]
+    fn test_pending_owner_accepts_ownership() {
+        testing_env!(get_context(accounts(0)));
+        let mut contract = NEARGateway::new(accounts(1));
+        contract.propose_owner(accounts(2));
+        
+        testing_env!(get_context(accounts(2)));
+        contract.accept_owner();
+        
+        assert_eq!(contract.owner, accounts(2));
+        assert_eq!(contract.get_pending_owner(), None);
+        assert!(near_sdk::test_utils::get_logs()
+            .iter()
+            .any(|log| log.contains("\"event\":\"owner_transferred\"")));
+        
+        // The new owner has admin rights
+        contract.set_coordinator(accounts(3));
+        assert_eq!(contract.get_pending_coordinator().unwrap().coordinator, accounts(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only pending owner")]
+    fn test_only_pending_owner_accepts() {
+        testing_env!(get_context(accounts(0)));
+        let mut contract = NEARGateway::new(accounts(1));
+        contract.propose_owner(accounts(2));
+        
+        testing_env!(get_context(accounts(3)));
+        contract.accept_owner();
+    }
+
+    fn context_at(predecessor: AccountId, seconds: u64) -> VMContext {
+        VMContextBuilder::new()
+            .predecessor_account_id(predecessor)
+            .block_timestamp(seconds * NANOS_PER_SEC)
+            .build()
+    }
+
+    #[test]
+    fn test_coordinator_change_waits_for_timelock() {
+        testing_env!(context_at(accounts(0), 1_000));
+        let mut contract = NEARGateway::new(accounts(1));
+        
+        contract.set_coordinator(accounts(2));
+        assert_eq!(contract.coordinator, accounts(1));
+        let pending = contract.get_pending_coordinator().unwrap();
+        assert_eq!(pending.coordinator, accounts(2));
+        assert_eq!(pending.activates_at, (1_000 + DEFAULT_COORDINATOR_TIMELOCK_SECS) * NANOS_PER_SEC);
+        assert!(near_sdk::test_utils::get_logs()
+            .iter()
+            .any(|log| log.contains("\"event\":\"coordinator_change_proposed\"")));
+        
+        // Anyone can activate it once the delay has passed
+        testing_env!(context_at(accounts(3), 1_000 + DEFAULT_COORDINATOR_TIMELOCK_SECS));
+        contract.activate_coordinator();
+        assert_eq!(contract.coordinator, accounts(2));
+        assert!(contract.get_pending_coordinator().is_none());
+        assert!(near_sdk::test_utils::get_logs()
+            .iter()
+            .any(|log| log.contains("\"event\":\"coordinator_updated\"")));
+    }
+
+    #[test]
+    #[should_panic(expected = "Coordinator timelock not expired")]
+    fn test_coordinator_change_not_active_early() {
+        testing_env!(context_at(accounts(0), 1_000));
+        let mut contract = NEARGateway::new(accounts(1));
+        contract.set_coordinator(accounts(2));
+        
+        testing_env!(context_at(accounts(0), 1_000 + DEFAULT_COORDINATOR_TIMELOCK_SECS - 1));
+        contract.activate_coordinator();
+    }
+
+    #[test]
+    #[should_panic(expected = "No pending coordinator change")]
+    fn test_cancelled_coordinator_change_never_activates() {
+        testing_env!(context_at(accounts(0), 1_000));
+        let mut contract = NEARGateway::new(accounts(1));
+        contract.set_coordinator(accounts(2));
+        
+        contract.cancel_coordinator_change();
+        assert!(contract.get_pending_coordinator().is_none());
+        assert!(near_sdk::test_utils::get_logs()
+            .iter()
+            .any(|log| log.contains("\"event\":\"coordinator_change_cancelled\"")));
+        
+        testing_env!(context_at(accounts(0), 1_000 + DEFAULT_COORDINATOR_TIMELOCK_SECS));
+        contract.activate_coordinator();
+    }
+
+    #[test]
+    #[should_panic(expected = "Timelock too short")]
+    fn test_coordinator_timelock_has_floor() {
+        testing_env!(get_context(accounts(0)));
+        let mut contract = NEARGateway::new(accounts(1));
+        contract.set_coordinator_timelock(MIN_COORDINATOR_TIMELOCK_SECS);
+        assert_eq!(contract.coordinator_timelock_secs, MIN_COORDINATOR_TIMELOCK_SECS);
+        
+        contract.set_coordinator_timelock(MIN_COORDINATOR_TIMELOCK_SECS - 1);
+    }
+
+    const COORDINATOR_KEY: [u8; 32] = [0x11; 32];
+    const NEXT_COORDINATOR_KEY: [u8; 32] = [0x22; 32];
+    /// Later than every block time the tests execute at (seconds)
+    const DEADLINE: u64 = 1_000_000;
+
+    fn key_account(key: [u8; 32]) -> AccountId {
+        let public_key = SigningKey::from_slice(&key).unwrap().verifying_key().to_encoded_point(false);
+        eth_implicit_account(public_key.as_bytes()[1..].try_into().unwrap())
+    }
+
+    /// Withdrawal of 1 NEAR by `accounts(2)`, backed by locked deposits
+    fn request_withdrawal(contract: &mut NEARGateway, nullifier: u8) -> String {
+        let token: AccountId = NEAR_TOKEN.parse().unwrap();
+        let locked = contract.locked_balances.get(&token).unwrap_or(0);
+        contract.locked_balances.insert(&token, &(locked + MIN_DEPOSIT * 10));
+        
+        contract.request_withdrawal(token, U128(MIN_DEPOSIT * 10), vec![nullifier; 32], vec![0; 32], vec![1; 32])
+    }
+
+    /// r ‖ s ‖ recovery id over the withdrawal, as the coordinator signs it
+    fn sign_withdrawal(contract: &NEARGateway, key: [u8; 32], withdrawal_id: &str) -> Vec<u8> {
+        let request = contract.get_withdrawal_request(withdrawal_id.to_string()).unwrap();
+        let (signature, recovery_id) = SigningKey::from_slice(&key)
+            .unwrap()
+            .sign_prehash_recoverable(&withdrawal_message_hash(withdrawal_id, &request, DEADLINE))
+            .unwrap();
+        let mut bytes = signature.to_bytes().to_vec();
+        bytes.push(recovery_id.to_byte());
+        bytes
+    }
+
+    /// Gateway whose coordinator moved from `COORDINATOR_KEY` to
+    /// `NEXT_COORDINATOR_KEY`, activated at second 1_000 + timelock. Returns
+    /// it with the end of the overlap in seconds, and two withdrawals
+    /// requested before the switch.
+    fn rotated_gateway() -> (NEARGateway, u64, String, String) {
+        testing_env!(context_at(accounts(0), 1_000));
+        let mut contract = NEARGateway::new(key_account(COORDINATOR_KEY));
+        contract.set_coordinator(key_account(NEXT_COORDINATOR_KEY));
+        
+        testing_env!(context_at(accounts(2), 1_000));
+        let first = request_withdrawal(&mut contract, 1);
+        let second = request_withdrawal(&mut contract, 2);
+        
+        let activated = 1_000 + DEFAULT_COORDINATOR_TIMELOCK_SECS;
+        testing_env!(context_at(accounts(3), activated));
+        contract.activate_coordinator();
+        let previous = contract.get_previous_coordinator().unwrap();
+        assert_eq!(previous.coordinator, key_account(COORDINATOR_KEY));
+        // The overlap runs from activation, not from the proposal
+        let valid_until = activated + DEFAULT_COORDINATOR_OVERLAP_SECS;
+        assert_eq!(previous.valid_until, valid_until * NANOS_PER_SEC);
+        
+        (contract, valid_until, first, second)
+    }
+
+    #[test]
+    fn test_both_coordinators_accepted_during_overlap() {
+        let (mut contract, valid_until, first, second) = rotated_gateway();
+        
+        testing_env!(context_at(accounts(3), valid_until - 1));
+        let old_signature = sign_withdrawal(&contract, COORDINATOR_KEY, &first);
+        contract.execute_withdrawal(first.clone(), DEADLINE, old_signature);
+        let new_signature = sign_withdrawal(&contract, NEXT_COORDINATOR_KEY, &second);
+        contract.execute_withdrawal(second.clone(), DEADLINE, new_signature);
+        
+        assert!(contract.get_withdrawal_request(first).unwrap().executed);
+        assert!(contract.get_withdrawal_request(second).unwrap().executed);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid coordinator")]
+    fn test_previous_coordinator_rejected_after_overlap() {
+        let (mut contract, valid_until, first, second) = rotated_gateway();
+        
+        // The new coordinator still verifies
+        testing_env!(context_at(accounts(3), valid_until));
+        let new_signature = sign_withdrawal(&contract, NEXT_COORDINATOR_KEY, &second);
+        contract.execute_withdrawal(second, DEADLINE, new_signature);
+        
+        let old_signature = sign_withdrawal(&contract, COORDINATOR_KEY, &first);
+        contract.execute_withdrawal(first, DEADLINE, old_signature);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid coordinator")]
+    fn test_other_signer_rejected() {
+        testing_env!(context_at(accounts(2), 1_000));
+        let mut contract = NEARGateway::new(key_account(COORDINATOR_KEY));
+        let withdrawal_id = request_withdrawal(&mut contract, 1);
+        
+        let signature = sign_withdrawal(&contract, NEXT_COORDINATOR_KEY, &withdrawal_id);
+        contract.execute_withdrawal(withdrawal_id, DEADLINE, signature);
+    }
+
+    #[test]
+    #[should_panic(expected = "Authorization expired")]
+    fn test_withdrawal_rejected_after_deadline() {
+        testing_env!(context_at(accounts(2), 1_000));
+        let mut contract = NEARGateway::new(key_account(COORDINATOR_KEY));
+        let first = request_withdrawal(&mut contract, 1);
+        let second = request_withdrawal(&mut contract, 2);
+        
+        // Still valid at the deadline itself
+        testing_env!(context_at(accounts(3), DEADLINE));
+        let signature = sign_withdrawal(&contract, COORDINATOR_KEY, &first);
+        contract.execute_withdrawal(first, DEADLINE, signature);
+        
+        testing_env!(context_at(accounts(3), DEADLINE + 1));
+        let signature = sign_withdrawal(&contract, COORDINATOR_KEY, &second);
+        contract.execute_withdrawal(second, DEADLINE, signature);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid coordinator")]
+    fn test_extended_deadline_rejected() {
+        testing_env!(context_at(accounts(2), DEADLINE + 1));
+        let mut contract = NEARGateway::new(key_account(COORDINATOR_KEY));
+        let withdrawal_id = request_withdrawal(&mut contract, 1);
+        
+        // Signed for DEADLINE; the relayer can't push it back
+        let signature = sign_withdrawal(&contract, COORDINATOR_KEY, &withdrawal_id);
+        contract.execute_withdrawal(withdrawal_id, DEADLINE + 1, signature);
+    }
+
+    #[test]
+    #[should_panic(expected = "Overlap too long")]
+    fn test_coordinator_overlap_has_ceiling() {
+        testing_env!(get_context(accounts(0)));
+        let mut contract = NEARGateway::new(accounts(1));
+        contract.set_coordinator_overlap(MAX_COORDINATOR_OVERLAP_SECS);
+        assert_eq!(contract.coordinator_overlap_secs, MAX_COORDINATOR_OVERLAP_SECS);
+        
+        contract.set_coordinator_overlap(MAX_COORDINATOR_OVERLAP_SECS + 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only owner")]
+    fn test_only_owner_proposes() {
+        testing_env!(get_context(accounts(0)));
+        let mut contract = NEARGateway::new(accounts(1));
+        
+        testing_env!(get_context(accounts(2)));
+        contract.propose_owner(accounts(2));
+    }
+}
@@ -3,29 +3,53 @@
 // Two-step withdrawal with coordinator signature verification
 
 use cosmwasm_std::{
-    entry_point, to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo,
-    Response, StdResult, Uint128, Addr, BankMsg, CosmosMsg, WasmMsg, Coin,
+    entry_point, from_json, to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo,
+    Response, StdResult, Uint128, Addr, BankMsg, CosmosMsg, WasmMsg, Coin, Order,
 };
 use cw2::set_contract_version;
-use cw20::Cw20ExecuteMsg;
+use cw_storage_plus::Bound;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use sha2::{Digest, Sha256};
-use k256::ecdsa::Signature as K256Signature;
+use ripemd::Ripemd160;
+use bech32::FromBase32;
+use k256::elliptic_curve::sec1::ToEncodedPoint;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::merkle::DepositAccumulator;
+use crate::msg::{
+    DepositRootResponse, ExecuteMsg, InstantiateMsg, QueryMsg, ReceiveMsg, SpentNullifiersResponse,
+};
 use crate::state::{
     Config, DepositInfo, WithdrawalRequestInfo, BridgeStats,
     CONFIG, DEPOSITS, WITHDRAWAL_REQUESTS, NULLIFIERS,
     LOCKED_BALANCES, LIQUIDITY_PROVIDERS, DEPOSIT_COUNT,
-    WITHDRAWAL_COUNT, TOTAL_DEPOSITS, TOTAL_WITHDRAWALS,
+    WITHDRAWAL_COUNT, TOTAL_DEPOSITS, TOTAL_WITHDRAWALS, ALLOWED_TOKENS,
+    PENDING_OWNER, PENDING_COORDINATOR, COORDINATOR_TIMELOCK, PendingCoordinator,
+    PREVIOUS_COORDINATOR, COORDINATOR_OVERLAP, PreviousCoordinator, DEPOSIT_TREE,
 };
 
 const CONTRACT_NAME: &str = "crates.io:zerobridge-osmosis-gateway";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+const NATIVE_DENOM: &str = "uosmo";
+
 const MIN_DEPOSIT: u128 = 1_000_000; // 1 OSMO
 const MAX_DEPOSIT: u128 = 1_000_000_000_000; // 1M OSMO
 
+/// Delay before a coordinator change can be activated, unless reconfigured
+const DEFAULT_COORDINATOR_TIMELOCK: u64 = 2 * 24 * 60 * 60;
+/// Floor for `SetCoordinatorTimelock`, so a compromised owner key still
+/// can't swap the coordinator unnoticed
+const MIN_COORDINATOR_TIMELOCK: u64 = 60 * 60;
+/// How long a replaced coordinator keeps verifying after activation, so
+/// authorizations it already signed still execute, unless reconfigured
+const DEFAULT_COORDINATOR_OVERLAP: u64 = 60 * 60;
+/// Ceiling for `SetCoordinatorOverlap`
+const MAX_COORDINATOR_OVERLAP: u64 = 7 * 24 * 60 * 60;
+
+const DEFAULT_QUERY_LIMIT: u32 = 100;
+const MAX_QUERY_LIMIT: u32 = 500;
+
 // ============ Instantiate ============
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -49,6 +73,8 @@ pub fn instantiate(
     WITHDRAWAL_COUNT.save(deps.storage, &0u64)?;
     TOTAL_DEPOSITS.save(deps.storage, &Uint128::zero())?;
     TOTAL_WITHDRAWALS.save(deps.storage, &Uint128::zero())?;
+    COORDINATOR_TIMELOCK.save(deps.storage, &DEFAULT_COORDINATOR_TIMELOCK)?;
+    DEPOSIT_TREE.save(deps.storage, &DepositAccumulator::default())?;
 
     Ok(Response::new()
         .add_attribute("action", "instantiate")
@@ -72,6 +98,8 @@ pub fn execute(
             zcash_address,
         } => execute_deposit(deps, env, info, target_chain_id, recipient, zcash_address),
         
+        ExecuteMsg::Receive(wrapper) => execute_receive(deps, env, info, wrapper),
+        
         ExecuteMsg::RequestWithdrawal {
             token,
             amount,
@@ -84,8 +112,9 @@ pub fn execute(
         
         ExecuteMsg::ExecuteWithdrawal {
             withdrawal_id,
+            deadline,
             coordinator_signature,
-        } => execute_execute_withdrawal(deps, env, info, withdrawal_id, coordinator_signature),
+        } => execute_execute_withdrawal(deps, env, info, withdrawal_id, deadline, coordinator_signature),
         
         ExecuteMsg::AddLiquidity { token } => {
             execute_add_liquidity(deps, info, token)
@@ -96,9 +125,27 @@ pub fn execute(
         }
         
         ExecuteMsg::SetCoordinator { new_coordinator } => {
-            execute_set_coordinator(deps, info, new_coordinator)
+            execute_set_coordinator(deps, env, info, new_coordinator)
+        }
+        
+        ExecuteMsg::ActivateCoordinator {} => execute_activate_coordinator(deps, env),
+        
+        ExecuteMsg::CancelCoordinatorChange {} => execute_cancel_coordinator_change(deps, info),
+        
+        ExecuteMsg::SetCoordinatorTimelock { seconds } => {
+            execute_set_coordinator_timelock(deps, info, seconds)
+        }
+        
+        ExecuteMsg::SetCoordinatorOverlap { seconds } => {
+            execute_set_coordinator_overlap(deps, info, seconds)
+        }
+        
+        ExecuteMsg::ProposeOwner { new_owner } => {
+            execute_propose_owner(deps, info, new_owner)
         }
         
+        ExecuteMsg::AcceptOwner {} => execute_accept_owner(deps, info),
+        
         ExecuteMsg::AddLiquidityProvider { provider } => {
             execute_add_liquidity_provider(deps, info, provider)
         }
@@ -115,6 +162,10 @@ pub fn execute(
             execute_set_bridge_fee(deps, info, new_fee)
         }
         
+        ExecuteMsg::SetTokenAllowed { token, allowed } => {
+            execute_set_token_allowed(deps, info, token, allowed)
+        }
+        
         ExecuteMsg::EmergencyWithdraw { token, to, amount } => {
             execute_emergency_withdraw(deps, info, token, to, amount)
         }
@@ -131,26 +182,11 @@ fn execute_deposit(
     recipient: String,
     zcash_address: String,
 ) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
-    
-    if config.paused {
-        return Err(ContractError::Paused {});
-    }
-    
-    // Validate inputs
-    if recipient.is_empty() || recipient.len() != 64 {
-        return Err(ContractError::InvalidRecipient {});
-    }
-    
-    if zcash_address.is_empty() || zcash_address.len() != 64 {
-        return Err(ContractError::InvalidZcashAddress {});
-    }
-    
     // Get deposited amount (native OSMO)
     let amount = info
         .funds
         .iter()
-        .find(|c| c.denom == "uosmo")
+        .find(|c| c.denom == NATIVE_DENOM)
         .map(|c| c.amount)
         .unwrap_or(Uint128::zero());
     
@@ -162,27 +198,119 @@ fn execute_deposit(
         return Err(ContractError::AmountTooLarge {});
     }
     
+    record_deposit(
+        deps,
+        env,
+        info.sender,
+        NATIVE_DENOM,
+        amount,
+        target_chain_id,
+        recipient,
+        zcash_address,
+    )
+}
+
+/// CW20 `send` into the gateway: `info.sender` is the token contract,
+/// `wrapper.sender` the depositor
+fn execute_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    if !is_token_allowed(deps.as_ref(), info.sender.as_str())? {
+        return Err(ContractError::TokenNotAllowed {});
+    }
+    
+    if wrapper.amount.is_zero() {
+        return Err(ContractError::InvalidAmount {});
+    }
+    
+    let sender = deps.api.addr_validate(&wrapper.sender)?;
+    
+    match from_json(&wrapper.msg)? {
+        ReceiveMsg::Deposit {
+            target_chain_id,
+            recipient,
+            zcash_address,
+        } => record_deposit(
+            deps,
+            env,
+            sender,
+            info.sender.as_str(),
+            wrapper.amount,
+            target_chain_id,
+            recipient,
+            zcash_address,
+        ),
+    }
+}
+
+/// Lock `amount` of `token` (native denom or CW20 contract address) for
+/// bridging, net of the bridge fee
+#[allow(clippy::too_many_arguments)]
+fn record_deposit(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    token: &str,
+    amount: Uint128,
+    target_chain_id: u64,
+    recipient: String,
+    zcash_address: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    
+    if config.paused {
+        return Err(ContractError::Paused {});
+    }
+    
+    // Validate inputs
+    if recipient.is_empty() || recipient.len() != 64 {
+        return Err(ContractError::InvalidRecipient {});
+    }
+    
+    if zcash_address.is_empty() || zcash_address.len() != 64 {
+        return Err(ContractError::InvalidZcashAddress {});
+    }
+    
     // Calculate fee
-    let fee = amount.multiply_ratio(config.bridge_fee, 10000u128);
-    let net_amount = amount.saturating_sub(fee);
+    let fee = amount
+        .checked_multiply_ratio(config.bridge_fee, 10000u128)
+        .map_err(|_| ContractError::Overflow {})?;
+    let net_amount = checked_sub(amount, fee)?;
+    
+    // Compute every balance update before writing anything
+    let new_locked = checked_add(
+        LOCKED_BALANCES
+            .may_load(deps.storage, token)?
+            .unwrap_or(Uint128::zero()),
+        net_amount,
+    )?;
+    let new_total_deposits = checked_add(TOTAL_DEPOSITS.load(deps.storage)?, net_amount)?;
     
     // Generate deposit ID
     let deposit_count = DEPOSIT_COUNT.load(deps.storage)?;
+    let next_deposit_count = deposit_count
+        .checked_add(1)
+        .ok_or(ContractError::Overflow {})?;
     let deposit_id = generate_deposit_id(
-        &info.sender,
-        "uosmo",
+        &sender,
+        token,
         amount,
         target_chain_id,
         &recipient,
         deposit_count,
         env.block.time.seconds(),
     );
+    let mut deposit_tree = DEPOSIT_TREE.may_load(deps.storage)?.unwrap_or_default();
+    let deposit_index = deposit_tree.insert(&deposit_id)?;
     
     // Store deposit info
     let deposit_info = DepositInfo {
         deposit_id: deposit_id.clone(),
-        sender: info.sender.clone(),
-        token: "uosmo".to_string(),
+        sender: sender.clone(),
+        token: token.to_string(),
         amount: net_amount,
         target_chain_id,
         recipient: recipient.clone(),
@@ -194,24 +322,22 @@ fn execute_deposit(
     DEPOSITS.save(deps.storage, &deposit_id, &deposit_info)?;
     
     // Update balances
-    let current_locked = LOCKED_BALANCES
-        .may_load(deps.storage, "uosmo")?
-        .unwrap_or(Uint128::zero());
-    LOCKED_BALANCES.save(deps.storage, "uosmo", &(current_locked + net_amount))?;
-    
-    let current_deposits = TOTAL_DEPOSITS.load(deps.storage)?;
-    TOTAL_DEPOSITS.save(deps.storage, &(current_deposits + net_amount))?;
-    
-    DEPOSIT_COUNT.save(deps.storage, &(deposit_count + 1))?;
+    LOCKED_BALANCES.save(deps.storage, token, &new_locked)?;
+    TOTAL_DEPOSITS.save(deps.storage, &new_total_deposits)?;
+    DEPOSIT_COUNT.save(deps.storage, &next_deposit_count)?;
+    DEPOSIT_TREE.save(deps.storage, &deposit_tree)?;
     
     Ok(Response::new()
         .add_attribute("action", "deposit")
         .add_attribute("deposit_id", deposit_id)
-        .add_attribute("sender", info.sender)
+        .add_attribute("sender", sender)
+        .add_attribute("token", token)
         .add_attribute("amount", net_amount)
         .add_attribute("target_chain_id", target_chain_id.to_string())
         .add_attribute("recipient", recipient)
-        .add_attribute("zcash_address", zcash_address))
+        .add_attribute("zcash_address", zcash_address)
+        .add_attribute("deposit_index", deposit_index.to_string())
+        .add_attribute("deposit_root", hex::encode(deposit_tree.root)))
 }
 
 // ============ WITHDRAWAL REQUEST (Step 1) ============
@@ -237,6 +363,10 @@ fn execute_request_withdrawal(
         return Err(ContractError::InvalidAmount {});
     }
     
+    if !is_token_allowed(deps.as_ref(), &token)? {
+        return Err(ContractError::TokenNotAllowed {});
+    }
+    
     if nullifier.is_empty() || nullifier.len() != 64 {
         return Err(ContractError::InvalidNullifier {});
     }
@@ -272,8 +402,12 @@ fn execute_request_withdrawal(
         executed: false,
     };
     
+    let next_withdrawal_count = withdrawal_count
+        .checked_add(1)
+        .ok_or(ContractError::Overflow {})?;
+    
     WITHDRAWAL_REQUESTS.save(deps.storage, &withdrawal_id, &request)?;
-    WITHDRAWAL_COUNT.save(deps.storage, &(withdrawal_count + 1))?;
+    WITHDRAWAL_COUNT.save(deps.storage, &next_withdrawal_count)?;
     
     Ok(Response::new()
         .add_attribute("action", "request_withdrawal")
@@ -288,11 +422,17 @@ fn execute_request_withdrawal(
 
 // ============ WITHDRAWAL EXECUTION (Step 2) ============
 
+/// Invariant: every check and state write (executed flag, nullifier,
+/// balances) happens before the transfer message is returned. Messages run
+/// only after this call's state is committed, so a token contract that calls
+/// back into `ExecuteWithdrawal` sees `executed == true` and is rejected.
+/// Only allowlisted CW20 contracts are ever called.
 fn execute_execute_withdrawal(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     _info: MessageInfo,
     withdrawal_id: String,
+    deadline: u64,
     coordinator_signature: String,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
@@ -301,13 +441,18 @@ fn execute_execute_withdrawal(
         return Err(ContractError::Paused {});
     }
     
-    // Load withdrawal request
+    // Load withdrawal request; the executed flag is the re-entrancy guard
     let mut request = WITHDRAWAL_REQUESTS.load(deps.storage, &withdrawal_id)?;
     
     if request.executed {
         return Err(ContractError::AlreadyExecuted {});
     }
     
+    // The allowlist may have changed since the request was made
+    if !is_token_allowed(deps.as_ref(), &request.token)? {
+        return Err(ContractError::TokenNotAllowed {});
+    }
+    
     // Check nullifier not used
     if NULLIFIERS.may_load(deps.storage, &request.nullifier)?.unwrap_or(false) {
         return Err(ContractError::NullifierUsed {});
@@ -324,14 +469,22 @@ fn execute_execute_withdrawal(
     
     // Verify coordinator signature
     verify_coordinator_signature(
+        deps.as_ref(),
+        &env,
         &withdrawal_id,
         &request.recipient,
         &request.token,
         request.amount,
         &request.nullifier,
+        deadline,
         &coordinator_signature,
     )?;
     
+    // Compute every balance update before writing anything
+    let new_locked = checked_sub(locked, request.amount)?;
+    let new_total_withdrawals =
+        checked_add(TOTAL_WITHDRAWALS.load(deps.storage)?, request.amount)?;
+    
     // Mark as executed
     request.executed = true;
     WITHDRAWAL_REQUESTS.save(deps.storage, &withdrawal_id, &request)?;
@@ -340,14 +493,11 @@ fn execute_execute_withdrawal(
     NULLIFIERS.save(deps.storage, &request.nullifier, &true)?;
     
     // Update balances
-    let new_locked = locked.saturating_sub(request.amount);
     LOCKED_BALANCES.save(deps.storage, &request.token, &new_locked)?;
-    
-    let current_withdrawals = TOTAL_WITHDRAWALS.load(deps.storage)?;
-    TOTAL_WITHDRAWALS.save(deps.storage, &(current_withdrawals + request.amount))?;
+    TOTAL_WITHDRAWALS.save(deps.storage, &new_total_withdrawals)?;
     
     // Create transfer message
-    let transfer_msg = if request.token == "uosmo" {
+    let transfer_msg = if request.token == NATIVE_DENOM {
         // Native OSMO transfer
         CosmosMsg::Bank(BankMsg::Send {
             to_address: request.recipient.to_string(),
@@ -469,23 +619,170 @@ fn execute_remove_liquidity(
 
 fn execute_set_coordinator(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     new_coordinator: String,
 ) -> Result<Response, ContractError> {
-    let mut config = CONFIG.load(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
     
     if info.sender != config.owner {
         return Err(ContractError::Unauthorized {});
     }
     
-    let old_coordinator = config.coordinator.clone();
-    config.coordinator = deps.api.addr_validate(&new_coordinator)?;
+    // Replaces any earlier proposal
+    let timelock = COORDINATOR_TIMELOCK
+        .may_load(deps.storage)?
+        .unwrap_or(DEFAULT_COORDINATOR_TIMELOCK);
+    let pending = PendingCoordinator {
+        coordinator: deps.api.addr_validate(&new_coordinator)?,
+        activates_at: env.block.time.seconds() + timelock,
+    };
+    PENDING_COORDINATOR.save(deps.storage, &pending)?;
+    
+    Ok(Response::new()
+        .add_attribute("action", "propose_coordinator")
+        .add_attribute("current_coordinator", config.coordinator)
+        .add_attribute("new_coordinator", new_coordinator)
+        .add_attribute("activates_at", pending.activates_at.to_string()))
+}
+
+/// Anyone may activate a change once it is due; the owner already decided it.
+/// The replaced coordinator stays accepted for the coordinator overlap from
+/// now, so authorizations it signed before the switch still execute.
+fn execute_activate_coordinator(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let pending = PENDING_COORDINATOR
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoPendingCoordinator {})?;
+    
+    if env.block.time.seconds() < pending.activates_at {
+        return Err(ContractError::TimelockNotExpired {});
+    }
+    
+    let mut config = CONFIG.load(deps.storage)?;
+    let old_coordinator = std::mem::replace(&mut config.coordinator, pending.coordinator);
+    let overlap = COORDINATOR_OVERLAP
+        .may_load(deps.storage)?
+        .unwrap_or(DEFAULT_COORDINATOR_OVERLAP);
+    let previous = PreviousCoordinator {
+        coordinator: old_coordinator,
+        valid_until: env.block.time.seconds() + overlap,
+    };
     CONFIG.save(deps.storage, &config)?;
+    PREVIOUS_COORDINATOR.save(deps.storage, &previous)?;
+    PENDING_COORDINATOR.remove(deps.storage);
     
     Ok(Response::new()
         .add_attribute("action", "set_coordinator")
-        .add_attribute("old_coordinator", old_coordinator)
-        .add_attribute("new_coordinator", new_coordinator))
+        .add_attribute("old_coordinator", previous.coordinator)
+        .add_attribute("old_coordinator_valid_until", previous.valid_until.to_string())
+        .add_attribute("new_coordinator", config.coordinator))
+}
+
+fn execute_cancel_coordinator_change(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    
+    let pending = PENDING_COORDINATOR
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoPendingCoordinator {})?;
+    PENDING_COORDINATOR.remove(deps.storage);
+    
+    Ok(Response::new()
+        .add_attribute("action", "cancel_coordinator_change")
+        .add_attribute("new_coordinator", pending.coordinator))
+}
+
+fn execute_set_coordinator_timelock(
+    deps: DepsMut,
+    info: MessageInfo,
+    seconds: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if seconds < MIN_COORDINATOR_TIMELOCK {
+        return Err(ContractError::TimelockTooShort {});
+    }
+    
+    COORDINATOR_TIMELOCK.save(deps.storage, &seconds)?;
+    
+    Ok(Response::new()
+        .add_attribute("action", "set_coordinator_timelock")
+        .add_attribute("seconds", seconds.to_string()))
+}
+
+/// Overlap for coordinator changes activated from now on
+fn execute_set_coordinator_overlap(
+    deps: DepsMut,
+    info: MessageInfo,
+    seconds: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if seconds > MAX_COORDINATOR_OVERLAP {
+        return Err(ContractError::OverlapTooLong {});
+    }
+    
+    COORDINATOR_OVERLAP.save(deps.storage, &seconds)?;
+    
+    Ok(Response::new()
+        .add_attribute("action", "set_coordinator_overlap")
+        .add_attribute("seconds", seconds.to_string()))
+}
+
+fn execute_propose_owner(
+    deps: DepsMut,
+    info: MessageInfo,
+    new_owner: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    
+    // Replaces any earlier proposal
+    let pending_owner = deps.api.addr_validate(&new_owner)?;
+    PENDING_OWNER.save(deps.storage, &pending_owner)?;
+    
+    Ok(Response::new()
+        .add_attribute("action", "propose_owner")
+        .add_attribute("owner", config.owner)
+        .add_attribute("pending_owner", pending_owner))
+}
+
+fn execute_accept_owner(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let pending_owner = PENDING_OWNER
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoPendingOwner {})?;
+    
+    if info.sender != pending_owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    
+    let mut config = CONFIG.load(deps.storage)?;
+    let old_owner = std::mem::replace(&mut config.owner, pending_owner);
+    CONFIG.save(deps.storage, &config)?;
+    PENDING_OWNER.remove(deps.storage);
+    
+    Ok(Response::new()
+        .add_attribute("action", "accept_owner")
+        .add_attribute("old_owner", old_owner)
+        .add_attribute("new_owner", config.owner))
 }
 
 fn execute_add_liquidity_provider(
@@ -570,6 +867,27 @@ fn execute_set_bridge_fee(
         .add_attribute("new_fee", new_fee.to_string()))
 }
 
+fn execute_set_token_allowed(
+    deps: DepsMut,
+    info: MessageInfo,
+    token: String,
+    allowed: bool,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    
+    let token_addr = deps.api.addr_validate(&token)?;
+    ALLOWED_TOKENS.save(deps.storage, token_addr.as_str(), &allowed)?;
+    
+    Ok(Response::new()
+        .add_attribute("action", "set_token_allowed")
+        .add_attribute("token", token)
+        .add_attribute("allowed", allowed.to_string()))
+}
+
 fn execute_emergency_withdraw(
     deps: DepsMut,
     info: MessageInfo,
@@ -621,6 +939,9 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::IsNullifierUsed { nullifier } => {
             to_json_binary(&query_is_nullifier_used(deps, nullifier)?)
         }
+        QueryMsg::GetSpentNullifiers { start_after, limit } => {
+            to_json_binary(&query_spent_nullifiers(deps, start_after, limit)?)
+        }
         QueryMsg::GetDeposit { deposit_id } => {
             to_json_binary(&query_deposit(deps, deposit_id)?)
         }
@@ -633,6 +954,21 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::GetConfig {} => {
             to_json_binary(&CONFIG.load(deps.storage)?)
         }
+        QueryMsg::GetPendingCoordinator {} => {
+            to_json_binary(&PENDING_COORDINATOR.may_load(deps.storage)?)
+        }
+        QueryMsg::GetPreviousCoordinator {} => {
+            to_json_binary(&PREVIOUS_COORDINATOR.may_load(deps.storage)?)
+        }
+        QueryMsg::GetPendingOwner {} => {
+            to_json_binary(&PENDING_OWNER.may_load(deps.storage)?)
+        }
+        QueryMsg::IsTokenAllowed { token } => {
+            to_json_binary(&is_token_allowed(deps, &token)?)
+        }
+        QueryMsg::GetDepositRoot {} => {
+            to_json_binary(&query_deposit_root(deps)?)
+        }
     }
 }
 
@@ -654,6 +990,24 @@ fn query_is_nullifier_used(deps: Deps, nullifier: String) -> StdResult<bool> {
         .unwrap_or(false))
 }
 
+fn query_spent_nullifiers(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<SpentNullifiersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+    
+    let nullifiers = NULLIFIERS
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|entry| !matches!(entry, Ok((_, false))))
+        .take(limit)
+        .map(|entry| entry.map(|(nullifier, _)| nullifier))
+        .collect::<StdResult<Vec<String>>>()?;
+    
+    Ok(SpentNullifiersResponse { nullifiers })
+}
+
 fn query_deposit(deps: Deps, deposit_id: String) -> StdResult<DepositInfo> {
     DEPOSITS.load(deps.storage, &deposit_id)
 }
@@ -665,6 +1019,14 @@ fn query_withdrawal_request(
     WITHDRAWAL_REQUESTS.load(deps.storage, &withdrawal_id)
 }
 
+fn query_deposit_root(deps: Deps) -> StdResult<DepositRootResponse> {
+    let tree = DEPOSIT_TREE.may_load(deps.storage)?.unwrap_or_default();
+    Ok(DepositRootResponse {
+        root: hex::encode(tree.root),
+        deposit_count: tree.next_index,
+    })
+}
+
 fn query_stats(deps: Deps) -> StdResult<BridgeStats> {
     let total_deposits = TOTAL_DEPOSITS.load(deps.storage)?;
     let total_withdrawals = TOTAL_WITHDRAWALS.load(deps.storage)?;
@@ -672,7 +1034,7 @@ fn query_stats(deps: Deps) -> StdResult<BridgeStats> {
     Ok(BridgeStats {
         total_deposits,
         total_withdrawals,
-        total_volume: total_deposits + total_withdrawals,
+        total_volume: total_deposits.checked_add(total_withdrawals)?,
         active_deposits: total_deposits.saturating_sub(total_withdrawals),
     })
 }
@@ -719,24 +1081,60 @@ fn generate_withdrawal_id(
     hex::encode(hasher.finalize())
 }
 
-fn verify_coordinator_signature(
+fn is_token_allowed(deps: Deps, token: &str) -> StdResult<bool> {
+    if token == NATIVE_DENOM {
+        return Ok(true);
+    }
+    Ok(ALLOWED_TOKENS.may_load(deps.storage, token)?.unwrap_or(false))
+}
+
+fn checked_add(a: Uint128, b: Uint128) -> Result<Uint128, ContractError> {
+    a.checked_add(b).map_err(|_| ContractError::Overflow {})
+}
+
+fn checked_sub(a: Uint128, b: Uint128) -> Result<Uint128, ContractError> {
+    a.checked_sub(b).map_err(|_| ContractError::Underflow {})
+}
+
+/// Hash the coordinator signs to authorize a withdrawal
+fn withdrawal_message_hash(
     withdrawal_id: &str,
     recipient: &Addr,
     token: &str,
     amount: Uint128,
     nullifier: &str,
-    signature: &str,
-) -> Result<(), ContractError> {
-    // Construct message hash
+    deadline: u64,
+) -> [u8; 32] {
     let mut hasher = Sha256::new();
     hasher.update(withdrawal_id.as_bytes());
     hasher.update(recipient.as_bytes());
     hasher.update(token.as_bytes());
     hasher.update(amount.to_string().as_bytes());
     hasher.update(nullifier.as_bytes());
-    let _message_hash = hasher.finalize();
+    hasher.update(deadline.to_string().as_bytes());
+    hasher.finalize().into()
+}
+
+/// Past `deadline` the coordinator releases the liquidity the withdrawal
+/// held, so the authorization no longer executes
+#[allow(clippy::too_many_arguments)]
+fn verify_coordinator_signature(
+    deps: Deps,
+    env: &Env,
+    withdrawal_id: &str,
+    recipient: &Addr,
+    token: &str,
+    amount: Uint128,
+    nullifier: &str,
+    deadline: u64,
+    signature: &str,
+) -> Result<(), ContractError> {
+    if env.block.time.seconds() > deadline {
+        return Err(ContractError::AuthorizationExpired {});
+    }
+    let message_hash = withdrawal_message_hash(withdrawal_id, recipient, token, amount, nullifier, deadline);
     
-    // Decode signature (hex encoded)
+    // Decode signature (hex encoded): r ‖ s ‖ recovery id
     let sig_bytes = hex::decode(signature)
         .map_err(|_| ContractError::InvalidSignature {})?;
     
@@ -744,19 +1142,47 @@ fn verify_coordinator_signature(
         return Err(ContractError::InvalidSignature {});
     }
     
-    // Parse signature (r, s, v)
-    let _signature = K256Signature::try_from(&sig_bytes[0..64])
+    let public_key = deps
+        .api
+        .secp256k1_recover_pubkey(&message_hash, &sig_bytes[0..64], sig_bytes[64])
         .map_err(|_| ContractError::InvalidSignature {})?;
     
-    let _recovery_id = sig_bytes[64];
-    
-    // In production, recover public key and verify against coordinator
-    // For now, just validate signature format
-    // TODO: Implement full ECDSA verification with public key recovery
+    if !accepts_coordinator(deps, env, &account_hash(&public_key)?)? {
+        return Err(ContractError::InvalidCoordinator {});
+    }
     
     Ok(())
 }
 
+/// Whether `signer` is the coordinator, or the previous one while its
+/// overlap lasts
+fn accepts_coordinator(deps: Deps, env: &Env, signer: &[u8]) -> StdResult<bool> {
+    if address_bytes(&CONFIG.load(deps.storage)?.coordinator).as_deref() == Some(signer) {
+        return Ok(true);
+    }
+    Ok(PREVIOUS_COORDINATOR
+        .may_load(deps.storage)?
+        .is_some_and(|previous| {
+            env.block.time.seconds() < previous.valid_until
+                && address_bytes(&previous.coordinator).as_deref() == Some(signer)
+        }))
+}
+
+/// Account bytes behind a secp256k1 key, as Cosmos SDK addresses encode
+/// them: `ripemd160(sha256(compressed public key))`
+fn account_hash(public_key: &[u8]) -> Result<Vec<u8>, ContractError> {
+    let compressed = k256::PublicKey::from_sec1_bytes(public_key)
+        .map_err(|_| ContractError::InvalidSignature {})?
+        .to_encoded_point(true);
+    Ok(Ripemd160::digest(Sha256::digest(compressed.as_bytes())).to_vec())
+}
+
+/// Data part of a bech32 address, whatever its prefix
+fn address_bytes(address: &Addr) -> Option<Vec<u8>> {
+    let (_, data, _) = bech32::decode(address.as_str()).ok()?;
+    Vec::<u8>::from_base32(&data).ok()
+}
+
 // ============ Tests ============
 
 #[cfg(test)]
@@ -764,6 +1190,8 @@ mod tests {
     use super::*;
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
     use cosmwasm_std::coins;
+    use bech32::ToBase32;
+    use k256::ecdsa::SigningKey;
 
     #[test]
     fn proper_initialization() {
@@ -804,6 +1232,558 @@ mod tests {
         };
 
         let res = execute(deps.as_mut(), env, info, msg).unwrap();
-        assert_eq!(res.attributes.len(), 7);
+        assert_eq!(res.attributes.len(), 10);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn spent_nullifiers_paging() {
+        let mut deps = mock_dependencies();
+
+        for (nullifier, spent) in [
+            ("n1", true),
+            ("n2", false),
+            ("n3", true),
+            ("n4", true),
+            ("n5", false),
+            ("n6", true),
+        ] {
+            NULLIFIERS.save(deps.as_mut().storage, nullifier, &spent).unwrap();
+        }
+
+        let page = query_spent_nullifiers(deps.as_ref(), None, Some(2)).unwrap();
+        assert_eq!(page.nullifiers, vec!["n1", "n3"]);
+
+        let page = query_spent_nullifiers(deps.as_ref(), Some("n3".to_string()), Some(2)).unwrap();
+        assert_eq!(page.nullifiers, vec!["n4", "n6"]);
+
+        let page = query_spent_nullifiers(deps.as_ref(), Some("n6".to_string()), None).unwrap();
+        assert!(page.nullifiers.is_empty());
+    }
+
+    const COORDINATOR_KEY: [u8; 32] = [0x11; 32];
+    const NEXT_COORDINATOR_KEY: [u8; 32] = [0x22; 32];
+    /// Later than every block time the tests execute at
+    const DEADLINE: u64 = 2_000_000_000;
+
+    /// Account address of a secp256k1 key under the mock chain's prefix
+    fn key_address(key: [u8; 32]) -> String {
+        let public_key = SigningKey::from_slice(&key).unwrap().verifying_key().to_encoded_point(false);
+        let account = account_hash(public_key.as_bytes()).unwrap();
+        bech32::encode("cosmwasm", account.to_base32(), bech32::Variant::Bech32).unwrap()
+    }
+
+    /// Hex r ‖ s ‖ recovery id, as the coordinator authorizes `request`
+    fn sign_withdrawal(key: [u8; 32], request: &WithdrawalRequestInfo) -> String {
+        let message_hash = withdrawal_message_hash(
+            &request.withdrawal_id,
+            &request.recipient,
+            &request.token,
+            request.amount,
+            &request.nullifier,
+            DEADLINE,
+        );
+        let (signature, recovery_id) = SigningKey::from_slice(&key)
+            .unwrap()
+            .sign_prehash_recoverable(&message_hash)
+            .unwrap();
+        let mut bytes = signature.to_bytes().to_vec();
+        bytes.push(recovery_id.to_byte());
+        hex::encode(bytes)
+    }
+
+    fn instantiate_gateway(deps: DepsMut) {
+        let msg = InstantiateMsg {
+            coordinator: key_address(COORDINATOR_KEY),
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+    }
+
+    #[test]
+    fn deposit_near_max_total_overflows() {
+        let mut deps = mock_dependencies();
+        instantiate_gateway(deps.as_mut());
+
+        let near_max = Uint128::MAX - Uint128::new(10);
+        TOTAL_DEPOSITS.save(deps.as_mut().storage, &near_max).unwrap();
+
+        let info = mock_info("sender", &coins(1_000_000, "uosmo"));
+        let msg = ExecuteMsg::Deposit {
+            target_chain_id: 1,
+            recipient: "0".repeat(64),
+            zcash_address: "0".repeat(64),
+        };
+
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::Overflow {}));
+
+        // Nothing was written before the overflow was detected
+        assert_eq!(TOTAL_DEPOSITS.load(&deps.storage).unwrap(), near_max);
+        assert_eq!(DEPOSIT_COUNT.load(&deps.storage).unwrap(), 0);
+        assert!(LOCKED_BALANCES.may_load(&deps.storage, "uosmo").unwrap().is_none());
+    }
+
+    #[test]
+    fn withdrawal_near_max_total_overflows() {
+        let mut deps = mock_dependencies();
+        instantiate_gateway(deps.as_mut());
+
+        let request = WithdrawalRequestInfo {
+            withdrawal_id: "w1".to_string(),
+            recipient: Addr::unchecked("recipient"),
+            token: "uosmo".to_string(),
+            amount: Uint128::new(500),
+            nullifier: "n1".to_string(),
+            timestamp: 0,
+            executed: false,
+        };
+        WITHDRAWAL_REQUESTS.save(deps.as_mut().storage, "w1", &request).unwrap();
+        LOCKED_BALANCES.save(deps.as_mut().storage, "uosmo", &Uint128::new(1_000)).unwrap();
+        TOTAL_WITHDRAWALS.save(deps.as_mut().storage, &(Uint128::MAX - Uint128::new(1))).unwrap();
+
+        let msg = ExecuteMsg::ExecuteWithdrawal {
+            withdrawal_id: "w1".to_string(),
+            deadline: DEADLINE,
+            coordinator_signature: sign_withdrawal(COORDINATOR_KEY, &request),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::Overflow {}));
+
+        assert!(!WITHDRAWAL_REQUESTS.load(&deps.storage, "w1").unwrap().executed);
+        assert!(NULLIFIERS.may_load(&deps.storage, "n1").unwrap().is_none());
+        assert_eq!(LOCKED_BALANCES.load(&deps.storage, "uosmo").unwrap(), Uint128::new(1_000));
+    }
+
+    #[test]
+    fn expired_authorization_rejected() {
+        let mut deps = mock_dependencies();
+        instantiate_gateway(deps.as_mut());
+
+        let request = WithdrawalRequestInfo {
+            withdrawal_id: "w1".to_string(),
+            recipient: Addr::unchecked("recipient"),
+            token: NATIVE_DENOM.to_string(),
+            amount: Uint128::new(500),
+            nullifier: "n1".to_string(),
+            timestamp: 0,
+            executed: false,
+        };
+        WITHDRAWAL_REQUESTS.save(deps.as_mut().storage, "w1", &request).unwrap();
+        LOCKED_BALANCES.save(deps.as_mut().storage, NATIVE_DENOM, &Uint128::new(1_000)).unwrap();
+        let withdraw = |deadline: u64| ExecuteMsg::ExecuteWithdrawal {
+            withdrawal_id: "w1".to_string(),
+            deadline,
+            coordinator_signature: sign_withdrawal(COORDINATOR_KEY, &request),
+        };
+
+        let err = execute(deps.as_mut(), env_at(DEADLINE + 1), mock_info("relayer", &[]), withdraw(DEADLINE))
+            .unwrap_err();
+        assert!(matches!(err, ContractError::AuthorizationExpired {}));
+
+        // The deadline is signed, so a relayer can't push it back
+        let err = execute(deps.as_mut(), env_at(DEADLINE + 1), mock_info("relayer", &[]), withdraw(DEADLINE + 1))
+            .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidCoordinator {}));
+        assert!(!WITHDRAWAL_REQUESTS.load(&deps.storage, "w1").unwrap().executed);
+
+        // Still valid at the deadline itself
+        execute(deps.as_mut(), env_at(DEADLINE), mock_info("relayer", &[]), withdraw(DEADLINE)).unwrap();
+        assert!(WITHDRAWAL_REQUESTS.load(&deps.storage, "w1").unwrap().executed);
+    }
+
+    #[test]
+    fn checked_helpers_at_bounds() {
+        assert_eq!(checked_add(Uint128::MAX - Uint128::one(), Uint128::one()).unwrap(), Uint128::MAX);
+        assert!(matches!(checked_add(Uint128::MAX, Uint128::one()), Err(ContractError::Overflow {})));
+        assert_eq!(checked_sub(Uint128::MAX, Uint128::MAX).unwrap(), Uint128::zero());
+        assert!(matches!(checked_sub(Uint128::zero(), Uint128::one()), Err(ContractError::Underflow {})));
+    }
+
+    #[test]
+    fn stats_overflow_is_an_error_not_a_panic() {
+        let mut deps = mock_dependencies();
+        instantiate_gateway(deps.as_mut());
+
+        TOTAL_DEPOSITS.save(deps.as_mut().storage, &Uint128::MAX).unwrap();
+        TOTAL_WITHDRAWALS.save(deps.as_mut().storage, &Uint128::one()).unwrap();
+
+        let err = query_stats(deps.as_ref()).unwrap_err();
+        assert!(matches!(err, cosmwasm_std::StdError::Overflow { .. }));
+
+        TOTAL_WITHDRAWALS.save(deps.as_mut().storage, &Uint128::zero()).unwrap();
+        let stats = query_stats(deps.as_ref()).unwrap();
+        assert_eq!(stats.total_volume, Uint128::MAX);
+        assert_eq!(stats.active_deposits, Uint128::MAX);
+    }
+
+    #[test]
+    fn reentrant_withdrawal_rejected_and_tokens_allowlisted() {
+        let mut deps = mock_dependencies();
+        instantiate_gateway(deps.as_mut());
+
+        let mut signatures = Vec::new();
+        for (id, token) in [("w1", "cw20token"), ("w2", "evil")] {
+            let request = WithdrawalRequestInfo {
+                withdrawal_id: id.to_string(),
+                recipient: Addr::unchecked("recipient"),
+                token: token.to_string(),
+                amount: Uint128::new(500),
+                nullifier: format!("n-{}", id),
+                timestamp: 0,
+                executed: false,
+            };
+            WITHDRAWAL_REQUESTS.save(deps.as_mut().storage, id, &request).unwrap();
+            LOCKED_BALANCES.save(deps.as_mut().storage, token, &Uint128::new(1_000)).unwrap();
+            signatures.push((id, sign_withdrawal(COORDINATOR_KEY, &request)));
+        }
+
+        let allow = |token: &str| ExecuteMsg::SetTokenAllowed {
+            token: token.to_string(),
+            allowed: true,
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), allow("cw20token")).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), allow("cw20token")).unwrap();
+
+        let withdraw = |id: &str| ExecuteMsg::ExecuteWithdrawal {
+            withdrawal_id: id.to_string(),
+            deadline: DEADLINE,
+            coordinator_signature: signatures.iter().find(|(signed, _)| *signed == id).unwrap().1.clone(),
+        };
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), withdraw("w1")).unwrap();
+        assert_eq!(res.messages.len(), 1);
+
+        // The token contract calling back in during its transfer is rejected
+        let err = execute(deps.as_mut(), mock_env(), mock_info("cw20token", &[]), withdraw("w1")).unwrap_err();
+        assert!(matches!(err, ContractError::AlreadyExecuted {}));
+        assert_eq!(LOCKED_BALANCES.load(&deps.storage, "cw20token").unwrap(), Uint128::new(500));
+
+        // A token that was never allowlisted is never called
+        let err = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), withdraw("w2")).unwrap_err();
+        assert!(matches!(err, ContractError::TokenNotAllowed {}));
+        assert!(!WITHDRAWAL_REQUESTS.load(&deps.storage, "w2").unwrap().executed);
+    }
+
+    fn cw20_deposit(token: &str, amount: u128) -> (MessageInfo, ExecuteMsg) {
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "depositor".to_string(),
+            amount: Uint128::new(amount),
+            msg: to_json_binary(&ReceiveMsg::Deposit {
+                target_chain_id: 8453,
+                recipient: "0".repeat(64),
+                zcash_address: "0".repeat(64),
+            })
+            .unwrap(),
+        });
+        (mock_info(token, &[]), msg)
+    }
+
+    #[test]
+    fn cw20_send_creates_deposit() {
+        let mut deps = mock_dependencies();
+        instantiate_gateway(deps.as_mut());
+
+        let allow = ExecuteMsg::SetTokenAllowed {
+            token: "cw20token".to_string(),
+            allowed: true,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), allow).unwrap();
+
+        let (info, msg) = cw20_deposit("cw20token", 5_000);
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let deposit_id = &res.attributes.iter().find(|a| a.key == "deposit_id").unwrap().value;
+        let deposit = DEPOSITS.load(&deps.storage, deposit_id).unwrap();
+        assert_eq!(deposit.token, "cw20token");
+        assert_eq!(deposit.sender, "depositor");
+        assert_eq!(deposit.target_chain_id, 8453);
+
+        // Locked against the CW20 contract, not the native denom
+        assert_eq!(LOCKED_BALANCES.load(&deps.storage, "cw20token").unwrap(), deposit.amount);
+        assert!(LOCKED_BALANCES.may_load(&deps.storage, "uosmo").unwrap().is_none());
+        assert_eq!(DEPOSIT_COUNT.load(&deps.storage).unwrap(), 1);
+    }
+
+    #[test]
+    fn cw20_send_from_unlisted_token_rejected() {
+        let mut deps = mock_dependencies();
+        instantiate_gateway(deps.as_mut());
+
+        let (info, msg) = cw20_deposit("unknown", 5_000);
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::TokenNotAllowed {}));
+        assert_eq!(DEPOSIT_COUNT.load(&deps.storage).unwrap(), 0);
+    }
+
+    #[test]
+    fn cw20_send_after_token_delisted_rejected() {
+        let mut deps = mock_dependencies();
+        instantiate_gateway(deps.as_mut());
+
+        for allowed in [true, false] {
+            let msg = ExecuteMsg::SetTokenAllowed {
+                token: "cw20token".to_string(),
+                allowed,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+
+        let (info, msg) = cw20_deposit("cw20token", 5_000);
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::TokenNotAllowed {}));
+        assert!(LOCKED_BALANCES.may_load(&deps.storage, "cw20token").unwrap().is_none());
+    }
+
+    fn propose_owner(deps: DepsMut, sender: &str, new_owner: &str) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::ProposeOwner {
+            new_owner: new_owner.to_string(),
+        };
+        execute(deps, mock_env(), mock_info(sender, &[]), msg)
+    }
+
+    fn accept_owner(deps: DepsMut, sender: &str) -> Result<Response, ContractError> {
+        execute(deps, mock_env(), mock_info(sender, &[]), ExecuteMsg::AcceptOwner {})
+    }
+
+    #[test]
+    fn owner_proposal_does_not_transfer_ownership() {
+        let mut deps = mock_dependencies();
+        instantiate_gateway(deps.as_mut());
+
+        // Only the owner proposes
+        let err = propose_owner(deps.as_mut(), "stranger", "stranger").unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        let res = propose_owner(deps.as_mut(), "creator", "newowner").unwrap();
+        assert!(res.attributes.iter().any(|a| a.key == "action" && a.value == "propose_owner"));
+        assert!(res.attributes.iter().any(|a| a.key == "pending_owner" && a.value == "newowner"));
+
+        assert_eq!(PENDING_OWNER.load(&deps.storage).unwrap(), "newowner");
+        assert_eq!(CONFIG.load(&deps.storage).unwrap().owner, "creator");
+    }
+
+    #[test]
+    fn pending_owner_accepts_ownership() {
+        let mut deps = mock_dependencies();
+        instantiate_gateway(deps.as_mut());
+        propose_owner(deps.as_mut(), "creator", "newowner").unwrap();
+
+        let res = accept_owner(deps.as_mut(), "newowner").unwrap();
+        assert!(res.attributes.iter().any(|a| a.key == "old_owner" && a.value == "creator"));
+        assert!(res.attributes.iter().any(|a| a.key == "new_owner" && a.value == "newowner"));
+        assert_eq!(CONFIG.load(&deps.storage).unwrap().owner, "newowner");
+        assert!(PENDING_OWNER.may_load(&deps.storage).unwrap().is_none());
+
+        // The new owner has admin rights, the old one lost them
+        let set_paused = |paused| ExecuteMsg::SetPaused { paused };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), set_paused(true)).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        execute(deps.as_mut(), mock_env(), mock_info("newowner", &[]), set_paused(true)).unwrap();
+    }
+
+    #[test]
+    fn accept_owner_rejected_for_other_accounts() {
+        let mut deps = mock_dependencies();
+        instantiate_gateway(deps.as_mut());
+
+        let err = accept_owner(deps.as_mut(), "newowner").unwrap_err();
+        assert!(matches!(err, ContractError::NoPendingOwner {}));
+
+        propose_owner(deps.as_mut(), "creator", "newowner").unwrap();
+        for sender in ["stranger", "creator"] {
+            let err = accept_owner(deps.as_mut(), sender).unwrap_err();
+            assert!(matches!(err, ContractError::Unauthorized {}));
+        }
+        assert_eq!(CONFIG.load(&deps.storage).unwrap().owner, "creator");
+        assert_eq!(PENDING_OWNER.load(&deps.storage).unwrap(), "newowner");
+    }
+
+    fn env_at(seconds: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = cosmwasm_std::Timestamp::from_seconds(seconds);
+        env
+    }
+
+    fn set_coordinator(deps: DepsMut, seconds: u64, new_coordinator: &str) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::SetCoordinator {
+            new_coordinator: new_coordinator.to_string(),
+        };
+        execute(deps, env_at(seconds), mock_info("creator", &[]), msg)
+    }
+
+    #[test]
+    fn coordinator_change_activates_after_timelock() {
+        let mut deps = mock_dependencies();
+        instantiate_gateway(deps.as_mut());
+
+        let res = set_coordinator(deps.as_mut(), 1_000, "newcoordinator").unwrap();
+        assert!(res.attributes.iter().any(|a| a.key == "action" && a.value == "propose_coordinator"));
+        let due = 1_000 + DEFAULT_COORDINATOR_TIMELOCK;
+        assert_eq!(PENDING_COORDINATOR.load(&deps.storage).unwrap().activates_at, due);
+        // Still the old coordinator until activated
+        assert_eq!(CONFIG.load(&deps.storage).unwrap().coordinator, key_address(COORDINATOR_KEY));
+
+        let activate = || ExecuteMsg::ActivateCoordinator {};
+        let err = execute(deps.as_mut(), env_at(due - 1), mock_info("anyone", &[]), activate()).unwrap_err();
+        assert!(matches!(err, ContractError::TimelockNotExpired {}));
+
+        let res = execute(deps.as_mut(), env_at(due), mock_info("anyone", &[]), activate()).unwrap();
+        assert!(res.attributes.iter().any(|a| a.key == "new_coordinator" && a.value == "newcoordinator"));
+        assert_eq!(CONFIG.load(&deps.storage).unwrap().coordinator, "newcoordinator");
+        assert!(PENDING_COORDINATOR.may_load(&deps.storage).unwrap().is_none());
+    }
+
+    #[test]
+    fn cancelled_coordinator_change_never_activates() {
+        let mut deps = mock_dependencies();
+        instantiate_gateway(deps.as_mut());
+        set_coordinator(deps.as_mut(), 1_000, "newcoordinator").unwrap();
+
+        let cancel = || ExecuteMsg::CancelCoordinatorChange {};
+        let err = execute(deps.as_mut(), env_at(1_001), mock_info("stranger", &[]), cancel()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let res = execute(deps.as_mut(), env_at(1_001), mock_info("creator", &[]), cancel()).unwrap();
+        assert!(res.attributes.iter().any(|a| a.key == "action" && a.value == "cancel_coordinator_change"));
+
+        let due = 1_000 + DEFAULT_COORDINATOR_TIMELOCK;
+        let msg = ExecuteMsg::ActivateCoordinator {};
+        let err = execute(deps.as_mut(), env_at(due), mock_info("creator", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::NoPendingCoordinator {}));
+        assert_eq!(CONFIG.load(&deps.storage).unwrap().coordinator, key_address(COORDINATOR_KEY));
+    }
+
+    #[test]
+    fn coordinator_timelock_has_floor() {
+        let mut deps = mock_dependencies();
+        instantiate_gateway(deps.as_mut());
+
+        let set_timelock = |seconds| ExecuteMsg::SetCoordinatorTimelock { seconds };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            set_timelock(MIN_COORDINATOR_TIMELOCK - 1),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::TimelockTooShort {}));
+
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), set_timelock(MIN_COORDINATOR_TIMELOCK)).unwrap();
+        set_coordinator(deps.as_mut(), 1_000, "newcoordinator").unwrap();
+        assert_eq!(
+            PENDING_COORDINATOR.load(&deps.storage).unwrap().activates_at,
+            1_000 + MIN_COORDINATOR_TIMELOCK
+        );
+    }
+
+    #[test]
+    fn previous_coordinator_accepted_only_during_overlap() {
+        let mut deps = mock_dependencies();
+        instantiate_gateway(deps.as_mut());
+
+        let mut requests = Vec::new();
+        for id in ["w1", "w2", "w3", "w4"] {
+            let request = WithdrawalRequestInfo {
+                withdrawal_id: id.to_string(),
+                recipient: Addr::unchecked("recipient"),
+                token: NATIVE_DENOM.to_string(),
+                amount: Uint128::new(100),
+                nullifier: format!("n-{}", id),
+                timestamp: 0,
+                executed: false,
+            };
+            WITHDRAWAL_REQUESTS.save(deps.as_mut().storage, id, &request).unwrap();
+            requests.push(request);
+        }
+        LOCKED_BALANCES.save(deps.as_mut().storage, NATIVE_DENOM, &Uint128::new(1_000)).unwrap();
+        let withdraw = |deps: DepsMut, seconds: u64, key: [u8; 32], request: &WithdrawalRequestInfo| {
+            let msg = ExecuteMsg::ExecuteWithdrawal {
+                withdrawal_id: request.withdrawal_id.clone(),
+                deadline: DEADLINE,
+                coordinator_signature: sign_withdrawal(key, request),
+            };
+            execute(deps, env_at(seconds), mock_info("relayer", &[]), msg)
+        };
+
+        set_coordinator(deps.as_mut(), 1_000, &key_address(NEXT_COORDINATOR_KEY)).unwrap();
+        let due = 1_000 + DEFAULT_COORDINATOR_TIMELOCK;
+        // The new key only counts once the timelock has passed
+        let err = withdraw(deps.as_mut(), due - 1, NEXT_COORDINATOR_KEY, &requests[0]).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidCoordinator {}));
+
+        // The overlap runs from activation, not from the proposal
+        let res = execute(deps.as_mut(), env_at(due), mock_info("anyone", &[]), ExecuteMsg::ActivateCoordinator {}).unwrap();
+        let valid_until = due + DEFAULT_COORDINATOR_OVERLAP;
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "old_coordinator_valid_until" && a.value == valid_until.to_string()));
+
+        // Both keys verify during the overlap
+        withdraw(deps.as_mut(), valid_until - 1, COORDINATOR_KEY, &requests[0]).unwrap();
+        withdraw(deps.as_mut(), valid_until - 1, NEXT_COORDINATOR_KEY, &requests[1]).unwrap();
+
+        // Only the new one after it
+        let err = withdraw(deps.as_mut(), valid_until, COORDINATOR_KEY, &requests[2]).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidCoordinator {}));
+        assert!(!WITHDRAWAL_REQUESTS.load(&deps.storage, "w3").unwrap().executed);
+        withdraw(deps.as_mut(), valid_until, NEXT_COORDINATOR_KEY, &requests[3]).unwrap();
+        assert_eq!(LOCKED_BALANCES.load(&deps.storage, NATIVE_DENOM).unwrap(), Uint128::new(700));
+    }
+
+    #[test]
+    fn coordinator_overlap_has_ceiling() {
+        let mut deps = mock_dependencies();
+        instantiate_gateway(deps.as_mut());
+
+        let set_overlap = |seconds| ExecuteMsg::SetCoordinatorOverlap { seconds };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("stranger", &[]), set_overlap(60)).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            set_overlap(MAX_COORDINATOR_OVERLAP + 1),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::OverlapTooLong {}));
+
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), set_overlap(60)).unwrap();
+        set_coordinator(deps.as_mut(), 1_000, "newcoordinator").unwrap();
+        let due = 1_000 + DEFAULT_COORDINATOR_TIMELOCK;
+        execute(deps.as_mut(), env_at(due), mock_info("anyone", &[]), ExecuteMsg::ActivateCoordinator {}).unwrap();
+        assert_eq!(PREVIOUS_COORDINATOR.load(&deps.storage).unwrap().valid_until, due + 60);
+    }
+
+    #[test]
+    fn deposit_root_follows_deposits() {
+        let mut deps = mock_dependencies();
+        instantiate_gateway(deps.as_mut());
+
+        let empty = query_deposit_root(deps.as_ref()).unwrap();
+        assert_eq!(empty.deposit_count, 0);
+        assert_eq!(empty.root, hex::encode(DepositAccumulator::default().root));
+
+        let mut expected = DepositAccumulator::default();
+        let mut roots = vec![empty.root];
+        for (i, sender) in ["alice", "bob", "carol"].iter().enumerate() {
+            let msg = ExecuteMsg::Deposit {
+                target_chain_id: 1,
+                recipient: "0".repeat(64),
+                zcash_address: "0".repeat(64),
+            };
+            let res = execute(deps.as_mut(), mock_env(), mock_info(sender, &coins(1_000_000, "uosmo")), msg).unwrap();
+            let attribute = |key: &str| res.attributes.iter().find(|a| a.key == key).unwrap().value.clone();
+
+            // Recomputable from the deposit IDs alone
+            assert_eq!(expected.insert(&attribute("deposit_id")).unwrap(), i as u64);
+            assert_eq!(attribute("deposit_index"), i.to_string());
+            assert_eq!(attribute("deposit_root"), hex::encode(expected.root));
+
+            let root = query_deposit_root(deps.as_ref()).unwrap();
+            assert_eq!(root.deposit_count, i as u64 + 1);
+            assert_eq!(root.root, hex::encode(expected.root));
+            assert!(!roots.contains(&root.root));
+            roots.push(root.root);
+        }
+    }
+}
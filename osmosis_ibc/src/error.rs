@@ -13,6 +13,21 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("No pending owner")]
+    NoPendingOwner {},
+
+    #[error("No pending coordinator change")]
+    NoPendingCoordinator {},
+
+    #[error("Coordinator timelock not expired")]
+    TimelockNotExpired {},
+
+    #[error("Timelock too short")]
+    TimelockTooShort {},
+
+    #[error("Coordinator overlap too long")]
+    OverlapTooLong {},
+
     #[error("Gateway is paused")]
     Paused {},
 
@@ -55,6 +70,24 @@ pub enum ContractError {
     #[error("Invalid signature")]
     InvalidSignature {},
 
+    #[error("Signature is not from the coordinator")]
+    InvalidCoordinator {},
+
+    #[error("Coordinator authorization has expired")]
+    AuthorizationExpired {},
+
     #[error("Fee too high")]
     FeeTooHigh {},
+
+    #[error("Token not allowed")]
+    TokenNotAllowed {},
+
+    #[error("Deposit tree is full")]
+    DepositTreeFull {},
+
+    #[error("Arithmetic overflow")]
+    Overflow {},
+
+    #[error("Arithmetic underflow")]
+    Underflow {},
 }
@@ -4,6 +4,7 @@
 
 pub mod contract;
 pub mod error;
+pub mod merkle;
 pub mod msg;
 pub mod state;
 
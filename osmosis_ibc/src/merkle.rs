@@ -0,0 +1,182 @@
+// ============================================
+// contracts/osmosis/src/merkle.rs
+// Incremental merkle accumulator over deposit IDs
+
+use cosmwasm_schema::cw_serde;
+use sha2::{Digest, Sha256};
+
+use crate::error::ContractError;
+
+/// Depth of the deposit tree; room for 2^32 deposits
+pub const DEPOSIT_TREE_DEPTH: usize = 32;
+
+/// Append-only merkle tree of deposit IDs. Only the rightmost filled node
+/// per level is kept, so each deposit costs `DEPOSIT_TREE_DEPTH` hashes and
+/// constant storage. Empty leaves are all-zero.
+///
+/// Leaves are `sha256(0x00 || deposit_id)` over the deposit ID string and
+/// inner nodes `sha256(0x01 || left || right)`.
+#[cw_serde]
+pub struct DepositAccumulator {
+    /// Leaf index the next deposit gets; also the number of deposits
+    pub next_index: u64,
+    pub filled_subtrees: Vec<[u8; 32]>,
+    pub root: [u8; 32],
+}
+
+impl Default for DepositAccumulator {
+    fn default() -> Self {
+        let mut zero = [0u8; 32];
+        for _ in 0..DEPOSIT_TREE_DEPTH {
+            zero = node_hash(&zero, &zero);
+        }
+        Self {
+            next_index: 0,
+            filled_subtrees: vec![[0u8; 32]; DEPOSIT_TREE_DEPTH],
+            root: zero,
+        }
+    }
+}
+
+impl DepositAccumulator {
+    /// Append a deposit and update the root. Returns the deposit's leaf index.
+    pub fn insert(&mut self, deposit_id: &str) -> Result<u64, ContractError> {
+        let index = self.next_index;
+        if index >= 1u64 << DEPOSIT_TREE_DEPTH {
+            return Err(ContractError::DepositTreeFull {});
+        }
+
+        let mut node = leaf_hash(deposit_id);
+        let mut zero = [0u8; 32];
+        let mut position = index;
+        for filled in self.filled_subtrees.iter_mut() {
+            node = if position & 1 == 0 {
+                // Left child; its right sibling is still empty
+                *filled = node;
+                node_hash(&node, &zero)
+            } else {
+                node_hash(filled, &node)
+            };
+            zero = node_hash(&zero, &zero);
+            position /= 2;
+        }
+
+        self.root = node;
+        self.next_index = index + 1;
+        Ok(index)
+    }
+}
+
+pub fn leaf_hash(deposit_id: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0x00]);
+    hasher.update(deposit_id.as_bytes());
+    hasher.finalize().into()
+}
+
+pub fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0x01]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Whether `deposit_id` is leaf `index` of the tree with `root`, given its
+/// sibling hashes from the leaf level up
+pub fn verify_deposit_proof(root: &[u8; 32], deposit_id: &str, index: u64, siblings: &[[u8; 32]]) -> bool {
+    if siblings.len() != DEPOSIT_TREE_DEPTH {
+        return false;
+    }
+
+    let mut node = leaf_hash(deposit_id);
+    let mut position = index;
+    for sibling in siblings {
+        node = if position & 1 == 0 {
+            node_hash(&node, sibling)
+        } else {
+            node_hash(sibling, &node)
+        };
+        position /= 2;
+    }
+    position == 0 && node == *root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Root and per-leaf sibling paths, computed over the whole tree
+    fn full_tree(deposit_ids: &[&str]) -> ([u8; 32], Vec<Vec<[u8; 32]>>) {
+        let mut level: Vec<[u8; 32]> = deposit_ids.iter().map(|id| leaf_hash(id)).collect();
+        let mut proofs = vec![Vec::new(); deposit_ids.len()];
+        let mut zero = [0u8; 32];
+        for _ in 0..DEPOSIT_TREE_DEPTH {
+            if level.len() % 2 == 1 {
+                level.push(zero);
+            }
+            for (leaf, proof) in proofs.iter_mut().enumerate() {
+                let position = leaf >> proof.len();
+                proof.push(level[position ^ 1]);
+            }
+            level = level.chunks(2).map(|pair| node_hash(&pair[0], &pair[1])).collect();
+            zero = node_hash(&zero, &zero);
+        }
+        (level.first().copied().unwrap_or(zero), proofs)
+    }
+
+    #[test]
+    fn root_tracks_every_deposit_deterministically() {
+        let ids = ["d0", "d1", "d2", "d3", "d4"];
+        let mut accumulator = DepositAccumulator::default();
+        let mut replay = DepositAccumulator::default();
+        assert_eq!(accumulator.root, full_tree(&[]).0);
+
+        let mut roots = vec![accumulator.root];
+        for (i, id) in ids.iter().enumerate() {
+            assert_eq!(accumulator.insert(id).unwrap(), i as u64);
+            replay.insert(id).unwrap();
+            assert_eq!(accumulator.root, full_tree(&ids[..=i]).0);
+            assert_eq!(accumulator.root, replay.root);
+            assert!(!roots.contains(&accumulator.root));
+            roots.push(accumulator.root);
+        }
+        assert_eq!(accumulator.next_index, 5);
+
+        // Order matters
+        let mut swapped = DepositAccumulator::default();
+        swapped.insert("d1").unwrap();
+        swapped.insert("d0").unwrap();
+        assert_ne!(swapped.root, roots[2]);
+    }
+
+    #[test]
+    fn inclusion_proofs_verify_against_root() {
+        let ids = ["d0", "d1", "d2", "d3", "d4", "d5", "d6"];
+        let mut accumulator = DepositAccumulator::default();
+        for id in ids {
+            accumulator.insert(id).unwrap();
+        }
+        let (root, proofs) = full_tree(&ids);
+        assert_eq!(accumulator.root, root);
+
+        for (index, id) in ids.iter().enumerate() {
+            assert!(verify_deposit_proof(&root, id, index as u64, &proofs[index]));
+        }
+        // Wrong leaf, wrong position, truncated path
+        assert!(!verify_deposit_proof(&root, "d9", 0, &proofs[0]));
+        assert!(!verify_deposit_proof(&root, "d0", 1, &proofs[0]));
+        assert!(!verify_deposit_proof(&root, "d0", 0, &proofs[0][1..]));
+    }
+
+    #[test]
+    fn full_tree_rejects_inserts() {
+        let mut accumulator = DepositAccumulator {
+            next_index: 1u64 << DEPOSIT_TREE_DEPTH,
+            ..DepositAccumulator::default()
+        };
+        let root = accumulator.root;
+        assert!(matches!(accumulator.insert("d0"), Err(ContractError::DepositTreeFull {})));
+        assert_eq!(accumulator.root, root);
+    }
+}
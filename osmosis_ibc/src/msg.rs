@@ -4,6 +4,7 @@
 
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::Uint128;
+use cw20::Cw20ReceiveMsg;
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -17,6 +18,8 @@ pub enum ExecuteMsg {
         recipient: String,
         zcash_address: String,
     },
+    /// CW20 deposit: `send` tokens to the gateway with a `ReceiveMsg`
+    Receive(Cw20ReceiveMsg),
     RequestWithdrawal {
         token: String,
         amount: Uint128,
@@ -26,6 +29,9 @@ pub enum ExecuteMsg {
     },
     ExecuteWithdrawal {
         withdrawal_id: String,
+        /// Last block time (unix seconds) the authorization is valid at;
+        /// the coordinator signs it along with the withdrawal
+        deadline: u64,
         coordinator_signature: String,
     },
     AddLiquidity {
@@ -35,9 +41,28 @@ pub enum ExecuteMsg {
         token: String,
         amount: Uint128,
     },
+    /// Schedule a coordinator change, applied by `ActivateCoordinator`
+    /// once the coordinator timelock has passed
     SetCoordinator {
         new_coordinator: String,
     },
+    ActivateCoordinator {},
+    CancelCoordinatorChange {},
+    /// Delay for coordinator changes proposed from now on
+    SetCoordinatorTimelock {
+        seconds: u64,
+    },
+    /// How long the replaced coordinator keeps verifying, for changes
+    /// activated from now on
+    SetCoordinatorOverlap {
+        seconds: u64,
+    },
+    /// First step of an ownership transfer; ownership moves only once the
+    /// proposed account sends `AcceptOwner`
+    ProposeOwner {
+        new_owner: String,
+    },
+    AcceptOwner {},
     AddLiquidityProvider {
         provider: String,
     },
@@ -50,6 +75,11 @@ pub enum ExecuteMsg {
     SetBridgeFee {
         new_fee: u16,
     },
+    /// Owner-managed allowlist of CW20 token contracts
+    SetTokenAllowed {
+        token: String,
+        allowed: bool,
+    },
     EmergencyWithdraw {
         token: String,
         to: String,
@@ -57,6 +87,16 @@ pub enum ExecuteMsg {
     },
 }
 
+/// Payload of a CW20 `send` into the gateway
+#[cw_serde]
+pub enum ReceiveMsg {
+    Deposit {
+        target_chain_id: u64,
+        recipient: String,
+        zcash_address: String,
+    },
+}
+
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
@@ -69,6 +109,13 @@ pub enum QueryMsg {
     #[returns(bool)]
     IsNullifierUsed { nullifier: String },
     
+    /// Page through spent nullifiers in key order for incremental indexer sync
+    #[returns(SpentNullifiersResponse)]
+    GetSpentNullifiers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    
     #[returns(crate::state::DepositInfo)]
     GetDeposit { deposit_id: String },
     
@@ -80,4 +127,36 @@ pub enum QueryMsg {
     
     #[returns(crate::state::Config)]
     GetConfig {},
-}
\ No newline at end of file
+    
+    #[returns(Option<crate::state::PendingCoordinator>)]
+    GetPendingCoordinator {},
+    
+    /// Replaced coordinator and until when its signatures still verify
+    #[returns(Option<crate::state::PreviousCoordinator>)]
+    GetPreviousCoordinator {},
+    
+    /// Account proposed by `ProposeOwner` that has not accepted yet
+    #[returns(Option<cosmwasm_std::Addr>)]
+    GetPendingOwner {},
+    
+    #[returns(bool)]
+    IsTokenAllowed { token: String },
+    
+    /// Root of the merkle accumulator over all deposit IDs
+    #[returns(DepositRootResponse)]
+    GetDepositRoot {},
+}
+
+#[cw_serde]
+pub struct SpentNullifiersResponse {
+    pub nullifiers: Vec<String>,
+}
+
+/// Deposit `i` (as reported in its `deposit_index` attribute) is leaf `i`;
+/// see `merkle::verify_deposit_proof`
+#[cw_serde]
+pub struct DepositRootResponse {
+    /// Hex-encoded root
+    pub root: String,
+    pub deposit_count: u64,
+}
@@ -6,6 +6,8 @@ use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Addr, Uint128};
 use cw_storage_plus::{Item, Map};
 
+use crate::merkle::DepositAccumulator;
+
 #[cw_serde]
 pub struct Config {
     pub owner: Addr,
@@ -14,6 +16,22 @@ pub struct Config {
     pub bridge_fee: u16,
 }
 
+/// Coordinator change waiting out the timelock
+#[cw_serde]
+pub struct PendingCoordinator {
+    pub coordinator: Addr,
+    /// Block time (unix seconds) from which it can be activated
+    pub activates_at: u64,
+}
+
+/// Coordinator replaced by the last activation, still accepted for a while
+#[cw_serde]
+pub struct PreviousCoordinator {
+    pub coordinator: Addr,
+    /// Block time (unix seconds) from which its signatures are rejected
+    pub valid_until: u64,
+}
+
 #[cw_serde]
 pub struct DepositInfo {
     pub deposit_id: String,
@@ -48,12 +66,24 @@ pub struct BridgeStats {
 
 // Storage
 pub const CONFIG: Item<Config> = Item::new("config");
+pub const PENDING_COORDINATOR: Item<PendingCoordinator> = Item::new("pending_coordinator");
+/// Seconds a coordinator change waits before it can be activated
+pub const COORDINATOR_TIMELOCK: Item<u64> = Item::new("coordinator_timelock");
+pub const PREVIOUS_COORDINATOR: Item<PreviousCoordinator> = Item::new("previous_coordinator");
+/// Seconds a replaced coordinator keeps verifying after activation
+pub const COORDINATOR_OVERLAP: Item<u64> = Item::new("coordinator_overlap");
+/// Proposed owner awaiting `AcceptOwner`
+pub const PENDING_OWNER: Item<Addr> = Item::new("pending_owner");
 pub const DEPOSITS: Map<&str, DepositInfo> = Map::new("deposits");
 pub const WITHDRAWAL_REQUESTS: Map<&str, WithdrawalRequestInfo> = Map::new("withdrawal_requests");
 pub const NULLIFIERS: Map<&str, bool> = Map::new("nullifiers");
 pub const LOCKED_BALANCES: Map<&str, Uint128> = Map::new("locked_balances");
 pub const LIQUIDITY_PROVIDERS: Map<&Addr, bool> = Map::new("liquidity_providers");
 pub const DEPOSIT_COUNT: Item<u64> = Item::new("deposit_count");
+/// Merkle accumulator over every deposit ID, in deposit order
+pub const DEPOSIT_TREE: Item<DepositAccumulator> = Item::new("deposit_tree");
 pub const WITHDRAWAL_COUNT: Item<u64> = Item::new("withdrawal_count");
 pub const TOTAL_DEPOSITS: Item<Uint128> = Item::new("total_deposits");
 pub const TOTAL_WITHDRAWALS: Item<Uint128> = Item::new("total_withdrawals");
+/// CW20 contracts the gateway may call; native `uosmo` is always allowed
+pub const ALLOWED_TOKENS: Map<&str, bool> = Map::new("allowed_tokens");
@@ -0,0 +1,326 @@
+// relayer/src/authorization.rs
+//! Verify coordinator authorizations before executing them, against the
+//! signing keys the coordinator serves at `GET /coordinator/pubkey`
+
+use anyhow::{Context, Result};
+use ethers::core::k256::ecdsa::VerifyingKey;
+use ethers::types::{Address, RecoveryMessage, Signature, H256};
+use ethers::utils::public_key_to_address;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+use zerobridge_types::{withdrawal_authorization_hash, AuthorizationScheme, CoordinatorPubkey};
+
+use crate::coordinator_client::{AuthorizedWithdrawal, CoordinatorClient};
+
+/// Checks authorization signatures against the coordinator's keys, fetched
+/// on first use and cached
+pub struct AuthorizationVerifier {
+    coordinator: Arc<CoordinatorClient>,
+    /// Keys authorizations may be signed with
+    keys: RwLock<Vec<AcceptedKey>>,
+}
+
+/// A coordinator key and, once rotated out, until when its signatures are
+/// still accepted (unix seconds)
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct AcceptedKey {
+    address: Address,
+    valid_until: Option<i64>,
+}
+
+impl AcceptedKey {
+    fn accepts(&self, signer: Address, now: i64) -> bool {
+        self.address == signer && self.valid_until.is_none_or(|until| now < until)
+    }
+}
+
+impl AuthorizationVerifier {
+    pub fn new(coordinator: Arc<CoordinatorClient>) -> Self {
+        Self {
+            coordinator,
+            keys: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Whether the coordinator signed exactly this withdrawal for the target
+    /// gateway's `scheme`, with its current key or a rotated-out key still
+    /// inside its overlap window, and its deadline hasn't passed (the
+    /// gateway would refuse it). A signature from any other key re-fetches
+    /// the coordinator's keys once, in case they were rotated since they
+    /// were cached.
+    pub async fn verify_withdrawal(
+        &self,
+        withdrawal: &AuthorizedWithdrawal,
+        scheme: AuthorizationScheme,
+    ) -> Result<bool> {
+        self.verify_withdrawal_at(withdrawal, scheme, chrono::Utc::now().timestamp()).await
+    }
+
+    async fn verify_withdrawal_at(
+        &self,
+        withdrawal: &AuthorizedWithdrawal,
+        scheme: AuthorizationScheme,
+        now: i64,
+    ) -> Result<bool> {
+        if (withdrawal.deadline as i64) < now {
+            return Ok(false);
+        }
+        let Some(signer) = withdrawal_signer(withdrawal, scheme) else {
+            return Ok(false);
+        };
+
+        if self.keys.read().await.iter().any(|key| key.accepts(signer, now)) {
+            return Ok(true);
+        }
+
+        let keys = self.refresh().await?;
+        Ok(keys.iter().any(|key| key.accepts(signer, now)))
+    }
+
+    /// Fetch the coordinator's current key and the rotated-out keys it
+    /// still vouches for
+    async fn refresh(&self) -> Result<Vec<AcceptedKey>> {
+        let pubkey = self
+            .coordinator
+            .coordinator_pubkey()
+            .await
+            .context("Failed to fetch coordinator public key")?;
+        let keys = accepted_keys(&pubkey)?;
+
+        let mut cached = self.keys.write().await;
+        if keys.first() != cached.first() {
+            info!("Coordinator signing key: {} ({:?})", pubkey.public_key, keys[0].address);
+        }
+        *cached = keys.clone();
+        Ok(keys)
+    }
+}
+
+/// The current key first, then retired keys with an overlap window
+fn accepted_keys(pubkey: &CoordinatorPubkey) -> Result<Vec<AcceptedKey>> {
+    let address = key_address(&pubkey.public_key)?;
+    if format!("{:?}", address) != pubkey.address.to_lowercase() {
+        warn!(
+            "Coordinator key {} announced with address {}, using {:?}",
+            pubkey.public_key, pubkey.address, address
+        );
+    }
+
+    let mut keys = vec![AcceptedKey { address, valid_until: None }];
+    for key in &pubkey.history {
+        // Retired without an overlap window: no longer accepted
+        let Some(valid_until) = key.valid_until.filter(|_| key.retired_at.is_some()) else {
+            continue;
+        };
+        match key_address(&key.public_key) {
+            Ok(address) => keys.push(AcceptedKey { address, valid_until: Some(valid_until) }),
+            Err(e) => warn!("Ignoring retired coordinator key {}: {:#}", key.public_key, e),
+        }
+    }
+    Ok(keys)
+}
+
+/// Ethereum address of a hex SEC1 secp256k1 public key
+fn key_address(public_key: &str) -> Result<Address> {
+    let bytes = hex::decode(public_key.trim_start_matches("0x")).context("Coordinator public key is not hex")?;
+    let key = VerifyingKey::from_sec1_bytes(&bytes).context("Invalid coordinator public key")?;
+    Ok(public_key_to_address(&key))
+}
+
+/// Address whose key signed the message the target gateway will check, if
+/// the withdrawal fits that gateway and the signature is well-formed
+fn withdrawal_signer(withdrawal: &AuthorizedWithdrawal, scheme: AuthorizationScheme) -> Option<Address> {
+    let message = withdrawal_authorization_hash(
+        scheme,
+        &withdrawal.withdrawal_id,
+        &withdrawal.recipient,
+        &withdrawal.token,
+        withdrawal.amount,
+        &withdrawal.nullifier,
+        withdrawal.deadline,
+    )
+    .ok()?;
+    Signature::try_from(withdrawal.authorization_signature.as_slice())
+        .and_then(|signature| signature.recover(RecoveryMessage::Hash(H256::from(message))))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::signers::{LocalWallet, Signer};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const COORDINATOR_KEY: &str = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+    const BASE: AuthorizationScheme = AuthorizationScheme::Evm { chain_id: 8453 };
+
+    fn public_key(wallet: &LocalWallet) -> String {
+        hex::encode(wallet.signer().verifying_key().to_encoded_point(true).as_bytes())
+    }
+
+    /// Coordinator stand-in serving `wallet`'s public key, counting fetches
+    async fn mock_coordinator(wallet: &LocalWallet, fetches: Arc<AtomicUsize>) -> String {
+        rotated_coordinator(wallet, &[], fetches).await
+    }
+
+    /// Coordinator stand-in that rotated to `wallet` from each of `retired`,
+    /// whose signatures stay valid until the paired time
+    async fn rotated_coordinator(
+        wallet: &LocalWallet,
+        retired: &[(&LocalWallet, i64)],
+        fetches: Arc<AtomicUsize>,
+    ) -> String {
+        use axum::{routing::get, Json, Router};
+
+        let mut history: Vec<serde_json::Value> = retired
+            .iter()
+            .map(|(old, valid_until)| {
+                serde_json::json!({
+                    "public_key": public_key(old),
+                    "address": format!("{:?}", old.address()),
+                    "activated_at": 1_600_000_000,
+                    "retired_at": valid_until - 3600,
+                    "valid_until": valid_until,
+                })
+            })
+            .collect();
+        history.push(serde_json::json!({
+            "public_key": public_key(wallet),
+            "address": format!("{:?}", wallet.address()),
+            "activated_at": 1_700_000_000,
+        }));
+        let pubkey = serde_json::json!({
+            "public_key": public_key(wallet),
+            "address": format!("{:?}", wallet.address()),
+            "history": history,
+        });
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route(
+            "/coordinator/pubkey",
+            get(move || async move {
+                fetches.fetch_add(1, Ordering::SeqCst);
+                Json(pubkey)
+            }),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    fn signed_withdrawal(wallet: &LocalWallet) -> AuthorizedWithdrawal {
+        let mut withdrawal = AuthorizedWithdrawal {
+            withdrawal_id: "11".repeat(32),
+            target_chain_id: 8453,
+            recipient: "0x00000000000000000000000000000000000000aa".to_string(),
+            token: "0x833589fcd6edb6e08f4c7c32d4f71b54bda02913".to_string(),
+            amount: 1_000_000,
+            nullifier: vec![7u8; 32],
+            authorization_signature: Vec::new(),
+            timestamp: 1_700_000_000,
+            relayer_fee: 250,
+            deadline: chrono::Utc::now().timestamp() as u64 + 3600,
+        };
+        let message = withdrawal_authorization_hash(
+            BASE,
+            &withdrawal.withdrawal_id,
+            &withdrawal.recipient,
+            &withdrawal.token,
+            withdrawal.amount,
+            &withdrawal.nullifier,
+            withdrawal.deadline,
+        )
+        .unwrap();
+        withdrawal.authorization_signature = wallet.sign_hash(H256::from(message)).unwrap().to_vec();
+        withdrawal
+    }
+
+    #[tokio::test]
+    async fn test_valid_authorization_verified_with_cached_key() {
+        let wallet: LocalWallet = COORDINATOR_KEY.parse().unwrap();
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let url = mock_coordinator(&wallet, fetches.clone()).await;
+        let verifier = AuthorizationVerifier::new(Arc::new(CoordinatorClient::mock(&url)));
+
+        let withdrawal = signed_withdrawal(&wallet);
+        assert!(verifier.verify_withdrawal(&withdrawal, BASE).await.unwrap());
+        assert!(verifier.verify_withdrawal(&withdrawal, BASE).await.unwrap());
+        // Key fetched once, then served from the cache
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_tampered_authorization_rejected() {
+        let wallet: LocalWallet = COORDINATOR_KEY.parse().unwrap();
+        let url = mock_coordinator(&wallet, Arc::new(AtomicUsize::new(0))).await;
+        let verifier = AuthorizationVerifier::new(Arc::new(CoordinatorClient::mock(&url)));
+
+        // Inflated amount
+        let mut tampered = signed_withdrawal(&wallet);
+        tampered.amount += 1;
+        assert!(!verifier.verify_withdrawal(&tampered, BASE).await.unwrap());
+
+        // Redirected payout
+        let mut tampered = signed_withdrawal(&wallet);
+        tampered.recipient = "0x00000000000000000000000000000000000000bb".to_string();
+        assert!(!verifier.verify_withdrawal(&tampered, BASE).await.unwrap());
+
+        // Signed by someone else
+        let forger: LocalWallet = "0101010101010101010101010101010101010101010101010101010101010101".parse().unwrap();
+        assert!(!verifier.verify_withdrawal(&signed_withdrawal(&forger), BASE).await.unwrap());
+
+        // Malformed or placeholder signatures
+        let mut tampered = signed_withdrawal(&wallet);
+        tampered.authorization_signature = vec![0u8; 32];
+        assert!(!verifier.verify_withdrawal(&tampered, BASE).await.unwrap());
+        tampered.authorization_signature.clear();
+        assert!(!verifier.verify_withdrawal(&tampered, BASE).await.unwrap());
+
+        // Deadline extended after signing
+        let mut tampered = signed_withdrawal(&wallet);
+        tampered.deadline += 1;
+        assert!(!verifier.verify_withdrawal(&tampered, BASE).await.unwrap());
+
+        // Signed for another chain's gateway
+        let withdrawal = signed_withdrawal(&wallet);
+        let ethereum = AuthorizationScheme::Evm { chain_id: 1 };
+        assert!(!verifier.verify_withdrawal(&withdrawal, ethereum).await.unwrap());
+        assert!(!verifier.verify_withdrawal(&withdrawal, AuthorizationScheme::Solana).await.unwrap());
+
+        assert!(verifier.verify_withdrawal(&signed_withdrawal(&wallet), BASE).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_authorization_rejected_after_deadline() {
+        let wallet: LocalWallet = COORDINATOR_KEY.parse().unwrap();
+        let url = mock_coordinator(&wallet, Arc::new(AtomicUsize::new(0))).await;
+        let verifier = AuthorizationVerifier::new(Arc::new(CoordinatorClient::mock(&url)));
+
+        // Gateways accept through the deadline itself
+        let withdrawal = signed_withdrawal(&wallet);
+        let deadline = withdrawal.deadline as i64;
+        assert!(verifier.verify_withdrawal_at(&withdrawal, BASE, deadline).await.unwrap());
+        assert!(!verifier.verify_withdrawal_at(&withdrawal, BASE, deadline + 1).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_rotated_key_accepted_only_during_overlap() {
+        let old: LocalWallet = COORDINATOR_KEY.parse().unwrap();
+        let new: LocalWallet = "0101010101010101010101010101010101010101010101010101010101010101".parse().unwrap();
+        let valid_until = 1_700_003_600;
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let url = rotated_coordinator(&new, &[(&old, valid_until)], fetches.clone()).await;
+        let verifier = AuthorizationVerifier::new(Arc::new(CoordinatorClient::mock(&url)));
+
+        // During the overlap both keys verify, from one fetch
+        let during = valid_until - 1;
+        assert!(verifier.verify_withdrawal_at(&signed_withdrawal(&new), BASE, during).await.unwrap());
+        assert!(verifier.verify_withdrawal_at(&signed_withdrawal(&old), BASE, during).await.unwrap());
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        // Afterwards only the new key does, even after re-fetching
+        assert!(!verifier.verify_withdrawal_at(&signed_withdrawal(&old), BASE, valid_until).await.unwrap());
+        assert!(verifier.verify_withdrawal_at(&signed_withdrawal(&new), BASE, valid_until).await.unwrap());
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,80 @@
+// relayer/src/chain_encoding.rs
+//! Byte fields as each chain's gateway emits them
+//!
+//! Deposit events carry `recipient`/`zcash_address` as raw ABI words on EVM
+//! chains, hex strings in NEAR `EVENT_JSON` logs and Osmosis event
+//! attributes, and base58 on Solana. Every listener decodes through here so
+//! `DepositNotification` always holds the same bytes whichever chain the
+//! deposit came from.
+
+use anyhow::{Context, Result};
+
+/// Width of an EVM ABI word
+const EVM_WORD_SIZE: usize = 32;
+
+/// The `index`th 32-byte word of EVM log data, as is
+pub fn evm_word(data: &[u8], index: usize) -> Result<Vec<u8>> {
+    let start = index * EVM_WORD_SIZE;
+    data.get(start..start + EVM_WORD_SIZE)
+        .map(<[u8]>::to_vec)
+        .with_context(|| format!("Log data has no ABI word {}", index))
+}
+
+/// NEAR `EVENT_JSON` byte fields: hex, with or without `0x`
+pub fn near_bytes(value: &str) -> Result<Vec<u8>> {
+    decode_hex(value).context("Invalid hex in NEAR event")
+}
+
+/// Osmosis event attributes: hex, with or without `0x`
+pub fn osmosis_bytes(value: &str) -> Result<Vec<u8>> {
+    decode_hex(value).context("Invalid hex in Osmosis event")
+}
+
+/// Solana byte fields: base58, as for public keys
+pub fn solana_bytes(value: &str) -> Result<Vec<u8>> {
+    bs58::decode(value)
+        .into_vec()
+        .context("Invalid base58 in Solana event")
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>> {
+    let value = value.strip_prefix("0x").unwrap_or(value);
+    Ok(hex::decode(value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One recipient as each chain emits it
+    fn recipient() -> Vec<u8> {
+        (1..=32).collect()
+    }
+
+    #[test]
+    fn test_each_chain_decodes_to_the_same_bytes() {
+        let expected = recipient();
+
+        // ABI words: amount, targetChainId, recipient
+        let mut log_data = vec![0u8; 64];
+        log_data.extend_from_slice(&expected);
+        assert_eq!(evm_word(&log_data, 2).unwrap(), expected);
+
+        let hex = hex::encode(&expected);
+        assert_eq!(near_bytes(&hex).unwrap(), expected);
+        assert_eq!(near_bytes(&format!("0x{}", hex)).unwrap(), expected);
+        assert_eq!(osmosis_bytes(&hex.to_uppercase()).unwrap(), expected);
+
+        let base58 = bs58::encode(&expected).into_string();
+        assert_eq!(solana_bytes(&base58).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_malformed_fields_rejected() {
+        assert!(evm_word(&[0u8; 64], 2).is_err());
+        assert!(near_bytes("0xzz").is_err());
+        assert!(osmosis_bytes("abc").is_err());
+        // `0`, `O`, `I` and `l` aren't base58
+        assert!(solana_bytes("0OIl").is_err());
+    }
+}
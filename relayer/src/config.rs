@@ -3,13 +3,22 @@
 //! NO overlap with coordinator config
 
 use anyhow::{Context, Result};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::Address;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
+use std::str::FromStr;
+use zerobridge_types::AuthorizationScheme;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RelayerConfig {
     /// Coordinator RPC URL (read-only access)
+    #[serde(default)]
     pub coordinator_url: String,
+
+    /// Redundant coordinators tried after `coordinator_url`
+    #[serde(default)]
+    pub coordinator_urls: Vec<String>,
     
     /// Chains to relay for
     pub chains: Vec<ChainConfig>,
@@ -33,6 +42,11 @@ pub struct RelayerConfig {
     /// Maximum concurrent relay tasks
     #[serde(default = "default_max_concurrent")]
     pub max_concurrent_tasks: usize,
+    
+    /// Minimum fee (in fee-token base units) that must remain after the
+    /// estimated destination gas cost for a withdrawal to be claimed
+    #[serde(default)]
+    pub min_profit_margin: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,6 +61,11 @@ pub struct ChainConfig {
     /// WebSocket for event listening
     pub ws_url: Option<String>,
     
+    /// Seconds without a log or successful head poll before the event
+    /// subscription is considered stalled and reconnected
+    #[serde(default = "default_stall_timeout")]
+    pub stall_timeout_secs: u64,
+    
     /// Gateway contract address
     pub gateway_address: String,
     
@@ -58,6 +77,64 @@ pub struct ChainConfig {
     
     /// Transaction retry settings
     pub retry_config: RetryConfig,
+    
+    /// Max authorized withdrawals bundled into one `executeWithdrawalBatch`
+    /// transaction (EVM only; 1 disables batching)
+    #[serde(default = "default_withdrawal_batch_size")]
+    pub withdrawal_batch_size: usize,
+    
+    /// Commitment for Solana RPC reads and transaction confirmation.
+    /// Deposits are only reported once finalized, whatever this is set to.
+    #[serde(default)]
+    pub commitment: SolanaCommitment,
+}
+
+impl ChainConfig {
+    /// How this chain's gateway rebuilds the messages coordinator
+    /// authorizations sign
+    pub fn authorization_scheme(&self) -> AuthorizationScheme {
+        match self.chain_type {
+            ChainType::Ethereum | ChainType::Base | ChainType::Polygon => {
+                AuthorizationScheme::Evm { chain_id: self.chain_id }
+            }
+            ChainType::Solana => AuthorizationScheme::Solana,
+            ChainType::Near => AuthorizationScheme::Near,
+            ChainType::Mina => AuthorizationScheme::Unchecked,
+        }
+    }
+
+    /// `commitment` as passed to Solana `RpcClient`s
+    pub fn solana_commitment(&self) -> solana_sdk::commitment_config::CommitmentConfig {
+        use solana_sdk::commitment_config::CommitmentConfig;
+        
+        match self.commitment {
+            SolanaCommitment::Processed => CommitmentConfig::processed(),
+            SolanaCommitment::Confirmed => CommitmentConfig::confirmed(),
+            SolanaCommitment::Finalized => CommitmentConfig::finalized(),
+        }
+    }
+}
+
+/// Solana commitment level; only `finalized` is safe from fork switching
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SolanaCommitment {
+    Processed,
+    Confirmed,
+    #[default]
+    Finalized,
+}
+
+/// How relayers split authorized withdrawals between them
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskAssignment {
+    /// Every relayer races to claim every withdrawal
+    #[default]
+    FreeForAll,
+    /// One primary per withdrawal, picked from the withdrawal id; the others
+    /// step in only after the grace period
+    Deterministic,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -75,6 +152,84 @@ impl ChainType {
     pub fn is_evm(&self) -> bool {
         matches!(self, ChainType::Ethereum | ChainType::Base | ChainType::Polygon)
     }
+    
+    /// Check `address` is a well-formed account/contract address on this
+    /// chain
+    pub fn check_address(&self, address: &str) -> Result<()> {
+        let (valid, expected) = match self {
+            ChainType::Ethereum | ChainType::Base | ChainType::Polygon => (
+                address
+                    .strip_prefix("0x")
+                    .map(|hex| is_hex(hex, 40))
+                    .unwrap_or(false),
+                "a 0x-prefixed 20-byte hex address",
+            ),
+            ChainType::Solana => (
+                solana_sdk::pubkey::Pubkey::from_str(address).is_ok(),
+                "a base58-encoded 32-byte public key",
+            ),
+            ChainType::Near => (
+                is_near_account_id(address),
+                "a NEAR account id (2-64 lowercase alphanumerics separated by '.', '-' or '_')",
+            ),
+            ChainType::Mina => (
+                address.len() == 55 && address.starts_with("B62") && address.chars().all(is_base58),
+                "a 55-character base58 public key starting with B62",
+            ),
+        };
+        
+        if !valid {
+            anyhow::bail!("{:?} is not a valid {:?} address, expected {}", address, self, expected);
+        }
+        Ok(())
+    }
+    
+    /// Check `key` is in the format the executor loads for this chain,
+    /// without echoing it in the error
+    pub fn check_private_key(&self, key: &str) -> Result<()> {
+        let (valid, expected) = match self {
+            // Parsed as an ethers `LocalWallet`
+            ChainType::Ethereum | ChainType::Base | ChainType::Polygon => (
+                is_hex(key.strip_prefix("0x").unwrap_or(key), 64),
+                "32 bytes of hex",
+            ),
+            // Hex-encoded 64-byte keypair (secret followed by public key)
+            ChainType::Solana => (is_hex(key, 128), "a hex-encoded 64-byte keypair"),
+            ChainType::Near => (
+                key.strip_prefix("ed25519:")
+                    .or_else(|| key.strip_prefix("secp256k1:"))
+                    .map(|data| !data.is_empty() && data.chars().all(is_base58))
+                    .unwrap_or(false),
+                "an ed25519:/secp256k1: prefixed base58 key",
+            ),
+            ChainType::Mina => (
+                key.len() == 52 && key.starts_with("EK") && key.chars().all(is_base58),
+                "a 52-character base58 private key starting with EK",
+            ),
+        };
+        
+        if !valid {
+            anyhow::bail!("not a valid {:?} private key, expected {}", self, expected);
+        }
+        Ok(())
+    }
+}
+
+fn is_hex(s: &str, len: usize) -> bool {
+    s.len() == len && s.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn is_base58(c: char) -> bool {
+    c.is_ascii_alphanumeric() && !matches!(c, '0' | 'O' | 'I' | 'l')
+}
+
+/// NEAR account ID rules: 2-64 lowercase alphanumerics separated by single
+/// `.`, `-` or `_`
+fn is_near_account_id(id: &str) -> bool {
+    (2..=64).contains(&id.len())
+        && id
+            .split(['.', '-', '_'])
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit()))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -82,6 +237,11 @@ pub struct RelayerIdentity {
     /// Relayer public address/ID
     pub address: String,
     
+    /// Key of `address`, signing this relayer's gossip so peers can tell
+    /// who sent it. Like chain private keys, may be an `env:`/`file:`
+    /// reference.
+    pub signing_key: String,
+    
     /// Relayer name (for P2P identification)
     pub name: String,
     
@@ -126,6 +286,23 @@ pub struct P2PConfig {
     #[serde(default = "default_max_peers")]
     pub max_peers: usize,
     
+    /// Re-dial bootstrap peers while fewer than this many peers are connected
+    #[serde(default = "default_min_peers")]
+    pub min_peers: usize,
+    
+    /// How withdrawals are split between relayers
+    #[serde(default)]
+    pub task_assignment: TaskAssignment,
+    
+    /// With deterministic assignment, seconds before a backup relayer may
+    /// claim a withdrawal its primary hasn't
+    #[serde(default = "default_assignment_grace_period")]
+    pub assignment_grace_period: u64,
+    
+    /// Claims from peers whose reputation falls below this are ignored
+    #[serde(default = "default_min_peer_reputation")]
+    pub min_peer_reputation: u32,
+    
     /// Gossip protocol settings
     pub gossip: GossipConfig,
 }
@@ -146,12 +323,22 @@ pub struct GasStrategy {
     /// Strategy type
     pub strategy_type: GasStrategyType,
     
-    /// Max gas price (in gwei for EVM)
+    /// Max gas price (in gwei for EVM; max priority fee in micro-lamports
+    /// per compute unit for Solana)
     pub max_gas_price: u64,
     
     /// Gas price multiplier
     #[serde(default = "default_gas_multiplier")]
     pub multiplier: f64,
+    
+    /// Fee-token base units per wei of gas, used to price withdrawals.
+    /// Without it the profitability filter is skipped for this chain.
+    #[serde(default)]
+    pub fee_token_rate: Option<f64>,
+    
+    /// Compute unit limit requested for Solana withdrawals
+    #[serde(default = "default_compute_unit_limit")]
+    pub compute_unit_limit: u32,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -202,6 +389,18 @@ fn default_max_peers() -> usize {
     50
 }
 
+fn default_assignment_grace_period() -> u64 {
+    60
+}
+
+fn default_min_peers() -> usize {
+    1
+}
+
+fn default_min_peer_reputation() -> u32 {
+    50
+}
+
 fn default_heartbeat() -> u64 {
     30
 }
@@ -210,6 +409,18 @@ fn default_message_ttl() -> u64 {
     300
 }
 
+fn default_stall_timeout() -> u64 {
+    120
+}
+
+fn default_withdrawal_batch_size() -> usize {
+    1
+}
+
+fn default_compute_unit_limit() -> u32 {
+    400_000
+}
+
 fn default_gas_multiplier() -> f64 {
     1.2
 }
@@ -232,20 +443,137 @@ impl RelayerConfig {
         let content = std::fs::read_to_string(path)
             .context("Failed to read config file")?;
         
-        let config: RelayerConfig = toml::from_str(&content)
+        Self::from_toml(&content)
+    }
+    
+    /// Parse configuration, substituting `${VAR}` in string values and
+    /// resolving `env:`/`file:` references in private keys
+    pub fn from_toml(content: &str) -> Result<Self> {
+        if content.contains(TEMPLATE_PLACEHOLDER) {
+            anyhow::bail!(
+                "Config file still contains {} placeholders from --generate-config",
+                TEMPLATE_PLACEHOLDER
+            );
+        }
+        
+        let mut value: toml::Value = toml::from_str(content)
+            .context("Failed to parse config file")?;
+        interpolate_env(&mut value)?;
+        
+        let mut config: RelayerConfig = value
+            .try_into()
             .context("Failed to parse config file")?;
         
+        for chain in &mut config.chains {
+            chain.private_key = resolve_secret(&chain.private_key)
+                .with_context(|| format!("Private key for chain {}", chain.name))?;
+        }
+        config.relayer_identity.signing_key = resolve_secret(&config.relayer_identity.signing_key)
+            .context("Relayer identity signing key")?;
+        
         config.validate()?;
         
         Ok(config)
     }
     
+    /// Fully populated config with default settings and placeholder secrets
+    pub fn template() -> Self {
+        Self {
+            coordinator_url: "http://localhost:8080".to_string(),
+            coordinator_urls: Vec::new(),
+            chains: vec![ChainConfig {
+                chain_id: 11155111,
+                name: "Ethereum Sepolia".to_string(),
+                chain_type: ChainType::Ethereum,
+                rpc_url: format!("https://sepolia.infura.io/v3/{}", TEMPLATE_PLACEHOLDER),
+                ws_url: Some(format!("wss://sepolia.infura.io/ws/v3/{}", TEMPLATE_PLACEHOLDER)),
+                stall_timeout_secs: default_stall_timeout(),
+                gateway_address: TEMPLATE_PLACEHOLDER.to_string(),
+                private_key: TEMPLATE_PLACEHOLDER.to_string(),
+                gas_strategy: GasStrategy {
+                    strategy_type: GasStrategyType::Standard,
+                    max_gas_price: 100,
+                    multiplier: default_gas_multiplier(),
+                    fee_token_rate: None,
+                    compute_unit_limit: default_compute_unit_limit(),
+                },
+                retry_config: RetryConfig {
+                    max_retries: default_max_retries(),
+                    initial_backoff: default_initial_backoff(),
+                    max_backoff: default_max_backoff(),
+                },
+                withdrawal_batch_size: default_withdrawal_batch_size(),
+                commitment: SolanaCommitment::default(),
+            }],
+            relayer_identity: RelayerIdentity {
+                address: TEMPLATE_PLACEHOLDER.to_string(),
+                signing_key: TEMPLATE_PLACEHOLDER.to_string(),
+                name: "my-relayer-node-1".to_string(),
+                reputation: 0,
+            },
+            staking: StakingConfig {
+                minimum_stake: 1_000_000_000_000_000_000, // 1 ETH
+                current_stake: 0,
+                hub_contract: TEMPLATE_PLACEHOLDER.to_string(),
+                hub_chain_id: 11155111,
+                auto_restake: true,
+            },
+            p2p: P2PConfig {
+                listen_addr: default_p2p_listen(),
+                port: default_p2p_port(),
+                bootstrap_peers: vec![],
+                max_peers: default_max_peers(),
+                min_peers: default_min_peers(),
+                task_assignment: TaskAssignment::default(),
+                assignment_grace_period: default_assignment_grace_period(),
+                min_peer_reputation: default_min_peer_reputation(),
+                gossip: GossipConfig {
+                    heartbeat_interval: default_heartbeat(),
+                    message_ttl: default_message_ttl(),
+                },
+            },
+            database_path: "data/relayer.db".to_string(),
+            poll_interval: default_poll_interval(),
+            max_concurrent_tasks: default_max_concurrent(),
+            min_profit_margin: 0,
+        }
+    }
+    
+    /// `template()` as commented TOML, written by `--generate-config`
+    pub fn template_toml() -> Result<String> {
+        let toml = toml::to_string_pretty(&Self::template())
+            .context("Failed to serialize config template")?;
+        
+        Ok(format!(
+            "# ZeroBridge relayer configuration\n\
+             # Replace every {} before starting the relayer\n\n{}",
+            TEMPLATE_PLACEHOLDER,
+            annotate_toml(&toml, TEMPLATE_COMMENTS)
+        ))
+    }
+    
+    /// Every configured coordinator, `coordinator_url` first, without duplicates
+    pub fn coordinator_endpoints(&self) -> Vec<String> {
+        let mut endpoints: Vec<String> = Vec::new();
+        let primary = Some(&self.coordinator_url).filter(|url| !url.is_empty());
+        for url in primary.into_iter().chain(&self.coordinator_urls) {
+            if !endpoints.contains(url) {
+                endpoints.push(url.clone());
+            }
+        }
+        endpoints
+    }
+
     /// Validate configuration
     fn validate(&self) -> Result<()> {
-        // Validate coordinator URL
-        if self.coordinator_url.is_empty() {
+        // Validate coordinator URLs
+        let coordinators = self.coordinator_endpoints();
+        if coordinators.is_empty() {
             anyhow::bail!("Coordinator URL cannot be empty");
         }
+        if coordinators.iter().any(|url| url.is_empty()) {
+            anyhow::bail!("coordinator_urls cannot contain empty URLs");
+        }
         
         // Validate chains
         if self.chains.is_empty() {
@@ -260,6 +588,39 @@ impl RelayerConfig {
             if chain.private_key.is_empty() {
                 anyhow::bail!("Private key for chain {} cannot be empty", chain.name);
             }
+            
+            if let Err(e) = chain.chain_type.check_address(&chain.gateway_address) {
+                anyhow::bail!("Gateway address for chain {} is invalid: {}", chain.name, e);
+            }
+            
+            if let Err(e) = chain.chain_type.check_private_key(&chain.private_key) {
+                anyhow::bail!("Private key for chain {} is invalid: {}", chain.name, e);
+            }
+            
+            if chain.withdrawal_batch_size == 0 {
+                anyhow::bail!("Withdrawal batch size for chain {} must be greater than 0", chain.name);
+            }
+            
+            if chain.stall_timeout_secs == 0 {
+                anyhow::bail!("Stall timeout for chain {} must be greater than 0", chain.name);
+            }
+        }
+        
+        // A zero-permit semaphore would never let a task run
+        if self.max_concurrent_tasks == 0 {
+            anyhow::bail!("max_concurrent_tasks must be greater than 0");
+        }
+        
+        // Peers only accept gossip signed by the address it names
+        let identity_key: LocalWallet = self.relayer_identity.signing_key.parse()
+            .map_err(|_| anyhow::anyhow!("Relayer identity signing key is not a valid secp256k1 key"))?;
+        let identity_address: Address = self.relayer_identity.address.parse()
+            .with_context(|| format!("Relayer address {} is invalid", self.relayer_identity.address))?;
+        if identity_key.address() != identity_address {
+            anyhow::bail!(
+                "Relayer identity signing key is not the key of {}",
+                self.relayer_identity.address
+            );
         }
         
         // Validate staking
@@ -271,6 +632,9 @@ impl RelayerConfig {
         if self.p2p.port == 0 {
             anyhow::bail!("P2P port must be greater than 0");
         }
+        if self.p2p.min_peers > self.p2p.max_peers {
+            anyhow::bail!("p2p.min_peers must not exceed p2p.max_peers");
+        }
         
         Ok(())
     }
@@ -279,37 +643,42 @@ impl RelayerConfig {
     pub fn get_chain(&self, chain_id: u64) -> Option<&ChainConfig> {
         self.chains.iter().find(|c| c.chain_id == chain_id)
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_config_validation() {
-        let config = RelayerConfig {
+    
+    #[cfg(test)]
+    pub fn mock() -> Self {
+        let signing_key = format!("0x{}", "45".repeat(32));
+        let identity: LocalWallet = signing_key.parse().unwrap();
+        
+        Self {
             coordinator_url: "http://localhost:8080".to_string(),
+            coordinator_urls: Vec::new(),
             chains: vec![ChainConfig {
                 chain_id: 1,
                 name: "Ethereum".to_string(),
                 chain_type: ChainType::Ethereum,
                 rpc_url: "http://localhost:8545".to_string(),
                 ws_url: None,
-                gateway_address: "0x123".to_string(),
-                private_key: "0xabc".to_string(),
+                stall_timeout_secs: 120,
+                gateway_address: "0x0000000000000000000000000000000000000123".to_string(),
+                private_key: format!("0x{}", "ab".repeat(32)),
                 gas_strategy: GasStrategy {
                     strategy_type: GasStrategyType::Standard,
                     max_gas_price: 100,
                     multiplier: 1.2,
+                    fee_token_rate: None,
+                    compute_unit_limit: 400_000,
                 },
                 retry_config: RetryConfig {
                     max_retries: 3,
                     initial_backoff: 5,
                     max_backoff: 300,
                 },
+                withdrawal_batch_size: 1,
+                commitment: SolanaCommitment::Finalized,
             }],
             relayer_identity: RelayerIdentity {
-                address: "0x456".to_string(),
+                address: format!("{:?}", identity.address()),
+                signing_key,
                 name: "test-relayer".to_string(),
                 reputation: 100,
             },
@@ -325,6 +694,10 @@ mod tests {
                 port: 9000,
                 bootstrap_peers: vec![],
                 max_peers: 50,
+                min_peers: 1,
+                task_assignment: TaskAssignment::FreeForAll,
+                assignment_grace_period: 60,
+                min_peer_reputation: 50,
                 gossip: GossipConfig {
                     heartbeat_interval: 30,
                     message_ttl: 300,
@@ -333,8 +706,328 @@ mod tests {
             database_path: "relayer.db".to_string(),
             poll_interval: 5,
             max_concurrent_tasks: 10,
+            min_profit_margin: 0,
+        }
+    }
+}
+
+/// Replace `${VAR}` in every string value with that environment variable
+fn interpolate_env(value: &mut toml::Value) -> Result<()> {
+    match value {
+        toml::Value::String(s) => *s = interpolate_str(s)?,
+        toml::Value::Array(items) => {
+            for item in items {
+                interpolate_env(item)?;
+            }
+        }
+        toml::Value::Table(table) => {
+            for (_, item) in table.iter_mut() {
+                interpolate_env(item)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn interpolate_str(s: &str) -> Result<String> {
+    let mut out = String::new();
+    let mut rest = s;
+    
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let len = rest[start..]
+            .find('}')
+            .with_context(|| format!("Unterminated ${{ in config value {:?}", s))?;
+        out.push_str(&env_var(&rest[start + 2..start + len])?);
+        rest = &rest[start + len + 1..];
+    }
+    
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Resolve an `env:NAME` or `file:PATH` secret reference; anything else is
+/// taken literally
+fn resolve_secret(value: &str) -> Result<String> {
+    if let Some(name) = value.strip_prefix("env:") {
+        env_var(name)
+    } else if let Some(path) = value.strip_prefix("file:") {
+        let secret = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read secret file {}", path))?;
+        Ok(secret.trim_end_matches(['\r', '\n']).to_string())
+    } else {
+        Ok(value.to_string())
+    }
+}
+
+fn env_var(name: &str) -> Result<String> {
+    std::env::var(name)
+        .with_context(|| format!("Environment variable {} referenced by config is not set", name))
+}
+
+/// Marks values `--generate-config` can't fill in; `RelayerConfig::load`
+/// refuses a file that still contains it
+pub const TEMPLATE_PLACEHOLDER: &str = "CHANGE_ME";
+
+/// Comments written above keys and tables of the generated template
+const TEMPLATE_COMMENTS: &[(&str, &str)] = &[
+    ("coordinator_url", "Coordinator RPC URL (read-only access)"),
+    ("coordinator_urls", "Redundant coordinators; the freshest reachable one is used and the rest are failed over to"),
+    ("database_path", "Relayer database"),
+    ("poll_interval", "Polling interval in seconds"),
+    ("max_concurrent_tasks", "Maximum concurrent relay tasks"),
+    ("min_profit_margin", "Fee (fee-token base units) that must remain after estimated gas before a withdrawal is claimed"),
+    ("relayer_identity", "Identity announced to the P2P network"),
+    ("relayer_identity.address", "Relayer public address"),
+    ("relayer_identity.signing_key", "Key of `address`, signing P2P messages. KEEP SECRET!"),
+    ("staking", "Relayer stake on the hub contract"),
+    ("staking.minimum_stake", "Minimum stake (wei) to be an active relayer"),
+    ("staking.hub_contract", "ZeroBridgeHub address"),
+    ("staking.hub_chain_id", "Chain the hub is deployed on"),
+    ("p2p", "Relayer P2P network"),
+    ("p2p.bootstrap_peers", "Multiaddrs of peers to join through"),
+    ("p2p.min_peers", "Re-dial bootstrap peers while fewer peers than this are connected"),
+    ("p2p.task_assignment", "free_for_all, or deterministic: one primary relayer per withdrawal, backups after the grace period"),
+    ("p2p.assignment_grace_period", "Seconds before a backup relayer may claim a withdrawal under deterministic assignment"),
+    ("p2p.min_peer_reputation", "Ignore task claims from peers below this reputation (peers start at 100)"),
+    ("p2p.gossip.heartbeat_interval", "Seconds between heartbeats"),
+    ("p2p.gossip.message_ttl", "Seconds a gossip message is relayed for"),
+    ("chains", "One [[chains]] table per chain to relay for"),
+    ("chains.chain_type", "ethereum, base, polygon, solana, near or mina"),
+    ("chains.stall_timeout_secs", "Reconnect the log subscription after this long without progress"),
+    ("chains.gateway_address", "Gateway contract address (program ID on Solana)"),
+    ("chains.private_key", "Transaction signing key. KEEP SECRET!"),
+    ("chains.withdrawal_batch_size", "Authorized withdrawals per executeWithdrawalBatch tx (1 = no batching)"),
+    ("chains.commitment", "Solana only: processed, confirmed or finalized; deposits are reported once finalized regardless"),
+    ("chains.gas_strategy.strategy_type", "fast, standard or slow"),
+    ("chains.gas_strategy.max_gas_price", "Gwei for EVM; max priority fee in micro-lamports per CU for Solana"),
+    ("chains.gas_strategy.compute_unit_limit", "Solana only; add `fee_token_rate = <units per wei>` to enable the profitability filter"),
+    ("chains.retry_config.initial_backoff", "Seconds, doubling per retry up to max_backoff"),
+];
+
+/// Put `# comment` lines above the keys and `[tables]` named in `comments`
+/// (dotted paths, arrays of tables by their name)
+fn annotate_toml(toml: &str, comments: &[(&str, &str)]) -> String {
+    let mut out = String::new();
+    let mut table = String::new();
+    
+    for line in toml.lines() {
+        let trimmed = line.trim();
+        let path = if trimmed.starts_with('[') {
+            table = trimmed.trim_matches(|c| c == '[' || c == ']').to_string();
+            Some(table.clone())
+        } else if let Some((key, _)) = trimmed.split_once(" = ") {
+            Some(if table.is_empty() { key.to_string() } else { format!("{}.{}", table, key) })
+        } else {
+            None
         };
         
+        if let Some((_, comment)) = path.and_then(|p| comments.iter().find(|(key, _)| *key == p)) {
+            out.push_str("# ");
+            out.push_str(comment);
+            out.push('\n');
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_validation() {
+        let mut config = RelayerConfig::mock();
+        
+        assert!(config.validate().is_ok());
+        
+        config.max_concurrent_tasks = 0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_identity_key_must_match_address() {
+        let mut config = RelayerConfig::mock();
+        config.relayer_identity.signing_key = format!("0x{}", "46".repeat(32));
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("not the key of"), "{}", err);
+        
+        config.relayer_identity.signing_key = "0x1234".to_string();
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("signing key"), "{}", err);
+        assert!(!err.contains("0x1234"), "{}", err);
+    }
+
+    #[test]
+    fn test_coordinator_endpoints() {
+        let mut config = RelayerConfig::mock();
+        config.coordinator_urls = vec![
+            "http://backup-1:8080".to_string(),
+            "http://localhost:8080".to_string(),
+            "http://backup-2:8080".to_string(),
+        ];
+        assert_eq!(
+            config.coordinator_endpoints(),
+            vec!["http://localhost:8080", "http://backup-1:8080", "http://backup-2:8080"]
+        );
+
+        // Redundant coordinators alone are enough
+        config.coordinator_url.clear();
         assert!(config.validate().is_ok());
+        assert_eq!(config.coordinator_endpoints().len(), 2);
+
+        config.coordinator_urls.clear();
+        assert!(config.validate().is_err());
+    }
+    
+    fn config_with_chain(rpc_url: &str, private_key: &str) -> String {
+        let mut config = RelayerConfig::mock();
+        config.chains[0].rpc_url = rpc_url.to_string();
+        config.chains[0].private_key = private_key.to_string();
+        toml::to_string(&config).unwrap()
+    }
+    
+    #[test]
+    fn test_env_interpolation_and_secret_references() {
+        std::env::set_var("ZB_TEST_RELAYER_RPC_KEY", "abc123");
+        let env_key = format!("0x{}", "11".repeat(32));
+        let file_key = format!("0x{}", "22".repeat(32));
+        std::env::set_var("ZB_TEST_RELAYER_KEY", &env_key);
+        
+        let content = config_with_chain(
+            "https://sepolia.infura.io/v3/${ZB_TEST_RELAYER_RPC_KEY}",
+            "env:ZB_TEST_RELAYER_KEY",
+        );
+        let config = RelayerConfig::from_toml(&content).unwrap();
+        assert_eq!(config.chains[0].rpc_url, "https://sepolia.infura.io/v3/abc123");
+        assert_eq!(config.chains[0].private_key, env_key);
+        
+        // Mounted secret file; its trailing newline is dropped
+        let dir = tempfile::tempdir().unwrap();
+        let key_file = dir.path().join("private_key");
+        std::fs::write(&key_file, format!("{}\n", file_key)).unwrap();
+        let content = config_with_chain(
+            "http://localhost:8545",
+            &format!("file:{}", key_file.display()),
+        );
+        let config = RelayerConfig::from_toml(&content).unwrap();
+        assert_eq!(config.chains[0].private_key, file_key);
+    }
+    
+    #[test]
+    fn test_missing_env_reference_rejected() {
+        std::env::remove_var("ZB_TEST_RELAYER_UNSET");
+        
+        let content = config_with_chain("http://${ZB_TEST_RELAYER_UNSET}:8545", &"ab".repeat(32));
+        let err = format!("{:#}", RelayerConfig::from_toml(&content).unwrap_err());
+        assert!(err.contains("ZB_TEST_RELAYER_UNSET"), "{}", err);
+        
+        let content = config_with_chain("http://localhost:8545", "env:ZB_TEST_RELAYER_UNSET");
+        assert!(RelayerConfig::from_toml(&content).is_err());
+        
+        let content = config_with_chain("http://localhost:8545", "file:/nonexistent/zerobridge/key");
+        assert!(RelayerConfig::from_toml(&content).is_err());
+    }
+    
+    #[test]
+    fn test_malformed_addresses_and_keys_rejected() {
+        let cases = [
+            (ChainType::Ethereum, "0x1234", "0x00000000000000000000000000000000000000aa", "0xabc", "ab".repeat(32)),
+            (ChainType::Base, "00000000000000000000000000000000000000aa", "0xAbCdEf0000000000000000000000000000000001", "0x1234", format!("0x{}", "ab".repeat(32))),
+            (ChainType::Solana, "0x00000000000000000000000000000000000000aa", "8FGoQPMAt83sMLrxNb3yr8fQS8VBhQPEu31wCGg7b6Tc", "0x1234", "ab".repeat(64)),
+            (ChainType::Near, "-zerobridge", "zerobridge.testnet", "0xabc", "ed25519:3D4YudUahN1nawWogh8pAKSj92sUNMdbZGjn7kERKzYoTy8tnFQuwoGUC51DowKqorvkr2pytJSnwuSbsNVfqygr".to_string()),
+            (ChainType::Mina, "B62qk...", "B62qkYa1o6Mj6uTTjDQCob7FYZspuhkm4RRQhgJg9j4koEBWiSrTQrS", "EKsecret", "EKE5nJtRFYVWqrCfdpqJqKKdt2Sskf5Co2q8CWJKEGSg71ZXzES7".to_string()),
+        ];
+        
+        for (chain_type, bad_address, good_address, bad_key, good_key) in cases {
+            let mut config = RelayerConfig::mock();
+            config.chains[0].chain_type = chain_type;
+            config.chains[0].gateway_address = good_address.to_string();
+            config.chains[0].private_key = good_key;
+            assert!(config.validate().is_ok(), "{:?}: {}", chain_type, config.validate().unwrap_err());
+            
+            let mut bad = config.clone();
+            bad.chains[0].gateway_address = bad_address.to_string();
+            let err = bad.validate().unwrap_err().to_string();
+            assert!(err.contains("Gateway address for chain"), "{}", err);
+            assert!(err.contains(bad_address), "{}", err);
+            assert!(err.contains(&format!("{:?}", chain_type)), "{}", err);
+            
+            let mut bad = config.clone();
+            bad.chains[0].private_key = bad_key.to_string();
+            let err = bad.validate().unwrap_err().to_string();
+            assert!(err.contains("Private key for chain"), "{}", err);
+            assert!(err.contains(&format!("{:?}", chain_type)), "{}", err);
+            // The key itself is never echoed
+            assert!(!err.contains(bad_key), "{}", err);
+        }
+    }
+    
+    #[test]
+    fn test_generated_config_loads_once_filled_in() {
+        let template = RelayerConfig::template_toml().unwrap();
+        assert!(template.contains("# Relayer P2P network\n[p2p]"));
+        assert!(template.contains("# Polling interval in seconds\npoll_interval = 5"));
+        
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("relayer-config.toml");
+        
+        // Unedited templates are refused
+        std::fs::write(&path, &template).unwrap();
+        let err = RelayerConfig::load(&path).unwrap_err().to_string();
+        assert!(err.contains(TEMPLATE_PLACEHOLDER), "{}", err);
+        
+        let private_key = "cd".repeat(32);
+        let identity: LocalWallet = private_key.parse().unwrap();
+        let filled = template
+            .replace(
+                &format!("gateway_address = \"{}\"", TEMPLATE_PLACEHOLDER),
+                "gateway_address = \"0x00000000000000000000000000000000000000aa\"",
+            )
+            .replace(
+                &format!("private_key = \"{}\"", TEMPLATE_PLACEHOLDER),
+                &format!("private_key = \"{}\"", private_key),
+            )
+            .replace(
+                &format!("address = \"{}\"", TEMPLATE_PLACEHOLDER),
+                &format!("address = \"{:?}\"", identity.address()),
+            )
+            .replace(
+                &format!("signing_key = \"{}\"", TEMPLATE_PLACEHOLDER),
+                &format!("signing_key = \"{}\"", private_key),
+            )
+            .replace(TEMPLATE_PLACEHOLDER, "0xfilledin");
+        std::fs::write(&path, filled).unwrap();
+        let config = RelayerConfig::load(&path).unwrap();
+        assert_eq!(config.chains[0].private_key, private_key);
+        assert_eq!(config.p2p.port, default_p2p_port());
+        assert_eq!(config.chains[0].gas_strategy.compute_unit_limit, default_compute_unit_limit());
+    }
+    
+    #[test]
+    fn test_solana_commitment_defaults_to_finalized() {
+        use solana_sdk::commitment_config::CommitmentConfig;
+        
+        let mut config = RelayerConfig::mock();
+        config.chains[0].chain_type = ChainType::Solana;
+        config.chains[0].gateway_address = "8FGoQPMAt83sMLrxNb3yr8fQS8VBhQPEu31wCGg7b6Tc".to_string();
+        config.chains[0].private_key = "ab".repeat(64);
+        
+        let content = toml::to_string(&config).unwrap().replace("commitment = \"finalized\"\n", "");
+        assert!(!content.contains("commitment"));
+        let loaded = RelayerConfig::from_toml(&content).unwrap();
+        assert_eq!(loaded.chains[0].commitment, SolanaCommitment::Finalized);
+        assert_eq!(loaded.chains[0].solana_commitment(), CommitmentConfig::finalized());
+        
+        let content = toml::to_string(&config).unwrap().replace("\"finalized\"", "\"confirmed\"");
+        let loaded = RelayerConfig::from_toml(&content).unwrap();
+        assert_eq!(loaded.chains[0].solana_commitment(), CommitmentConfig::confirmed());
+        
+        let content = toml::to_string(&config).unwrap().replace("\"finalized\"", "\"recent\"");
+        assert!(RelayerConfig::from_toml(&content).is_err());
     }
 }
\ No newline at end of file
@@ -0,0 +1,196 @@
+// relayer/src/config_check.rs
+//! `--check`: verify a loaded config against the outside world before
+//! relaying on it. `RelayerConfig::validate` only sees the file; this
+//! connects to the coordinator and every chain node and checks gateway
+//! address formats per chain.
+
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use crate::config::{ChainType, RelayerConfig};
+
+/// Per-request timeout for connectivity probes
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Outcome of a single check
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub target: String,
+    pub check: &'static str,
+    pub error: Option<String>,
+}
+
+impl CheckResult {
+    fn new(target: &str, check: &'static str, result: Result<()>) -> Self {
+        Self {
+            target: target.to_string(),
+            check,
+            error: result.err().map(|e| format!("{:#}", e)),
+        }
+    }
+
+    pub fn passed(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Probe the coordinator and every configured chain
+pub async fn run_checks(config: &RelayerConfig) -> Vec<CheckResult> {
+    let client = Client::builder()
+        .timeout(CHECK_TIMEOUT)
+        .build()
+        .unwrap_or_default();
+    let mut results = Vec::new();
+
+    let coordinators = config.coordinator_endpoints();
+    for url in &coordinators {
+        let target = if coordinators.len() == 1 {
+            "coordinator".to_string()
+        } else {
+            format!("coordinator ({})", url)
+        };
+        let coordinator = coordinator_health(&client, url).await;
+        results.push(CheckResult::new(&target, "health", coordinator));
+    }
+
+    for chain in &config.chains {
+        let target = format!("{} ({})", chain.name, chain.chain_id);
+        let rpc = match rpc_probe(chain.chain_type) {
+            Some(method) => json_rpc(&client, &chain.rpc_url, method).await,
+            None => reachable(&client, &chain.rpc_url).await,
+        };
+        results.push(CheckResult::new(&target, "rpc", rpc));
+        results.push(CheckResult::new(
+            &target,
+            "gateway_address",
+            chain.chain_type.check_address(&chain.gateway_address),
+        ));
+    }
+
+    results
+}
+
+async fn coordinator_health(client: &Client, base_url: &str) -> Result<()> {
+    let url = format!("{}/health", base_url);
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .with_context(|| format!("{} unreachable", base_url))?;
+    if !response.status().is_success() {
+        anyhow::bail!("/health returned {}", response.status());
+    }
+    Ok(())
+}
+
+/// Cheap read-only JSON-RPC method each chain's node answers; `None` for
+/// chains without a JSON-RPC API (Mina serves GraphQL)
+fn rpc_probe(chain_type: ChainType) -> Option<&'static str> {
+    match chain_type {
+        ChainType::Ethereum | ChainType::Base | ChainType::Polygon => Some("eth_chainId"),
+        ChainType::Solana => Some("getHealth"),
+        ChainType::Near => Some("status"),
+        ChainType::Mina => None,
+    }
+}
+
+async fn json_rpc(client: &Client, url: &str, method: &str) -> Result<()> {
+    let response = client
+        .post(url)
+        .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": [] }))
+        .send()
+        .await
+        .with_context(|| format!("{} unreachable", url))?;
+    if !response.status().is_success() {
+        anyhow::bail!("{} returned {}", method, response.status());
+    }
+
+    let body: Value = response.json().await.context("Response is not JSON")?;
+    match body.get("error") {
+        Some(error) if !error.is_null() => anyhow::bail!("{} failed: {}", method, error),
+        _ => Ok(()),
+    }
+}
+
+async fn reachable(client: &Client, url: &str) -> Result<()> {
+    client
+        .get(url)
+        .send()
+        .await
+        .with_context(|| format!("{} unreachable", url))?;
+    Ok(())
+}
+
+/// Pass/fail table for `--check`
+pub fn format_report(results: &[CheckResult]) -> String {
+    let target_width = results.iter().map(|r| r.target.len()).max().unwrap_or(0).max("TARGET".len());
+    let check_width = results.iter().map(|r| r.check.len()).max().unwrap_or(0).max("CHECK".len());
+
+    let mut out = format!(
+        "{:<6} {:<target_width$} {:<check_width$} DETAIL\n",
+        "RESULT", "TARGET", "CHECK"
+    );
+    for result in results {
+        out.push_str(&format!(
+            "{:<6} {:<target_width$} {:<check_width$} {}\n",
+            if result.passed() { "pass" } else { "FAIL" },
+            result.target,
+            result.check,
+            result.error.as_deref().unwrap_or(""),
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::{get, post}, Json, Router};
+
+    /// Local server with a coordinator `/health` and a JSON-RPC root
+    async fn mock_server() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new()
+            .route("/health", get(|| async { Json(json!({ "status": "ok" })) }))
+            .route(
+                "/",
+                post(|| async { Json(json!({ "jsonrpc": "2.0", "id": 1, "result": "0xaa36a7" })) }),
+            );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    /// A local URL nothing listens on
+    async fn dead_url() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_checks_report_reachable_and_unreachable_endpoints() {
+        let url = mock_server().await;
+        let mut config = RelayerConfig::mock();
+        config.coordinator_url = url.clone();
+        config.chains[0].rpc_url = url;
+        config.chains[0].gateway_address = "0x00000000000000000000000000000000000000aa".to_string();
+
+        let results = run_checks(&config).await;
+        assert!(results.iter().all(|r| r.passed()), "{}", format_report(&results));
+
+        config.coordinator_url = dead_url().await;
+        config.chains[0].rpc_url = dead_url().await;
+        config.chains[0].gateway_address = "0x123".to_string();
+
+        let results = run_checks(&config).await;
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|r| !r.passed()));
+        let report = format_report(&results);
+        assert_eq!(report.lines().filter(|l| l.starts_with("FAIL")).count(), 3);
+        assert!(report.contains("coordinator"));
+    }
+}
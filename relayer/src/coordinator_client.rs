@@ -2,92 +2,295 @@
 //! Client to query coordinator (read-only)
 //! Relayer queries coordinator for authorization, doesn't duplicate coordinator logic
 
-use anyhow::Result;
-use serde::{Deserialize, Serialize};
+use anyhow::{Context, Result};
+use futures::future::{join_all, BoxFuture, FutureExt};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+use tracing::{debug, error, info, warn};
 
+use crate::telemetry;
+
+pub use zerobridge_types::{
+    AuthorizedWithdrawal, CanonicalTokenId, CoordinatorPubkey, DepositNotification, RefundAuthorization,
+    WithdrawalNotification,
+};
+
+/// Coordinator RPC major version this relayer speaks
+const COORDINATOR_MAJOR_VERSION: u64 = 1;
+
+/// Coordinator minor version this relayer was built against
+const COORDINATOR_MINOR_VERSION: u64 = 0;
+
+/// Coordinator capabilities the relayer cannot run without
+const REQUIRED_CAPABILITIES: &[&str] = &[
+    "deposits.notify",
+    "deposits.status",
+    "withdrawals.notify",
+    "withdrawals.authorized",
+    "liquidity.check",
+    "coordinator.pubkey",
+];
+
+/// Capability of coordinators that take and return bincode bodies
+const BINCODE_CAPABILITY: &str = "encoding.bincode";
+
+const BINCODE_CONTENT_TYPE: &str = "application/x-bincode";
+
+/// Client for one or more redundant coordinators. Calls go to the freshest
+/// one first and fail over to the others in turn.
 pub struct CoordinatorClient {
+    endpoints: Vec<CoordinatorEndpoint>,
+    /// Endpoint indices, freshest first
+    order: RwLock<Vec<usize>>,
+}
+
+/// One coordinator's RPC
+struct CoordinatorEndpoint {
     base_url: String,
     client: reqwest::Client,
+    /// Send notifications as bincode rather than JSON. Bincode isn't
+    /// self-describing; the coordinator decodes the same `zerobridge-types`
+    /// structs.
+    binary: AtomicBool,
+    /// Version handshake done; calls skip the endpoint until then
+    connected: AtomicBool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct DepositNotification {
-    pub deposit_id: String,
-    pub source_chain_id: u64,
-    pub target_chain_id: u64,
-    pub sender: String,
-    pub token: String,
-    pub amount: u64,
-    pub recipient: Vec<u8>,
-    pub zcash_address: Vec<u8>,
-    pub timestamp: u64,
+/// Coordinator's answer to a deposit notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepositNotifyOutcome {
+    /// First notification; deposit queued for processing
+    Queued,
+    /// Another relayer already notified this deposit
+    Duplicate,
+}
+
+#[derive(Debug, Deserialize)]
+struct StatusResponse {
+    status: String,
+}
+
+/// Coordinator totals (`GET /stats`); a coordinator that has seen more has
+/// the fresher state
+#[derive(Debug, Deserialize)]
+struct StatsResponse {
+    total_deposits: u64,
+    total_withdrawals: u64,
 }
 
+/// Coordinator version and capabilities (`GET /version`)
 #[derive(Debug, Serialize, Deserialize)]
-pub struct AuthorizedWithdrawal {
-    pub withdrawal_id: String,
-    pub target_chain_id: u64,
-    pub recipient: String,
-    pub token: String,
-    pub amount: u64,
-    pub nullifier: Vec<u8>,
-    pub authorization_signature: Vec<u8>,
-    pub timestamp: u64,
+pub struct CoordinatorVersion {
+    pub version: String,
+    pub capabilities: Vec<String>,
 }
 
-impl CoordinatorClient {
-    pub fn new(base_url: &str) -> Result<Self> {
-        Ok(Self {
+impl CoordinatorVersion {
+    /// Refuse coordinators on a different major version or missing a
+    /// required capability; warn when minor versions differ
+    pub fn check_compatible(&self) -> Result<()> {
+        let mut parts = self.version.split('.').map(|p| p.parse::<u64>());
+        let major = parts
+            .next()
+            .and_then(|p| p.ok())
+            .with_context(|| format!("Invalid coordinator version: {}", self.version))?;
+        let minor = parts.next().and_then(|p| p.ok()).unwrap_or(0);
+
+        if major != COORDINATOR_MAJOR_VERSION {
+            anyhow::bail!(
+                "Incompatible coordinator version {} (relayer requires {}.x)",
+                self.version,
+                COORDINATOR_MAJOR_VERSION
+            );
+        }
+
+        let missing: Vec<&str> = REQUIRED_CAPABILITIES
+            .iter()
+            .copied()
+            .filter(|c| !self.capabilities.iter().any(|have| have == c))
+            .collect();
+        if !missing.is_empty() {
+            anyhow::bail!(
+                "Coordinator {} is missing required capabilities: {}",
+                self.version,
+                missing.join(", ")
+            );
+        }
+
+        if minor != COORDINATOR_MINOR_VERSION {
+            warn!(
+                "Coordinator version {} differs from expected {}.{}; some features may be unavailable",
+                self.version, COORDINATOR_MAJOR_VERSION, COORDINATOR_MINOR_VERSION
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl CoordinatorEndpoint {
+    fn new(base_url: &str) -> Self {
+        Self {
             base_url: base_url.to_string(),
             client: reqwest::Client::new(),
-        })
+            binary: AtomicBool::new(false),
+            connected: AtomicBool::new(false),
+        }
+    }
+
+    /// Verify the coordinator speaks a compatible RPC version and pick the
+    /// notification encoding
+    fn negotiate(&self, version: CoordinatorVersion) -> Result<()> {
+        version.check_compatible()?;
+        let binary = version.capabilities.iter().any(|c| c == BINCODE_CAPABILITY);
+        self.binary.store(binary, Ordering::Relaxed);
+        self.connected.store(true, Ordering::Release);
+
+        info!(
+            "Coordinator {} version {} ({} capabilities, {} notifications)",
+            self.base_url,
+            version.version,
+            version.capabilities.len(),
+            if binary { "bincode" } else { "JSON" }
+        );
+
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Acquire)
+    }
+
+    /// How much the coordinator has seen, from `/stats`
+    async fn freshness(&self) -> Result<u64> {
+        let url = format!("{}/stats", self.base_url);
+        let response = self
+            .client
+            .get(&url)
+            .timeout(std::time::Duration::from_secs(5))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("Failed to query stats: {}", response.status());
+        }
+
+        let stats: StatsResponse = response.json().await?;
+        Ok(stats.total_deposits + stats.total_withdrawals)
+    }
+
+    /// Query coordinator version and supported capabilities
+    pub async fn get_version(&self) -> Result<CoordinatorVersion> {
+        let url = format!("{}/version", self.base_url);
+        let response = self.client.get(&url).send().await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("Failed to query version: {}", response.status());
+        }
+
+        let version: CoordinatorVersion = response.json().await?;
+        Ok(version)
+    }
+
+    /// Key the coordinator signs authorizations with
+    pub async fn coordinator_pubkey(&self) -> Result<CoordinatorPubkey> {
+        let url = format!("{}/coordinator/pubkey", self.base_url);
+        let response = self.client.get(&url).send().await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("Failed to query coordinator public key: {}", response.status());
+        }
+
+        Ok(response.json().await?)
+    }
+
+    /// Whether the coordinator currently answers its health endpoint
+    pub async fn is_reachable(&self) -> bool {
+        let url = format!("{}/health", self.base_url);
+        self.client
+            .get(&url)
+            .timeout(std::time::Duration::from_secs(5))
+            .send()
+            .await
+            .map(|response| response.status().is_success())
+            .unwrap_or(false)
+    }
+
+    /// POST `body` in the negotiated encoding
+    async fn post_encoded<T: Serialize>(&self, url: &str, body: &T) -> Result<reqwest::Response> {
+        let request = telemetry::propagate(self.client.post(url));
+        let request = if self.binary.load(Ordering::Relaxed) {
+            request
+                .header(reqwest::header::CONTENT_TYPE, BINCODE_CONTENT_TYPE)
+                .header(reqwest::header::ACCEPT, BINCODE_CONTENT_TYPE)
+                .body(bincode::serialize(body)?)
+        } else {
+            request.json(body)
+        };
+        Ok(request.send().await?)
+    }
+
+    /// Decode a response body by its `Content-Type`
+    async fn read_encoded<T: DeserializeOwned>(response: reqwest::Response) -> Result<T> {
+        let binary = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.starts_with(BINCODE_CONTENT_TYPE))
+            .unwrap_or(false);
+        if binary {
+            Ok(bincode::deserialize(&response.bytes().await?)?)
+        } else {
+            Ok(response.json().await?)
+        }
     }
 
     /// Notify coordinator about a deposit event
     /// Coordinator will create the Zcash note
-    pub async fn notify_deposit(&self, deposit: DepositNotification) -> Result<()> {
+    pub async fn notify_deposit(&self, deposit: DepositNotification) -> Result<DepositNotifyOutcome> {
         let url = format!("{}/deposits/notify", self.base_url);
-        let response = self.client
-            .post(&url)
-            .json(&deposit)
-            .send()
-            .await?;
+        let response = self.post_encoded(&url, &deposit).await?;
         
         if !response.status().is_success() {
             anyhow::bail!("Failed to notify deposit: {}", response.status());
         }
         
-        Ok(())
+        let status: StatusResponse = Self::read_encoded(response).await?;
+        Ok(if status.status == "duplicate" {
+            DepositNotifyOutcome::Duplicate
+        } else {
+            DepositNotifyOutcome::Queued
+        })
     }
 
     /// Notify coordinator about a withdrawal request
     /// Coordinator will verify the proof and authorize if valid
+    #[allow(clippy::too_many_arguments)]
     pub async fn notify_withdrawal(
         &self,
         withdrawal_id: &str,
         target_chain_id: u64,
         recipient: &str,
-        token: &str,
+        canonical_token_id: &str,
         amount: u64,
         nullifier: Vec<u8>,
         zcash_proof: Vec<u8>,
         merkle_root: Vec<u8>,
     ) -> Result<()> {
         let url = format!("{}/withdrawals/notify", self.base_url);
-        let response = self.client
-            .post(&url)
-            .json(&serde_json::json!({
-                "withdrawal_id": withdrawal_id,
-                "target_chain_id": target_chain_id,
-                "recipient": recipient,
-                "token": token,
-                "amount": amount,
-                "nullifier": nullifier,
-                "zcash_proof": zcash_proof,
-                "merkle_root": merkle_root,
-            }))
-            .send()
-            .await?;
+        let notification = WithdrawalNotification {
+            withdrawal_id: withdrawal_id.to_string(),
+            target_chain_id,
+            recipient: recipient.to_string(),
+            canonical_token_id: CanonicalTokenId(canonical_token_id.to_string()),
+            amount,
+            nullifier,
+            zcash_proof,
+            merkle_root,
+        };
+        let response = self.post_encoded(&url, &notification).await?;
         
         if !response.status().is_success() {
             anyhow::bail!("Failed to notify withdrawal: {}", response.status());
@@ -110,6 +313,20 @@ impl CoordinatorClient {
         Ok(withdrawals)
     }
 
+    /// Query for deposits the coordinator gave up on, to be refunded on
+    /// their source chains
+    pub async fn query_refundable_deposits(&self) -> Result<Vec<RefundAuthorization>> {
+        let url = format!("{}/deposits/refundable", self.base_url);
+        let response = self.client.get(&url).send().await?;
+        
+        if !response.status().is_success() {
+            anyhow::bail!("Failed to query refundable deposits: {}", response.status());
+        }
+        
+        let refunds: Vec<RefundAuthorization> = response.json().await?;
+        Ok(refunds)
+    }
+
     /// Check if a specific deposit has been processed by coordinator
     pub async fn check_deposit_status(&self, deposit_id: &str) -> Result<bool> {
         let url = format!("{}/deposits/{}/status", self.base_url, deposit_id);
@@ -149,4 +366,535 @@ impl CoordinatorClient {
         let result: serde_json::Value = response.json().await?;
         Ok(result["available"].as_bool().unwrap_or(false))
     }
-}
\ No newline at end of file
+
+}
+
+impl CoordinatorClient {
+    /// Connect to the coordinator and verify it speaks a compatible RPC version
+    pub async fn new(base_url: &str) -> Result<Self> {
+        Self::connect(&[base_url.to_string()]).await
+    }
+
+    /// Connect to redundant coordinators. Every reachable one must speak a
+    /// compatible RPC version. Unreachable ones are kept, skipped by calls
+    /// until `connect_pending` completes their handshake, so the relayer can
+    /// start while every coordinator is down.
+    pub async fn connect(base_urls: &[String]) -> Result<Self> {
+        if base_urls.is_empty() {
+            anyhow::bail!("No coordinator URLs configured");
+        }
+
+        let endpoints: Vec<CoordinatorEndpoint> = base_urls.iter().map(|url| CoordinatorEndpoint::new(url)).collect();
+        for endpoint in &endpoints {
+            match endpoint.get_version().await {
+                Ok(version) => endpoint.negotiate(version)?,
+                Err(e) => warn!("Coordinator {} unreachable: {}", endpoint.base_url, e),
+            }
+        }
+
+        let client = Self {
+            order: RwLock::new((0..endpoints.len()).collect()),
+            endpoints,
+        };
+        if !client.is_connected() {
+            warn!("No coordinator reachable yet; not ready until one answers");
+        }
+        client.refresh_preference().await;
+        Ok(client)
+    }
+
+    /// Whether any coordinator has completed its version handshake
+    pub fn is_connected(&self) -> bool {
+        self.endpoints.iter().any(|endpoint| endpoint.is_connected())
+    }
+
+    /// Retry the version handshake with every coordinator that hasn't
+    /// completed it. Returns whether all of them now have.
+    pub async fn connect_pending(&self) -> bool {
+        let pending: Vec<_> = self.endpoints.iter().filter(|endpoint| !endpoint.is_connected()).collect();
+        if pending.is_empty() {
+            return true;
+        }
+
+        let mut all_connected = true;
+        for endpoint in pending {
+            let negotiated = match endpoint.get_version().await {
+                Ok(version) => endpoint.negotiate(version),
+                Err(e) => {
+                    debug!("Coordinator {} still unreachable: {}", endpoint.base_url, e);
+                    all_connected = false;
+                    continue;
+                }
+            };
+            if let Err(e) = negotiated {
+                // Stays skipped; an operator has to fix the deployment
+                error!("Coordinator {} is incompatible: {:#}", endpoint.base_url, e);
+                all_connected = false;
+            }
+        }
+        if all_connected {
+            self.refresh_preference().await;
+        }
+        all_connected
+    }
+
+    /// Re-rank coordinators by their `/stats`, freshest first. Unreachable
+    /// ones go last; ties keep the configured order.
+    pub async fn refresh_preference(&self) {
+        let freshness = join_all(self.endpoints.iter().map(|endpoint| endpoint.freshness())).await;
+
+        let mut order: Vec<usize> = (0..self.endpoints.len()).collect();
+        order.sort_by_key(|&index| std::cmp::Reverse(freshness[index].as_ref().ok().copied()));
+
+        if self.endpoints.len() > 1 {
+            debug!(
+                "Coordinator preference: {}",
+                order.iter().map(|&index| self.endpoints[index].base_url.as_str()).collect::<Vec<_>>().join(", ")
+            );
+        }
+        *self.order.write().unwrap() = order;
+    }
+
+    /// Coordinators that completed the version handshake, freshest first
+    fn connected_order(&self) -> Result<Vec<usize>> {
+        let order: Vec<usize> = self
+            .order
+            .read()
+            .unwrap()
+            .iter()
+            .copied()
+            .filter(|&index| self.endpoints[index].is_connected())
+            .collect();
+        if order.is_empty() {
+            anyhow::bail!("No coordinator connected yet");
+        }
+        Ok(order)
+    }
+
+    /// Try `call` on each coordinator, freshest first, until one succeeds
+    async fn failover<'a, T>(
+        &'a self,
+        call: impl Fn(&'a CoordinatorEndpoint) -> BoxFuture<'a, Result<T>>,
+    ) -> Result<T> {
+        let order = self.connected_order()?;
+        let mut last_error = None;
+        for index in order {
+            let endpoint = &self.endpoints[index];
+            match call(endpoint).await {
+                Ok(value) => return Ok(value),
+                Err(e) => {
+                    if self.endpoints.len() > 1 {
+                        warn!("Coordinator {} failed, failing over: {:#}", endpoint.base_url, e);
+                    }
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.expect("at least one coordinator endpoint"))
+    }
+
+    /// Run `query` against every coordinator and merge the results, keeping
+    /// the first copy of each item by `key` (freshest coordinator first).
+    /// Fails only if no coordinator answered.
+    async fn query_all<'a, T, K>(
+        &'a self,
+        query: impl Fn(&'a CoordinatorEndpoint) -> BoxFuture<'a, Result<Vec<T>>>,
+        key: impl Fn(&T) -> K,
+    ) -> Result<Vec<T>>
+    where
+        K: std::hash::Hash + Eq,
+    {
+        let order = self.connected_order()?;
+        let results = join_all(order.iter().map(|&index| query(&self.endpoints[index]))).await;
+
+        let mut seen = HashSet::new();
+        let mut merged = Vec::new();
+        let mut last_error = None;
+        let mut answered = false;
+        for (index, result) in order.into_iter().zip(results) {
+            match result {
+                Ok(items) => {
+                    answered = true;
+                    merged.extend(items.into_iter().filter(|item| seen.insert(key(item))));
+                }
+                Err(e) => {
+                    if self.endpoints.len() > 1 {
+                        warn!("Coordinator {} failed: {:#}", self.endpoints[index].base_url, e);
+                    }
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        match last_error {
+            Some(e) if !answered => Err(e),
+            _ => Ok(merged),
+        }
+    }
+
+    /// Query coordinator version and supported capabilities
+    pub async fn get_version(&self) -> Result<CoordinatorVersion> {
+        self.failover(|endpoint| endpoint.get_version().boxed()).await
+    }
+
+    /// Key the coordinator signs authorizations with
+    pub async fn coordinator_pubkey(&self) -> Result<CoordinatorPubkey> {
+        self.failover(|endpoint| endpoint.coordinator_pubkey().boxed()).await
+    }
+
+    /// Whether any connected coordinator currently answers its health
+    /// endpoint
+    pub async fn is_reachable(&self) -> bool {
+        let connected = self.endpoints.iter().filter(|endpoint| endpoint.is_connected());
+        join_all(connected.map(|endpoint| endpoint.is_reachable()))
+            .await
+            .into_iter()
+            .any(|reachable| reachable)
+    }
+
+    /// Notify coordinator about a deposit event
+    /// Coordinator will create the Zcash note
+    #[tracing::instrument(name = "notify_deposit", skip_all, fields(deposit_id = %deposit.deposit_id))]
+    pub async fn notify_deposit(&self, deposit: DepositNotification) -> Result<DepositNotifyOutcome> {
+        self.failover(|endpoint| endpoint.notify_deposit(deposit.clone()).boxed()).await
+    }
+
+    /// Notify coordinator about a withdrawal request
+    /// Coordinator will verify the proof and authorize if valid
+    #[tracing::instrument(name = "notify_withdrawal", skip_all, fields(withdrawal_id = %withdrawal_id))]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn notify_withdrawal(
+        &self,
+        withdrawal_id: &str,
+        target_chain_id: u64,
+        recipient: &str,
+        canonical_token_id: &str,
+        amount: u64,
+        nullifier: Vec<u8>,
+        zcash_proof: Vec<u8>,
+        merkle_root: Vec<u8>,
+    ) -> Result<()> {
+        self.failover(|endpoint| {
+            endpoint
+                .notify_withdrawal(
+                    withdrawal_id,
+                    target_chain_id,
+                    recipient,
+                    canonical_token_id,
+                    amount,
+                    nullifier.clone(),
+                    zcash_proof.clone(),
+                    merkle_root.clone(),
+                )
+                .boxed()
+        })
+        .await
+    }
+
+    /// Authorized withdrawals from every coordinator, each withdrawal once
+    pub async fn query_authorized_withdrawals(&self) -> Result<Vec<AuthorizedWithdrawal>> {
+        self.query_all(
+            |endpoint| endpoint.query_authorized_withdrawals().boxed(),
+            |withdrawal| withdrawal.withdrawal_id.clone(),
+        )
+        .await
+    }
+
+    /// Refundable deposits from every coordinator, each deposit once
+    pub async fn query_refundable_deposits(&self) -> Result<Vec<RefundAuthorization>> {
+        self.query_all(
+            |endpoint| endpoint.query_refundable_deposits().boxed(),
+            |refund| refund.deposit_id.clone(),
+        )
+        .await
+    }
+
+    /// Check if a specific deposit has been processed by coordinator
+    pub async fn check_deposit_status(&self, deposit_id: &str) -> Result<bool> {
+        self.failover(|endpoint| endpoint.check_deposit_status(deposit_id).boxed()).await
+    }
+
+    /// Get liquidity status for a chain/token
+    /// Coordinator manages liquidity, relayer just queries
+    pub async fn check_liquidity(&self, chain_id: u64, token: &str, amount: u64) -> Result<bool> {
+        self.failover(|endpoint| endpoint.check_liquidity(chain_id, token, amount).boxed()).await
+    }
+
+    #[cfg(test)]
+    pub fn mock(base_url: &str) -> Self {
+        let endpoint = CoordinatorEndpoint::new(base_url);
+        endpoint.connected.store(true, Ordering::Release);
+        Self {
+            endpoints: vec![endpoint],
+            order: RwLock::new(vec![0]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(version: &str, capabilities: &[&str]) -> CoordinatorVersion {
+        CoordinatorVersion {
+            version: version.to_string(),
+            capabilities: capabilities.iter().map(|c| c.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_compatible_coordinator_version() {
+        assert!(version("1.0.0", REQUIRED_CAPABILITIES).check_compatible().is_ok());
+
+        // Newer minor with extra capabilities is still accepted
+        let mut capabilities = REQUIRED_CAPABILITIES.to_vec();
+        capabilities.push("withdrawals.reauthorize");
+        assert!(version("1.3.2", &capabilities).check_compatible().is_ok());
+    }
+
+    /// Coordinator stand-in that decodes withdrawal notifications in either
+    /// encoding and answers in the one asked for
+    async fn mock_coordinator() -> String {
+        use axum::{http::HeaderMap, routing::post, Router};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route(
+            "/withdrawals/notify",
+            post(|headers: HeaderMap, body: axum::body::Bytes| async move {
+                let binary = headers
+                    .get("content-type")
+                    .map(|v| v == BINCODE_CONTENT_TYPE)
+                    .unwrap_or(false);
+                let notification: WithdrawalNotification = if binary {
+                    bincode::deserialize(&body).unwrap()
+                } else {
+                    serde_json::from_slice(&body).unwrap()
+                };
+                assert_eq!(notification.nullifier, vec![7u8; 32]);
+                assert_eq!(notification.zcash_proof.len(), 192);
+
+                #[derive(Serialize)]
+                struct Status {
+                    status: String,
+                }
+                let status = Status { status: format!("queued:{}", notification.withdrawal_id) };
+                if binary {
+                    ([("content-type", BINCODE_CONTENT_TYPE)], bincode::serialize(&status).unwrap())
+                } else {
+                    ([("content-type", "application/json")], serde_json::to_vec(&status).unwrap())
+                }
+            }),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_notifications_sent_in_negotiated_encoding() {
+        let url = mock_coordinator().await;
+
+        for binary in [false, true] {
+            let client = CoordinatorClient::mock(&url);
+            client.endpoints[0].binary.store(binary, Ordering::Relaxed);
+            client
+                .notify_withdrawal("w1", 8453, "0xrecipient", "canonical", 1_000, vec![7u8; 32], vec![1u8; 192], vec![0u8; 32])
+                .await
+                .unwrap();
+
+            let notification = WithdrawalNotification {
+                withdrawal_id: "w1".to_string(),
+                target_chain_id: 8453,
+                recipient: "0xrecipient".to_string(),
+                canonical_token_id: CanonicalTokenId("canonical".to_string()),
+                amount: 1_000,
+                nullifier: vec![7u8; 32],
+                zcash_proof: vec![1u8; 192],
+                merkle_root: vec![0u8; 32],
+            };
+            let response = client.endpoints[0]
+                .post_encoded(&format!("{}/withdrawals/notify", url), &notification)
+                .await
+                .unwrap();
+            let status: StatusResponse = CoordinatorEndpoint::read_encoded(response).await.unwrap();
+            assert_eq!(status.status, "queued:w1");
+        }
+    }
+
+    #[test]
+    fn test_authorized_withdrawals_from_coordinator_json() {
+        // `GET /withdrawals/authorized` body as the coordinator serves it
+        let json = r#"[{
+            "withdrawal_id": "w1",
+            "target_chain_id": 8453,
+            "recipient": "0x00000000000000000000000000000000000000aa",
+            "token": "0x833589fcd6edb6e08f4c7c32d4f71b54bda02913",
+            "amount": 1000000,
+            "nullifier": "/v7+/g==",
+            "authorization_signature": "qqqqqg==",
+            "timestamp": 1700000000,
+            "relayer_fee": 250,
+            "deadline": 1700003600
+        }]"#;
+        let withdrawals: Vec<AuthorizedWithdrawal> = serde_json::from_str(json).unwrap();
+        assert_eq!(withdrawals.len(), 1);
+        assert_eq!(withdrawals[0].withdrawal_id, "w1");
+        assert_eq!(withdrawals[0].nullifier, vec![0xfe; 4]);
+        assert_eq!(withdrawals[0].authorization_signature, vec![0xaa; 4]);
+        assert_eq!(withdrawals[0].timestamp, 1_700_000_000);
+        assert_eq!(withdrawals[0].relayer_fee, 250);
+        assert_eq!(withdrawals[0].deadline, 1_700_003_600);
+
+        // Coordinators that predate the field still parse
+        let legacy = json.replace(r#""timestamp": 1700000000,"#, "");
+        let withdrawals: Vec<AuthorizedWithdrawal> = serde_json::from_str(&legacy).unwrap();
+        assert_eq!(withdrawals[0].timestamp, 0);
+    }
+
+    #[test]
+    fn test_byte_fields_are_base64_in_json() {
+        let json = r#"{
+            "deposit_id": "d1",
+            "source_chain_id": 1,
+            "sender": "0xsender",
+            "token": "0xtoken",
+            "amount": 5,
+            "authorization_signature": "3q2+7w=="
+        }"#;
+        let refund: RefundAuthorization = serde_json::from_str(json).unwrap();
+        assert_eq!(refund.authorization_signature, vec![0xde, 0xad, 0xbe, 0xef]);
+
+        let value = serde_json::to_value(&refund).unwrap();
+        assert_eq!(value["authorization_signature"], "3q2+7w==");
+
+        // Integer arrays are no longer accepted
+        let legacy = json.replace(r#""3q2+7w==""#, "[222, 173, 190, 239]");
+        assert!(serde_json::from_str::<RefundAuthorization>(&legacy).is_err());
+    }
+
+    /// Coordinator stand-in for failover tests. A broken one still answers
+    /// `/version` but fails every other call.
+    async fn redundant_coordinator(broken: bool, seen: u64, withdrawal_ids: &[&str]) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        serve_coordinator(listener, broken, seen, withdrawal_ids)
+    }
+
+    fn serve_coordinator(listener: tokio::net::TcpListener, broken: bool, seen: u64, withdrawal_ids: &[&str]) -> String {
+        use axum::{http::StatusCode, routing::{get, post}, Json, Router};
+
+        let withdrawals: Vec<serde_json::Value> = withdrawal_ids
+            .iter()
+            .map(|id| {
+                serde_json::json!({
+                    "withdrawal_id": id,
+                    "target_chain_id": 8453,
+                    "recipient": "0x00000000000000000000000000000000000000aa",
+                    "token": "0x833589fcd6edb6e08f4c7c32d4f71b54bda02913",
+                    "amount": 1000,
+                    "nullifier": "/v7+/g==",
+                    "authorization_signature": "qqqqqg==",
+                })
+            })
+            .collect();
+        let status = if broken { StatusCode::INTERNAL_SERVER_ERROR } else { StatusCode::OK };
+
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new()
+            .route("/health", get(move || async move { status }))
+            .route(
+                "/version",
+                get(|| async {
+                    Json(serde_json::json!({ "version": "1.0.0", "capabilities": REQUIRED_CAPABILITIES }))
+                }),
+            )
+            .route(
+                "/stats",
+                get(move || async move {
+                    (status, Json(serde_json::json!({ "total_deposits": seen, "total_withdrawals": 0 })))
+                }),
+            )
+            .route(
+                "/withdrawals/authorized",
+                get(move || async move { (status, Json(serde_json::Value::Array(withdrawals))) }),
+            )
+            .route(
+                "/liquidity/check",
+                post(move || async move { (status, Json(serde_json::json!({ "available": !broken }))) }),
+            );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    fn withdrawal_ids(withdrawals: &[AuthorizedWithdrawal]) -> Vec<&str> {
+        withdrawals.iter().map(|w| w.withdrawal_id.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_fails_over_when_primary_errors() {
+        let primary = redundant_coordinator(true, 0, &[]).await;
+        let secondary = redundant_coordinator(false, 0, &["w1"]).await;
+
+        let client = CoordinatorClient::connect(&[primary.clone(), secondary.clone()]).await.unwrap();
+        assert!(client.check_liquidity(8453, "0xtoken", 1_000).await.unwrap());
+        let withdrawals = client.query_authorized_withdrawals().await.unwrap();
+        assert_eq!(withdrawal_ids(&withdrawals), vec!["w1"]);
+
+        // A primary that is down at startup doesn't stop the relayer either
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let dead = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+        let client = CoordinatorClient::connect(&[dead.clone(), secondary]).await.unwrap();
+        assert!(client.is_reachable().await);
+        assert_eq!(withdrawal_ids(&client.query_authorized_withdrawals().await.unwrap()), vec!["w1"]);
+
+        // Calls fail while every coordinator is failing
+        let client = CoordinatorClient::connect(&[primary]).await.unwrap();
+        assert!(client.query_authorized_withdrawals().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_starts_without_coordinator_and_connects_later() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        // Down at boot: the client still comes up, but isn't ready
+        let client = CoordinatorClient::connect(&[format!("http://{}", addr)]).await.unwrap();
+        assert!(!client.is_connected());
+        assert!(!client.is_reachable().await);
+        assert!(client.query_authorized_withdrawals().await.is_err());
+        assert!(!client.connect_pending().await);
+
+        // Once the coordinator answers, the retry completes the handshake
+        let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+        serve_coordinator(listener, false, 0, &["w1"]);
+        assert!(client.connect_pending().await);
+        assert!(client.is_connected());
+        assert!(client.is_reachable().await);
+        assert_eq!(withdrawal_ids(&client.query_authorized_withdrawals().await.unwrap()), vec!["w1"]);
+    }
+
+    #[tokio::test]
+    async fn test_prefers_freshest_coordinator_and_dedupes_withdrawals() {
+        let stale = redundant_coordinator(false, 5, &["w1", "w2"]).await;
+        let fresh = redundant_coordinator(false, 10, &["w2", "w3"]).await;
+
+        let client = CoordinatorClient::connect(&[stale, fresh.clone()]).await.unwrap();
+        let preferred = client.order.read().unwrap()[0];
+        assert_eq!(client.endpoints[preferred].base_url, fresh);
+
+        // Each withdrawal once, freshest coordinator's first
+        let withdrawals = client.query_authorized_withdrawals().await.unwrap();
+        assert_eq!(withdrawal_ids(&withdrawals), vec!["w2", "w3", "w1"]);
+    }
+
+    #[test]
+    fn test_incompatible_coordinator_version() {
+        // Major version mismatch
+        assert!(version("2.0.0", REQUIRED_CAPABILITIES).check_compatible().is_err());
+        assert!(version("garbage", REQUIRED_CAPABILITIES).check_compatible().is_err());
+
+        // Missing a required capability
+        assert!(version("1.0.0", &["deposits.notify"]).check_compatible().is_err());
+    }
+}
@@ -4,7 +4,8 @@
 //! Does NOT duplicate coordinator's deposit/withdrawal tracking
 
 use anyhow::Result;
-use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+use sqlx::{SqlitePool, sqlite::{SqliteConnectOptions, SqlitePoolOptions}};
+use std::str::FromStr;
 use tracing::info;
 
 #[derive(Clone)]
@@ -23,6 +24,14 @@ pub struct WithdrawalExecution {
     pub fee_earned: u64,
 }
 
+/// One withdrawal's share of a batch execution
+#[derive(Debug, Clone)]
+pub struct BatchItem {
+    pub withdrawal_id: String,
+    pub fee_earned: u64,
+    pub gas_used: u64,
+}
+
 /// Relayer performance statistics
 #[derive(Debug, Default)]
 pub struct RelayerStats {
@@ -36,10 +45,11 @@ pub struct RelayerStats {
 impl RelayerDatabase {
     pub async fn new(path: &str) -> Result<Self> {
         let url = format!("sqlite:{}", path);
+        let options = SqliteConnectOptions::from_str(&url)?.create_if_missing(true);
         
         let pool = SqlitePoolOptions::new()
             .max_connections(5)
-            .connect(&url)
+            .connect_with(options)
             .await?;
         
         Self::create_tables(&pool).await?;
@@ -89,6 +99,17 @@ impl RelayerDatabase {
         .execute(pool)
         .await?;
 
+        // Last gateway block each chain listener has fully processed
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS listener_checkpoints (
+                chain_id INTEGER PRIMARY KEY,
+                last_block INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+        )
+        .execute(pool)
+        .await?;
+
         // Create indexes
         sqlx::query(
             "CREATE INDEX IF NOT EXISTS idx_executions_chain 
@@ -112,7 +133,9 @@ impl RelayerDatabase {
         &self,
         withdrawal_id: &str,
         tx_hash: &str,
+        chain_id: u64,
         executed_at: i64,
+        fee_earned: u64,
     ) -> Result<()> {
         sqlx::query(
             "INSERT INTO withdrawal_executions 
@@ -121,10 +144,10 @@ impl RelayerDatabase {
         )
         .bind(withdrawal_id)
         .bind(tx_hash)
-        .bind(0i64) // Chain ID to be filled
+        .bind(chain_id as i64)
         .bind(executed_at)
         .bind(0i64) // Gas used to be filled
-        .bind(0i64) // Fee earned to be filled
+        .bind(fee_earned as i64)
         .execute(&self.pool)
         .await?;
 
@@ -134,6 +157,47 @@ impl RelayerDatabase {
         Ok(())
     }
 
+    /// Record every withdrawal settled by one batch transaction, each with
+    /// its own fee and share of the batch's gas
+    pub async fn record_batch_execution(
+        &self,
+        tx_hash: &str,
+        chain_id: u64,
+        executed_at: i64,
+        items: &[BatchItem],
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        for item in items {
+            sqlx::query(
+                "INSERT INTO withdrawal_executions 
+                 (withdrawal_id, tx_hash, chain_id, executed_at, gas_used, fee_earned) 
+                 VALUES (?, ?, ?, ?, ?, ?)"
+            )
+            .bind(&item.withdrawal_id)
+            .bind(tx_hash)
+            .bind(chain_id as i64)
+            .bind(executed_at)
+            .bind(item.gas_used as i64)
+            .bind(item.fee_earned as i64)
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query(
+                "INSERT INTO relay_performance 
+                 (withdrawal_id, success, error_message, timestamp) 
+                 VALUES (?, 1, NULL, ?)"
+            )
+            .bind(&item.withdrawal_id)
+            .bind(executed_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
     /// Record relay performance
     pub async fn record_relay_performance(
         &self,
@@ -263,6 +327,38 @@ impl RelayerDatabase {
         }).collect())
     }
 
+    /// Advance the listener checkpoint for a chain (never moves backwards)
+    pub async fn save_listener_checkpoint(&self, chain_id: u64, block: u64) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+
+        sqlx::query(
+            "INSERT INTO listener_checkpoints (chain_id, last_block, updated_at)
+             VALUES (?, ?, ?)
+             ON CONFLICT(chain_id) DO UPDATE SET
+                last_block = MAX(last_block, excluded.last_block),
+                updated_at = excluded.updated_at"
+        )
+        .bind(chain_id as i64)
+        .bind(block as i64)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Last block the listener for a chain has processed, if any
+    pub async fn get_listener_checkpoint(&self, chain_id: u64) -> Result<Option<u64>> {
+        let result: Option<(i64,)> = sqlx::query_as(
+            "SELECT last_block FROM listener_checkpoints WHERE chain_id = ?"
+        )
+        .bind(chain_id as i64)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(result.map(|r| r.0 as u64))
+    }
+
     /// Clean up expired task claims
     pub async fn cleanup_expired_claims(&self) -> Result<()> {
         let now = chrono::Utc::now().timestamp();
@@ -276,4 +372,63 @@ impl RelayerDatabase {
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fee_earned_flows_into_stats() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("relayer.db");
+        let db = RelayerDatabase::new(path.to_str().unwrap()).await.unwrap();
+
+        db.record_withdrawal_execution("w1", "0xaa", 1, 100, 2_000).await.unwrap();
+        db.record_withdrawal_execution("w2", "0xbb", 8453, 200, 500).await.unwrap();
+
+        let stats = db.get_stats().await.unwrap();
+        assert_eq!(stats.withdrawals_executed, 2);
+        assert_eq!(stats.total_rewards, 2_500);
+        assert_eq!(stats.successful_relays, 2);
+
+        let base = db.get_executions_for_chain(8453).await.unwrap();
+        assert_eq!(base.len(), 1);
+        assert_eq!(base[0].fee_earned, 500);
+    }
+
+    #[tokio::test]
+    async fn test_batch_execution_recorded_per_item() {
+        let db = RelayerDatabase::new(":memory:").await.unwrap();
+
+        let items = vec![
+            BatchItem { withdrawal_id: "w1".into(), fee_earned: 10, gas_used: 100_000 },
+            BatchItem { withdrawal_id: "w2".into(), fee_earned: 30, gas_used: 300_000 },
+        ];
+        db.record_batch_execution("0xbatch", 1, 100, &items).await.unwrap();
+
+        let executions = db.get_executions_for_chain(1).await.unwrap();
+        assert_eq!(executions.len(), 2);
+        assert!(executions.iter().all(|e| e.tx_hash == "0xbatch"));
+        let w2 = executions.iter().find(|e| e.withdrawal_id == "w2").unwrap();
+        assert_eq!((w2.fee_earned, w2.gas_used), (30, 300_000));
+
+        let stats = db.get_stats().await.unwrap();
+        assert_eq!(stats.total_rewards, 40);
+        assert_eq!(stats.total_gas_spent, 400_000);
+        assert_eq!(stats.successful_relays, 2);
+    }
+
+    #[tokio::test]
+    async fn test_listener_checkpoint_only_advances() {
+        let db = RelayerDatabase::new(":memory:").await.unwrap();
+        assert_eq!(db.get_listener_checkpoint(1).await.unwrap(), None);
+
+        db.save_listener_checkpoint(1, 100).await.unwrap();
+        db.save_listener_checkpoint(1, 90).await.unwrap();
+        db.save_listener_checkpoint(8453, 5).await.unwrap();
+
+        assert_eq!(db.get_listener_checkpoint(1).await.unwrap(), Some(100));
+        assert_eq!(db.get_listener_checkpoint(8453).await.unwrap(), Some(5));
+    }
+}
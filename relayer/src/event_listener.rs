@@ -3,18 +3,26 @@
 //! FOCUSED: Monitor events and notify coordinator
 //! Does NOT verify proofs or manage liquidity (coordinator's job)
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use futures::{Stream, StreamExt};
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
 use tracing::{debug, info, warn};
 
+use crate::chain_encoding;
 use crate::config::{ChainType, RelayerConfig};
-use crate::coordinator_client::{CoordinatorClient, DepositNotification};
+use crate::coordinator_client::{CoordinatorClient, DepositNotification, DepositNotifyOutcome};
 use crate::transaction_executor::TransactionExecutor;
 use crate::p2p_network::P2PNetwork;
 use crate::database::RelayerDatabase;
+use crate::health::ListenerTracker;
 
 pub struct EventListenerManager {
-    listeners: Vec<Box<dyn EventListener>>,
+    /// Listeners keyed by the chain they watch
+    listeners: Vec<(u64, Box<dyn EventListener>)>,
+    tracker: ListenerTracker,
 }
 
 impl EventListenerManager {
@@ -24,10 +32,14 @@ impl EventListenerManager {
         tx_executor: Arc<TransactionExecutor>,
         p2p_network: Arc<P2PNetwork>,
         db: RelayerDatabase,
+        tracker: ListenerTracker,
     ) -> Result<Self> {
-        let mut listeners: Vec<Box<dyn EventListener>> = Vec::new();
+        let mut listeners: Vec<(u64, Box<dyn EventListener>)> = Vec::new();
 
         for chain_config in config.chains {
+            let chain_id = chain_config.chain_id;
+            tracker.register(chain_id);
+
             let listener: Box<dyn EventListener> = match chain_config.chain_type {
                 ChainType::Ethereum | ChainType::Base | ChainType::Polygon => {
                     Box::new(
@@ -37,6 +49,7 @@ impl EventListenerManager {
                             tx_executor.clone(),
                             p2p_network.clone(),
                             db.clone(),
+                            tracker.clone(),
                         )
                         .await?,
                     )
@@ -79,15 +92,16 @@ impl EventListenerManager {
                 }
             };
 
-            listeners.push(listener);
+            listeners.push((chain_id, listener));
         }
 
-        Ok(Self { listeners })
+        Ok(Self { listeners, tracker })
     }
 
     pub async fn start_all(&mut self) -> Result<()> {
-        for listener in &mut self.listeners {
+        for (chain_id, listener) in &mut self.listeners {
             listener.start().await?;
+            self.tracker.set_running(*chain_id, true);
         }
         Ok(())
     }
@@ -100,12 +114,27 @@ pub trait EventListener: Send + Sync {
 
 // ============ EVM Event Listener ============
 
+/// Gateway deposit event
+const TOKENS_LOCKED_EVENT: &str =
+    "TokensLocked(bytes32,address,address,uint256,uint64,bytes32,bytes32,uint256)";
+
+/// Block span per `eth_getLogs` request when backfilling
+const BACKFILL_CHUNK_SIZE: u64 = 2_000;
+
+/// Interval between `eth_blockNumber` heartbeats on the listener's connection
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// First and largest delay between WebSocket reconnect attempts
+const RECONNECT_BASE_DELAY: Duration = Duration::from_secs(1);
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(60);
+
 struct EvmEventListener {
     chain_config: crate::config::ChainConfig,
     coordinator_client: Arc<CoordinatorClient>,
     tx_executor: Arc<TransactionExecutor>,
     p2p_network: Arc<P2PNetwork>,
     db: RelayerDatabase,
+    tracker: ListenerTracker,
 }
 
 impl EvmEventListener {
@@ -115,6 +144,7 @@ impl EvmEventListener {
         tx_executor: Arc<TransactionExecutor>,
         p2p_network: Arc<P2PNetwork>,
         db: RelayerDatabase,
+        tracker: ListenerTracker,
     ) -> Result<Self> {
         Ok(Self {
             chain_config,
@@ -122,6 +152,7 @@ impl EvmEventListener {
             tx_executor,
             p2p_network,
             db,
+            tracker,
         })
     }
 }
@@ -141,22 +172,29 @@ impl EventListener for EvmEventListener {
             .clone()
             .unwrap_or_else(|| self.chain_config.rpc_url.clone());
         let gateway_address = self.chain_config.gateway_address.clone();
+        let stall_timeout = Duration::from_secs(self.chain_config.stall_timeout_secs);
 
         let coordinator = self.coordinator_client.clone();
         let p2p = self.p2p_network.clone();
+        let db = self.db.clone();
+        let tracker = self.tracker.clone();
 
         tokio::spawn(async move {
-            if let Err(e) = Self::listen_loop(
-                chain_id,
-                &ws_url,
-                &gateway_address,
-                coordinator,
-                p2p,
-            )
-            .await
-            {
-                warn!("EVM listener error for chain {}: {}", chain_id, e);
-            }
+            let subscribe = || {
+                Self::subscribe_once(
+                    chain_id,
+                    &ws_url,
+                    &gateway_address,
+                    stall_timeout,
+                    &coordinator,
+                    &p2p,
+                    &db,
+                    &tracker,
+                )
+            };
+
+            let backoff = ReconnectBackoff::new(RECONNECT_BASE_DELAY, RECONNECT_MAX_DELAY);
+            reconnect_loop(chain_id, subscribe, backoff, &tracker).await;
         });
 
         Ok(())
@@ -164,13 +202,20 @@ impl EventListener for EvmEventListener {
 }
 
 impl EvmEventListener {
-    async fn listen_loop(
+    /// Connect, subscribe to gateway logs, replay anything missed since the
+    /// last checkpoint, then process live logs until the subscription closes
+    /// or stalls
+    #[allow(clippy::too_many_arguments)]
+    async fn subscribe_once(
         chain_id: u64,
         ws_url: &str,
         gateway_address: &str,
-        coordinator: Arc<CoordinatorClient>,
-        p2p: Arc<P2PNetwork>,
-    ) -> Result<()> {
+        stall_timeout: Duration,
+        coordinator: &CoordinatorClient,
+        p2p: &P2PNetwork,
+        db: &RelayerDatabase,
+        tracker: &ListenerTracker,
+    ) -> Result<SubscriptionEnd> {
         use ethers::prelude::*;
 
         let provider = Provider::<Ws>::connect(ws_url).await?;
@@ -179,28 +224,65 @@ impl EvmEventListener {
         // Subscribe to TokensLocked events
         let filter = Filter::new()
             .address(gateway_address)
-            .event("TokensLocked(bytes32,address,address,uint256,uint64,bytes32,bytes32,uint256)");
+            .event(TOKENS_LOCKED_EVENT);
 
-        let mut stream = provider.subscribe_logs(&filter).await?;
+        // Subscribe before catching up so nothing falls between the two;
+        // overlap is deduplicated by the coordinator
+        let stream = provider.subscribe_logs(&filter).await?;
 
         info!("Subscribed to gateway events on chain {}", chain_id);
+        tracker.set_running(chain_id, true);
 
-        while let Some(log) = stream.next().await {
+        let head = provider.get_block_number().await?.as_u64();
+        if let Some(checkpoint) = db.get_listener_checkpoint(chain_id).await? {
+            if checkpoint < head {
+                let missed =
+                    fetch_tokens_locked(&provider, chain_id, gateway_address, checkpoint + 1, head)
+                        .await?;
+                info!(
+                    "Replaying {} deposits on chain {} from blocks {}..={}",
+                    missed.len(), chain_id, checkpoint + 1, head
+                );
+                for notification in missed {
+                    Self::notify_deposit(notification, coordinator, p2p).await?;
+                }
+            }
+        }
+        db.save_listener_checkpoint(chain_id, head).await?;
+
+        let poll_head = || async {
+            Ok::<_, anyhow::Error>(provider.get_block_number().await?.as_u64())
+        };
+
+        let live_checkpoint = &std::sync::Mutex::new(LiveCheckpoint::default());
+        let on_log = |log: Log| async move {
             debug!("Received TokensLocked event on chain {}: {:?}", chain_id, log);
+            let block = log.block_number.map(|n| n.as_u64());
 
-            if let Err(e) = Self::handle_tokens_locked(
-                chain_id,
-                log,
-                &coordinator,
-                &p2p,
-            )
-            .await
-            {
+            let result = Self::handle_tokens_locked(chain_id, log, coordinator, p2p).await;
+            if let Err(e) = &result {
                 warn!("Failed to handle TokensLocked event: {}", e);
             }
-        }
+            let Some(block) = block else { return };
 
-        Ok(())
+            let checkpoint = live_checkpoint.lock().unwrap().record(block, result.is_ok());
+            if let Some(checkpoint) = checkpoint {
+                if let Err(e) = db.save_listener_checkpoint(chain_id, checkpoint).await {
+                    warn!("Failed to checkpoint chain {}: {}", chain_id, e);
+                }
+            }
+        };
+
+        Ok(watch_subscription(
+            chain_id,
+            Box::pin(stream),
+            poll_head,
+            on_log,
+            tracker,
+            HEARTBEAT_INTERVAL,
+            stall_timeout,
+        )
+        .await)
     }
 
     async fn handle_tokens_locked(
@@ -209,50 +291,336 @@ impl EvmEventListener {
         coordinator: &CoordinatorClient,
         p2p: &P2PNetwork,
     ) -> Result<()> {
-        // Parse event data
-        let deposit_id = hex::encode(log.topics[1].as_bytes());
-        let sender = format!("0x{}", hex::encode(&log.topics[2].as_bytes()[12..]));
-        let token = format!("0x{}", hex::encode(&log.topics[3].as_bytes()[12..]));
-        
-        // Parse amount, target_chain_id, recipient, zcash_address from log.data
-        // Simplified parsing for example
-        let amount = u64::from_be_bytes(log.data[0..8].try_into().unwrap());
-        let target_chain_id = u64::from_be_bytes(log.data[8..16].try_into().unwrap());
-        let recipient = log.data[16..48].to_vec();
-        let zcash_address = log.data[48..80].to_vec();
+        let notification = parse_tokens_locked(source_chain_id, &log)?;
 
         info!(
             "TokensLocked event: deposit_id={}, source={}, target={}",
-            deposit_id, source_chain_id, target_chain_id
+            notification.deposit_id, source_chain_id, notification.target_chain_id
         );
 
-        // Notify coordinator (coordinator will create Zcash note)
-        let notification = DepositNotification {
-            deposit_id: deposit_id.clone(),
-            source_chain_id,
-            target_chain_id,
-            sender,
-            token,
-            amount,
-            recipient,
-            zcash_address,
-            timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
-        };
+        Self::notify_deposit(notification, coordinator, p2p).await
+    }
 
-        coordinator.notify_deposit(notification).await?;
+    async fn notify_deposit(
+        notification: DepositNotification,
+        coordinator: &CoordinatorClient,
+        p2p: &P2PNetwork,
+    ) -> Result<()> {
+        let deposit_id = notification.deposit_id.clone();
+
+        // A peer relayer already reported it
+        if p2p.is_deposit_notified(&deposit_id).await {
+            debug!("Skipping deposit {} already notified by peer", deposit_id);
+            return Ok(());
+        }
+
+        // Notify coordinator (coordinator will create Zcash note)
+        let outcome = coordinator.notify_deposit(notification).await?;
 
-        // Broadcast to P2P that we've notified coordinator
+        // Broadcast to P2P that the coordinator knows about it
         p2p.broadcast_deposit_notification(&deposit_id).await?;
 
-        info!("Notified coordinator about deposit: {}", deposit_id);
+        match outcome {
+            DepositNotifyOutcome::Queued => {
+                info!("Notified coordinator about deposit: {}", deposit_id);
+            }
+            DepositNotifyOutcome::Duplicate => {
+                debug!("Deposit {} was already known to coordinator", deposit_id);
+            }
+        }
 
         Ok(())
     }
 }
 
+/// Why a log subscription stopped delivering
+#[derive(Debug, PartialEq, Eq)]
+enum SubscriptionEnd {
+    /// The provider closed the stream
+    Closed,
+    /// Neither a log nor a successful head poll within the stall timeout
+    Stalled,
+}
+
+/// Checkpoint for live logs. It only advances while every log so far was
+/// notified; after a failure it stays put, so the next subscription replays
+/// from the failed log.
+#[derive(Debug, Default)]
+struct LiveCheckpoint {
+    pinned: bool,
+}
+
+impl LiveCheckpoint {
+    /// Block to checkpoint after handling a log from `block`, if any. More
+    /// logs from the same block may follow, so only the block before it is
+    /// known to be done.
+    fn record(&mut self, block: u64, notified: bool) -> Option<u64> {
+        self.pinned |= !notified;
+        if self.pinned {
+            return None;
+        }
+        block.checked_sub(1)
+    }
+}
+
+/// Feed `stream` items to `on_log`, polling the chain head every
+/// `heartbeat_interval` on the same connection. Returns once the stream ends
+/// or nothing has made progress for `stall_timeout`; WebSocket providers can
+/// stop delivering logs without ever closing the stream.
+async fn watch_subscription<T, S, P, PF, H, HF>(
+    chain_id: u64,
+    mut stream: S,
+    mut poll_head: P,
+    mut on_log: H,
+    tracker: &ListenerTracker,
+    heartbeat_interval: Duration,
+    stall_timeout: Duration,
+) -> SubscriptionEnd
+where
+    S: Stream<Item = T> + Unpin,
+    P: FnMut() -> PF,
+    PF: Future<Output = Result<u64>>,
+    H: FnMut(T) -> HF,
+    HF: Future<Output = ()>,
+{
+    let mut last_progress = Instant::now();
+    let mut heartbeat = tokio::time::interval(heartbeat_interval);
+    heartbeat.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            item = stream.next() => match item {
+                Some(item) => {
+                    last_progress = Instant::now();
+                    tracker.record_event(chain_id);
+                    on_log(item).await;
+                }
+                None => return SubscriptionEnd::Closed,
+            },
+            _ = heartbeat.tick() => {
+                // A hung connection must not hold up the stall check
+                match tokio::time::timeout(heartbeat_interval, poll_head()).await {
+                    Ok(Ok(head)) => {
+                        debug!("Chain {} head at {}", chain_id, head);
+                        last_progress = Instant::now();
+                        tracker.record_heartbeat(chain_id);
+                    }
+                    Ok(Err(e)) => debug!("Head poll failed on chain {}: {}", chain_id, e),
+                    Err(_) => debug!("Head poll timed out on chain {}", chain_id),
+                }
+            }
+            _ = tokio::time::sleep_until(last_progress + stall_timeout) => {
+                return SubscriptionEnd::Stalled;
+            }
+        }
+    }
+}
+
+/// Exponential reconnect delay with up to 50% random jitter, so relayers
+/// sharing a provider don't all reconnect in lockstep
+pub(crate) struct ReconnectBackoff {
+    base: Duration,
+    max: Duration,
+    failures: u32,
+}
+
+impl ReconnectBackoff {
+    pub(crate) fn new(base: Duration, max: Duration) -> Self {
+        Self {
+            base,
+            max,
+            failures: 0,
+        }
+    }
+
+    /// Delay before the next attempt, without jitter
+    fn current(&self) -> Duration {
+        self.base
+            .saturating_mul(2u32.saturating_pow(self.failures))
+            .min(self.max)
+    }
+
+    /// Delay to wait now; each call doubles the next one up to `max`
+    pub(crate) fn next_delay(&mut self) -> Duration {
+        use ethers::core::rand::Rng;
+
+        let delay = self.current();
+        self.failures = self.failures.saturating_add(1);
+
+        let jitter_ms = (delay.as_millis() / 2) as u64;
+        delay + Duration::from_millis(ethers::core::rand::thread_rng().gen_range(0..=jitter_ms))
+    }
+
+    fn reset(&mut self) {
+        self.failures = 0;
+    }
+}
+
+/// Re-run `subscribe` forever: after a closed or stalled subscription, and
+/// with growing backoff while connecting keeps failing
+async fn reconnect_loop<C, CF>(
+    chain_id: u64,
+    mut subscribe: C,
+    mut backoff: ReconnectBackoff,
+    tracker: &ListenerTracker,
+) where
+    C: FnMut() -> CF,
+    CF: Future<Output = Result<SubscriptionEnd>>,
+{
+    loop {
+        match subscribe().await {
+            Ok(end) => {
+                // The connection worked; start the backoff over
+                backoff.reset();
+                match end {
+                    SubscriptionEnd::Stalled => {
+                        warn!("Event subscription on chain {} stalled, reconnecting", chain_id)
+                    }
+                    SubscriptionEnd::Closed => {
+                        warn!("Event subscription on chain {} closed, reconnecting", chain_id)
+                    }
+                }
+            }
+            Err(e) => {
+                tracker.set_running(chain_id, false);
+                warn!("EVM listener error for chain {}: {}", chain_id, e);
+            }
+        }
+
+        let delay = backoff.next_delay();
+        debug!("Reconnecting to chain {} in {:?}", chain_id, delay);
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Decode a `TokensLocked` log into a coordinator deposit notification
+fn parse_tokens_locked(
+    source_chain_id: u64,
+    log: &ethers::types::Log,
+) -> Result<DepositNotification> {
+    if log.topics.len() < 4 || log.data.len() < 128 {
+        anyhow::bail!("Malformed TokensLocked log");
+    }
+    let tx_hash = log.transaction_hash
+        .context("TokensLocked log without a transaction hash")?;
+
+    // Parse event data
+    let deposit_id = hex::encode(log.topics[1].as_bytes());
+    let sender = format!("0x{}", hex::encode(&log.topics[2].as_bytes()[12..]));
+    let token = format!("0x{}", hex::encode(&log.topics[3].as_bytes()[12..]));
+    
+    // ABI words: amount, targetChainId, recipient, zcashAddress, timestamp.
+    // The coordinator decodes the same layout when verifying the deposit.
+    if log.data[..24].iter().any(|b| *b != 0) {
+        anyhow::bail!("TokensLocked amount exceeds u64");
+    }
+    let amount = u64::from_be_bytes(log.data[24..32].try_into()?);
+    let target_chain_id = u64::from_be_bytes(log.data[56..64].try_into()?);
+    let recipient = chain_encoding::evm_word(&log.data, 2)?;
+    let zcash_address = chain_encoding::evm_word(&log.data, 3)?;
+
+    Ok(DepositNotification {
+        deposit_id,
+        source_chain_id,
+        target_chain_id,
+        sender,
+        token,
+        amount,
+        recipient,
+        zcash_address,
+        timestamp: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs(),
+        target_chain_preferences: Vec::new(),
+        block_number: log.block_number.map(|n| n.as_u64()),
+        tx_hash: format!("{:?}", tx_hash),
+    })
+}
+
+/// Fetch every `TokensLocked` event emitted by `gateway` in
+/// `[from_block, to_block]`, in chunks to stay under RPC log limits
+pub async fn fetch_tokens_locked<M: ethers::providers::Middleware>(
+    provider: &M,
+    chain_id: u64,
+    gateway: ethers::types::Address,
+    from_block: u64,
+    to_block: u64,
+) -> Result<Vec<DepositNotification>> {
+    use ethers::types::Filter;
+
+    if from_block > to_block {
+        anyhow::bail!("from_block {} is after to_block {}", from_block, to_block);
+    }
+
+    let mut notifications = Vec::new();
+    let mut start = from_block;
+
+    while start <= to_block {
+        let end = start.saturating_add(BACKFILL_CHUNK_SIZE - 1).min(to_block);
+
+        let filter = Filter::new()
+            .address(gateway)
+            .event(TOKENS_LOCKED_EVENT)
+            .from_block(start)
+            .to_block(end);
+
+        let logs = provider
+            .get_logs(&filter)
+            .await
+            .map_err(|e| anyhow::anyhow!("get_logs {}..{} failed: {}", start, end, e))?;
+
+        debug!("Backfill chain {}: {} logs in blocks {}..={}", chain_id, logs.len(), start, end);
+
+        for log in &logs {
+            match parse_tokens_locked(chain_id, log) {
+                Ok(notification) => notifications.push(notification),
+                Err(e) => warn!("Skipping log {:?}: {}", log.transaction_hash, e),
+            }
+        }
+
+        start = end + 1;
+    }
+
+    Ok(notifications)
+}
+
+/// Re-notify the coordinator of every deposit on `chain_id` in a block range.
+/// Used by the relayer's `--backfill` mode after extended downtime.
+pub async fn backfill_deposits(
+    config: &RelayerConfig,
+    coordinator: &CoordinatorClient,
+    chain_id: u64,
+    from_block: u64,
+    to_block: u64,
+) -> Result<usize> {
+    use ethers::providers::{Http, Provider};
+
+    let chain = config
+        .get_chain(chain_id)
+        .with_context(|| format!("Chain {} is not configured", chain_id))?;
+
+    if !chain.chain_type.is_evm() {
+        anyhow::bail!("Backfill is only supported for EVM chains");
+    }
+
+    let provider = Provider::<Http>::try_from(chain.rpc_url.as_str())?;
+    let gateway = chain.gateway_address.parse()?;
+
+    let notifications =
+        fetch_tokens_locked(&provider, chain_id, gateway, from_block, to_block).await?;
+    let count = notifications.len();
+
+    for notification in notifications {
+        let deposit_id = notification.deposit_id.clone();
+        if coordinator.notify_deposit(notification).await? == DepositNotifyOutcome::Queued {
+            info!("Backfilled deposit: {}", deposit_id);
+        }
+    }
+
+    Ok(count)
+}
+
 // ============ Solana Event Listener ============
 
 struct SolanaEventListener {
@@ -290,11 +658,19 @@ impl EventListener for SolanaEventListener {
         );
 
         let chain_id = self.chain_config.chain_id;
-        let rpc_url = self.chain_config.rpc_url.clone();
+        let program_id: solana_sdk::pubkey::Pubkey = self
+            .chain_config
+            .gateway_address
+            .parse()
+            .context("Invalid Solana gateway program ID")?;
+        let client = solana_client::nonblocking::rpc_client::RpcClient::new_with_commitment(
+            self.chain_config.rpc_url.clone(),
+            self.chain_config.solana_commitment(),
+        );
         let coordinator = self.coordinator_client.clone();
 
         tokio::spawn(async move {
-            if let Err(e) = Self::listen_loop(chain_id, &rpc_url, coordinator).await {
+            if let Err(e) = Self::listen_loop(chain_id, client, program_id, coordinator).await {
                 warn!("Solana listener error for chain {}: {}", chain_id, e);
             }
         });
@@ -306,22 +682,110 @@ impl EventListener for SolanaEventListener {
 impl SolanaEventListener {
     async fn listen_loop(
         chain_id: u64,
-        rpc_url: &str,
+        client: solana_client::nonblocking::rpc_client::RpcClient,
+        program_id: solana_sdk::pubkey::Pubkey,
         _coordinator: Arc<CoordinatorClient>,
     ) -> Result<()> {
-        use solana_client::rpc_client::RpcClient;
-
-        let _client = RpcClient::new(rpc_url.to_string());
+        info!(
+            "Connected to Solana RPC on chain {} at {:?} commitment",
+            chain_id,
+            client.commitment().commitment
+        );
 
-        info!("Connected to Solana RPC on chain {}", chain_id);
+        let mut finality = SolanaFinality::default();
 
         loop {
             tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
             debug!("Polling Solana for new transactions on chain {}", chain_id);
-            
-            // Poll for TokensLocked events and notify coordinator
-            // Similar to EVM implementation
+
+            let finalized = match finality.poll(&client, &program_id).await {
+                Ok(finalized) => finalized,
+                Err(e) => {
+                    warn!("Solana poll failed on chain {}: {}", chain_id, e);
+                    continue;
+                }
+            };
+
+            for signature in finalized {
+                debug!("Gateway transaction {} finalized on chain {}", signature, chain_id);
+                // Parse TokensLocked events and notify coordinator
+                // Similar to EVM implementation
+            }
+        }
+    }
+}
+
+/// Most signatures `getSignatureStatuses` answers for in one call
+const MAX_SIGNATURE_STATUSES: usize = 256;
+
+/// Gateway transactions seen on a Solana chain but not yet finalized
+#[derive(Debug, Default)]
+struct SolanaFinality {
+    /// Newest transaction listed so far; the next poll lists only later ones
+    newest: Option<solana_sdk::signature::Signature>,
+    /// Successful transactions awaiting finality, oldest first
+    pending: Vec<solana_sdk::signature::Signature>,
+}
+
+impl SolanaFinality {
+    /// List gateway transactions since the last poll at the client's
+    /// commitment and return those now finalized, oldest first. Deposits
+    /// are only reported once finalized whatever commitment the chain is
+    /// configured for, since a fork switch can drop anything less.
+    async fn poll(
+        &mut self,
+        client: &solana_client::nonblocking::rpc_client::RpcClient,
+        program_id: &solana_sdk::pubkey::Pubkey,
+    ) -> Result<Vec<solana_sdk::signature::Signature>> {
+        use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+        use solana_sdk::commitment_config::CommitmentConfig;
+
+        // getSignaturesForAddress doesn't serve `processed`
+        let commitment = if client.commitment().is_at_least_confirmed() {
+            client.commitment()
+        } else {
+            CommitmentConfig::confirmed()
+        };
+
+        // Newest first
+        let listed = client
+            .get_signatures_for_address_with_config(
+                program_id,
+                GetConfirmedSignaturesForAddress2Config {
+                    before: None,
+                    until: self.newest,
+                    limit: None,
+                    commitment: Some(commitment),
+                },
+            )
+            .await?;
+
+        if let Some(newest) = listed.first() {
+            self.newest = Some(newest.signature.parse()?);
+        }
+        for entry in listed.iter().rev() {
+            // Failed transactions locked nothing
+            if entry.err.is_none() {
+                self.pending.push(entry.signature.parse()?);
+            }
+        }
+
+        let mut finalized = Vec::new();
+        let mut still_pending = Vec::new();
+        for chunk in self.pending.chunks(MAX_SIGNATURE_STATUSES) {
+            let statuses = client.get_signature_statuses(chunk).await?.value;
+            for (signature, status) in chunk.iter().zip(statuses) {
+                match status {
+                    Some(status) if status.satisfies_commitment(CommitmentConfig::finalized()) => {
+                        finalized.push(*signature)
+                    }
+                    _ => still_pending.push(*signature),
+                }
+            }
         }
+        self.pending = still_pending;
+
+        Ok(finalized)
     }
 }
 
@@ -421,4 +885,315 @@ impl EventListener for MinaEventListener {
 
         Ok(())
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::providers::Provider;
+    use ethers::types::{Address, Bytes, Log, H256};
+
+    fn tokens_locked_log(deposit_id: u8, amount: u64, target_chain_id: u64) -> Log {
+        let mut data = vec![0u8; 160];
+        data[24..32].copy_from_slice(&amount.to_be_bytes());
+        data[56..64].copy_from_slice(&target_chain_id.to_be_bytes());
+
+        Log {
+            topics: vec![
+                H256::zero(),
+                H256::repeat_byte(deposit_id),
+                H256::repeat_byte(0xaa),
+                H256::repeat_byte(0xbb),
+            ],
+            data: Bytes::from(data),
+            transaction_hash: Some(H256::repeat_byte(deposit_id)),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_backfill_parses_logs_in_range() {
+        let (provider, mock) = Provider::mocked();
+        mock.push::<Vec<Log>, _>(vec![
+            tokens_locked_log(1, 1_000, 8453),
+            tokens_locked_log(2, 2_500, 137),
+        ])
+        .unwrap();
+
+        let notifications = fetch_tokens_locked(&provider, 1, Address::zero(), 100, 200)
+            .await
+            .unwrap();
+
+        assert_eq!(notifications.len(), 2);
+        assert_eq!(notifications[0].deposit_id, hex::encode([1u8; 32]));
+        assert_eq!(notifications[0].amount, 1_000);
+        assert_eq!(notifications[0].tx_hash, format!("{:?}", H256::repeat_byte(1)));
+        assert_eq!(notifications[0].target_chain_id, 8453);
+        assert_eq!(notifications[0].source_chain_id, 1);
+        assert_eq!(notifications[1].amount, 2_500);
+        assert_eq!(notifications[1].token, format!("0x{}", hex::encode([0xbbu8; 20])));
+    }
+
+    #[tokio::test]
+    async fn test_backfill_rejects_inverted_range() {
+        let (provider, _mock) = Provider::mocked();
+
+        assert!(fetch_tokens_locked(&provider, 1, Address::zero(), 200, 100).await.is_err());
+    }
+
+    fn fast_backoff() -> ReconnectBackoff {
+        ReconnectBackoff::new(Duration::from_millis(1), Duration::from_millis(5))
+    }
+
+    #[tokio::test]
+    async fn test_stalled_stream_triggers_reconnect() {
+        let tracker = ListenerTracker::default();
+        let attempts = std::sync::atomic::AtomicUsize::new(0);
+
+        // A stream that never yields and a head poll that never succeeds:
+        // the provider has silently gone away
+        let subscribe = || async {
+            attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(watch_subscription(
+                1,
+                futures::stream::pending::<Log>(),
+                || async { Err(anyhow::anyhow!("connection dropped")) },
+                |_log| async {},
+                &tracker,
+                Duration::from_millis(10),
+                Duration::from_millis(50),
+            )
+            .await)
+        };
+
+        let _ = tokio::time::timeout(
+            Duration::from_millis(500),
+            reconnect_loop(1, subscribe, fast_backoff(), &tracker),
+        )
+        .await;
+
+        // Each 50ms stall was followed by a fresh subscription
+        assert!(attempts.load(std::sync::atomic::Ordering::SeqCst) >= 3);
+    }
+
+    #[tokio::test]
+    async fn test_reconnects_after_socket_closes() {
+        let tracker = ListenerTracker::default();
+        tracker.register(1);
+        let attempts = std::sync::atomic::AtomicUsize::new(0);
+        let handled = std::sync::atomic::AtomicUsize::new(0);
+
+        // Connection 1 delivers a log then the socket closes, connection 2
+        // fails outright, connection 3 delivers another log and stays open
+        let subscribe = || async {
+            let attempt = attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let logs = match attempt {
+                0 => futures::stream::iter(vec![tokens_locked_log(1, 1, 8453)]).boxed(),
+                1 => anyhow::bail!("connection refused"),
+                _ => futures::stream::iter(vec![tokens_locked_log(2, 1, 8453)])
+                    .chain(futures::stream::pending())
+                    .boxed(),
+            };
+            tracker.set_running(1, true);
+
+            Ok(watch_subscription(
+                1,
+                logs,
+                || async { Ok(100) },
+                |_log| {
+                    handled.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    async {}
+                },
+                &tracker,
+                Duration::from_millis(10),
+                Duration::from_secs(60),
+            )
+            .await)
+        };
+
+        let _ = tokio::time::timeout(
+            Duration::from_millis(300),
+            reconnect_loop(1, subscribe, fast_backoff(), &tracker),
+        )
+        .await;
+
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 3);
+        assert_eq!(handled.load(std::sync::atomic::Ordering::SeqCst), 2);
+        assert!(tracker.is_running(1));
+    }
+
+    #[test]
+    fn test_live_checkpoint_stops_at_first_failure() {
+        let mut checkpoint = LiveCheckpoint::default();
+        assert_eq!(checkpoint.record(100, true), Some(99));
+        assert_eq!(checkpoint.record(100, true), Some(99));
+        assert_eq!(checkpoint.record(101, true), Some(100));
+
+        // A later success must not skip past the failed log's block
+        assert_eq!(checkpoint.record(102, false), None);
+        assert_eq!(checkpoint.record(103, true), None);
+        assert_eq!(checkpoint.record(104, true), None);
+    }
+
+    #[test]
+    fn test_reconnect_backoff_grows_with_jitter() {
+        let mut backoff = ReconnectBackoff::new(Duration::from_secs(1), Duration::from_secs(8));
+
+        for expected in [1, 2, 4, 8, 8] {
+            let base = Duration::from_secs(expected);
+            assert_eq!(backoff.current(), base);
+            let delay = backoff.next_delay();
+            assert!(delay >= base && delay <= base + base / 2);
+        }
+
+        backoff.reset();
+        assert_eq!(backoff.current(), Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_heartbeats_and_logs_keep_subscription_alive() {
+        let tracker = ListenerTracker::default();
+
+        // No logs, but the head poll keeps answering
+        let quiet = watch_subscription(
+            1,
+            futures::stream::pending::<Log>(),
+            || async { Ok(100) },
+            |_log| async {},
+            &tracker,
+            Duration::from_millis(10),
+            Duration::from_millis(50),
+        );
+        assert!(tokio::time::timeout(Duration::from_millis(300), quiet).await.is_err());
+
+        // Logs delivered, then the provider closes the stream
+        let mut handled = 0;
+        let end = watch_subscription(
+            1,
+            futures::stream::iter(vec![tokens_locked_log(1, 1, 8453), tokens_locked_log(2, 1, 8453)]),
+            || async { Ok(100) },
+            |_log| {
+                handled += 1;
+                async {}
+            },
+            &tracker,
+            Duration::from_millis(10),
+            Duration::from_millis(50),
+        )
+        .await;
+
+        assert_eq!(end, SubscriptionEnd::Closed);
+        assert_eq!(handled, 2);
+    }
+
+    /// Solana RPC stand-in with one successful gateway transaction, reported
+    /// `confirmed` until `finalized` is set. Records every request.
+    async fn mock_solana_node(
+        signature: solana_sdk::signature::Signature,
+        finalized: Arc<std::sync::atomic::AtomicBool>,
+        requests: Arc<std::sync::Mutex<Vec<serde_json::Value>>>,
+    ) -> String {
+        use axum::{routing::post, Json, Router};
+        use serde_json::{json, Value};
+        use std::sync::atomic::Ordering;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route(
+            "/",
+            post(move |Json(request): Json<Value>| {
+                requests.lock().unwrap().push(request.clone());
+                let confirmation = if finalized.load(Ordering::SeqCst) { "finalized" } else { "confirmed" };
+                let result = match request["method"].as_str().unwrap() {
+                    "getVersion" => json!({ "solana-core": "1.16.0", "feature-set": 0 }),
+                    "getSignaturesForAddress" if request["params"][1]["until"].is_null() => json!([{
+                        "signature": signature.to_string(),
+                        "slot": 10,
+                        "err": null,
+                        "memo": null,
+                        "blockTime": null,
+                        "confirmationStatus": confirmation,
+                    }]),
+                    "getSignaturesForAddress" => json!([]),
+                    "getSignatureStatuses" => json!({
+                        "context": { "slot": 12 },
+                        "value": [{
+                            "slot": 10,
+                            "confirmations": null,
+                            "status": { "Ok": null },
+                            "err": null,
+                            "confirmationStatus": confirmation,
+                        }],
+                    }),
+                    method => panic!("unexpected Solana RPC {}", method),
+                };
+                async move { Json(json!({ "jsonrpc": "2.0", "id": request["id"], "result": result })) }
+            }),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_solana_deposits_wait_for_finality() {
+        use crate::config::SolanaCommitment;
+        use solana_client::nonblocking::rpc_client::RpcClient;
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let signature = solana_sdk::signature::Signature::from([7u8; 64]);
+        let finalized = Arc::new(AtomicBool::new(false));
+        let requests = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let url = mock_solana_node(signature, finalized.clone(), requests.clone()).await;
+        let program_id = solana_sdk::pubkey::Pubkey::new_unique();
+
+        let mut chain = RelayerConfig::mock().chains.remove(0);
+        chain.commitment = SolanaCommitment::Confirmed;
+        let client = RpcClient::new_with_commitment(url.clone(), chain.solana_commitment());
+        let mut finality = SolanaFinality::default();
+
+        // Seen at `confirmed`, but not reported until finalized
+        assert!(finality.poll(&client, &program_id).await.unwrap().is_empty());
+        assert_eq!(finality.pending, vec![signature]);
+        assert!(finality.poll(&client, &program_id).await.unwrap().is_empty());
+
+        finalized.store(true, Ordering::SeqCst);
+        assert_eq!(finality.poll(&client, &program_id).await.unwrap(), vec![signature]);
+        assert!(finality.pending.is_empty());
+        assert!(finality.poll(&client, &program_id).await.unwrap().is_empty());
+
+        let listings: Vec<_> = requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|request| request["method"] == "getSignaturesForAddress")
+            .map(|request| request["params"].clone())
+            .collect();
+        assert_eq!(listings.len(), 4);
+        for params in &listings {
+            assert_eq!(params[0], program_id.to_string());
+            assert_eq!(params[1]["commitment"], "confirmed");
+        }
+        // Later polls only list transactions after the newest already seen
+        assert!(listings[0][1]["until"].is_null());
+        assert_eq!(listings[1][1]["until"], signature.to_string());
+
+        // Each level reaches the node; `processed` is raised to `confirmed`
+        // for listing, the lowest getSignaturesForAddress serves
+        for (commitment, sent) in [
+            (SolanaCommitment::Finalized, "finalized"),
+            (SolanaCommitment::Processed, "confirmed"),
+        ] {
+            chain.commitment = commitment;
+            let client = RpcClient::new_with_commitment(url.clone(), chain.solana_commitment());
+            SolanaFinality::default().poll(&client, &program_id).await.unwrap();
+
+            let requests = requests.lock().unwrap();
+            let listing = requests
+                .iter()
+                .rev()
+                .find(|request| request["method"] == "getSignaturesForAddress")
+                .unwrap();
+            assert_eq!(listing["params"][1]["commitment"], sent);
+        }
+    }
 }
\ No newline at end of file
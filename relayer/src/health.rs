@@ -0,0 +1,198 @@
+// relayer/src/health.rs
+//! Liveness/readiness reporting for the relayer's HTTP server
+//! Liveness only covers the relayer itself (its chain listeners), so an
+//! orchestrator doesn't restart relayers when the coordinator goes down.
+//! Readiness also needs the coordinator, the source of authorized
+//! withdrawals. P2P peers are reported but a relayer without peers can still
+//! relay, so they don't fail either probe.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::coordinator_client::CoordinatorClient;
+use crate::p2p_network::P2PNetwork;
+
+/// Per-chain listener state, shared between listeners and the health probe
+#[derive(Clone, Default)]
+pub struct ListenerTracker {
+    chains: Arc<Mutex<HashMap<u64, ListenerStatus>>>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct ListenerStatus {
+    running: bool,
+    last_event_at: Option<u64>,
+    last_heartbeat_at: Option<u64>,
+}
+
+impl ListenerTracker {
+    /// Track a configured chain (not running until its listener starts)
+    pub fn register(&self, chain_id: u64) {
+        self.chains.lock().unwrap().entry(chain_id).or_default();
+    }
+
+    pub fn set_running(&self, chain_id: u64, running: bool) {
+        self.chains.lock().unwrap().entry(chain_id).or_default().running = running;
+    }
+
+    pub fn is_running(&self, chain_id: u64) -> bool {
+        self.chains
+            .lock()
+            .unwrap()
+            .get(&chain_id)
+            .map(|status| status.running)
+            .unwrap_or(false)
+    }
+
+    /// Note that a gateway event was just received on `chain_id`
+    pub fn record_event(&self, chain_id: u64) {
+        self.chains.lock().unwrap().entry(chain_id).or_default().last_event_at = Some(now());
+    }
+
+    /// Note that the listener's connection to `chain_id` answered a head poll
+    pub fn record_heartbeat(&self, chain_id: u64) {
+        self.chains.lock().unwrap().entry(chain_id).or_default().last_heartbeat_at = Some(now());
+    }
+
+    fn snapshot(&self) -> Vec<ChainHealth> {
+        let mut chains: Vec<ChainHealth> = self
+            .chains
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(chain_id, status)| ChainHealth {
+                chain_id: *chain_id,
+                listener_running: status.running,
+                last_event_at: status.last_event_at,
+                last_heartbeat_at: status.last_heartbeat_at,
+            })
+            .collect();
+        chains.sort_by_key(|c| c.chain_id);
+        chains
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChainHealth {
+    pub chain_id: u64,
+    pub listener_running: bool,
+    pub last_event_at: Option<u64>,
+    pub last_heartbeat_at: Option<u64>,
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+#[derive(Debug, Serialize)]
+pub struct HealthReport {
+    pub status: String,
+    pub coordinator_reachable: bool,
+    pub peer_count: usize,
+    pub chains: Vec<ChainHealth>,
+}
+
+impl HealthReport {
+    fn new(coordinator_reachable: bool, peer_count: usize, chains: Vec<ChainHealth>) -> Self {
+        let mut report = Self {
+            status: String::new(),
+            coordinator_reachable,
+            peer_count,
+            chains,
+        };
+        report.status = if report.is_ready() { "ok" } else { "degraded" }.to_string();
+        report
+    }
+
+    /// At least one listener running; restarting won't bring back an
+    /// unreachable coordinator, so it isn't considered
+    pub fn is_live(&self) -> bool {
+        self.chains.is_empty() || self.chains.iter().any(|c| c.listener_running)
+    }
+
+    /// Coordinator reachable and every configured listener running
+    pub fn is_ready(&self) -> bool {
+        self.coordinator_reachable && self.chains.iter().all(|c| c.listener_running)
+    }
+}
+
+/// Handles the probe needs to assess the relayer's dependencies
+#[derive(Clone)]
+pub struct HealthState {
+    pub coordinator: Arc<CoordinatorClient>,
+    pub p2p_network: Arc<P2PNetwork>,
+    pub listeners: ListenerTracker,
+}
+
+impl HealthState {
+    pub async fn report(&self) -> HealthReport {
+        HealthReport::new(
+            self.coordinator.is_reachable().await,
+            self.p2p_network.peer_count().await,
+            self.listeners.snapshot(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RelayerConfig;
+    use crate::database::RelayerDatabase;
+    use crate::stake_manager::StakeManager;
+
+    #[test]
+    fn test_healthy_report() {
+        let listeners = ListenerTracker::default();
+        listeners.register(1);
+        listeners.register(8453);
+        listeners.set_running(1, true);
+        listeners.set_running(8453, true);
+        listeners.record_event(1);
+
+        let report = HealthReport::new(true, 3, listeners.snapshot());
+        assert_eq!(report.status, "ok");
+        assert!(report.is_live());
+        assert!(report.is_ready());
+        assert!(report.chains[0].last_event_at.is_some());
+        assert!(report.chains[1].last_event_at.is_none());
+
+        // One listener down: still live, no longer ready
+        listeners.set_running(8453, false);
+        let report = HealthReport::new(true, 0, listeners.snapshot());
+        assert_eq!(report.status, "degraded");
+        assert!(report.is_live());
+        assert!(!report.is_ready());
+
+        // Every listener down: not live either
+        listeners.set_running(1, false);
+        assert!(!HealthReport::new(true, 0, listeners.snapshot()).is_live());
+    }
+
+    #[tokio::test]
+    async fn test_not_ready_but_live_when_coordinator_unreachable() {
+        let config = RelayerConfig::mock();
+        let db = RelayerDatabase::new(":memory:").await.unwrap();
+        let stake_manager = Arc::new(StakeManager::new(config.clone(), db).await.unwrap());
+        let listeners = ListenerTracker::default();
+        listeners.set_running(1, true);
+
+        let state = HealthState {
+            // Nothing listens on port 1
+            coordinator: Arc::new(CoordinatorClient::mock("http://127.0.0.1:1")),
+            p2p_network: Arc::new(P2PNetwork::new(config, stake_manager).await.unwrap()),
+            listeners,
+        };
+
+        let report = state.report().await;
+        assert_eq!(report.status, "degraded");
+        assert!(!report.coordinator_reachable);
+        // Not worth a restart, but not worth routing work to either
+        assert!(report.is_live());
+        assert!(!report.is_ready());
+    }
+}
@@ -0,0 +1,29 @@
+// relayer/src/lib.rs
+//! ZeroBridge Relayer Library
+//!
+//! The relayer's components, for embedding outside the `zerobridge-relayer`
+//! binary (e.g. the coordinator's end-to-end harness, which drives a real
+//! `CoordinatorClient` against an in-process coordinator).
+
+pub mod config;
+pub mod event_listener;
+pub mod transaction_executor;
+pub mod solana_gateway;
+pub mod p2p_network;
+pub mod stake_manager;
+pub mod database;
+pub mod coordinator_client;
+pub mod authorization;
+pub mod metrics;
+pub mod health;
+pub mod config_check;
+pub mod chain_encoding;
+pub mod telemetry;
+
+// Re-export commonly used types
+pub use config::RelayerConfig;
+pub use coordinator_client::{CoordinatorClient, DepositNotifyOutcome};
+pub use transaction_executor::TransactionExecutor;
+
+/// Library version
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -19,28 +19,44 @@
 
 use anyhow::{Context, Result};
 use clap::Parser;
-use tracing::{error, info, warn};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tracing::{debug, error, info, warn};
 use tokio::signal;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
 
 mod config;
 mod event_listener;
 mod transaction_executor;
+mod solana_gateway;
 mod p2p_network;
 mod stake_manager;
 mod database;
 mod coordinator_client;
+mod authorization;
 mod metrics;
+mod health;
+mod config_check;
+mod chain_encoding;
+mod telemetry;
 
-use config::RelayerConfig;
+use config::{ChainConfig, RelayerConfig};
 use event_listener::EventListenerManager;
 use transaction_executor::TransactionExecutor;
 use p2p_network::P2PNetwork;
 use stake_manager::StakeManager;
-use database::RelayerDatabase;
-use coordinator_client::CoordinatorClient;
+use database::{BatchItem, RelayerDatabase};
+use coordinator_client::{AuthorizedWithdrawal, CoordinatorClient};
+use authorization::AuthorizationVerifier;
+use zerobridge_types::AuthorizationScheme;
+use health::{HealthState, ListenerTracker};
+
+/// Backoff while retrying coordinators that were down at startup
+const COORDINATOR_RETRY_BASE_DELAY: std::time::Duration = std::time::Duration::from_secs(1);
+const COORDINATOR_RETRY_MAX_DELAY: std::time::Duration = std::time::Duration::from_secs(60);
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
@@ -53,12 +69,43 @@ struct Args {
 
     #[clap(short, long, default_value = "9091")]
     metrics_port: u16,
+
+    /// Re-notify the coordinator of deposits on this chain, then exit
+    #[clap(long, value_name = "CHAIN_ID", requires_all = ["from_block", "to_block"])]
+    backfill: Option<u64>,
+
+    /// First block of the backfill range (inclusive)
+    #[clap(long)]
+    from_block: Option<u64>,
+
+    /// Last block of the backfill range (inclusive)
+    #[clap(long)]
+    to_block: Option<u64>,
+
+    /// Check connectivity to the coordinator and every chain node and
+    /// gateway address formats, print a report and exit
+    #[clap(long)]
+    check: bool,
+
+    /// Write a commented template config to PATH and exit
+    #[clap(long, value_name = "PATH")]
+    generate_config: Option<PathBuf>,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
-    init_tracing(args.verbose)?;
+    telemetry::init(args.verbose)?;
+
+    if let Some(path) = &args.generate_config {
+        if path.exists() {
+            anyhow::bail!("Refusing to overwrite existing file {:?}", path);
+        }
+        std::fs::write(path, RelayerConfig::template_toml()?)
+            .with_context(|| format!("Failed to write {:?}", path))?;
+        info!("✓ Template config written to {:?}; fill in its placeholders before starting", path);
+        return Ok(());
+    }
 
     info!("🔄 Starting ZeroBridge Relayer v{}", env!("CARGO_PKG_VERSION"));
     info!("Configuration: {:?}", args.config);
@@ -66,8 +113,20 @@ async fn main() -> Result<()> {
     let config = RelayerConfig::load(&args.config)
         .context("Failed to load configuration")?;
     
+    if args.check {
+        let results = config_check::run_checks(&config).await;
+        print!("{}", config_check::format_report(&results));
+        
+        let failed = results.iter().filter(|r| !r.passed()).count();
+        if failed > 0 {
+            anyhow::bail!("{} of {} checks failed", failed, results.len());
+        }
+        return Ok(());
+    }
+    
     info!("✓ Configuration loaded");
-    info!("  Coordinator: {}", config.coordinator_url);
+    let coordinators = config.coordinator_endpoints();
+    info!("  Coordinators: {}", coordinators.join(", "));
     info!("  Monitoring chains: {}", config.chains.len());
     info!("  Relayer identity: {}", config.relayer_identity.name);
 
@@ -76,12 +135,51 @@ async fn main() -> Result<()> {
         .context("Failed to initialize database")?;
     info!("✓ Database initialized");
 
-    // Connect to coordinator (read-only access)
+    // Connect to coordinator (read-only access). Unreachable coordinators
+    // don't stop startup; readiness stays false until one answers.
     let coordinator_client = Arc::new(
-        CoordinatorClient::new(&config.coordinator_url)
+        CoordinatorClient::connect(&coordinators)
+            .await
             .context("Failed to connect to coordinator")?
     );
-    info!("✓ Connected to coordinator at {}", config.coordinator_url);
+    if coordinator_client.is_connected() {
+        info!("✓ Connected to {} coordinator(s)", coordinators.len());
+    }
+
+    // Backfill mode: replay a block range and exit without joining the network
+    if let (Some(chain_id), Some(from_block), Some(to_block)) =
+        (args.backfill, args.from_block, args.to_block)
+    {
+        if !coordinator_client.is_connected() {
+            anyhow::bail!("Backfill needs a reachable coordinator");
+        }
+        info!("Backfilling chain {} blocks {}..={}", chain_id, from_block, to_block);
+        let count = event_listener::backfill_deposits(
+            &config,
+            &coordinator_client,
+            chain_id,
+            from_block,
+            to_block,
+        )
+        .await
+        .context("Backfill failed")?;
+        info!("✓ Backfill complete: {} deposits notified", count);
+        return Ok(());
+    }
+
+    // Keep retrying coordinators that were down at startup
+    {
+        let coordinator_client = coordinator_client.clone();
+        tokio::spawn(async move {
+            let mut backoff = event_listener::ReconnectBackoff::new(
+                COORDINATOR_RETRY_BASE_DELAY,
+                COORDINATOR_RETRY_MAX_DELAY,
+            );
+            while !coordinator_client.connect_pending().await {
+                tokio::time::sleep(backoff.next_delay()).await;
+            }
+        });
+    }
 
     let stake_manager = Arc::new(
         StakeManager::new(config.clone(), db.clone())
@@ -112,32 +210,44 @@ async fn main() -> Result<()> {
     );
     info!("✓ Transaction executor initialized");
 
+    let listener_tracker = ListenerTracker::default();
     let mut event_listeners = EventListenerManager::new(
         config.clone(),
         coordinator_client.clone(),
         tx_executor.clone(),
         p2p_network.clone(),
         db.clone(),
+        listener_tracker.clone(),
     )
     .await
     .context("Failed to initialize event listeners")?;
     info!("✓ Event listeners initialized for {} chains", config.chains.len());
 
+    let health = HealthState {
+        coordinator: coordinator_client.clone(),
+        p2p_network: p2p_network.clone(),
+        listeners: listener_tracker,
+    };
     let metrics_handle = tokio::spawn(async move {
-        if let Err(e) = metrics::start_server(args.metrics_port).await {
+        if let Err(e) = metrics::start_server(args.metrics_port, health).await {
             error!("Metrics server error: {}", e);
         }
     });
     info!("✓ Metrics server started on port {}", args.metrics_port);
 
+    let task_limiter = Arc::new(Semaphore::new(config.max_concurrent_tasks));
+
     let relayer = Relayer {
         config,
         db,
+        authorizations: Arc::new(AuthorizationVerifier::new(coordinator_client.clone())),
         coordinator_client,
         stake_manager,
         p2p_network,
         tx_executor,
         event_listeners,
+        unsupported_chains: Mutex::new(HashSet::new()),
+        task_limiter,
     };
 
     info!("🚀 Relayer fully initialized and running");
@@ -158,6 +268,7 @@ async fn main() -> Result<()> {
     metrics_handle.abort();
     
     info!("Relayer stopped gracefully");
+    telemetry::shutdown();
     Ok(())
 }
 
@@ -165,10 +276,16 @@ struct Relayer {
     config: RelayerConfig,
     db: RelayerDatabase,
     coordinator_client: Arc<CoordinatorClient>,
+    /// Checks authorization signatures against the coordinator's key
+    authorizations: Arc<AuthorizationVerifier>,
     stake_manager: Arc<StakeManager>,
     p2p_network: Arc<P2PNetwork>,
     tx_executor: Arc<TransactionExecutor>,
     event_listeners: EventListenerManager,
+    /// Chains we've already warned about (coordinator authorizes, we don't serve)
+    unsupported_chains: Mutex<HashSet<u64>>,
+    /// Bounds in-flight withdrawal executions to `max_concurrent_tasks`
+    task_limiter: Arc<Semaphore>,
 }
 
 impl Relayer {
@@ -218,9 +335,10 @@ impl Relayer {
                 }
             }
 
-            // Update metrics
+            // Update metrics and re-rank coordinators by freshness
             if tick_count % 30 == 0 {
                 self.update_metrics().await;
+                self.coordinator_client.refresh_preference().await;
             }
 
             // P2P heartbeat
@@ -228,6 +346,7 @@ impl Relayer {
                 if let Err(e) = self.p2p_network.send_heartbeat().await {
                     warn!("Failed to send heartbeat: {}", e);
                 }
+                self.p2p_network.maintain_connectivity().await;
             }
         }
     }
@@ -240,13 +359,50 @@ impl Relayer {
             .query_authorized_withdrawals()
             .await?;
         
+        // Only consider withdrawals for chains we can actually execute on
+        let authorized = {
+            let mut warned = self.unsupported_chains.lock().unwrap();
+            filter_supported_withdrawals(&self.config, authorized, &mut warned)
+        };
+
         if !authorized.is_empty() {
             info!("Found {} authorized withdrawals from coordinator", authorized.len());
         }
 
+        let mut claimed = Vec::new();
         for withdrawal in authorized {
-            // Check if another relayer is already handling this
+            // Leave withdrawals whose fee doesn't cover gas for other relayers
+            match self.tx_executor.estimate_withdrawal_cost(withdrawal.target_chain_id).await {
+                Ok(Some(gas_cost))
+                    if !is_profitable(withdrawal.relayer_fee, gas_cost, self.config.min_profit_margin) =>
+                {
+                    debug!(
+                        "Skipping unprofitable withdrawal {}: fee={}, estimated gas={}",
+                        withdrawal.withdrawal_id, withdrawal.relayer_fee, gas_cost
+                    );
+                    continue;
+                }
+                Ok(_) => {}
+                Err(e) => warn!(
+                    "Failed to estimate gas for withdrawal {}: {}",
+                    withdrawal.withdrawal_id, e
+                ),
+            }
+
+            // Under deterministic assignment, leave it to its primary
+            // relayer until the grace period is up
+            if !self.p2p_network.may_claim(&withdrawal.withdrawal_id).await {
+                continue;
+            }
+
+            // Check if another relayer is already handling this, taking
+            // over if that relayer stopped heartbeating
             if self.p2p_network.is_task_claimed(&withdrawal.withdrawal_id).await? {
+                match self.p2p_network.steal_stale_claim(&withdrawal.withdrawal_id).await {
+                    Ok(true) => claimed.push(withdrawal),
+                    Ok(false) => {}
+                    Err(e) => warn!("Failed to steal stalled claim: {}", e),
+                }
                 continue;
             }
 
@@ -259,34 +415,180 @@ impl Relayer {
                 continue;
             }
 
-            match self.execute_authorized_withdrawal(withdrawal).await {
-                Ok(tx_hash) => {
-                    info!("✓ Executed withdrawal: tx={}", tx_hash);
-                    
-                    // Earn fee for this relay
+            claimed.push(withdrawal);
+        }
+
+        // Execute in the background, bounded by max_concurrent_tasks
+        for mut batch in group_into_batches(&self.config, claimed) {
+            let worker = self.withdrawal_worker();
+            if batch.len() == 1 {
+                let withdrawal = batch.remove(0);
+                spawn_bounded(self.task_limiter.clone(), async move {
+                    worker.relay(withdrawal).await;
+                })
+                .await?;
+            } else {
+                spawn_bounded(self.task_limiter.clone(), async move {
+                    worker.relay_batch(batch).await;
+                })
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handles needed to execute a withdrawal from a spawned task
+    fn withdrawal_worker(&self) -> WithdrawalWorker {
+        WithdrawalWorker {
+            tx_executor: self.tx_executor.clone(),
+            p2p_network: self.p2p_network.clone(),
+            stake_manager: self.stake_manager.clone(),
+            db: self.db.clone(),
+        }
+    }
+
+    /// Claim accumulated rewards from hub contract
+    async fn claim_rewards(&self) -> Result<()> {
+        let rewards = self.stake_manager.get_pending_rewards().await?;
+        
+        if rewards > 0 {
+            info!("Claiming {} accumulated rewards", rewards);
+            self.stake_manager.claim_rewards().await?;
+            info!("✓ Rewards claimed successfully");
+        }
+
+        Ok(())
+    }
+
+    /// Update metrics for monitoring
+    async fn update_metrics(&self) {
+        if let Ok(stats) = self.db.get_stats().await {
+            metrics::WITHDRAWALS_EXECUTED.set(stats.withdrawals_executed as i64);
+            metrics::REWARDS_EARNED.set(stats.total_rewards as i64);
+            metrics::STAKE_AMOUNT.set(self.config.staking.current_stake as i64);
+            metrics::SUCCESSFUL_RELAYS.set(stats.successful_relays as i64);
+        }
+    }
+}
+
+/// Executes a single claimed withdrawal; cheap to clone into spawned tasks
+#[derive(Clone)]
+struct WithdrawalWorker {
+    tx_executor: Arc<TransactionExecutor>,
+    p2p_network: Arc<P2PNetwork>,
+    stake_manager: Arc<StakeManager>,
+    db: RelayerDatabase,
+}
+
+impl WithdrawalWorker {
+    /// Execute a claimed withdrawal and record the outcome
+    async fn relay(&self, withdrawal: AuthorizedWithdrawal) {
+        match self.execute_authorized_withdrawal(withdrawal).await {
+            Ok(tx_hash) => {
+                info!("✓ Executed withdrawal: tx={}", tx_hash);
+                
+                // Earn fee for this relay
+                if let Err(e) = self.stake_manager.record_successful_relay().await {
+                    warn!("Failed to record relay: {}", e);
+                }
+            }
+            Err(e) => {
+                warn!("Failed to execute withdrawal: {}", e);
+            }
+        }
+    }
+
+    /// Execute claimed withdrawals for one chain as a single batch
+    /// transaction and record each outcome
+    async fn relay_batch(&self, batch: Vec<AuthorizedWithdrawal>) {
+        match self.execute_authorized_batch(&batch).await {
+            Ok(tx_hash) => {
+                info!("✓ Executed batch of {} withdrawals: tx={}", batch.len(), tx_hash);
+
+                for _ in &batch {
                     if let Err(e) = self.stake_manager.record_successful_relay().await {
                         warn!("Failed to record relay: {}", e);
                     }
                 }
-                Err(e) => {
-                    warn!("Failed to execute withdrawal: {}", e);
-                }
+            }
+            Err(e) => {
+                warn!("Failed to execute withdrawal batch: {}", e);
             }
         }
+    }
 
-        Ok(())
+    /// How the gateway on `chain_id` checks coordinator authorizations
+    fn authorization_scheme(&self, chain_id: u64) -> Result<AuthorizationScheme> {
+        self.config
+            .get_chain(chain_id)
+            .map(ChainConfig::authorization_scheme)
+            .with_context(|| format!("Chain {} is not configured", chain_id))
+    }
+
+    /// Execute authorized withdrawals for the same chain in one transaction,
+    /// attributing the batch's gas to each withdrawal by its fee
+    async fn execute_authorized_batch(&self, batch: &[AuthorizedWithdrawal]) -> Result<String> {
+        let chain_id = batch
+            .first()
+            .map(|w| w.target_chain_id)
+            .context("Empty withdrawal batch")?;
+
+        let scheme = self.authorization_scheme(chain_id)?;
+        for withdrawal in batch {
+            if !self.authorizations.verify_withdrawal(withdrawal, scheme).await? {
+                anyhow::bail!(
+                    "Invalid coordinator authorization signature for {}",
+                    withdrawal.withdrawal_id
+                );
+            }
+        }
+
+        let execution = self.tx_executor
+            .execute_withdrawal_batch(chain_id, batch)
+            .await?;
+
+        for withdrawal in batch {
+            self.p2p_network
+                .broadcast_withdrawal_execution(&withdrawal.withdrawal_id, &execution.tx_hash)
+                .await?;
+        }
+
+        let fees: Vec<u64> = batch.iter().map(|w| w.relayer_fee).collect();
+        let items: Vec<BatchItem> = batch
+            .iter()
+            .zip(transaction_executor::attribute_batch_gas(execution.gas_used, &fees))
+            .map(|(withdrawal, gas_used)| BatchItem {
+                withdrawal_id: withdrawal.withdrawal_id.clone(),
+                fee_earned: withdrawal.relayer_fee,
+                gas_used,
+            })
+            .collect();
+
+        self.db
+            .record_batch_execution(
+                &execution.tx_hash,
+                chain_id,
+                chrono::Utc::now().timestamp(),
+                &items,
+            )
+            .await?;
+
+        Ok(execution.tx_hash)
     }
 
     /// Execute an authorized withdrawal on the destination chain
     /// Coordinator has already verified the proof and provided authorization signature
+    #[tracing::instrument(name = "execute_withdrawal", skip_all, fields(withdrawal_id = %withdrawal.withdrawal_id))]
     async fn execute_authorized_withdrawal(
         &self,
-        withdrawal: coordinator_client::AuthorizedWithdrawal,
+        withdrawal: AuthorizedWithdrawal,
     ) -> Result<String> {
         info!("Executing authorized withdrawal: {}", withdrawal.withdrawal_id);
 
         // Verify coordinator authorization signature
-        if !self.verify_coordinator_signature(&withdrawal)? {
+        let scheme = self.authorization_scheme(withdrawal.target_chain_id)?;
+        if !self.authorizations.verify_withdrawal(&withdrawal, scheme).await? {
             anyhow::bail!("Invalid coordinator authorization signature");
         }
 
@@ -294,10 +596,12 @@ impl Relayer {
         let tx_hash = self.tx_executor
             .execute_withdrawal(
                 withdrawal.target_chain_id,
+                &withdrawal.withdrawal_id,
                 &withdrawal.recipient,
                 &withdrawal.token,
                 withdrawal.amount,
                 &withdrawal.nullifier,
+                withdrawal.deadline,
                 &withdrawal.authorization_signature,
             )
             .await?;
@@ -312,63 +616,225 @@ impl Relayer {
             .record_withdrawal_execution(
                 &withdrawal.withdrawal_id,
                 &tx_hash,
+                withdrawal.target_chain_id,
                 chrono::Utc::now().timestamp(),
+                withdrawal.relayer_fee,
             )
             .await?;
 
         Ok(tx_hash)
     }
+}
 
-    /// Verify coordinator's authorization signature
-    fn verify_coordinator_signature(
-        &self,
-        withdrawal: &coordinator_client::AuthorizedWithdrawal,
-    ) -> Result<bool> {
-        // In production, verify the signature using coordinator's public key
-        // For now, just check it's not empty
-        Ok(!withdrawal.authorization_signature.is_empty())
-    }
+/// Spawn `job` once a permit is available, so no more than the semaphore's
+/// capacity runs at once. Waits (rather than queueing unboundedly) when full.
+async fn spawn_bounded<F>(limiter: Arc<Semaphore>, job: F) -> Result<JoinHandle<()>>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let permit = limiter.acquire_owned().await?;
+    
+    Ok(tokio::spawn(async move {
+        job.await;
+        drop(permit);
+    }))
+}
 
-    /// Claim accumulated rewards from hub contract
-    async fn claim_rewards(&self) -> Result<()> {
-        let rewards = self.stake_manager.get_pending_rewards().await?;
-        
-        if rewards > 0 {
-            info!("Claiming {} accumulated rewards", rewards);
-            self.stake_manager.claim_rewards().await?;
-            info!("✓ Rewards claimed successfully");
-        }
+/// Drop withdrawals targeting chains this relayer doesn't serve.
+/// Warns once per unsupported chain instead of failing on every tick.
+fn filter_supported_withdrawals(
+    config: &RelayerConfig,
+    withdrawals: Vec<AuthorizedWithdrawal>,
+    warned_chains: &mut HashSet<u64>,
+) -> Vec<AuthorizedWithdrawal> {
+    withdrawals
+        .into_iter()
+        .filter(|w| {
+            if config.get_chain(w.target_chain_id).is_some() {
+                return true;
+            }
 
-        Ok(())
+            if warned_chains.insert(w.target_chain_id) {
+                warn!(
+                    "Skipping authorized withdrawals for chain {} (not configured on this relayer)",
+                    w.target_chain_id
+                );
+            }
+            false
+        })
+        .collect()
+}
+
+/// Split claimed withdrawals into execution units: chunks of up to
+/// `withdrawal_batch_size` per EVM chain that batches, singles otherwise.
+/// Order within a chain is preserved.
+fn group_into_batches(
+    config: &RelayerConfig,
+    withdrawals: Vec<AuthorizedWithdrawal>,
+) -> Vec<Vec<AuthorizedWithdrawal>> {
+    let mut by_chain: HashMap<u64, Vec<AuthorizedWithdrawal>> = HashMap::new();
+    let mut chain_order = Vec::new();
+    for withdrawal in withdrawals {
+        let chain_id = withdrawal.target_chain_id;
+        by_chain
+            .entry(chain_id)
+            .or_insert_with(|| {
+                chain_order.push(chain_id);
+                Vec::new()
+            })
+            .push(withdrawal);
     }
 
-    /// Update metrics for monitoring
-    async fn update_metrics(&self) {
-        if let Ok(stats) = self.db.get_stats().await {
-            metrics::WITHDRAWALS_EXECUTED.set(stats.withdrawals_executed as i64);
-            metrics::REWARDS_EARNED.set(stats.total_rewards as i64);
-            metrics::STAKE_AMOUNT.set(self.config.staking.current_stake as i64);
-            metrics::SUCCESSFUL_RELAYS.set(stats.successful_relays as i64);
+    let mut batches = Vec::new();
+    for chain_id in chain_order {
+        let withdrawals = by_chain.remove(&chain_id).unwrap_or_default();
+        let batch_size = config
+            .get_chain(chain_id)
+            .filter(|chain| chain.chain_type.is_evm())
+            .map(|chain| chain.withdrawal_batch_size.max(1))
+            .unwrap_or(1);
+
+        let mut withdrawals = withdrawals.into_iter().peekable();
+        while withdrawals.peek().is_some() {
+            batches.push(withdrawals.by_ref().take(batch_size).collect());
         }
     }
+
+    batches
 }
 
-fn init_tracing(verbose: bool) -> Result<()> {
-    let log_level = if verbose {
-        tracing::Level::DEBUG
-    } else {
-        tracing::Level::INFO
-    };
+/// Whether the fee left after paying `gas_cost` meets `min_profit_margin`
+fn is_profitable(fee_earned: u64, gas_cost: u64, min_profit_margin: u64) -> bool {
+    fee_earned
+        .checked_sub(gas_cost)
+        .is_some_and(|profit| profit >= min_profit_margin)
+}
 
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| {
-                    format!("zerobridge_relayer={},tower_http=debug", log_level).into()
-                }),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn withdrawal(id: &str, target_chain_id: u64) -> AuthorizedWithdrawal {
+        AuthorizedWithdrawal {
+            withdrawal_id: id.to_string(),
+            target_chain_id,
+            recipient: "0xrecipient".to_string(),
+            token: "0xtoken".to_string(),
+            amount: 1_000,
+            nullifier: vec![1u8; 32],
+            authorization_signature: vec![2u8; 65],
+            timestamp: 0,
+            relayer_fee: 0,
+            deadline: 0,
+        }
+    }
 
-    Ok(())
-}
\ No newline at end of file
+    #[test]
+    fn test_unconfigured_chain_withdrawals_not_claimed() {
+        let config = RelayerConfig::mock();
+        let mut warned = HashSet::new();
+
+        let supported = filter_supported_withdrawals(
+            &config,
+            vec![withdrawal("w1", 1), withdrawal("w2", 999), withdrawal("w3", 999)],
+            &mut warned,
+        );
+
+        assert_eq!(supported.len(), 1);
+        assert_eq!(supported[0].withdrawal_id, "w1");
+        assert!(warned.contains(&999));
+
+        // Second tick: still filtered, no new warning recorded
+        let supported = filter_supported_withdrawals(
+            &config,
+            vec![withdrawal("w2", 999)],
+            &mut warned,
+        );
+        assert!(supported.is_empty());
+        assert_eq!(warned.len(), 1);
+    }
+
+    #[test]
+    fn test_withdrawals_grouped_into_batches_per_chain() {
+        let mut config = RelayerConfig::mock();
+        config.chains[0].withdrawal_batch_size = 2;
+        let mut solana = config.chains[0].clone();
+        solana.chain_id = 900;
+        solana.chain_type = config::ChainType::Solana;
+        solana.withdrawal_batch_size = 2;
+        config.chains.push(solana);
+
+        let batches = group_into_batches(
+            &config,
+            vec![
+                withdrawal("w1", 1),
+                withdrawal("s1", 900),
+                withdrawal("w2", 1),
+                withdrawal("w3", 1),
+                withdrawal("s2", 900),
+            ],
+        );
+
+        let ids: Vec<Vec<&str>> = batches
+            .iter()
+            .map(|b| b.iter().map(|w| w.withdrawal_id.as_str()).collect())
+            .collect();
+
+        // EVM chain chunked by its batch size; non-EVM always executed singly
+        assert_eq!(ids, vec![vec!["w1", "w2"], vec!["w3"], vec!["s1"], vec!["s2"]]);
+    }
+
+    #[test]
+    fn test_profitability_low_gas() {
+        // 20 gwei * 300k gas = 6e15 wei, at 1e-12 fee units per wei = 6000
+        let gas_cost = transaction_executor::gas_cost_in_fee_units(20_000_000_000, 300_000, 1.0, 1e-12);
+        assert_eq!(gas_cost, 6_000);
+
+        assert!(is_profitable(10_000, gas_cost, 0));
+        assert!(is_profitable(10_000, gas_cost, 4_000));
+        assert!(!is_profitable(10_000, gas_cost, 4_001));
+    }
+
+    #[test]
+    fn test_profitability_high_gas() {
+        // Gas spike to 200 gwei eats the whole fee
+        let gas_cost = transaction_executor::gas_cost_in_fee_units(200_000_000_000, 300_000, 1.0, 1e-12);
+        assert_eq!(gas_cost, 60_000);
+
+        assert!(!is_profitable(10_000, gas_cost, 0));
+        assert!(!is_profitable(gas_cost - 1, gas_cost, 0));
+        assert!(is_profitable(gas_cost, gas_cost, 0));
+    }
+
+    #[tokio::test]
+    async fn test_spawn_bounded_limits_concurrency() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let limit = 3;
+        let limiter = Arc::new(Semaphore::new(limit));
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_seen = Arc::new(AtomicUsize::new(0));
+
+        let mut handles = Vec::new();
+        for _ in 0..20 {
+            let in_flight = in_flight.clone();
+            let max_seen = max_seen.clone();
+            let handle = spawn_bounded(limiter.clone(), async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_seen.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+            })
+            .await
+            .unwrap();
+            handles.push(handle);
+        }
+
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        assert!(max_seen.load(Ordering::SeqCst) <= limit);
+        assert_eq!(max_seen.load(Ordering::SeqCst), limit);
+    }
+}
@@ -1,10 +1,15 @@
 // ============================================
 // relayer/src/metrics.rs
-//! Prometheus metrics
+//! Prometheus metrics and health probes
 
 use anyhow::Result;
+use axum::{http::StatusCode, routing::get, Json, Router};
 use lazy_static::lazy_static;
-use prometheus::{IntGauge, Registry};
+use prometheus::{Encoder, IntGauge, Registry, TextEncoder};
+use tracing::info;
+
+use crate::health::{HealthReport, HealthState};
+use crate::p2p_network::NetworkStats;
 
 lazy_static! {
     pub static ref TASKS_COMPLETED: IntGauge =
@@ -13,9 +18,117 @@ lazy_static! {
         IntGauge::new("rewards_earned", "Total rewards earned").unwrap();
     pub static ref STAKE_AMOUNT: IntGauge =
         IntGauge::new("stake_amount", "Current stake amount").unwrap();
+    static ref REGISTRY: Registry = {
+        let registry = Registry::new();
+        registry.register(Box::new(TASKS_COMPLETED.clone())).unwrap();
+        registry.register(Box::new(REWARDS_EARNED.clone())).unwrap();
+        registry.register(Box::new(STAKE_AMOUNT.clone())).unwrap();
+        registry
+    };
 }
 
-pub async fn start_server(port: u16) -> Result<()> {
-    // Start Prometheus metrics server
+/// Serve `/metrics` (Prometheus), `/health` (liveness), `/ready`
+/// (readiness) and `/p2p/stats` (peers and task claims)
+pub async fn start_server(port: u16, health: HealthState) -> Result<()> {
+    let app = Router::new()
+        .route("/metrics", get(metrics_handler))
+        .route("/health", get(health_handler))
+        .route("/ready", get(ready_handler))
+        .route("/p2p/stats", get(p2p_stats_handler))
+        .with_state(health);
+
+    let addr = format!("0.0.0.0:{}", port);
+    let listener = tokio::net::TcpListener::bind(&addr).await?;
+
+    info!("Metrics server listening on {}", addr);
+
+    axum::serve(listener, app).await?;
+
     Ok(())
-}
\ No newline at end of file
+}
+
+async fn metrics_handler() -> Result<String, StatusCode> {
+    let mut buffer = Vec::new();
+    TextEncoder::new()
+        .encode(&REGISTRY.gather(), &mut buffer)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    String::from_utf8(buffer).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// 503 when no chain listener is running
+async fn health_handler(
+    axum::extract::State(state): axum::extract::State<HealthState>,
+) -> (StatusCode, Json<HealthReport>) {
+    let report = state.report().await;
+    let status = if report.is_live() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (status, Json(report))
+}
+
+/// 503 until the coordinator is reachable and every listener is running
+async fn ready_handler(
+    axum::extract::State(state): axum::extract::State<HealthState>,
+) -> (StatusCode, Json<HealthReport>) {
+    let report = state.report().await;
+    let status = if report.is_ready() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (status, Json(report))
+}
+
+/// Peer counts and current task claims, for debugging claim contention
+async fn p2p_stats_handler(
+    axum::extract::State(state): axum::extract::State<HealthState>,
+) -> Json<NetworkStats> {
+    Json(state.p2p_network.network_stats().await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RelayerConfig;
+    use crate::coordinator_client::CoordinatorClient;
+    use crate::database::RelayerDatabase;
+    use crate::health::ListenerTracker;
+    use crate::p2p_network::P2PNetwork;
+    use crate::stake_manager::StakeManager;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_p2p_stats_reflect_claims() {
+        let config = RelayerConfig::mock();
+        let db = RelayerDatabase::new(":memory:").await.unwrap();
+        let stake_manager = Arc::new(StakeManager::new(config.clone(), db).await.unwrap());
+        let p2p_network = Arc::new(P2PNetwork::new(config.clone(), stake_manager).await.unwrap());
+        let state = HealthState {
+            coordinator: Arc::new(CoordinatorClient::mock("http://127.0.0.1:1")),
+            p2p_network: p2p_network.clone(),
+            listeners: ListenerTracker::default(),
+        };
+
+        let Json(stats) = p2p_stats_handler(axum::extract::State(state.clone())).await;
+        assert_eq!(stats.active_claims, 0);
+        assert!(stats.claims.is_empty());
+
+        p2p_network.broadcast_task_claim("w1").await.unwrap();
+
+        let Json(stats) = p2p_stats_handler(axum::extract::State(state)).await;
+        assert_eq!(stats.active_claims, 1);
+        assert_eq!(stats.bootstrap_peers, config.p2p.bootstrap_peers.len());
+        let claim = &stats.claims[0];
+        assert_eq!(claim.task_id, "w1");
+        assert_eq!(claim.claimed_by, config.relayer_identity.address);
+        assert!(claim.expires_at > claim.claimed_at);
+
+        let body = serde_json::to_value(&stats).unwrap();
+        assert_eq!(body["claims"][0]["task_id"], "w1");
+    }
+}
@@ -2,38 +2,110 @@
 //! P2P gossip network for relayer coordination
 //! FOCUSED: Prevent duplicate work, coordinate task claiming
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::{Address, Signature};
+use serde::Serialize;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::collections::HashMap;
 use tokio::sync::RwLock;
-use tracing::{info, debug};
+use tracing::{info, debug, warn};
 
-use crate::config::RelayerConfig;
+use crate::config::{RelayerConfig, TaskAssignment};
 use crate::stake_manager::StakeManager;
 
 pub struct P2PNetwork {
     config: RelayerConfig,
-    _stake_manager: Arc<StakeManager>,
+    /// Signs the messages that speak for this relayer
+    identity: LocalWallet,
+    stake_manager: Arc<StakeManager>,
     task_claims: Arc<RwLock<HashMap<String, TaskClaim>>>,
+    /// Deposits some relayer already reported to the coordinator, with the
+    /// time we learned of it
+    notified_deposits: Arc<RwLock<HashMap<String, i64>>>,
+    /// Reputation by relayer address; peers not in here have the initial one
+    peer_reputation: Arc<RwLock<HashMap<String, u32>>>,
+    /// Peers by relayer address, as of their last heartbeat
+    peers: Arc<RwLock<HashMap<String, PeerInfo>>>,
+    /// Below `min_peers` as of the last connectivity check
+    partitioned: AtomicBool,
+    /// Bootstrap peer dials made to recover from a partition
+    bootstrap_redials: AtomicU64,
+    /// When each task was first considered, for the assignment grace period
+    task_first_seen: Arc<RwLock<HashMap<String, i64>>>,
 }
 
+/// What a peer's heartbeats told us about it
+#[derive(Debug, Clone)]
+pub struct PeerInfo {
+    /// Stake it announced
+    pub stake: u64,
+    /// Reputation it announced; ours for it is `peer_reputation`
+    pub reputation: u32,
+    pub last_heartbeat: i64,
+    /// Announced stake matched the hub. Unverified peers are evicted: their
+    /// claims are dropped and ignored until they announce a stake that checks
+    /// out.
+    pub verified: bool,
+}
+
+/// How long a deposit notification suppresses re-notifying (seconds)
+const DEPOSIT_NOTIFIED_TTL: i64 = 3600;
+
+/// How long a task's first-seen time is kept (seconds)
+const TASK_SEEN_TTL: i64 = 3600;
+
+/// Reputation every peer starts with, and the most it can have
+pub const INITIAL_PEER_REPUTATION: u32 = 100;
+
+/// Earned when a claimed task is executed
+const CLAIM_EXECUTED_REWARD: u32 = 1;
+
+/// Lost when a claim expires with its task never executed
+const CLAIM_ABANDONED_PENALTY: u32 = 10;
+
+/// Heartbeat intervals a claimer may miss before its claim can be stolen
+const CLAIM_MISSED_HEARTBEATS: i64 = 3;
+
+/// How far ahead of ours a peer's clock may be (seconds)
+const HEARTBEAT_CLOCK_SKEW: i64 = 30;
+
+/// How far (basis points) an announced stake may be from the hub's figure,
+/// which can move between the heartbeat and our check
+const STAKE_TOLERANCE_BPS: u128 = 100;
+
 #[derive(Debug, Clone)]
 struct TaskClaim {
     task_id: String,
     claimed_by: String,
     claimed_at: i64,
     expires_at: i64,
+    /// Last sign of life from the claimer for this task
+    last_heartbeat: i64,
 }
 
 impl P2PNetwork {
     pub async fn new(
-        config: RelayerConfig,
+        mut config: RelayerConfig,
         stake_manager: Arc<StakeManager>,
     ) -> Result<Self> {
+        let identity: LocalWallet = config.relayer_identity.signing_key.parse()
+            .context("Invalid relayer identity signing key")?;
+        // Peers know us by the address our signatures recover to
+        config.relayer_identity.address = format!("{:?}", identity.address());
+        
         Ok(Self {
             config,
-            _stake_manager: stake_manager,
+            identity,
+            stake_manager,
             task_claims: Arc::new(RwLock::new(HashMap::new())),
+            notified_deposits: Arc::new(RwLock::new(HashMap::new())),
+            peer_reputation: Arc::new(RwLock::new(HashMap::new())),
+            peers: Arc::new(RwLock::new(HashMap::new())),
+            partitioned: AtomicBool::new(false),
+            bootstrap_redials: AtomicU64::new(0),
+            task_first_seen: Arc::new(RwLock::new(HashMap::new())),
         })
     }
 
@@ -56,17 +128,99 @@ impl P2PNetwork {
         Ok(())
     }
 
-    /// Send heartbeat to peers
+    /// Send heartbeat to peers, with one per task we hold a claim on so
+    /// peers know we're still working on it
     pub async fn send_heartbeat(&self) -> Result<()> {
         debug!("Sending P2P heartbeat");
         
-        // Broadcast heartbeat message to peers
-        // Contains: relayer ID, stake amount, reputation
+        let address = &self.config.relayer_identity.address;
+        let now = chrono::Utc::now().timestamp();
+        self.gossip_signed(&format!(
+            "HEARTBEAT:{}:{}:{}:{}",
+            address,
+            self.stake_manager.current_stake(),
+            self.peer_reputation(address).await,
+            now
+        ))
+        .await?;
+        
+        let own_claims: Vec<String> = {
+            let mut claims = self.task_claims.write().await;
+            claims
+                .values_mut()
+                .filter(|claim| &claim.claimed_by == address)
+                .map(|claim| {
+                    claim.last_heartbeat = now;
+                    claim.task_id.clone()
+                })
+                .collect()
+        };
+        for task_id in own_claims {
+            self.gossip_signed(&format!("CLAIM_HEARTBEAT:{}:{}", task_id, address)).await?;
+        }
         
         Ok(())
     }
 
-    /// Check if a task is already claimed by another relayer
+    /// Relayers sharing tasks under deterministic assignment: us and every
+    /// connected peer, highest stake first (address breaks ties)
+    async fn active_relayers(&self) -> Vec<String> {
+        let now = chrono::Utc::now().timestamp();
+        let stale_after = self.config.p2p.gossip.heartbeat_interval as i64 * CLAIM_MISSED_HEARTBEATS;
+        
+        let mut relayers: Vec<(u64, String)> = self
+            .peers
+            .read()
+            .await
+            .iter()
+            .filter(|(_, peer)| peer.verified && now - peer.last_heartbeat <= stale_after)
+            .map(|(address, peer)| (peer.stake, address.clone()))
+            .collect();
+        relayers.push((self.stake_manager.current_stake(), self.config.relayer_identity.address.clone()));
+        relayers.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+        
+        relayers.into_iter().map(|(_, address)| address).collect()
+    }
+
+    /// The relayer that should execute `task_id` under deterministic
+    /// assignment: `hash(task_id) % active relayers`
+    pub async fn primary_relayer(&self, task_id: &str) -> String {
+        let relayers = self.active_relayers().await;
+        let hash = ethers::utils::keccak256(task_id.as_bytes());
+        let index = u64::from_be_bytes(hash[..8].try_into().unwrap()) % relayers.len() as u64;
+        relayers[index as usize].clone()
+    }
+
+    /// Whether we may try to claim `task_id` yet. Always, in a free-for-all;
+    /// under deterministic assignment only as its primary, or as a backup
+    /// once the grace period has passed since we first saw it.
+    pub async fn may_claim(&self, task_id: &str) -> bool {
+        if self.config.p2p.task_assignment == TaskAssignment::FreeForAll {
+            return true;
+        }
+        
+        let now = chrono::Utc::now().timestamp();
+        let first_seen = *self
+            .task_first_seen
+            .write()
+            .await
+            .entry(task_id.to_string())
+            .or_insert(now);
+        
+        let primary = self.primary_relayer(task_id).await;
+        if primary == self.config.relayer_identity.address {
+            return true;
+        }
+        if now - first_seen >= self.config.p2p.assignment_grace_period as i64 {
+            debug!("Backing up {} for task {}", primary, task_id);
+            return true;
+        }
+        false
+    }
+
+    /// Check if a task is already claimed by another relayer. Claims by
+    /// peers that have since dropped below the reputation threshold don't
+    /// count.
     pub async fn is_task_claimed(&self, task_id: &str) -> Result<bool> {
         let claims = self.task_claims.read().await;
         
@@ -74,7 +228,7 @@ impl P2PNetwork {
             let now = chrono::Utc::now().timestamp();
             
             // Check if claim is still valid
-            if claim.expires_at > now {
+            if claim.expires_at > now && self.is_trusted(&claim.claimed_by).await {
                 debug!("Task {} already claimed by {}", task_id, claim.claimed_by);
                 return Ok(true);
             }
@@ -94,6 +248,7 @@ impl P2PNetwork {
             claimed_by: self.config.relayer_identity.address.clone(),
             claimed_at: now,
             expires_at,
+            last_heartbeat: now,
         };
         
         // Store locally
@@ -104,11 +259,50 @@ impl P2PNetwork {
         
         // Broadcast to P2P network
         info!("Broadcasting task claim: {}", task_id);
-        self.gossip_message(&format!("CLAIM:{}", task_id)).await?;
+        self.gossip_signed(&format!("CLAIM:{}:{}", task_id, claim.claimed_by)).await?;
         
         Ok(())
     }
 
+    /// Take over a peer's claim whose claimer has stopped heartbeating,
+    /// before the claim expires. `false` if the claim is ours, gone, or
+    /// its claimer is still alive.
+    pub async fn steal_stale_claim(&self, task_id: &str) -> Result<bool> {
+        let address = self.config.relayer_identity.address.clone();
+        let now = chrono::Utc::now().timestamp();
+        
+        let stalled = {
+            let mut claims = self.task_claims.write().await;
+            let Some(claim) = claims.get(task_id) else {
+                return Ok(false);
+            };
+            if claim.claimed_by == address || !self.is_stale(claim, now) {
+                return Ok(false);
+            }
+            let stalled = claim.claimed_by.clone();
+            claims.insert(task_id.to_string(), TaskClaim {
+                task_id: task_id.to_string(),
+                claimed_by: address.clone(),
+                claimed_at: now,
+                expires_at: now + 300,
+                last_heartbeat: now,
+            });
+            stalled
+        };
+        
+        info!("Stealing claim on {} from stalled relayer {}", task_id, stalled);
+        self.penalize_peer(&stalled).await;
+        self.gossip_signed(&format!("STEAL:{}:{}", task_id, address)).await?;
+        
+        Ok(true)
+    }
+
+    /// Whether a claim's holder has missed too many heartbeats
+    fn is_stale(&self, claim: &TaskClaim, now: i64) -> bool {
+        let interval = self.config.p2p.gossip.heartbeat_interval as i64;
+        now - claim.last_heartbeat > interval * CLAIM_MISSED_HEARTBEATS
+    }
+
     /// Broadcast withdrawal execution completion
     /// This notifies other relayers that the task is done
     pub async fn broadcast_withdrawal_execution(
@@ -122,9 +316,9 @@ impl P2PNetwork {
         );
         
         // Remove from claims
-        {
-            let mut claims = self.task_claims.write().await;
-            claims.remove(withdrawal_id);
+        let claim = self.task_claims.write().await.remove(withdrawal_id);
+        if let Some(claim) = claim {
+            self.reward_peer(&claim.claimed_by).await;
         }
         
         // Broadcast to network
@@ -139,20 +333,49 @@ impl P2PNetwork {
     pub async fn broadcast_deposit_notification(&self, deposit_id: &str) -> Result<()> {
         debug!("Broadcasting deposit notification: {}", deposit_id);
         
+        self.mark_deposit_notified(deposit_id).await;
+        
         self.gossip_message(&format!("DEPOSIT_NOTIFIED:{}", deposit_id))
             .await?;
         
         Ok(())
     }
 
-    /// Handle incoming P2P message from another relayer
+    /// Handle incoming P2P message from another relayer. Claims, steals and
+    /// heartbeats end in the signature of the relayer they name and are
+    /// dropped unless it checks out.
     pub async fn handle_incoming_message(&self, message: &str) -> Result<()> {
         debug!("Received P2P message: {}", message);
         
         if message.starts_with("CLAIM:") {
-            // Another relayer claimed a task
-            let task_id = &message[6..];
-            self.handle_claim_message(task_id).await?;
+            // Another relayer claimed a task: CLAIM:<task_id>:<relayer>:<signature>
+            match authenticate(message, "CLAIM:", 2, 1) {
+                Some((fields, peer)) => self.handle_claim_message(fields[0], &peer).await?,
+                None => debug!("Dropping unauthenticated claim: {}", message),
+            }
+        } else if message.starts_with("HEARTBEAT:") {
+            // A peer's liveness: HEARTBEAT:<relayer>:<stake>:<reputation>:<sent_at>:<signature>
+            match authenticate(message, "HEARTBEAT:", 4, 0) {
+                Some((fields, peer)) => match (fields[1].parse(), fields[2].parse(), fields[3].parse()) {
+                    (Ok(stake), Ok(reputation), Ok(sent_at)) => {
+                        self.handle_heartbeat(&peer, stake, reputation, sent_at).await
+                    }
+                    _ => debug!("Malformed heartbeat: {}", message),
+                },
+                None => debug!("Dropping unauthenticated heartbeat: {}", message),
+            }
+        } else if message.starts_with("CLAIM_HEARTBEAT:") {
+            // A claimer is still working: CLAIM_HEARTBEAT:<task_id>:<relayer>:<signature>
+            match authenticate(message, "CLAIM_HEARTBEAT:", 2, 1) {
+                Some((fields, peer)) => self.handle_claim_heartbeat(fields[0], &peer).await,
+                None => debug!("Dropping unauthenticated claim heartbeat: {}", message),
+            }
+        } else if message.starts_with("STEAL:") {
+            // A peer took over a stalled claim: STEAL:<task_id>:<relayer>:<signature>
+            match authenticate(message, "STEAL:", 2, 1) {
+                Some((fields, peer)) => self.handle_steal_message(fields[0], &peer).await?,
+                None => debug!("Dropping unauthenticated steal: {}", message),
+            }
         } else if message.starts_with("EXECUTED:") {
             // Another relayer executed a withdrawal
             let parts: Vec<&str> = message[9..].split(':').collect();
@@ -163,21 +386,135 @@ impl P2PNetwork {
             // Another relayer notified coordinator about deposit
             let deposit_id = &message[17..];
             debug!("Deposit {} already notified by peer", deposit_id);
+            self.mark_deposit_notified(deposit_id).await;
         }
         
         Ok(())
     }
 
+    /// Whether this or a peer relayer has already notified the coordinator
+    pub async fn is_deposit_notified(&self, deposit_id: &str) -> bool {
+        self.notified_deposits.read().await.contains_key(deposit_id)
+    }
+
+    async fn mark_deposit_notified(&self, deposit_id: &str) {
+        let now = chrono::Utc::now().timestamp();
+        self.notified_deposits
+            .write()
+            .await
+            .insert(deposit_id.to_string(), now);
+    }
+
+    /// Current reputation of the relayer at `peer`
+    pub async fn peer_reputation(&self, peer: &str) -> u32 {
+        self.peer_reputation
+            .read()
+            .await
+            .get(peer)
+            .copied()
+            .unwrap_or(INITIAL_PEER_REPUTATION)
+    }
+
+    /// Whether claims from `peer` are honoured: not evicted for its stake,
+    /// and with enough reputation
+    async fn is_trusted(&self, peer: &str) -> bool {
+        let evicted = self
+            .peers
+            .read()
+            .await
+            .get(peer)
+            .map(|info| !info.verified)
+            .unwrap_or(false);
+        !evicted && self.peer_reputation(peer).await >= self.config.p2p.min_peer_reputation
+    }
+
+    /// The peer table entry for `peer`, if it has sent a heartbeat
+    pub async fn peer_info(&self, peer: &str) -> Option<PeerInfo> {
+        self.peers.read().await.get(peer).cloned()
+    }
+
+    /// Record a heartbeat `peer` signed at `sent_at`. The peer is only as
+    /// live as that time, so a captured heartbeat replayed later can't keep
+    /// it in the table; one already stale, or older than the last, is
+    /// ignored. Its stake is checked against the hub whenever the announced
+    /// figure changes; a peer whose stake doesn't check out is evicted.
+    async fn handle_heartbeat(&self, peer: &str, stake: u64, reputation: u32, sent_at: i64) {
+        if peer == self.config.relayer_identity.address {
+            return;
+        }
+        let now = chrono::Utc::now().timestamp();
+        let stale_after = self.config.p2p.gossip.heartbeat_interval as i64 * CLAIM_MISSED_HEARTBEATS;
+        if now - sent_at > stale_after || sent_at > now + HEARTBEAT_CLOCK_SKEW {
+            debug!("Ignoring heartbeat from {} sent at {}", peer, sent_at);
+            return;
+        }
+        
+        let known = self.peers.read().await.get(peer).map(|info| (info.stake, info.verified, info.last_heartbeat));
+        if matches!(known, Some((_, _, last_heartbeat)) if sent_at < last_heartbeat) {
+            debug!("Ignoring out-of-date heartbeat from {}", peer);
+            return;
+        }
+        let verified = match known {
+            Some((known_stake, verified, _)) if known_stake == stake => verified,
+            _ => match self.stake_manager.onchain_stake(peer).await {
+                Ok(onchain) if !stake_matches(stake, onchain) => {
+                    warn!("Evicting peer {}: announced stake {}, hub has {}", peer, stake, onchain);
+                    false
+                }
+                Ok(onchain) if onchain < self.stake_manager.minimum_stake() => {
+                    warn!("Evicting peer {}: stake {} below minimum", peer, onchain);
+                    false
+                }
+                Ok(_) => true,
+                Err(e) => {
+                    warn!("Evicting peer {}: stake unverifiable: {}", peer, e);
+                    false
+                }
+            },
+        };
+        
+        self.peers.write().await.insert(peer.to_string(), PeerInfo {
+            stake,
+            reputation,
+            last_heartbeat: sent_at.min(now),
+            verified,
+        });
+        if !verified {
+            self.task_claims.write().await.retain(|_, claim| claim.claimed_by != peer);
+        }
+    }
+
+    async fn reward_peer(&self, peer: &str) {
+        let mut reputation = self.peer_reputation.write().await;
+        let score = reputation.entry(peer.to_string()).or_insert(INITIAL_PEER_REPUTATION);
+        *score = (*score + CLAIM_EXECUTED_REWARD).min(INITIAL_PEER_REPUTATION);
+    }
+
+    async fn penalize_peer(&self, peer: &str) {
+        let mut reputation = self.peer_reputation.write().await;
+        let score = reputation.entry(peer.to_string()).or_insert(INITIAL_PEER_REPUTATION);
+        *score = score.saturating_sub(CLAIM_ABANDONED_PENALTY);
+        if *score < self.config.p2p.min_peer_reputation {
+            warn!("Ignoring task claims from {}: reputation {}", peer, score);
+        }
+    }
+
     /// Handle claim message from another relayer
-    async fn handle_claim_message(&self, task_id: &str) -> Result<()> {
+    async fn handle_claim_message(&self, task_id: &str, peer: &str) -> Result<()> {
+        if !self.is_trusted(peer).await {
+            debug!("Ignoring claim on {} from low-reputation peer {}", task_id, peer);
+            return Ok(());
+        }
+        
         let now = chrono::Utc::now().timestamp();
         let expires_at = now + 300;
         
         let claim = TaskClaim {
             task_id: task_id.to_string(),
-            claimed_by: "peer".to_string(), // Would be actual peer ID
+            claimed_by: peer.to_string(),
             claimed_at: now,
             expires_at,
+            last_heartbeat: now,
         };
         
         let mut claims = self.task_claims.write().await;
@@ -186,29 +523,100 @@ impl P2PNetwork {
         Ok(())
     }
 
+    async fn handle_claim_heartbeat(&self, task_id: &str, peer: &str) {
+        let mut claims = self.task_claims.write().await;
+        if let Some(claim) = claims.get_mut(task_id) {
+            if claim.claimed_by == peer {
+                claim.last_heartbeat = chrono::Utc::now().timestamp();
+            }
+        }
+    }
+
+    /// Handle a peer stealing a claim. Only a claim we also see as stalled
+    /// can be stolen; a live claimer keeps its task.
+    async fn handle_steal_message(&self, task_id: &str, peer: &str) -> Result<()> {
+        if !self.is_trusted(peer).await {
+            debug!("Ignoring steal of {} by low-reputation peer {}", task_id, peer);
+            return Ok(());
+        }
+        
+        let now = chrono::Utc::now().timestamp();
+        let stalled = {
+            let mut claims = self.task_claims.write().await;
+            let stalled = match claims.get(task_id) {
+                Some(claim) if claim.claimed_by != peer && !self.is_stale(claim, now) => {
+                    debug!("Ignoring steal of {} by {}: {} is still alive",
+                        task_id, peer, claim.claimed_by);
+                    return Ok(());
+                }
+                Some(claim) if claim.claimed_by != peer => Some(claim.claimed_by.clone()),
+                _ => None,
+            };
+            claims.insert(task_id.to_string(), TaskClaim {
+                task_id: task_id.to_string(),
+                claimed_by: peer.to_string(),
+                claimed_at: now,
+                expires_at: now + 300,
+                last_heartbeat: now,
+            });
+            stalled
+        };
+        
+        if let Some(stalled) = stalled {
+            self.penalize_peer(&stalled).await;
+        }
+        
+        Ok(())
+    }
+
     /// Handle execution message from another relayer
     async fn handle_execution_message(
         &self,
         withdrawal_id: &str,
         _tx_hash: &str,
     ) -> Result<()> {
-        // Remove from our claims
-        let mut claims = self.task_claims.write().await;
-        claims.remove(withdrawal_id);
+        // Remove from our claims, crediting whoever claimed it
+        let claim = self.task_claims.write().await.remove(withdrawal_id);
+        if let Some(claim) = claim {
+            self.reward_peer(&claim.claimed_by).await;
+        }
         
         Ok(())
     }
 
-    /// Cleanup expired claims
+    /// Cleanup expired claims. A claim that expires was never executed, so
+    /// its claimant loses reputation.
     pub async fn cleanup_expired_claims(&self) -> Result<()> {
         let now = chrono::Utc::now().timestamp();
         
-        let mut claims = self.task_claims.write().await;
-        claims.retain(|_, claim| claim.expires_at > now);
+        let mut abandoned = Vec::new();
+        self.task_claims.write().await.retain(|_, claim| {
+            let live = claim.expires_at > now;
+            if !live {
+                abandoned.push(claim.claimed_by.clone());
+            }
+            live
+        });
+        for peer in abandoned {
+            self.penalize_peer(&peer).await;
+        }
+        
+        let mut notified = self.notified_deposits.write().await;
+        notified.retain(|_, seen_at| *seen_at + DEPOSIT_NOTIFIED_TTL > now);
+        
+        let mut first_seen = self.task_first_seen.write().await;
+        first_seen.retain(|_, seen_at| *seen_at + TASK_SEEN_TTL > now);
         
         Ok(())
     }
 
+    /// Gossip `message` with our signature appended, for messages peers
+    /// attribute to us
+    async fn gossip_signed(&self, message: &str) -> Result<()> {
+        let signature = self.identity.sign_hash(ethers::utils::hash_message(message))?;
+        self.gossip_message(&format!("{}:{}", message, signature)).await
+    }
+
     /// Gossip message to all peers
     async fn gossip_message(&self, message: &str) -> Result<()> {
         // In production, use libp2p GossipSub to broadcast
@@ -220,27 +628,601 @@ impl P2PNetwork {
         Ok(())
     }
 
-    /// Get current number of connected peers
+    /// Get current number of connected peers: verified peers whose
+    /// heartbeats are still arriving
     pub async fn peer_count(&self) -> usize {
         // In production, query libp2p peer store
-        self.config.p2p.bootstrap_peers.len()
+        let now = chrono::Utc::now().timestamp();
+        let stale_after = self.config.p2p.gossip.heartbeat_interval as i64 * CLAIM_MISSED_HEARTBEATS;
+        self.peers
+            .read()
+            .await
+            .values()
+            .filter(|peer| peer.verified && now - peer.last_heartbeat <= stale_after)
+            .count()
     }
 
-    /// Get network statistics
+    /// Re-dial every bootstrap peer while fewer than `p2p.min_peers` are
+    /// connected, so a relayer whose bootstrap peers were down at startup
+    /// doesn't stay isolated. Returns the number of dials made.
+    pub async fn maintain_connectivity(&self) -> usize {
+        let connected = self.peer_count().await;
+        let minimum = self.config.p2p.min_peers;
+        
+        if connected >= minimum {
+            if self.partitioned.swap(false, Ordering::SeqCst) {
+                info!("P2P network recovered: {} peers connected", connected);
+            }
+            return 0;
+        }
+        
+        let bootstrap_peers = &self.config.p2p.bootstrap_peers;
+        if !self.partitioned.swap(true, Ordering::SeqCst) {
+            warn!(
+                "P2P network partitioned: {} of {} required peers connected; re-dialing {} bootstrap peers",
+                connected, minimum, bootstrap_peers.len()
+            );
+        }
+        
+        let mut dialed = 0;
+        for addr in bootstrap_peers {
+            match self.dial(addr).await {
+                Ok(()) => dialed += 1,
+                Err(e) => debug!("Failed to dial bootstrap peer {}: {}", addr, e),
+            }
+        }
+        self.bootstrap_redials.fetch_add(dialed as u64, Ordering::SeqCst);
+        
+        dialed
+    }
+
+    /// Dial a peer by multiaddr
+    async fn dial(&self, addr: &str) -> Result<()> {
+        // In production, use libp2p: swarm.dial(addr.parse::<Multiaddr>()?)
+        debug!("Dialing {}", addr);
+        
+        Ok(())
+    }
+
+    /// Get network statistics, with the unexpired claims soonest-expiring first
     pub async fn network_stats(&self) -> NetworkStats {
-        let claims = self.task_claims.read().await;
+        let now = chrono::Utc::now().timestamp();
+        let mut claims: Vec<ClaimInfo> = self
+            .task_claims
+            .read()
+            .await
+            .values()
+            .filter(|claim| claim.expires_at > now)
+            .map(|claim| ClaimInfo {
+                task_id: claim.task_id.clone(),
+                claimed_by: claim.claimed_by.clone(),
+                claimed_at: claim.claimed_at,
+                expires_at: claim.expires_at,
+                last_heartbeat_at: claim.last_heartbeat,
+            })
+            .collect();
+        claims.sort_by(|a, b| a.expires_at.cmp(&b.expires_at).then_with(|| a.task_id.cmp(&b.task_id)));
         
         NetworkStats {
             connected_peers: self.peer_count().await,
             active_claims: claims.len(),
             bootstrap_peers: self.config.p2p.bootstrap_peers.len(),
+            bootstrap_redials: self.bootstrap_redials.load(Ordering::SeqCst),
+            partitioned: self.partitioned.load(Ordering::SeqCst),
+            claims,
         }
     }
 }
 
-#[derive(Debug)]
+/// Check a signed message: `prefix`, then `fields` colon-separated fields,
+/// then a signature over everything before it. Returns the fields and the
+/// relayer that signed, provided it is the one named at `sender`.
+fn authenticate<'a>(
+    message: &'a str,
+    prefix: &str,
+    fields: usize,
+    sender: usize,
+) -> Option<(Vec<&'a str>, String)> {
+    let (body, signature) = message.rsplit_once(':')?;
+    let parts: Vec<&str> = body.strip_prefix(prefix)?.split(':').collect();
+    if parts.len() != fields {
+        return None;
+    }
+    
+    let named: Address = parts[sender].parse().ok()?;
+    let signer = signature.parse::<Signature>().ok()?.recover(body).ok()?;
+    (signer == named).then(|| (parts, format!("{:?}", signer)))
+}
+
+/// Whether an announced stake is within tolerance of the hub's figure
+fn stake_matches(announced: u64, onchain: u64) -> bool {
+    (announced.abs_diff(onchain) as u128) * 10_000 <= onchain as u128 * STAKE_TOLERANCE_BPS
+}
+
+#[derive(Debug, Serialize)]
 pub struct NetworkStats {
     pub connected_peers: usize,
     pub active_claims: usize,
     pub bootstrap_peers: usize,
-}
\ No newline at end of file
+    pub bootstrap_redials: u64,
+    pub partitioned: bool,
+    pub claims: Vec<ClaimInfo>,
+}
+
+/// A task claim as reported by `/p2p/stats`
+#[derive(Debug, Serialize)]
+pub struct ClaimInfo {
+    pub task_id: String,
+    pub claimed_by: String,
+    pub claimed_at: i64,
+    pub expires_at: i64,
+    pub last_heartbeat_at: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::RelayerDatabase;
+
+    async fn test_network() -> P2PNetwork {
+        network_with(RelayerConfig::mock()).await
+    }
+
+    async fn network_with(config: RelayerConfig) -> P2PNetwork {
+        let db = RelayerDatabase::new(":memory:").await.unwrap();
+        let stake_manager = Arc::new(StakeManager::new(config.clone(), db).await.unwrap());
+        P2PNetwork::new(config, stake_manager).await.unwrap()
+    }
+
+    fn relayer_key(seed: u8) -> String {
+        format!("{:02x}", seed).repeat(32)
+    }
+
+    /// A peer relayer's identity
+    fn relayer(seed: u8) -> LocalWallet {
+        relayer_key(seed).parse().unwrap()
+    }
+
+    fn address(relayer: &LocalWallet) -> String {
+        format!("{:?}", relayer.address())
+    }
+
+    /// `message` with `relayer`'s signature, as it would gossip it
+    fn signed(relayer: &LocalWallet, message: &str) -> String {
+        let signature = relayer.sign_hash(ethers::utils::hash_message(message)).unwrap();
+        format!("{}:{}", message, signature)
+    }
+
+    fn claim(relayer: &LocalWallet, task_id: &str) -> String {
+        signed(relayer, &format!("CLAIM:{}:{}", task_id, address(relayer)))
+    }
+
+    fn claim_heartbeat(relayer: &LocalWallet, task_id: &str) -> String {
+        signed(relayer, &format!("CLAIM_HEARTBEAT:{}:{}", task_id, address(relayer)))
+    }
+
+    fn steal(relayer: &LocalWallet, task_id: &str) -> String {
+        signed(relayer, &format!("STEAL:{}:{}", task_id, address(relayer)))
+    }
+
+    fn heartbeat(relayer: &LocalWallet, stake: &str, reputation: u32) -> String {
+        heartbeat_at(relayer, stake, reputation, chrono::Utc::now().timestamp())
+    }
+
+    fn heartbeat_at(relayer: &LocalWallet, stake: &str, reputation: u32, sent_at: i64) -> String {
+        signed(relayer, &format!("HEARTBEAT:{}:{}:{}:{}", address(relayer), stake, reputation, sent_at))
+    }
+
+    const STAKED_PEER: u8 = 0xaa;
+
+    fn staked_peer() -> LocalWallet {
+        relayer(STAKED_PEER)
+    }
+
+    fn understaked_peer() -> LocalWallet {
+        relayer(0xbb)
+    }
+
+    /// Hub chain stand-in answering `stakeOf` from `stakes`, counting calls
+    async fn mock_hub(stakes: HashMap<String, u64>, calls: Arc<std::sync::atomic::AtomicUsize>) -> String {
+        use axum::{routing::post, Json, Router};
+        use serde_json::{json, Value};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route(
+            "/",
+            post(move |Json(request): Json<Value>| {
+                assert_eq!(request["method"], "eth_call");
+                calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                let call = &request["params"][0];
+                let data = call["data"].as_str().or(call["input"].as_str()).unwrap();
+                let relayer = format!("0x{}", &data[data.len() - 40..]);
+                let stake = stakes.get(&relayer).copied().unwrap_or(0);
+                let result = format!("0x{:064x}", stake);
+                async move { Json(json!({ "jsonrpc": "2.0", "id": request["id"], "result": result })) }
+            }),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    async fn network_with_hub(calls: Arc<std::sync::atomic::AtomicUsize>) -> P2PNetwork {
+        let stakes = HashMap::from([
+            (address(&staked_peer()), 1_000),
+            (address(&understaked_peer()), 50),
+        ]);
+        let mut config = RelayerConfig::mock();
+        config.chains[0].rpc_url = mock_hub(stakes, calls).await;
+        config.staking.hub_contract = format!("0x{}", "78".repeat(20));
+        network_with(config).await
+    }
+
+    #[tokio::test]
+    async fn test_peer_deposit_notification_suppresses_duplicates() {
+        let network = test_network().await;
+        assert!(!network.is_deposit_notified("d1").await);
+
+        network.handle_incoming_message("DEPOSIT_NOTIFIED:d1").await.unwrap();
+        assert!(network.is_deposit_notified("d1").await);
+
+        // Our own broadcast marks it too
+        network.broadcast_deposit_notification("d2").await.unwrap();
+        assert!(network.is_deposit_notified("d2").await);
+        assert!(!network.is_deposit_notified("d3").await);
+    }
+
+    async fn expire_claims(network: &P2PNetwork) {
+        let now = chrono::Utc::now().timestamp();
+        for claim in network.task_claims.write().await.values_mut() {
+            claim.expires_at = now - 1;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reputation_follows_claim_outcomes() {
+        let network = test_network().await;
+        let peer = relayer(1);
+        assert_eq!(network.peer_reputation(&address(&peer)).await, INITIAL_PEER_REPUTATION);
+
+        // A claim left to expire costs reputation
+        network.handle_incoming_message(&claim(&peer, "w1")).await.unwrap();
+        assert!(network.is_task_claimed("w1").await.unwrap());
+        expire_claims(&network).await;
+        network.cleanup_expired_claims().await.unwrap();
+        assert_eq!(
+            network.peer_reputation(&address(&peer)).await,
+            INITIAL_PEER_REPUTATION - CLAIM_ABANDONED_PENALTY
+        );
+
+        // Executing a claimed task earns it back, up to the initial score
+        network.handle_incoming_message(&claim(&peer, "w2")).await.unwrap();
+        network.handle_incoming_message("EXECUTED:w2:0xtx").await.unwrap();
+        assert_eq!(
+            network.peer_reputation(&address(&peer)).await,
+            INITIAL_PEER_REPUTATION - CLAIM_ABANDONED_PENALTY + CLAIM_EXECUTED_REWARD
+        );
+        assert!(!network.is_task_claimed("w2").await.unwrap());
+
+        // Our own claims are scored the same way
+        let own = network.config.relayer_identity.address.clone();
+        network.broadcast_task_claim("w3").await.unwrap();
+        network.broadcast_withdrawal_execution("w3", "0xtx").await.unwrap();
+        assert_eq!(network.peer_reputation(&own).await, INITIAL_PEER_REPUTATION);
+    }
+
+    #[tokio::test]
+    async fn test_low_reputation_claims_ignored() {
+        let network = test_network().await;
+        let threshold = network.config.p2p.min_peer_reputation;
+        let (peer, other) = (relayer(1), relayer(2));
+
+        // Abandon claims until the peer drops below the threshold
+        let mut abandoned = 0;
+        while network.peer_reputation(&address(&peer)).await >= threshold {
+            network.handle_incoming_message(&claim(&peer, &format!("w{}", abandoned))).await.unwrap();
+            expire_claims(&network).await;
+            network.cleanup_expired_claims().await.unwrap();
+            abandoned += 1;
+        }
+        assert!(abandoned > 1);
+
+        // Its new claims no longer block the task
+        network.handle_incoming_message(&claim(&peer, "w-next")).await.unwrap();
+        assert!(!network.is_task_claimed("w-next").await.unwrap());
+
+        // Other peers are unaffected
+        network.handle_incoming_message(&claim(&other, "w-next")).await.unwrap();
+        assert!(network.is_task_claimed("w-next").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_claims_in_another_relayers_name_dropped() {
+        let network = test_network().await;
+        let (peer, forger) = (relayer(1), relayer(2));
+        // `message` naming `sender`, but signed by the forger
+        let forged = |message: &str, sender: &LocalWallet| {
+            signed(&forger, &format!("{}:{}", message, address(sender)))
+        };
+
+        // Unsigned, signed by someone else, or altered after signing
+        network.handle_incoming_message(&format!("CLAIM:w1:{}", address(&peer))).await.unwrap();
+        network.handle_incoming_message(&forged("CLAIM:w1", &peer)).await.unwrap();
+        network.handle_incoming_message(&claim(&peer, "w0").replacen("w0", "w1", 1)).await.unwrap();
+        assert!(!network.is_task_claimed("w1").await.unwrap());
+
+        // The real claim stands, and a forged steal can't move it or cost
+        // the claimer reputation
+        network.handle_incoming_message(&claim(&peer, "w1")).await.unwrap();
+        stall_claimers(&network).await;
+        network.handle_incoming_message(&forged("STEAL:w1", &relayer(3))).await.unwrap();
+        assert_eq!(claimant(&network, "w1").await, address(&peer));
+        assert_eq!(network.peer_reputation(&address(&peer)).await, INITIAL_PEER_REPUTATION);
+
+        // Nor can a forged claim heartbeat keep a stalled claim alive
+        network.handle_incoming_message(&forged("CLAIM_HEARTBEAT:w1", &peer)).await.unwrap();
+        network.handle_incoming_message(&steal(&forger, "w1")).await.unwrap();
+        assert_eq!(claimant(&network, "w1").await, address(&forger));
+    }
+
+    /// Backdate every claim's last heartbeat past the stale threshold
+    async fn stall_claimers(network: &P2PNetwork) {
+        let stale_after = network.config.p2p.gossip.heartbeat_interval as i64 * CLAIM_MISSED_HEARTBEATS;
+        for claim in network.task_claims.write().await.values_mut() {
+            claim.last_heartbeat -= stale_after + 1;
+        }
+    }
+
+    async fn claimant(network: &P2PNetwork, task_id: &str) -> String {
+        network.task_claims.read().await[task_id].claimed_by.clone()
+    }
+
+    #[tokio::test]
+    async fn test_stalled_claim_is_stolen() {
+        let network = test_network().await;
+        let own = network.config.relayer_identity.address.clone();
+        let (peer, other) = (relayer(1), relayer(2));
+
+        // A live claimer keeps its task, heartbeats included
+        network.handle_incoming_message(&claim(&peer, "w1")).await.unwrap();
+        assert!(!network.steal_stale_claim("w1").await.unwrap());
+        stall_claimers(&network).await;
+        network.handle_incoming_message(&claim_heartbeat(&peer, "w1")).await.unwrap();
+        assert!(!network.steal_stale_claim("w1").await.unwrap());
+
+        // Once it stops heartbeating the claim is taken over before it expires
+        stall_claimers(&network).await;
+        assert!(network.steal_stale_claim("w1").await.unwrap());
+        assert_eq!(claimant(&network, "w1").await, own);
+        assert!(network.is_task_claimed("w1").await.unwrap());
+        assert_eq!(
+            network.peer_reputation(&address(&peer)).await,
+            INITIAL_PEER_REPUTATION - CLAIM_ABANDONED_PENALTY
+        );
+
+        // Our own claims are never stolen from ourselves, and our heartbeat
+        // keeps them live for peers
+        stall_claimers(&network).await;
+        assert!(!network.steal_stale_claim("w1").await.unwrap());
+        network.send_heartbeat().await.unwrap();
+        network.handle_incoming_message(&steal(&other, "w1")).await.unwrap();
+        assert_eq!(claimant(&network, "w1").await, own);
+    }
+
+    #[tokio::test]
+    async fn test_peer_steal_supersedes_only_stalled_claims() {
+        let network = test_network().await;
+        let (peer, other) = (relayer(1), relayer(2));
+        network.handle_incoming_message(&claim(&peer, "w1")).await.unwrap();
+
+        network.handle_incoming_message(&steal(&other, "w1")).await.unwrap();
+        assert_eq!(claimant(&network, "w1").await, address(&peer));
+
+        stall_claimers(&network).await;
+        network.handle_incoming_message(&steal(&other, "w1")).await.unwrap();
+        assert_eq!(claimant(&network, "w1").await, address(&other));
+        assert!(network.is_task_claimed("w1").await.unwrap());
+
+        // The stalled claimer's late heartbeat doesn't win it back
+        network.handle_incoming_message(&claim_heartbeat(&peer, "w1")).await.unwrap();
+        assert_eq!(claimant(&network, "w1").await, address(&other));
+    }
+
+    #[tokio::test]
+    async fn test_heartbeats_update_peer_table() {
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let network = network_with_hub(calls.clone()).await;
+        let staked = staked_peer();
+        assert!(network.peer_info(&address(&staked)).await.is_none());
+
+        // Within tolerance of the hub's 1000
+        network.handle_incoming_message(&heartbeat(&staked, "1005", 90)).await.unwrap();
+        let peer = network.peer_info(&address(&staked)).await.unwrap();
+        assert!(peer.verified);
+        assert_eq!((peer.stake, peer.reputation), (1005, 90));
+
+        // Same stake again: refreshed without another hub lookup
+        network.handle_incoming_message(&heartbeat(&staked, "1005", 95)).await.unwrap();
+        assert_eq!(network.peer_info(&address(&staked)).await.unwrap().reputation, 95);
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        // Our own heartbeats and malformed ones don't touch the table
+        let own = network.config.relayer_identity.address.clone();
+        network.handle_incoming_message(&heartbeat(&network.identity, "150", 100)).await.unwrap();
+        let peer = relayer(1);
+        network.handle_incoming_message(&heartbeat(&peer, "lots", 100)).await.unwrap();
+        assert!(network.peer_info(&own).await.is_none());
+        assert!(network.peer_info(&address(&peer)).await.is_none());
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_spoofed_heartbeats_rejected() {
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let network = network_with_hub(calls.clone()).await;
+        let (staked, forger) = (staked_peer(), relayer(1));
+        let now = chrono::Utc::now().timestamp();
+
+        // A staked relayer's heartbeat, unsigned or signed by someone else
+        let body = format!("HEARTBEAT:{}:1000:100:{}", address(&staked), now);
+        network.handle_incoming_message(&body).await.unwrap();
+        network.handle_incoming_message(&signed(&forger, &body)).await.unwrap();
+        assert!(network.peer_info(&address(&staked)).await.is_none());
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+        // Its real heartbeat, captured and replayed later, is no newer than
+        // when it was signed
+        let captured = heartbeat_at(&staked, "1000", 90, now - 10);
+        network.handle_incoming_message(&captured).await.unwrap();
+        network.handle_incoming_message(&heartbeat_at(&staked, "1000", 95, now)).await.unwrap();
+        network.handle_incoming_message(&captured).await.unwrap();
+        let peer = network.peer_info(&address(&staked)).await.unwrap();
+        assert_eq!((peer.reputation, peer.last_heartbeat), (95, now));
+
+        // Nor does one replayed after going stale bring back a quiet peer
+        let stale_after = network.config.p2p.gossip.heartbeat_interval as i64 * CLAIM_MISSED_HEARTBEATS;
+        let understaked = understaked_peer();
+        network.handle_incoming_message(&heartbeat_at(&understaked, "50", 100, now - stale_after - 1)).await.unwrap();
+        assert!(network.peer_info(&address(&understaked)).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unverifiable_stake_evicts_peer() {
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let network = network_with_hub(calls).await;
+        let (staked, understaked) = (staked_peer(), understaked_peer());
+
+        network.handle_incoming_message(&heartbeat(&staked, "1000", 100)).await.unwrap();
+        network.handle_incoming_message(&claim(&staked, "w1")).await.unwrap();
+        assert!(network.is_task_claimed("w1").await.unwrap());
+
+        // Announcing more stake than the hub holds evicts it and its claims
+        network.handle_incoming_message(&heartbeat(&staked, "5000", 100)).await.unwrap();
+        assert!(!network.peer_info(&address(&staked)).await.unwrap().verified);
+        assert!(!network.is_task_claimed("w1").await.unwrap());
+        network.handle_incoming_message(&claim(&staked, "w2")).await.unwrap();
+        assert!(!network.is_task_claimed("w2").await.unwrap());
+
+        // So do an honest stake below the minimum and a relayer the hub has
+        // never seen
+        network.handle_incoming_message(&heartbeat(&understaked, "50", 100)).await.unwrap();
+        assert!(!network.peer_info(&address(&understaked)).await.unwrap().verified);
+        let unknown = relayer(1);
+        network.handle_incoming_message(&heartbeat(&unknown, "1000", 100)).await.unwrap();
+        assert!(!network.peer_info(&address(&unknown)).await.unwrap().verified);
+
+        // Announcing the real figure again restores it
+        network.handle_incoming_message(&heartbeat(&staked, "1000", 100)).await.unwrap();
+        assert!(network.peer_info(&address(&staked)).await.unwrap().verified);
+        network.handle_incoming_message(&claim(&staked, "w2")).await.unwrap();
+        assert!(network.is_task_claimed("w2").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_redials_bootstrap_peers_below_minimum() {
+        let mut config = RelayerConfig::mock();
+        config.p2p.min_peers = 1;
+        config.p2p.bootstrap_peers = vec![
+            "/ip4/10.0.0.1/tcp/9000/p2p/12D3KooWBoot1".to_string(),
+            "/ip4/10.0.0.2/tcp/9000/p2p/12D3KooWBoot2".to_string(),
+        ];
+        let network = network_with(config).await;
+
+        // Isolated: every bootstrap peer is re-dialed on each check
+        assert_eq!(network.peer_count().await, 0);
+        assert_eq!(network.maintain_connectivity().await, 2);
+        assert_eq!(network.maintain_connectivity().await, 2);
+        let stats = network.network_stats().await;
+        assert!(stats.partitioned);
+        assert_eq!(stats.bootstrap_redials, 4);
+
+        // A live verified peer brings it back to the minimum
+        let now = chrono::Utc::now().timestamp();
+        let staked = address(&staked_peer());
+        network.peers.write().await.insert(staked.clone(), PeerInfo {
+            stake: 1_000,
+            reputation: 100,
+            last_heartbeat: now,
+            verified: true,
+        });
+        assert_eq!(network.peer_count().await, 1);
+        assert_eq!(network.maintain_connectivity().await, 0);
+        assert!(!network.network_stats().await.partitioned);
+
+        // Peers gone quiet or evicted don't count
+        network.peers.write().await.get_mut(&staked).unwrap().last_heartbeat = now - 3_600;
+        assert_eq!(network.peer_count().await, 0);
+        assert_eq!(network.maintain_connectivity().await, 2);
+    }
+
+    /// Two relayers, the mock one (stake 150) and `staked_peer` (stake
+    /// 1000), each as seen from the other
+    async fn assigned_pair() -> (P2PNetwork, P2PNetwork) {
+        let mut config = RelayerConfig::mock();
+        config.p2p.task_assignment = TaskAssignment::Deterministic;
+        let ours = network_with(config.clone()).await;
+
+        let staked = staked_peer();
+        config.relayer_identity.address = address(&staked);
+        config.relayer_identity.signing_key = relayer_key(STAKED_PEER);
+        config.staking.current_stake = 1_000;
+        let theirs = network_with(config).await;
+
+        let now = chrono::Utc::now().timestamp();
+        let mock_relayer = ours.config.relayer_identity.address.clone();
+        for (network, peer, stake) in [(&ours, address(&staked), 1_000), (&theirs, mock_relayer, 150)] {
+            network.peers.write().await.insert(peer, PeerInfo {
+                stake,
+                reputation: 100,
+                last_heartbeat: now,
+                verified: true,
+            });
+        }
+        (ours, theirs)
+    }
+
+    #[tokio::test]
+    async fn test_deterministic_primary() {
+        let (ours, theirs) = assigned_pair().await;
+
+        let mut primaries = std::collections::HashSet::new();
+        for i in 0..16 {
+            let task_id = format!("w{}", i);
+            let primary = ours.primary_relayer(&task_id).await;
+            // Both sides agree, and asking again gives the same answer
+            assert_eq!(primary, theirs.primary_relayer(&task_id).await);
+            assert_eq!(primary, ours.primary_relayer(&task_id).await);
+
+            // Exactly one of them goes first
+            assert_ne!(ours.may_claim(&task_id).await, theirs.may_claim(&task_id).await);
+            primaries.insert(primary);
+        }
+        // Work is spread over both
+        assert_eq!(primaries.len(), 2);
+
+        // Free-for-all lets anyone claim
+        let network = test_network().await;
+        assert!(network.may_claim("w0").await);
+    }
+
+    #[tokio::test]
+    async fn test_backup_claims_after_grace_period() {
+        let (ours, theirs) = assigned_pair().await;
+        let mut task_id = String::new();
+        for i in 0.. {
+            task_id = format!("w{}", i);
+            if ours.primary_relayer(&task_id).await == address(&staked_peer()) {
+                break;
+            }
+        }
+
+        // The primary can claim at once; we wait
+        assert!(theirs.may_claim(&task_id).await);
+        assert!(!ours.may_claim(&task_id).await);
+
+        // It never did, so once the grace period is up we step in
+        let grace = ours.config.p2p.assignment_grace_period as i64;
+        *ours.task_first_seen.write().await.get_mut(&task_id).unwrap() -= grace;
+        assert!(ours.may_claim(&task_id).await);
+    }
+}
@@ -0,0 +1,293 @@
+// relayer/src/solana_gateway.rs
+//! Typed client for the Solana gateway program
+//! Mirrors the instructions and account contexts in
+//! `solana_gateway/programs/solana_gateway/src/lib.rs`, so discriminators,
+//! Borsh layouts and PDA seeds live in one place
+
+use anyhow::Result;
+use solana_sdk::hash::hash;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+
+/// Associated token account program (owns recipients' token accounts)
+const SPL_ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+
+/// Anchor instruction discriminator: first 8 bytes of sha256("global:<name>")
+pub fn discriminator(name: &str) -> [u8; 8] {
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash(format!("global:{}", name).as_bytes()).to_bytes()[..8]);
+    discriminator
+}
+
+// ============ PDAs ============
+
+pub fn gateway_pda(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"gateway"], program_id).0
+}
+
+pub fn deposit_pda(program_id: &Pubkey, deposit_count: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"deposit", &deposit_count.to_le_bytes()], program_id).0
+}
+
+pub fn withdrawal_request_pda(program_id: &Pubkey, withdrawal_id: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(&[b"withdrawal_request", withdrawal_id], program_id).0
+}
+
+pub fn nullifier_pda(program_id: &Pubkey, nullifier: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(&[b"nullifier_check", nullifier], program_id).0
+}
+
+pub fn vault_pda(program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault", mint.as_ref()], program_id).0
+}
+
+pub fn mint_state_pda(program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"mint_state", mint.as_ref()], program_id).0
+}
+
+/// Associated token account of `owner` for `mint` under `token_program`
+/// (SPL Token or Token-2022)
+pub fn associated_token_address(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    let ata_program: Pubkey = SPL_ASSOCIATED_TOKEN_PROGRAM_ID.parse().unwrap();
+    Pubkey::find_program_address(
+        &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &ata_program,
+    )
+    .0
+}
+
+// ============ Instruction Args ============
+
+/// `execute_withdrawal(withdrawal_id: [u8; 32], deadline: i64, coordinator_signature: [u8; 65])`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecuteWithdrawalArgs {
+    pub withdrawal_id: [u8; 32],
+    pub deadline: i64,
+    pub coordinator_signature: [u8; 65],
+}
+
+impl ExecuteWithdrawalArgs {
+    const NAME: &'static str = "execute_withdrawal";
+
+    /// Discriminator followed by the Borsh args (fixed arrays are raw bytes)
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = discriminator(Self::NAME).to_vec();
+        data.extend_from_slice(&self.withdrawal_id);
+        data.extend_from_slice(&self.deadline.to_le_bytes());
+        data.extend_from_slice(&self.coordinator_signature);
+        data
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let mut reader = ArgReader::new(Self::NAME, data)?;
+        let args = Self {
+            withdrawal_id: reader.array()?,
+            deadline: i64::from_le_bytes(reader.array()?),
+            coordinator_signature: reader.array()?,
+        };
+        reader.finish()?;
+        Ok(args)
+    }
+}
+
+/// `deposit(amount, target_chain_id, recipient, zcash_address)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepositArgs {
+    pub amount: u64,
+    pub target_chain_id: u64,
+    pub recipient: [u8; 32],
+    pub zcash_address: [u8; 32],
+}
+
+impl DepositArgs {
+    const NAME: &'static str = "deposit";
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = discriminator(Self::NAME).to_vec();
+        data.extend_from_slice(&self.amount.to_le_bytes());
+        data.extend_from_slice(&self.target_chain_id.to_le_bytes());
+        data.extend_from_slice(&self.recipient);
+        data.extend_from_slice(&self.zcash_address);
+        data
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let mut reader = ArgReader::new(Self::NAME, data)?;
+        let args = Self {
+            amount: u64::from_le_bytes(reader.array()?),
+            target_chain_id: u64::from_le_bytes(reader.array()?),
+            recipient: reader.array()?,
+            zcash_address: reader.array()?,
+        };
+        reader.finish()?;
+        Ok(args)
+    }
+}
+
+/// Cursor over instruction data that checks the discriminator up front
+struct ArgReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> ArgReader<'a> {
+    fn new(name: &str, data: &'a [u8]) -> Result<Self> {
+        if data.len() < 8 || data[..8] != discriminator(name) {
+            anyhow::bail!("Instruction data is not `{}`", name);
+        }
+        Ok(Self { data: &data[8..] })
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        if self.data.len() < N {
+            anyhow::bail!("Instruction data truncated");
+        }
+        let (head, rest) = self.data.split_at(N);
+        self.data = rest;
+        Ok(head.try_into()?)
+    }
+
+    fn finish(self) -> Result<()> {
+        if !self.data.is_empty() {
+            anyhow::bail!("{} trailing bytes in instruction data", self.data.len());
+        }
+        Ok(())
+    }
+}
+
+// ============ Client ============
+
+/// Builds checked instructions for one deployment of the gateway program
+#[derive(Debug, Clone, Copy)]
+pub struct SolanaGatewayClient {
+    pub program_id: Pubkey,
+}
+
+impl SolanaGatewayClient {
+    pub fn new(program_id: Pubkey) -> Self {
+        Self { program_id }
+    }
+
+    /// `execute_withdrawal`, paying out to the recipient's associated token
+    /// account. Accounts follow the program's `ExecuteWithdrawal` order.
+    pub fn execute_withdrawal(
+        &self,
+        executor: &Pubkey,
+        recipient: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        nullifier: &[u8; 32],
+        args: &ExecuteWithdrawalArgs,
+    ) -> Instruction {
+        let program_id = &self.program_id;
+
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(gateway_pda(program_id), false),
+                AccountMeta::new(withdrawal_request_pda(program_id, &args.withdrawal_id), false),
+                AccountMeta::new(nullifier_pda(program_id, nullifier), false),
+                AccountMeta::new(*executor, true),
+                AccountMeta::new(*recipient, false),
+                AccountMeta::new_readonly(*mint, false),
+                AccountMeta::new(vault_pda(program_id, mint), false),
+                AccountMeta::new(associated_token_address(recipient, mint, token_program), false),
+                AccountMeta::new_readonly(*token_program, false),
+                AccountMeta::new(mint_state_pda(program_id, mint), false),
+            ],
+            data: args.encode(),
+        }
+    }
+
+    /// `deposit` from the user's associated token account. `deposit_count`
+    /// is the gateway's current count, which seeds the new deposit account.
+    pub fn deposit(
+        &self,
+        user: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        deposit_count: u64,
+        args: &DepositArgs,
+    ) -> Instruction {
+        let program_id = &self.program_id;
+
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(gateway_pda(program_id), false),
+                AccountMeta::new(deposit_pda(program_id, deposit_count), false),
+                AccountMeta::new(*user, true),
+                AccountMeta::new_readonly(*mint, false),
+                AccountMeta::new(associated_token_address(user, mint, token_program), false),
+                AccountMeta::new(vault_pda(program_id, mint), false),
+                AccountMeta::new_readonly(*token_program, false),
+                AccountMeta::new_readonly(solana_sdk::system_program::id(), false),
+                AccountMeta::new(mint_state_pda(program_id, mint), false),
+            ],
+            data: args.encode(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_execute_withdrawal_roundtrip() {
+        let client = SolanaGatewayClient::new(Pubkey::new_unique());
+        let executor = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let token_program = Pubkey::new_unique();
+
+        let args = ExecuteWithdrawalArgs {
+            withdrawal_id: [1; 32],
+            deadline: 1_700_003_600,
+            coordinator_signature: [3; 65],
+        };
+        let ix = client.execute_withdrawal(&executor, &recipient, &mint, &token_program, &[2; 32], &args);
+
+        // sha256("global:execute_withdrawal")[..8], then the Borsh args
+        assert_eq!(&ix.data[..8], &hash(b"global:execute_withdrawal").to_bytes()[..8]);
+        assert_eq!(ix.data.len(), 8 + 32 + 8 + 65);
+        assert_eq!(ExecuteWithdrawalArgs::decode(&ix.data).unwrap(), args);
+
+        let program_id = &client.program_id;
+        let keys: Vec<Pubkey> = ix.accounts.iter().map(|a| a.pubkey).collect();
+        assert_eq!(keys[0], gateway_pda(program_id));
+        assert_eq!(keys[1], withdrawal_request_pda(program_id, &[1; 32]));
+        assert_eq!(keys[2], nullifier_pda(program_id, &[2; 32]));
+        assert_eq!(keys[3], executor);
+        assert_eq!(keys[4], recipient);
+        assert_eq!(keys[5], mint);
+        assert_eq!(keys[6], vault_pda(program_id, &mint));
+        assert_eq!(keys[7], associated_token_address(&recipient, &mint, &token_program));
+        assert_eq!(keys[8], token_program);
+        assert_eq!(keys[9], mint_state_pda(program_id, &mint));
+
+        // Only the executor signs; mint and token program are read-only
+        let signers: Vec<bool> = ix.accounts.iter().map(|a| a.is_signer).collect();
+        assert_eq!(signers, vec![false, false, false, true, false, false, false, false, false, false]);
+        assert!(!ix.accounts[5].is_writable && !ix.accounts[8].is_writable);
+    }
+
+    #[test]
+    fn test_decode_rejects_other_instructions() {
+        let deposit = DepositArgs {
+            amount: 5_000_000,
+            target_chain_id: 8453,
+            recipient: [7; 32],
+            zcash_address: [9; 32],
+        };
+        let data = deposit.encode();
+
+        assert_eq!(DepositArgs::decode(&data).unwrap(), deposit);
+        assert!(ExecuteWithdrawalArgs::decode(&data).is_err());
+
+        // Truncated and padded payloads are rejected
+        assert!(DepositArgs::decode(&data[..data.len() - 1]).is_err());
+        let mut padded = data.clone();
+        padded.push(0);
+        assert!(DepositArgs::decode(&padded).is_err());
+    }
+}
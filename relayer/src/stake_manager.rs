@@ -2,12 +2,15 @@
 // relayer/src/stake_manager.rs
 //! Manage relayer stake
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use tracing::info;
 
 use crate::config::RelayerConfig;
 use crate::database::RelayerDatabase;
 
+/// Hub view of a relayer's stake
+const STAKE_OF_SIG: &str = "stakeOf(address)";
+
 pub struct StakeManager {
     config: RelayerConfig,
     _db: RelayerDatabase,
@@ -25,6 +28,43 @@ impl StakeManager {
         Ok(())
     }
 
+    /// Our stake, as announced to peers
+    pub fn current_stake(&self) -> u64 {
+        self.config.staking.current_stake
+    }
+
+    /// Minimum stake an active relayer must hold
+    pub fn minimum_stake(&self) -> u64 {
+        self.config.staking.minimum_stake
+    }
+
+    /// Stake the hub contract holds for `relayer`, for checking what peers
+    /// announce
+    pub async fn onchain_stake(&self, relayer: &str) -> Result<u64> {
+        use ethers::abi::{decode, encode, ParamType, Token};
+        use ethers::prelude::*;
+
+        let hub_chain = self
+            .config
+            .get_chain(self.config.staking.hub_chain_id)
+            .with_context(|| format!("Hub chain {} not configured", self.config.staking.hub_chain_id))?;
+        let hub: Address = self.config.staking.hub_contract.parse().context("Invalid hub contract address")?;
+        let relayer: Address = relayer.parse().with_context(|| format!("Invalid relayer address {}", relayer))?;
+
+        let mut call_data = ethers::utils::id(STAKE_OF_SIG).to_vec();
+        call_data.extend(encode(&[Token::Address(relayer)]));
+        let call: TypedTransaction = TransactionRequest::new().to(hub).data(call_data).into();
+
+        let provider = Provider::<Http>::try_from(&hub_chain.rpc_url)?;
+        let output = provider.call(&call, None).await?;
+        let stake = decode(&[ParamType::Uint(256)], &output)?
+            .pop()
+            .and_then(Token::into_uint)
+            .context("Malformed stakeOf result")?;
+
+        Ok(stake.min(U256::from(u64::MAX)).as_u64())
+    }
+
     pub async fn get_pending_rewards(&self) -> Result<u64> {
         // Query hub contract for rewards
         Ok(0)
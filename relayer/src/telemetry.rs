@@ -0,0 +1,153 @@
+// relayer/src/telemetry.rs
+//! Logging, plus optional OpenTelemetry trace export
+//!
+//! Spans always go to the fmt subscriber. With `OTEL_EXPORTER_OTLP_ENDPOINT`
+//! set they are also exported over OTLP. Requests to the coordinator carry
+//! the current trace in a W3C `traceparent` header, so its spans for the same
+//! deposit or withdrawal land in the relayer's trace.
+
+use anyhow::Result;
+use opentelemetry::{global, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{propagation::TraceContextPropagator, trace, Resource};
+use std::collections::HashMap;
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+/// Name spans are exported under
+const SERVICE_NAME: &str = "zerobridge-relayer";
+
+/// OTLP collector endpoint (e.g. `http://localhost:4317`); unset disables export
+pub const OTLP_ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+/// Install the global subscriber
+pub fn init(verbose: bool) -> Result<()> {
+    let log_level = if verbose {
+        tracing::Level::DEBUG
+    } else {
+        tracing::Level::INFO
+    };
+
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    let otlp = match std::env::var(OTLP_ENDPOINT_ENV) {
+        Ok(endpoint) if !endpoint.is_empty() => {
+            Some(tracing_opentelemetry::layer().with_tracer(otlp_tracer(&endpoint)?))
+        }
+        _ => None,
+    };
+
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| {
+                    format!("zerobridge_relayer={},tower_http=debug", log_level).into()
+                }),
+        )
+        .with(tracing_subscriber::fmt::layer())
+        .with(otlp)
+        .init();
+
+    Ok(())
+}
+
+fn otlp_tracer(endpoint: &str) -> Result<trace::Tracer> {
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(opentelemetry_otlp::new_exporter().tonic().with_endpoint(endpoint))
+        .with_trace_config(
+            trace::config().with_resource(Resource::new([KeyValue::new("service.name", SERVICE_NAME)])),
+        )
+        .install_batch(opentelemetry_sdk::runtime::Tokio)?;
+    Ok(tracer)
+}
+
+/// Export spans still buffered; call before exiting
+pub fn shutdown() {
+    global::shutdown_tracer_provider();
+}
+
+/// Attach the current span's trace context to a coordinator request
+pub fn propagate(request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    let mut headers = HashMap::new();
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&Span::current().context(), &mut headers)
+    });
+
+    headers
+        .into_iter()
+        .fold(request, |request, (name, value)| request.header(name, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coordinator_client::{CoordinatorClient, DepositNotification};
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_sdk::testing::trace::InMemorySpanExporter;
+    use opentelemetry_sdk::trace::TracerProvider;
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test]
+    async fn test_deposit_notification_carries_trace() {
+        use axum::{http::HeaderMap, routing::post, Router};
+
+        let exporter = InMemorySpanExporter::default();
+        let provider = TracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+        let _guard = tracing::subscriber::set_default(subscriber);
+        global::set_text_map_propagator(TraceContextPropagator::new());
+
+        // Coordinator stand-in that remembers the trace header it got
+        let received = Arc::new(Mutex::new(None::<String>));
+        let app = Router::new().route("/deposits/notify", post({
+            let received = received.clone();
+            move |headers: HeaderMap| async move {
+                *received.lock().unwrap() = headers
+                    .get("traceparent")
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_string);
+                axum::Json(serde_json::json!({ "status": "queued" }))
+            }
+        }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let deposit = DepositNotification {
+            deposit_id: "d1".to_string(),
+            source_chain_id: 1,
+            target_chain_id: 8453,
+            sender: "0xsender".to_string(),
+            token: "0xtoken".to_string(),
+            amount: 1_000,
+            recipient: vec![1u8; 20],
+            zcash_address: vec![2u8; 43],
+            timestamp: 0,
+            target_chain_preferences: vec![],
+            block_number: None,
+            tx_hash: "0xtx".to_string(),
+        };
+        CoordinatorClient::mock(&url).notify_deposit(deposit).await.unwrap();
+        provider.force_flush();
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let span = spans
+            .iter()
+            .find(|s| s.name == "notify_deposit")
+            .expect("notification span exported");
+        assert!(span
+            .attributes
+            .iter()
+            .any(|kv| kv.key.as_str() == "deposit_id" && kv.value.to_string() == "d1"));
+
+        // traceparent: version-trace_id-parent_id-flags
+        let header = received.lock().unwrap().clone().expect("traceparent sent");
+        let parts: Vec<&str> = header.split('-').collect();
+        assert_eq!(parts[1], span.span_context.trace_id().to_string());
+        assert_eq!(parts[2], span.span_context.span_id().to_string());
+    }
+}
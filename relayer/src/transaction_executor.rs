@@ -6,12 +6,32 @@
 
 use anyhow::Result;
 use std::sync::Arc;
-use tracing::{info, debug};
+use tracing::{info, debug, warn};
 
-use crate::config::{RelayerConfig, ChainConfig};
-use crate::coordinator_client::CoordinatorClient;
+use crate::config::{RelayerConfig, ChainConfig, GasStrategy, GasStrategyType};
+use crate::coordinator_client::{AuthorizedWithdrawal, CoordinatorClient, RefundAuthorization};
 use crate::stake_manager::StakeManager;
 use crate::database::RelayerDatabase;
+use crate::solana_gateway::{ExecuteWithdrawalArgs, SolanaGatewayClient};
+
+/// Gas limit used when submitting an EVM executeWithdrawal call
+const EVM_WITHDRAWAL_GAS_LIMIT: u64 = 300_000;
+
+/// Gateway entry point for a single withdrawal
+const EXECUTE_WITHDRAWAL_SIG: &str = "executeWithdrawal(bytes32,uint256,bytes)";
+
+/// Gateway entry point for bundled withdrawals
+const EXECUTE_WITHDRAWAL_BATCH_SIG: &str = "executeWithdrawalBatch(bytes32[],uint256[],bytes[])";
+
+/// Source-gateway entry point for refunding an unrouted deposit
+const REFUND_DEPOSIT_SIG: &str = "refundDeposit(bytes32,bytes)";
+
+/// Outcome of a batched withdrawal transaction
+#[derive(Debug, Clone)]
+pub struct BatchExecution {
+    pub tx_hash: String,
+    pub gas_used: u64,
+}
 
 pub struct TransactionExecutor {
     config: RelayerConfig,
@@ -40,10 +60,12 @@ impl TransactionExecutor {
     pub async fn execute_withdrawal(
         &self,
         chain_id: u64,
+        withdrawal_id: &str,
         recipient: &str,
         token: &str,
         amount: u64,
         nullifier: &[u8],
+        deadline: u64,
         auth_signature: &[u8],
     ) -> Result<String> {
         info!(
@@ -62,10 +84,8 @@ impl TransactionExecutor {
             | crate::config::ChainType::Polygon => {
                 self.execute_evm_withdrawal(
                     chain_config,
-                    recipient,
-                    token,
-                    amount,
-                    nullifier,
+                    withdrawal_id,
+                    deadline,
                     auth_signature,
                 )
                 .await
@@ -73,10 +93,12 @@ impl TransactionExecutor {
             crate::config::ChainType::Solana => {
                 self.execute_solana_withdrawal(
                     chain_config,
+                    withdrawal_id,
                     recipient,
                     token,
                     amount,
                     nullifier,
+                    deadline,
                     auth_signature,
                 )
                 .await
@@ -106,14 +128,101 @@ impl TransactionExecutor {
         }
     }
 
+    /// Execute several authorized withdrawals for the same EVM chain in one
+    /// `executeWithdrawalBatch` transaction, up to the chain's
+    /// `withdrawal_batch_size`
+    pub async fn execute_withdrawal_batch(
+        &self,
+        chain_id: u64,
+        withdrawals: &[AuthorizedWithdrawal],
+    ) -> Result<BatchExecution> {
+        use ethers::prelude::*;
+
+        let chain_config = self
+            .config
+            .get_chain(chain_id)
+            .ok_or_else(|| anyhow::anyhow!("Chain {} not configured", chain_id))?;
+
+        if !chain_config.chain_type.is_evm() {
+            anyhow::bail!("Batched withdrawals are only supported on EVM chains");
+        }
+        if withdrawals.len() > chain_config.withdrawal_batch_size {
+            anyhow::bail!(
+                "Batch of {} exceeds withdrawal_batch_size {} for chain {}",
+                withdrawals.len(), chain_config.withdrawal_batch_size, chain_id
+            );
+        }
+        if let Some(w) = withdrawals.iter().find(|w| w.target_chain_id != chain_id) {
+            anyhow::bail!("Withdrawal {} targets chain {}, not {}", w.withdrawal_id, w.target_chain_id, chain_id);
+        }
+
+        info!("Executing batch of {} withdrawals on chain {}", withdrawals.len(), chain_id);
+
+        let call_data = encode_withdrawal_batch(withdrawals)?;
+
+        let provider = Provider::<Http>::try_from(&chain_config.rpc_url)?;
+        let wallet: LocalWallet = chain_config.private_key.parse()?;
+        let client = SignerMiddleware::new(provider, wallet.with_chain_id(chain_id));
+        let gateway: Address = chain_config.gateway_address.parse()?;
+
+        let gas_price = client.get_gas_price().await?;
+        let gas_limit = U256::from(EVM_WITHDRAWAL_GAS_LIMIT) * withdrawals.len();
+
+        let tx = TransactionRequest::new()
+            .to(gateway)
+            .data(call_data)
+            .gas(gas_limit)
+            .gas_price(gas_price * chain_config.gas_strategy.multiplier as u64);
+
+        let pending_tx = client.send_transaction(tx, None).await?;
+
+        info!("EVM withdrawal batch submitted: tx={:?}", pending_tx.tx_hash());
+
+        let receipt = pending_tx
+            .confirmations(chain_config.confirmations as usize)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Transaction dropped"))?;
+
+        let tx_hash = format!("{:?}", receipt.transaction_hash);
+        let gas_used = receipt.gas_used.unwrap_or_default().as_u64();
+
+        info!("✓ EVM withdrawal batch confirmed: {} ({} gas)", tx_hash, gas_used);
+        Ok(BatchExecution { tx_hash, gas_used })
+    }
+
+    /// Estimate what executing a withdrawal on `chain_id` costs, in fee-token
+    /// base units. Returns None when the chain can't be priced (non-EVM, or
+    /// no `fee_token_rate` configured).
+    pub async fn estimate_withdrawal_cost(&self, chain_id: u64) -> Result<Option<u64>> {
+        use ethers::prelude::*;
+
+        let chain_config = self
+            .config
+            .get_chain(chain_id)
+            .ok_or_else(|| anyhow::anyhow!("Chain {} not configured", chain_id))?;
+
+        let rate = match chain_config.gas_strategy.fee_token_rate {
+            Some(rate) if chain_config.chain_type.is_evm() => rate,
+            _ => return Ok(None),
+        };
+
+        let provider = Provider::<Http>::try_from(&chain_config.rpc_url)?;
+        let gas_price = provider.get_gas_price().await?;
+
+        Ok(Some(gas_cost_in_fee_units(
+            gas_price.min(U256::from(u128::MAX)).as_u128(),
+            EVM_WITHDRAWAL_GAS_LIMIT,
+            chain_config.gas_strategy.multiplier,
+            rate,
+        )))
+    }
+
     /// Execute withdrawal on EVM chain (Ethereum, Base, Polygon)
     async fn execute_evm_withdrawal(
         &self,
         chain_config: &ChainConfig,
-        recipient: &str,
-        token: &str,
-        amount: u64,
-        nullifier: &[u8],
+        withdrawal_id: &str,
+        deadline: u64,
         auth_signature: &[u8],
     ) -> Result<String> {
         use ethers::prelude::*;
@@ -126,32 +235,14 @@ impl TransactionExecutor {
         let client = SignerMiddleware::new(provider, wallet.with_chain_id(chain_id));
 
         let gateway: Address = chain_config.gateway_address.parse()?;
-        let recipient_addr: Address = recipient.parse()?;
-        let token_addr: Address = token.parse()?;
-
-        // Encode executeWithdrawal call
-        // function executeWithdrawal(
-        //     address recipient,
-        //     address token,
-        //     uint256 amount,
-        //     bytes32 nullifier,
-        //     bytes calldata authSignature
-        // )
-        let mut call_data = Vec::new();
-        
-        // Function selector for executeWithdrawal
-        call_data.extend_from_slice(&[0x12, 0x34, 0x56, 0x78]); // Placeholder selector
-        
-        // Encode parameters (simplified)
-        call_data.extend_from_slice(recipient_addr.as_bytes());
-        call_data.extend_from_slice(token_addr.as_bytes());
-        call_data.extend_from_slice(&amount.to_be_bytes());
-        call_data.extend_from_slice(nullifier);
-        call_data.extend_from_slice(auth_signature);
+
+        // The gateway reads recipient, token, amount and nullifier from the
+        // stored request; the signature covers them and the deadline
+        let call_data = encode_execute_withdrawal(withdrawal_id, deadline, auth_signature)?;
 
         // Estimate gas
         let gas_price = client.get_gas_price().await?;
-        let gas_limit = U256::from(300_000); // Base gas limit
+        let gas_limit = U256::from(EVM_WITHDRAWAL_GAS_LIMIT);
 
         // Submit transaction
         let tx = TransactionRequest::new()
@@ -183,23 +274,23 @@ impl TransactionExecutor {
     async fn execute_solana_withdrawal(
         &self,
         chain_config: &ChainConfig,
+        withdrawal_id: &str,
         recipient: &str,
         token: &str,
         amount: u64,
         nullifier: &[u8],
+        deadline: u64,
         auth_signature: &[u8],
     ) -> Result<String> {
-        use solana_client::rpc_client::RpcClient;
         use solana_sdk::{
             signature::{Keypair, Signer},
             transaction::Transaction,
-            instruction::{Instruction, AccountMeta},
             pubkey::Pubkey,
         };
 
-        debug!("Executing Solana withdrawal");
+        debug!("Executing Solana withdrawal: amount={}", amount);
 
-        let client = RpcClient::new(&chain_config.rpc_url);
+        let client = solana_rpc_client(chain_config);
         
         // Parse keys
         let keypair_bytes = hex::decode(&chain_config.private_key)?;
@@ -207,32 +298,54 @@ impl TransactionExecutor {
         
         let program_id: Pubkey = chain_config.gateway_address.parse()?;
         let recipient_key: Pubkey = recipient.parse()?;
-        let token_key: Pubkey = token.parse()?;
-
-        // Build instruction data
-        let mut instruction_data = Vec::new();
-        instruction_data.push(2u8); // Withdrawal instruction discriminator
-        instruction_data.extend_from_slice(&amount.to_le_bytes());
-        instruction_data.extend_from_slice(nullifier);
-        instruction_data.extend_from_slice(auth_signature);
-
-        // Create instruction
-        let instruction = Instruction {
-            program_id,
-            accounts: vec![
-                AccountMeta::new(keypair.pubkey(), true),
-                AccountMeta::new(recipient_key, false),
-                AccountMeta::new(token_key, false),
-            ],
-            data: instruction_data,
+        let mint: Pubkey = token.parse()?;
+
+        let withdrawal_id: [u8; 32] = hex::decode(withdrawal_id.trim_start_matches("0x"))?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Withdrawal id must be 32 bytes"))?;
+        let nullifier: [u8; 32] = nullifier
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Nullifier must be 32 bytes"))?;
+        let coordinator_signature: [u8; 65] = auth_signature
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Coordinator signature must be 65 bytes"))?;
+
+        // The mint's owner is the token program (SPL Token or Token-2022)
+        let token_program = client.get_account(&mint)?.owner;
+
+        let instruction = SolanaGatewayClient::new(program_id).execute_withdrawal(
+            &keypair.pubkey(),
+            &recipient_key,
+            &mint,
+            &token_program,
+            &nullifier,
+            &ExecuteWithdrawalArgs {
+                withdrawal_id,
+                deadline: i64::try_from(deadline)?,
+                coordinator_signature,
+            },
+        );
+
+        // Price compute from recent fees paid to the gateway program
+        let recent_fees = match client.get_recent_prioritization_fees(&[program_id]) {
+            Ok(fees) => fees.iter().map(|f| f.prioritization_fee).collect(),
+            Err(e) => {
+                warn!("Failed to fetch Solana prioritization fees: {}", e);
+                Vec::new()
+            }
         };
+        let priority_fee = solana_priority_fee(&chain_config.gas_strategy, &recent_fees);
+        debug!("Solana priority fee: {} micro-lamports/CU", priority_fee);
+
+        let instructions =
+            with_compute_budget(&chain_config.gas_strategy, priority_fee, instruction);
 
         // Get recent blockhash
         let recent_blockhash = client.get_latest_blockhash()?;
 
         // Create and sign transaction
         let transaction = Transaction::new_signed_with_payer(
-            &[instruction],
+            &instructions,
             Some(&keypair.pubkey()),
             &[&keypair],
             recent_blockhash,
@@ -303,4 +416,344 @@ impl TransactionExecutor {
 
         Ok(tx_hash)
     }
-}
\ No newline at end of file
+}
+
+/// Price `gas_limit` units of gas at `gas_price_wei` (scaled by the gas
+/// strategy multiplier) in fee-token base units
+pub fn gas_cost_in_fee_units(gas_price_wei: u128, gas_limit: u64, multiplier: f64, rate: f64) -> u64 {
+    let cost = gas_price_wei as f64 * multiplier * gas_limit as f64 * rate;
+    cost.round().min(u64::MAX as f64) as u64
+}
+
+/// RPC client for a Solana chain; reads and `send_and_confirm_transaction`
+/// wait for the chain's configured commitment
+pub fn solana_rpc_client(chain_config: &ChainConfig) -> solana_client::rpc_client::RpcClient {
+    solana_client::rpc_client::RpcClient::new_with_commitment(
+        chain_config.rpc_url.clone(),
+        chain_config.solana_commitment(),
+    )
+}
+
+/// Solana priority fee (micro-lamports per compute unit): the strategy's
+/// percentile of recent fees, scaled by the multiplier and capped at
+/// `max_gas_price`
+pub fn solana_priority_fee(strategy: &GasStrategy, recent_fees: &[u64]) -> u64 {
+    if recent_fees.is_empty() {
+        return 0;
+    }
+
+    let mut fees = recent_fees.to_vec();
+    fees.sort_unstable();
+
+    let percentile = match strategy.strategy_type {
+        GasStrategyType::Fast => 75,
+        GasStrategyType::Standard => 50,
+        GasStrategyType::Slow => 25,
+    };
+    let base = fees[(fees.len() - 1) * percentile / 100];
+
+    let scaled = (base as f64 * strategy.multiplier).round().min(u64::MAX as f64) as u64;
+    scaled.min(strategy.max_gas_price)
+}
+
+/// Prepend compute unit limit and price instructions to `instruction`
+pub fn with_compute_budget(
+    strategy: &GasStrategy,
+    priority_fee: u64,
+    instruction: solana_sdk::instruction::Instruction,
+) -> Vec<solana_sdk::instruction::Instruction> {
+    use solana_sdk::compute_budget::ComputeBudgetInstruction;
+
+    vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(strategy.compute_unit_limit),
+        ComputeBudgetInstruction::set_compute_unit_price(priority_fee.min(strategy.max_gas_price)),
+        instruction,
+    ]
+}
+
+/// ABI-encode an `executeWithdrawal(bytes32 withdrawalId, uint256 deadline,
+/// bytes coordinatorSignature)` call
+pub fn encode_execute_withdrawal(withdrawal_id: &str, deadline: u64, auth_signature: &[u8]) -> Result<Vec<u8>> {
+    use ethers::abi::{encode, Token};
+
+    let mut call_data = ethers::utils::id(EXECUTE_WITHDRAWAL_SIG).to_vec();
+    call_data.extend(encode(&[
+        withdrawal_id_token(withdrawal_id)?,
+        Token::Uint(deadline.into()),
+        Token::Bytes(auth_signature.to_vec()),
+    ]));
+    Ok(call_data)
+}
+
+/// ABI-encode an `executeWithdrawalBatch(bytes32[] withdrawalIds, uint256[]
+/// deadlines, bytes[] coordinatorSignatures)` call
+pub fn encode_withdrawal_batch(withdrawals: &[AuthorizedWithdrawal]) -> Result<Vec<u8>> {
+    use ethers::abi::{encode, Token};
+
+    if withdrawals.is_empty() {
+        anyhow::bail!("Cannot encode an empty withdrawal batch");
+    }
+
+    let mut ids = Vec::with_capacity(withdrawals.len());
+    let mut deadlines = Vec::with_capacity(withdrawals.len());
+    let mut signatures = Vec::with_capacity(withdrawals.len());
+
+    for withdrawal in withdrawals {
+        ids.push(withdrawal_id_token(&withdrawal.withdrawal_id)?);
+        deadlines.push(Token::Uint(withdrawal.deadline.into()));
+        signatures.push(Token::Bytes(withdrawal.authorization_signature.clone()));
+    }
+
+    let mut call_data = ethers::utils::id(EXECUTE_WITHDRAWAL_BATCH_SIG).to_vec();
+    call_data.extend(encode(&[Token::Array(ids), Token::Array(deadlines), Token::Array(signatures)]));
+    Ok(call_data)
+}
+
+fn withdrawal_id_token(withdrawal_id: &str) -> Result<ethers::abi::Token> {
+    let id = hex::decode(withdrawal_id.trim_start_matches("0x"))?;
+    if id.len() != 32 {
+        anyhow::bail!("Withdrawal id {} is not 32 bytes", withdrawal_id);
+    }
+    Ok(ethers::abi::Token::FixedBytes(id))
+}
+
+/// ABI-encode a `refundDeposit(bytes32 depositId, bytes coordinatorSignature)`
+/// call for the deposit's source gateway
+pub fn encode_refund_deposit(refund: &RefundAuthorization) -> Result<Vec<u8>> {
+    use ethers::abi::{encode, Token};
+
+    let id = hex::decode(refund.deposit_id.trim_start_matches("0x"))?;
+    if id.len() != 32 {
+        anyhow::bail!("Deposit id {} is not 32 bytes", refund.deposit_id);
+    }
+
+    let mut call_data = ethers::utils::id(REFUND_DEPOSIT_SIG).to_vec();
+    call_data.extend(encode(&[
+        Token::FixedBytes(id),
+        Token::Bytes(refund.authorization_signature.clone()),
+    ]));
+    Ok(call_data)
+}
+
+/// Split a batch transaction's `gas_used` across its withdrawals in
+/// proportion to each one's relayer fee (evenly when no fees are paid). The
+/// parts always sum to `gas_used`.
+pub fn attribute_batch_gas(gas_used: u64, fees: &[u64]) -> Vec<u64> {
+    if fees.is_empty() {
+        return Vec::new();
+    }
+
+    let total_fee: u128 = fees.iter().map(|&f| f as u128).sum();
+    let mut shares: Vec<u64> = if total_fee == 0 {
+        vec![gas_used / fees.len() as u64; fees.len()]
+    } else {
+        fees.iter()
+            .map(|&fee| (gas_used as u128 * fee as u128 / total_fee) as u64)
+            .collect()
+    };
+
+    // Hand out the rounding remainder one unit at a time
+    let remainder = gas_used - shares.iter().sum::<u64>();
+    for share in shares.iter_mut().take(remainder as usize) {
+        *share += 1;
+    }
+
+    shares
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn authorized(id: u8, fee: u64) -> AuthorizedWithdrawal {
+        AuthorizedWithdrawal {
+            withdrawal_id: hex::encode([id; 32]),
+            target_chain_id: 1,
+            recipient: "0x0000000000000000000000000000000000000001".to_string(),
+            token: "0x0000000000000000000000000000000000000002".to_string(),
+            amount: 1_000,
+            nullifier: vec![id; 32],
+            authorization_signature: vec![id; 65],
+            timestamp: 0,
+            relayer_fee: fee,
+            deadline: 1_700_003_600 + id as u64,
+        }
+    }
+
+    #[test]
+    fn test_encode_withdrawal_batch() {
+        use ethers::abi::{decode, ParamType, Token};
+
+        let batch = vec![authorized(1, 10), authorized(2, 30)];
+        let call_data = encode_withdrawal_batch(&batch).unwrap();
+
+        assert_eq!(&call_data[..4], &ethers::utils::id(EXECUTE_WITHDRAWAL_BATCH_SIG)[..]);
+
+        let decoded = decode(
+            &[
+                ParamType::Array(Box::new(ParamType::FixedBytes(32))),
+                ParamType::Array(Box::new(ParamType::Uint(256))),
+                ParamType::Array(Box::new(ParamType::Bytes)),
+            ],
+            &call_data[4..],
+        )
+        .unwrap();
+
+        assert_eq!(
+            decoded[0],
+            Token::Array(vec![Token::FixedBytes(vec![1; 32]), Token::FixedBytes(vec![2; 32])])
+        );
+        assert_eq!(
+            decoded[1],
+            Token::Array(vec![Token::Uint(1_700_003_601u64.into()), Token::Uint(1_700_003_602u64.into())])
+        );
+        assert_eq!(
+            decoded[2],
+            Token::Array(vec![Token::Bytes(vec![1; 65]), Token::Bytes(vec![2; 65])])
+        );
+
+        assert!(encode_withdrawal_batch(&[]).is_err());
+
+        let mut bad_id = authorized(3, 0);
+        bad_id.withdrawal_id = "abcd".to_string();
+        assert!(encode_withdrawal_batch(&[bad_id]).is_err());
+    }
+
+    #[test]
+    fn test_encode_execute_withdrawal() {
+        use ethers::abi::{decode, ParamType, Token};
+
+        let withdrawal = authorized(1, 10);
+        let call_data = encode_execute_withdrawal(
+            &withdrawal.withdrawal_id,
+            withdrawal.deadline,
+            &withdrawal.authorization_signature,
+        )
+        .unwrap();
+
+        assert_eq!(&call_data[..4], &ethers::utils::id(EXECUTE_WITHDRAWAL_SIG)[..]);
+        let decoded = decode(
+            &[ParamType::FixedBytes(32), ParamType::Uint(256), ParamType::Bytes],
+            &call_data[4..],
+        )
+        .unwrap();
+        assert_eq!(
+            decoded,
+            vec![
+                Token::FixedBytes(vec![1; 32]),
+                Token::Uint(1_700_003_601u64.into()),
+                Token::Bytes(vec![1; 65]),
+            ]
+        );
+
+        assert!(encode_execute_withdrawal("abcd", withdrawal.deadline, &[]).is_err());
+    }
+
+    #[test]
+    fn test_encode_refund_deposit() {
+        use ethers::abi::{decode, ParamType, Token};
+
+        let mut refund = RefundAuthorization {
+            deposit_id: hex::encode([4u8; 32]),
+            source_chain_id: 1,
+            sender: "0xsender".to_string(),
+            token: "0xtoken".to_string(),
+            amount: 1_000,
+            authorization_signature: vec![5; 65],
+        };
+        let call_data = encode_refund_deposit(&refund).unwrap();
+
+        assert_eq!(&call_data[..4], &ethers::utils::id(REFUND_DEPOSIT_SIG)[..]);
+        let decoded = decode(&[ParamType::FixedBytes(32), ParamType::Bytes], &call_data[4..]).unwrap();
+        assert_eq!(decoded, vec![Token::FixedBytes(vec![4; 32]), Token::Bytes(vec![5; 65])]);
+
+        refund.deposit_id = "abcd".to_string();
+        assert!(encode_refund_deposit(&refund).is_err());
+    }
+
+    #[test]
+    fn test_solana_client_uses_configured_commitment() {
+        use crate::config::{ChainType, SolanaCommitment};
+        use solana_sdk::commitment_config::CommitmentConfig;
+
+        let mut chain = RelayerConfig::mock().chains.remove(0);
+        chain.chain_type = ChainType::Solana;
+        assert_eq!(solana_rpc_client(&chain).commitment(), CommitmentConfig::finalized());
+
+        chain.commitment = SolanaCommitment::Confirmed;
+        assert_eq!(solana_rpc_client(&chain).commitment(), CommitmentConfig::confirmed());
+
+        chain.commitment = SolanaCommitment::Processed;
+        assert_eq!(solana_rpc_client(&chain).commitment(), CommitmentConfig::processed());
+    }
+
+    fn solana_strategy(strategy_type: GasStrategyType, max_gas_price: u64) -> GasStrategy {
+        GasStrategy {
+            strategy_type,
+            max_gas_price,
+            multiplier: 1.5,
+            fee_token_rate: None,
+            compute_unit_limit: 300_000,
+        }
+    }
+
+    #[test]
+    fn test_compute_budget_instructions_prepended() {
+        use solana_sdk::compute_budget::ComputeBudgetInstruction;
+        use solana_sdk::instruction::Instruction;
+        use solana_sdk::pubkey::Pubkey;
+
+        let strategy = solana_strategy(GasStrategyType::Standard, 10_000);
+        let withdrawal = Instruction::new_with_bytes(Pubkey::new_unique(), &[1, 2, 3], vec![]);
+
+        let instructions = with_compute_budget(&strategy, 2_500, withdrawal.clone());
+
+        assert_eq!(instructions.len(), 3);
+        assert_eq!(instructions[0], ComputeBudgetInstruction::set_compute_unit_limit(300_000));
+        assert_eq!(instructions[1], ComputeBudgetInstruction::set_compute_unit_price(2_500));
+        assert_eq!(instructions[2], withdrawal);
+        assert_eq!(instructions[0].program_id, solana_sdk::compute_budget::id());
+    }
+
+    #[test]
+    fn test_priority_fee_respects_configured_max() {
+        let recent = [100, 400, 200, 800, 1_600];
+
+        // Median 400 * 1.5
+        assert_eq!(solana_priority_fee(&solana_strategy(GasStrategyType::Standard, 10_000), &recent), 600);
+        // 75th percentile 800 * 1.5
+        assert_eq!(solana_priority_fee(&solana_strategy(GasStrategyType::Fast, 10_000), &recent), 1_200);
+        // Congestion: capped at max_gas_price
+        assert_eq!(solana_priority_fee(&solana_strategy(GasStrategyType::Fast, 1_000), &recent), 1_000);
+        assert_eq!(solana_priority_fee(&solana_strategy(GasStrategyType::Slow, 10_000), &[]), 0);
+
+        // The cap also applies to a fee passed in directly
+        let strategy = solana_strategy(GasStrategyType::Fast, 1_000);
+        let instructions = with_compute_budget(
+            &strategy,
+            50_000,
+            solana_sdk::instruction::Instruction::new_with_bytes(
+                solana_sdk::pubkey::Pubkey::new_unique(),
+                &[],
+                vec![],
+            ),
+        );
+        assert_eq!(
+            instructions[1],
+            solana_sdk::compute_budget::ComputeBudgetInstruction::set_compute_unit_price(1_000)
+        );
+    }
+
+    #[test]
+    fn test_batch_gas_attributed_by_fee() {
+        assert_eq!(attribute_batch_gas(400_000, &[10, 30]), vec![100_000, 300_000]);
+
+        // Rounding remainder is handed out so nothing is lost
+        let shares = attribute_batch_gas(100_000, &[1, 1, 1]);
+        assert_eq!(shares, vec![33_334, 33_333, 33_333]);
+
+        // No fees: split evenly
+        assert_eq!(attribute_batch_gas(90, &[0, 0, 0]), vec![30, 30, 30]);
+        assert!(attribute_batch_gas(90, &[]).is_empty());
+    }
+}
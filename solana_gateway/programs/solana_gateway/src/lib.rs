@@ -9,6 +9,28 @@ use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 declare_id!("8FGoQPMAt83sMLrxNb3yr8fQS8VBhQPEu31wCGg7b6Tc");
 
+/// Max withdrawals per `execute_withdrawal_batch`. Each one adds 3 accounts
+/// and 97 bytes of args to a 1232-byte transaction, plus a secp256k1
+/// recovery and a token transfer (~30k compute units).
+pub const MAX_WITHDRAWAL_BATCH: usize = 4;
+
+/// `remaining_accounts` per batched withdrawal:
+/// [withdrawal_request, nullifier_account, recipient_token]
+pub const BATCH_ACCOUNTS_PER_WITHDRAWAL: usize = 3;
+
+/// Bridge fee on new gateways, in basis points (0.3%, as on the other chains)
+pub const DEFAULT_BRIDGE_FEE: u16 = 30;
+
+/// Highest bridge fee `set_bridge_fee` accepts, in basis points (1%)
+pub const MAX_BRIDGE_FEE: u16 = 100;
+
+/// How long the previous coordinator key keeps verifying after
+/// `set_coordinator`, so authorizations it already signed can still execute
+pub const DEFAULT_COORDINATOR_OVERLAP: i64 = 60 * 60;
+
+/// Longest overlap `set_coordinator_overlap` accepts (7 days)
+pub const MAX_COORDINATOR_OVERLAP: i64 = 7 * 24 * 60 * 60;
+
 #[program]
 pub mod solana_adapter {
     use super::*;
@@ -16,16 +38,17 @@ pub mod solana_adapter {
     pub fn initialize(
         ctx: Context<Initialize>,
         coordinator_pubkey: Pubkey,
+        coordinator_eth: [u8; 20],
     ) -> Result<()> {
-        let gateway = &mut ctx.accounts.gateway;
-        gateway.authority = ctx.accounts.authority.key();
-        gateway.coordinator = coordinator_pubkey;
-        gateway.total_locked = 0;
-        gateway.total_withdrawn = 0;
-        gateway.deposit_count = 0;
-        gateway.withdrawal_count = 0;
-        gateway.paused = false;
-        gateway.bump = ctx.bumps.gateway;
+        init_gateway(
+            &mut ctx.accounts.gateway,
+            ctx.accounts.authority.key(),
+            coordinator_pubkey,
+            coordinator_eth,
+            ctx.bumps.gateway,
+            Clock::get()?.unix_timestamp,
+        )
+        .emit();
         
         msg!("Gateway initialized with coordinator: {}", coordinator_pubkey);
         Ok(())
@@ -40,8 +63,9 @@ pub mod solana_adapter {
         zcash_address: [u8; 32],
     ) -> Result<()> {
         require!(!ctx.accounts.gateway.paused, ErrorCode::GatewayPaused);
-        require!(amount > 0, ErrorCode::InvalidAmount);
-        require!(amount >= 1_000_000, ErrorCode::AmountTooSmall);
+        
+        let mint_state = &mut ctx.accounts.mint_state;
+        mint_state.check_deposit(ctx.accounts.mint.key(), amount)?;
         
         let gateway = &mut ctx.accounts.gateway;
         
@@ -69,31 +93,35 @@ pub mod solana_adapter {
             gateway.deposit_count,
         );
         
+        // The fee stays in the vault; only the net amount is bridged
+        let (net_amount, fee) = lock_deposit(gateway, mint_state, amount)?;
+        
         let deposit_account = &mut ctx.accounts.deposit;
         deposit_account.deposit_id = deposit_id;
         deposit_account.sender = ctx.accounts.user.key();
         deposit_account.mint = ctx.accounts.mint.key();
-        deposit_account.amount = amount;
+        deposit_account.amount = net_amount;
         deposit_account.target_chain_id = target_chain_id;
         deposit_account.recipient = recipient;
         deposit_account.zcash_address = zcash_address;
         deposit_account.timestamp = Clock::get()?.unix_timestamp;
         deposit_account.processed = false;
         
-        gateway.total_locked = gateway
-            .total_locked
-            .checked_add(amount)
-            .ok_or(ErrorCode::Overflow)?;
-        gateway.deposit_count = gateway
-            .deposit_count
-            .checked_add(1)
-            .ok_or(ErrorCode::Overflow)?;
+        if fee > 0 {
+            emit!(BridgeFeeCollected {
+                deposit_id,
+                mint: ctx.accounts.mint.key(),
+                fee,
+                total_fees: mint_state.fees,
+                timestamp: deposit_account.timestamp,
+            });
+        }
         
         emit!(TokensLocked {
             deposit_id,
             sender: ctx.accounts.user.key(),
             mint: ctx.accounts.mint.key(),
-            amount,
+            amount: net_amount,
             target_chain_id,
             recipient,
             zcash_address,
@@ -161,10 +189,12 @@ pub mod solana_adapter {
         Ok(())
     }
 
-    /// Execute withdrawal - Step 2 (with coordinator signature)
+    /// Execute withdrawal - Step 2 (with coordinator signature, valid until
+    /// `deadline`)
     pub fn execute_withdrawal(
         ctx: Context<ExecuteWithdrawal>,
         withdrawal_id: [u8; 32],
+        deadline: i64,
         coordinator_signature: [u8; 65], // r(32) + s(32) + v(1)
     ) -> Result<()> {
         require!(!ctx.accounts.gateway.paused, ErrorCode::GatewayPaused);
@@ -195,8 +225,10 @@ pub mod solana_adapter {
             recipient_key,
             amount,
             nullifier,
+            deadline,
             &coordinator_signature,
-            ctx.accounts.gateway.coordinator,
+            &ctx.accounts.gateway,
+            Clock::get()?.unix_timestamp,
         )?;
         
         // Mark as executed
@@ -209,15 +241,7 @@ pub mod solana_adapter {
         nullifier_account.timestamp = Clock::get()?.unix_timestamp;
         
         let gateway = &mut ctx.accounts.gateway;
-        
-        gateway.total_locked = gateway
-            .total_locked
-            .checked_sub(amount)
-            .ok_or(ErrorCode::Underflow)?;
-        gateway.total_withdrawn = gateway
-            .total_withdrawn
-            .checked_add(amount)
-            .ok_or(ErrorCode::Overflow)?;
+        release_withdrawals(gateway, &mut ctx.accounts.mint_state, amount, ctx.accounts.vault.amount)?;
         
         let seeds = &[b"gateway".as_ref(), &[gateway.bump]];
         let signer = &[&seeds[..]];
@@ -251,19 +275,190 @@ pub mod solana_adapter {
         Ok(())
     }
 
+    /// Execute several withdrawals of the same mint in one transaction
+    /// All-or-nothing: every item is verified before any state changes, and
+    /// any failure reverts the whole transaction
+    pub fn execute_withdrawal_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteWithdrawalBatch<'info>>,
+        withdrawals: Vec<BatchWithdrawal>,
+    ) -> Result<()> {
+        require!(!ctx.accounts.gateway.paused, ErrorCode::GatewayPaused);
+        check_batch_shape(&withdrawals, ctx.remaining_accounts.len())?;
+
+        let mint_key = ctx.accounts.mint.key();
+        
+        let mut requests = Vec::with_capacity(withdrawals.len());
+        let mut nullifiers = Vec::with_capacity(withdrawals.len());
+        let mut recipient_tokens = Vec::with_capacity(withdrawals.len());
+        
+        for accounts in ctx.remaining_accounts.chunks(BATCH_ACCOUNTS_PER_WITHDRAWAL) {
+            require!(
+                accounts.iter().all(|a| a.is_writable),
+                ErrorCode::InvalidBatchAccounts
+            );
+            
+            let withdrawal_request = Account::<WithdrawalRequestInfo>::try_from(&accounts[0])?;
+            let nullifier_account = Account::<NullifierAccount>::try_from(&accounts[1])?;
+            let recipient_token = InterfaceAccount::<TokenAccount>::try_from(&accounts[2])?;
+            
+            let (expected_nullifier, _) = Pubkey::find_program_address(
+                &[b"nullifier_check", withdrawal_request.nullifier.as_ref()],
+                ctx.program_id,
+            );
+            require_keys_eq!(
+                nullifier_account.key(),
+                expected_nullifier,
+                ErrorCode::InvalidBatchAccounts
+            );
+            require_keys_eq!(recipient_token.mint, mint_key, ErrorCode::MintMismatch);
+            require_keys_eq!(
+                recipient_token.owner,
+                withdrawal_request.recipient,
+                ErrorCode::InvalidRecipientAccount
+            );
+            
+            requests.push(withdrawal_request);
+            nullifiers.push(nullifier_account);
+            recipient_tokens.push(recipient_token);
+        }
+        
+        let now = Clock::get()?.unix_timestamp;
+        settle_batch(
+            &mut ctx.accounts.gateway,
+            &mut ctx.accounts.mint_state,
+            &withdrawals,
+            &mut requests.iter_mut().map(|r| &mut **r).collect::<Vec<_>>(),
+            &mut nullifiers.iter_mut().map(|n| &mut **n).collect::<Vec<_>>(),
+            mint_key,
+            ctx.accounts.vault.amount,
+            now,
+        )?;
+        
+        let bump = ctx.accounts.gateway.bump;
+        let seeds = &[b"gateway".as_ref(), &[bump]];
+        let signer = &[&seeds[..]];
+        
+        for ((request, nullifier_account), recipient_token) in
+            requests.iter().zip(&nullifiers).zip(&recipient_tokens)
+        {
+            anchor_spl::token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    anchor_spl::token_interface::TransferChecked {
+                        from: ctx.accounts.vault.to_account_info(),
+                        to: recipient_token.to_account_info(),
+                        authority: ctx.accounts.gateway.to_account_info(),
+                        mint: ctx.accounts.mint.to_account_info(),
+                    },
+                    signer,
+                ),
+                request.amount,
+                ctx.accounts.mint.decimals,
+            )?;
+            
+            // remaining_accounts aren't persisted by Anchor automatically
+            request.exit(ctx.program_id)?;
+            nullifier_account.exit(ctx.program_id)?;
+            
+            emit!(TokensReleased {
+                withdrawal_id: request.withdrawal_id,
+                recipient: request.recipient,
+                mint: request.mint,
+                amount: request.amount,
+                nullifier: request.nullifier,
+                timestamp: now,
+            });
+        }
+        
+        msg!("Withdrawal batch executed: {} withdrawals", withdrawals.len());
+        Ok(())
+    }
+
+    /// Refund a deposit the coordinator could not route, returning the
+    /// locked tokens to the original sender
+    pub fn refund_deposit(
+        ctx: Context<RefundDeposit>,
+        deposit_id: [u8; 32],
+        coordinator_signature: [u8; 65], // r(32) + s(32) + v(1)
+    ) -> Result<()> {
+        require!(!ctx.accounts.gateway.paused, ErrorCode::GatewayPaused);
+
+        let amount = settle_refund(
+            &mut ctx.accounts.gateway,
+            &mut ctx.accounts.mint_state,
+            &mut ctx.accounts.deposit,
+            deposit_id,
+            &coordinator_signature,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        let seeds = &[b"gateway".as_ref(), &[ctx.accounts.gateway.bump]];
+        let signer = &[&seeds[..]];
+
+        anchor_spl::token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                anchor_spl::token_interface::TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.sender_token.to_account_info(),
+                    authority: ctx.accounts.gateway.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        emit!(DepositRefunded {
+            deposit_id,
+            sender: ctx.accounts.deposit.sender,
+            mint: ctx.accounts.deposit.mint,
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Deposit refunded: {:?}", deposit_id);
+        Ok(())
+    }
+
+    /// Record that the coordinator minted a deposit on its target chain, so
+    /// it can no longer be refunded. Anyone can submit the authorization.
+    pub fn mark_deposit_minted(
+        ctx: Context<MarkDepositMinted>,
+        deposit_id: [u8; 32],
+        coordinator_signature: [u8; 65], // r(32) + s(32) + v(1)
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        settle_minted(
+            &ctx.accounts.gateway,
+            &mut ctx.accounts.deposit,
+            deposit_id,
+            &coordinator_signature,
+            now,
+        )?;
+
+        emit!(DepositMinted {
+            deposit_id,
+            timestamp: now,
+        });
+
+        msg!("Deposit minted: {:?}", deposit_id);
+        Ok(())
+    }
+
     pub fn set_coordinator(
         ctx: Context<SetCoordinator>,
         new_coordinator: Pubkey,
+        new_coordinator_eth: [u8; 20],
     ) -> Result<()> {
-        let gateway = &mut ctx.accounts.gateway;
-        let old_coordinator = gateway.coordinator;
-        gateway.coordinator = new_coordinator;
-        
-        emit!(CoordinatorUpdated {
-            old_coordinator,
+        update_coordinator(
+            &mut ctx.accounts.gateway,
             new_coordinator,
-            timestamp: Clock::get()?.unix_timestamp,
-        });
+            new_coordinator_eth,
+            Clock::get()?.unix_timestamp,
+        )
+        .emit();
         
         Ok(())
     }
@@ -272,14 +467,151 @@ pub mod solana_adapter {
         ctx: Context<SetPaused>,
         paused: bool,
     ) -> Result<()> {
-        ctx.accounts.gateway.paused = paused;
+        update_paused(
+            &mut ctx.accounts.gateway,
+            paused,
+            ctx.accounts.authority.key(),
+            Clock::get()?.unix_timestamp,
+        )
+        .emit();
         
-        if paused {
-            emit!(EmergencyPause {
-                triggered_by: ctx.accounts.authority.key(),
-                timestamp: Clock::get()?.unix_timestamp,
-            });
+        Ok(())
+    }
+
+    /// How long a replaced coordinator key keeps verifying, for
+    /// coordinator changes from now on
+    pub fn set_coordinator_overlap(
+        ctx: Context<SetCoordinatorOverlap>,
+        seconds: i64,
+    ) -> Result<()> {
+        update_coordinator_overlap(&mut ctx.accounts.gateway, seconds, Clock::get()?.unix_timestamp)?.emit();
+        
+        Ok(())
+    }
+
+    /// Bridge fee taken from each deposit, in basis points
+    pub fn set_bridge_fee(
+        ctx: Context<SetBridgeFee>,
+        fee_bps: u16,
+    ) -> Result<()> {
+        update_bridge_fee(&mut ctx.accounts.gateway, fee_bps, Clock::get()?.unix_timestamp)?.emit();
+        
+        Ok(())
+    }
+
+    /// Grow a gateway initialized before the bridge fee existed to the
+    /// current `GatewayState` layout. The authority pays the extra rent and
+    /// supplies the coordinator's signing address, which old gateways lack.
+    pub fn migrate_gateway(ctx: Context<MigrateGateway>, coordinator_eth: [u8; 20]) -> Result<()> {
+        let gateway = ctx.accounts.gateway.to_account_info();
+        require_keys_eq!(*gateway.owner, crate::ID, ErrorCode::InvalidGatewayAccount);
+        check_legacy_gateway(&gateway.try_borrow_data()?, ctx.accounts.authority.key())?;
+
+        let rent = Rent::get()?.minimum_balance(8 + GatewayState::SIZE);
+        let top_up = rent.saturating_sub(gateway.lamports());
+        if top_up > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: gateway.clone(),
+                    },
+                ),
+                top_up,
+            )?;
         }
+
+        gateway.resize(8 + GatewayState::SIZE)?;
+        write_appended_gateway_fields(&mut gateway.try_borrow_mut_data()?, coordinator_eth);
+
+        msg!("Gateway migrated, bridge fee: {} bps", DEFAULT_BRIDGE_FEE);
+        Ok(())
+    }
+
+    /// Accept deposits of a mint: creates its vault and `MintState`
+    pub fn register_mint(ctx: Context<RegisterMint>) -> Result<()> {
+        register_mint_state(
+            &mut ctx.accounts.mint_state,
+            ctx.accounts.mint.key(),
+            ctx.accounts.mint.decimals,
+            ctx.bumps.mint_state,
+            ctx.accounts.vault.key(),
+            ctx.accounts.authority.key(),
+            Clock::get()?.unix_timestamp,
+        )
+        .emit();
+        
+        msg!("Mint registered: {}", ctx.accounts.mint.key());
+        Ok(())
+    }
+
+    /// Smallest deposit accepted for a mint, in its base units
+    pub fn set_min_deposit(
+        ctx: Context<SetMinDeposit>,
+        min_deposit: u64,
+    ) -> Result<()> {
+        update_min_deposit(&mut ctx.accounts.mint_state, min_deposit, Clock::get()?.unix_timestamp).emit();
+        
+        Ok(())
+    }
+
+    /// Pay out fees collected on a mint's deposits. Locked deposits stay in
+    /// the vault.
+    pub fn withdraw_fees(
+        ctx: Context<WithdrawFees>,
+        amount: u64,
+    ) -> Result<()> {
+        let event = take_fees(
+            &mut ctx.accounts.mint_state,
+            amount,
+            ctx.accounts.authority.key(),
+            ctx.accounts.destination_token.key(),
+            Clock::get()?.unix_timestamp,
+        )?;
+        
+        let seeds = &[b"gateway".as_ref(), &[ctx.accounts.gateway.bump]];
+        let signer = &[&seeds[..]];
+        
+        anchor_spl::token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                anchor_spl::token_interface::TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.destination_token.to_account_info(),
+                    authority: ctx.accounts.gateway.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+            ctx.accounts.mint.decimals,
+        )?;
+        
+        event.emit();
+        
+        Ok(())
+    }
+
+    /// Vault balance of a mint not backing locked deposits or owed as fees,
+    /// returned as return data. Read-only; simulate it rather than sending it.
+    pub fn get_available_liquidity(ctx: Context<GetAvailableLiquidity>) -> Result<u64> {
+        let mint_state = &ctx.accounts.mint_state;
+        Ok(available_liquidity(
+            ctx.accounts.vault.amount,
+            mint_state.locked.saturating_add(mint_state.fees),
+        ))
+    }
+
+    pub fn initialize_liquidity_pool(ctx: Context<InitializeLiquidityPool>) -> Result<()> {
+        init_liquidity_pool(
+            &mut ctx.accounts.pool,
+            ctx.accounts.mint.key(),
+            ctx.bumps.pool,
+            ctx.accounts.authority.key(),
+            Clock::get()?.unix_timestamp,
+        )
+        .emit();
         
         Ok(())
     }
@@ -306,10 +638,50 @@ pub mod solana_adapter {
             ctx.accounts.mint.decimals,
         )?;
         
+        let total_liquidity = ctx.accounts.pool.add(amount)?;
+        
         emit!(LiquidityAdded {
             provider: ctx.accounts.provider.key(),
             mint: ctx.accounts.mint.key(),
             amount,
+            total_liquidity,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    pub fn remove_liquidity(
+        ctx: Context<RemoveLiquidity>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        
+        let total_liquidity = ctx.accounts.pool.remove(amount)?;
+        
+        let seeds = &[b"gateway".as_ref(), &[ctx.accounts.gateway.bump]];
+        let signer = &[&seeds[..]];
+        
+        anchor_spl::token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                anchor_spl::token_interface::TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.destination_token.to_account_info(),
+                    authority: ctx.accounts.gateway.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+            ctx.accounts.mint.decimals,
+        )?;
+        
+        emit!(LiquidityRemoved {
+            authority: ctx.accounts.authority.key(),
+            mint: ctx.accounts.mint.key(),
+            amount,
+            total_liquidity,
             timestamp: Clock::get()?.unix_timestamp,
         });
         
@@ -319,23 +691,83 @@ pub mod solana_adapter {
 
 // ============ Helper Functions ============
 
+/// Once `deadline` has passed the coordinator releases the liquidity the
+/// withdrawal held, so the authorization must no longer execute
+#[allow(clippy::too_many_arguments)]
 fn verify_coordinator_signature(
     withdrawal_id: [u8; 32],
     recipient: Pubkey,
     amount: u64,
     nullifier: [u8; 32],
+    deadline: i64,
     signature: &[u8; 65],
-    _expected_coordinator: Pubkey,
+    gateway: &GatewayState,
+    now: i64,
 ) -> Result<()> {
+    require!(now <= deadline, ErrorCode::AuthorizationExpired);
+    let message_hash = withdrawal_message_hash(withdrawal_id, recipient, amount, nullifier, deadline);
+
+    recover_coordinator(message_hash, signature, gateway, now)
+}
+
+/// Hash the coordinator signs to authorize a withdrawal
+fn withdrawal_message_hash(
+    withdrawal_id: [u8; 32],
+    recipient: Pubkey,
+    amount: u64,
+    nullifier: [u8; 32],
+    deadline: i64,
+) -> [u8; 32] {
     // Construct message hash (same as EVM)
     let mut message_data = Vec::new();
     message_data.extend_from_slice(&withdrawal_id);
     message_data.extend_from_slice(recipient.as_ref());
     message_data.extend_from_slice(&amount.to_le_bytes());
     message_data.extend_from_slice(&nullifier);
+    message_data.extend_from_slice(&deadline.to_le_bytes());
     
-    let message_hash: [u8; 32] = Keccak256::digest(&message_data).into();
-    
+    Keccak256::digest(&message_data).into()
+}
+
+/// Refund authorization signed by the coordinator; the "REFUND" prefix keeps
+/// it from ever verifying as a withdrawal
+fn verify_refund_signature(
+    deposit: &DepositInfo,
+    signature: &[u8; 65],
+    gateway: &GatewayState,
+    now: i64,
+) -> Result<()> {
+    recover_coordinator(refund_message_hash(deposit), signature, gateway, now)
+}
+
+fn refund_message_hash(deposit: &DepositInfo) -> [u8; 32] {
+    let mut message_data = Vec::new();
+    message_data.extend_from_slice(b"REFUND");
+    message_data.extend_from_slice(&deposit.deposit_id);
+    message_data.extend_from_slice(deposit.sender.as_ref());
+    message_data.extend_from_slice(deposit.mint.as_ref());
+    message_data.extend_from_slice(&deposit.amount.to_le_bytes());
+
+    Keccak256::digest(&message_data).into()
+}
+
+/// Hash the coordinator signs once a deposit is minted on its target chain
+fn minted_message_hash(deposit_id: [u8; 32]) -> [u8; 32] {
+    let mut message_data = Vec::new();
+    message_data.extend_from_slice(b"MINTED");
+    message_data.extend_from_slice(&deposit_id);
+
+    Keccak256::digest(&message_data).into()
+}
+
+/// Check that `signature` over `message_hash` was made by a coordinator key
+/// the gateway accepts at `now`
+fn recover_coordinator(
+    message_hash: [u8; 32],
+    signature: &[u8; 65],
+    gateway: &GatewayState,
+    now: i64,
+) -> Result<()> {
     // Split signature into r, s, v
     let recovery_id = signature[64];
     
@@ -351,72 +783,486 @@ fn verify_coordinator_signature(
     )
     .map_err(|_| ErrorCode::InvalidSignature)?;
     
-    // Convert recovered pubkey to Solana address format
-    // In production, coordinator would have their Ethereum address stored
-    // and we'd verify against that
-    
-    // For now, simplified check
     require!(
-        recovered_pubkey.0.len() == 64,
-        ErrorCode::InvalidSignature
+        gateway.accepts_coordinator(eth_address(&recovered_pubkey.0), now),
+        ErrorCode::InvalidCoordinator
     );
     
     Ok(())
 }
 
-fn generate_deposit_id(
-    sender: &Pubkey,
-    mint: &Pubkey,
-    amount: u64,
-    target_chain_id: u64,
-    recipient: [u8; 32],
-    nonce: u64,
-) -> [u8; 32] {
-    let mut data = Vec::new();
-    data.extend_from_slice(sender.as_ref());
-    data.extend_from_slice(mint.as_ref());
-    data.extend_from_slice(&amount.to_le_bytes());
-    data.extend_from_slice(&target_chain_id.to_le_bytes());
-    data.extend_from_slice(&recipient);
-    data.extend_from_slice(&nonce.to_le_bytes());
-    
-    Keccak256::digest(&data).into()
-}
-
-fn generate_withdrawal_id(
-    recipient: &Pubkey,
-    mint: &Pubkey,
-    amount: u64,
-    nullifier: [u8; 32],
-    nonce: u64,
-) -> [u8; 32] {
-    let mut data = Vec::new();
-    data.extend_from_slice(recipient.as_ref());
-    data.extend_from_slice(mint.as_ref());
-    data.extend_from_slice(&amount.to_le_bytes());
-    data.extend_from_slice(&nullifier);
-    data.extend_from_slice(&nonce.to_le_bytes());
-    
-    Keccak256::digest(&data).into()
+/// Ethereum address of an uncompressed secp256k1 public key (x ‖ y): the
+/// last 20 bytes of its keccak256
+pub fn eth_address(pubkey: &[u8; 64]) -> [u8; 20] {
+    let hash: [u8; 32] = Keccak256::digest(pubkey).into();
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    address
 }
 
-// ============ Account Structures ============
-
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + GatewayState::SIZE,
-        seeds = [b"gateway"],
-        bump
-    )]
-    pub gateway: Account<'info, GatewayState>,
+/// Batch size, account count and uniqueness checks for
+/// `execute_withdrawal_batch`
+fn check_batch_shape(withdrawals: &[BatchWithdrawal], remaining_accounts: usize) -> Result<()> {
+    require!(!withdrawals.is_empty(), ErrorCode::EmptyBatch);
+    require!(
+        withdrawals.len() <= MAX_WITHDRAWAL_BATCH,
+        ErrorCode::BatchTooLarge
+    );
+    require!(
+        remaining_accounts == withdrawals.len() * BATCH_ACCOUNTS_PER_WITHDRAWAL,
+        ErrorCode::InvalidBatchAccounts
+    );
     
-    #[account(mut)]
-    pub authority: Signer<'info>,
+    for (i, item) in withdrawals.iter().enumerate() {
+        require!(
+            !withdrawals[..i].iter().any(|w| w.withdrawal_id == item.withdrawal_id),
+            ErrorCode::DuplicateWithdrawal
+        );
+    }
     
-    pub system_program: Program<'info, System>,
+    Ok(())
+}
+
+/// Verify every batched withdrawal, then mark them all executed and update
+/// gateway totals. Nothing is modified unless every item verifies.
+#[allow(clippy::too_many_arguments)]
+fn settle_batch(
+    gateway: &mut GatewayState,
+    mint_state: &mut MintState,
+    withdrawals: &[BatchWithdrawal],
+    requests: &mut [&mut WithdrawalRequestInfo],
+    nullifiers: &mut [&mut NullifierAccount],
+    mint: Pubkey,
+    vault_balance: u64,
+    now: i64,
+) -> Result<()> {
+    let mut total: u64 = 0;
+    
+    for ((item, request), nullifier_account) in
+        withdrawals.iter().zip(requests.iter()).zip(nullifiers.iter())
+    {
+        require!(
+            request.withdrawal_id == item.withdrawal_id,
+            ErrorCode::InvalidWithdrawalId
+        );
+        require!(!request.executed, ErrorCode::AlreadyExecuted);
+        require!(!nullifier_account.used, ErrorCode::NullifierUsed);
+        require_keys_eq!(request.mint, mint, ErrorCode::MintMismatch);
+        
+        verify_coordinator_signature(
+            item.withdrawal_id,
+            request.recipient,
+            request.amount,
+            request.nullifier,
+            item.deadline,
+            &item.coordinator_signature,
+            gateway,
+            now,
+        )?;
+        
+        total = total.checked_add(request.amount).ok_or(ErrorCode::Overflow)?;
+    }
+    
+    require_keys_eq!(mint_state.mint, mint, ErrorCode::MintMismatch);
+    release_withdrawals(gateway, mint_state, total, vault_balance)?;
+    
+    for (request, nullifier_account) in requests.iter_mut().zip(nullifiers.iter_mut()) {
+        request.executed = true;
+        nullifier_account.nullifier = request.nullifier;
+        nullifier_account.used = true;
+        nullifier_account.timestamp = now;
+    }
+    
+    Ok(())
+}
+
+/// Bridge fee on a deposit of `amount` at `fee_bps` basis points, rounded
+/// down
+pub fn bridge_fee(amount: u64, fee_bps: u16) -> u64 {
+    // At most `amount`, since fees are capped below 100%
+    (amount as u128 * fee_bps as u128 / 10_000) as u64
+}
+
+/// Count a deposit of `amount` into the mint's and the gateway's totals,
+/// net of the bridge fee, which the mint accrues separately. Returns the net
+/// amount and the fee. Nothing changes unless both can be updated.
+fn lock_deposit(gateway: &mut GatewayState, mint_state: &mut MintState, amount: u64) -> Result<(u64, u64)> {
+    let fee = bridge_fee(amount, gateway.bridge_fee);
+    let amount = amount.checked_sub(fee).ok_or(ErrorCode::Underflow)?;
+    
+    let total_locked = gateway
+        .total_locked
+        .checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+    let deposit_count = gateway
+        .deposit_count
+        .checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
+    let locked = mint_state.locked.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+    let mint_deposit_count = mint_state
+        .deposit_count
+        .checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
+    let fees = mint_state.fees.checked_add(fee).ok_or(ErrorCode::Overflow)?;
+    
+    gateway.total_locked = total_locked;
+    gateway.deposit_count = deposit_count;
+    mint_state.locked = locked;
+    mint_state.deposit_count = mint_deposit_count;
+    mint_state.fees = fees;
+    Ok((amount, fee))
+}
+
+/// Count `amount` paid out of a vault holding `vault_balance` as withdrawn
+/// in the mint's and the gateway's totals. Locked deposits pay first; the
+/// rest must fit in the vault's provided liquidity, so fees are never paid
+/// out. Nothing changes unless both can be updated.
+fn release_withdrawals(
+    gateway: &mut GatewayState,
+    mint_state: &mut MintState,
+    amount: u64,
+    vault_balance: u64,
+) -> Result<()> {
+    let from_locked = amount.min(mint_state.locked);
+    let from_liquidity = amount - from_locked;
+    require!(
+        from_liquidity <= available_liquidity(vault_balance, mint_state.locked.saturating_add(mint_state.fees)),
+        ErrorCode::InsufficientLiquidity
+    );
+    
+    let total_locked = gateway
+        .total_locked
+        .checked_sub(from_locked)
+        .ok_or(ErrorCode::Underflow)?;
+    let total_withdrawn = gateway
+        .total_withdrawn
+        .checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+    let locked = mint_state.locked - from_locked;
+    let withdrawn = mint_state.withdrawn.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+    
+    gateway.total_locked = total_locked;
+    gateway.total_withdrawn = total_withdrawn;
+    mint_state.locked = locked;
+    mint_state.withdrawn = withdrawn;
+    Ok(())
+}
+
+/// Part of a vault's balance free to pay withdrawals: whatever isn't
+/// `reserved` for the mint's locked deposits and uncollected fees
+pub fn available_liquidity(vault_balance: u64, reserved: u64) -> u64 {
+    vault_balance.saturating_sub(reserved)
+}
+
+/// Verify a refund authorization, then mark the deposit processed and
+/// release its amount from the gateway totals. Returns the amount to refund.
+fn settle_refund(
+    gateway: &mut GatewayState,
+    mint_state: &mut MintState,
+    deposit: &mut DepositInfo,
+    deposit_id: [u8; 32],
+    signature: &[u8; 65],
+    now: i64,
+) -> Result<u64> {
+    require!(deposit.deposit_id == deposit_id, ErrorCode::InvalidDepositId);
+    require!(!deposit.processed, ErrorCode::DepositAlreadyProcessed);
+    require_keys_eq!(mint_state.mint, deposit.mint, ErrorCode::MintMismatch);
+
+    verify_refund_signature(deposit, signature, gateway, now)?;
+
+    let total_locked = gateway
+        .total_locked
+        .checked_sub(deposit.amount)
+        .ok_or(ErrorCode::Underflow)?;
+    let locked = mint_state
+        .locked
+        .checked_sub(deposit.amount)
+        .ok_or(ErrorCode::Underflow)?;
+    gateway.total_locked = total_locked;
+    mint_state.locked = locked;
+    deposit.processed = true;
+
+    Ok(deposit.amount)
+}
+
+/// Verify the coordinator's word that a deposit was minted, then mark it
+/// processed so it can't be refunded as well
+fn settle_minted(
+    gateway: &GatewayState,
+    deposit: &mut DepositInfo,
+    deposit_id: [u8; 32],
+    signature: &[u8; 65],
+    now: i64,
+) -> Result<()> {
+    require!(deposit.deposit_id == deposit_id, ErrorCode::InvalidDepositId);
+    require!(!deposit.processed, ErrorCode::DepositAlreadyProcessed);
+
+    recover_coordinator(minted_message_hash(deposit_id), signature, gateway, now)?;
+
+    deposit.processed = true;
+    Ok(())
+}
+
+/// Set up a fresh gateway owned by `authority`
+fn init_gateway(
+    gateway: &mut GatewayState,
+    authority: Pubkey,
+    coordinator: Pubkey,
+    coordinator_eth: [u8; 20],
+    bump: u8,
+    timestamp: i64,
+) -> AdminEvent {
+    gateway.authority = authority;
+    gateway.coordinator = coordinator;
+    gateway.total_locked = 0;
+    gateway.total_withdrawn = 0;
+    gateway.deposit_count = 0;
+    gateway.withdrawal_count = 0;
+    gateway.paused = false;
+    gateway.bump = bump;
+    gateway.bridge_fee = DEFAULT_BRIDGE_FEE;
+    gateway.coordinator_eth = coordinator_eth;
+    gateway.previous_coordinator_eth = [0; 20];
+    gateway.previous_coordinator_valid_until = 0;
+    gateway.coordinator_overlap = DEFAULT_COORDINATOR_OVERLAP;
+    
+    AdminEvent::GatewayInitialized(GatewayInitialized {
+        authority,
+        coordinator,
+        coordinator_eth,
+        timestamp,
+    })
+}
+
+/// Replace the coordinator. Its old key keeps verifying for the gateway's
+/// `coordinator_overlap`, so authorizations already in flight still execute.
+fn update_coordinator(
+    gateway: &mut GatewayState,
+    new_coordinator: Pubkey,
+    new_coordinator_eth: [u8; 20],
+    timestamp: i64,
+) -> AdminEvent {
+    let old_coordinator = gateway.coordinator;
+    let old_coordinator_eth = gateway.coordinator_eth;
+    let old_coordinator_valid_until = timestamp.saturating_add(gateway.coordinator_overlap);
+    gateway.coordinator = new_coordinator;
+    gateway.coordinator_eth = new_coordinator_eth;
+    gateway.previous_coordinator_eth = old_coordinator_eth;
+    gateway.previous_coordinator_valid_until = old_coordinator_valid_until;
+    
+    AdminEvent::CoordinatorUpdated(CoordinatorUpdated {
+        old_coordinator,
+        new_coordinator,
+        old_coordinator_eth,
+        new_coordinator_eth,
+        old_coordinator_valid_until,
+        timestamp,
+    })
+}
+
+fn update_coordinator_overlap(gateway: &mut GatewayState, seconds: i64, timestamp: i64) -> Result<AdminEvent> {
+    require!(
+        (0..=MAX_COORDINATOR_OVERLAP).contains(&seconds),
+        ErrorCode::InvalidCoordinatorOverlap
+    );
+    
+    let old_overlap = gateway.coordinator_overlap;
+    gateway.coordinator_overlap = seconds;
+    
+    Ok(AdminEvent::CoordinatorOverlapUpdated(CoordinatorOverlapUpdated {
+        old_overlap,
+        new_overlap: seconds,
+        timestamp,
+    }))
+}
+
+fn update_paused(gateway: &mut GatewayState, paused: bool, triggered_by: Pubkey, timestamp: i64) -> AdminEvent {
+    gateway.paused = paused;
+    
+    if paused {
+        AdminEvent::EmergencyPause(EmergencyPause { triggered_by, timestamp })
+    } else {
+        AdminEvent::Unpaused(Unpaused { triggered_by, timestamp })
+    }
+}
+
+fn update_bridge_fee(gateway: &mut GatewayState, fee_bps: u16, timestamp: i64) -> Result<AdminEvent> {
+    require!(fee_bps <= MAX_BRIDGE_FEE, ErrorCode::FeeTooHigh);
+    
+    let old_fee = gateway.bridge_fee;
+    gateway.bridge_fee = fee_bps;
+    
+    Ok(AdminEvent::BridgeFeeUpdated(BridgeFeeUpdated {
+        old_fee,
+        new_fee: fee_bps,
+        timestamp,
+    }))
+}
+
+/// Whether `data` is a gateway account in the layout from before the bridge
+/// fee, owned by `authority`
+fn check_legacy_gateway(data: &[u8], authority: Pubkey) -> Result<()> {
+    require!(
+        data.len() == 8 + GatewayState::LEGACY_SIZE,
+        ErrorCode::GatewayAlreadyMigrated
+    );
+    require!(
+        data[..8] == *GatewayState::DISCRIMINATOR,
+        ErrorCode::InvalidGatewayAccount
+    );
+    // `authority` is the first field after the discriminator
+    require!(data[8..40] == *authority.as_ref(), ErrorCode::Unauthorized);
+    Ok(())
+}
+
+/// Fill the fields appended after `GatewayState::LEGACY_SIZE` in a gateway
+/// account just grown to the current size
+fn write_appended_gateway_fields(data: &mut [u8], coordinator_eth: [u8; 20]) {
+    let start = 8 + GatewayState::LEGACY_SIZE;
+    data[start..start + 2].copy_from_slice(&DEFAULT_BRIDGE_FEE.to_le_bytes());
+    data[start + 2..start + 22].copy_from_slice(&coordinator_eth);
+    // No previous coordinator key, valid until 0
+    data[start + 22..start + 50].fill(0);
+    data[start + 50..start + 58].copy_from_slice(&DEFAULT_COORDINATOR_OVERLAP.to_le_bytes());
+}
+
+/// Set up a freshly created `MintState`. New mints start with a one-token
+/// minimum deposit.
+fn register_mint_state(
+    mint_state: &mut MintState,
+    mint: Pubkey,
+    decimals: u8,
+    bump: u8,
+    vault: Pubkey,
+    authority: Pubkey,
+    timestamp: i64,
+) -> AdminEvent {
+    mint_state.mint = mint;
+    mint_state.locked = 0;
+    mint_state.withdrawn = 0;
+    mint_state.deposit_count = 0;
+    mint_state.fees = 0;
+    mint_state.min_deposit = default_min_deposit(decimals);
+    mint_state.bump = bump;
+    
+    AdminEvent::MintRegistered(MintRegistered {
+        authority,
+        mint,
+        vault,
+        min_deposit: mint_state.min_deposit,
+        timestamp,
+    })
+}
+
+fn update_min_deposit(mint_state: &mut MintState, min_deposit: u64, timestamp: i64) -> AdminEvent {
+    let old_min_deposit = mint_state.min_deposit;
+    mint_state.min_deposit = min_deposit;
+    
+    AdminEvent::MinDepositUpdated(MinDepositUpdated {
+        mint: mint_state.mint,
+        old_min_deposit,
+        new_min_deposit: min_deposit,
+        timestamp,
+    })
+}
+
+/// Deduct `amount` from the mint's collected fees. Fails without change if
+/// more is asked for than was collected, so locked deposits are never paid
+/// out as fees.
+fn take_fees(
+    mint_state: &mut MintState,
+    amount: u64,
+    authority: Pubkey,
+    destination: Pubkey,
+    timestamp: i64,
+) -> Result<AdminEvent> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    mint_state.fees = mint_state
+        .fees
+        .checked_sub(amount)
+        .ok_or(ErrorCode::InsufficientFees)?;
+    
+    Ok(AdminEvent::FeesWithdrawn(FeesWithdrawn {
+        authority,
+        mint: mint_state.mint,
+        destination,
+        amount,
+        remaining_fees: mint_state.fees,
+        timestamp,
+    }))
+}
+
+fn init_liquidity_pool(
+    pool: &mut LiquidityPoolState,
+    mint: Pubkey,
+    bump: u8,
+    authority: Pubkey,
+    timestamp: i64,
+) -> AdminEvent {
+    pool.mint = mint;
+    pool.total_liquidity = 0;
+    pool.bump = bump;
+    
+    AdminEvent::LiquidityPoolInitialized(LiquidityPoolInitialized {
+        authority,
+        mint,
+        timestamp,
+    })
+}
+
+fn generate_deposit_id(
+    sender: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+    target_chain_id: u64,
+    recipient: [u8; 32],
+    nonce: u64,
+) -> [u8; 32] {
+    let mut data = Vec::new();
+    data.extend_from_slice(sender.as_ref());
+    data.extend_from_slice(mint.as_ref());
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&target_chain_id.to_le_bytes());
+    data.extend_from_slice(&recipient);
+    data.extend_from_slice(&nonce.to_le_bytes());
+    
+    Keccak256::digest(&data).into()
+}
+
+fn generate_withdrawal_id(
+    recipient: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+    nullifier: [u8; 32],
+    nonce: u64,
+) -> [u8; 32] {
+    let mut data = Vec::new();
+    data.extend_from_slice(recipient.as_ref());
+    data.extend_from_slice(mint.as_ref());
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&nullifier);
+    data.extend_from_slice(&nonce.to_le_bytes());
+    
+    Keccak256::digest(&data).into()
+}
+
+// ============ Account Structures ============
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + GatewayState::SIZE,
+        seeds = [b"gateway"],
+        bump
+    )]
+    pub gateway: Account<'info, GatewayState>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
@@ -455,6 +1301,14 @@ pub struct Deposit<'info> {
     
     pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
+    
+    /// Only exists once the mint is registered
+    #[account(
+        mut,
+        seeds = [b"mint_state", mint.key().as_ref()],
+        bump = mint_state.bump,
+    )]
+    pub mint_state: Account<'info, MintState>,
 }
 
 #[derive(Accounts)]
@@ -534,7 +1388,97 @@ pub struct ExecuteWithdrawal<'info> {
     )]
     pub recipient_token: InterfaceAccount<'info, TokenAccount>,
     
-    pub token_program: Interface<'info, TokenInterface>,
+    pub token_program: Interface<'info, TokenInterface>,    
+    #[account(
+        mut,
+        seeds = [b"mint_state", mint.key().as_ref()],
+        bump = mint_state.bump,
+    )]
+    pub mint_state: Account<'info, MintState>,
+}
+
+/// Shared accounts for `execute_withdrawal_batch`; per-withdrawal accounts
+/// come through `remaining_accounts`
+#[derive(Accounts)]
+pub struct ExecuteWithdrawalBatch<'info> {
+    #[account(mut, seeds = [b"gateway"], bump = gateway.bump)]
+    pub gateway: Account<'info, GatewayState>,
+    
+    #[account(mut)]
+    pub executor: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_program: Interface<'info, TokenInterface>,    
+    #[account(
+        mut,
+        seeds = [b"mint_state", mint.key().as_ref()],
+        bump = mint_state.bump,
+    )]
+    pub mint_state: Account<'info, MintState>,
+}
+
+#[derive(Accounts)]
+pub struct RefundDeposit<'info> {
+    #[account(mut, seeds = [b"gateway"], bump = gateway.bump)]
+    pub gateway: Account<'info, GatewayState>,
+    
+    #[account(mut)]
+    pub deposit: Account<'info, DepositInfo>,
+    
+    /// Can be anyone (relayer); funds only ever go to the depositor
+    #[account(mut)]
+    pub executor: Signer<'info>,
+    
+    /// CHECK: Must be the original depositor
+    #[account(address = deposit.sender @ ErrorCode::InvalidRecipientAccount)]
+    pub sender: AccountInfo<'info>,
+    
+    #[account(address = deposit.mint @ ErrorCode::MintMismatch)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = sender,
+    )]
+    pub sender_token: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_program: Interface<'info, TokenInterface>,    
+    #[account(
+        mut,
+        seeds = [b"mint_state", mint.key().as_ref()],
+        bump = mint_state.bump,
+    )]
+    pub mint_state: Account<'info, MintState>,
+}
+
+#[derive(Accounts)]
+pub struct MarkDepositMinted<'info> {
+    #[account(seeds = [b"gateway"], bump = gateway.bump)]
+    pub gateway: Account<'info, GatewayState>,
+    
+    #[account(mut)]
+    pub deposit: Account<'info, DepositInfo>,
+    
+    /// Can be anyone (relayer); the coordinator's signature is the authority
+    pub executor: Signer<'info>,
 }
 
 #[derive(Accounts)]
@@ -543,7 +1487,7 @@ pub struct SetCoordinator<'info> {
         mut,
         seeds = [b"gateway"],
         bump = gateway.bump,
-        constraint = gateway.authority == authority.key()
+        constraint = gateway.authority == authority.key() @ ErrorCode::Unauthorized
     )]
     pub gateway: Account<'info, GatewayState>,
     
@@ -556,7 +1500,7 @@ pub struct SetPaused<'info> {
         mut,
         seeds = [b"gateway"],
         bump = gateway.bump,
-        constraint = gateway.authority == authority.key()
+        constraint = gateway.authority == authority.key() @ ErrorCode::Unauthorized
     )]
     pub gateway: Account<'info, GatewayState>,
     
@@ -564,49 +1508,294 @@ pub struct SetPaused<'info> {
 }
 
 #[derive(Accounts)]
-pub struct AddLiquidity<'info> {
-    #[account(seeds = [b"gateway"], bump = gateway.bump)]
-    pub gateway: Account<'info, GatewayState>,
-    
-    #[account(mut)]
-    pub provider: Signer<'info>,
-    
-    pub mint: InterfaceAccount<'info, Mint>,
-    
+pub struct SetCoordinatorOverlap<'info> {
     #[account(
         mut,
-        token::mint = mint,
-        token::authority = provider,
+        seeds = [b"gateway"],
+        bump = gateway.bump,
+        constraint = gateway.authority == authority.key() @ ErrorCode::Unauthorized
     )]
-    pub provider_token: InterfaceAccount<'info, TokenAccount>,
+    pub gateway: Account<'info, GatewayState>,
     
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetBridgeFee<'info> {
     #[account(
         mut,
-        seeds = [b"vault", mint.key().as_ref()],
-        bump,
-        token::mint = mint,
+        seeds = [b"gateway"],
+        bump = gateway.bump,
+        constraint = gateway.authority == authority.key() @ ErrorCode::Unauthorized
     )]
-    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub gateway: Account<'info, GatewayState>,
     
-    pub token_program: Interface<'info, TokenInterface>,
+    pub authority: Signer<'info>,
 }
 
-// ============ State Accounts ============
+#[derive(Accounts)]
+pub struct MigrateGateway<'info> {
+    /// CHECK: in the legacy layout, which `Account<GatewayState>` can't
+    /// load; owner, size, discriminator and authority are checked by hand
+    #[account(mut, seeds = [b"gateway"], bump)]
+    pub gateway: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
 
-#[account]
-pub struct GatewayState {
-    pub authority: Pubkey,
-    pub coordinator: Pubkey,
+#[derive(Accounts)]
+pub struct RegisterMint<'info> {
+    #[account(
+        seeds = [b"gateway"],
+        bump = gateway.bump,
+        constraint = gateway.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub gateway: Account<'info, GatewayState>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    /// Owned by the gateway PDA, which signs withdrawals from it
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = gateway,
+        token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + MintState::SIZE,
+        seeds = [b"mint_state", mint.key().as_ref()],
+        bump
+    )]
+    pub mint_state: Account<'info, MintState>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMinDeposit<'info> {
+    #[account(
+        seeds = [b"gateway"],
+        bump = gateway.bump,
+        constraint = gateway.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub gateway: Account<'info, GatewayState>,
+    
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"mint_state", mint.key().as_ref()],
+        bump = mint_state.bump,
+    )]
+    pub mint_state: Account<'info, MintState>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    #[account(
+        seeds = [b"gateway"],
+        bump = gateway.bump,
+        constraint = gateway.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub gateway: Account<'info, GatewayState>,
+    
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = mint,
+    )]
+    pub destination_token: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"mint_state", mint.key().as_ref()],
+        bump = mint_state.bump,
+    )]
+    pub mint_state: Account<'info, MintState>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct GetAvailableLiquidity<'info> {
+    #[account(seeds = [b"gateway"], bump = gateway.bump)]
+    pub gateway: Account<'info, GatewayState>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"vault", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(seeds = [b"mint_state", mint.key().as_ref()], bump = mint_state.bump)]
+    pub mint_state: Account<'info, MintState>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeLiquidityPool<'info> {
+    #[account(
+        seeds = [b"gateway"],
+        bump = gateway.bump,
+        constraint = gateway.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub gateway: Account<'info, GatewayState>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + LiquidityPoolState::SIZE,
+        seeds = [b"liquidity_pool", mint.key().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, LiquidityPoolState>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddLiquidity<'info> {
+    #[account(seeds = [b"gateway"], bump = gateway.bump)]
+    pub gateway: Account<'info, GatewayState>,
+    
+    #[account(
+        mut,
+        seeds = [b"liquidity_pool", mint.key().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, LiquidityPoolState>,
+    
+    #[account(mut)]
+    pub provider: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = provider,
+    )]
+    pub provider_token: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveLiquidity<'info> {
+    #[account(
+        seeds = [b"gateway"],
+        bump = gateway.bump,
+        constraint = gateway.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub gateway: Account<'info, GatewayState>,
+    
+    #[account(
+        mut,
+        seeds = [b"liquidity_pool", mint.key().as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, LiquidityPoolState>,
+    
+    pub authority: Signer<'info>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = mint,
+    )]
+    pub destination_token: InterfaceAccount<'info, TokenAccount>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// ============ State Accounts ============
+
+#[account]
+pub struct GatewayState {
+    pub authority: Pubkey,
+    pub coordinator: Pubkey,
+    /// Sums over every mint's `MintState`
     pub total_locked: u64,
     pub total_withdrawn: u64,
     pub deposit_count: u64,
     pub withdrawal_count: u64,
     pub paused: bool,
     pub bump: u8,
+    /// Fields from here on were appended after launch; `migrate_gateway`
+    /// grows older accounts to fit them
+    pub bridge_fee: u16, // basis points
+    /// Ethereum address of the coordinator's secp256k1 key; every
+    /// authorization must recover to it, or to the previous key below
+    pub coordinator_eth: [u8; 20],
+    /// Key replaced by the last `set_coordinator`, still accepted until
+    /// `previous_coordinator_valid_until` (unix seconds)
+    pub previous_coordinator_eth: [u8; 20],
+    pub previous_coordinator_valid_until: i64,
+    /// Seconds a replaced coordinator key keeps verifying
+    pub coordinator_overlap: i64,
 }
 
 impl GatewayState {
-    pub const SIZE: usize = 32 + 32 + 8 + 8 + 8 + 8 + 1 + 1;
+    pub const SIZE: usize = Self::LEGACY_SIZE + 2 + 20 + 20 + 8 + 8;
+    /// Size of gateways initialized before the bridge fee existed
+    pub const LEGACY_SIZE: usize = 32 + 32 + 8 + 8 + 8 + 8 + 1 + 1;
+
+    /// Whether an authorization signed by `signer` verifies at `now`
+    pub fn accepts_coordinator(&self, signer: [u8; 20], now: i64) -> bool {
+        signer == self.coordinator_eth
+            || (signer == self.previous_coordinator_eth && now < self.previous_coordinator_valid_until)
+    }
 }
 
 #[account]
@@ -619,6 +1808,7 @@ pub struct DepositInfo {
     pub recipient: [u8; 32],
     pub zcash_address: [u8; 32],
     pub timestamp: i64,
+    /// Refunded, or minted on the target chain
     pub processed: bool,
 }
 
@@ -652,8 +1842,122 @@ impl NullifierAccount {
     pub const SIZE: usize = 32 + 1 + 8;
 }
 
+/// Per-mint bridge totals. `GatewayState` keeps the sums across mints.
+/// Created by `register_mint`; a mint without one can't be deposited.
+#[account]
+pub struct MintState {
+    pub mint: Pubkey,
+    pub locked: u64,
+    pub withdrawn: u64,
+    pub deposit_count: u64,
+    /// Bridge fees collected and not yet withdrawn
+    pub fees: u64,
+    /// Smallest deposit accepted, in the mint's base units
+    pub min_deposit: u64,
+    pub bump: u8,
+}
+
+impl MintState {
+    pub const SIZE: usize = 32 + 8 + 8 + 8 + 8 + 8 + 1;
+
+    /// Whether a deposit of `amount` of `mint` is accepted against this state
+    pub fn check_deposit(&self, mint: Pubkey, amount: u64) -> Result<()> {
+        require!(
+            self.mint != Pubkey::default() && self.mint == mint,
+            ErrorCode::MintNotRegistered
+        );
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(amount >= self.min_deposit, ErrorCode::AmountTooSmall);
+        Ok(())
+    }
+}
+
+/// One whole token in base units of a mint with `decimals`
+pub fn default_min_deposit(decimals: u8) -> u64 {
+    10u64.saturating_pow(decimals as u32)
+}
+
+/// Per-mint liquidity provided through `add_liquidity`/`remove_liquidity`,
+/// so indexers and the coordinator can read the pool size directly
+#[account]
+pub struct LiquidityPoolState {
+    pub mint: Pubkey,
+    pub total_liquidity: u64,
+    pub bump: u8,
+}
+
+impl LiquidityPoolState {
+    pub const SIZE: usize = 32 + 8 + 1;
+
+    /// Returns the new total
+    pub fn add(&mut self, amount: u64) -> Result<u64> {
+        self.total_liquidity = self
+            .total_liquidity
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(self.total_liquidity)
+    }
+
+    /// Returns the new total; fails without change if the pool is too small
+    pub fn remove(&mut self, amount: u64) -> Result<u64> {
+        self.total_liquidity = self
+            .total_liquidity
+            .checked_sub(amount)
+            .ok_or(ErrorCode::InsufficientLiquidity)?;
+        Ok(self.total_liquidity)
+    }
+}
+
+/// One item of an `execute_withdrawal_batch` call
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BatchWithdrawal {
+    pub withdrawal_id: [u8; 32],
+    pub deadline: i64,
+    pub coordinator_signature: [u8; 65], // r(32) + s(32) + v(1)
+}
+
 // ============ Events ============
 
+/// Event for an admin state change. Every admin instruction emits exactly
+/// one, so off-chain indexers can follow configuration as well as funds.
+pub enum AdminEvent {
+    GatewayInitialized(GatewayInitialized),
+    CoordinatorUpdated(CoordinatorUpdated),
+    CoordinatorOverlapUpdated(CoordinatorOverlapUpdated),
+    EmergencyPause(EmergencyPause),
+    Unpaused(Unpaused),
+    BridgeFeeUpdated(BridgeFeeUpdated),
+    FeesWithdrawn(FeesWithdrawn),
+    MintRegistered(MintRegistered),
+    MinDepositUpdated(MinDepositUpdated),
+    LiquidityPoolInitialized(LiquidityPoolInitialized),
+}
+
+impl AdminEvent {
+    pub fn emit(self) {
+        match self {
+            AdminEvent::GatewayInitialized(event) => emit!(event),
+            AdminEvent::CoordinatorUpdated(event) => emit!(event),
+            AdminEvent::CoordinatorOverlapUpdated(event) => emit!(event),
+            AdminEvent::EmergencyPause(event) => emit!(event),
+            AdminEvent::Unpaused(event) => emit!(event),
+            AdminEvent::BridgeFeeUpdated(event) => emit!(event),
+            AdminEvent::FeesWithdrawn(event) => emit!(event),
+            AdminEvent::MintRegistered(event) => emit!(event),
+            AdminEvent::MinDepositUpdated(event) => emit!(event),
+            AdminEvent::LiquidityPoolInitialized(event) => emit!(event),
+        }
+    }
+}
+
+#[event]
+pub struct GatewayInitialized {
+    pub authority: Pubkey,
+    pub coordinator: Pubkey,
+    pub coordinator_eth: [u8; 20],
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct TokensLocked {
     pub deposit_id: [u8; 32],
@@ -666,6 +1970,15 @@ pub struct TokensLocked {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct BridgeFeeCollected {
+    pub deposit_id: [u8; 32],
+    pub mint: Pubkey,
+    pub fee: u64,
+    pub total_fees: u64,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct WithdrawalRequested {
     pub withdrawal_id: [u8; 32],
@@ -688,10 +2001,36 @@ pub struct TokensReleased {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct DepositRefunded {
+    pub deposit_id: [u8; 32],
+    pub sender: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DepositMinted {
+    pub deposit_id: [u8; 32],
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct CoordinatorUpdated {
     pub old_coordinator: Pubkey,
     pub new_coordinator: Pubkey,
+    pub old_coordinator_eth: [u8; 20],
+    pub new_coordinator_eth: [u8; 20],
+    /// Until when the old key still verifies
+    pub old_coordinator_valid_until: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CoordinatorOverlapUpdated {
+    pub old_overlap: i64,
+    pub new_overlap: i64,
     pub timestamp: i64,
 }
 
@@ -701,11 +2040,68 @@ pub struct EmergencyPause {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct Unpaused {
+    pub triggered_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BridgeFeeUpdated {
+    pub old_fee: u16,
+    pub new_fee: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeesWithdrawn {
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub remaining_fees: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MintRegistered {
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub min_deposit: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MinDepositUpdated {
+    pub mint: Pubkey,
+    pub old_min_deposit: u64,
+    pub new_min_deposit: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidityPoolInitialized {
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct LiquidityAdded {
     pub provider: Pubkey,
     pub mint: Pubkey,
     pub amount: u64,
+    pub total_liquidity: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidityRemoved {
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub total_liquidity: u64,
     pub timestamp: i64,
 }
 
@@ -742,4 +2138,759 @@ pub enum ErrorCode {
     
     #[msg("Arithmetic underflow")]
     Underflow,
-}
\ No newline at end of file
+    
+    #[msg("Withdrawal batch is empty")]
+    EmptyBatch,
+    
+    #[msg("Withdrawal batch too large")]
+    BatchTooLarge,
+    
+    #[msg("Invalid batch accounts")]
+    InvalidBatchAccounts,
+    
+    #[msg("Duplicate withdrawal in batch")]
+    DuplicateWithdrawal,
+    
+    #[msg("Mint mismatch")]
+    MintMismatch,
+    
+    #[msg("Invalid recipient token account")]
+    InvalidRecipientAccount,
+    
+    #[msg("Insufficient liquidity")]
+    InsufficientLiquidity,
+    
+    #[msg("Invalid deposit ID")]
+    InvalidDepositId,
+    
+    #[msg("Deposit already processed")]
+    DepositAlreadyProcessed,
+    
+    #[msg("Signer is not the gateway authority")]
+    Unauthorized,
+    
+    #[msg("Bridge fee too high")]
+    FeeTooHigh,
+    
+    #[msg("Not enough collected fees")]
+    InsufficientFees,
+    
+    #[msg("Mint is not registered")]
+    MintNotRegistered,
+    
+    #[msg("Not a gateway account")]
+    InvalidGatewayAccount,
+    
+    #[msg("Gateway already uses the current layout")]
+    GatewayAlreadyMigrated,
+    
+    #[msg("Coordinator overlap out of range")]
+    InvalidCoordinatorOverlap,
+    
+    #[msg("Coordinator authorization has expired")]
+    AuthorizationExpired,
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COORDINATOR_KEY: [u8; 32] = [0x11; 32];
+    const OTHER_KEY: [u8; 32] = [0x22; 32];
+    /// Later than every `now` the tests settle at
+    const DEADLINE: i64 = 1_000_000;
+
+    fn signing_address(key: [u8; 32]) -> [u8; 20] {
+        let secret = libsecp256k1::SecretKey::parse(&key).unwrap();
+        let public = libsecp256k1::PublicKey::from_secret_key(&secret).serialize();
+        eth_address(public[1..].try_into().unwrap())
+    }
+
+    /// r ‖ s ‖ recovery id (0 or 1), as the coordinator signs for Solana
+    fn sign(key: [u8; 32], message_hash: [u8; 32]) -> [u8; 65] {
+        let secret = libsecp256k1::SecretKey::parse(&key).unwrap();
+        let (signature, recovery_id) =
+            libsecp256k1::sign(&libsecp256k1::Message::parse(&message_hash), &secret);
+        let mut bytes = [0u8; 65];
+        bytes[..64].copy_from_slice(&signature.serialize());
+        bytes[64] = recovery_id.serialize();
+        bytes
+    }
+
+    fn gateway(total_locked: u64) -> GatewayState {
+        GatewayState {
+            authority: Pubkey::new_unique(),
+            coordinator: Pubkey::new_unique(),
+            total_locked,
+            total_withdrawn: 0,
+            deposit_count: 0,
+            withdrawal_count: 0,
+            paused: false,
+            bump: 255,
+            bridge_fee: 0,
+            coordinator_eth: signing_address(COORDINATOR_KEY),
+            previous_coordinator_eth: [0; 20],
+            previous_coordinator_valid_until: 0,
+            coordinator_overlap: DEFAULT_COORDINATOR_OVERLAP,
+        }
+    }
+
+    fn mint_state(mint: Pubkey, locked: u64) -> MintState {
+        MintState {
+            mint,
+            locked,
+            withdrawn: 0,
+            deposit_count: 0,
+            fees: 0,
+            min_deposit: 0,
+            bump: 254,
+        }
+    }
+
+    /// Withdrawal `id` of `amount`, authorized by the coordinator
+    fn request(id: u8, mint: Pubkey, amount: u64) -> (BatchWithdrawal, WithdrawalRequestInfo, NullifierAccount) {
+        let request = WithdrawalRequestInfo {
+            withdrawal_id: [id; 32],
+            recipient: Pubkey::new_unique(),
+            mint,
+            amount,
+            nullifier: [id.wrapping_add(100); 32],
+            timestamp: 0,
+            executed: false,
+        };
+        let item = BatchWithdrawal {
+            withdrawal_id: [id; 32],
+            deadline: DEADLINE,
+            coordinator_signature: sign(
+                COORDINATOR_KEY,
+                withdrawal_message_hash(request.withdrawal_id, request.recipient, amount, request.nullifier, DEADLINE),
+            ),
+        };
+        let nullifier = NullifierAccount {
+            nullifier: [0; 32],
+            used: false,
+            timestamp: 0,
+        };
+        (item, request, nullifier)
+    }
+
+    fn settle(
+        gateway: &mut GatewayState,
+        mint_state: &mut MintState,
+        batch: &mut [(BatchWithdrawal, WithdrawalRequestInfo, NullifierAccount)],
+        vault_balance: u64,
+    ) -> Result<()> {
+        settle_at(gateway, mint_state, batch, vault_balance, 42)
+    }
+
+    fn settle_at(
+        gateway: &mut GatewayState,
+        mint_state: &mut MintState,
+        batch: &mut [(BatchWithdrawal, WithdrawalRequestInfo, NullifierAccount)],
+        vault_balance: u64,
+        now: i64,
+    ) -> Result<()> {
+        let items: Vec<BatchWithdrawal> = batch.iter().map(|(item, _, _)| item.clone()).collect();
+        let (mut requests, mut nullifiers): (Vec<_>, Vec<_>) =
+            batch.iter_mut().map(|(_, r, n)| (r, n)).unzip();
+        let mint = mint_state.mint;
+        settle_batch(gateway, mint_state, &items, &mut requests, &mut nullifiers, mint, vault_balance, now)
+    }
+
+    #[test]
+    fn test_batch_settles_every_withdrawal() {
+        let mint = Pubkey::new_unique();
+        let mut gateway = gateway(10_000);
+        let mut mint_state = mint_state(mint, 10_000);
+        let mut batch = vec![request(1, mint, 1_000), request(2, mint, 2_500), request(3, mint, 500)];
+
+        check_batch_shape(
+            &batch.iter().map(|(item, _, _)| item.clone()).collect::<Vec<_>>(),
+            3 * BATCH_ACCOUNTS_PER_WITHDRAWAL,
+        )
+        .unwrap();
+        settle(&mut gateway, &mut mint_state, &mut batch, 10_000).unwrap();
+
+        assert_eq!(gateway.total_locked, 6_000);
+        assert_eq!(gateway.total_withdrawn, 4_000);
+        assert_eq!((mint_state.locked, mint_state.withdrawn), (6_000, 4_000));
+        for (_, request, nullifier) in &batch {
+            assert!(request.executed);
+            assert!(nullifier.used);
+            assert_eq!(nullifier.nullifier, request.nullifier);
+            assert_eq!(nullifier.timestamp, 42);
+        }
+    }
+
+    #[test]
+    fn test_invalid_signature_rolls_back_whole_batch() {
+        let mint = Pubkey::new_unique();
+        let mut gateway = gateway(10_000);
+        let mut mint_state = mint_state(mint, 10_000);
+        let mut batch = vec![request(1, mint, 1_000), request(2, mint, 2_500), request(3, mint, 500)];
+        // Out-of-range recovery id on the last item
+        batch[2].0.coordinator_signature[64] = 4;
+
+        assert!(settle(&mut gateway, &mut mint_state, &mut batch, 10_000).is_err());
+
+        // The valid items before it were not applied either
+        assert_eq!(gateway.total_locked, 10_000);
+        assert_eq!(gateway.total_withdrawn, 0);
+        assert_eq!((mint_state.locked, mint_state.withdrawn), (10_000, 0));
+        for (_, request, nullifier) in &batch {
+            assert!(!request.executed);
+            assert!(!nullifier.used);
+        }
+    }
+
+    #[test]
+    fn test_batch_signed_by_other_key_rolls_back_whole_batch() {
+        let mint = Pubkey::new_unique();
+        let mut gateway = gateway(10_000);
+        let mut mint_state = mint_state(mint, 10_000);
+        let mut batch = vec![request(1, mint, 1_000), request(2, mint, 2_500), request(3, mint, 500)];
+        // Recovers fine, just not to the coordinator
+        let (_, forged, _) = &batch[1];
+        batch[1].0.coordinator_signature = sign(
+            OTHER_KEY,
+            withdrawal_message_hash(forged.withdrawal_id, forged.recipient, forged.amount, forged.nullifier, DEADLINE),
+        );
+
+        assert!(settle(&mut gateway, &mut mint_state, &mut batch, 10_000).is_err());
+
+        assert_eq!((gateway.total_locked, gateway.total_withdrawn), (10_000, 0));
+        assert_eq!((mint_state.locked, mint_state.withdrawn), (10_000, 0));
+        for (_, request, nullifier) in &batch {
+            assert!(!request.executed);
+            assert!(!nullifier.used);
+        }
+    }
+
+    #[test]
+    fn test_expired_authorization_rolls_back_whole_batch() {
+        let mint = Pubkey::new_unique();
+        let mut gateway = gateway(10_000);
+        let mut mint_state = mint_state(mint, 10_000);
+        let mut batch = vec![request(1, mint, 1_000), request(2, mint, 2_500)];
+
+        let err = settle_at(&mut gateway, &mut mint_state, &mut batch, 10_000, DEADLINE + 1).unwrap_err();
+        assert_eq!(err, ErrorCode::AuthorizationExpired.into());
+        assert_eq!((mint_state.locked, mint_state.withdrawn), (10_000, 0));
+        assert!(batch.iter().all(|(_, request, _)| !request.executed));
+
+        // The deadline is signed, so a relayer can't push it back
+        batch[1].0.deadline += 1;
+        assert!(settle_at(&mut gateway, &mut mint_state, &mut batch, 10_000, DEADLINE).is_err());
+        batch[1].0.deadline -= 1;
+
+        // Still valid at the deadline itself
+        settle_at(&mut gateway, &mut mint_state, &mut batch, 10_000, DEADLINE).unwrap();
+        assert_eq!(mint_state.withdrawn, 3_500);
+    }
+
+    #[test]
+    fn test_previous_coordinator_accepted_only_during_overlap() {
+        let mint = Pubkey::new_unique();
+        let mut gateway = gateway(10_000);
+        let mut mint_state = mint_state(mint, 10_000);
+        // Authorized by the old key before the change
+        let mut in_flight = vec![request(1, mint, 1_000)];
+        let mut stale = vec![request(2, mint, 1_000)];
+        let mut deposit = deposit_info(1_000);
+        deposit.mint = mint;
+        let stale_refund = sign(COORDINATOR_KEY, refund_message_hash(&deposit));
+        let by_new_key = |id: u8| {
+            let (mut item, request, nullifier) = request(id, mint, 1_000);
+            item.coordinator_signature = sign(
+                OTHER_KEY,
+                withdrawal_message_hash(
+                    request.withdrawal_id, request.recipient, request.amount, request.nullifier, DEADLINE,
+                ),
+            );
+            vec![(item, request, nullifier)]
+        };
+
+        update_coordinator(&mut gateway, Pubkey::new_unique(), signing_address(OTHER_KEY), 1_000);
+        let valid_until = 1_000 + DEFAULT_COORDINATOR_OVERLAP;
+        assert_eq!(gateway.previous_coordinator_valid_until, valid_until);
+
+        // Both keys verify during the overlap
+        settle_at(&mut gateway, &mut mint_state, &mut in_flight, 10_000, valid_until - 1).unwrap();
+        settle_at(&mut gateway, &mut mint_state, &mut by_new_key(3), 10_000, valid_until - 1).unwrap();
+
+        // Only the new one after it
+        assert!(settle_at(&mut gateway, &mut mint_state, &mut stale, 10_000, valid_until).is_err());
+        assert!(!stale[0].1.executed);
+        assert!(settle_refund(&mut gateway, &mut mint_state, &mut deposit, [9; 32], &stale_refund, valid_until).is_err());
+        settle_at(&mut gateway, &mut mint_state, &mut by_new_key(4), 10_000, valid_until).unwrap();
+        assert_eq!(mint_state.withdrawn, 3_000);
+    }
+
+    #[test]
+    fn test_batch_shape_limits() {
+        let mint = Pubkey::new_unique();
+        let items = |ids: &[u8]| -> Vec<BatchWithdrawal> {
+            ids.iter().map(|&id| request(id, mint, 1).0).collect()
+        };
+
+        assert!(check_batch_shape(&[], 0).is_err());
+        assert!(check_batch_shape(&items(&[1, 2, 3, 4]), 12).is_ok());
+        assert!(check_batch_shape(&items(&[1, 2, 3, 4, 5]), 15).is_err());
+        // Missing accounts for the second withdrawal
+        assert!(check_batch_shape(&items(&[1, 2]), 3).is_err());
+        assert!(check_batch_shape(&items(&[1, 1]), 6).is_err());
+    }
+
+    fn deposit_info(amount: u64) -> DepositInfo {
+        DepositInfo {
+            deposit_id: [9; 32],
+            sender: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            amount,
+            target_chain_id: 8453,
+            recipient: [1; 32],
+            zcash_address: [2; 32],
+            timestamp: 0,
+            processed: false,
+        }
+    }
+
+    #[test]
+    fn test_refund_requires_valid_signature() {
+        let mut gateway = gateway(10_000);
+        let mut deposit = deposit_info(4_000);
+        let mut other = mint_state(Pubkey::new_unique(), 10_000);
+        let mut mint_state = mint_state(deposit.mint, 10_000);
+        let authorization = sign(COORDINATOR_KEY, refund_message_hash(&deposit));
+
+        let mut signature = authorization;
+        signature[64] = 4;
+        assert!(settle_refund(&mut gateway, &mut mint_state, &mut deposit, [9; 32], &signature, 42).is_err());
+        // Authorization for a different deposit
+        assert!(settle_refund(&mut gateway, &mut mint_state, &mut deposit, [8; 32], &authorization, 42).is_err());
+        // Another mint's totals
+        assert!(settle_refund(&mut gateway, &mut other, &mut deposit, [9; 32], &authorization, 42).is_err());
+
+        assert_eq!(gateway.total_locked, 10_000);
+        assert!(!deposit.processed);
+
+        assert_eq!(
+            settle_refund(&mut gateway, &mut mint_state, &mut deposit, [9; 32], &authorization, 42).unwrap(),
+            4_000
+        );
+        assert_eq!((gateway.total_locked, mint_state.locked), (6_000, 6_000));
+        assert!(deposit.processed);
+    }
+
+    #[test]
+    fn test_deposit_cannot_be_refunded_twice() {
+        let mut gateway = gateway(10_000);
+        let mut deposit = deposit_info(4_000);
+        let mut mint_state = mint_state(deposit.mint, 10_000);
+        let authorization = sign(COORDINATOR_KEY, refund_message_hash(&deposit));
+
+        settle_refund(&mut gateway, &mut mint_state, &mut deposit, [9; 32], &authorization, 42).unwrap();
+        assert!(settle_refund(&mut gateway, &mut mint_state, &mut deposit, [9; 32], &authorization, 42).is_err());
+        assert_eq!(gateway.total_locked, 6_000);
+
+        // Nor can one that was already bridged
+        let mut bridged = deposit_info(1_000);
+        bridged.mint = deposit.mint;
+        bridged.processed = true;
+        let authorization = sign(COORDINATOR_KEY, refund_message_hash(&bridged));
+        assert!(settle_refund(&mut gateway, &mut mint_state, &mut bridged, [9; 32], &authorization, 42).is_err());
+        assert_eq!((gateway.total_locked, mint_state.locked), (6_000, 6_000));
+    }
+
+    #[test]
+    fn test_refund_signed_by_other_key_rejected() {
+        let mut gateway = gateway(10_000);
+        let mut deposit = deposit_info(4_000);
+        let mut mint_state = mint_state(deposit.mint, 10_000);
+
+        // Addresses are Ethereum's: private key 1 is 0x7e5f…5bdf
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        assert_eq!(
+            signing_address(one),
+            [
+                0x7e, 0x5f, 0x45, 0x52, 0x09, 0x1a, 0x69, 0x12, 0x5d, 0x5d, 0xfc, 0xb7, 0xb8, 0xc2,
+                0x65, 0x90, 0x29, 0x39, 0x5b, 0xdf,
+            ]
+        );
+
+        // A well-formed signature over the right message, by the wrong key
+        let forged = sign(OTHER_KEY, refund_message_hash(&deposit));
+        assert!(settle_refund(&mut gateway, &mut mint_state, &mut deposit, [9; 32], &forged, 42).is_err());
+        // The coordinator's signature over another amount
+        let mut other = deposit_info(5_000);
+        other.mint = deposit.mint;
+        let misdirected = sign(COORDINATOR_KEY, refund_message_hash(&other));
+        assert!(settle_refund(&mut gateway, &mut mint_state, &mut deposit, [9; 32], &misdirected, 42).is_err());
+        assert_eq!((gateway.total_locked, mint_state.locked), (10_000, 10_000));
+        assert!(!deposit.processed);
+
+        // Still refundable once the coordinator itself signs
+        let authorization = sign(COORDINATOR_KEY, refund_message_hash(&deposit));
+        assert_eq!(
+            settle_refund(&mut gateway, &mut mint_state, &mut deposit, [9; 32], &authorization, 42).unwrap(),
+            4_000
+        );
+    }
+
+    #[test]
+    fn test_minted_deposit_cannot_be_refunded() {
+        let mut gateway = gateway(10_000);
+        let mut deposit = deposit_info(4_000);
+        let mut mint_state = mint_state(deposit.mint, 10_000);
+        let refund = sign(COORDINATOR_KEY, refund_message_hash(&deposit));
+
+        // Only the coordinator can mark it minted
+        let forged = sign(OTHER_KEY, minted_message_hash([9; 32]));
+        assert!(settle_minted(&gateway, &mut deposit, [9; 32], &forged, 42).is_err());
+        // A refund authorization doesn't double as one
+        assert!(settle_minted(&gateway, &mut deposit, [9; 32], &refund, 42).is_err());
+        assert!(!deposit.processed);
+
+        let minted = sign(COORDINATOR_KEY, minted_message_hash([9; 32]));
+        assert!(settle_minted(&gateway, &mut deposit, [8; 32], &minted, 42).is_err());
+        settle_minted(&gateway, &mut deposit, [9; 32], &minted, 42).unwrap();
+        assert!(deposit.processed);
+
+        // Its tokens stay locked behind the minted funds
+        assert!(settle_refund(&mut gateway, &mut mint_state, &mut deposit, [9; 32], &refund, 42).is_err());
+        assert_eq!((gateway.total_locked, mint_state.locked), (10_000, 10_000));
+        assert!(settle_minted(&gateway, &mut deposit, [9; 32], &minted, 42).is_err());
+    }
+
+    #[test]
+    fn test_liquidity_pool_tracks_adds_and_removes() {
+        let mut pool = LiquidityPoolState {
+            mint: Pubkey::new_unique(),
+            total_liquidity: 0,
+            bump: 255,
+        };
+
+        assert_eq!(pool.add(5_000).unwrap(), 5_000);
+        assert_eq!(pool.add(2_500).unwrap(), 7_500);
+        assert_eq!(pool.remove(3_000).unwrap(), 4_500);
+
+        // Over-removal fails and leaves the balance untouched
+        assert!(pool.remove(4_501).is_err());
+        assert_eq!(pool.total_liquidity, 4_500);
+        assert_eq!(pool.remove(4_500).unwrap(), 0);
+
+        pool.total_liquidity = u64::MAX;
+        assert!(pool.add(1).is_err());
+        assert_eq!(pool.total_liquidity, u64::MAX);
+    }
+
+    #[test]
+    fn test_available_liquidity_tracks_deposits_and_withdrawals() {
+        let mint = Pubkey::new_unique();
+        let mut gateway = gateway(0);
+        let mut mint_state = mint_state(mint, 0);
+        let mut vault: u64 = 0;
+        assert_eq!(available_liquidity(vault, mint_state.locked), 0);
+
+        // Deposits land in the vault but back bridged funds
+        for amount in [4_000, 6_000] {
+            lock_deposit(&mut gateway, &mut mint_state, amount).unwrap();
+            vault += amount;
+        }
+        assert_eq!(available_liquidity(vault, mint_state.locked), 0);
+
+        // Provided liquidity is what's available
+        vault += 5_000;
+        assert_eq!(available_liquidity(vault, mint_state.locked), 5_000);
+
+        let mut batch = vec![request(1, mint, 1_000), request(2, mint, 2_500)];
+        settle(&mut gateway, &mut mint_state, &mut batch, vault).unwrap();
+        vault -= 3_500;
+        assert_eq!((vault, mint_state.locked), (11_500, 6_500));
+        assert_eq!(available_liquidity(vault, mint_state.locked), 5_000);
+
+        // A vault short of its locked amount has nothing available
+        assert_eq!(available_liquidity(1_000, mint_state.locked), 0);
+    }
+
+    #[test]
+    fn test_withdrawals_beyond_deposits_paid_from_liquidity() {
+        let mint = Pubkey::new_unique();
+        let mut gateway = gateway(0);
+        gateway.bridge_fee = DEFAULT_BRIDGE_FEE;
+        let mut mint_state = mint_state(mint, 0);
+
+        // 997 locked and 3 in fees, plus 5_000 provided by LPs
+        lock_deposit(&mut gateway, &mut mint_state, 1_000).unwrap();
+        let mut vault: u64 = 1_000 + 5_000;
+
+        // Bridged in from elsewhere: the deposit pays what it can, the pool
+        // the rest
+        let mut batch = vec![request(1, mint, 3_997)];
+        settle(&mut gateway, &mut mint_state, &mut batch, vault).unwrap();
+        vault -= 3_997;
+        assert_eq!((mint_state.locked, mint_state.withdrawn), (0, 3_997));
+        assert_eq!((gateway.total_locked, gateway.total_withdrawn), (0, 3_997));
+        assert_eq!(available_liquidity(vault, mint_state.locked + mint_state.fees), 2_000);
+
+        // Fees are not liquidity
+        let mut batch = vec![request(2, mint, 2_001)];
+        assert!(settle(&mut gateway, &mut mint_state, &mut batch, vault).is_err());
+        assert_eq!((mint_state.withdrawn, mint_state.fees), (3_997, 3));
+        assert!(!batch[0].1.executed);
+
+        let mut batch = vec![request(3, mint, 2_000)];
+        settle(&mut gateway, &mut mint_state, &mut batch, vault).unwrap();
+        vault -= 2_000;
+        assert_eq!(vault, mint_state.fees);
+        assert_eq!((mint_state.locked, gateway.total_locked), (0, 0));
+    }
+
+    #[test]
+    fn test_deposit_fee_collected_and_withdrawn_separately() {
+        let mint = Pubkey::new_unique();
+        let mut gateway = gateway(0);
+        gateway.bridge_fee = DEFAULT_BRIDGE_FEE;
+        let mut mint_state = mint_state(mint, 0);
+        let mut vault: u64 = 0;
+
+        // 0.3% withheld; only the rest is bridged
+        assert_eq!(lock_deposit(&mut gateway, &mut mint_state, 1_000_000).unwrap(), (997_000, 3_000));
+        vault += 1_000_000;
+        // Rounded down
+        assert_eq!(lock_deposit(&mut gateway, &mut mint_state, 1_000_333).unwrap(), (997_333, 3_000));
+        vault += 1_000_333;
+        assert_eq!((mint_state.locked, mint_state.fees), (1_994_333, 6_000));
+        assert_eq!(gateway.total_locked, 1_994_333);
+        assert_eq!(available_liquidity(vault, mint_state.locked + mint_state.fees), 0);
+
+        // Collected fees can be withdrawn in parts, never beyond what was
+        // collected, so locked deposits stay put
+        let (authority, destination) = (Pubkey::new_unique(), Pubkey::new_unique());
+        match take_fees(&mut mint_state, 5_999, authority, destination, 20).unwrap() {
+            AdminEvent::FeesWithdrawn(event) => {
+                assert_eq!((event.mint, event.destination), (mint, destination));
+                assert_eq!((event.amount, event.remaining_fees), (5_999, 1));
+            }
+            _ => panic!("expected FeesWithdrawn"),
+        }
+        vault -= 5_999;
+        assert!(take_fees(&mut mint_state, 2, authority, destination, 21).is_err());
+        assert!(take_fees(&mut mint_state, 0, authority, destination, 21).is_err());
+        assert_eq!((mint_state.locked, mint_state.fees), (1_994_333, 1));
+        assert_eq!(gateway.total_locked, 1_994_333);
+        assert_eq!(vault, mint_state.locked + mint_state.fees);
+
+        // Withdrawals pay out the net amount locked, fees untouched
+        let mut batch = vec![request(1, mint, 1_994_333)];
+        settle(&mut gateway, &mut mint_state, &mut batch, vault).unwrap();
+        assert_eq!((mint_state.locked, mint_state.fees), (0, 1));
+
+        // No fee, nothing withheld
+        gateway.bridge_fee = 0;
+        assert_eq!(lock_deposit(&mut gateway, &mut mint_state, 1_000_000).unwrap(), (1_000_000, 0));
+        assert_eq!(bridge_fee(u64::MAX, MAX_BRIDGE_FEE), u64::MAX / 100);
+    }
+
+    #[test]
+    fn test_min_deposit_scales_with_mint_decimals() {
+        // 9 decimals, like wrapped SOL: the old 1_000_000 floor let 0.001 through
+        let wsol = Pubkey::new_unique();
+        let mut wsol_state = mint_state(Pubkey::default(), 0);
+        register_mint_state(&mut wsol_state, wsol, 9, 250, Pubkey::new_unique(), Pubkey::new_unique(), 29);
+        assert_eq!((wsol_state.mint, wsol_state.min_deposit), (wsol, 1_000_000_000));
+        assert!(wsol_state.check_deposit(wsol, 1_000_000).is_err());
+        assert!(wsol_state.check_deposit(wsol, 1_000_000_000).is_ok());
+
+        // Lowered to 0.01
+        match update_min_deposit(&mut wsol_state, 10_000_000, 30) {
+            AdminEvent::MinDepositUpdated(event) => {
+                assert_eq!(event.mint, wsol);
+                assert_eq!((event.old_min_deposit, event.new_min_deposit), (1_000_000_000, 10_000_000));
+            }
+            _ => panic!("expected MinDepositUpdated"),
+        }
+        assert!(wsol_state.check_deposit(wsol, 9_999_999).is_err());
+        assert!(wsol_state.check_deposit(wsol, 10_000_000).is_ok());
+
+        // 2 decimals: the old floor demanded 10,000 whole tokens
+        let cents = Pubkey::new_unique();
+        let mut cents_state = mint_state(Pubkey::default(), 0);
+        register_mint_state(&mut cents_state, cents, 2, 249, Pubkey::new_unique(), Pubkey::new_unique(), 30);
+        assert_eq!(cents_state.min_deposit, 100);
+        assert!(cents_state.check_deposit(cents, 99).is_err());
+        assert!(cents_state.check_deposit(cents, 100).is_ok());
+
+        update_min_deposit(&mut cents_state, 500, 31);
+        assert!(cents_state.check_deposit(cents, 499).is_err());
+        assert!(cents_state.check_deposit(cents, 500).is_ok());
+
+        // Zero is never a deposit, whatever the minimum
+        update_min_deposit(&mut cents_state, 0, 32);
+        assert!(cents_state.check_deposit(cents, 0).is_err());
+        assert!(cents_state.check_deposit(cents, 1).is_ok());
+
+        assert_eq!(default_min_deposit(6), 1_000_000);
+        assert_eq!(default_min_deposit(30), u64::MAX);
+    }
+
+    #[test]
+    fn test_deposits_need_registered_mint() {
+        let (mint, vault, authority) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+
+        // Before registration there is no state for the mint
+        let mut state = mint_state(Pubkey::default(), 0);
+        assert!(state.check_deposit(mint, 1_000_000).is_err());
+        assert!(state.check_deposit(Pubkey::default(), 1_000_000).is_err());
+
+        match register_mint_state(&mut state, mint, 6, 251, vault, authority, 40) {
+            AdminEvent::MintRegistered(event) => {
+                assert_eq!((event.mint, event.vault, event.authority), (mint, vault, authority));
+                assert_eq!((event.min_deposit, event.timestamp), (1_000_000, 40));
+            }
+            _ => panic!("expected MintRegistered"),
+        }
+        assert_eq!((state.mint, state.bump, state.locked, state.fees), (mint, 251, 0, 0));
+        assert!(state.check_deposit(mint, 1_000_000).is_ok());
+
+        // Registering one mint doesn't admit another
+        assert!(state.check_deposit(Pubkey::new_unique(), 1_000_000).is_err());
+    }
+
+    #[test]
+    fn test_mints_tracked_independently() {
+        let (usdc, wsol) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut gateway = gateway(0);
+        let mut usdc_state = mint_state(usdc, 0);
+        let mut wsol_state = mint_state(wsol, 0);
+
+        lock_deposit(&mut gateway, &mut usdc_state, 4_000).unwrap();
+        lock_deposit(&mut gateway, &mut usdc_state, 1_000).unwrap();
+        lock_deposit(&mut gateway, &mut wsol_state, 9_000).unwrap();
+        assert_eq!((usdc_state.locked, usdc_state.deposit_count), (5_000, 2));
+        assert_eq!((wsol_state.locked, wsol_state.deposit_count), (9_000, 1));
+        assert_eq!((gateway.total_locked, gateway.deposit_count), (14_000, 3));
+
+        let mut batch = vec![request(1, usdc, 3_000)];
+        settle(&mut gateway, &mut usdc_state, &mut batch, 5_000).unwrap();
+        assert_eq!((usdc_state.locked, usdc_state.withdrawn), (2_000, 3_000));
+        assert_eq!((wsol_state.locked, wsol_state.withdrawn), (9_000, 0));
+        assert_eq!((gateway.total_locked, gateway.total_withdrawn), (11_000, 3_000));
+
+        // A mint can't pay out more than its vault holds, even though the
+        // gateway as a whole holds enough
+        let mut batch = vec![request(2, usdc, 2_001)];
+        assert!(settle(&mut gateway, &mut usdc_state, &mut batch, 2_000).is_err());
+        assert_eq!((usdc_state.locked, gateway.total_locked), (2_000, 11_000));
+
+        // Nor settle against another mint's totals
+        let mut batch = vec![request(3, usdc, 1_000)];
+        assert!(settle(&mut gateway, &mut wsol_state, &mut batch, 9_000).is_err());
+        assert_eq!(wsol_state.locked, 9_000);
+    }
+
+    #[test]
+    fn test_legacy_gateway_migrates_to_current_layout() {
+        let authority = Pubkey::new_unique();
+        let mut current = gateway(12_345);
+        current.authority = authority;
+        current.deposit_count = 7;
+        current.paused = true;
+
+        // Pre-fee accounts are the current layout without the appended fields
+        let mut data = Vec::new();
+        current.try_serialize(&mut data).unwrap();
+        data.truncate(8 + GatewayState::LEGACY_SIZE);
+        assert!(GatewayState::try_deserialize(&mut data.as_slice()).is_err());
+
+        assert!(check_legacy_gateway(&data, Pubkey::new_unique()).is_err());
+        let mut other = data.clone();
+        other[0] ^= 1;
+        assert!(check_legacy_gateway(&other, authority).is_err());
+        check_legacy_gateway(&data, authority).unwrap();
+
+        data.resize(8 + GatewayState::SIZE, 0);
+        write_appended_gateway_fields(&mut data, [0xc0; 20]);
+        let migrated = GatewayState::try_deserialize(&mut data.as_slice()).unwrap();
+        assert_eq!((migrated.authority, migrated.coordinator), (authority, current.coordinator));
+        assert_eq!((migrated.total_locked, migrated.deposit_count), (12_345, 7));
+        assert_eq!((migrated.paused, migrated.bump), (true, 255));
+        assert_eq!((migrated.bridge_fee, migrated.coordinator_eth), (DEFAULT_BRIDGE_FEE, [0xc0; 20]));
+        assert_eq!((migrated.previous_coordinator_eth, migrated.previous_coordinator_valid_until), ([0; 20], 0));
+        assert_eq!(migrated.coordinator_overlap, DEFAULT_COORDINATOR_OVERLAP);
+
+        // Only once
+        assert!(check_legacy_gateway(&data, authority).is_err());
+    }
+
+    #[test]
+    fn test_every_admin_action_emits_event() {
+        let authority = Pubkey::new_unique();
+        let coordinator = Pubkey::new_unique();
+        let mut gateway = gateway(0);
+
+        match init_gateway(&mut gateway, authority, coordinator, [0xc0; 20], 254, 10) {
+            AdminEvent::GatewayInitialized(event) => {
+                assert_eq!((event.authority, event.coordinator, event.timestamp), (authority, coordinator, 10));
+                assert_eq!(event.coordinator_eth, [0xc0; 20]);
+            }
+            _ => panic!("expected GatewayInitialized"),
+        }
+        assert_eq!((gateway.authority, gateway.bump), (authority, 254));
+        assert_eq!(gateway.bridge_fee, DEFAULT_BRIDGE_FEE);
+
+        let next = Pubkey::new_unique();
+        match update_coordinator(&mut gateway, next, [0xc1; 20], 11) {
+            AdminEvent::CoordinatorUpdated(event) => {
+                assert_eq!((event.old_coordinator, event.new_coordinator), (coordinator, next));
+                assert_eq!((event.old_coordinator_eth, event.new_coordinator_eth), ([0xc0; 20], [0xc1; 20]));
+                assert_eq!(event.old_coordinator_valid_until, 11 + DEFAULT_COORDINATOR_OVERLAP);
+            }
+            _ => panic!("expected CoordinatorUpdated"),
+        }
+        assert_eq!((gateway.coordinator, gateway.coordinator_eth), (next, [0xc1; 20]));
+
+        assert!(matches!(
+            update_coordinator_overlap(&mut gateway, 600, 11).unwrap(),
+            AdminEvent::CoordinatorOverlapUpdated(CoordinatorOverlapUpdated {
+                old_overlap: DEFAULT_COORDINATOR_OVERLAP,
+                new_overlap: 600,
+                timestamp: 11,
+            })
+        ));
+        assert!(update_coordinator_overlap(&mut gateway, MAX_COORDINATOR_OVERLAP + 1, 11).is_err());
+        assert!(update_coordinator_overlap(&mut gateway, -1, 11).is_err());
+        assert_eq!(gateway.coordinator_overlap, 600);
+
+        assert!(matches!(
+            update_paused(&mut gateway, true, authority, 12),
+            AdminEvent::EmergencyPause(EmergencyPause { triggered_by, timestamp: 12 }) if triggered_by == authority
+        ));
+        assert!(gateway.paused);
+        // Unpausing is announced too
+        assert!(matches!(
+            update_paused(&mut gateway, false, authority, 13),
+            AdminEvent::Unpaused(Unpaused { triggered_by, timestamp: 13 }) if triggered_by == authority
+        ));
+        assert!(!gateway.paused);
+
+        assert!(matches!(
+            update_bridge_fee(&mut gateway, 50, 14).unwrap(),
+            AdminEvent::BridgeFeeUpdated(BridgeFeeUpdated { old_fee: DEFAULT_BRIDGE_FEE, new_fee: 50, timestamp: 14 })
+        ));
+        assert!(update_bridge_fee(&mut gateway, MAX_BRIDGE_FEE + 1, 14).is_err());
+        assert_eq!(gateway.bridge_fee, 50);
+
+        let mint = Pubkey::new_unique();
+        let mut pool = LiquidityPoolState {
+            mint: Pubkey::default(),
+            total_liquidity: 7,
+            bump: 0,
+        };
+        assert!(matches!(
+            init_liquidity_pool(&mut pool, mint, 253, authority, 14),
+            AdminEvent::LiquidityPoolInitialized(event) if event.mint == mint && event.authority == authority
+        ));
+        assert_eq!((pool.mint, pool.total_liquidity, pool.bump), (mint, 0, 253));
+    }
+}
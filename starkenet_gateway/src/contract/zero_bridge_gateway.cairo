@@ -253,10 +253,13 @@ use starknet::{
         fn execute_withdrawal(
             ref self: ContractState,
             withdrawal_id: felt252,
+            deadline: u64,
             coordinator_signature_r: felt252,
             coordinator_signature_s: felt252,
         ) -> bool {
             assert(!self.paused.read(), 'Bridge paused');
+            // Past it the coordinator releases the liquidity this withdrawal held
+            assert(get_block_timestamp() <= deadline, 'Authorization expired');
 
             let request = self.withdrawal_requests.read(withdrawal_id);
             assert(request.withdrawal_id != 0, 'Withdrawal not found');
@@ -273,6 +276,7 @@ use starknet::{
                     request.token,
                     request.amount,
                     request.nullifier,
+                    deadline,
                     coordinator_signature_r,
                     coordinator_signature_s,
                 );
@@ -513,6 +517,7 @@ use starknet::{
             token: ContractAddress,
             amount: u256,
             nullifier: felt252,
+            deadline: u64,
             r: felt252,
             s: felt252,
         ) {
@@ -523,6 +528,7 @@ use starknet::{
             data.append(amount.low.into());
             data.append(amount.high.into());
             data.append(nullifier);
+            data.append(deadline.into());
 
             let hash = poseidon_hash_span(data.span());
             let coordinator = self.coordinator.read();
@@ -25,6 +25,7 @@ pub trait IZeroBridgeGateway<TContractState> {
     fn execute_withdrawal(
         ref self: TContractState,
         withdrawal_id: felt252,
+        deadline: u64,
         coordinator_signature_r: felt252,
         coordinator_signature_s: felt252,
     ) -> bool;
@@ -0,0 +1,182 @@
+// zcash-coordinator/src/amount.rs
+//! Token amounts tagged with their decimals
+//!
+//! Raw integers travel in different units on every chain (wei, lamports,
+//! yoctoNEAR, uosmo, zatoshis). `Amount` carries its precision with it so
+//! values are only combined or compared after an explicit, checked rescale.
+
+use anyhow::{Context, Result};
+use std::fmt;
+
+/// An amount of some token in base units at `decimals` precision
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Amount {
+    raw: u128,
+    decimals: u8,
+}
+
+impl Amount {
+    pub fn new(raw: u128, decimals: u8) -> Self {
+        Self { raw, decimals }
+    }
+
+    /// Amount from a `u64` as stored in the database and signed over
+    pub fn from_u64(raw: u64, decimals: u8) -> Self {
+        Self::new(raw as u128, decimals)
+    }
+
+    pub fn zero(decimals: u8) -> Self {
+        Self::new(0, decimals)
+    }
+
+    pub fn raw(&self) -> u128 {
+        self.raw
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.raw == 0
+    }
+
+    /// Base units as a `u64`, failing rather than truncating
+    pub fn to_u64(&self) -> Result<u64> {
+        u64::try_from(self.raw).with_context(|| format!("Amount {} does not fit in u64", self))
+    }
+
+    /// The same value at `decimals` precision.
+    ///
+    /// Fails if scaling down would drop a non-zero remainder, or scaling up
+    /// would overflow, rather than silently yielding a different amount.
+    pub fn rescale(&self, decimals: u8) -> Result<Self> {
+        if decimals == self.decimals {
+            return Ok(*self);
+        }
+
+        let diff = (self.decimals as i32 - decimals as i32).unsigned_abs();
+        let factor = 10u128
+            .checked_pow(diff)
+            .context("Decimals difference too large")?;
+
+        let raw = if decimals > self.decimals {
+            self.raw
+                .checked_mul(factor)
+                .context("Amount overflows when scaling to destination decimals")?
+        } else {
+            if self.raw % factor != 0 {
+                anyhow::bail!(
+                    "Amount {} loses precision converting from {} to {} decimals",
+                    self.raw,
+                    self.decimals,
+                    decimals
+                );
+            }
+            self.raw / factor
+        };
+
+        Ok(Self::new(raw, decimals))
+    }
+
+    pub fn checked_add(&self, other: Amount) -> Result<Self> {
+        self.same_decimals(&other)?;
+        let raw = self.raw.checked_add(other.raw).context("Amount overflow")?;
+        Ok(Self::new(raw, self.decimals))
+    }
+
+    pub fn checked_sub(&self, other: Amount) -> Result<Self> {
+        self.same_decimals(&other)?;
+        let raw = self
+            .raw
+            .checked_sub(other.raw)
+            .with_context(|| format!("Amount {} is less than {}", self, other))?;
+        Ok(Self::new(raw, self.decimals))
+    }
+
+    /// Compare two amounts, which must be at the same precision
+    pub fn covers(&self, other: &Amount) -> Result<bool> {
+        self.same_decimals(other)?;
+        Ok(self.raw >= other.raw)
+    }
+
+    fn same_decimals(&self, other: &Amount) -> Result<()> {
+        if self.decimals != other.decimals {
+            anyhow::bail!(
+                "Cannot combine amounts at {} and {} decimals without rescaling",
+                self.decimals,
+                other.decimals
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Whole units with trailing fractional zeros trimmed, e.g. `1.5`
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(unit) = 10u128.checked_pow(self.decimals as u32) else {
+            return write!(f, "{}e-{}", self.raw, self.decimals);
+        };
+
+        let whole = self.raw / unit;
+        let fraction = self.raw % unit;
+        if fraction == 0 {
+            return write!(f, "{}", whole);
+        }
+
+        let digits = format!("{:0width$}", fraction, width = self.decimals as usize);
+        write!(f, "{}.{}", whole, digits.trim_end_matches('0'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rescale_up_and_down() {
+        // 1 USDC at 6 decimals -> 18 decimals and back
+        let usdc = Amount::from_u64(1_000_000, 6);
+        let wide = usdc.rescale(18).unwrap();
+        assert_eq!(wide, Amount::new(1_000_000_000_000_000_000, 18));
+        assert_eq!(wide.rescale(6).unwrap(), usdc);
+        assert_eq!(usdc.rescale(6).unwrap(), usdc);
+
+        // Sub-unit dust would be lost at 6 decimals
+        assert!(Amount::new(1_000_000_000_000_001, 18).rescale(6).is_err());
+    }
+
+    #[test]
+    fn test_overflow() {
+        // Fits u128 but not u64
+        let big = Amount::from_u64(1, 0).rescale(20).unwrap();
+        assert!(big.to_u64().is_err());
+        assert_eq!(Amount::from_u64(u64::MAX, 8).to_u64().unwrap(), u64::MAX);
+
+        assert!(Amount::new(u128::MAX, 0).rescale(1).is_err());
+        assert!(Amount::from_u64(1, 0).rescale(39).is_err());
+        assert!(Amount::new(u128::MAX, 6).checked_add(Amount::new(1, 6)).is_err());
+        assert!(Amount::new(1, 6).checked_sub(Amount::new(2, 6)).is_err());
+    }
+
+    #[test]
+    fn test_mixed_decimals_rejected() {
+        let zat = Amount::from_u64(100, 8);
+        let wei = Amount::from_u64(100, 18);
+        assert!(zat.checked_add(wei).is_err());
+        assert!(zat.checked_sub(wei).is_err());
+        assert!(zat.covers(&wei).is_err());
+
+        assert_eq!(zat.checked_add(zat).unwrap(), Amount::from_u64(200, 8));
+        assert!(zat.covers(&Amount::from_u64(99, 8)).unwrap());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Amount::from_u64(1_500_000, 6).to_string(), "1.5");
+        assert_eq!(Amount::from_u64(2_000_000, 6).to_string(), "2");
+        assert_eq!(Amount::from_u64(1, 8).to_string(), "0.00000001");
+        assert_eq!(Amount::from_u64(42, 0).to_string(), "42");
+    }
+}
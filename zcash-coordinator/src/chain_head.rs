@@ -0,0 +1,80 @@
+// zcash-coordinator/src/chain_head.rs
+//! Source-chain head lookups for deposit finality
+//! FOCUSED: Only asks "how deep is this block now"; event listening stays
+//! with the relayers
+
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde_json::{json, Value};
+
+use crate::config::ChainConfig;
+
+#[derive(Clone, Default)]
+pub struct ChainHeadClient {
+    client: Client,
+}
+
+impl ChainHeadClient {
+    pub fn new() -> Self {
+        Self {
+            client: Client::new(),
+        }
+    }
+
+    /// Latest block on `chain`, or `None` for chains whose relayers only
+    /// report already-final events (non-EVM finality models)
+    pub async fn latest_block(&self, chain: &ChainConfig) -> Result<Option<u64>> {
+        if !chain.chain_type.is_evm() {
+            return Ok(None);
+        }
+
+        let payload = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_blockNumber",
+            "params": []
+        });
+
+        let response: Value = self.client
+            .post(&chain.rpc_url)
+            .json(&payload)
+            .send()
+            .await
+            .with_context(|| format!("eth_blockNumber request to chain {} failed", chain.chain_id))?
+            .json()
+            .await?;
+
+        let hex = response
+            .get("result")
+            .and_then(|r| r.as_str())
+            .context("No result in eth_blockNumber response")?;
+
+        let height = u64::from_str_radix(hex.trim_start_matches("0x"), 16)
+            .context("Invalid block number")?;
+
+        Ok(Some(height))
+    }
+}
+
+/// Whether a block at `block_number` has `confirmations` blocks on top of
+/// it (counting itself) given the current `head`
+pub fn has_confirmations(head: u64, block_number: u64, confirmations: u32) -> bool {
+    head >= block_number && head - block_number + 1 >= confirmations as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_has_confirmations() {
+        // Inclusion block counts as the first confirmation
+        assert!(has_confirmations(100, 100, 1));
+        assert!(!has_confirmations(100, 100, 2));
+        assert!(has_confirmations(111, 100, 12));
+        assert!(!has_confirmations(110, 100, 12));
+
+        // Head behind the reported block (lagging RPC): not final
+        assert!(!has_confirmations(99, 100, 1));
+    }
+}
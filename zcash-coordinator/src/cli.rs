@@ -0,0 +1,352 @@
+// zcash-coordinator/src/cli.rs
+//! One-off operator commands that run against the database and token
+//! registry without starting the coordinator daemon
+
+use anyhow::{Context, Result};
+use clap::Subcommand;
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::config::{self, ChainConfig, Config, DatabaseConfig};
+use crate::database::Database;
+use crate::signing::CoordinatorSigner;
+use crate::token_registry::TokenRegistry;
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Inspect the token registry
+    Tokens {
+        #[clap(subcommand)]
+        command: TokensCommand,
+    },
+    /// Inspect withdrawals
+    Withdrawals {
+        #[clap(subcommand)]
+        command: WithdrawalsCommand,
+    },
+    /// Database maintenance
+    Db {
+        #[clap(subcommand)]
+        command: DbCommand,
+    },
+    /// Print the authorization signature a withdrawal would be given,
+    /// without authorizing it
+    SignTest {
+        withdrawal_id: String,
+        /// Deadline to sign with, in unix seconds. Defaults to the one the
+        /// withdrawal was authorized with.
+        #[clap(long)]
+        deadline: Option<u64>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TokensCommand {
+    /// List registered tokens and their chain representations
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum WithdrawalsCommand {
+    /// Show a withdrawal and its authorization state
+    Show {
+        withdrawal_id: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DbCommand {
+    /// Create the database and any missing tables
+    Migrate,
+}
+
+/// Run `command`, returning its output
+pub async fn run(command: &Command, config: &Config, database: &Path) -> Result<String> {
+    match command {
+        Command::Db { command: DbCommand::Migrate } => db_migrate(database, &config.database).await,
+        Command::Tokens { command: TokensCommand::List } => {
+            let db = open_database(config, database).await?;
+            let registry = load_registry(config, &db).await?;
+            Ok(tokens_list(&registry))
+        }
+        Command::Withdrawals { command: WithdrawalsCommand::Show { withdrawal_id } } => {
+            let db = open_database(config, database).await?;
+            withdrawal_show(&db, withdrawal_id).await
+        }
+        Command::SignTest { withdrawal_id, deadline } => {
+            let db = open_database(config, database).await?;
+            let registry = load_registry(config, &db).await?;
+            let signer = CoordinatorSigner::from_hex(&config.signing_key)?;
+            sign_test(&db, &registry, &config.chains, &signer, withdrawal_id, *deadline).await
+        }
+    }
+}
+
+async fn open_database(config: &Config, database: &Path) -> Result<Database> {
+    Database::new(database, &config.database)
+        .await
+        .context("Failed to open database")
+}
+
+/// Token registry as the daemon sees it, including operator removals
+async fn load_registry(config: &Config, db: &Database) -> Result<TokenRegistry> {
+    let mut registry = TokenRegistry::load(&config.tokens_config)
+        .await
+        .context("Failed to load token registry")?;
+    registry.apply_overrides(db.get_token_overrides().await?);
+    Ok(registry)
+}
+
+/// `tokens list`
+pub fn tokens_list(registry: &TokenRegistry) -> String {
+    let mut out = String::new();
+    for token in registry.get_all_tokens() {
+        let _ = writeln!(
+            out,
+            "{} ({}), {} decimals, id {}",
+            token.symbol, token.name, token.decimals, token.canonical_id.0
+        );
+        for repr in &token.representations {
+            let _ = writeln!(
+                out,
+                "  {} {}: {} ({} decimals{})",
+                repr.chain_id,
+                repr.chain_name,
+                repr.address,
+                repr.decimals,
+                if repr.native { ", native" } else { "" }
+            );
+        }
+    }
+    out
+}
+
+/// `withdrawals show <id>`
+pub async fn withdrawal_show(db: &Database, withdrawal_id: &str) -> Result<String> {
+    let withdrawal = db
+        .get_withdrawal(withdrawal_id)
+        .await?
+        .with_context(|| format!("Withdrawal {} not found", withdrawal_id))?;
+
+    let status = if withdrawal.expired {
+        "expired"
+    } else if withdrawal.authorized {
+        "authorized"
+    } else {
+        "pending"
+    };
+
+    let mut out = String::new();
+    let _ = writeln!(out, "withdrawal_id:   {}", withdrawal.withdrawal_id);
+    let _ = writeln!(out, "status:          {}", status);
+    let _ = writeln!(out, "target_chain_id: {}", withdrawal.target_chain_id);
+    let _ = writeln!(out, "recipient:       {}", withdrawal.recipient);
+    let _ = writeln!(out, "token:           {}", withdrawal.token);
+    let _ = writeln!(out, "amount:          {}", withdrawal.amount);
+    let _ = writeln!(out, "relayer_fee:     {}", withdrawal.relayer_fee);
+    let _ = writeln!(out, "nullifier:       {}", hex::encode(&withdrawal.nullifier));
+    let _ = writeln!(out, "created_at:      {}", withdrawal.created_at);
+    if let Some(authorized_at) = withdrawal.authorized_at {
+        let _ = writeln!(out, "authorized_at:   {}", authorized_at);
+    }
+    if let Some(deadline) = withdrawal.auth_deadline {
+        let _ = writeln!(out, "deadline:        {}", deadline);
+    }
+    if let Some(signature) = &withdrawal.auth_signature {
+        let _ = writeln!(out, "signature:       {}", hex::encode(signature));
+    }
+    Ok(out)
+}
+
+/// `db migrate`: opening the database creates any missing tables
+pub async fn db_migrate(path: &Path, config: &DatabaseConfig) -> Result<String> {
+    Database::new(path, config)
+        .await
+        .with_context(|| format!("Failed to migrate database {}", path.display()))?;
+    Ok(format!("Database {} is up to date\n", path.display()))
+}
+
+/// `sign-test <id>`: the signature `withdrawal_id` would be authorized
+/// with, computed exactly as the daemon does. The daemon picks the deadline
+/// when it authorizes, so a pending withdrawal needs one passed in.
+pub async fn sign_test(
+    db: &Database,
+    registry: &TokenRegistry,
+    chains: &[ChainConfig],
+    signer: &CoordinatorSigner,
+    withdrawal_id: &str,
+    deadline: Option<u64>,
+) -> Result<String> {
+    let withdrawal = db
+        .get_withdrawal(withdrawal_id)
+        .await?
+        .with_context(|| format!("Withdrawal {} not found", withdrawal_id))?;
+    let deadline = deadline
+        .or(withdrawal.auth_deadline)
+        .with_context(|| format!("Withdrawal {} has no deadline yet; pass --deadline", withdrawal_id))?;
+
+    let (token_info, amount) =
+        registry.resolve_payout(withdrawal.target_chain_id, &withdrawal.token, withdrawal.amount)?;
+    let signature = signer.withdrawal_signature(
+        config::authorization_scheme(chains, withdrawal.target_chain_id)?,
+        &withdrawal.withdrawal_id,
+        &withdrawal.recipient,
+        &token_info.address,
+        amount.to_u64()?,
+        &withdrawal.nullifier,
+        deadline,
+    )?;
+
+    Ok(format!("{}\n", hex::encode(signature)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ChainType;
+    use crate::database::Withdrawal;
+    use zerobridge_types::AuthorizationScheme;
+
+    async fn test_db() -> (Database, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(&dir.path().join("coordinator.db"), &DatabaseConfig::default())
+            .await
+            .unwrap();
+        (db, dir)
+    }
+
+    fn test_registry() -> TokenRegistry {
+        TokenRegistry::from_toml(r#"
+[[tokens]]
+symbol = "USDC"
+name = "USD Coin"
+decimals = 6
+
+[[tokens.representations]]
+chain_id = 1
+chain_name = "Ethereum"
+address = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"
+
+[[tokens.representations]]
+chain_id = 56
+chain_name = "BNB Chain"
+address = "0x8AC76a51cc950d9822D68b83fE1Ad97B32Cd580d"
+decimals = 18
+wrapped_version = "Binance-Peg USDC"
+"#).unwrap()
+    }
+
+    fn test_withdrawal(target_chain_id: u64, token: &str) -> Withdrawal {
+        Withdrawal {
+            withdrawal_id: "w1".to_string(),
+            target_chain_id,
+            recipient: "0xrecipient".to_string(),
+            token: token.to_string(),
+            amount: 1_500_000,
+            nullifier: vec![1u8; 32],
+            zcash_proof: vec![2u8; 192],
+            merkle_root: vec![3u8; 32],
+            authorized: false,
+            auth_signature: None,
+            created_at: 0,
+            authorized_at: None,
+            expired: false,
+            relayer_fee: 0,
+            auth_deadline: None,
+        }
+    }
+
+    #[test]
+    fn test_tokens_list() {
+        let out = tokens_list(&test_registry());
+        assert!(out.starts_with("USDC (USD Coin), 6 decimals"));
+        assert!(out.contains("  1 Ethereum: 0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48 (6 decimals)"));
+        assert!(out.contains("  56 BNB Chain: 0x8AC76a51cc950d9822D68b83fE1Ad97B32Cd580d (18 decimals)"));
+    }
+
+    #[tokio::test]
+    async fn test_withdrawal_show() {
+        let (db, _dir) = test_db().await;
+        assert!(withdrawal_show(&db, "w1").await.is_err());
+
+        db.store_withdrawal(&test_withdrawal(1, "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48")).await.unwrap();
+        let out = withdrawal_show(&db, "w1").await.unwrap();
+        assert!(out.contains("status:          pending"));
+        assert!(out.contains("amount:          1500000"));
+        assert!(!out.contains("signature:"));
+
+        db.authorize_withdrawal("w1", "0xtoken", 1_500_000, 0, 1_700_003_600, &[0xab]).await.unwrap();
+        let out = withdrawal_show(&db, "w1").await.unwrap();
+        assert!(out.contains("status:          authorized"));
+        assert!(out.contains("deadline:        1700003600"));
+        assert!(out.contains("signature:       ab"));
+    }
+
+    #[tokio::test]
+    async fn test_db_migrate_is_repeatable() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("coordinator.db");
+
+        db_migrate(&path, &DatabaseConfig::default()).await.unwrap();
+        db_migrate(&path, &DatabaseConfig::default()).await.unwrap();
+
+        let db = Database::new(&path, &DatabaseConfig::default()).await.unwrap();
+        assert!(db.get_withdrawal("w1").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_sign_test_matches_daemon_signature() {
+        let (db, _dir) = test_db().await;
+        let registry = test_registry();
+        let chains = [ChainConfig {
+            chain_id: 56,
+            name: "BNB Chain".to_string(),
+            chain_type: ChainType::Ethereum,
+            rpc_url: "http://localhost:8545".to_string(),
+            ws_url: None,
+            gateway_address: "0x0000000000000000000000000000000000000001".to_string(),
+            start_block: 0,
+            enabled: true,
+            confirmations: 12,
+            accept_unverified_deposits: false,
+        }];
+        let signer = CoordinatorSigner::test();
+        let withdrawal_id = "11".repeat(32);
+        let deadline = Some(1_700_003_600);
+        assert!(sign_test(&db, &registry, &chains, &signer, &withdrawal_id, deadline).await.is_err());
+
+        // Paid out on an 18-decimal representation
+        let bsc_usdc = "0x8ac76a51cc950d9822d68b83fe1ad97b32cd580d";
+        let recipient = "0x00000000000000000000000000000000000000aa";
+        db.store_withdrawal(&Withdrawal {
+            withdrawal_id: withdrawal_id.clone(),
+            recipient: recipient.to_string(),
+            ..test_withdrawal(56, bsc_usdc)
+        })
+        .await
+        .unwrap();
+
+        let expected = signer
+            .withdrawal_signature(
+                AuthorizationScheme::Evm { chain_id: 56 },
+                &withdrawal_id,
+                recipient,
+                "0x8AC76a51cc950d9822D68b83fE1Ad97B32Cd580d",
+                1_500_000_000_000_000_000,
+                &[1u8; 32],
+                1_700_003_600,
+            )
+            .unwrap();
+        assert_eq!(
+            sign_test(&db, &registry, &chains, &signer, &withdrawal_id, deadline).await.unwrap().trim(),
+            hex::encode(expected)
+        );
+        // Not authorized yet, so there's no deadline to default to
+        assert!(sign_test(&db, &registry, &chains, &signer, &withdrawal_id, None).await.is_err());
+        // Chains the config doesn't know can't be signed for
+        assert!(sign_test(&db, &registry, &[], &signer, &withdrawal_id, deadline).await.is_err());
+
+        // Signing doesn't authorize
+        assert!(!db.get_withdrawal(&withdrawal_id).await.unwrap().unwrap().authorized);
+    }
+}
@@ -3,7 +3,10 @@
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
+use std::str::FromStr;
+use zerobridge_types::AuthorizationScheme;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -22,6 +25,72 @@ pub struct Config {
     /// Polling interval in seconds
     #[serde(default = "default_poll_interval")]
     pub poll_interval: u64,
+    
+    /// Seconds a withdrawal authorization stays valid on the target gateway.
+    /// Signed in as its deadline; once that passes its liquidity is released.
+    #[serde(default = "default_authorization_ttl")]
+    pub authorization_ttl: u64,
+    
+    /// Seconds a deposit may hold destination liquidity while awaiting
+    /// source confirmations before the hold is released
+    #[serde(default = "default_deposit_hold_ttl")]
+    pub deposit_hold_ttl: u64,
+    
+    /// Seconds a deposit may wait for destination liquidity before it is
+    /// made refundable on its source chain
+    #[serde(default = "default_deposit_refund_timeout")]
+    pub deposit_refund_timeout: u64,
+    
+    /// Deposits/withdrawals processed concurrently
+    #[serde(default = "default_worker_pool_size")]
+    pub worker_pool_size: usize,
+    
+    /// Deposit notifications buffered ahead of the database before relayers
+    /// are told to back off (429)
+    #[serde(default = "default_deposit_queue_capacity")]
+    pub deposit_queue_capacity: usize,
+    
+    /// Seconds an RPC request may run before it is answered with 504
+    #[serde(default = "default_rpc_request_timeout")]
+    pub rpc_request_timeout: u64,
+    
+    /// Backoff and dead-lettering for deposits that keep failing
+    #[serde(default)]
+    pub deposit_retry: DepositRetryConfig,
+    
+    /// SQLite connection tuning
+    #[serde(default)]
+    pub database: DatabaseConfig,
+    
+    /// Bridge fee and how it is split with relayers
+    #[serde(default)]
+    pub fees: FeeConfig,
+    
+    /// Bearer token for `/admin` RPC endpoints (disabled when unset)
+    #[serde(default)]
+    pub admin_token: Option<String>,
+    
+    /// Hex secp256k1 private key withdrawal and refund authorizations are
+    /// signed with. Relayers fetch its public key from
+    /// `GET /coordinator/pubkey`.
+    #[serde(default)]
+    pub signing_key: String,
+    
+    /// Seconds authorizations signed with a rotated-out key stay valid
+    #[serde(default = "default_key_rotation_overlap")]
+    pub key_rotation_overlap: u64,
+    
+    /// Serve `POST /simulate/withdrawal`, which signs withdrawals without
+    /// verifying a proof. Only honoured by builds with the `simulation`
+    /// feature, and refused on mainnet. Admin-only.
+    #[serde(default)]
+    pub enable_simulation: bool,
+    
+    /// Hex secp256k1 private key simulated withdrawals are signed with.
+    /// Required with `enable_simulation` and must differ from
+    /// `signing_key`, so gateways never accept a simulated authorization.
+    #[serde(default)]
+    pub simulation_signing_key: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,6 +110,24 @@ pub struct ZcashConfig {
     /// Spending key (base58-encoded)
     pub spending_key: String,
     
+    /// Shielded (Sapling/unified) address notes are funded from. Never a
+    /// transparent address, so deposits don't mix with transparent funds.
+    pub source_address: String,
+    
+    /// Full viewing key for `source_address` (`zxviews...`/`uview...`),
+    /// imported into the node at startup so its wallet scans the notes
+    /// received there
+    #[serde(default)]
+    pub viewing_key: Option<String>,
+    
+    /// `z_sendmany` fee in zatoshis; unset lets the node apply ZIP-317
+    #[serde(default)]
+    pub fee: Option<u64>,
+    
+    /// `z_sendmany` privacy policy
+    #[serde(default)]
+    pub privacy_policy: PrivacyPolicy,
+    
     /// Number of confirmations required
     #[serde(default = "default_confirmations")]
     pub confirmations: u32,
@@ -68,6 +155,23 @@ impl ZcashNetwork {
     }
 }
 
+/// zcashd `privacyPolicy` for `z_sendmany`, weakest last. The node refuses
+/// a send that would reveal more than the policy allows.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PrivacyPolicy {
+    /// Only sends within a single shielded pool
+    #[default]
+    FullPrivacy,
+    /// Cross-pool (e.g. Sapling to Orchard) sends, revealing the amount
+    AllowRevealedAmounts,
+    AllowRevealedRecipients,
+    /// Spends transparent funds; never needed with a shielded source
+    AllowRevealedSenders,
+    AllowFullyTransparent,
+    AllowLinkingAccountAddresses,
+    NoPrivacy,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChainConfig {
     /// Chain ID
@@ -98,6 +202,37 @@ pub struct ChainConfig {
     /// Required confirmations
     #[serde(default = "default_confirmations")]
     pub confirmations: u32,
+    
+    /// Take deposits from this chain as relayers report them when the
+    /// coordinator can't read them back from the gateway (non-EVM chains).
+    /// Off by default: such deposits are rejected.
+    #[serde(default)]
+    pub accept_unverified_deposits: bool,
+}
+
+impl ChainConfig {
+    /// How this chain's gateway rebuilds the messages coordinator
+    /// authorizations sign
+    pub fn authorization_scheme(&self) -> AuthorizationScheme {
+        match self.chain_type {
+            ChainType::Ethereum | ChainType::Base | ChainType::Polygon => {
+                AuthorizationScheme::Evm { chain_id: self.chain_id }
+            }
+            ChainType::Solana => AuthorizationScheme::Solana,
+            ChainType::Near => AuthorizationScheme::Near,
+            ChainType::Osmosis => AuthorizationScheme::Osmosis,
+            ChainType::Mina | ChainType::Starknet => AuthorizationScheme::Unchecked,
+        }
+    }
+}
+
+/// Authorization scheme of the gateway on `chain_id`, one of `chains`
+pub fn authorization_scheme(chains: &[ChainConfig], chain_id: u64) -> Result<AuthorizationScheme> {
+    chains
+        .iter()
+        .find(|c| c.chain_id == chain_id)
+        .map(ChainConfig::authorization_scheme)
+        .with_context(|| format!("Chain {} is not configured", chain_id))
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -124,6 +259,260 @@ impl ChainType {
     pub fn is_non_evm(&self) -> bool {
         !self.is_evm()
     }
+    
+    /// Check `address` is a well-formed account/contract address on this
+    /// chain
+    pub fn check_address(&self, address: &str) -> Result<()> {
+        let (valid, expected) = match self {
+            ChainType::Ethereum | ChainType::Base | ChainType::Polygon => (
+                address
+                    .strip_prefix("0x")
+                    .map(|hex| hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+                    .unwrap_or(false),
+                "a 0x-prefixed 20-byte hex address",
+            ),
+            ChainType::Starknet => (
+                address
+                    .strip_prefix("0x")
+                    .map(|hex| (1..=64).contains(&hex.len()) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+                    .unwrap_or(false),
+                "a 0x-prefixed felt of at most 64 hex digits",
+            ),
+            ChainType::Solana => (
+                solana_sdk::pubkey::Pubkey::from_str(address).is_ok(),
+                "a base58-encoded 32-byte public key",
+            ),
+            ChainType::Near => (
+                is_near_account_id(address),
+                "a NEAR account id (2-64 lowercase alphanumerics separated by '.', '-' or '_')",
+            ),
+            ChainType::Mina => (
+                address.len() == 55 && address.starts_with("B62") && address.chars().all(is_base58),
+                "a 55-character base58 public key starting with B62",
+            ),
+            ChainType::Osmosis => (
+                address
+                    .strip_prefix("osmo1")
+                    .map(|data| data.len() >= 38 && data.chars().all(|c| BECH32_CHARSET.contains(c)))
+                    .unwrap_or(false),
+                "an osmo1-prefixed bech32 address",
+            ),
+        };
+        
+        if !valid {
+            anyhow::bail!("{:?} is not a valid {:?} address, expected {}", address, self, expected);
+        }
+        Ok(())
+    }
+    
+    /// Sane `(min, max)` confirmations for the chain's finality model.
+    /// Probabilistic chains need depth (Polygon reorgs run deep); Solana
+    /// counts slots towards `finalized`; BFT chains are final in a block.
+    pub fn confirmation_bounds(&self) -> (u32, u32) {
+        match self {
+            ChainType::Ethereum => (12, 1_000),
+            ChainType::Base => (5, 1_000),
+            ChainType::Polygon => (128, 5_000),
+            ChainType::Solana => (1, 64),
+            ChainType::Near => (2, 100),
+            ChainType::Mina => (15, 290),
+            ChainType::Starknet => (1, 1_000),
+            ChainType::Osmosis => (1, 10),
+        }
+    }
+}
+
+const BECH32_CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+fn is_base58(c: char) -> bool {
+    c.is_ascii_alphanumeric() && !matches!(c, '0' | 'O' | 'I' | 'l')
+}
+
+/// NEAR account ID rules: 2-64 lowercase alphanumerics separated by single
+/// `.`, `-` or `_`
+fn is_near_account_id(id: &str) -> bool {
+    (2..=64).contains(&id.len())
+        && id
+            .split(['.', '-', '_'])
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit()))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeConfig {
+    /// Bridge fee charged by the gateways, in basis points of the amount
+    #[serde(default = "default_bridge_fee_bps")]
+    pub bridge_fee_bps: u16,
+    
+    /// Part of the bridge fee paid to the executing relayer, in basis points
+    /// of the amount; the remainder goes to the protocol
+    #[serde(default = "default_relayer_fee_bps")]
+    pub relayer_fee_bps: u16,
+    
+    /// Estimated Zcash network fee for creating one deposit note, in zatoshis
+    #[serde(default = "default_zcash_network_fee")]
+    pub zcash_network_fee: u64,
+    
+    /// Canonical token base units per zatoshi, keyed by token symbol. The
+    /// Zcash fee is deducted from deposits of listed tokens only.
+    #[serde(default)]
+    pub zcash_fee_rates: HashMap<String, f64>,
+}
+
+impl Default for FeeConfig {
+    fn default() -> Self {
+        Self {
+            bridge_fee_bps: default_bridge_fee_bps(),
+            relayer_fee_bps: default_relayer_fee_bps(),
+            zcash_network_fee: default_zcash_network_fee(),
+            zcash_fee_rates: HashMap::new(),
+        }
+    }
+}
+
+/// Zcash network fee taken out of a deposit before its note is created
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZcashFeeDeduction {
+    /// Fee reserved, in canonical token units
+    pub fee: u64,
+    /// Amount the note is created for
+    pub net_amount: u64,
+}
+
+/// Bridge fee on a single transfer, split between relayer and protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeSplit {
+    pub relayer_fee: u64,
+    pub protocol_fee: u64,
+}
+
+impl FeeConfig {
+    /// Split the bridge fee on `amount`; rounding dust goes to the protocol
+    pub fn split(&self, amount: u64) -> FeeSplit {
+        let bps = |rate: u16| (amount as u128 * rate as u128 / 10_000) as u64;
+        
+        let total_fee = bps(self.bridge_fee_bps);
+        let relayer_fee = bps(self.relayer_fee_bps).min(total_fee);
+        
+        FeeSplit {
+            relayer_fee,
+            protocol_fee: total_fee - relayer_fee,
+        }
+    }
+    
+    /// Zcash network fee in `symbol`'s canonical units, rounded up so the
+    /// coordinator never subsidizes a note
+    pub fn zcash_fee_reserve(&self, symbol: &str) -> u64 {
+        match self.zcash_fee_rates.get(symbol) {
+            Some(rate) => (self.zcash_network_fee as f64 * rate).ceil() as u64,
+            None => 0,
+        }
+    }
+    
+    /// Deduct the Zcash network fee from a deposit of `amount` canonical
+    /// units. Fails for deposits too small to leave anything after the fee.
+    pub fn deduct_zcash_fee(&self, symbol: &str, amount: u64) -> Result<ZcashFeeDeduction> {
+        let fee = self.zcash_fee_reserve(symbol);
+        if amount <= fee {
+            anyhow::bail!(
+                "Deposit of {} {} does not cover the Zcash network fee of {}",
+                amount,
+                symbol,
+                fee
+            );
+        }
+        
+        Ok(ZcashFeeDeduction {
+            fee,
+            net_amount: amount - fee,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DepositRetryConfig {
+    /// Failed attempts before a deposit is moved to the `failed` state
+    #[serde(default = "default_max_deposit_attempts")]
+    pub max_attempts: u32,
+    
+    /// Delay after the first failure (seconds); doubles on each further failure
+    #[serde(default = "default_base_backoff_secs")]
+    pub base_backoff_secs: u64,
+    
+    /// Upper bound on the delay between attempts (seconds)
+    #[serde(default = "default_max_backoff_secs")]
+    pub max_backoff_secs: u64,
+}
+
+impl Default for DepositRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_max_deposit_attempts(),
+            base_backoff_secs: default_base_backoff_secs(),
+            max_backoff_secs: default_max_backoff_secs(),
+        }
+    }
+}
+
+impl DepositRetryConfig {
+    /// Delay before the next attempt after `attempts` failures, or `None`
+    /// once the deposit has used up its attempts
+    pub fn backoff(&self, attempts: u32) -> Option<u64> {
+        if attempts >= self.max_attempts {
+            return None;
+        }
+        
+        let factor = 1u64.checked_shl(attempts.saturating_sub(1)).unwrap_or(u64::MAX);
+        Some(self.base_backoff_secs.saturating_mul(factor).min(self.max_backoff_secs))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseConfig {
+    /// Maximum pooled connections
+    #[serde(default = "default_db_max_connections")]
+    pub max_connections: u32,
+    
+    /// Maximum read-only connections serving RPC stats/status queries
+    #[serde(default = "default_db_read_max_connections")]
+    pub read_max_connections: u32,
+    
+    /// Use write-ahead logging so readers don't block writers
+    #[serde(default = "default_true")]
+    pub wal: bool,
+    
+    /// How long a connection waits on a locked database (milliseconds)
+    #[serde(default = "default_busy_timeout_ms")]
+    pub busy_timeout_ms: u64,
+    
+    /// SQLite `synchronous` pragma
+    #[serde(default = "default_synchronous")]
+    pub synchronous: SynchronousMode,
+    
+    /// Enforce foreign key constraints
+    #[serde(default = "default_true")]
+    pub foreign_keys: bool,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: default_db_max_connections(),
+            read_max_connections: default_db_read_max_connections(),
+            wal: true,
+            busy_timeout_ms: default_busy_timeout_ms(),
+            synchronous: default_synchronous(),
+            foreign_keys: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SynchronousMode {
+    Off,
+    Normal,
+    Full,
+    Extra,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -145,11 +534,91 @@ pub struct LiquidityConfig {
     pub max_rebalance_usd: u64,
 }
 
+impl LiquidityConfig {
+    #[cfg(test)]
+    pub fn mock() -> Self {
+        Self {
+            rebalance_threshold: 0.8,
+            target_utilization: 0.5,
+            min_liquidity_usd: 10_000,
+            max_rebalance_usd: 100_000,
+        }
+    }
+}
+
 // Default values
 fn default_poll_interval() -> u64 {
     10 // 10 seconds
 }
 
+fn default_authorization_ttl() -> u64 {
+    3600 // 1 hour
+}
+
+fn default_key_rotation_overlap() -> u64 {
+    3600 // authorization_ttl
+}
+
+fn default_deposit_hold_ttl() -> u64 {
+    3600 // 1 hour, well past any configured chain's confirmation depth
+}
+
+fn default_deposit_refund_timeout() -> u64 {
+    86400 // 24 hours
+}
+
+fn default_worker_pool_size() -> usize {
+    8
+}
+
+fn default_deposit_queue_capacity() -> usize {
+    1024
+}
+
+fn default_rpc_request_timeout() -> u64 {
+    30
+}
+
+fn default_max_deposit_attempts() -> u32 {
+    8
+}
+
+fn default_base_backoff_secs() -> u64 {
+    10
+}
+
+fn default_max_backoff_secs() -> u64 {
+    3600 // 1 hour
+}
+
+fn default_bridge_fee_bps() -> u16 {
+    30 // 0.3%, matches the gateways' default
+}
+
+fn default_relayer_fee_bps() -> u16 {
+    20 // two thirds of the default bridge fee
+}
+
+fn default_zcash_network_fee() -> u64 {
+    10_000 // ZIP-317 marginal fee (5_000) times the two-action minimum
+}
+
+fn default_db_max_connections() -> u32 {
+    10
+}
+
+fn default_db_read_max_connections() -> u32 {
+    20
+}
+
+fn default_busy_timeout_ms() -> u64 {
+    5_000 // 5 seconds
+}
+
+fn default_synchronous() -> SynchronousMode {
+    SynchronousMode::Normal // Safe with WAL, much faster than FULL
+}
+
 fn default_confirmations() -> u32 {
     6
 }
@@ -180,14 +649,121 @@ impl Config {
         let content = std::fs::read_to_string(path)
             .context("Failed to read config file")?;
         
-        let config: Config = toml::from_str(&content)
+        Self::from_toml(&content)
+    }
+    
+    /// Parse configuration, substituting `${VAR}` in string values and
+    /// resolving `env:`/`file:` references in secret fields
+    pub fn from_toml(content: &str) -> Result<Self> {
+        if content.contains(TEMPLATE_PLACEHOLDER) {
+            anyhow::bail!(
+                "Config file still contains {} placeholders from --generate-config",
+                TEMPLATE_PLACEHOLDER
+            );
+        }
+        
+        let mut value: toml::Value = toml::from_str(content)
+            .context("Failed to parse config file")?;
+        interpolate_env(&mut value)?;
+        
+        let mut config: Config = value
+            .try_into()
             .context("Failed to parse config file")?;
         
+        config.zcash.rpc_password = resolve_secret(&config.zcash.rpc_password)?;
+        config.zcash.spending_key = resolve_secret(&config.zcash.spending_key)?;
+        if let Some(viewing_key) = &config.zcash.viewing_key {
+            config.zcash.viewing_key = Some(resolve_secret(viewing_key)?);
+        }
+        if let Some(admin_token) = &config.admin_token {
+            config.admin_token = Some(resolve_secret(admin_token)?);
+        }
+        config.signing_key = resolve_secret(&config.signing_key)?;
+        if let Some(simulation_signing_key) = &config.simulation_signing_key {
+            config.simulation_signing_key = Some(resolve_secret(simulation_signing_key)?);
+        }
+        
         config.validate()?;
         
         Ok(config)
     }
     
+    /// Fully populated config with default settings and placeholder secrets
+    pub fn template() -> Self {
+        Self {
+            zcash: ZcashConfig {
+                network: ZcashNetwork::Testnet,
+                rpc_url: "http://localhost:18232".to_string(),
+                rpc_user: "zcashrpc".to_string(),
+                rpc_password: TEMPLATE_PLACEHOLDER.to_string(),
+                spending_key: TEMPLATE_PLACEHOLDER.to_string(),
+                source_address: TEMPLATE_PLACEHOLDER.to_string(),
+                viewing_key: None,
+                fee: None,
+                privacy_policy: PrivacyPolicy::default(),
+                confirmations: default_confirmations(),
+                enable_orchard: true,
+                enable_sapling: true,
+            },
+            chains: vec![ChainConfig {
+                chain_id: 11155111,
+                name: "Ethereum Sepolia".to_string(),
+                chain_type: ChainType::Ethereum,
+                rpc_url: format!("https://sepolia.infura.io/v3/{}", TEMPLATE_PLACEHOLDER),
+                ws_url: Some(format!("wss://sepolia.infura.io/ws/v3/{}", TEMPLATE_PLACEHOLDER)),
+                gateway_address: TEMPLATE_PLACEHOLDER.to_string(),
+                start_block: 0,
+                enabled: true,
+                confirmations: ChainType::Ethereum.confirmation_bounds().0,
+                accept_unverified_deposits: false,
+            }],
+            tokens_config: "config/tokens.toml".to_string(),
+            liquidity: LiquidityConfig {
+                rebalance_threshold: default_rebalance_threshold(),
+                target_utilization: default_target_utilization(),
+                min_liquidity_usd: default_min_liquidity(),
+                max_rebalance_usd: default_max_rebalance(),
+            },
+            poll_interval: default_poll_interval(),
+            authorization_ttl: default_authorization_ttl(),
+            deposit_hold_ttl: default_deposit_hold_ttl(),
+            deposit_refund_timeout: default_deposit_refund_timeout(),
+            worker_pool_size: default_worker_pool_size(),
+            deposit_queue_capacity: default_deposit_queue_capacity(),
+            rpc_request_timeout: default_rpc_request_timeout(),
+            deposit_retry: DepositRetryConfig::default(),
+            database: DatabaseConfig::default(),
+            fees: FeeConfig {
+                zcash_fee_rates: HashMap::from([("USDC".to_string(), 0.0003)]),
+                ..FeeConfig::default()
+            },
+            admin_token: Some(TEMPLATE_PLACEHOLDER.to_string()),
+            signing_key: TEMPLATE_PLACEHOLDER.to_string(),
+            key_rotation_overlap: default_key_rotation_overlap(),
+            enable_simulation: false,
+            simulation_signing_key: None,
+        }
+    }
+    
+    /// `template()` as commented TOML, written by `--generate-config`
+    pub fn template_toml() -> Result<String> {
+        let toml = toml::to_string_pretty(&Self::template())
+            .context("Failed to serialize config template")?;
+        
+        Ok(format!(
+            "# ZeroBridge coordinator configuration\n\
+             # Replace every {} before starting the coordinator\n\n{}",
+            TEMPLATE_PLACEHOLDER,
+            annotate_toml(&toml, TEMPLATE_COMMENTS)
+        ))
+    }
+    
+    /// Whether a deposit received at `created_at` has waited out the refund
+    /// timeout by `now` (unix seconds)
+    pub fn refund_due(&self, created_at: i64, now: i64) -> bool {
+        now.saturating_sub(created_at) >= self.deposit_refund_timeout as i64
+    }
+    
     /// Validate configuration
     fn validate(&self) -> Result<()> {
         // Validate Zcash config
@@ -199,6 +775,19 @@ impl Config {
             anyhow::bail!("Zcash spending key cannot be empty");
         }
         
+        if self.zcash.source_address.is_empty() || self.zcash.source_address.starts_with('t') {
+            anyhow::bail!("Zcash source address must be a shielded address");
+        }
+        
+        if self.zcash.viewing_key.as_deref() == Some("") {
+            anyhow::bail!("Zcash viewing key cannot be empty; omit it instead");
+        }
+        
+        if self.signing_key.is_empty() {
+            anyhow::bail!("Coordinator signing key cannot be empty");
+        }
+        crate::signing::CoordinatorSigner::from_hex(&self.signing_key)?;
+        
         // Validate chains
         if self.chains.is_empty() {
             anyhow::bail!("At least one chain must be configured");
@@ -212,6 +801,84 @@ impl Config {
             if chain.gateway_address.is_empty() {
                 anyhow::bail!("Gateway address for chain {} cannot be empty", chain.name);
             }
+            
+            if let Err(e) = chain.chain_type.check_address(&chain.gateway_address) {
+                anyhow::bail!("Gateway address for chain {} is invalid: {}", chain.name, e);
+            }
+            
+            let (min, max) = chain.chain_type.confirmation_bounds();
+            if chain.confirmations < min || chain.confirmations > max {
+                anyhow::bail!(
+                    "Confirmations for chain {} ({:?}) must be between {} and {}, got {}",
+                    chain.name, chain.chain_type, min, max, chain.confirmations
+                );
+            }
+        }
+        
+        if self.worker_pool_size == 0 {
+            anyhow::bail!("Worker pool size must be greater than 0");
+        }
+        
+        if self.deposit_queue_capacity == 0 {
+            anyhow::bail!("Deposit queue capacity must be greater than 0");
+        }
+        
+        if self.rpc_request_timeout == 0 {
+            anyhow::bail!("RPC request timeout must be greater than 0");
+        }
+        
+        if self.enable_simulation {
+            if self.zcash.network.is_mainnet() {
+                anyhow::bail!("Withdrawal simulation cannot be enabled on mainnet");
+            }
+            let simulation_key = self
+                .simulation_signing_key
+                .as_deref()
+                .context("Withdrawal simulation needs a simulation_signing_key")?;
+            let simulation_signer = crate::signing::CoordinatorSigner::from_hex(simulation_key)?;
+            let signer = crate::signing::CoordinatorSigner::from_hex(&self.signing_key)?;
+            if simulation_signer.address() == signer.address() {
+                anyhow::bail!("simulation_signing_key must differ from signing_key");
+            }
+        }
+        
+        if self.deposit_retry.max_attempts == 0 {
+            anyhow::bail!("Deposit retry max_attempts must be greater than 0");
+        }
+        
+        if self.database.max_connections == 0 || self.database.read_max_connections == 0 {
+            anyhow::bail!("Database connection limits must be greater than 0");
+        }
+        
+        if self.fees.bridge_fee_bps > 10_000 {
+            anyhow::bail!("Bridge fee cannot exceed 10000 bps");
+        }
+        
+        if self.fees.relayer_fee_bps > self.fees.bridge_fee_bps {
+            anyhow::bail!("Relayer fee cannot exceed the bridge fee");
+        }
+        
+        if self.fees.zcash_fee_rates.values().any(|rate| !rate.is_finite() || *rate < 0.0) {
+            anyhow::bail!("Zcash fee rates must be finite and non-negative");
+        }
+        
+        if self.authorization_ttl == 0 {
+            anyhow::bail!("Authorization TTL must be greater than 0");
+        }
+        
+        if self.key_rotation_overlap < self.authorization_ttl {
+            anyhow::bail!(
+                "Key rotation overlap ({}s) must cover the authorization TTL ({}s) so authorizations signed before a rotation can still be executed",
+                self.key_rotation_overlap, self.authorization_ttl
+            );
+        }
+        
+        if self.deposit_hold_ttl == 0 {
+            anyhow::bail!("Deposit hold TTL must be greater than 0");
+        }
+        
+        if self.deposit_refund_timeout == 0 {
+            anyhow::bail!("Deposit refund timeout must be greater than 0");
         }
         
         // Validate liquidity config
@@ -235,12 +902,152 @@ impl Config {
         self.chains.iter().find(|c| c.chain_id == chain_id)
     }
     
+    /// Authorization scheme of a configured chain's gateway
+    pub fn authorization_scheme(&self, chain_id: u64) -> Result<AuthorizationScheme> {
+        authorization_scheme(&self.chains, chain_id)
+    }
+    
     /// Get enabled chains
     pub fn enabled_chains(&self) -> Vec<&ChainConfig> {
         self.chains.iter().filter(|c| c.enabled).collect()
     }
 }
 
+/// Replace `${VAR}` in every string value with that environment variable
+fn interpolate_env(value: &mut toml::Value) -> Result<()> {
+    match value {
+        toml::Value::String(s) => *s = interpolate_str(s)?,
+        toml::Value::Array(items) => {
+            for item in items {
+                interpolate_env(item)?;
+            }
+        }
+        toml::Value::Table(table) => {
+            for (_, item) in table.iter_mut() {
+                interpolate_env(item)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn interpolate_str(s: &str) -> Result<String> {
+    let mut out = String::new();
+    let mut rest = s;
+    
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let len = rest[start..]
+            .find('}')
+            .with_context(|| format!("Unterminated ${{ in config value {:?}", s))?;
+        out.push_str(&env_var(&rest[start + 2..start + len])?);
+        rest = &rest[start + len + 1..];
+    }
+    
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Resolve an `env:NAME` or `file:PATH` secret reference; anything else is
+/// taken literally
+pub fn resolve_secret(value: &str) -> Result<String> {
+    if let Some(name) = value.strip_prefix("env:") {
+        env_var(name)
+    } else if let Some(path) = value.strip_prefix("file:") {
+        let secret = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read secret file {}", path))?;
+        Ok(secret.trim_end_matches(['\r', '\n']).to_string())
+    } else {
+        Ok(value.to_string())
+    }
+}
+
+fn env_var(name: &str) -> Result<String> {
+    std::env::var(name)
+        .with_context(|| format!("Environment variable {} referenced by config is not set", name))
+}
+
+/// Marks values `--generate-config` can't fill in; `Config::load` refuses
+/// a file that still contains it
+pub const TEMPLATE_PLACEHOLDER: &str = "CHANGE_ME";
+
+/// Comments written above keys and tables of the generated template
+const TEMPLATE_COMMENTS: &[(&str, &str)] = &[
+    ("tokens_config", "Token registry file"),
+    ("poll_interval", "Polling interval in seconds"),
+    ("authorization_ttl", "Seconds a withdrawal authorization is valid on the target gateway; its liquidity is released once it lapses"),
+    ("deposit_hold_ttl", "Seconds a deposit may hold destination liquidity while awaiting source confirmations"),
+    ("deposit_refund_timeout", "Seconds a deposit may wait for destination liquidity before it becomes refundable"),
+    ("worker_pool_size", "Deposits/withdrawals processed concurrently"),
+    ("deposit_queue_capacity", "Deposit notifications buffered ahead of the database; relayers get 429 beyond this"),
+    ("rpc_request_timeout", "Seconds an RPC request may run before it is answered with 504"),
+    ("admin_token", "Bearer token for /admin endpoints; remove to disable the admin API"),
+    ("signing_key", "Hex secp256k1 private key authorizations are signed with (relayers verify against GET /coordinator/pubkey)"),
+    ("key_rotation_overlap", "Seconds authorizations signed with a rotated-out key are still accepted; at least authorization_ttl"),
+    ("enable_simulation", "Sign withdrawals without proofs at /simulate/withdrawal (integration testing only)"),
+    ("zcash", "Zcash node"),
+    ("zcash.network", "mainnet, testnet or regtest"),
+    ("zcash.rpc_password", "zcashd RPC password"),
+    ("zcash.spending_key", "Generate with: zcash-cli z_getnewaddress, then z_exportkey; add `viewing_key` (z_exportviewingkey) to scan received notes"),
+    ("zcash.source_address", "Shielded address deposit notes are funded from; add `fee = <zatoshis>` to override ZIP-317"),
+    ("zcash.privacy_policy", "AllowRevealedAmounts if notes cross pools (Sapling -> Orchard)"),
+    ("zcash.confirmations", "Confirmations before a note counts as created"),
+    ("chains", "One [[chains]] table per gateway chain"),
+    ("chains.chain_type", "ethereum, base, polygon, solana, near, mina, starknet or osmosis"),
+    ("chains.gateway_address", "Gateway contract address (program ID on Solana)"),
+    ("chains.start_block", "Start block for event scanning"),
+    ("chains.confirmations", "Source confirmations before a deposit is final; bounds depend on chain_type"),
+    ("liquidity", "Rebalancing"),
+    ("liquidity.rebalance_threshold", "Rebalance when utilization exceeds this (0.0 - 1.0)"),
+    ("liquidity.target_utilization", "Target utilization after rebalancing (0.0 - 1.0)"),
+    ("liquidity.min_liquidity_usd", "Minimum liquidity per chain (USD)"),
+    ("liquidity.max_rebalance_usd", "Maximum single rebalance (USD)"),
+    ("deposit_retry", "Backoff and dead-lettering for deposits that keep failing"),
+    ("deposit_retry.max_attempts", "Failed attempts before a deposit is dead-lettered"),
+    ("deposit_retry.base_backoff_secs", "Backoff after the first failure, doubling per attempt"),
+    ("deposit_retry.max_backoff_secs", "Upper bound on the backoff"),
+    ("database", "SQLite tuning"),
+    ("database.read_max_connections", "Read-only pool for stats/status endpoints"),
+    ("database.wal", "Write-ahead logging: readers don't block writers"),
+    ("database.busy_timeout_ms", "Wait on a locked database instead of erroring"),
+    ("database.synchronous", "off, normal, full or extra"),
+    ("fees", "Bridge fee split (basis points of the transfer amount)"),
+    ("fees.bridge_fee_bps", "Must match the gateways' bridge_fee"),
+    ("fees.relayer_fee_bps", "Paid to the executing relayer; remainder to the protocol"),
+    ("fees.zcash_network_fee", "Zatoshis per deposit note, deducted from the bridged amount"),
+    ("fees.zcash_fee_rates", "Canonical token base units per zatoshi, by token symbol"),
+];
+
+/// Put `# comment` lines above the keys and `[tables]` named in `comments`
+/// (dotted paths, arrays of tables by their name)
+fn annotate_toml(toml: &str, comments: &[(&str, &str)]) -> String {
+    let mut out = String::new();
+    let mut table = String::new();
+    
+    for line in toml.lines() {
+        let trimmed = line.trim();
+        let path = if trimmed.starts_with('[') {
+            table = trimmed.trim_matches(|c| c == '[' || c == ']').to_string();
+            Some(table.clone())
+        } else if let Some((key, _)) = trimmed.split_once(" = ") {
+            Some(if table.is_empty() { key.to_string() } else { format!("{}.{}", table, key) })
+        } else {
+            None
+        };
+        
+        if let Some((_, comment)) = path.and_then(|p| comments.iter().find(|(key, _)| *key == p)) {
+            out.push_str("# ");
+            out.push_str(comment);
+            out.push('\n');
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -254,6 +1061,10 @@ mod tests {
                 rpc_user: "user".to_string(),
                 rpc_password: "pass".to_string(),
                 spending_key: "test_key".to_string(),
+                source_address: "ztestsapling1test".to_string(),
+                viewing_key: None,
+                fee: None,
+                privacy_policy: PrivacyPolicy::default(),
                 confirmations: 6,
                 enable_orchard: true,
                 enable_sapling: true,
@@ -265,10 +1076,11 @@ mod tests {
                     chain_type: ChainType::Ethereum,
                     rpc_url: "http://localhost:8545".to_string(),
                     ws_url: None,
-                    gateway_address: "0x1234".to_string(),
+                    gateway_address: "0x00000000000000000000000000000000000000aa".to_string(),
                     start_block: 0,
                     enabled: true,
                     confirmations: 12,
+                    accept_unverified_deposits: false,
                 },
             ],
             tokens_config: "tokens.toml".to_string(),
@@ -279,9 +1091,41 @@ mod tests {
                 max_rebalance_usd: 100_000,
             },
             poll_interval: 10,
+            authorization_ttl: 3600,
+            deposit_hold_ttl: 3600,
+            deposit_refund_timeout: 86400,
+            worker_pool_size: 8,
+            deposit_queue_capacity: 1024,
+            rpc_request_timeout: 30,
+            deposit_retry: DepositRetryConfig::default(),
+            database: DatabaseConfig::default(),
+            fees: FeeConfig::default(),
+            admin_token: None,
+            signing_key: crate::signing::TEST_SIGNING_KEY.to_string(),
+            key_rotation_overlap: 3600,
+            enable_simulation: false,
+            simulation_signing_key: None,
         };
         
         assert!(config.validate().is_ok());
+        
+        // Authorizations can't be signed without a usable key
+        let mut unsigned = config.clone();
+        unsigned.signing_key.clear();
+        assert!(unsigned.validate().is_err());
+        unsigned.signing_key = "0x1234".to_string();
+        assert!(unsigned.validate().is_err());
+        
+        // Authorizations issued just before a rotation must outlive it
+        let mut short_overlap = config.clone();
+        short_overlap.key_rotation_overlap = short_overlap.authorization_ttl - 1;
+        assert!(short_overlap.validate().is_err());
+        
+        // Refunds open up once the deposit has waited out the timeout
+        assert!(!config.refund_due(1_000, 1_000 + 86_399));
+        assert!(config.refund_due(1_000, 1_000 + 86_400));
+        // Clock behind the deposit timestamp
+        assert!(!config.refund_due(1_000, 0));
     }
     
     #[test]
@@ -293,6 +1137,10 @@ mod tests {
                 rpc_user: "user".to_string(),
                 rpc_password: "pass".to_string(),
                 spending_key: "test_key".to_string(),
+                source_address: "ztestsapling1test".to_string(),
+                viewing_key: None,
+                fee: None,
+                privacy_policy: PrivacyPolicy::default(),
                 confirmations: 6,
                 enable_orchard: true,
                 enable_sapling: true,
@@ -306,6 +1154,20 @@ mod tests {
                 max_rebalance_usd: 100_000,
             },
             poll_interval: 10,
+            authorization_ttl: 3600,
+            deposit_hold_ttl: 3600,
+            deposit_refund_timeout: 86400,
+            worker_pool_size: 8,
+            deposit_queue_capacity: 1024,
+            rpc_request_timeout: 30,
+            deposit_retry: DepositRetryConfig::default(),
+            database: DatabaseConfig::default(),
+            fees: FeeConfig::default(),
+            admin_token: None,
+            signing_key: crate::signing::TEST_SIGNING_KEY.to_string(),
+            key_rotation_overlap: 3600,
+            enable_simulation: false,
+            simulation_signing_key: None,
         };
         
         config.chains.push(ChainConfig {
@@ -314,12 +1176,299 @@ mod tests {
             chain_type: ChainType::Ethereum,
             rpc_url: "http://localhost:8545".to_string(),
             ws_url: None,
-            gateway_address: "0x1234".to_string(),
+            gateway_address: "0x00000000000000000000000000000000000000aa".to_string(),
             start_block: 0,
             enabled: true,
             confirmations: 12,
+            accept_unverified_deposits: false,
         });
         
         assert!(config.validate().is_err());
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_fee_split() {
+        let fees = FeeConfig {
+            bridge_fee_bps: 30,
+            relayer_fee_bps: 20,
+            ..FeeConfig::default()
+        };
+        
+        // 0.3% of 1_000_000 = 3_000, relayer takes 0.2% = 2_000
+        assert_eq!(fees.split(1_000_000), FeeSplit { relayer_fee: 2_000, protocol_fee: 1_000 });
+        
+        // Rounding dust goes to the protocol
+        assert_eq!(fees.split(1_001), FeeSplit { relayer_fee: 2, protocol_fee: 1 });
+        assert_eq!(fees.split(0), FeeSplit { relayer_fee: 0, protocol_fee: 0 });
+        
+        // No overflow at the top of the range
+        let split = fees.split(u64::MAX);
+        assert_eq!(split.relayer_fee + split.protocol_fee, (u64::MAX as u128 * 30 / 10_000) as u64);
+    }
+    
+    #[test]
+    fn test_zcash_fee_deduction() {
+        let fees = FeeConfig {
+            zcash_network_fee: 10_000,
+            // USDC (6 decimals) at $30/ZEC: 0.0003 base units per zatoshi
+            zcash_fee_rates: HashMap::from([("USDC".to_string(), 0.0003)]),
+            ..FeeConfig::default()
+        };
+        
+        let deduction = fees.deduct_zcash_fee("USDC", 5_000_000).unwrap();
+        assert_eq!(deduction, ZcashFeeDeduction { fee: 3, net_amount: 4_999_997 });
+        
+        // Dust that can't cover the fee is rejected
+        assert!(fees.deduct_zcash_fee("USDC", 3).is_err());
+        assert!(fees.deduct_zcash_fee("USDC", 0).is_err());
+        
+        // Fractional fees round up
+        let fees = FeeConfig {
+            zcash_fee_rates: HashMap::from([("USDC".to_string(), 0.00025)]),
+            ..fees
+        };
+        assert_eq!(fees.zcash_fee_reserve("USDC"), 3);
+        
+        // Tokens without a rate aren't charged
+        assert_eq!(fees.deduct_zcash_fee("ETH", 1).unwrap().net_amount, 1);
+    }
+    
+    #[test]
+    fn test_deposit_backoff_progression() {
+        let retry = DepositRetryConfig {
+            max_attempts: 5,
+            base_backoff_secs: 10,
+            max_backoff_secs: 60,
+        };
+        
+        assert_eq!(retry.backoff(1), Some(10));
+        assert_eq!(retry.backoff(2), Some(20));
+        assert_eq!(retry.backoff(3), Some(40));
+        // Capped
+        assert_eq!(retry.backoff(4), Some(60));
+        // Out of attempts: dead-letter
+        assert_eq!(retry.backoff(5), None);
+        assert_eq!(retry.backoff(100), None);
+        
+        // Huge attempt counts don't overflow
+        let retry = DepositRetryConfig { max_attempts: u32::MAX, ..retry };
+        assert_eq!(retry.backoff(200), Some(60));
+    }
+    
+    #[test]
+    fn test_generated_config_loads_once_filled_in() {
+        let template = Config::template_toml().unwrap();
+        assert!(template.contains("# Zcash node\n[zcash]"));
+        assert!(template.contains("# Polling interval in seconds\npoll_interval = 10"));
+        
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("coordinator.toml");
+        
+        // Unedited templates are refused
+        std::fs::write(&path, &template).unwrap();
+        let err = Config::load(&path).unwrap_err().to_string();
+        assert!(err.contains(TEMPLATE_PLACEHOLDER), "{}", err);
+        
+        let filled = template
+            .replace(
+                &format!("signing_key = \"{}\"", TEMPLATE_PLACEHOLDER),
+                &format!("signing_key = \"{}\"", crate::signing::TEST_SIGNING_KEY),
+            )
+            .replace(
+                &format!("gateway_address = \"{}\"", TEMPLATE_PLACEHOLDER),
+                "gateway_address = \"0x00000000000000000000000000000000000000aa\"",
+            )
+            .replace(TEMPLATE_PLACEHOLDER, "zs1filledin");
+        std::fs::write(&path, filled).unwrap();
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.zcash.spending_key, "zs1filledin");
+        assert_eq!(config.chains.len(), 1);
+        assert_eq!(config.worker_pool_size, default_worker_pool_size());
+        assert_eq!(config.fees.zcash_fee_rates.get("USDC"), Some(&0.0003));
+    }
+    
+    const MINIMAL_CONFIG: &str = r#"
+        tokens_config = "tokens.toml"
+        admin_token = "env:ZB_TEST_ADMIN_TOKEN"
+        signing_key = "env:ZB_TEST_SIGNING_KEY"
+        
+        [zcash]
+        network = "testnet"
+        rpc_url = "http://${ZB_TEST_ZCASH_HOST}:18232"
+        rpc_user = "user"
+        rpc_password = "${ZB_TEST_ZCASH_PASSWORD}"
+        spending_key = "file:SPENDING_KEY_FILE"
+        source_address = "ztestsapling1test"
+        viewing_key = "env:ZB_TEST_VIEWING_KEY"
+        
+        [[chains]]
+        chain_id = 1
+        name = "Ethereum"
+        chain_type = "ethereum"
+        rpc_url = "https://mainnet.infura.io/v3/${ZB_TEST_INFURA_KEY}"
+        gateway_address = "0x00000000000000000000000000000000000000aa"
+        start_block = 0
+        confirmations = 12
+        
+        [liquidity]
+        rebalance_threshold = 0.8
+        target_utilization = 0.5
+        min_liquidity_usd = 10000
+        max_rebalance_usd = 100000
+    "#;
+    
+    #[test]
+    fn test_env_interpolation_and_secret_references() {
+        let dir = tempfile::tempdir().unwrap();
+        let key_file = dir.path().join("spending_key");
+        std::fs::write(&key_file, "secret-extended-key-test1abc\n").unwrap();
+        let content = MINIMAL_CONFIG.replace("SPENDING_KEY_FILE", key_file.to_str().unwrap());
+        
+        std::env::set_var("ZB_TEST_ADMIN_TOKEN", "admin-secret");
+        std::env::set_var("ZB_TEST_ZCASH_HOST", "zcashd");
+        std::env::set_var("ZB_TEST_ZCASH_PASSWORD", "hunter2");
+        std::env::set_var("ZB_TEST_INFURA_KEY", "abc123");
+        std::env::set_var("ZB_TEST_VIEWING_KEY", "zxviewtestsapling1abc");
+        std::env::set_var("ZB_TEST_SIGNING_KEY", crate::signing::TEST_SIGNING_KEY);
+        
+        let config = Config::from_toml(&content).unwrap();
+        assert_eq!(config.zcash.rpc_url, "http://zcashd:18232");
+        assert_eq!(config.zcash.rpc_password, "hunter2");
+        assert_eq!(config.chains[0].rpc_url, "https://mainnet.infura.io/v3/abc123");
+        // Secret files lose their trailing newline
+        assert_eq!(config.zcash.spending_key, "secret-extended-key-test1abc");
+        assert_eq!(config.zcash.viewing_key.as_deref(), Some("zxviewtestsapling1abc"));
+        assert_eq!(config.admin_token.as_deref(), Some("admin-secret"));
+        assert_eq!(config.signing_key, crate::signing::TEST_SIGNING_KEY);
+        
+        // Prefixes only mean something at the start of a secret field
+        assert_eq!(resolve_secret("0xenv:abc").unwrap(), "0xenv:abc");
+        assert_eq!(interpolate_str("no references").unwrap(), "no references");
+    }
+    
+    #[test]
+    fn test_missing_env_reference_rejected() {
+        std::env::remove_var("ZB_TEST_UNSET_VAR");
+        
+        let err = interpolate_str("http://${ZB_TEST_UNSET_VAR}:18232").unwrap_err().to_string();
+        assert!(err.contains("ZB_TEST_UNSET_VAR"), "{}", err);
+        assert!(resolve_secret("env:ZB_TEST_UNSET_VAR").is_err());
+        assert!(resolve_secret("file:/nonexistent/zerobridge/secret").is_err());
+        assert!(interpolate_str("${ZB_TEST_UNSET_VAR").is_err());
+        
+        let content = MINIMAL_CONFIG.replace("${ZB_TEST_INFURA_KEY}", "${ZB_TEST_UNSET_VAR}");
+        assert!(Config::from_toml(&content).is_err());
+    }
+    
+    #[test]
+    fn test_malformed_gateway_address_rejected() {
+        let mut config = Config::template();
+        config.signing_key = crate::signing::TEST_SIGNING_KEY.to_string();
+        config.chains[0].gateway_address = "0x00000000000000000000000000000000000000aa".to_string();
+        assert!(config.validate().is_ok());
+        
+        let cases = [
+            // Truncated EVM address
+            (ChainType::Ethereum, 12, "0x1234", "0x-prefixed 20-byte hex"),
+            // Solana pubkey on an EVM chain
+            (ChainType::Base, 12, "8FGoQPMAt83sMLrxNb3yr8fQS8VBhQPEu31wCGg7b6Tc", "0x-prefixed 20-byte hex"),
+            (ChainType::Polygon, 128, "0xZZ00000000000000000000000000000000000000", "0x-prefixed 20-byte hex"),
+            // EVM address on Solana
+            (ChainType::Solana, 32, "0x00000000000000000000000000000000000000aa", "base58-encoded 32-byte"),
+            (ChainType::Near, 2, "ZeroBridge.testnet", "NEAR account id"),
+            (ChainType::Mina, 15, "B62qk...", "B62"),
+            (ChainType::Starknet, 10, "0x", "felt"),
+            (ChainType::Osmosis, 5, "cosmos1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5lzv7xu", "osmo1"),
+        ];
+        
+        for (chain_type, confirmations, address, expected) in cases {
+            config.chains[0].chain_type = chain_type;
+            config.chains[0].confirmations = confirmations;
+            config.chains[0].gateway_address = address.to_string();
+            
+            let err = config.validate().unwrap_err().to_string();
+            assert!(err.contains("Ethereum Sepolia"), "{}", err);
+            assert!(err.contains(expected), "{:?}: {}", chain_type, err);
+        }
+    }
+    
+    #[test]
+    fn test_simulation_refused_on_mainnet() {
+        let mut config = Config::template();
+        config.signing_key = crate::signing::TEST_SIGNING_KEY.to_string();
+        config.chains[0].gateway_address = "0x00000000000000000000000000000000000000aa".to_string();
+        assert!(!config.enable_simulation);
+        
+        // Needs its own key
+        config.enable_simulation = true;
+        assert!(config.validate().is_err());
+        config.simulation_signing_key = Some(crate::signing::TEST_SIGNING_KEY.to_string());
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("must differ"), "{}", err);
+        config.simulation_signing_key = Some(crate::signing::TEST_SIMULATION_KEY.to_string());
+        assert!(config.validate().is_ok());
+        
+        config.zcash.network = ZcashNetwork::Mainnet;
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("simulation"), "{}", err);
+        
+        config.enable_simulation = false;
+        assert!(config.validate().is_ok());
+    }
+    
+    #[test]
+    fn test_low_confirmations_flagged() {
+        let chain = |chain_type: ChainType, confirmations: u32| ChainConfig {
+            chain_id: 137,
+            name: "Test".to_string(),
+            chain_type,
+            rpc_url: "http://localhost:8545".to_string(),
+            ws_url: None,
+            gateway_address: match chain_type {
+                ChainType::Solana => "8FGoQPMAt83sMLrxNb3yr8fQS8VBhQPEu31wCGg7b6Tc".to_string(),
+                _ => "0x00000000000000000000000000000000000000aa".to_string(),
+            },
+            start_block: 0,
+            enabled: true,
+            confirmations,
+            accept_unverified_deposits: false,
+        };
+        let mut config: Config = toml::from_str(
+            r#"
+            tokens_config = "tokens.toml"
+            signing_key = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"
+            chains = []
+            
+            [zcash]
+            network = "testnet"
+            rpc_url = "http://localhost:18232"
+            rpc_user = "user"
+            rpc_password = "pass"
+            spending_key = "test_key"
+            source_address = "ztestsapling1test"
+            
+            [liquidity]
+            rebalance_threshold = 0.8
+            target_utilization = 0.5
+            min_liquidity_usd = 10000
+            max_rebalance_usd = 100000
+            "#,
+        )
+        .unwrap();
+        
+        // Fine for Ethereum, far too shallow for Polygon
+        config.chains = vec![chain(ChainType::Ethereum, 12)];
+        assert!(config.validate().is_ok());
+        config.chains = vec![chain(ChainType::Polygon, 12)];
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("between 128 and 5000"), "{}", err);
+        config.chains = vec![chain(ChainType::Polygon, 256)];
+        assert!(config.validate().is_ok());
+        
+        // Solana counts slots; a block-depth figure from EVM is out of range
+        config.chains = vec![chain(ChainType::Solana, 0)];
+        assert!(config.validate().is_err());
+        config.chains = vec![chain(ChainType::Solana, 32)];
+        assert!(config.validate().is_ok());
+    }
+}
@@ -0,0 +1,216 @@
+// zcash-coordinator/src/config_check.rs
+//! `--check`: verify a loaded config against the outside world before
+//! running on it. `Config::validate` only sees the file; this connects to
+//! every configured node.
+
+use anyhow::{Context, Result};
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use crate::config::{ChainType, Config};
+
+/// Per-request timeout for connectivity probes
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Outcome of a single check
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub target: String,
+    pub check: &'static str,
+    pub error: Option<String>,
+}
+
+impl CheckResult {
+    fn new(target: &str, check: &'static str, result: Result<()>) -> Self {
+        Self {
+            target: target.to_string(),
+            check,
+            error: result.err().map(|e| format!("{:#}", e)),
+        }
+    }
+
+    pub fn passed(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Probe the Zcash node and every configured chain
+pub async fn run_checks(config: &Config) -> Vec<CheckResult> {
+    let client = Client::builder()
+        .timeout(CHECK_TIMEOUT)
+        .build()
+        .unwrap_or_default();
+    let mut results = Vec::new();
+
+    let zcash = json_rpc(
+        &client,
+        &config.zcash.rpc_url,
+        "getblockchaininfo",
+        Some((&config.zcash.rpc_user, &config.zcash.rpc_password)),
+    )
+    .await;
+    results.push(CheckResult::new("zcash", "rpc", zcash));
+
+    for chain in &config.chains {
+        let target = format!("{} ({})", chain.name, chain.chain_id);
+        let rpc = match rpc_probe(chain.chain_type) {
+            Some(method) => json_rpc(&client, &chain.rpc_url, method, None).await,
+            None => reachable(&client, &chain.rpc_url).await,
+        };
+        results.push(CheckResult::new(&target, "rpc", rpc));
+        results.push(CheckResult::new(
+            &target,
+            "gateway_address",
+            chain.chain_type.check_address(&chain.gateway_address),
+        ));
+    }
+
+    results
+}
+
+/// Cheap read-only JSON-RPC method each chain's node answers; `None` for
+/// chains without a JSON-RPC API (Mina serves GraphQL)
+fn rpc_probe(chain_type: ChainType) -> Option<&'static str> {
+    match chain_type {
+        ChainType::Ethereum | ChainType::Base | ChainType::Polygon => Some("eth_chainId"),
+        ChainType::Solana => Some("getHealth"),
+        ChainType::Near | ChainType::Osmosis => Some("status"),
+        ChainType::Starknet => Some("starknet_chainId"),
+        ChainType::Mina => None,
+    }
+}
+
+async fn json_rpc(client: &Client, url: &str, method: &str, auth: Option<(&str, &str)>) -> Result<()> {
+    let mut request = client
+        .post(url)
+        .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": [] }));
+    if let Some((user, password)) = auth {
+        request = request.basic_auth(user, Some(password));
+    }
+
+    let response = request
+        .send()
+        .await
+        .with_context(|| format!("{} unreachable", url))?;
+    if !response.status().is_success() {
+        anyhow::bail!("{} returned {}", method, response.status());
+    }
+
+    let body: Value = response.json().await.context("Response is not JSON")?;
+    match body.get("error") {
+        Some(error) if !error.is_null() => anyhow::bail!("{} failed: {}", method, error),
+        _ => Ok(()),
+    }
+}
+
+async fn reachable(client: &Client, url: &str) -> Result<()> {
+    client
+        .get(url)
+        .send()
+        .await
+        .with_context(|| format!("{} unreachable", url))?;
+    Ok(())
+}
+
+/// Pass/fail table for `--check`
+pub fn format_report(results: &[CheckResult]) -> String {
+    let target_width = results.iter().map(|r| r.target.len()).max().unwrap_or(0).max("TARGET".len());
+    let check_width = results.iter().map(|r| r.check.len()).max().unwrap_or(0).max("CHECK".len());
+
+    let mut out = format!(
+        "{:<6} {:<target_width$} {:<check_width$} DETAIL\n",
+        "RESULT", "TARGET", "CHECK"
+    );
+    for result in results {
+        out.push_str(&format!(
+            "{:<6} {:<target_width$} {:<check_width$} {}\n",
+            if result.passed() { "pass" } else { "FAIL" },
+            result.target,
+            result.check,
+            result.error.as_deref().unwrap_or(""),
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::post, Json, Router};
+
+    /// JSON-RPC endpoint answering every call with `result`
+    async fn mock_rpc(result: Value) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route(
+            "/",
+            post(move || {
+                let result = result.clone();
+                async move { Json(json!({ "jsonrpc": "2.0", "id": 1, "result": result })) }
+            }),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    /// A local URL nothing listens on
+    async fn dead_url() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_checks_report_reachable_and_unreachable_endpoints() {
+        let mut config = Config::template();
+        config.zcash.rpc_url = mock_rpc(json!({ "blocks": 100 })).await;
+        config.chains[0].rpc_url = mock_rpc(json!("0xaa36a7")).await;
+        config.chains[0].gateway_address = "0x00000000000000000000000000000000000000aa".to_string();
+
+        let mut broken = config.chains[0].clone();
+        broken.chain_id = 84532;
+        broken.name = "Base Sepolia".to_string();
+        broken.chain_type = ChainType::Base;
+        broken.rpc_url = dead_url().await;
+        broken.gateway_address = "0x1234".to_string();
+        config.chains.push(broken);
+
+        let results = run_checks(&config).await;
+        let outcome = |target: &str, check: &str| {
+            results
+                .iter()
+                .find(|r| r.target == target && r.check == check)
+                .unwrap()
+                .passed()
+        };
+
+        assert!(outcome("zcash", "rpc"));
+        assert!(outcome("Ethereum Sepolia (11155111)", "rpc"));
+        assert!(outcome("Ethereum Sepolia (11155111)", "gateway_address"));
+        assert!(!outcome("Base Sepolia (84532)", "rpc"));
+        assert!(!outcome("Base Sepolia (84532)", "gateway_address"));
+
+        let report = format_report(&results);
+        assert!(report.starts_with("RESULT"));
+        assert_eq!(report.lines().filter(|l| l.starts_with("FAIL")).count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_rpc_error_response_fails() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route(
+            "/",
+            post(|| async { Json(json!({ "jsonrpc": "2.0", "id": 1, "error": { "code": -28, "message": "Loading block index" } })) }),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = Client::new();
+        let err = json_rpc(&client, &format!("http://{}", addr), "getblockchaininfo", None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Loading block index"));
+    }
+}
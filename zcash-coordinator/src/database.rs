@@ -2,14 +2,123 @@
 //! SQLite database for coordinator state persistence
 //! FOCUSED: Track deposit/withdrawal state and authorization
 
-use anyhow::Result;
-use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+use futures::future::BoxFuture;
+use sqlx::{
+    QueryBuilder, Sqlite, SqliteConnection, SqlitePool,
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous},
+};
+use std::collections::HashSet;
+use std::future::Future;
 use std::path::Path;
-use tracing::info;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use thiserror::Error;
+use tracing::{info, warn};
+
+use crate::config::{DatabaseConfig, DepositRetryConfig, SynchronousMode};
+use zerobridge_types::CoordinatorKey;
+
+/// How long a deposit lock holds if its worker never releases it (e.g. the
+/// coordinator died mid-pass). Longer than any one pass, which may wait out
+/// a note send and its confirmations.
+pub const DEPOSIT_LOCK_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// How long past its deadline an authorization keeps its liquidity, in
+/// seconds. Gateways judge the deadline by block time, which can trail the
+/// coordinator's clock; releasing early would let a late execution spend
+/// liquidity already promised elsewhere.
+pub const AUTHORIZATION_DEADLINE_GRACE: i64 = 5 * 60;
+
+/// Schema changes made after tables first shipped, oldest first. A database
+/// at `PRAGMA user_version` n has the first n; `migrate` applies the rest.
+/// Append only.
+const MIGRATIONS: &[Migration] = &[
+    Migration::AddColumn("withdrawals", "authorized_at", "INTEGER"),
+    Migration::AddColumn("withdrawals", "expired", "INTEGER NOT NULL DEFAULT 0"),
+    Migration::AddColumn("deposits", "target_chain_preferences", "TEXT NOT NULL DEFAULT '[]'"),
+    Migration::AddColumn("withdrawals", "relayer_fee", "INTEGER NOT NULL DEFAULT 0"),
+    Migration::AddColumn("deposits", "attempts", "INTEGER NOT NULL DEFAULT 0"),
+    Migration::AddColumn("deposits", "next_retry_at", "INTEGER NOT NULL DEFAULT 0"),
+    Migration::AddColumn("deposits", "failed", "INTEGER NOT NULL DEFAULT 0"),
+    Migration::AddColumn("deposits", "last_error", "TEXT"),
+    Migration::AddColumn("deposits", "block_number", "INTEGER"),
+    Migration::AddColumn("deposits", "tx_hash", "TEXT"),
+    Migration::AddColumn("deposits", "zcash_fee", "INTEGER"),
+    Migration::AddColumn("deposits", "refundable", "INTEGER NOT NULL DEFAULT 0"),
+    Migration::AddColumn("deposits", "refund_signature", "BLOB"),
+    Migration::AddColumn("deposits", "note_started_at", "INTEGER"),
+    Migration::AddColumn("deposits", "processing", "INTEGER NOT NULL DEFAULT 0"),
+    Migration::AddColumn("deposits", "processing_since", "INTEGER"),
+    // Fails, leaving the database as it was, if an older coordinator stored
+    // two withdrawals for one nullifier; remove the extra row by hand
+    Migration::Execute(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_withdrawals_nullifier ON withdrawals(nullifier)"
+    ),
+    Migration::AddColumn("withdrawals", "auth_deadline", "INTEGER"),
+];
+
+enum Migration {
+    /// `(table, column, definition)`; a table created with the column
+    /// already has it
+    AddColumn(&'static str, &'static str, &'static str),
+    /// A statement that is also safe on a freshly created database
+    Execute(&'static str),
+}
+
+/// Database layer errors, split by what a caller can do about them
+#[derive(Debug, Error)]
+pub enum DbError {
+    /// The referenced row doesn't exist
+    #[error("{0} not found")]
+    NotFound(String),
+    
+    /// A write collided with an existing row (unique/primary key)
+    #[error("conflict: {0}")]
+    Conflict(String),
+    
+    /// The database is unreachable or the pool is exhausted/closed
+    #[error("database unavailable: {0}")]
+    Connection(#[source] sqlx::Error),
+    
+    /// Any other query failure
+    #[error("query failed: {0}")]
+    Query(#[source] sqlx::Error),
+    
+    /// A JSON column failed to (de)serialize
+    #[error("invalid column data: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+impl From<sqlx::Error> for DbError {
+    fn from(err: sqlx::Error) -> Self {
+        match err {
+            sqlx::Error::RowNotFound => DbError::NotFound("row".to_string()),
+            sqlx::Error::Database(ref db_err) if db_err.is_unique_violation() => {
+                DbError::Conflict(db_err.message().to_string())
+            }
+            sqlx::Error::Io(_)
+            | sqlx::Error::Tls(_)
+            | sqlx::Error::PoolTimedOut
+            | sqlx::Error::PoolClosed
+            | sqlx::Error::WorkerCrashed => DbError::Connection(err),
+            _ => DbError::Query(err),
+        }
+    }
+}
+
+pub type DbResult<T> = std::result::Result<T, DbError>;
 
 #[derive(Clone)]
 pub struct Database {
+    /// Primary pool; all writes go through here
     pool: SqlitePool,
+    /// Read-only pool for stats and status queries, so RPC reads don't
+    /// queue behind the processing loop's writes
+    read_pool: SqlitePool,
+    /// Nullifiers known to be spent (hex). A spend is never undone, so a hit
+    /// needs no query; a miss is checked against the database, which other
+    /// coordinators sharing it may have written since.
+    spent_nullifiers: Arc<RwLock<HashSet<String>>>,
 }
 
 /// Deposit record
@@ -27,6 +136,68 @@ pub struct Deposit {
     pub zcash_txid: Option<String>,
     pub note_commitment: Option<String>,
     pub created_at: i64,
+    /// Ordered acceptable destinations; when non-empty the coordinator routes
+    /// to the first one with enough liquidity and records it as `target_chain_id`
+    pub target_chain_preferences: Vec<u64>,
+    /// Source-chain block the deposit landed in, if the relayer reported it
+    pub block_number: Option<u64>,
+    /// Source-chain transaction that emitted the deposit
+    pub tx_hash: Option<String>,
+    /// Zcash network fee deducted from the note, in canonical token units
+    pub zcash_fee: Option<u64>,
+}
+
+/// Outcome of a failed deposit attempt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepositFailure {
+    pub attempts: u32,
+    /// When the deposit becomes eligible again; `None` once dead-lettered
+    pub next_retry_at: Option<i64>,
+}
+
+/// Deposit that exhausted its attempts
+#[derive(Debug, Clone)]
+pub struct FailedDeposit {
+    pub deposit_id: String,
+    pub source_chain_id: u64,
+    pub target_chain_id: u64,
+    pub amount: u64,
+    pub attempts: u32,
+    pub last_error: String,
+}
+
+/// Deposit the coordinator gave up on, with the authorization to refund it
+/// on its source gateway
+#[derive(Debug, Clone)]
+pub struct RefundableDeposit {
+    pub deposit_id: String,
+    pub source_chain_id: u64,
+    pub sender: String,
+    pub token: String,
+    pub amount: u64,
+    pub refund_signature: Vec<u8>,
+}
+
+/// Operator intervention on a liquidity pool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiquidityAuditEntry {
+    pub chain_id: u64,
+    pub token: String,
+    pub action: String,
+    pub amount: u64,
+    pub reason: String,
+    pub created_at: i64,
+}
+
+/// Liquidity set aside for a deposit still awaiting source confirmations.
+/// Already taken out of the pool's `available`, but not yet `locked`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiquidityHold {
+    pub deposit_id: String,
+    pub chain_id: u64,
+    pub token: String,
+    pub amount: u64,
+    pub created_at: i64,
 }
 
 /// Withdrawal record
@@ -43,6 +214,182 @@ pub struct Withdrawal {
     pub authorized: bool,
     pub auth_signature: Option<Vec<u8>>,
     pub created_at: i64,
+    pub authorized_at: Option<i64>,
+    pub expired: bool,
+    /// Share of the bridge fee owed to the relayer that executes this withdrawal
+    pub relayer_fee: u64,
+    /// Last unix second the target gateway accepts `auth_signature`. `None`
+    /// for authorizations signed before deadlines existed.
+    pub auth_deadline: Option<u64>,
+}
+
+/// Column order shared by all withdrawal queries
+const WITHDRAWAL_COLUMNS: &str = "withdrawal_id, target_chain_id, recipient, token, amount, \
+    nullifier, zcash_proof, merkle_root, authorized, auth_signature, created_at, \
+    authorized_at, expired, relayer_fee, auth_deadline";
+
+type WithdrawalRow = (
+    String, i64, String, String, i64, Vec<u8>, Vec<u8>, Vec<u8>,
+    i32, Option<Vec<u8>>, i64, Option<i64>, i32, i64, Option<i64>,
+);
+
+impl From<WithdrawalRow> for Withdrawal {
+    fn from(r: WithdrawalRow) -> Self {
+        Withdrawal {
+            withdrawal_id: r.0,
+            target_chain_id: r.1 as u64,
+            recipient: r.2,
+            token: r.3,
+            amount: r.4 as u64,
+            nullifier: r.5,
+            zcash_proof: r.6,
+            merkle_root: r.7,
+            authorized: r.8 != 0,
+            auth_signature: r.9,
+            created_at: r.10,
+            authorized_at: r.11,
+            expired: r.12 != 0,
+            relayer_fee: r.13 as u64,
+            auth_deadline: r.14.map(|d| d as u64),
+        }
+    }
+}
+
+/// Hex (EVM) hashes compare case-insensitively; base58 signatures (Solana)
+/// are case-sensitive and kept as-is
+fn normalize_tx_hash(tx_hash: &str) -> String {
+    if tx_hash.starts_with("0x") || tx_hash.starts_with("0X") {
+        tx_hash.to_lowercase()
+    } else {
+        tx_hash.to_string()
+    }
+}
+
+/// Column order shared by all deposit queries
+const DEPOSIT_COLUMNS: &str = "deposit_id, source_chain_id, target_chain_id, sender, recipient, \
+    token, amount, zcash_address, processed, zcash_txid, note_commitment, created_at, \
+    target_chain_preferences, block_number, tx_hash, zcash_fee";
+
+type DepositRow = (
+    String, i64, i64, String, Vec<u8>, String, i64, Vec<u8>,
+    i32, Option<String>, Option<String>, i64, String, Option<i64>, Option<String>, Option<i64>,
+);
+
+impl From<DepositRow> for Deposit {
+    fn from(r: DepositRow) -> Self {
+        Deposit {
+            deposit_id: r.0,
+            source_chain_id: r.1 as u64,
+            target_chain_id: r.2 as u64,
+            sender: r.3,
+            recipient: r.4,
+            token: r.5,
+            amount: r.6 as u64,
+            zcash_address: r.7,
+            processed: r.8 != 0,
+            zcash_txid: r.9,
+            note_commitment: r.10,
+            created_at: r.11,
+            target_chain_preferences: serde_json::from_str(&r.12).unwrap_or_default(),
+            block_number: r.13.map(|n| n as u64),
+            tx_hash: r.14,
+            zcash_fee: r.15.map(|fee| fee as u64),
+        }
+    }
+}
+
+// ============ List Filters ============
+
+/// Filter for deposit listings. Column names are fixed here; values only
+/// ever reach SQL as bound parameters.
+#[derive(Debug, Clone, Default)]
+pub struct DepositFilter {
+    pub processed: Option<bool>,
+    pub failed: Option<bool>,
+    pub refundable: Option<bool>,
+    pub source_chain_id: Option<u64>,
+    pub target_chain_id: Option<u64>,
+    /// Only deposits whose next retry is due at or before this time
+    pub due_at: Option<i64>,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+impl DepositFilter {
+    fn build(&self) -> QueryBuilder<'static, Sqlite> {
+        let conditions = [
+            ("processed =", self.processed.map(i64::from)),
+            ("failed =", self.failed.map(i64::from)),
+            ("refundable =", self.refundable.map(i64::from)),
+            ("source_chain_id =", self.source_chain_id.map(|id| id as i64)),
+            ("target_chain_id =", self.target_chain_id.map(|id| id as i64)),
+            ("next_retry_at <=", self.due_at),
+        ];
+        
+        list_query(
+            &format!("SELECT {} FROM deposits", DEPOSIT_COLUMNS),
+            &conditions,
+            self.limit,
+            self.offset,
+        )
+    }
+}
+
+/// Filter for withdrawal listings, see [`DepositFilter`]
+#[derive(Debug, Clone, Default)]
+pub struct WithdrawalFilter {
+    pub authorized: Option<bool>,
+    pub expired: Option<bool>,
+    pub target_chain_id: Option<u64>,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+impl WithdrawalFilter {
+    fn build(&self) -> QueryBuilder<'static, Sqlite> {
+        let conditions = [
+            ("authorized =", self.authorized.map(i64::from)),
+            ("expired =", self.expired.map(i64::from)),
+            ("target_chain_id =", self.target_chain_id.map(|id| id as i64)),
+        ];
+        
+        list_query(
+            &format!("SELECT {} FROM withdrawals", WITHDRAWAL_COLUMNS),
+            &conditions,
+            self.limit,
+            self.offset,
+        )
+    }
+}
+
+/// `select` followed by a WHERE clause over the set conditions (each a
+/// column and comparison operator), oldest first, with optional LIMIT/OFFSET
+fn list_query(
+    select: &str,
+    conditions: &[(&'static str, Option<i64>)],
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> QueryBuilder<'static, Sqlite> {
+    let mut query = QueryBuilder::new(select);
+    
+    let mut first = true;
+    for (column, value) in conditions {
+        if let Some(value) = value {
+            query.push(if first { " WHERE " } else { " AND " });
+            query.push(column).push(" ").push_bind(*value);
+            first = false;
+        }
+    }
+    
+    query.push(" ORDER BY created_at ASC");
+    
+    if limit.is_some() || offset.is_some() {
+        // SQLite needs a LIMIT before OFFSET; -1 means unbounded
+        query.push(" LIMIT ").push_bind(limit.map(i64::from).unwrap_or(-1));
+        query.push(" OFFSET ").push_bind(i64::from(offset.unwrap_or(0)));
+    }
+    
+    query
 }
 
 /// Statistics
@@ -56,24 +403,101 @@ pub struct Stats {
 
 impl Database {
     /// Create new database connection
-    pub async fn new(path: &Path) -> Result<Self> {
-        let url = format!("sqlite:{}", path.display());
+    pub async fn new(path: &Path, config: &DatabaseConfig) -> DbResult<Self> {
+        let synchronous = match config.synchronous {
+            SynchronousMode::Off => SqliteSynchronous::Off,
+            SynchronousMode::Normal => SqliteSynchronous::Normal,
+            SynchronousMode::Full => SqliteSynchronous::Full,
+            SynchronousMode::Extra => SqliteSynchronous::Extra,
+        };
+        
+        // Pragmas are applied to every pooled connection, so concurrent writers
+        // from RPC handlers and the processing loop wait instead of failing
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true)
+            .journal_mode(if config.wal {
+                SqliteJournalMode::Wal
+            } else {
+                SqliteJournalMode::Delete
+            })
+            .busy_timeout(Duration::from_millis(config.busy_timeout_ms))
+            .synchronous(synchronous)
+            .foreign_keys(config.foreign_keys);
         
         let pool = SqlitePoolOptions::new()
-            .max_connections(10)
-            .connect(&url)
+            .max_connections(config.max_connections)
+            .connect_with(options)
             .await?;
         
         // Create tables
         Self::create_tables(&pool).await?;
         
-        info!("Database initialized at {:?}", path);
+        // Opened after the schema exists; journal mode is a property of the
+        // file, so the read-only connections pick up WAL from the primary
+        let read_options = SqliteConnectOptions::new()
+            .filename(path)
+            .read_only(true)
+            .busy_timeout(Duration::from_millis(config.busy_timeout_ms));
+        
+        let read_pool = SqlitePoolOptions::new()
+            .max_connections(config.read_max_connections)
+            .connect_with(read_options)
+            .await?;
+        
+        let spent: Vec<(String,)> = sqlx::query_as(
+            "SELECT nullifier FROM nullifiers WHERE spent = 1"
+        )
+        .fetch_all(&pool)
+        .await?;
+        let spent_nullifiers = spent.into_iter().map(|(nullifier,)| nullifier).collect::<HashSet<_>>();
+        
+        info!(
+            "Database initialized at {:?} (wal={}, {} spent nullifiers)",
+            path, config.wal, spent_nullifiers.len()
+        );
+        
+        Ok(Self {
+            pool,
+            read_pool,
+            spent_nullifiers: Arc::new(RwLock::new(spent_nullifiers)),
+        })
+    }
+    
+    /// Primary (read-write) connection pool
+    pub fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+    
+    /// Read-only connection pool
+    pub fn read_pool(&self) -> &SqlitePool {
+        &self.read_pool
+    }
+    
+    /// Run `f` in a single transaction: committed if it returns `Ok`, rolled
+    /// back (nothing written) if it returns `Err`. Captures must be owned,
+    /// since the future may borrow the connection only.
+    pub async fn with_transaction<T, F>(&self, f: F) -> DbResult<T>
+    where
+        T: Send,
+        F: for<'c> FnOnce(&'c mut SqliteConnection) -> BoxFuture<'c, DbResult<T>>,
+    {
+        let mut tx = self.pool.begin().await?;
         
-        Ok(Self { pool })
+        match f(&mut *tx).await {
+            Ok(value) => {
+                tx.commit().await?;
+                Ok(value)
+            }
+            Err(e) => {
+                tx.rollback().await?;
+                Err(e)
+            }
+        }
     }
     
     /// Create database tables
-    async fn create_tables(pool: &SqlitePool) -> Result<()> {
+    async fn create_tables(pool: &SqlitePool) -> DbResult<()> {
         sqlx::query(
             "CREATE TABLE IF NOT EXISTS deposits (
                 deposit_id TEXT PRIMARY KEY,
@@ -87,7 +511,20 @@ impl Database {
                 processed INTEGER NOT NULL DEFAULT 0,
                 zcash_txid TEXT,
                 note_commitment TEXT,
-                created_at INTEGER NOT NULL
+                created_at INTEGER NOT NULL,
+                target_chain_preferences TEXT NOT NULL DEFAULT '[]',
+                block_number INTEGER,
+                tx_hash TEXT,
+                zcash_fee INTEGER,
+                attempts INTEGER NOT NULL DEFAULT 0,
+                next_retry_at INTEGER NOT NULL DEFAULT 0,
+                failed INTEGER NOT NULL DEFAULT 0,
+                last_error TEXT,
+                refundable INTEGER NOT NULL DEFAULT 0,
+                refund_signature BLOB,
+                note_started_at INTEGER,
+                processing INTEGER NOT NULL DEFAULT 0,
+                processing_since INTEGER
             )"
         )
         .execute(pool)
@@ -105,7 +542,11 @@ impl Database {
                 merkle_root BLOB NOT NULL,
                 authorized INTEGER NOT NULL DEFAULT 0,
                 auth_signature BLOB,
-                created_at INTEGER NOT NULL
+                created_at INTEGER NOT NULL,
+                authorized_at INTEGER,
+                expired INTEGER NOT NULL DEFAULT 0,
+                relayer_fee INTEGER NOT NULL DEFAULT 0,
+                auth_deadline INTEGER
             )"
         )
         .execute(pool)
@@ -160,21 +601,183 @@ impl Database {
         .execute(pool)
         .await?;
         
+        // One deposit per source transaction, so a relayer can't mint a
+        // second deposit_id for a transaction that was already bridged
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS replay_protection (
+                source_chain_id INTEGER NOT NULL,
+                tx_hash TEXT NOT NULL,
+                deposit_id TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                PRIMARY KEY (source_chain_id, tx_hash)
+            )"
+        )
+        .execute(pool)
+        .await?;
+        
+        // Operator interventions on liquidity pools (force unlocks etc.)
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS liquidity_audit (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                chain_id INTEGER NOT NULL,
+                token TEXT NOT NULL,
+                action TEXT NOT NULL,
+                amount INTEGER NOT NULL,
+                reason TEXT NOT NULL,
+                created_at INTEGER NOT NULL
+            )"
+        )
+        .execute(pool)
+        .await?;
+        
+        // Soft holds for deposits awaiting finality (one per deposit)
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS liquidity_holds (
+                deposit_id TEXT PRIMARY KEY,
+                chain_id INTEGER NOT NULL,
+                token TEXT NOT NULL,
+                amount INTEGER NOT NULL,
+                created_at INTEGER NOT NULL
+            )"
+        )
+        .execute(pool)
+        .await?;
+        
+        // Operator removals layered over the token registry file.
+        // chain_id/address NULL means the whole token was removed.
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS token_overrides (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                canonical_id TEXT NOT NULL,
+                chain_id INTEGER,
+                address TEXT,
+                created_at INTEGER NOT NULL
+            )"
+        )
+        .execute(pool)
+        .await?;
+        
+        // Keys authorizations were signed with; the one with no retired_at
+        // is current
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS signing_keys (
+                public_key TEXT PRIMARY KEY,
+                address TEXT NOT NULL,
+                activated_at INTEGER NOT NULL,
+                retired_at INTEGER,
+                valid_until INTEGER
+            )"
+        )
+        .execute(pool)
+        .await?;
+        
+        // Tables that already existed keep their old columns until migrated;
+        // indexes below may need the new ones
+        Self::migrate(pool).await?;
+        
         // Create indexes
         sqlx::query("CREATE INDEX IF NOT EXISTS idx_deposits_processed ON deposits(processed)")
             .execute(pool).await?;
         sqlx::query("CREATE INDEX IF NOT EXISTS idx_withdrawals_authorized ON withdrawals(authorized)")
             .execute(pool).await?;
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_withdrawals_authorized_at ON withdrawals(authorized_at)")
+            .execute(pool).await?;
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_withdrawals_auth_deadline ON withdrawals(auth_deadline)")
+            .execute(pool).await?;
+        
+        Ok(())
+    }
+    
+    /// Bring tables created by an older coordinator up to date: apply every
+    /// step in `MIGRATIONS` past the database's `user_version`, then record
+    /// the new version. Fresh tables already have all the columns.
+    async fn migrate(pool: &SqlitePool) -> DbResult<()> {
+        let mut tx = pool.begin().await?;
         
+        let (version,): (i64,) = sqlx::query_as("PRAGMA user_version")
+            .fetch_one(&mut *tx)
+            .await?;
+        let pending = MIGRATIONS.get(version as usize..).unwrap_or_default();
+        if pending.is_empty() {
+            return Ok(());
+        }
+        
+        for migration in pending {
+            match *migration {
+                Migration::AddColumn(table, column, definition) => {
+                    let (existing,): (i64,) = sqlx::query_as(
+                        "SELECT COUNT(*) FROM pragma_table_info(?) WHERE name = ?"
+                    )
+                    .bind(table)
+                    .bind(column)
+                    .fetch_one(&mut *tx)
+                    .await?;
+                    if existing == 0 {
+                        sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))
+                            .execute(&mut *tx)
+                            .await?;
+                    }
+                }
+                Migration::Execute(statement) => {
+                    sqlx::query(statement).execute(&mut *tx).await?;
+                }
+            }
+        }
+        
+        // PRAGMA takes no bound parameters
+        sqlx::query(&format!("PRAGMA user_version = {}", MIGRATIONS.len()))
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        
+        info!("Database schema migrated from version {} to {}", version, MIGRATIONS.len());
         Ok(())
     }
     
     // ============ Deposit Operations ============
     
-    pub async fn store_deposit(&self, deposit: &Deposit) -> Result<()> {
-        sqlx::query(
-            "INSERT INTO deposits VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
-        )
+    /// Store a deposit, idempotent by `deposit_id`. Returns `false` if it
+    /// was already known (e.g. another relayer notified first).
+    ///
+    /// Deposits with a `tx_hash` also claim `(source_chain_id, tx_hash)`: a
+    /// notification reusing a transaction already claimed by a different
+    /// deposit is a `Conflict` and nothing is stored.
+    pub async fn store_deposit(&self, deposit: &Deposit) -> DbResult<bool> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        
+        let mut tx = self.pool.begin().await?;
+        
+        if let Some(tx_hash) = &deposit.tx_hash {
+            let claimed_by: Option<(String,)> = sqlx::query_as(
+                "SELECT deposit_id FROM replay_protection WHERE source_chain_id = ? AND tx_hash = ?"
+            )
+            .bind(deposit.source_chain_id as i64)
+            .bind(normalize_tx_hash(tx_hash))
+            .fetch_optional(&mut *tx)
+            .await?;
+            
+            match claimed_by {
+                Some((deposit_id,)) if deposit_id == deposit.deposit_id => return Ok(false),
+                Some((deposit_id,)) => {
+                    return Err(DbError::Conflict(format!(
+                        "transaction {} already bridged as deposit {}",
+                        tx_hash, deposit_id
+                    )));
+                }
+                None => {}
+            }
+        }
+        
+        // Only a repeated deposit_id is a no-op; other constraint violations
+        // still fail
+        let result = sqlx::query(&format!(
+            "INSERT INTO deposits ({}) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(deposit_id) DO NOTHING",
+            DEPOSIT_COLUMNS
+        ))
         .bind(&deposit.deposit_id)
         .bind(deposit.source_chain_id as i64)
         .bind(deposit.target_chain_id as i64)
@@ -187,143 +790,670 @@ impl Database {
         .bind(&deposit.zcash_txid)
         .bind(&deposit.note_commitment)
         .bind(deposit.created_at)
-        .execute(&self.pool)
+        .bind(serde_json::to_string(&deposit.target_chain_preferences)?)
+        .bind(deposit.block_number.map(|n| n as i64))
+        .bind(&deposit.tx_hash)
+        .bind(deposit.zcash_fee.map(|fee| fee as i64))
+        .execute(&mut *tx)
         .await?;
         
-        Ok(())
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+        
+        if let Some(tx_hash) = &deposit.tx_hash {
+            sqlx::query(
+                "INSERT INTO replay_protection (source_chain_id, tx_hash, deposit_id, created_at) 
+                 VALUES (?, ?, ?, ?)"
+            )
+            .bind(deposit.source_chain_id as i64)
+            .bind(normalize_tx_hash(tx_hash))
+            .bind(&deposit.deposit_id)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        }
+        
+        tx.commit().await?;
+        
+        Ok(true)
     }
     
-    pub async fn get_pending_deposits(&self) -> Result<Vec<Deposit>> {
-        let rows = sqlx::query_as::<_, (String, i64, i64, String, Vec<u8>, String, i64, Vec<u8>, i32, Option<String>, Option<String>, i64)>(
-            "SELECT * FROM deposits WHERE processed = 0 ORDER BY created_at ASC"
-        )
-        .fetch_all(&self.pool)
-        .await?;
+    /// Unprocessed deposits that aren't dead-lettered and are due for an attempt
+    pub async fn get_pending_deposits(&self) -> DbResult<Vec<Deposit>> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
         
-        Ok(rows.into_iter().map(|r| Deposit {
-            deposit_id: r.0,
-            source_chain_id: r.1 as u64,
-            target_chain_id: r.2 as u64,
-            sender: r.3,
-            recipient: r.4,
-            token: r.5,
-            amount: r.6 as u64,
-            zcash_address: r.7,
-            processed: r.8 != 0,
-            zcash_txid: r.9,
-            note_commitment: r.10,
-            created_at: r.11,
-        }).collect())
+        let filter = DepositFilter {
+            processed: Some(false),
+            failed: Some(false),
+            refundable: Some(false),
+            due_at: Some(now),
+            ..Default::default()
+        };
+        
+        let rows = filter
+            .build()
+            .build_query_as::<DepositRow>()
+            .fetch_all(&self.pool)
+            .await?;
+        
+        Ok(rows.into_iter().map(Deposit::from).collect())
     }
     
-    pub async fn mark_deposit_processed(
-        &self,
-        deposit_id: &str,
-        note_commitment: &str,
-        zcash_txid: &str,
-    ) -> Result<()> {
-        sqlx::query(
-            "UPDATE deposits SET processed = 1, note_commitment = ?, zcash_txid = ? WHERE deposit_id = ?"
-        )
-        .bind(note_commitment)
-        .bind(zcash_txid)
+    /// Filtered, paginated deposit listing (read pool)
+    pub async fn list_deposits(&self, filter: &DepositFilter) -> DbResult<Vec<Deposit>> {
+        let rows = filter
+            .build()
+            .build_query_as::<DepositRow>()
+            .fetch_all(&self.read_pool)
+            .await?;
+        
+        Ok(rows.into_iter().map(Deposit::from).collect())
+    }
+    
+    /// Look up a single deposit for status queries (read pool)
+    pub async fn get_deposit(&self, deposit_id: &str) -> DbResult<Option<Deposit>> {
+        let row = sqlx::query_as::<_, DepositRow>(&format!(
+            "SELECT {} FROM deposits WHERE deposit_id = ?",
+            DEPOSIT_COLUMNS
+        ))
         .bind(deposit_id)
-        .execute(&self.pool)
+        .fetch_optional(&self.read_pool)
         .await?;
         
-        Ok(())
+        Ok(row.map(Deposit::from))
     }
     
-    // ============ Withdrawal Operations ============
+    /// Look up a deposit in any state; `NotFound` if the id is unknown
+    pub async fn get_deposit_by_id(&self, deposit_id: &str) -> DbResult<Deposit> {
+        self.get_deposit(deposit_id)
+            .await?
+            .ok_or_else(|| DbError::NotFound(format!("deposit {}", deposit_id)))
+    }
     
-    pub async fn store_withdrawal(&self, withdrawal: &Withdrawal) -> Result<()> {
-        sqlx::query(
-            "INSERT INTO withdrawals VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
-        )
-        .bind(&withdrawal.withdrawal_id)
-        .bind(withdrawal.target_chain_id as i64)
-        .bind(&withdrawal.recipient)
-        .bind(&withdrawal.token)
-        .bind(withdrawal.amount as i64)
-        .bind(&withdrawal.nullifier)
-        .bind(&withdrawal.zcash_proof)
-        .bind(&withdrawal.merkle_root)
-        .bind(withdrawal.authorized as i32)
-        .bind(&withdrawal.auth_signature)
-        .bind(withdrawal.created_at)
-        .execute(&self.pool)
-        .await?;
+    /// Record the destination chosen by liquidity-aware routing
+    pub async fn set_deposit_target_chain(&self, deposit_id: &str, target_chain_id: u64) -> DbResult<()> {
+        let result = sqlx::query("UPDATE deposits SET target_chain_id = ? WHERE deposit_id = ?")
+            .bind(target_chain_id as i64)
+            .bind(deposit_id)
+            .execute(&self.pool)
+            .await?;
+        
+        if result.rows_affected() == 0 {
+            return Err(DbError::NotFound(format!("deposit {}", deposit_id)));
+        }
         
         Ok(())
     }
     
-    pub async fn get_pending_withdrawals(&self) -> Result<Vec<Withdrawal>> {
-        let rows = sqlx::query_as::<_, (String, i64, String, String, i64, Vec<u8>, Vec<u8>, Vec<u8>, i32, Option<Vec<u8>>, i64)>(
-            "SELECT * FROM withdrawals WHERE authorized = 0 ORDER BY created_at ASC"
+    /// Take the processing lock on a deposit. `false` if another worker,
+    /// in this coordinator or another one on the same database, holds it.
+    /// A lock older than `ttl` is taken over.
+    pub async fn lock_deposit(&self, deposit_id: &str, ttl: Duration) -> DbResult<bool> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        
+        let result = sqlx::query(
+            "UPDATE deposits SET processing = 1, processing_since = ? 
+             WHERE deposit_id = ? AND (processing = 0 OR processing_since < ?)"
         )
-        .fetch_all(&self.pool)
+        .bind(now)
+        .bind(deposit_id)
+        .bind(now - ttl.as_secs() as i64)
+        .execute(&self.pool)
         .await?;
         
-        Ok(rows.into_iter().map(|r| Withdrawal {
-            withdrawal_id: r.0,
-            target_chain_id: r.1 as u64,
-            recipient: r.2,
+        Ok(result.rows_affected() == 1)
+    }
+    
+    pub async fn unlock_deposit(&self, deposit_id: &str) -> DbResult<()> {
+        sqlx::query("UPDATE deposits SET processing = 0, processing_since = NULL WHERE deposit_id = ?")
+            .bind(deposit_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+    
+    /// Run `work` holding the deposit's lock, or return `None` without
+    /// running it if the deposit is already being processed. A lock that
+    /// can't be released is left to expire after `ttl`.
+    pub async fn with_deposit_lock<F: Future>(
+        &self,
+        deposit_id: &str,
+        ttl: Duration,
+        work: F,
+    ) -> DbResult<Option<F::Output>> {
+        if !self.lock_deposit(deposit_id, ttl).await? {
+            return Ok(None);
+        }
+        let output = work.await;
+        if let Err(e) = self.unlock_deposit(deposit_id).await {
+            warn!("Deposit {} stays locked until it expires: {}", deposit_id, e);
+        }
+        Ok(Some(output))
+    }
+    
+    /// Claim a pending deposit's one note send, just before it goes to the
+    /// Zcash node. `false` means an earlier attempt already claimed it and
+    /// may have sent the note before it could record the txid, so sending
+    /// again could mint twice.
+    pub async fn begin_deposit_note(&self, deposit_id: &str) -> DbResult<bool> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        
+        let result = sqlx::query(
+            "UPDATE deposits SET note_started_at = ? 
+             WHERE deposit_id = ? AND processed = 0 AND note_started_at IS NULL"
+        )
+        .bind(now)
+        .bind(deposit_id)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(result.rows_affected() == 1)
+    }
+    
+    /// Whether a pending deposit's note send was claimed without a txid
+    /// being recorded yet, i.e. an earlier attempt was interrupted mid-send
+    pub async fn deposit_note_started(&self, deposit_id: &str) -> DbResult<bool> {
+        let started: Option<bool> = sqlx::query_scalar(
+            "SELECT note_started_at IS NOT NULL AND zcash_txid IS NULL
+             FROM deposits WHERE deposit_id = ? AND processed = 0"
+        )
+        .bind(deposit_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(started.unwrap_or(false))
+    }
+    
+    /// Record the note sent for a deposit, leaving it pending until the
+    /// transaction confirms. A pending deposit with a `zcash_txid` already
+    /// has its note: processing resumes by waiting for confirmation.
+    pub async fn mark_deposit_note_sent(
+        &self,
+        deposit_id: &str,
+        note_commitment: &str,
+        zcash_txid: &str,
+        zcash_fee: u64,
+    ) -> DbResult<()> {
+        let result = sqlx::query(
+            "UPDATE deposits SET note_commitment = ?, zcash_txid = ?, zcash_fee = ? 
+             WHERE deposit_id = ? AND processed = 0"
+        )
+        .bind(note_commitment)
+        .bind(zcash_txid)
+        .bind(zcash_fee as i64)
+        .bind(deposit_id)
+        .execute(&self.pool)
+        .await?;
+        
+        if result.rows_affected() == 0 {
+            return Err(DbError::NotFound(format!("pending deposit {}", deposit_id)));
+        }
+        
+        Ok(())
+    }
+    
+    pub async fn mark_deposit_processed(
+        &self,
+        deposit_id: &str,
+        note_commitment: &str,
+        zcash_txid: &str,
+        zcash_fee: u64,
+    ) -> DbResult<()> {
+        let result = sqlx::query(
+            "UPDATE deposits SET processed = 1, note_commitment = ?, zcash_txid = ?, zcash_fee = ? 
+             WHERE deposit_id = ?"
+        )
+        .bind(note_commitment)
+        .bind(zcash_txid)
+        .bind(zcash_fee as i64)
+        .bind(deposit_id)
+        .execute(&self.pool)
+        .await?;
+        
+        if result.rows_affected() == 0 {
+            return Err(DbError::NotFound(format!("deposit {}", deposit_id)));
+        }
+        
+        Ok(())
+    }
+    
+    /// Record a failed processing attempt. Schedules the next attempt with
+    /// exponential backoff, or moves the deposit to `failed` once it is out
+    /// of attempts.
+    pub async fn record_deposit_failure(
+        &self,
+        deposit_id: &str,
+        error: &str,
+        retry: &DepositRetryConfig,
+    ) -> DbResult<DepositFailure> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        
+        let mut tx = self.pool.begin().await?;
+        
+        let attempts: Option<(i64,)> = sqlx::query_as(
+            "SELECT attempts FROM deposits WHERE deposit_id = ?"
+        )
+        .bind(deposit_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let attempts = attempts
+            .ok_or_else(|| DbError::NotFound(format!("deposit {}", deposit_id)))?
+            .0 as u32 + 1;
+        
+        let backoff = retry.backoff(attempts);
+        let next_retry_at = backoff.map(|secs| now + secs as i64);
+        
+        sqlx::query(
+            "UPDATE deposits SET attempts = ?, next_retry_at = ?, failed = ?, last_error = ? 
+             WHERE deposit_id = ?"
+        )
+        .bind(attempts as i64)
+        .bind(next_retry_at.unwrap_or(now))
+        .bind(backoff.is_none() as i32)
+        .bind(error)
+        .bind(deposit_id)
+        .execute(&mut *tx)
+        .await?;
+        
+        tx.commit().await?;
+        
+        Ok(DepositFailure { attempts, next_retry_at })
+    }
+    
+    /// Dead-letter a deposit that failed source-chain verification. No
+    /// retries: a mismatch with the chain won't resolve itself.
+    pub async fn mark_deposit_invalid(&self, deposit_id: &str, reason: &str) -> DbResult<()> {
+        let result = sqlx::query(
+            "UPDATE deposits SET failed = 1, last_error = ? WHERE deposit_id = ? AND processed = 0"
+        )
+        .bind(reason)
+        .bind(deposit_id)
+        .execute(&self.pool)
+        .await?;
+        
+        if result.rows_affected() == 0 {
+            return Err(DbError::NotFound(format!("pending deposit {}", deposit_id)));
+        }
+        
+        Ok(())
+    }
+    
+    /// Give up on a pending deposit and record the authorization that lets
+    /// its sender be refunded on the source chain
+    pub async fn mark_deposit_refundable(&self, deposit_id: &str, refund_signature: &[u8]) -> DbResult<()> {
+        let result = sqlx::query(
+            "UPDATE deposits SET refundable = 1, refund_signature = ? 
+             WHERE deposit_id = ? AND processed = 0 AND refundable = 0"
+        )
+        .bind(refund_signature)
+        .bind(deposit_id)
+        .execute(&self.pool)
+        .await?;
+        
+        if result.rows_affected() == 0 {
+            return Err(DbError::NotFound(format!("pending deposit {}", deposit_id)));
+        }
+        
+        Ok(())
+    }
+    
+    /// Deposits awaiting a refund on their source chain, oldest first (read pool)
+    pub async fn get_refundable_deposits(&self) -> DbResult<Vec<RefundableDeposit>> {
+        let rows = sqlx::query_as::<_, (String, i64, String, String, i64, Vec<u8>)>(
+            "SELECT deposit_id, source_chain_id, sender, token, amount, refund_signature 
+             FROM deposits WHERE refundable = 1 ORDER BY created_at ASC"
+        )
+        .fetch_all(&self.read_pool)
+        .await?;
+        
+        Ok(rows.into_iter().map(|r| RefundableDeposit {
+            deposit_id: r.0,
+            source_chain_id: r.1 as u64,
+            sender: r.2,
             token: r.3,
             amount: r.4 as u64,
-            nullifier: r.5,
-            zcash_proof: r.6,
-            merkle_root: r.7,
-            authorized: r.8 != 0,
-            auth_signature: r.9,
-            created_at: r.10,
+            refund_signature: r.5,
         }).collect())
     }
     
-    pub async fn get_authorized_withdrawals(&self) -> Result<Vec<Withdrawal>> {
-        let rows = sqlx::query_as::<_, (String, i64, String, String, i64, Vec<u8>, Vec<u8>, Vec<u8>, i32, Option<Vec<u8>>, i64)>(
-            "SELECT * FROM withdrawals WHERE authorized = 1 ORDER BY created_at ASC"
+    /// Dead-lettered deposits with their last error, oldest first (read pool)
+    pub async fn get_failed_deposits(&self) -> DbResult<Vec<FailedDeposit>> {
+        let rows = sqlx::query_as::<_, (String, i64, i64, i64, i64, Option<String>)>(
+            "SELECT deposit_id, source_chain_id, target_chain_id, amount, attempts, last_error 
+             FROM deposits WHERE failed = 1 ORDER BY created_at ASC"
         )
-        .fetch_all(&self.pool)
+        .fetch_all(&self.read_pool)
         .await?;
         
-        Ok(rows.into_iter().map(|r| Withdrawal {
-            withdrawal_id: r.0,
-            target_chain_id: r.1 as u64,
-            recipient: r.2,
-            token: r.3,
-            amount: r.4 as u64,
-            nullifier: r.5,
-            zcash_proof: r.6,
-            merkle_root: r.7,
-            authorized: r.8 != 0,
-            auth_signature: r.9,
-            created_at: r.10,
+        Ok(rows.into_iter().map(|r| FailedDeposit {
+            deposit_id: r.0,
+            source_chain_id: r.1 as u64,
+            target_chain_id: r.2 as u64,
+            amount: r.3 as u64,
+            attempts: r.4 as u32,
+            last_error: r.5.unwrap_or_default(),
         }).collect())
     }
     
+    // ============ Withdrawal Operations ============
+    
+    /// Store a new withdrawal. A nullifier can back only one withdrawal at a
+    /// time: if another pending or authorized withdrawal (or an earlier
+    /// spend) already uses it, this is a `Conflict` and nothing is written,
+    /// so two requests can't both pass verification before either is spent.
+    /// The unique index on `nullifier` enforces this, so concurrent
+    /// notifications can't both get in.
+    pub async fn store_withdrawal(&self, withdrawal: &Withdrawal) -> DbResult<()> {
+        let result = sqlx::query(&format!(
+            "INSERT INTO withdrawals ({}) 
+             SELECT ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ? 
+             WHERE NOT EXISTS (SELECT 1 FROM nullifiers WHERE nullifier = ? AND spent = 1)",
+            WITHDRAWAL_COLUMNS
+        ))
+        .bind(&withdrawal.withdrawal_id)
+        .bind(withdrawal.target_chain_id as i64)
+        .bind(&withdrawal.recipient)
+        .bind(&withdrawal.token)
+        .bind(withdrawal.amount as i64)
+        .bind(&withdrawal.nullifier)
+        .bind(&withdrawal.zcash_proof)
+        .bind(&withdrawal.merkle_root)
+        .bind(withdrawal.authorized as i32)
+        .bind(&withdrawal.auth_signature)
+        .bind(withdrawal.created_at)
+        .bind(withdrawal.authorized_at)
+        .bind(withdrawal.expired as i32)
+        .bind(withdrawal.relayer_fee as i64)
+        .bind(withdrawal.auth_deadline.map(|d| d as i64))
+        .bind(hex::encode(&withdrawal.nullifier))
+        .execute(&self.pool)
+        .await?;
+        
+        if result.rows_affected() == 0 {
+            return Err(DbError::Conflict(format!(
+                "nullifier {} already spent",
+                hex::encode(&withdrawal.nullifier)
+            )));
+        }
+        
+        Ok(())
+    }
+    
+    pub async fn get_withdrawal(&self, withdrawal_id: &str) -> DbResult<Option<Withdrawal>> {
+        let row = sqlx::query_as::<_, WithdrawalRow>(&format!(
+            "SELECT {} FROM withdrawals WHERE withdrawal_id = ?",
+            WITHDRAWAL_COLUMNS
+        ))
+        .bind(withdrawal_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(row.map(Withdrawal::from))
+    }
+    
+    pub async fn get_pending_withdrawals(&self) -> DbResult<Vec<Withdrawal>> {
+        let filter = WithdrawalFilter { authorized: Some(false), ..Default::default() };
+        
+        let rows = filter
+            .build()
+            .build_query_as::<WithdrawalRow>()
+            .fetch_all(&self.pool)
+            .await?;
+        
+        Ok(rows.into_iter().map(Withdrawal::from).collect())
+    }
+    
+    /// Filtered, paginated withdrawal listing (read pool)
+    pub async fn list_withdrawals(&self, filter: &WithdrawalFilter) -> DbResult<Vec<Withdrawal>> {
+        let rows = filter
+            .build()
+            .build_query_as::<WithdrawalRow>()
+            .fetch_all(&self.read_pool)
+            .await?;
+        
+        Ok(rows.into_iter().map(Withdrawal::from).collect())
+    }
+    
+    /// Authorized withdrawals whose deadline hasn't passed. Expired ones,
+    /// and ones signed before deadlines existed, are skipped.
+    pub async fn get_authorized_withdrawals(&self) -> DbResult<Vec<Withdrawal>> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        
+        let rows = sqlx::query_as::<_, WithdrawalRow>(&format!(
+            "SELECT {} FROM withdrawals 
+             WHERE authorized = 1 AND expired = 0 AND auth_deadline >= ? 
+             ORDER BY created_at ASC",
+            WITHDRAWAL_COLUMNS
+        ))
+        .bind(now)
+        .fetch_all(&self.read_pool)
+        .await?;
+        
+        Ok(rows.into_iter().map(Withdrawal::from).collect())
+    }
+    
+    /// Authorize a pending withdrawal. Conditional on it not being authorized
+    /// yet, so of two concurrent attempts exactly one wins; the loser gets
+    /// `Ok(false)` and must not act on its signature.
+    ///
+    /// The withdrawal's nullifier is marked spent in the same transaction: a
+    /// crash can't leave the nullifier spent without an authorization (which
+    /// would make the withdrawal impossible to re-authorize), and a retry
+    /// after a crash finds neither written. A nullifier already spent by a
+    /// different withdrawal is a `Conflict` and nothing is written.
     pub async fn authorize_withdrawal(
         &self,
         withdrawal_id: &str,
-        _token_address: &str,
-        _amount: u64,
+        token_address: &str,
+        amount: u64,
+        relayer_fee: u64,
+        deadline: u64,
+        auth_signature: &[u8],
+    ) -> DbResult<bool> {
+        let withdrawal_id = withdrawal_id.to_string();
+        let token_address = token_address.to_string();
+        let auth_signature = auth_signature.to_vec();
+        
+        // The nullifier it spent, if this call authorized it
+        let spent = self.with_transaction(move |conn| Box::pin(async move {
+            let authorized = Self::authorize_withdrawal_in(
+                conn,
+                &withdrawal_id,
+                &token_address,
+                amount,
+                relayer_fee,
+                deadline,
+                &auth_signature,
+            )
+            .await?;
+            if !authorized {
+                return Ok(None);
+            }
+            
+            let (nullifier,): (Vec<u8>,) = sqlx::query_as(
+                "SELECT nullifier FROM withdrawals WHERE withdrawal_id = ?"
+            )
+            .bind(&withdrawal_id)
+            .fetch_one(&mut *conn)
+            .await?;
+            Ok(Some(hex::encode(nullifier)))
+        }))
+        .await?;
+        
+        let authorized = spent.is_some();
+        if let Some(nullifier) = spent {
+            self.cache_spent_nullifier(&nullifier);
+        }
+        Ok(authorized)
+    }
+    
+    /// `authorize_withdrawal` on a connection the caller controls, for
+    /// composing with other writes in `with_transaction`. Once committed,
+    /// pass the nullifier to `cache_spent_nullifier` so `is_nullifier_spent`
+    /// answers without a query.
+    pub async fn authorize_withdrawal_in(
+        conn: &mut SqliteConnection,
+        withdrawal_id: &str,
+        token_address: &str,
+        amount: u64,
+        relayer_fee: u64,
+        deadline: u64,
         auth_signature: &[u8],
-    ) -> Result<()> {
+    ) -> DbResult<bool> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        
+        // Store the registry address, destination-precision amount and
+        // deadline the signature covers, so relayers and expiry use exactly
+        // those values
+        let result = sqlx::query(
+            "UPDATE withdrawals 
+             SET authorized = 1, auth_signature = ?, token = ?, amount = ?, relayer_fee = ?, 
+                 authorized_at = ?, auth_deadline = ?, expired = 0 
+             WHERE withdrawal_id = ? AND authorized = 0"
+        )
+        .bind(auth_signature)
+        .bind(token_address)
+        .bind(amount as i64)
+        .bind(relayer_fee as i64)
+        .bind(now)
+        .bind(deadline as i64)
+        .bind(withdrawal_id)
+        .execute(&mut *conn)
+        .await?;
+        
+        if result.rows_affected() == 0 {
+            let exists: Option<(String,)> = sqlx::query_as(
+                "SELECT withdrawal_id FROM withdrawals WHERE withdrawal_id = ?"
+            )
+            .bind(withdrawal_id)
+            .fetch_optional(&mut *conn)
+            .await?;
+            if exists.is_none() {
+                return Err(DbError::NotFound(format!("withdrawal {}", withdrawal_id)));
+            }
+            return Ok(false);
+        }
+        
+        let (nullifier,): (Vec<u8>,) = sqlx::query_as(
+            "SELECT nullifier FROM withdrawals WHERE withdrawal_id = ?"
+        )
+        .bind(withdrawal_id)
+        .fetch_one(&mut *conn)
+        .await?;
+        let nullifier = hex::encode(nullifier);
+        
+        let spent_by: Option<(Option<String>,)> = sqlx::query_as(
+            "SELECT withdrawal_id FROM nullifiers WHERE nullifier = ? AND spent = 1"
+        )
+        .bind(&nullifier)
+        .fetch_optional(&mut *conn)
+        .await?;
+        if let Some((spent_by,)) = spent_by {
+            if spent_by.as_deref() != Some(withdrawal_id) {
+                return Err(DbError::Conflict(format!("nullifier {} already spent", nullifier)));
+            }
+        }
+        
         sqlx::query(
-            "UPDATE withdrawals SET authorized = 1, auth_signature = ? WHERE withdrawal_id = ?"
+            "INSERT OR REPLACE INTO nullifiers (nullifier, spent, withdrawal_id, spent_at) 
+             VALUES (?, 1, ?, ?)"
+        )
+        .bind(&nullifier)
+        .bind(withdrawal_id)
+        .bind(now)
+        .execute(&mut *conn)
+        .await?;
+        
+        Ok(true)
+    }
+    
+    /// Mark authorizations more than `AUTHORIZATION_DEADLINE_GRACE` past
+    /// their deadline as expired; gateways no longer accept them. Ones signed
+    /// without a deadline expire too, since upgraded gateways reject their
+    /// signatures. Returns the withdrawals that transitioned so their
+    /// liquidity can be released.
+    pub async fn expire_authorizations(&self) -> DbResult<Vec<Withdrawal>> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        
+        let mut tx = self.pool.begin().await?;
+        
+        let rows = sqlx::query_as::<_, WithdrawalRow>(&format!(
+            "SELECT {} FROM withdrawals 
+             WHERE authorized = 1 AND expired = 0 
+               AND (auth_deadline IS NULL OR auth_deadline < ?)",
+            WITHDRAWAL_COLUMNS
+        ))
+        .bind(now - AUTHORIZATION_DEADLINE_GRACE)
+        .fetch_all(&mut *tx)
+        .await?;
+        
+        for row in &rows {
+            sqlx::query("UPDATE withdrawals SET expired = 1 WHERE withdrawal_id = ?")
+                .bind(&row.0)
+                .execute(&mut *tx)
+                .await?;
+        }
+        
+        tx.commit().await?;
+        
+        Ok(rows.into_iter().map(|r| Withdrawal { expired: true, ..Withdrawal::from(r) }).collect())
+    }
+    
+    /// Re-offer an expired authorization to relayers under a new signature
+    /// with a fresh deadline
+    pub async fn reauthorize_withdrawal(
+        &self,
+        withdrawal_id: &str,
+        deadline: u64,
+        auth_signature: &[u8],
+    ) -> DbResult<bool> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        
+        let result = sqlx::query(
+            "UPDATE withdrawals 
+             SET expired = 0, authorized_at = ?, auth_deadline = ?, auth_signature = ? 
+             WHERE withdrawal_id = ? AND authorized = 1 AND expired = 1"
         )
+        .bind(now)
+        .bind(deadline as i64)
         .bind(auth_signature)
         .bind(withdrawal_id)
         .execute(&self.pool)
         .await?;
         
-        Ok(())
+        Ok(result.rows_affected() > 0)
     }
     
     pub async fn mark_withdrawal_invalid(
         &self,
         withdrawal_id: &str,
         _reason: &str,
-    ) -> Result<()> {
+    ) -> DbResult<()> {
         sqlx::query(
             "DELETE FROM withdrawals WHERE withdrawal_id = ?"
         )
@@ -336,24 +1466,21 @@ impl Database {
     
     // ============ Nullifier Operations ============
     
-    pub async fn mark_nullifier_spent(&self, nullifier: &str) -> Result<()> {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
-        
-        sqlx::query(
-            "INSERT OR REPLACE INTO nullifiers (nullifier, spent, spent_at) VALUES (?, 1, ?)"
-        )
-        .bind(nullifier)
-        .bind(now)
-        .execute(&self.pool)
-        .await?;
-        
-        Ok(())
+    // Nullifiers are marked spent by `authorize_withdrawal`
+    
+    /// Record a nullifier whose spend was committed outside
+    /// `authorize_withdrawal`
+    pub fn cache_spent_nullifier(&self, nullifier: &str) {
+        self.spent_nullifiers.write().unwrap().insert(nullifier.to_string());
     }
     
-    pub async fn is_nullifier_spent(&self, nullifier: &str) -> Result<bool> {
+    /// Cached spends answer from memory; anything else queries the
+    /// database and caches the spend if it finds one
+    pub async fn is_nullifier_spent(&self, nullifier: &str) -> DbResult<bool> {
+        if self.spent_nullifiers.read().unwrap().contains(nullifier) {
+            return Ok(true);
+        }
+        
         let result: Option<(i32,)> = sqlx::query_as(
             "SELECT spent FROM nullifiers WHERE nullifier = ?"
         )
@@ -361,7 +1488,11 @@ impl Database {
         .fetch_optional(&self.pool)
         .await?;
         
-        Ok(result.map(|r| r.0 != 0).unwrap_or(false))
+        let spent = result.map(|r| r.0 != 0).unwrap_or(false);
+        if spent {
+            self.cache_spent_nullifier(nullifier);
+        }
+        Ok(spent)
     }
     
     // ============ Shielded Note Operations ============
@@ -373,7 +1504,7 @@ impl Database {
         amount: u64,
         source_chain_id: u64,
         token: &str,
-    ) -> Result<()> {
+    ) -> DbResult<()> {
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
@@ -402,7 +1533,19 @@ impl Database {
         token: &str,
         available: u64,
         locked: u64,
-    ) -> Result<()> {
+    ) -> DbResult<()> {
+        let mut conn = self.pool.acquire().await?;
+        Self::update_liquidity_pool_in(&mut *conn, chain_id, token, available, locked).await
+    }
+    
+    /// `update_liquidity_pool` on a connection the caller controls
+    pub async fn update_liquidity_pool_in(
+        conn: &mut SqliteConnection,
+        chain_id: u64,
+        token: &str,
+        available: u64,
+        locked: u64,
+    ) -> DbResult<()> {
         sqlx::query(
             "INSERT OR REPLACE INTO liquidity_pools (chain_id, token, available, locked, target) 
              VALUES (?, ?, ?, ?, ?)"
@@ -412,13 +1555,13 @@ impl Database {
         .bind(available as i64)
         .bind(locked as i64)
         .bind(0i64)
-        .execute(&self.pool)
+        .execute(conn)
         .await?;
         
         Ok(())
     }
     
-    pub async fn get_all_liquidity_pools(&self) -> Result<Vec<(u64, String, u64, u64, u64)>> {
+    pub async fn get_all_liquidity_pools(&self) -> DbResult<Vec<(u64, String, u64, u64, u64)>> {
         let rows = sqlx::query_as::<_, (i64, String, i64, i64, i64)>(
             "SELECT chain_id, token, available, locked, target FROM liquidity_pools"
         )
@@ -430,6 +1573,150 @@ impl Database {
         }).collect())
     }
     
+    /// Write a pool state together with the audit entry explaining it, so an
+    /// operator change is never applied without a record
+    pub async fn update_liquidity_pool_audited(
+        &self,
+        chain_id: u64,
+        token: &str,
+        available: u64,
+        locked: u64,
+        action: &str,
+        amount: u64,
+        reason: &str,
+    ) -> DbResult<()> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        
+        let mut tx = self.pool.begin().await?;
+        
+        sqlx::query(
+            "INSERT OR REPLACE INTO liquidity_pools (chain_id, token, available, locked, target) 
+             VALUES (?, ?, ?, ?, ?)"
+        )
+        .bind(chain_id as i64)
+        .bind(token)
+        .bind(available as i64)
+        .bind(locked as i64)
+        .bind(0i64)
+        .execute(&mut *tx)
+        .await?;
+        
+        sqlx::query(
+            "INSERT INTO liquidity_audit (chain_id, token, action, amount, reason, created_at) 
+             VALUES (?, ?, ?, ?, ?, ?)"
+        )
+        .bind(chain_id as i64)
+        .bind(token)
+        .bind(action)
+        .bind(amount as i64)
+        .bind(reason)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+        
+        tx.commit().await?;
+        
+        Ok(())
+    }
+    
+    /// Audit trail for a pool, oldest first
+    pub async fn get_liquidity_audit(&self, chain_id: u64, token: &str) -> DbResult<Vec<LiquidityAuditEntry>> {
+        let rows = sqlx::query_as::<_, (i64, String, String, i64, String, i64)>(
+            "SELECT chain_id, token, action, amount, reason, created_at FROM liquidity_audit 
+             WHERE chain_id = ? AND token = ? ORDER BY id ASC"
+        )
+        .bind(chain_id as i64)
+        .bind(token)
+        .fetch_all(&self.read_pool)
+        .await?;
+        
+        Ok(rows.into_iter().map(|r| LiquidityAuditEntry {
+            chain_id: r.0 as u64,
+            token: r.1,
+            action: r.2,
+            amount: r.3 as u64,
+            reason: r.4,
+            created_at: r.5,
+        }).collect())
+    }
+    
+    /// Record a hold together with the pool state it leaves behind
+    pub async fn create_liquidity_hold(
+        &self,
+        hold: &LiquidityHold,
+        available: u64,
+        locked: u64,
+    ) -> DbResult<()> {
+        let mut tx = self.pool.begin().await?;
+        
+        let result = sqlx::query(
+            "INSERT OR IGNORE INTO liquidity_holds (deposit_id, chain_id, token, amount, created_at) 
+             VALUES (?, ?, ?, ?, ?)"
+        )
+        .bind(&hold.deposit_id)
+        .bind(hold.chain_id as i64)
+        .bind(&hold.token)
+        .bind(hold.amount as i64)
+        .bind(hold.created_at)
+        .execute(&mut *tx)
+        .await?;
+        
+        if result.rows_affected() == 0 {
+            return Err(DbError::Conflict(format!("deposit {} already holds liquidity", hold.deposit_id)));
+        }
+        
+        Self::update_liquidity_pool_in(&mut *tx, hold.chain_id, &hold.token, available, locked).await?;
+        tx.commit().await?;
+        
+        Ok(())
+    }
+    
+    /// Drop a hold together with the pool state after it is released or
+    /// converted into a lock
+    pub async fn delete_liquidity_hold(
+        &self,
+        hold: &LiquidityHold,
+        available: u64,
+        locked: u64,
+    ) -> DbResult<()> {
+        let mut tx = self.pool.begin().await?;
+        
+        let result = sqlx::query("DELETE FROM liquidity_holds WHERE deposit_id = ?")
+            .bind(&hold.deposit_id)
+            .execute(&mut *tx)
+            .await?;
+        
+        if result.rows_affected() == 0 {
+            return Err(DbError::NotFound(format!("liquidity hold for deposit {}", hold.deposit_id)));
+        }
+        
+        Self::update_liquidity_pool_in(&mut *tx, hold.chain_id, &hold.token, available, locked).await?;
+        tx.commit().await?;
+        
+        Ok(())
+    }
+    
+    /// Outstanding holds, oldest first
+    pub async fn get_liquidity_holds(&self) -> DbResult<Vec<LiquidityHold>> {
+        let rows = sqlx::query_as::<_, (String, i64, String, i64, i64)>(
+            "SELECT deposit_id, chain_id, token, amount, created_at FROM liquidity_holds 
+             ORDER BY created_at ASC"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(rows.into_iter().map(|r| LiquidityHold {
+            deposit_id: r.0,
+            chain_id: r.1 as u64,
+            token: r.2,
+            amount: r.3 as u64,
+            created_at: r.4,
+        }).collect())
+    }
+    
     // ============ Zcash State Operations ============
     
     pub async fn update_zcash_state(
@@ -437,7 +1724,7 @@ impl Database {
         block_height: u32,
         best_block_hash: &str,
         sync_progress: f64,
-    ) -> Result<()> {
+    ) -> DbResult<()> {
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
@@ -457,25 +1744,141 @@ impl Database {
         Ok(())
     }
     
+    // ============ Token Registry Overrides ============
+    
+    /// Persist a token removal or (with `chain_id`/`address`) a disabled representation
+    pub async fn store_token_override(
+        &self,
+        canonical_id: &str,
+        representation: Option<(u64, &str)>,
+    ) -> DbResult<()> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        
+        sqlx::query(
+            "INSERT INTO token_overrides (canonical_id, chain_id, address, created_at) 
+             VALUES (?, ?, ?, ?)"
+        )
+        .bind(canonical_id)
+        .bind(representation.map(|(chain_id, _)| chain_id as i64))
+        .bind(representation.map(|(_, address)| address))
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+    
+    /// All token overrides, oldest first
+    pub async fn get_token_overrides(&self) -> DbResult<Vec<(String, Option<(u64, String)>)>> {
+        let rows = sqlx::query_as::<_, (String, Option<i64>, Option<String>)>(
+            "SELECT canonical_id, chain_id, address FROM token_overrides ORDER BY id ASC"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(rows.into_iter().map(|r| {
+            let representation = match (r.1, r.2) {
+                (Some(chain_id), Some(address)) => Some((chain_id as u64, address)),
+                _ => None,
+            };
+            (r.0, representation)
+        }).collect())
+    }
+    
+    // ============ Signing Keys ============
+    
+    /// Record the key the coordinator now signs with, retiring the previous
+    /// one; its signatures stay valid for another `overlap_secs`. Returns
+    /// `false` if the key was already current.
+    pub async fn record_signing_key(&self, public_key: &str, address: &str, overlap_secs: u64) -> DbResult<bool> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        
+        let mut tx = self.pool.begin().await?;
+        
+        let current: Option<(String,)> = sqlx::query_as(
+            "SELECT public_key FROM signing_keys WHERE retired_at IS NULL"
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        if current.as_ref().map(|c| c.0.as_str()) == Some(public_key) {
+            return Ok(false);
+        }
+        
+        sqlx::query("UPDATE signing_keys SET retired_at = ?, valid_until = ? WHERE retired_at IS NULL")
+            .bind(now)
+            .bind(now + overlap_secs as i64)
+            .execute(&mut *tx)
+            .await?;
+        // A key brought back after rotation becomes current again
+        sqlx::query(
+            "INSERT INTO signing_keys (public_key, address, activated_at, retired_at) 
+             VALUES (?, ?, ?, NULL)
+             ON CONFLICT(public_key) DO UPDATE SET
+                 activated_at = excluded.activated_at, retired_at = NULL, valid_until = NULL"
+        )
+        .bind(public_key)
+        .bind(address)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+        
+        tx.commit().await?;
+        
+        info!("Coordinator signing key is now {} ({})", public_key, address);
+        Ok(true)
+    }
+    
+    /// Every signing key, oldest first
+    pub async fn signing_keys(&self) -> DbResult<Vec<CoordinatorKey>> {
+        let rows = sqlx::query_as::<_, (String, String, i64, Option<i64>, Option<i64>)>(
+            "SELECT public_key, address, activated_at, retired_at, valid_until FROM signing_keys 
+             ORDER BY activated_at ASC, retired_at IS NULL ASC"
+        )
+        .fetch_all(&self.read_pool)
+        .await?;
+        
+        Ok(rows.into_iter().map(|r| CoordinatorKey {
+            public_key: r.0,
+            address: r.1,
+            activated_at: r.2,
+            retired_at: r.3,
+            valid_until: r.4,
+        }).collect())
+    }
+    
+    // ============ Health ============
+    
+    /// Round trip on the write pool; fails while the database can't take writes
+    pub async fn ping(&self) -> DbResult<()> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+    
     // ============ Statistics ============
     
-    pub async fn get_stats(&self) -> Result<Stats> {
+    pub async fn get_stats(&self) -> DbResult<Stats> {
         let deposits: (i64,) = sqlx::query_as(
             "SELECT COUNT(*) FROM deposits WHERE processed = 1"
         )
-        .fetch_one(&self.pool)
+        .fetch_one(&self.read_pool)
         .await?;
         
         let withdrawals: (i64,) = sqlx::query_as(
             "SELECT COUNT(*) FROM withdrawals WHERE authorized = 1"
         )
-        .fetch_one(&self.pool)
+        .fetch_one(&self.read_pool)
         .await?;
         
         let volume: (Option<i64>,) = sqlx::query_as(
             "SELECT SUM(amount) FROM deposits WHERE processed = 1"
         )
-        .fetch_one(&self.pool)
+        .fetch_one(&self.read_pool)
         .await?;
         
         Ok(Stats {
@@ -485,4 +1888,769 @@ impl Database {
             active_deposits: (deposits.0 - withdrawals.0) as u64,
         })
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Far enough ahead that test authorizations never lapse
+    const DEADLINE: u64 = 4_000_000_000;
+
+    async fn test_db() -> (Database, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(&dir.path().join("coordinator.db"), &DatabaseConfig::default())
+            .await
+            .unwrap();
+        (db, dir)
+    }
+
+    fn test_withdrawal(id: &str) -> Withdrawal {
+        Withdrawal {
+            withdrawal_id: id.to_string(),
+            target_chain_id: 1,
+            recipient: "0xrecipient".to_string(),
+            token: "0xToken".to_string(),
+            amount: 500,
+            nullifier: vec![1u8; 32],
+            zcash_proof: vec![2u8; 192],
+            merkle_root: vec![3u8; 32],
+            authorized: false,
+            auth_signature: None,
+            created_at: 0,
+            authorized_at: None,
+            expired: false,
+            relayer_fee: 0,
+            auth_deadline: None,
+        }
+    }
+
+    fn test_deposit(id: &str) -> Deposit {
+        Deposit {
+            deposit_id: id.to_string(),
+            source_chain_id: 1,
+            target_chain_id: 2,
+            sender: "0xsender".to_string(),
+            recipient: vec![1u8; 32],
+            token: "0xtoken".to_string(),
+            amount: 1_000,
+            zcash_address: vec![2u8; 43],
+            processed: false,
+            zcash_txid: None,
+            note_commitment: None,
+            created_at: 0,
+            target_chain_preferences: vec![],
+            block_number: None,
+            tx_hash: None,
+            zcash_fee: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_inserts_do_not_lock() {
+        let (db, _dir) = test_db().await;
+
+        let handles: Vec<_> = (0..50)
+            .map(|i| {
+                let db = db.clone();
+                tokio::spawn(async move {
+                    db.store_deposit(&test_deposit(&format!("d{}", i))).await
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.await.unwrap().expect("concurrent insert failed");
+        }
+
+        assert_eq!(db.get_pending_deposits().await.unwrap().len(), 50);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_duplicate_deposits() {
+        let (db, _dir) = test_db().await;
+
+        let handles: Vec<_> = (0..10)
+            .map(|_| {
+                let db = db.clone();
+                tokio::spawn(async move { db.store_deposit(&test_deposit("dup")).await })
+            })
+            .collect();
+
+        let mut inserted = 0;
+        for handle in handles {
+            if handle.await.unwrap().unwrap() {
+                inserted += 1;
+            }
+        }
+
+        assert_eq!(inserted, 1);
+        assert_eq!(db.get_pending_deposits().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reads_during_write_transaction() {
+        let (db, _dir) = test_db().await;
+        db.store_deposit(&test_deposit("committed")).await.unwrap();
+
+        // Hold an open write transaction on the primary pool
+        let mut tx = db.pool().begin().await.unwrap();
+        sqlx::query("UPDATE deposits SET processed = 1 WHERE deposit_id = ?")
+            .bind("committed")
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+
+        // Readers see the last committed snapshot without waiting on the writer
+        let stats = tokio::time::timeout(Duration::from_secs(1), db.get_stats())
+            .await
+            .expect("read blocked by writer")
+            .unwrap();
+        assert_eq!(stats.total_deposits, 0);
+
+        // Read-only pool rejects writes
+        assert!(sqlx::query("DELETE FROM deposits").execute(db.read_pool()).await.is_err());
+
+        tx.commit().await.unwrap();
+        assert_eq!(db.get_stats().await.unwrap().total_deposits, 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_deposit_by_id_among_many() {
+        let (db, _dir) = test_db().await;
+        for i in 0..100 {
+            db.store_deposit(&test_deposit(&format!("d{}", i))).await.unwrap();
+        }
+        db.mark_deposit_processed("d42", "cm42", "txid42", 0).await.unwrap();
+
+        let deposit = db.get_deposit_by_id("d42").await.unwrap();
+        assert_eq!(deposit.deposit_id, "d42");
+        assert!(deposit.processed);
+        assert_eq!(deposit.note_commitment.as_deref(), Some("cm42"));
+        assert!(!db.get_pending_deposits().await.unwrap().iter().any(|d| d.deposit_id == "d42"));
+
+        assert_eq!(db.get_deposit_by_id("d7").await.unwrap().deposit_id, "d7");
+        assert!(db.get_deposit("d100").await.unwrap().is_none());
+        assert!(matches!(db.get_deposit_by_id("d100").await, Err(DbError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_note_sent_deposit_pending_until_confirmed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("coordinator.db");
+        let db = Database::new(&path, &DatabaseConfig::default()).await.unwrap();
+        db.store_deposit(&test_deposit("d1")).await.unwrap();
+
+        db.mark_deposit_note_sent("d1", "cm1", "txid1", 25).await.unwrap();
+
+        // Still pending after a restart, carrying the note to wait for
+        db.pool().close().await;
+        db.read_pool().close().await;
+        let db = Database::new(&path, &DatabaseConfig::default()).await.unwrap();
+        let pending = db.get_pending_deposits().await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert!(!pending[0].processed);
+        assert_eq!(pending[0].zcash_txid.as_deref(), Some("txid1"));
+        assert_eq!(pending[0].note_commitment.as_deref(), Some("cm1"));
+        assert_eq!(pending[0].zcash_fee, Some(25));
+
+        db.mark_deposit_processed("d1", "cm1", "txid1", 25).await.unwrap();
+        assert!(db.get_pending_deposits().await.unwrap().is_empty());
+        // A processed deposit's note isn't rewritten
+        assert!(matches!(
+            db.mark_deposit_note_sent("d1", "cm2", "txid2", 25).await,
+            Err(DbError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_deposit_note_claimed_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("coordinator.db");
+        let db = Database::new(&path, &DatabaseConfig::default()).await.unwrap();
+        db.store_deposit(&test_deposit("d1")).await.unwrap();
+
+        assert!(!db.deposit_note_started("d1").await.unwrap());
+        assert!(db.begin_deposit_note("d1").await.unwrap());
+        assert!(!db.begin_deposit_note("d1").await.unwrap());
+
+        // The claim outlives a crash before the txid was recorded
+        db.pool().close().await;
+        db.read_pool().close().await;
+        let db = Database::new(&path, &DatabaseConfig::default()).await.unwrap();
+        assert_eq!(db.get_pending_deposits().await.unwrap().len(), 1);
+        assert!(!db.begin_deposit_note("d1").await.unwrap());
+        assert!(db.deposit_note_started("d1").await.unwrap());
+
+        // Once the txid is recorded the send is no longer in doubt
+        db.mark_deposit_note_sent("d1", "cm1", "txid1", 25).await.unwrap();
+        assert!(!db.deposit_note_started("d1").await.unwrap());
+
+        assert!(!db.begin_deposit_note("unknown").await.unwrap());
+        assert!(!db.deposit_note_started("unknown").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_contended_deposit_handled_once() {
+        let (db, _dir) = test_db().await;
+        db.store_deposit(&test_deposit("d1")).await.unwrap();
+
+        // Two coordinators' worker pools pick up the same deposit
+        let handled = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let workers: Vec<_> = (0..8)
+            .map(|_| {
+                let db = db.clone();
+                let handled = handled.clone();
+                tokio::spawn(async move {
+                    db.with_deposit_lock("d1", DEPOSIT_LOCK_TTL, async {
+                        handled.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                    })
+                    .await
+                    .unwrap()
+                })
+            })
+            .collect();
+        let mut ran = 0;
+        for worker in workers {
+            ran += worker.await.unwrap().is_some() as usize;
+        }
+        assert_eq!(ran, 1);
+        assert_eq!(handled.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        // Released once the pass ends
+        assert!(db.lock_deposit("d1", DEPOSIT_LOCK_TTL).await.unwrap());
+        assert!(!db.lock_deposit("d1", DEPOSIT_LOCK_TTL).await.unwrap());
+
+        // A lock left by a dead worker expires
+        sqlx::query("UPDATE deposits SET processing_since = processing_since - 3600 WHERE deposit_id = 'd1'")
+            .execute(db.pool())
+            .await
+            .unwrap();
+        assert!(db.lock_deposit("d1", DEPOSIT_LOCK_TTL).await.unwrap());
+    }
+
+    #[test]
+    fn test_list_filter_binds_values() {
+        let filter = DepositFilter {
+            processed: Some(true),
+            target_chain_id: Some(8453),
+            limit: Some(10),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            filter.build().sql(),
+            format!(
+                "SELECT {} FROM deposits WHERE processed = ? AND target_chain_id = ? \
+                 ORDER BY created_at ASC LIMIT ? OFFSET ?",
+                DEPOSIT_COLUMNS
+            )
+        );
+        assert_eq!(
+            DepositFilter::default().build().sql(),
+            format!("SELECT {} FROM deposits ORDER BY created_at ASC", DEPOSIT_COLUMNS)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_deposits_filters_and_pages() {
+        let (db, _dir) = test_db().await;
+        for i in 0..5 {
+            let deposit = Deposit {
+                target_chain_id: if i % 2 == 0 { 2 } else { 3 },
+                created_at: i,
+                ..test_deposit(&format!("d{}", i))
+            };
+            db.store_deposit(&deposit).await.unwrap();
+        }
+
+        let on_chain_2 = db
+            .list_deposits(&DepositFilter { target_chain_id: Some(2), ..Default::default() })
+            .await
+            .unwrap();
+        let ids: Vec<_> = on_chain_2.iter().map(|d| d.deposit_id.as_str()).collect();
+        assert_eq!(ids, vec!["d0", "d2", "d4"]);
+
+        let page = db
+            .list_deposits(&DepositFilter { limit: Some(2), offset: Some(3), ..Default::default() })
+            .await
+            .unwrap();
+        let ids: Vec<_> = page.iter().map(|d| d.deposit_id.as_str()).collect();
+        assert_eq!(ids, vec!["d3", "d4"]);
+    }
+
+    #[tokio::test]
+    async fn test_db_error_variants() {
+        let (db, _dir) = test_db().await;
+
+        assert!(matches!(DbError::from(sqlx::Error::RowNotFound), DbError::NotFound(_)));
+
+        let err = db.mark_deposit_processed("missing", "cm", "txid", 0).await.unwrap_err();
+        assert!(matches!(err, DbError::NotFound(_)));
+        let err = db.authorize_withdrawal("missing", "0xtoken", 1, 0, DEADLINE, &[1]).await.unwrap_err();
+        assert!(matches!(err, DbError::NotFound(_)));
+
+        db.store_withdrawal(&test_withdrawal("w1")).await.unwrap();
+        let err = db.store_withdrawal(&test_withdrawal("w1")).await.unwrap_err();
+        assert!(matches!(err, DbError::Conflict(_)));
+
+        let err: DbError = sqlx::query("SELECT * FROM no_such_table")
+            .execute(db.pool())
+            .await
+            .unwrap_err()
+            .into();
+        assert!(matches!(err, DbError::Query(_)));
+
+        db.ping().await.unwrap();
+        db.pool().close().await;
+        let err = db.get_pending_deposits().await.unwrap_err();
+        assert!(matches!(err, DbError::Connection(_)));
+        assert!(matches!(db.ping().await, Err(DbError::Connection(_))));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_authorization_single_winner() {
+        let (db, _dir) = test_db().await;
+        db.store_withdrawal(&test_withdrawal("w1")).await.unwrap();
+
+        let handles: Vec<_> = (0..10u8)
+            .map(|i| {
+                let db = db.clone();
+                tokio::spawn(async move {
+                    db.authorize_withdrawal("w1", "0xtoken", 500, 1, DEADLINE, &[i; 32]).await
+                })
+            })
+            .collect();
+
+        let mut winners = 0;
+        for handle in handles {
+            if handle.await.unwrap().unwrap() {
+                winners += 1;
+            }
+        }
+        assert_eq!(winners, 1);
+
+        // Re-running after the fact is also refused
+        assert!(!db.authorize_withdrawal("w1", "0xtoken", 500, 1, DEADLINE, &[0xff; 32]).await.unwrap());
+        assert_eq!(db.get_authorized_withdrawals().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_authorization_marks_nullifier_atomically() {
+        let (db, _dir) = test_db().await;
+        let nullifier = hex::encode([1u8; 32]);
+        db.store_withdrawal(&test_withdrawal("w1")).await.unwrap();
+
+        // Crash after proof verification, before authorizing: nothing was
+        // written, so the nullifier is still free and a retry succeeds
+        assert!(!db.is_nullifier_spent(&nullifier).await.unwrap());
+        assert!(db.authorize_withdrawal("w1", "0xtoken", 500, 1, DEADLINE, &[9u8; 32]).await.unwrap());
+        assert!(db.is_nullifier_spent(&nullifier).await.unwrap());
+        assert_eq!(db.get_authorized_withdrawals().await.unwrap().len(), 1);
+
+        // A withdrawal whose nullifier was spent after it was stored (say by
+        // another coordinator) is refused as a whole
+        db.store_withdrawal(&Withdrawal { nullifier: vec![2u8; 32], ..test_withdrawal("w2") }).await.unwrap();
+        sqlx::query("INSERT INTO nullifiers (nullifier, spent, withdrawal_id, spent_at) VALUES (?, 1, 'w0', 0)")
+            .bind(hex::encode([2u8; 32]))
+            .execute(db.pool())
+            .await
+            .unwrap();
+        assert!(matches!(
+            db.authorize_withdrawal("w2", "0xtoken", 500, 1, DEADLINE, &[9u8; 32]).await,
+            Err(DbError::Conflict(_))
+        ));
+        let w2 = db.get_withdrawal("w2").await.unwrap().unwrap();
+        assert!(!w2.authorized);
+        assert!(w2.auth_signature.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_one_withdrawal_per_nullifier() {
+        let (db, _dir) = test_db().await;
+        db.store_withdrawal(&test_withdrawal("w1")).await.unwrap();
+
+        // Same nullifier under another id while w1 is pending
+        let err = db.store_withdrawal(&test_withdrawal("w2")).await.unwrap_err();
+        assert!(matches!(err, DbError::Conflict(_)));
+        assert!(db.get_withdrawal("w2").await.unwrap().is_none());
+        
+        // The index refuses it even without the check in `store_withdrawal`
+        let err = sqlx::query(
+            "INSERT INTO withdrawals (withdrawal_id, target_chain_id, recipient, token, amount, \
+             nullifier, zcash_proof, merkle_root, created_at) \
+             VALUES ('w2', 1, '0xrecipient', '0xtoken', 500, ?, x'02', x'03', 0)"
+        )
+        .bind(vec![1u8; 32])
+        .execute(db.pool())
+        .await
+        .unwrap_err();
+        assert!(matches!(DbError::from(err), DbError::Conflict(_)));
+
+        // ...and once it's authorized
+        assert!(db.authorize_withdrawal("w1", "0xtoken", 500, 1, DEADLINE, &[9u8; 32]).await.unwrap());
+        let err = db.store_withdrawal(&test_withdrawal("w3")).await.unwrap_err();
+        assert!(matches!(err, DbError::Conflict(_)));
+
+        // Other nullifiers are unaffected
+        db.store_withdrawal(&Withdrawal { nullifier: vec![2u8; 32], ..test_withdrawal("w4") }).await.unwrap();
+
+        // A withdrawal rejected before authorization frees its nullifier
+        db.mark_withdrawal_invalid("w4", "bad proof").await.unwrap();
+        db.store_withdrawal(&Withdrawal { nullifier: vec![2u8; 32], ..test_withdrawal("w5") }).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failure_mid_transaction_rolls_back_everything() {
+        let (db, _dir) = test_db().await;
+        db.store_withdrawal(&test_withdrawal("w1")).await.unwrap();
+        db.update_liquidity_pool(1, "0xtoken", 1_000, 500).await.unwrap();
+
+        // Authorize and release liquidity, then fail before commit
+        let result: DbResult<()> = db
+            .with_transaction(|conn| Box::pin(async move {
+                assert!(Database::authorize_withdrawal_in(conn, "w1", "0xtoken", 500, 1, DEADLINE, &[9u8; 32]).await?);
+                Database::update_liquidity_pool_in(conn, 1, "0xtoken", 1_000, 0).await?;
+                Err(DbError::Query(sqlx::Error::Protocol("injected failure".into())))
+            }))
+            .await;
+        assert!(matches!(result, Err(DbError::Query(_))));
+
+        let w1 = db.get_withdrawal("w1").await.unwrap().unwrap();
+        assert!(!w1.authorized);
+        assert!(!db.is_nullifier_spent(&hex::encode([1u8; 32])).await.unwrap());
+        assert_eq!(db.get_all_liquidity_pools().await.unwrap(), vec![(1, "0xtoken".to_string(), 1_000, 500, 0)]);
+
+        // The same work without the failure lands as a unit
+        db.with_transaction(|conn| Box::pin(async move {
+            Database::authorize_withdrawal_in(conn, "w1", "0xtoken", 500, 1, DEADLINE, &[9u8; 32]).await?;
+            Database::update_liquidity_pool_in(conn, 1, "0xtoken", 1_000, 0).await
+        }))
+        .await
+        .unwrap();
+        db.cache_spent_nullifier(&hex::encode([1u8; 32]));
+        assert!(db.get_withdrawal("w1").await.unwrap().unwrap().authorized);
+        assert!(db.is_nullifier_spent(&hex::encode([1u8; 32])).await.unwrap());
+        assert_eq!(db.get_all_liquidity_pools().await.unwrap()[0].3, 0);
+    }
+
+    #[tokio::test]
+    async fn test_nullifier_cache_tracks_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("coordinator.db");
+        let db = Database::new(&path, &DatabaseConfig::default()).await.unwrap();
+        let spent = hex::encode([1u8; 32]);
+        let composed = hex::encode([2u8; 32]);
+        
+        db.store_withdrawal(&test_withdrawal("w1")).await.unwrap();
+        assert!(db.authorize_withdrawal("w1", "0xtoken", 500, 1, DEADLINE, &[9u8; 32]).await.unwrap());
+        assert!(db.is_nullifier_spent(&spent).await.unwrap());
+        
+        // Spent in a caller's transaction: found in the database before the
+        // caller caches it
+        db.store_withdrawal(&Withdrawal { nullifier: vec![2u8; 32], ..test_withdrawal("w2") }).await.unwrap();
+        db.with_transaction(|conn| Box::pin(async move {
+            Database::authorize_withdrawal_in(conn, "w2", "0xtoken", 500, 1, DEADLINE, &[9u8; 32]).await
+        }))
+        .await
+        .unwrap();
+        assert!(db.is_nullifier_spent(&composed).await.unwrap());
+        db.cache_spent_nullifier(&composed);
+        
+        // A rolled-back authorization leaves both unspent
+        db.store_withdrawal(&Withdrawal { nullifier: vec![3u8; 32], ..test_withdrawal("w3") }).await.unwrap();
+        let result: DbResult<()> = db
+            .with_transaction(|conn| Box::pin(async move {
+                Database::authorize_withdrawal_in(conn, "w3", "0xtoken", 500, 1, DEADLINE, &[9u8; 32]).await?;
+                Err(DbError::Query(sqlx::Error::Protocol("injected failure".into())))
+            }))
+            .await;
+        assert!(result.is_err());
+        assert!(!db.is_nullifier_spent(&hex::encode([3u8; 32])).await.unwrap());
+        
+        // A restart reloads every spent nullifier from the database
+        db.pool().close().await;
+        db.read_pool().close().await;
+        let db = Database::new(&path, &DatabaseConfig::default()).await.unwrap();
+        assert!(db.is_nullifier_spent(&spent).await.unwrap());
+        assert!(db.is_nullifier_spent(&composed).await.unwrap());
+        assert!(!db.is_nullifier_spent(&hex::encode([3u8; 32])).await.unwrap());
+        
+        // Spent answers come from memory alone; misses still hit SQLite
+        db.pool().close().await;
+        assert!(db.is_nullifier_spent(&spent).await.unwrap());
+        assert!(matches!(
+            db.is_nullifier_spent(&hex::encode([4u8; 32])).await,
+            Err(DbError::Connection(_))
+        ));
+    }
+    
+    #[tokio::test]
+    async fn test_nullifier_spent_by_another_coordinator_seen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("coordinator.db");
+        let first = Database::new(&path, &DatabaseConfig::default()).await.unwrap();
+        let second = Database::new(&path, &DatabaseConfig::default()).await.unwrap();
+        let nullifier = hex::encode([1u8; 32]);
+        
+        // Both have looked it up while it was unspent
+        assert!(!first.is_nullifier_spent(&nullifier).await.unwrap());
+        assert!(!second.is_nullifier_spent(&nullifier).await.unwrap());
+        
+        first.store_withdrawal(&test_withdrawal("w1")).await.unwrap();
+        assert!(first.authorize_withdrawal("w1", "0xtoken", 500, 1, DEADLINE, &[9u8; 32]).await.unwrap());
+        
+        assert!(second.is_nullifier_spent(&nullifier).await.unwrap());
+    }
+    
+    #[tokio::test]
+    async fn test_failing_deposit_backs_off_then_dead_letters() {
+        let (db, _dir) = test_db().await;
+        db.store_deposit(&test_deposit("d1")).await.unwrap();
+        let retry = DepositRetryConfig {
+            max_attempts: 3,
+            base_backoff_secs: 10,
+            max_backoff_secs: 3600,
+        };
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        let first = db.record_deposit_failure("d1", "no liquidity", &retry).await.unwrap();
+        assert_eq!(first.attempts, 1);
+        assert!((10..=11).contains(&(first.next_retry_at.unwrap() - now)));
+        // Backing off: not offered again until the retry time
+        assert!(db.get_pending_deposits().await.unwrap().is_empty());
+
+        let second = db.record_deposit_failure("d1", "no liquidity", &retry).await.unwrap();
+        assert_eq!(second.attempts, 2);
+        assert!((20..=21).contains(&(second.next_retry_at.unwrap() - now)));
+        assert!(db.get_failed_deposits().await.unwrap().is_empty());
+
+        let last = db.record_deposit_failure("d1", "pool missing", &retry).await.unwrap();
+        assert_eq!(last, DepositFailure { attempts: 3, next_retry_at: None });
+
+        let failed = db.get_failed_deposits().await.unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].deposit_id, "d1");
+        assert_eq!(failed[0].attempts, 3);
+        assert_eq!(failed[0].last_error, "pool missing");
+
+        // Make the retry time irrelevant: dead-lettered deposits stay out
+        sqlx::query("UPDATE deposits SET next_retry_at = 0").execute(db.pool()).await.unwrap();
+        assert!(db.get_pending_deposits().await.unwrap().is_empty());
+
+        assert!(matches!(
+            db.record_deposit_failure("missing", "x", &retry).await,
+            Err(DbError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_refundable_deposit_leaves_pending_queue() {
+        let (db, _dir) = test_db().await;
+        db.store_deposit(&test_deposit("d1")).await.unwrap();
+        db.store_deposit(&test_deposit("d2")).await.unwrap();
+
+        db.mark_deposit_refundable("d1", &[7u8; 32]).await.unwrap();
+
+        let pending = db.get_pending_deposits().await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].deposit_id, "d2");
+
+        let refundable = db.get_refundable_deposits().await.unwrap();
+        assert_eq!(refundable.len(), 1);
+        assert_eq!(refundable[0].deposit_id, "d1");
+        assert_eq!(refundable[0].refund_signature, vec![7u8; 32]);
+
+        // Only once, and never for processed deposits
+        assert!(matches!(db.mark_deposit_refundable("d1", &[8u8; 32]).await, Err(DbError::NotFound(_))));
+        db.mark_deposit_processed("d2", "cm2", "txid2", 0).await.unwrap();
+        assert!(matches!(db.mark_deposit_refundable("d2", &[8u8; 32]).await, Err(DbError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_deposit_dead_lettered_without_retries() {
+        let (db, _dir) = test_db().await;
+        db.store_deposit(&test_deposit("d1")).await.unwrap();
+
+        db.mark_deposit_invalid("d1", "amount 5000 != on-chain 1000").await.unwrap();
+
+        assert!(db.get_pending_deposits().await.unwrap().is_empty());
+        let failed = db.get_failed_deposits().await.unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].attempts, 0);
+        assert_eq!(failed[0].last_error, "amount 5000 != on-chain 1000");
+
+        assert!(matches!(
+            db.mark_deposit_invalid("missing", "x").await,
+            Err(DbError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_replayed_source_transaction_rejected() {
+        let (db, _dir) = test_db().await;
+        let with_tx = |id: &str, tx_hash: &str| Deposit {
+            tx_hash: Some(tx_hash.to_string()),
+            ..test_deposit(id)
+        };
+
+        assert!(db.store_deposit(&with_tx("d1", "0xAbC")).await.unwrap());
+        // Another relayer reporting the same deposit is a harmless duplicate
+        assert!(!db.store_deposit(&with_tx("d1", "0xabc")).await.unwrap());
+
+        // A fabricated deposit_id for the same transaction is refused
+        let err = db.store_deposit(&with_tx("forged", "0xabc")).await.unwrap_err();
+        assert!(matches!(err, DbError::Conflict(_)));
+        assert!(matches!(db.get_deposit_by_id("forged").await, Err(DbError::NotFound(_))));
+
+        // Same hash on a different chain is a different transaction
+        let other_chain = Deposit { source_chain_id: 2, ..with_tx("d2", "0xabc") };
+        assert!(db.store_deposit(&other_chain).await.unwrap());
+        assert_eq!(db.get_deposit_by_id("d1").await.unwrap().tx_hash.as_deref(), Some("0xAbC"));
+    }
+
+    #[tokio::test]
+    async fn test_token_overrides_roundtrip() {
+        let (db, _dir) = test_db().await;
+
+        db.store_token_override("usdc", Some((8453, "0xabc"))).await.unwrap();
+        db.store_token_override("eth", None).await.unwrap();
+
+        let overrides = db.get_token_overrides().await.unwrap();
+        assert_eq!(overrides, vec![
+            ("usdc".to_string(), Some((8453, "0xabc".to_string()))),
+            ("eth".to_string(), None),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_signing_key_history() {
+        let (db, _dir) = test_db().await;
+        assert!(db.signing_keys().await.unwrap().is_empty());
+
+        assert!(db.record_signing_key("02aa", "0xaa", 600).await.unwrap());
+        // Restarting with the same key changes nothing
+        assert!(!db.record_signing_key("02aa", "0xaa", 600).await.unwrap());
+        assert!(db.record_signing_key("03bb", "0xbb", 600).await.unwrap());
+
+        let keys = db.signing_keys().await.unwrap();
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys[0].public_key, "02aa");
+        let retired_at = keys[0].retired_at.unwrap();
+        // The old key overlaps the new one
+        assert_eq!(keys[0].valid_until, Some(retired_at + 600));
+        assert_eq!(keys[1].public_key, "03bb");
+        assert_eq!((keys[1].retired_at, keys[1].valid_until), (None, None));
+    }
+
+    #[tokio::test]
+    async fn test_database_from_before_migrations_upgraded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("coordinator.db");
+        
+        // Tables as the first release created them, with a row in each
+        let options = SqliteConnectOptions::new().filename(&path).create_if_missing(true);
+        let old = SqlitePool::connect_with(options).await.unwrap();
+        for statement in [
+            "CREATE TABLE deposits (
+                deposit_id TEXT PRIMARY KEY, source_chain_id INTEGER NOT NULL,
+                target_chain_id INTEGER NOT NULL, sender TEXT NOT NULL, recipient BLOB NOT NULL,
+                token TEXT NOT NULL, amount INTEGER NOT NULL, zcash_address BLOB NOT NULL,
+                processed INTEGER NOT NULL DEFAULT 0, zcash_txid TEXT, note_commitment TEXT,
+                created_at INTEGER NOT NULL
+            )",
+            "CREATE TABLE withdrawals (
+                withdrawal_id TEXT PRIMARY KEY, target_chain_id INTEGER NOT NULL,
+                recipient TEXT NOT NULL, token TEXT NOT NULL, amount INTEGER NOT NULL,
+                nullifier BLOB NOT NULL, zcash_proof BLOB NOT NULL, merkle_root BLOB NOT NULL,
+                authorized INTEGER NOT NULL DEFAULT 0, auth_signature BLOB, created_at INTEGER NOT NULL
+            )",
+            "INSERT INTO deposits VALUES ('d1', 1, 2, '0xsender', x'01', '0xtoken', 1000, x'02', 0, NULL, NULL, 5)",
+            "INSERT INTO withdrawals VALUES ('w1', 1, '0xrecipient', '0xtoken', 500, x'03', x'04', x'05', 0, NULL, 6)",
+        ] {
+            sqlx::query(statement).execute(&old).await.unwrap();
+        }
+        old.close().await;
+        
+        let db = Database::new(&path, &DatabaseConfig::default()).await.unwrap();
+        let (version,): (i64,) = sqlx::query_as("PRAGMA user_version").fetch_one(db.pool()).await.unwrap();
+        assert_eq!(version, MIGRATIONS.len() as i64);
+        
+        // Old rows read back with the new columns' defaults, and take writes
+        // that use them
+        let deposit = db.get_deposit("d1").await.unwrap().unwrap();
+        assert_eq!((deposit.amount, deposit.target_chain_preferences.len()), (1000, 0));
+        assert_eq!(db.get_pending_deposits().await.unwrap().len(), 1);
+        db.record_deposit_failure("d1", "rpc down", &DepositRetryConfig::default()).await.unwrap();
+        assert!(db.authorize_withdrawal("w1", "0xtoken", 500, 1, DEADLINE, &[9u8; 32]).await.unwrap());
+        assert_eq!(db.get_authorized_withdrawals().await.unwrap()[0].relayer_fee, 1);
+        drop(db);
+        
+        // Nothing left to do on the next start
+        let db = Database::new(&path, &DatabaseConfig::default()).await.unwrap();
+        assert!(db.get_withdrawal("w1").await.unwrap().unwrap().authorized);
+    }
+
+    #[tokio::test]
+    async fn test_authorization_expiry_transition() {
+        let (db, _dir) = test_db().await;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        db.store_withdrawal(&test_withdrawal("w1")).await.unwrap();
+        db.authorize_withdrawal("w1", "0xtoken", 500, 1, DEADLINE, &[9u8; 32]).await.unwrap();
+
+        // Fresh authorization is offered and not expired
+        assert_eq!(db.get_authorized_withdrawals().await.unwrap().len(), 1);
+        assert!(db.expire_authorizations().await.unwrap().is_empty());
+
+        // Just past its deadline: no longer offered, but the gateway's clock
+        // may lag, so its liquidity stays put until the grace ends
+        sqlx::query("UPDATE withdrawals SET auth_deadline = ? WHERE withdrawal_id = 'w1'")
+            .bind(now - 1)
+            .execute(db.pool())
+            .await
+            .unwrap();
+        assert!(db.get_authorized_withdrawals().await.unwrap().is_empty());
+        assert!(db.expire_authorizations().await.unwrap().is_empty());
+
+        // Past the grace: expired exactly once
+        sqlx::query("UPDATE withdrawals SET auth_deadline = ? WHERE withdrawal_id = 'w1'")
+            .bind(now - AUTHORIZATION_DEADLINE_GRACE - 1)
+            .execute(db.pool())
+            .await
+            .unwrap();
+        let expired = db.expire_authorizations().await.unwrap();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].token, "0xtoken");
+        assert!(expired[0].expired);
+        assert!(db.expire_authorizations().await.unwrap().is_empty());
+        assert!(db.get_authorized_withdrawals().await.unwrap().is_empty());
+
+        // Re-authorization puts it back on offer under the new signature
+        assert!(db.reauthorize_withdrawal("w1", DEADLINE, &[8u8; 32]).await.unwrap());
+        assert!(!db.reauthorize_withdrawal("w1", DEADLINE, &[7u8; 32]).await.unwrap());
+        let offered = db.get_authorized_withdrawals().await.unwrap();
+        assert_eq!(offered.len(), 1);
+        assert_eq!(offered[0].auth_signature.as_deref(), Some(&[8u8; 32][..]));
+        assert_eq!(offered[0].auth_deadline, Some(DEADLINE));
+
+        // Signed before deadlines existed: never offered, expired right away
+        db.store_withdrawal(&Withdrawal { nullifier: vec![2u8; 32], ..test_withdrawal("w2") }).await.unwrap();
+        db.authorize_withdrawal("w2", "0xtoken", 500, 1, DEADLINE, &[9u8; 32]).await.unwrap();
+        sqlx::query("UPDATE withdrawals SET auth_deadline = NULL WHERE withdrawal_id = 'w2'")
+            .execute(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(db.get_authorized_withdrawals().await.unwrap().len(), 1);
+        let expired = db.expire_authorizations().await.unwrap();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].withdrawal_id, "w2");
+    }
+}
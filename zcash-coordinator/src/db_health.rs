@@ -0,0 +1,162 @@
+// zcash-coordinator/src/db_health.rs
+//! Database availability gate
+//!
+//! A probe pings the database on an interval. While pings fail, write
+//! endpoints answer 503 rather than accept notifications that can't be
+//! stored, and the processing loop waits; both resume on the first ping that
+//! succeeds.
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+use crate::database::{Database, DbResult};
+
+/// How often the database is pinged, healthy or not
+pub const PROBE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Something that can tell whether the database takes writes
+#[async_trait]
+pub trait DbProbe: Send + Sync {
+    async fn ping(&self) -> DbResult<()>;
+}
+
+#[async_trait]
+impl DbProbe for Database {
+    async fn ping(&self) -> DbResult<()> {
+        Database::ping(self).await
+    }
+}
+
+/// Last probe result, shared by the RPC server and the processing loop
+#[derive(Clone)]
+pub struct DbHealth {
+    healthy: Arc<watch::Sender<bool>>,
+}
+
+impl Default for DbHealth {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DbHealth {
+    /// Starts healthy; the database was just opened
+    pub fn new() -> Self {
+        Self {
+            healthy: Arc::new(watch::channel(true).0),
+        }
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        *self.healthy.borrow()
+    }
+
+    /// Ping once and record the result, logging transitions
+    pub async fn probe<P: DbProbe + ?Sized>(&self, db: &P) -> bool {
+        let result = db.ping().await;
+        let healthy = result.is_ok();
+        let was_healthy = self.healthy.send_replace(healthy);
+
+        match result {
+            Err(e) if was_healthy => warn!("Database unhealthy, refusing writes: {}", e),
+            Ok(()) if !was_healthy => info!("Database recovered"),
+            _ => {}
+        }
+
+        healthy
+    }
+
+    /// Probe forever; spawned once at startup
+    pub async fn run_probe<P: DbProbe>(self, db: P, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            self.probe(&db).await;
+        }
+    }
+
+    /// Return once the database is healthy (immediately if it is)
+    pub async fn wait_healthy(&self) {
+        let mut receiver = self.healthy.subscribe();
+        // The sender lives as long as `self`, so this can't fail
+        let _ = receiver.wait_for(|healthy| *healthy).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DbError;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// Database stand-in that fails pings while `down` is set
+    #[derive(Clone, Default)]
+    struct FlakyDb {
+        down: Arc<AtomicBool>,
+    }
+
+    #[async_trait]
+    impl DbProbe for FlakyDb {
+        async fn ping(&self) -> DbResult<()> {
+            if self.down.load(Ordering::SeqCst) {
+                Err(DbError::Connection(sqlx::Error::PoolTimedOut))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failure_and_recovery() {
+        let db = FlakyDb::default();
+        let health = DbHealth::new();
+        assert!(health.probe(&db).await);
+
+        db.down.store(true, Ordering::SeqCst);
+        assert!(!health.probe(&db).await);
+        assert!(!health.is_healthy());
+
+        // The processing loop stays paused while the database is down
+        let waiter = tokio::spawn({
+            let health = health.clone();
+            async move { health.wait_healthy().await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+
+        // A failing probe doesn't wake it either
+        assert!(!health.probe(&db).await);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+
+        db.down.store(false, Ordering::SeqCst);
+        assert!(health.probe(&db).await);
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("waiter resumes on recovery")
+            .unwrap();
+        assert!(health.is_healthy());
+    }
+
+    #[tokio::test]
+    async fn test_background_probe_tracks_database() {
+        let db = FlakyDb::default();
+        db.down.store(true, Ordering::SeqCst);
+
+        let health = DbHealth::new();
+        let probe = tokio::spawn(health.clone().run_probe(db.clone(), Duration::from_millis(10)));
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!health.is_healthy());
+
+        db.down.store(false, Ordering::SeqCst);
+        tokio::time::timeout(Duration::from_secs(1), health.wait_healthy())
+            .await
+            .expect("probe notices recovery");
+
+        probe.abort();
+    }
+}
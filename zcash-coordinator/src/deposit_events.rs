@@ -0,0 +1,155 @@
+// zcash-coordinator/src/deposit_events.rs
+//! Live deposit progress for `GET /ws/deposits/:id`
+//!
+//! The processing loop publishes each stage a deposit reaches; the RPC
+//! server forwards them to whoever subscribed to that deposit. Channels only
+//! exist while someone is watching, so publishing for an unwatched deposit
+//! is free.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+/// Updates buffered per subscriber before it starts missing them
+const CHANNEL_CAPACITY: usize = 16;
+
+/// Stages of a deposit, in order. `Rejected` and `Refundable` end a deposit
+/// that won't complete.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DepositStage {
+    Queued,
+    LiquidityLocked,
+    NoteCreated,
+    Complete,
+    Rejected,
+    Refundable,
+}
+
+impl DepositStage {
+    /// No further updates follow
+    pub fn is_final(&self) -> bool {
+        matches!(self, DepositStage::Complete | DepositStage::Rejected | DepositStage::Refundable)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DepositUpdate {
+    pub deposit_id: String,
+    pub stage: DepositStage,
+    pub timestamp: i64,
+    /// Zcash txid once the note exists, or the rejection reason
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Per-deposit broadcast channels shared by the coordinator and RPC server
+#[derive(Clone, Default)]
+pub struct DepositEvents {
+    channels: Arc<Mutex<HashMap<String, broadcast::Sender<DepositUpdate>>>>,
+}
+
+impl DepositEvents {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Receive every update published for `deposit_id` from now on
+    pub fn subscribe(&self, deposit_id: &str) -> broadcast::Receiver<DepositUpdate> {
+        self.channels
+            .lock()
+            .unwrap()
+            .entry(deposit_id.to_string())
+            .or_insert_with(|| broadcast::channel(CHANNEL_CAPACITY).0)
+            .subscribe()
+    }
+
+    pub fn publish(&self, deposit_id: &str, stage: DepositStage, detail: Option<String>) {
+        let mut channels = self.channels.lock().unwrap();
+        let Some(sender) = channels.get(deposit_id) else {
+            return;
+        };
+
+        let update = DepositUpdate {
+            deposit_id: deposit_id.to_string(),
+            stage,
+            timestamp: chrono::Utc::now().timestamp(),
+            detail,
+        };
+        // Fails only when every subscriber has gone away
+        let delivered = sender.send(update).is_ok();
+
+        if stage.is_final() || !delivered {
+            channels.remove(deposit_id);
+        }
+    }
+
+    /// Deposits with at least one subscriber
+    pub fn watched(&self) -> usize {
+        self.channels.lock().unwrap().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_subscriber_receives_stages_in_order() {
+        let events = DepositEvents::new();
+        let mut updates = events.subscribe("d1");
+
+        // As published by notify and the processing loop
+        events.publish("d1", DepositStage::Queued, None);
+        events.publish("d2", DepositStage::Queued, None);
+        events.publish("d1", DepositStage::LiquidityLocked, None);
+        events.publish("d1", DepositStage::NoteCreated, Some("txid".to_string()));
+        events.publish("d1", DepositStage::Complete, None);
+
+        let mut stages = Vec::new();
+        while let Ok(update) = updates.recv().await {
+            assert_eq!(update.deposit_id, "d1");
+            stages.push(update.stage);
+        }
+        assert_eq!(
+            stages,
+            vec![
+                DepositStage::Queued,
+                DepositStage::LiquidityLocked,
+                DepositStage::NoteCreated,
+                DepositStage::Complete,
+            ]
+        );
+
+        // The channel is gone once the deposit is done
+        assert_eq!(events.watched(), 0);
+    }
+
+    #[test]
+    fn test_unwatched_deposits_keep_no_channel() {
+        let events = DepositEvents::new();
+        events.publish("d1", DepositStage::Queued, None);
+        assert_eq!(events.watched(), 0);
+
+        // Subscriber disconnected before the deposit finished
+        drop(events.subscribe("d1"));
+        assert_eq!(events.watched(), 1);
+        events.publish("d1", DepositStage::NoteCreated, None);
+        assert_eq!(events.watched(), 0);
+    }
+
+    #[test]
+    fn test_update_json() {
+        let update = DepositUpdate {
+            deposit_id: "d1".to_string(),
+            stage: DepositStage::LiquidityLocked,
+            timestamp: 1,
+            detail: None,
+        };
+        assert_eq!(
+            serde_json::to_string(&update).unwrap(),
+            r#"{"deposit_id":"d1","stage":"liquidity_locked","timestamp":1}"#
+        );
+    }
+}
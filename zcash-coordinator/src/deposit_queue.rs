@@ -0,0 +1,205 @@
+// zcash-coordinator/src/deposit_queue.rs
+//! Deposit notifications on their way to SQLite
+//!
+//! `POST /deposits/notify` validates and enqueues; a single writer task
+//! drains the queue into the database, so a slow disk delays processing
+//! rather than relayers. The queue is bounded, and a full queue is refused
+//! immediately (429) so relayers back off instead of piling up requests.
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{debug, error, info, warn};
+
+use crate::database::{Database, DbError, Deposit};
+use crate::db_health::DbHealth;
+use crate::deposit_events::{DepositEvents, DepositStage};
+use crate::metrics;
+
+/// Pause before retrying a write the database couldn't take
+const WRITE_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// What happened to a notification handed to the queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Enqueued {
+    Queued,
+    /// Already waiting to be written; another relayer got there first
+    Duplicate,
+    /// No room; the relayer should retry later
+    Full,
+}
+
+/// Handler side of the queue
+#[derive(Clone)]
+pub struct DepositQueue {
+    sender: mpsc::Sender<Deposit>,
+    /// Deposits enqueued but not yet written, so repeat notifications are
+    /// answered as duplicates before they reach the database
+    in_flight: Arc<Mutex<HashSet<String>>>,
+}
+
+/// Writer side of the queue; `run` it on its own task
+pub struct DepositWriter {
+    receiver: mpsc::Receiver<Deposit>,
+    in_flight: Arc<Mutex<HashSet<String>>>,
+}
+
+impl DepositQueue {
+    pub fn new(capacity: usize) -> (Self, DepositWriter) {
+        let (sender, receiver) = mpsc::channel(capacity);
+        let in_flight = Arc::new(Mutex::new(HashSet::new()));
+
+        let queue = Self {
+            sender,
+            in_flight: in_flight.clone(),
+        };
+        (queue, DepositWriter { receiver, in_flight })
+    }
+
+    /// Hand a deposit to the writer. Never waits.
+    pub fn enqueue(&self, deposit: Deposit) -> Enqueued {
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if in_flight.contains(&deposit.deposit_id) {
+            return Enqueued::Duplicate;
+        }
+
+        let deposit_id = deposit.deposit_id.clone();
+        match self.sender.try_send(deposit) {
+            Ok(()) => {
+                in_flight.insert(deposit_id);
+                metrics::DEPOSIT_QUEUE_DEPTH.set(in_flight.len() as i64);
+                Enqueued::Queued
+            }
+            // A closed queue means the writer died; refuse like a full one
+            Err(_) => Enqueued::Full,
+        }
+    }
+
+    /// Deposits accepted but not yet written
+    pub fn pending(&self) -> usize {
+        self.in_flight.lock().unwrap().len()
+    }
+}
+
+impl DepositWriter {
+    /// Write deposits until every `DepositQueue` handle is dropped
+    pub async fn run(mut self, db: Database, events: DepositEvents, health: DbHealth) {
+        while let Some(deposit) = self.receiver.recv().await {
+            Self::write(&db, &events, &health, &deposit).await;
+            let mut in_flight = self.in_flight.lock().unwrap();
+            in_flight.remove(&deposit.deposit_id);
+            metrics::DEPOSIT_QUEUE_DEPTH.set(in_flight.len() as i64);
+        }
+    }
+
+    /// Store one deposit. Relayers were already told it's queued, so
+    /// connection failures are retried until the database comes back.
+    async fn write(db: &Database, events: &DepositEvents, health: &DbHealth, deposit: &Deposit) {
+        loop {
+            match db.store_deposit(deposit).await {
+                Ok(true) => {
+                    info!("Deposit queued for processing: {}", deposit.deposit_id);
+                    events.publish(&deposit.deposit_id, DepositStage::Queued, None);
+                }
+                // Several relayers watch the same chains; later notifications are expected
+                Ok(false) => debug!("Deposit already known: {}", deposit.deposit_id),
+                Err(DbError::Conflict(reason)) => {
+                    warn!("Rejected deposit {}: {}", deposit.deposit_id, reason);
+                    events.publish(&deposit.deposit_id, DepositStage::Rejected, Some(reason));
+                }
+                Err(e @ DbError::Connection(_)) => {
+                    warn!("Deposit {} not stored, retrying: {}", deposit.deposit_id, e);
+                    health.wait_healthy().await;
+                    tokio::time::sleep(WRITE_RETRY_DELAY).await;
+                    continue;
+                }
+                Err(e) => error!("Failed to store deposit {}: {}", deposit.deposit_id, e),
+            }
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DatabaseConfig;
+    use std::time::Instant;
+
+    fn deposit(id: &str) -> Deposit {
+        Deposit {
+            deposit_id: id.to_string(),
+            source_chain_id: 1,
+            target_chain_id: 8453,
+            sender: "0xsender".to_string(),
+            recipient: vec![1u8; 20],
+            token: "0xtoken".to_string(),
+            amount: 1_000,
+            zcash_address: vec![2u8; 43],
+            processed: false,
+            zcash_txid: None,
+            note_commitment: None,
+            created_at: 0,
+            target_chain_preferences: vec![],
+            block_number: None,
+            tx_hash: Some(format!("0x{}", id)),
+            zcash_fee: None,
+        }
+    }
+
+    #[test]
+    fn test_full_queue_rejects_without_blocking() {
+        // No writer draining it
+        let (queue, writer) = DepositQueue::new(2);
+
+        let started = Instant::now();
+        assert_eq!(queue.enqueue(deposit("d1")), Enqueued::Queued);
+        assert_eq!(queue.enqueue(deposit("d2")), Enqueued::Queued);
+        assert_eq!(queue.enqueue(deposit("d1")), Enqueued::Duplicate);
+        assert_eq!(queue.enqueue(deposit("d3")), Enqueued::Full);
+        assert!(started.elapsed() < Duration::from_millis(100));
+
+        // Refused deposits aren't tracked, so the relayer's retry is accepted
+        assert_eq!(queue.pending(), 2);
+
+        // With the writer gone nothing can be queued
+        drop(writer);
+        assert_eq!(queue.enqueue(deposit("d4")), Enqueued::Full);
+    }
+
+    #[tokio::test]
+    async fn test_writer_drains_to_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(&dir.path().join("coordinator.db"), &DatabaseConfig::default())
+            .await
+            .unwrap();
+        let events = DepositEvents::new();
+        let mut updates = events.subscribe("d1");
+
+        let (queue, writer) = DepositQueue::new(16);
+        let writer = tokio::spawn(writer.run(db.clone(), events, DbHealth::new()));
+
+        for id in ["d1", "d2", "d3"] {
+            assert_eq!(queue.enqueue(deposit(id)), Enqueued::Queued);
+        }
+        // Same source transaction as d1 under another id
+        let mut replay = deposit("d4");
+        replay.tx_hash = Some("0xd1".to_string());
+        assert_eq!(queue.enqueue(replay), Enqueued::Queued);
+
+        let update = tokio::time::timeout(Duration::from_secs(5), updates.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(update.stage, DepositStage::Queued);
+
+        drop(queue);
+        tokio::time::timeout(Duration::from_secs(5), writer).await.unwrap().unwrap();
+
+        for id in ["d1", "d2", "d3"] {
+            assert!(db.get_deposit(id).await.unwrap().is_some(), "{} not written", id);
+        }
+        assert!(db.get_deposit("d4").await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,408 @@
+// zcash-coordinator/src/deposit_verifier.rs
+//! Confirm a relayer-reported deposit against the source chain before a
+//! Zcash note is minted for it
+//! FOCUSED: Reads the deposit event back from the reported transaction;
+//! event listening stays with the relayers
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use ethers::utils::keccak256;
+use reqwest::Client;
+use serde_json::{json, Value};
+
+use crate::config::ChainConfig;
+use crate::database::Deposit;
+
+/// EVM gateway deposit event
+pub const TOKENS_LOCKED_SIGNATURE: &str =
+    "TokensLocked(bytes32,address,address,uint256,uint64,bytes32,bytes32,uint256)";
+
+/// A deposit as recorded by the source-chain gateway
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OnchainDeposit {
+    pub token: String,
+    pub amount: u64,
+    pub target_chain_id: u64,
+    pub recipient: Vec<u8>,
+    /// The gateway's `bytes32` field: the leading 32 bytes of the raw
+    /// Zcash address the note goes to
+    pub zcash_address: Vec<u8>,
+    /// Block the deposit transaction was included in
+    pub block_number: u64,
+}
+
+/// Result of looking a deposit up on its source chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DepositLookup {
+    Found(OnchainDeposit),
+    /// The transaction doesn't exist, failed, or didn't emit this deposit
+    NotFound,
+    /// No client for this chain type
+    Unsupported,
+}
+
+/// Outcome of verifying a pending deposit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DepositVerification {
+    /// Matches the source chain; finality is judged from its block there,
+    /// not the one the relayer reported
+    Verified { block_number: u64 },
+    /// The chain can't be read back; acceptable only where the chain is
+    /// configured with `accept_unverified_deposits`
+    Unverifiable,
+    Invalid(String),
+}
+
+/// Per-chain access to gateway deposit records
+#[async_trait]
+pub trait SourceChainClient: Send + Sync {
+    async fn fetch_deposit(
+        &self,
+        chain: &ChainConfig,
+        tx_hash: &str,
+        deposit_id: &str,
+    ) -> Result<DepositLookup>;
+}
+
+/// Check `deposit` against what its source chain recorded
+pub async fn verify_deposit(
+    client: &dyn SourceChainClient,
+    chain: &ChainConfig,
+    deposit: &Deposit,
+) -> Result<DepositVerification> {
+    let Some(tx_hash) = &deposit.tx_hash else {
+        return Ok(DepositVerification::Invalid("no source transaction reported".to_string()));
+    };
+
+    let onchain = match client.fetch_deposit(chain, tx_hash, &deposit.deposit_id).await? {
+        DepositLookup::Found(onchain) => onchain,
+        DepositLookup::NotFound => {
+            return Ok(DepositVerification::Invalid(format!(
+                "deposit not emitted by transaction {}",
+                tx_hash
+            )));
+        }
+        DepositLookup::Unsupported => return Ok(DepositVerification::Unverifiable),
+    };
+
+    // Routing may move `target_chain_id`; the requested chain stays first
+    // among the preferences
+    let requested_target = deposit.target_chain_preferences.first().copied().unwrap_or(deposit.target_chain_id);
+
+    let mismatch = if onchain.amount != deposit.amount {
+        Some(format!("amount {} != on-chain {}", deposit.amount, onchain.amount))
+    } else if onchain.recipient != deposit.recipient {
+        Some("recipient differs from on-chain deposit".to_string())
+    } else if !onchain.token.eq_ignore_ascii_case(&deposit.token) {
+        Some(format!("token {} != on-chain {}", deposit.token, onchain.token))
+    } else if onchain.target_chain_id != requested_target {
+        Some(format!("target chain {} != on-chain {}", requested_target, onchain.target_chain_id))
+    } else if !deposit.zcash_address.starts_with(&onchain.zcash_address) {
+        Some("Zcash address differs from on-chain deposit".to_string())
+    } else if let Some(block) = deposit.block_number.filter(|&block| block != onchain.block_number) {
+        Some(format!("block {} != on-chain {}", block, onchain.block_number))
+    } else {
+        None
+    };
+
+    Ok(match mismatch {
+        Some(reason) => DepositVerification::Invalid(reason),
+        None => DepositVerification::Verified { block_number: onchain.block_number },
+    })
+}
+
+/// Reads `TokensLocked` events back from EVM transaction receipts
+#[derive(Clone, Default)]
+pub struct EvmSourceClient {
+    client: Client,
+}
+
+impl EvmSourceClient {
+    pub fn new() -> Self {
+        Self {
+            client: Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl SourceChainClient for EvmSourceClient {
+    async fn fetch_deposit(
+        &self,
+        chain: &ChainConfig,
+        tx_hash: &str,
+        deposit_id: &str,
+    ) -> Result<DepositLookup> {
+        if !chain.chain_type.is_evm() {
+            return Ok(DepositLookup::Unsupported);
+        }
+
+        let payload = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_getTransactionReceipt",
+            "params": [tx_hash]
+        });
+
+        let response: Value = self.client
+            .post(&chain.rpc_url)
+            .json(&payload)
+            .send()
+            .await
+            .with_context(|| format!("eth_getTransactionReceipt request to chain {} failed", chain.chain_id))?
+            .json()
+            .await?;
+
+        if let Some(error) = response.get("error") {
+            anyhow::bail!("eth_getTransactionReceipt failed: {}", error);
+        }
+
+        let receipt = response.get("result").unwrap_or(&Value::Null);
+        Ok(match deposit_from_receipt(receipt, &chain.gateway_address, deposit_id)? {
+            Some(onchain) => DepositLookup::Found(onchain),
+            None => DepositLookup::NotFound,
+        })
+    }
+}
+
+/// Find the gateway's `TokensLocked` log for `deposit_id` in a receipt.
+/// Unknown (`null`) or reverted transactions have none.
+fn deposit_from_receipt(
+    receipt: &Value,
+    gateway_address: &str,
+    deposit_id: &str,
+) -> Result<Option<OnchainDeposit>> {
+    if receipt.get("status").and_then(|s| s.as_str()) != Some("0x1") {
+        return Ok(None);
+    }
+
+    let event_topic = format!("0x{}", hex::encode(keccak256(TOKENS_LOCKED_SIGNATURE)));
+    let deposit_topic = format!("0x{}", deposit_id.trim_start_matches("0x").to_lowercase());
+
+    let logs = receipt.get("logs").and_then(|l| l.as_array()).map(Vec::as_slice).unwrap_or(&[]);
+    for log in logs {
+        let from_gateway = log
+            .get("address")
+            .and_then(|a| a.as_str())
+            .map(|a| a.eq_ignore_ascii_case(gateway_address))
+            .unwrap_or(false);
+        let topics: Vec<String> = log
+            .get("topics")
+            .and_then(|t| t.as_array())
+            .map(|t| t.iter().filter_map(|t| t.as_str()).map(str::to_lowercase).collect())
+            .unwrap_or_default();
+
+        if !from_gateway || topics.len() < 4 || topics[0] != event_topic || topics[1] != deposit_topic {
+            continue;
+        }
+
+        let data = log.get("data").and_then(|d| d.as_str()).context("Log without data")?;
+        let data = hex::decode(data.trim_start_matches("0x")).context("Invalid log data")?;
+        if data.len() < 128 {
+            anyhow::bail!("Malformed TokensLocked log");
+        }
+
+        // ABI words: amount, targetChainId, recipient, zcashAddress, timestamp
+        if data[..24].iter().any(|b| *b != 0) {
+            anyhow::bail!("Deposit amount exceeds u64");
+        }
+        let amount = u64::from_be_bytes(data[24..32].try_into()?);
+        if data[32..56].iter().any(|b| *b != 0) {
+            anyhow::bail!("Target chain id exceeds u64");
+        }
+        let target_chain_id = u64::from_be_bytes(data[56..64].try_into()?);
+
+        let block_number = receipt
+            .get("blockNumber")
+            .and_then(|b| b.as_str())
+            .context("Receipt without blockNumber")?;
+        let block_number = u64::from_str_radix(block_number.trim_start_matches("0x"), 16)
+            .context("Invalid receipt blockNumber")?;
+
+        return Ok(Some(OnchainDeposit {
+            token: format!("0x{}", &topics[3][topics[3].len() - 40..]),
+            amount,
+            target_chain_id,
+            recipient: data[64..96].to_vec(),
+            zcash_address: data[96..128].to_vec(),
+            block_number,
+        }));
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ChainType;
+
+    const GATEWAY: &str = "0xAbCdEf0000000000000000000000000000000001";
+    const TOKEN: &str = "0x00000000000000000000000000000000000000bb";
+
+    fn chain() -> ChainConfig {
+        ChainConfig {
+            chain_id: 1,
+            name: "Ethereum".to_string(),
+            chain_type: ChainType::Ethereum,
+            rpc_url: "http://localhost:8545".to_string(),
+            ws_url: None,
+            gateway_address: GATEWAY.to_string(),
+            start_block: 0,
+            enabled: true,
+            confirmations: 1,
+            accept_unverified_deposits: false,
+        }
+    }
+
+    fn deposit(amount: u64, recipient: Vec<u8>) -> Deposit {
+        Deposit {
+            deposit_id: hex::encode([7u8; 32]),
+            source_chain_id: 1,
+            target_chain_id: 8453,
+            sender: "0xsender".to_string(),
+            recipient,
+            token: TOKEN.to_string(),
+            amount,
+            zcash_address: vec![2u8; 43],
+            processed: false,
+            zcash_txid: None,
+            note_commitment: None,
+            created_at: 0,
+            target_chain_preferences: vec![],
+            block_number: Some(100),
+            tx_hash: Some("0xfeed".to_string()),
+            zcash_fee: None,
+        }
+    }
+
+    /// Returns the same lookup for every transaction
+    struct MockSource(DepositLookup);
+
+    #[async_trait]
+    impl SourceChainClient for MockSource {
+        async fn fetch_deposit(&self, _: &ChainConfig, _: &str, _: &str) -> Result<DepositLookup> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn found(amount: u64, recipient: Vec<u8>) -> MockSource {
+        MockSource(DepositLookup::Found(OnchainDeposit {
+            token: TOKEN.to_string(),
+            amount,
+            target_chain_id: 8453,
+            recipient,
+            zcash_address: vec![2u8; 32],
+            block_number: 100,
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_matching_deposit_verifies() {
+        let result = verify_deposit(&found(1_000, vec![1; 32]), &chain(), &deposit(1_000, vec![1; 32]))
+            .await
+            .unwrap();
+        assert_eq!(result, DepositVerification::Verified { block_number: 100 });
+    }
+
+    #[tokio::test]
+    async fn test_mismatching_deposit_is_invalid() {
+        // Relayer inflated the amount
+        let result = verify_deposit(&found(1_000, vec![1; 32]), &chain(), &deposit(5_000, vec![1; 32]))
+            .await
+            .unwrap();
+        assert!(matches!(result, DepositVerification::Invalid(reason) if reason.contains("amount")));
+
+        // Relayer redirected the recipient
+        let result = verify_deposit(&found(1_000, vec![1; 32]), &chain(), &deposit(1_000, vec![9; 32]))
+            .await
+            .unwrap();
+        assert!(matches!(result, DepositVerification::Invalid(reason) if reason.contains("recipient")));
+
+        // Deposit never happened
+        let result = verify_deposit(&MockSource(DepositLookup::NotFound), &chain(), &deposit(1_000, vec![1; 32]))
+            .await
+            .unwrap();
+        assert!(matches!(result, DepositVerification::Invalid(_)));
+
+        let unsupported = MockSource(DepositLookup::Unsupported);
+        let result = verify_deposit(&unsupported, &chain(), &deposit(1_000, vec![1; 32])).await.unwrap();
+        assert_eq!(result, DepositVerification::Unverifiable);
+    }
+
+    #[tokio::test]
+    async fn test_deposit_must_match_target_address_and_block() {
+        async fn check(deposit: Deposit) -> DepositVerification {
+            verify_deposit(&found(1_000, vec![1; 32]), &chain(), &deposit).await.unwrap()
+        }
+
+        // Redirected to another destination chain
+        let mut redirected = deposit(1_000, vec![1; 32]);
+        redirected.target_chain_id = 137;
+        assert!(matches!(check(redirected).await, DepositVerification::Invalid(reason) if reason.contains("target chain")));
+
+        // Routed after verification: the requested chain leads the preferences
+        let mut routed = deposit(1_000, vec![1; 32]);
+        routed.target_chain_id = 137;
+        routed.target_chain_preferences = vec![8453, 137];
+        assert_eq!(check(routed).await, DepositVerification::Verified { block_number: 100 });
+
+        // Note sent to someone else's Zcash address
+        let mut stolen = deposit(1_000, vec![1; 32]);
+        stolen.zcash_address = vec![9u8; 43];
+        assert!(matches!(check(stolen).await, DepositVerification::Invalid(reason) if reason.contains("Zcash address")));
+
+        // Reported in a block the transaction isn't in
+        let mut misdated = deposit(1_000, vec![1; 32]);
+        misdated.block_number = Some(90);
+        assert!(matches!(check(misdated).await, DepositVerification::Invalid(reason) if reason.contains("block")));
+    }
+
+    fn receipt(status: &str, address: &str, deposit_id: [u8; 32], amount: u64) -> Value {
+        let mut data = vec![0u8; 160];
+        data[24..32].copy_from_slice(&amount.to_be_bytes());
+        data[56..64].copy_from_slice(&8453u64.to_be_bytes());
+        data[64..96].copy_from_slice(&[1u8; 32]);
+        data[96..128].copy_from_slice(&[2u8; 32]);
+
+        json!({
+            "status": status,
+            "blockNumber": "0x64",
+            "logs": [{
+                "address": address,
+                "topics": [
+                    format!("0x{}", hex::encode(keccak256(TOKENS_LOCKED_SIGNATURE))),
+                    format!("0x{}", hex::encode(deposit_id)),
+                    format!("0x{:0>64}", "aa"),
+                    format!("0x{:0>64}", TOKEN.trim_start_matches("0x")),
+                ],
+                "data": format!("0x{}", hex::encode(data)),
+            }]
+        })
+    }
+
+    #[test]
+    fn test_deposit_from_receipt() {
+        let deposit_id = hex::encode([7u8; 32]);
+
+        let onchain = deposit_from_receipt(&receipt("0x1", &GATEWAY.to_lowercase(), [7; 32], 1_000), GATEWAY, &deposit_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(onchain.amount, 1_000);
+        assert_eq!(onchain.target_chain_id, 8453);
+        assert_eq!(onchain.recipient, vec![1u8; 32]);
+        assert_eq!(onchain.zcash_address, vec![2u8; 32]);
+        assert_eq!(onchain.token, TOKEN);
+        assert_eq!(onchain.block_number, 100);
+
+        // Unknown transaction
+        assert!(deposit_from_receipt(&Value::Null, GATEWAY, &deposit_id).unwrap().is_none());
+        // Reverted
+        assert!(deposit_from_receipt(&receipt("0x0", GATEWAY, [7; 32], 1_000), GATEWAY, &deposit_id).unwrap().is_none());
+        // Emitted by some other contract
+        let other = "0x0000000000000000000000000000000000000002";
+        assert!(deposit_from_receipt(&receipt("0x1", other, [7; 32], 1_000), GATEWAY, &deposit_id).unwrap().is_none());
+        // A different deposit in the same transaction
+        assert!(deposit_from_receipt(&receipt("0x1", GATEWAY, [8; 32], 1_000), GATEWAY, &deposit_id).unwrap().is_none());
+    }
+}
@@ -0,0 +1,914 @@
+// zcash-coordinator/src/e2e_tests.rs
+//! End-to-end harness: deposit -> note -> authorization -> execution
+//!
+//! Run with `cargo test --features e2e`. Everything runs in this process:
+//!
+//! - `MockZcashNode` answers the JSON-RPC calls `ZcashClient` makes. Every
+//!   `z_sendmany` succeeds at once and is recorded, so the real
+//!   `ShieldedPoolManager` mints notes without a zcashd.
+//! - `MockGateway` stands in for one chain's gateway contract. It keeps
+//!   deposits and payouts in memory and serves the EVM JSON-RPC the
+//!   coordinator reads (`eth_blockNumber` for finality,
+//!   `eth_getTransactionReceipt` for deposit verification). `execute`
+//!   checks the authorization recovers to the coordinator's signing key, as
+//!   the contracts do.
+//! - The coordinator is the real `RpcServer`, deposit writer and processing
+//!   loop over a temporary database, wired up the way `main` does it.
+//! - The relayer is the relayer crate's own `CoordinatorClient`, so every
+//!   message crosses the wire in the encoding the two sides negotiate.
+
+use anyhow::Result;
+use axum::{extract::State, routing::post, Json, Router};
+use ethers::types::{RecoveryMessage, Signature, H256};
+use ethers::utils::keccak256;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use zerobridge_relayer::coordinator_client::{CoordinatorClient, DepositNotification, DepositNotifyOutcome};
+use zerobridge_types::{withdrawal_authorization_hash, AuthorizationScheme, AuthorizedWithdrawal};
+
+use crate::config::{ChainConfig, ChainType, Config, DatabaseConfig};
+use crate::deposit_events::{DepositEvents, DepositStage};
+use crate::deposit_queue::DepositQueue;
+use crate::deposit_verifier::{EvmSourceClient, TOKENS_LOCKED_SIGNATURE};
+use crate::{
+    ChainHeadClient, Coordinator, Database, DbHealth, EvmBalanceClient, LiquidityManager,
+    CoordinatorSigner, RpcServer, ShieldedPoolManager, TokenRegistry, WorkerPool, ZcashClient,
+};
+
+/// Longest any stage of a flow may take; the processing loop ticks every second
+const STAGE_TIMEOUT: Duration = Duration::from_secs(30);
+
+const ETHEREUM: u64 = 1;
+const BASE: u64 = 8453;
+const ETHEREUM_GATEWAY: &str = "0x00000000000000000000000000000000000000e1";
+const BASE_GATEWAY: &str = "0x00000000000000000000000000000000000000e2";
+const ETHEREUM_USDC: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+const BASE_USDC: &str = "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913";
+const WITHDRAWAL_RECIPIENT: &str = "0x00000000000000000000000000000000000000bb";
+const ADMIN_TOKEN: &str = "e2e-admin-token";
+
+const TOKENS: &str = r#"
+[[tokens]]
+symbol = "USDC"
+name = "USD Coin"
+decimals = 6
+
+[[tokens.representations]]
+chain_id = 1
+chain_name = "Ethereum"
+address = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"
+
+[[tokens.representations]]
+chain_id = 8453
+chain_name = "Base"
+address = "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"
+"#;
+
+// ============ Mock Zcash Node ============
+
+/// zcashd stand-in: synced, and every shielded send succeeds
+#[derive(Clone, Default)]
+struct MockZcashNode {
+    /// `z_sendmany` params, in the order they arrived
+    sends: Arc<Mutex<Vec<Value>>>,
+    /// Answer `z_getoperationstatus` as a restarted zcashd does, knowing no
+    /// operations, so a sent note's txid never reaches the coordinator
+    forget_operations: Arc<AtomicBool>,
+    /// Keep every shielded send executing, so the coordinator that made it
+    /// is still waiting on its txid
+    stall_operations: Arc<AtomicBool>,
+    /// Leave every transaction in the mempool, with no confirmations
+    unconfirmed: Arc<AtomicBool>,
+}
+
+impl MockZcashNode {
+    fn sends(&self) -> usize {
+        self.sends.lock().unwrap().len()
+    }
+
+    fn handle(&self, method: &str, params: &Value) -> Result<Value, String> {
+        match method {
+            "getblockchaininfo" => Ok(json!({
+                "chain": "test",
+                "blocks": 100,
+                "bestblockhash": "00".repeat(32),
+                "verificationprogress": 1.0,
+                "chainwork": "00",
+            })),
+            "z_sendmany" => {
+                let mut sends = self.sends.lock().unwrap();
+                sends.push(params.clone());
+                Ok(json!(format!("opid-{}", sends.len())))
+            }
+            "z_getoperationstatus" if self.forget_operations.load(Ordering::SeqCst) => Ok(json!([])),
+            "z_getoperationstatus" if self.stall_operations.load(Ordering::SeqCst) => {
+                let opid = params[0][0].as_str().ok_or("missing operation id")?;
+                Ok(json!([{ "id": opid, "status": "executing" }]))
+            }
+            "z_getoperationstatus" => {
+                let opid = params[0][0].as_str().ok_or("missing operation id")?;
+                let txid = hex::encode(keccak256(opid));
+                Ok(json!([{ "id": opid, "status": "success", "result": { "txid": txid } }]))
+            }
+            "gettransaction" => Ok(json!({
+                "txid": params[0],
+                "confirmations": if self.unconfirmed.load(Ordering::SeqCst) { 0 } else { 100 },
+                "blockhash": "00".repeat(32),
+                "blockindex": 0,
+            })),
+            "getrawtransaction" => Ok(json!({ "txid": params[0] })),
+            _ => Err(format!("Method not found: {}", method)),
+        }
+    }
+}
+
+// ============ Mock Gateway ============
+
+/// One chain's gateway contract, in memory
+#[derive(Clone)]
+struct MockGateway {
+    chain_id: u64,
+    address: String,
+    state: Arc<Mutex<GatewayState>>,
+}
+
+#[derive(Default)]
+struct GatewayState {
+    head: u64,
+    /// `eth_getTransactionReceipt` results by transaction hash
+    receipts: HashMap<String, Value>,
+    /// Amounts paid out by withdrawals, by (token, recipient)
+    paid: HashMap<(String, String), u64>,
+    spent_nullifiers: HashSet<Vec<u8>>,
+}
+
+impl MockGateway {
+    fn new(chain_id: u64, address: &str) -> Self {
+        Self {
+            chain_id,
+            address: address.to_string(),
+            state: Arc::new(Mutex::new(GatewayState {
+                head: 1_000,
+                ..GatewayState::default()
+            })),
+        }
+    }
+
+    /// Lock `amount` of `token` for a note, emitting `TokensLocked` in a new
+    /// block, and bury that block under enough confirmations for any chain.
+    /// Returns what a relayer's listener reports for the event.
+    fn lock(
+        &self,
+        deposit_id: &str,
+        token: &str,
+        amount: u64,
+        recipient: [u8; 32],
+        zcash_address: &[u8],
+        target_chain_id: u64,
+    ) -> DepositNotification {
+        let mut state = self.state.lock().unwrap();
+        state.head += 1;
+        let block = state.head;
+        state.head += 1_000;
+
+        let tx_hash = format!("0x{}", hex::encode(keccak256(deposit_id)));
+        let word = |value: u64| format!("{:064x}", value);
+        // ABI words: amount, targetChainId, recipient, zcashAddress, timestamp.
+        // Orchard addresses don't fit a word; the gateway keeps the leading
+        // 32 bytes.
+        let data = format!(
+            "0x{}{}{}{}{}",
+            word(amount),
+            word(target_chain_id),
+            hex::encode(recipient),
+            hex::encode(&zcash_address[..32]),
+            word(1_700_000_000),
+        );
+        let topic = |address: &str| format!("0x{:0>64}", address.trim_start_matches("0x").to_lowercase());
+        state.receipts.insert(tx_hash.clone(), json!({
+            "transactionHash": tx_hash,
+            "blockNumber": format!("{:#x}", block),
+            "status": "0x1",
+            "logs": [{
+                "address": self.address,
+                "topics": [
+                    format!("0x{}", hex::encode(keccak256(TOKENS_LOCKED_SIGNATURE))),
+                    format!("0x{}", deposit_id),
+                    topic("0x00000000000000000000000000000000000000aa"),
+                    topic(token),
+                ],
+                "data": data,
+            }],
+        }));
+
+        DepositNotification {
+            deposit_id: deposit_id.to_string(),
+            source_chain_id: self.chain_id,
+            target_chain_id,
+            sender: "0x00000000000000000000000000000000000000aa".to_string(),
+            token: token.to_lowercase(),
+            amount,
+            recipient: recipient.to_vec(),
+            zcash_address: zcash_address.to_vec(),
+            timestamp: 1_700_000_000,
+            target_chain_preferences: Vec::new(),
+            block_number: Some(block),
+            tx_hash,
+        }
+    }
+
+    /// Pay out an authorized withdrawal, refusing anything the coordinator
+    /// didn't sign exactly, past its deadline, or whose nullifier was
+    /// already used
+    fn execute(&self, withdrawal: &AuthorizedWithdrawal) -> Result<()> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs();
+        if now > withdrawal.deadline {
+            anyhow::bail!("Authorization expired");
+        }
+
+        let message = withdrawal_authorization_hash(
+            AuthorizationScheme::Evm { chain_id: self.chain_id },
+            &withdrawal.withdrawal_id,
+            &withdrawal.recipient,
+            &withdrawal.token,
+            withdrawal.amount,
+            &withdrawal.nullifier,
+            withdrawal.deadline,
+        )?;
+        let signer = Signature::try_from(withdrawal.authorization_signature.as_slice())
+            .and_then(|signature| signature.recover(RecoveryMessage::Hash(H256::from(message))))
+            .map(|address| format!("{:?}", address));
+        if signer.ok() != Some(CoordinatorSigner::test().address()) {
+            anyhow::bail!("Invalid coordinator authorization");
+        }
+
+        let mut state = self.state.lock().unwrap();
+        if !state.spent_nullifiers.insert(withdrawal.nullifier.clone()) {
+            anyhow::bail!("Nullifier already used");
+        }
+        *state
+            .paid
+            .entry((withdrawal.token.to_lowercase(), withdrawal.recipient.to_lowercase()))
+            .or_default() += withdrawal.amount;
+        Ok(())
+    }
+
+    fn paid(&self, token: &str, recipient: &str) -> u64 {
+        let state = self.state.lock().unwrap();
+        state
+            .paid
+            .get(&(token.to_lowercase(), recipient.to_lowercase()))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn handle(&self, method: &str, params: &Value) -> Result<Value, String> {
+        let state = self.state.lock().unwrap();
+        match method {
+            "eth_blockNumber" => Ok(json!(format!("{:#x}", state.head))),
+            "eth_getTransactionReceipt" => {
+                let tx_hash = params[0].as_str().ok_or("missing transaction hash")?;
+                Ok(state.receipts.get(tx_hash).cloned().unwrap_or(Value::Null))
+            }
+            _ => Err(format!("Method not found: {}", method)),
+        }
+    }
+}
+
+// ============ JSON-RPC Plumbing ============
+
+type RpcHandler = Arc<dyn Fn(&str, &Value) -> Result<Value, String> + Send + Sync>;
+
+/// Serve `handler` as a JSON-RPC endpoint on a free local port
+async fn serve_json_rpc(handler: RpcHandler) -> String {
+    let app = Router::new()
+        .route("/", post(json_rpc))
+        .with_state(handler);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    url
+}
+
+async fn json_rpc(State(handler): State<RpcHandler>, Json(request): Json<Value>) -> Json<Value> {
+    let method = request["method"].as_str().unwrap_or_default();
+    Json(match handler(method, &request["params"]) {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": request["id"], "result": result, "error": null }),
+        Err(message) => json!({
+            "jsonrpc": "2.0",
+            "id": request["id"],
+            "error": { "code": -32601, "message": message },
+        }),
+    })
+}
+
+/// Poll `check` until it yields a value
+async fn eventually<T, F, Fut>(what: &str, mut check: F) -> T
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Option<T>>>,
+{
+    let deadline = Instant::now() + STAGE_TIMEOUT;
+    loop {
+        match check().await {
+            Ok(Some(value)) => return value,
+            Ok(None) => {}
+            // The coordinator may still be starting up
+            Err(e) => tracing::debug!("Waiting for {}: {}", what, e),
+        }
+        assert!(Instant::now() < deadline, "Timed out waiting for {}", what);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+/// A valid Orchard address for deposits to mint notes to
+fn orchard_address() -> Vec<u8> {
+    use orchard::keys::{FullViewingKey, Scope, SpendingKey};
+
+    let spending_key: SpendingKey = Option::from(SpendingKey::from_bytes([7u8; 32])).unwrap();
+    FullViewingKey::from(&spending_key)
+        .address_at(0u32, Scope::External)
+        .to_raw_address_bytes()
+        .to_vec()
+}
+
+// ============ Harness ============
+
+struct Harness {
+    url: String,
+    coordinator: Arc<Coordinator>,
+    relayer: CoordinatorClient,
+    zcashd: MockZcashNode,
+    ethereum: MockGateway,
+    base: MockGateway,
+    tasks: Vec<JoinHandle<()>>,
+    /// Holds the database; shared with the coordinator after a restart
+    dir: Arc<tempfile::TempDir>,
+}
+
+impl Drop for Harness {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+impl Harness {
+    /// Start a coordinator over Ethereum and Base with `base_liquidity` USDC
+    /// available on Base, and connect a relayer to it
+    async fn start(base_liquidity: u64) -> Self {
+        Self::start_with(base_liquidity, |_| {}).await
+    }
+
+    /// `start`, with `configure` adjusting the coordinator's config first
+    async fn start_with(base_liquidity: u64, configure: impl FnOnce(&mut Config)) -> Self {
+        let dir = Arc::new(tempfile::tempdir().unwrap());
+
+        let zcashd = MockZcashNode::default();
+        let ethereum = MockGateway::new(ETHEREUM, ETHEREUM_GATEWAY);
+        let base = MockGateway::new(BASE, BASE_GATEWAY);
+        let zcash_url = serve_json_rpc(Arc::new({
+            let zcashd = zcashd.clone();
+            move |method: &str, params: &Value| zcashd.handle(method, params)
+        }))
+        .await;
+        let ethereum_url = serve_json_rpc(Arc::new({
+            let ethereum = ethereum.clone();
+            move |method: &str, params: &Value| ethereum.handle(method, params)
+        }))
+        .await;
+        let base_url = serve_json_rpc(Arc::new({
+            let base = base.clone();
+            move |method: &str, params: &Value| base.handle(method, params)
+        }))
+        .await;
+
+        let chain = |chain_id, name: &str, chain_type: ChainType, rpc_url: String, gateway: &str| ChainConfig {
+            chain_id,
+            name: name.to_string(),
+            chain_type,
+            rpc_url,
+            ws_url: None,
+            gateway_address: gateway.to_string(),
+            start_block: 0,
+            enabled: true,
+            confirmations: chain_type.confirmation_bounds().0,
+            accept_unverified_deposits: false,
+        };
+        let mut config = Config::template();
+        config.zcash.rpc_url = zcash_url;
+        config.chains = vec![
+            chain(ETHEREUM, "Ethereum", ChainType::Ethereum, ethereum_url, ETHEREUM_GATEWAY),
+            chain(BASE, "Base", ChainType::Base, base_url, BASE_GATEWAY),
+        ];
+        config.poll_interval = 1;
+        config.deposit_retry.base_backoff_secs = 1;
+        config.admin_token = Some(ADMIN_TOKEN.to_string());
+        config.signing_key = crate::signing::TEST_SIGNING_KEY.to_string();
+        configure(&mut config);
+
+        Self::launch(dir, config, zcashd, ethereum, base, Some(base_liquidity)).await
+    }
+
+    /// Crash the coordinator and start a new one over the same database.
+    /// Work the old one had in flight can't write anything more, and locks it
+    /// held stay taken until they expire, as after a real crash.
+    async fn restart(&mut self) {
+        for task in self.tasks.drain(..) {
+            task.abort();
+        }
+        self.coordinator.db.pool().close().await;
+
+        *self = Self::launch(
+            self.dir.clone(),
+            self.coordinator.config.clone(),
+            self.zcashd.clone(),
+            self.ethereum.clone(),
+            self.base.clone(),
+            None,
+        )
+        .await;
+    }
+
+    /// Wire up a coordinator and relayer as `main` does, funding Base with
+    /// `base_liquidity` USDC unless the database already has its pools
+    async fn launch(
+        dir: Arc<tempfile::TempDir>,
+        config: Config,
+        zcashd: MockZcashNode,
+        ethereum: MockGateway,
+        base: MockGateway,
+        base_liquidity: Option<u64>,
+    ) -> Self {
+        let db = Database::new(&dir.path().join("coordinator.db"), &DatabaseConfig::default())
+            .await
+            .unwrap();
+        let signer = Arc::new(CoordinatorSigner::from_hex(&config.signing_key).unwrap());
+        db.record_signing_key(&signer.public_key(), &signer.address(), config.key_rotation_overlap)
+            .await
+            .unwrap();
+        let zcash_client = ZcashClient::new(config.zcash.clone()).await.unwrap();
+        let token_registry = Arc::new(RwLock::new(TokenRegistry::from_toml(TOKENS).unwrap()));
+        let liquidity_manager = Arc::new(
+            LiquidityManager::new(db.clone(), config.liquidity.clone()).await.unwrap()
+        );
+        if let Some(base_liquidity) = base_liquidity {
+            liquidity_manager.add_liquidity(BASE, BASE_USDC, base_liquidity).await.unwrap();
+        }
+        let shielded_pool = Arc::new(RwLock::new(
+            ShieldedPoolManager::new(
+                zcash_client.clone(),
+                db.clone(),
+                token_registry.clone(),
+                liquidity_manager.clone(),
+            )
+            .await
+            .unwrap(),
+        ));
+
+        let db_health = DbHealth::new();
+        let deposit_events = DepositEvents::new();
+        let (deposit_queue, deposit_writer) = DepositQueue::new(config.deposit_queue_capacity);
+        let mut tasks = vec![tokio::spawn(deposit_writer.run(
+            db.clone(),
+            deposit_events.clone(),
+            db_health.clone(),
+        ))];
+
+        let port = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().port()
+        };
+        let rpc_server = RpcServer::new(
+            port,
+            config.authorization_ttl,
+            Duration::from_secs(config.rpc_request_timeout),
+            config.admin_token.clone(),
+            None,
+            signer.clone(),
+            config.key_rotation_overlap,
+            db.clone(),
+            shielded_pool.clone(),
+            token_registry.clone(),
+            liquidity_manager.clone(),
+            config.chains.clone(),
+            deposit_events.clone(),
+            db_health.clone(),
+            deposit_queue,
+        );
+        tasks.push(tokio::spawn(async move {
+            rpc_server.start(std::future::pending()).await.unwrap();
+        }));
+
+        let workers = WorkerPool::new(config.worker_pool_size);
+        let coordinator = Arc::new(Coordinator {
+            config,
+            signer,
+            db,
+            zcash_client,
+            shielded_pool,
+            token_registry,
+            liquidity_manager,
+            workers,
+            chain_heads: ChainHeadClient::new(),
+            source_chains: EvmSourceClient::new(),
+            balances: EvmBalanceClient::new(),
+            deposit_events,
+            db_health,
+        });
+        tasks.push(tokio::spawn({
+            let coordinator = coordinator.clone();
+            async move {
+                coordinator.run().await.unwrap();
+            }
+        }));
+
+        let url = format!("http://127.0.0.1:{}", port);
+        let relayer = eventually("coordinator RPC", || {
+            let url = url.clone();
+            async move { CoordinatorClient::new(&url).await.map(Some) }
+        })
+        .await;
+
+        Self {
+            url,
+            coordinator,
+            relayer,
+            zcashd,
+            ethereum,
+            base,
+            tasks,
+            dir,
+        }
+    }
+
+    async fn locked_on_base(&self) -> u64 {
+        self.coordinator
+            .liquidity_manager
+            .get_pool(BASE, BASE_USDC)
+            .await
+            .map(|pool| pool.locked)
+            .unwrap_or(0)
+    }
+}
+
+// ============ Flows ============
+
+#[tokio::test]
+async fn test_deposit_to_executed_withdrawal() {
+    let harness = Harness::start(1_000_000_000).await;
+    let relayer = &harness.relayer;
+    let deposit_id = hex::encode([0xd1; 32]);
+
+    // 1. USDC is locked on Ethereum for Base; two relayers report it
+    let notification = harness.ethereum.lock(
+        &deposit_id,
+        ETHEREUM_USDC,
+        5_000_000,
+        [0x11; 32],
+        &orchard_address(),
+        BASE,
+    );
+    let second_report: DepositNotification =
+        serde_json::from_value(serde_json::to_value(&notification).unwrap()).unwrap();
+    assert_eq!(relayer.notify_deposit(notification).await.unwrap(), DepositNotifyOutcome::Queued);
+    let outcome = relayer.notify_deposit(second_report).await.unwrap();
+    assert_eq!(outcome, DepositNotifyOutcome::Duplicate);
+
+    // 2. The coordinator checks it against Ethereum, mints one note and
+    // locks Base liquidity for it
+    eventually("deposit processed", || {
+        let deposit_id = &deposit_id;
+        async move { Ok(relayer.check_deposit_status(deposit_id).await?.then_some(())) }
+    })
+    .await;
+    assert_eq!(harness.zcashd.sends(), 1);
+
+    let deposit = harness.coordinator.db.get_deposit(&deposit_id).await.unwrap().unwrap();
+    let note_amount = deposit.amount - deposit.zcash_fee.unwrap_or(0);
+    assert_eq!(harness.locked_on_base().await, note_amount);
+
+    // 3. The note is spent to Base; the relayer forwards the withdrawal
+    let withdrawal_id = hex::encode([0xa1; 32]);
+    let merkle_root = harness.coordinator.shielded_pool.read().await.get_current_merkle_root();
+    let canonical_id = harness
+        .coordinator
+        .token_registry
+        .read()
+        .await
+        .canonical_id_for_symbol("USDC")
+        .unwrap()
+        .0
+        .clone();
+    relayer
+        .notify_withdrawal(
+            &withdrawal_id,
+            BASE,
+            WITHDRAWAL_RECIPIENT,
+            &canonical_id,
+            note_amount,
+            vec![0x22; 32],
+            vec![0x33; 192],
+            merkle_root,
+        )
+        .await
+        .unwrap();
+
+    // 4. The coordinator verifies and signs it, and the relayer executes
+    // exactly what it was handed
+    let authorized = eventually("withdrawal authorized", || {
+        let withdrawal_id = &withdrawal_id;
+        async move {
+            Ok(relayer
+                .query_authorized_withdrawals()
+                .await?
+                .into_iter()
+                .find(|w| &w.withdrawal_id == withdrawal_id))
+        }
+    })
+    .await;
+    assert_eq!(authorized.target_chain_id, BASE);
+    assert_eq!(authorized.token, BASE_USDC);
+    assert_eq!(authorized.amount, note_amount);
+    assert_eq!(harness.locked_on_base().await, 0);
+
+    // A relayer can't inflate what it was authorized for
+    let tampered = AuthorizedWithdrawal {
+        amount: authorized.amount + 1,
+        authorization_signature: authorized.authorization_signature.clone(),
+        nullifier: authorized.nullifier.clone(),
+        withdrawal_id: authorized.withdrawal_id.clone(),
+        recipient: authorized.recipient.clone(),
+        token: authorized.token.clone(),
+        ..authorized
+    };
+    assert!(harness.base.execute(&tampered).is_err());
+    // ...or how long it stays valid
+    let extended = AuthorizedWithdrawal {
+        deadline: authorized.deadline + 1,
+        authorization_signature: authorized.authorization_signature.clone(),
+        nullifier: authorized.nullifier.clone(),
+        withdrawal_id: authorized.withdrawal_id.clone(),
+        recipient: authorized.recipient.clone(),
+        token: authorized.token.clone(),
+        ..authorized
+    };
+    assert!(harness.base.execute(&extended).is_err());
+
+    harness.base.execute(&authorized).unwrap();
+    assert_eq!(harness.base.paid(BASE_USDC, WITHDRAWAL_RECIPIENT), note_amount);
+    // Replays are refused on chain
+    assert!(harness.base.execute(&authorized).is_err());
+}
+
+#[tokio::test]
+async fn test_misreported_deposit_mints_nothing() {
+    let harness = Harness::start(1_000_000_000).await;
+    let deposit_id = hex::encode([0xd2; 32]);
+
+    let mut notification = harness.ethereum.lock(
+        &deposit_id,
+        ETHEREUM_USDC,
+        5_000_000,
+        [0x11; 32],
+        &orchard_address(),
+        BASE,
+    );
+    // A relayer claiming more than was locked
+    notification.amount *= 10;
+
+    let mut updates = harness.coordinator.deposit_events.subscribe(&deposit_id);
+    harness.relayer.notify_deposit(notification).await.unwrap();
+
+    let rejected = tokio::time::timeout(STAGE_TIMEOUT, async {
+        loop {
+            let update = updates.recv().await.unwrap();
+            if update.stage.is_final() {
+                return update;
+            }
+        }
+    })
+    .await
+    .expect("deposit reaches a final stage");
+    assert_eq!(rejected.stage, DepositStage::Rejected);
+    assert!(rejected.detail.unwrap().contains("amount"));
+
+    assert_eq!(harness.zcashd.sends(), 0);
+    assert_eq!(harness.locked_on_base().await, 0);
+    assert!(!harness.relayer.check_deposit_status(&deposit_id).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_interrupted_note_send_is_not_reminted() {
+    let harness = Harness::start(1_000_000_000).await;
+    let deposit_id = hex::encode([0xd3; 32]);
+
+    // The note goes out, but its txid is lost before it can be recorded
+    harness.zcashd.forget_operations.store(true, Ordering::SeqCst);
+    let notification = harness.ethereum.lock(
+        &deposit_id,
+        ETHEREUM_USDC,
+        5_000_000,
+        [0x11; 32],
+        &orchard_address(),
+        BASE,
+    );
+    let mut updates = harness.coordinator.deposit_events.subscribe(&deposit_id);
+    harness.relayer.notify_deposit(notification).await.unwrap();
+
+    // The retry finds the earlier attempt and stops rather than minting again
+    let rejected = tokio::time::timeout(STAGE_TIMEOUT, async {
+        loop {
+            let update = updates.recv().await.unwrap();
+            if update.stage.is_final() {
+                return update;
+            }
+        }
+    })
+    .await
+    .expect("deposit reaches a final stage");
+    assert_eq!(rejected.stage, DepositStage::Rejected);
+    assert!(rejected.detail.unwrap().contains("interrupted"));
+
+    assert_eq!(harness.zcashd.sends(), 1);
+    assert!(!harness.relayer.check_deposit_status(&deposit_id).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_crash_between_note_send_and_txid_save() {
+    let harness = Harness::start(1_000_000_000).await;
+    let deposit_id = hex::encode([0xd4; 32]);
+
+    // The node accepts the send, but the coordinator dies before the txid
+    // reaches the database
+    sqlx::query(
+        "CREATE TRIGGER crash_on_txid BEFORE UPDATE OF zcash_txid ON deposits
+         BEGIN SELECT RAISE(ABORT, 'crashed'); END",
+    )
+    .execute(harness.coordinator.db.pool())
+    .await
+    .unwrap();
+    let notification = harness.ethereum.lock(
+        &deposit_id,
+        ETHEREUM_USDC,
+        5_000_000,
+        [0x11; 32],
+        &orchard_address(),
+        BASE,
+    );
+    let mut updates = harness.coordinator.deposit_events.subscribe(&deposit_id);
+    harness.relayer.notify_deposit(notification).await.unwrap();
+
+    let rejected = tokio::time::timeout(STAGE_TIMEOUT, async {
+        loop {
+            let update = updates.recv().await.unwrap();
+            if update.stage.is_final() {
+                return update;
+            }
+        }
+    })
+    .await
+    .expect("deposit reaches a final stage");
+    assert_eq!(rejected.stage, DepositStage::Rejected);
+    assert!(rejected.detail.unwrap().contains("interrupted"));
+
+    // One note, and the liquidity backing it was reserved before the send
+    // and stays locked rather than being locked a second time
+    assert_eq!(harness.zcashd.sends(), 1);
+    assert!(harness.locked_on_base().await > 0);
+    assert!(!harness.relayer.check_deposit_status(&deposit_id).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_restart_after_note_claim_does_not_resend() {
+    let mut harness = Harness::start(1_000_000_000).await;
+    let deposit_id = hex::encode([0xd7; 32]);
+
+    // The note send is claimed and reaches the node, then the coordinator
+    // dies before it learns the txid
+    harness.zcashd.stall_operations.store(true, Ordering::SeqCst);
+    let notification = harness.ethereum.lock(
+        &deposit_id,
+        ETHEREUM_USDC,
+        5_000_000,
+        [0x11; 32],
+        &orchard_address(),
+        BASE,
+    );
+    harness.relayer.notify_deposit(notification).await.unwrap();
+    eventually("note send", || async { Ok((harness.zcashd.sends() == 1).then_some(())) }).await;
+    assert!(harness.coordinator.db.deposit_note_started(&deposit_id).await.unwrap());
+
+    harness.restart().await;
+    let mut updates = harness.coordinator.deposit_events.subscribe(&deposit_id);
+    // The dead worker's lock outlives it; let it expire
+    sqlx::query("UPDATE deposits SET processing_since = processing_since - 3600 WHERE deposit_id = ?")
+        .bind(&deposit_id)
+        .execute(harness.coordinator.db.pool())
+        .await
+        .unwrap();
+
+    // The restarted coordinator finds the claim and stops rather than
+    // minting again
+    let rejected = tokio::time::timeout(STAGE_TIMEOUT, async {
+        loop {
+            let update = updates.recv().await.unwrap();
+            if update.stage.is_final() {
+                return update;
+            }
+        }
+    })
+    .await
+    .expect("deposit reaches a final stage");
+    assert_eq!(rejected.stage, DepositStage::Rejected);
+    assert!(rejected.detail.unwrap().contains("interrupted"));
+
+    assert_eq!(harness.zcashd.sends(), 1);
+    assert!(!harness.relayer.check_deposit_status(&deposit_id).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_reauthorize_requires_admin_token() {
+    let harness = Harness::start(1_000_000_000).await;
+    let http = reqwest::Client::new();
+    let url = format!("{}/withdrawals/{}/reauthorize", harness.url, "unknown");
+
+    let anonymous = http.post(&url).send().await.unwrap();
+    assert_eq!(anonymous.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let wrong_token = http.post(&url).bearer_auth("not-the-token").send().await.unwrap();
+    assert_eq!(wrong_token.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    // With the token the request gets as far as looking up the withdrawal
+    let admin = http.post(&url).bearer_auth(ADMIN_TOKEN).send().await.unwrap();
+    assert_eq!(admin.status(), reqwest::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_unconfirmed_note_does_not_hold_a_worker() {
+    // One worker: a deposit waiting on its note would stall everything else
+    let harness = Harness::start_with(1_000_000_000, |config| config.worker_pool_size = 1).await;
+    let relayer = &harness.relayer;
+    harness.zcashd.unconfirmed.store(true, Ordering::SeqCst);
+
+    let note_created = |deposit_id: &str| {
+        let mut updates = harness.coordinator.deposit_events.subscribe(deposit_id);
+        async move {
+            tokio::time::timeout(STAGE_TIMEOUT, async {
+                while updates.recv().await.unwrap().stage != DepositStage::NoteCreated {}
+            })
+            .await
+            .expect("note is sent");
+        }
+    };
+
+    let first = hex::encode([0xd5; 32]);
+    let sent = note_created(&first);
+    let notification = harness.ethereum.lock(
+        &first,
+        ETHEREUM_USDC,
+        5_000_000,
+        [0x11; 32],
+        &orchard_address(),
+        BASE,
+    );
+    relayer.notify_deposit(notification).await.unwrap();
+    sent.await;
+
+    // The first note never confirms, yet the next deposit still gets its own
+    let second = hex::encode([0xd6; 32]);
+    let sent = note_created(&second);
+    let notification = harness.ethereum.lock(
+        &second,
+        ETHEREUM_USDC,
+        5_000_000,
+        [0x12; 32],
+        &orchard_address(),
+        BASE,
+    );
+    relayer.notify_deposit(notification).await.unwrap();
+    sent.await;
+
+    assert_eq!(harness.zcashd.sends(), 2);
+    assert!(!relayer.check_deposit_status(&first).await.unwrap());
+    assert!(!relayer.check_deposit_status(&second).await.unwrap());
+
+    // Once the notes confirm, a later pass completes both without resending
+    harness.zcashd.unconfirmed.store(false, Ordering::SeqCst);
+    for deposit_id in [&first, &second] {
+        eventually("deposit processed", || async move {
+            Ok(relayer.check_deposit_status(deposit_id).await?.then_some(()))
+        })
+        .await;
+    }
+    assert_eq!(harness.zcashd.sends(), 2);
+}
@@ -5,15 +5,28 @@
 //! which orchestrates privacy-preserving cross-chain transfers using Zcash's
 //! shielded transaction technology.
 
+pub mod amount;
 pub mod config;
 pub mod shielded_pool;
 pub mod token_registry;
 pub mod liquidity_manager;
+pub mod liquidity_sync;
+pub mod deposit_verifier;
+pub mod deposit_events;
+pub mod deposit_queue;
 pub mod database;
+pub mod db_health;
+pub mod metrics;
 pub mod rpc_server;
 pub mod zcash_client;
+pub mod signing;
+pub mod telemetry;
+pub mod cli;
+pub mod config_check;
+pub mod wire;
 
 // Re-export commonly used types
+pub use amount::Amount;
 pub use config::{Config, ZcashConfig, ChainConfig};
 pub use shielded_pool::ShieldedPoolManager;
 pub use token_registry::TokenRegistry;
@@ -3,21 +3,46 @@
 
 use anyhow::{Context, Result};
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use tokio::sync::OwnedMutexGuard;
 use tracing::{debug, info, warn};
 
+use crate::amount::Amount;
 use crate::config::LiquidityConfig;
-use crate::database::Database;
+#[cfg(test)]
+use crate::config::DatabaseConfig;
+use crate::database::{Database, LiquidityHold};
 
 /// Manages liquidity pools across all gateway chains
+///
+/// Each pool has its own lock, held across that pool's database write, so
+/// operations on different pools never wait for each other. The pool map and
+/// holds sit behind plain locks that are never held across an `await`.
 pub struct LiquidityManager {
     db: Database,
     config: LiquidityConfig,
-    pools: HashMap<PoolKey, LiquidityPool>,
+    pools: RwLock<HashMap<PoolKey, SharedPool>>,
+    /// Soft holds by deposit ID; a hold only changes under its pool's lock
+    holds: Mutex<HashMap<String, LiquidityHold>>,
 }
 
 /// Pool identifier (chain_id, token_address)
 type PoolKey = (u64, String);
 
+type SharedPool = Arc<tokio::sync::Mutex<LiquidityPool>>;
+
+/// Exclusive access to one pool, from `LiquidityManager::lock_pool`
+pub type PoolGuard = OwnedMutexGuard<LiquidityPool>;
+
+/// A candidate destination for liquidity-aware routing, with the amount
+/// already expressed in that chain's token precision
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteCandidate {
+    pub chain_id: u64,
+    pub token: String,
+    pub amount: Amount,
+}
+
 /// Liquidity pool state
 #[derive(Debug, Clone)]
 pub struct LiquidityPool {
@@ -30,6 +55,17 @@ pub struct LiquidityPool {
 }
 
 impl LiquidityPool {
+    fn empty(chain_id: u64, token: &str) -> Self {
+        Self {
+            chain_id,
+            token: token.to_string(),
+            available: 0,
+            locked: 0,
+            target: 0,
+            last_rebalance: 0,
+        }
+    }
+    
     /// Calculate utilization ratio
     pub fn utilization(&self) -> f64 {
         let total = self.available + self.locked;
@@ -53,6 +89,18 @@ impl LiquidityPool {
         
         (target_locked - current_locked) as i64
     }
+    
+    /// `(available, locked)` after releasing `amount`, without applying it,
+    /// so the write can join a caller's transaction. `None` if less than
+    /// `amount` is locked (nothing to release).
+    pub fn released_state(&self, amount: u64) -> Option<(u64, u64)> {
+        if self.locked < amount {
+            warn!("Attempting to release more than locked: {}", amount);
+            return None;
+        }
+        
+        Some((self.available, self.locked - amount))
+    }
 }
 
 impl LiquidityManager {
@@ -64,7 +112,8 @@ impl LiquidityManager {
         let mut manager = Self {
             db,
             config,
-            pools: HashMap::new(),
+            pools: RwLock::new(HashMap::new()),
+            holds: Mutex::new(HashMap::new()),
         };
         
         // Load existing pool states from database
@@ -85,8 +134,7 @@ impl LiquidityManager {
             chain_id, token, amount
         );
         
-        let key = (chain_id, token.to_string());
-        let pool = self.pools.get(&key)
+        let pool = self.get_pool(chain_id, token).await
             .context("Pool not found")?;
         
         if pool.available < amount {
@@ -100,28 +148,46 @@ impl LiquidityManager {
         Ok(())
     }
     
+    /// Pick the first candidate (in preference order) whose pool can cover it
+    pub async fn select_route<'a>(&self, candidates: &'a [RouteCandidate]) -> Option<&'a RouteCandidate> {
+        for candidate in candidates {
+            if let Some(pool) = self.get_pool(candidate.chain_id, &candidate.token).await {
+                if u128::from(pool.available) >= candidate.amount.raw() {
+                    return Some(candidate);
+                }
+            }
+        }
+        None
+    }
+    
+    /// Lock one pool until the guard is dropped, for callers that persist
+    /// its new state themselves (e.g. inside a wider transaction) and then
+    /// write it back through the guard
+    pub async fn lock_pool(&self, chain_id: u64, token: &str) -> Result<PoolGuard> {
+        let pool = self.shared_pool(chain_id, token).context("Pool not found")?;
+        Ok(pool.lock_owned().await)
+    }
+    
     /// Lock liquidity for a pending withdrawal
     pub async fn lock_liquidity(
-        &mut self,
+        &self,
         chain_id: u64,
         token: &str,
         amount: u64,
     ) -> Result<()> {
-        let key = (chain_id, token.to_string());
-        let pool = self.pools.get_mut(&key)
-            .context("Pool not found")?;
+        let mut pool = self.lock_pool(chain_id, token).await?;
         
         if pool.available < amount {
             anyhow::bail!("Insufficient available liquidity");
         }
-        
-        pool.available -= amount;
-        pool.locked += amount;
+        let (available, locked) = (pool.available - amount, pool.locked + amount);
         
         // Update database
         self.db
-            .update_liquidity_pool(chain_id, token, pool.available, pool.locked)
+            .update_liquidity_pool(chain_id, token, available, locked)
             .await?;
+        pool.available = available;
+        pool.locked = locked;
         
         debug!("Locked {} liquidity on chain {}", amount, chain_id);
         Ok(())
@@ -129,55 +195,195 @@ impl LiquidityManager {
     
     /// Release locked liquidity after withdrawal completion
     pub async fn release_liquidity(
-        &mut self,
+        &self,
         chain_id: u64,
         token: &str,
         amount: u64,
     ) -> Result<()> {
-        let key = (chain_id, token.to_string());
-        let pool = self.pools.get_mut(&key)
-            .context("Pool not found")?;
-        
-        if pool.locked < amount {
-            warn!("Attempting to release more than locked: {}", amount);
+        let mut pool = self.lock_pool(chain_id, token).await?;
+        let Some((available, locked)) = pool.released_state(amount) else {
             return Ok(());
-        }
-        
-        pool.locked -= amount;
+        };
         
         // Update database
         self.db
-            .update_liquidity_pool(chain_id, token, pool.available, pool.locked)
+            .update_liquidity_pool(chain_id, token, available, locked)
             .await?;
+        pool.available = available;
+        pool.locked = locked;
         
         debug!("Released {} liquidity on chain {}", amount, chain_id);
         Ok(())
     }
     
-    /// Add liquidity to a pool
-    pub async fn add_liquidity(
-        &mut self,
+    /// Return liquidity consumed by a withdrawal authorization that expired
+    /// without being executed, making it available to other transfers again
+    pub async fn restore_liquidity(
+        &self,
         chain_id: u64,
         token: &str,
         amount: u64,
     ) -> Result<()> {
-        let key = (chain_id, token.to_string());
+        let mut pool = self.lock_pool(chain_id, token).await?;
+        let available = pool.available + amount;
         
-        let pool = self.pools.entry(key.clone()).or_insert(LiquidityPool {
+        // Update database
+        self.db
+            .update_liquidity_pool(chain_id, token, available, pool.locked)
+            .await?;
+        pool.available = available;
+        
+        debug!("Restored {} liquidity on chain {}", amount, chain_id);
+        Ok(())
+    }
+    
+    /// Reserve liquidity again for a re-authorized withdrawal
+    pub async fn reserve_liquidity(
+        &self,
+        chain_id: u64,
+        token: &str,
+        amount: u64,
+    ) -> Result<()> {
+        let mut pool = self.lock_pool(chain_id, token).await?;
+        
+        if pool.available < amount {
+            anyhow::bail!("Insufficient available liquidity");
+        }
+        let available = pool.available - amount;
+        
+        // Update database
+        self.db
+            .update_liquidity_pool(chain_id, token, available, pool.locked)
+            .await?;
+        pool.available = available;
+        
+        debug!("Reserved {} liquidity on chain {}", amount, chain_id);
+        Ok(())
+    }
+    
+    /// Set aside liquidity for a deposit still awaiting source confirmations,
+    /// so it can't be promised to another transfer in the meantime. No-op if
+    /// the deposit already holds liquidity.
+    pub async fn hold_liquidity(
+        &self,
+        deposit_id: &str,
+        chain_id: u64,
+        token: &str,
+        amount: u64,
+    ) -> Result<()> {
+        if self.get_hold(deposit_id).is_some() {
+            return Ok(());
+        }
+        
+        let mut pool = self.lock_pool(chain_id, token).await?;
+        // Held by a concurrent pass while we waited for the pool
+        if self.get_hold(deposit_id).is_some() {
+            return Ok(());
+        }
+        
+        if pool.available < amount {
+            anyhow::bail!("Insufficient available liquidity");
+        }
+        let (available, locked) = (pool.available - amount, pool.locked);
+        
+        let hold = LiquidityHold {
+            deposit_id: deposit_id.to_string(),
             chain_id,
             token: token.to_string(),
-            available: 0,
-            locked: 0,
-            target: 0,
-            last_rebalance: 0,
-        });
+            amount,
+            created_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs() as i64,
+        };
         
-        pool.available += amount;
+        self.db.create_liquidity_hold(&hold, available, locked).await?;
+        pool.available = available;
+        self.holds.lock().unwrap().insert(deposit_id.to_string(), hold);
+        
+        debug!("Held {} liquidity on chain {} for deposit {}", amount, chain_id, deposit_id);
+        Ok(())
+    }
+    
+    /// Turn a deposit's hold into locked liquidity once the deposit is
+    /// final. Returns the hold, or `None` if the deposit had none.
+    pub async fn confirm_hold(&self, deposit_id: &str) -> Result<Option<LiquidityHold>> {
+        self.settle_hold(deposit_id, true).await
+    }
+    
+    /// Return a deposit's held liquidity to the pool. Returns the hold, or
+    /// `None` if the deposit had none.
+    pub async fn release_hold(&self, deposit_id: &str) -> Result<Option<LiquidityHold>> {
+        self.settle_hold(deposit_id, false).await
+    }
+    
+    async fn settle_hold(&self, deposit_id: &str, lock: bool) -> Result<Option<LiquidityHold>> {
+        let Some(hold) = self.get_hold(deposit_id) else {
+            return Ok(None);
+        };
+        
+        let mut pool = self.lock_pool(hold.chain_id, &hold.token).await?;
+        // Settled by a concurrent pass while we waited for the pool
+        if self.get_hold(deposit_id).is_none() {
+            return Ok(None);
+        }
+        
+        let (available, locked) = if lock {
+            (pool.available, pool.locked + hold.amount)
+        } else {
+            (pool.available + hold.amount, pool.locked)
+        };
+        
+        self.db.delete_liquidity_hold(&hold, available, locked).await?;
+        pool.available = available;
+        pool.locked = locked;
+        self.holds.lock().unwrap().remove(deposit_id);
+        
+        debug!(
+            "{} {} held liquidity on chain {} for deposit {}",
+            if lock { "Locked" } else { "Released" },
+            hold.amount, hold.chain_id, deposit_id
+        );
+        Ok(Some(hold))
+    }
+    
+    /// Hold placed for a deposit, if any
+    pub fn get_hold(&self, deposit_id: &str) -> Option<LiquidityHold> {
+        self.holds.lock().unwrap().get(deposit_id).cloned()
+    }
+    
+    /// All outstanding holds
+    pub fn get_holds(&self) -> Vec<LiquidityHold> {
+        self.holds.lock().unwrap().values().cloned().collect()
+    }
+    
+    /// Total held on one pool
+    fn held_amount(&self, chain_id: u64, token: &str) -> u64 {
+        self.holds
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|h| h.chain_id == chain_id && h.token == token)
+            .map(|h| h.amount)
+            .sum()
+    }
+    
+    /// Add liquidity to a pool
+    pub async fn add_liquidity(
+        &self,
+        chain_id: u64,
+        token: &str,
+        amount: u64,
+    ) -> Result<()> {
+        let pool = self.pool_entry(chain_id, token);
+        let mut pool = pool.lock().await;
+        let available = pool.available + amount;
         
         // Update database
         self.db
-            .update_liquidity_pool(chain_id, token, pool.available, pool.locked)
+            .update_liquidity_pool(chain_id, token, available, pool.locked)
             .await?;
+        pool.available = available;
         
         info!("Added {} liquidity to chain {}", amount, chain_id);
         Ok(())
@@ -185,35 +391,121 @@ impl LiquidityManager {
     
     /// Remove liquidity from a pool
     pub async fn remove_liquidity(
-        &mut self,
+        &self,
         chain_id: u64,
         token: &str,
         amount: u64,
     ) -> Result<()> {
-        let key = (chain_id, token.to_string());
-        let pool = self.pools.get_mut(&key)
-            .context("Pool not found")?;
+        let mut pool = self.lock_pool(chain_id, token).await?;
         
         if pool.available < amount {
             anyhow::bail!("Insufficient available liquidity");
         }
-        
-        pool.available -= amount;
+        let available = pool.available - amount;
         
         // Update database
         self.db
-            .update_liquidity_pool(chain_id, token, pool.available, pool.locked)
+            .update_liquidity_pool(chain_id, token, available, pool.locked)
             .await?;
+        pool.available = available;
         
         info!("Removed {} liquidity from chain {}", amount, chain_id);
         Ok(())
     }
     
+    /// Operator recovery for liquidity stuck in `locked` (a withdrawal that
+    /// expired or was reorged out never releases it). Moves `amount` back to
+    /// `available` and records an audit entry; can't exceed what's locked.
+    pub async fn force_unlock_liquidity(
+        &self,
+        chain_id: u64,
+        token: &str,
+        amount: u64,
+        reason: &str,
+    ) -> Result<()> {
+        let mut pool = self.lock_pool(chain_id, token).await?;
+        
+        if amount == 0 || amount > pool.locked {
+            anyhow::bail!(
+                "Cannot unlock {}: {} locked",
+                amount,
+                pool.locked
+            );
+        }
+        
+        let available = pool.available
+            .checked_add(amount)
+            .context("Available liquidity overflow")?;
+        let locked = pool.locked - amount;
+        
+        // Persist with the audit entry before touching the in-memory pool
+        self.db
+            .update_liquidity_pool_audited(
+                chain_id,
+                token,
+                available,
+                locked,
+                "force_unlock",
+                amount,
+                reason,
+            )
+            .await?;
+        
+        pool.available = available;
+        pool.locked = locked;
+        
+        warn!(
+            "Force-unlocked {} liquidity on chain {} token {}: {}",
+            amount, chain_id, token, reason
+        );
+        Ok(())
+    }
+    
+    /// Reconcile a pool against the gateway's on-chain token balance.
+    /// Locked and held liquidity is still in the gateway, so the rest of the
+    /// on-chain balance is what's available. Returns the change in
+    /// `available` (0 when the pool already matched).
+    pub async fn reconcile_pool(
+        &self,
+        chain_id: u64,
+        token: &str,
+        onchain_balance: u64,
+    ) -> Result<i128> {
+        let pool = self.pool_entry(chain_id, token);
+        let mut pool = pool.lock().await;
+        // Holds on this pool only change under its lock
+        let held = self.held_amount(chain_id, token);
+        
+        if onchain_balance < pool.locked {
+            warn!(
+                "On-chain balance {} below locked liquidity {}: chain={}, token={}",
+                onchain_balance, pool.locked, chain_id, token
+            );
+        }
+        
+        let available = onchain_balance.saturating_sub(pool.locked).saturating_sub(held);
+        let delta = available as i128 - pool.available as i128;
+        
+        if delta != 0 {
+            warn!(
+                "Liquidity drift on chain {} token {}: stored {}, on-chain {} ({:+})",
+                chain_id, token, pool.available, available, delta
+            );
+            
+            self.db
+                .update_liquidity_pool(chain_id, token, available, pool.locked)
+                .await?;
+            pool.available = available;
+        }
+        
+        Ok(delta)
+    }
+    
     /// Check which pools need rebalancing
     pub async fn check_rebalancing_needed(&self) -> Result<Vec<(u64, String)>> {
         let mut needs_rebalancing = Vec::new();
         
-        for (key, pool) in &self.pools {
+        for pool in self.get_all_pools().await {
             if pool.needs_rebalancing(self.config.rebalance_threshold) {
                 info!(
                     "Pool needs rebalancing: chain={}, token={}, utilization={:.2}%",
@@ -221,7 +513,7 @@ impl LiquidityManager {
                     pool.token,
                     pool.utilization() * 100.0
                 );
-                needs_rebalancing.push(key.clone());
+                needs_rebalancing.push((pool.chain_id, pool.token));
             }
         }
         
@@ -230,15 +522,13 @@ impl LiquidityManager {
     
     /// Trigger rebalancing for a specific pool
     pub async fn trigger_rebalance(
-        &mut self,
+        &self,
         chain_id: u64,
         token: &str,
     ) -> Result<()> {
         info!("Triggering rebalance for chain {} token {}", chain_id, token);
         
-        let key = (chain_id, token.to_string());
-        let pool = self.pools.get_mut(&key)
-            .context("Pool not found")?;
+        let mut pool = self.lock_pool(chain_id, token).await?;
         
         // Calculate rebalance amount
         let amount = pool.calculate_rebalance_amount(self.config.target_utilization);
@@ -271,37 +561,70 @@ impl LiquidityManager {
         Ok(())
     }
     
-    /// Get pool state
-    pub fn get_pool(&self, chain_id: u64, token: &str) -> Option<&LiquidityPool> {
-        let key = (chain_id, token.to_string());
-        self.pools.get(&key)
+    /// Snapshot of a pool, waiting out any write in progress on it
+    pub async fn get_pool(&self, chain_id: u64, token: &str) -> Option<LiquidityPool> {
+        let pool = self.shared_pool(chain_id, token)?;
+        let pool = pool.lock().await;
+        Some(pool.clone())
     }
     
-    /// Get all pools
-    pub fn get_all_pools(&self) -> Vec<&LiquidityPool> {
-        self.pools.values().collect()
+    /// Snapshots of all pools
+    pub async fn get_all_pools(&self) -> Vec<LiquidityPool> {
+        let shared: Vec<SharedPool> = self.pools.read().unwrap().values().cloned().collect();
+        
+        let mut pools = Vec::with_capacity(shared.len());
+        for pool in shared {
+            pools.push(pool.lock().await.clone());
+        }
+        pools
+    }
+    
+    fn shared_pool(&self, chain_id: u64, token: &str) -> Option<SharedPool> {
+        self.pools.read().unwrap().get(&(chain_id, token.to_string())).cloned()
+    }
+    
+    /// The pool for a chain/token pair, added empty (in memory only) if
+    /// missing
+    fn pool_entry(&self, chain_id: u64, token: &str) -> SharedPool {
+        if let Some(pool) = self.shared_pool(chain_id, token) {
+            return pool;
+        }
+        
+        let mut pools = self.pools.write().unwrap();
+        pools
+            .entry((chain_id, token.to_string()))
+            .or_insert_with(|| Arc::new(tokio::sync::Mutex::new(LiquidityPool::empty(chain_id, token))))
+            .clone()
     }
     
     /// Load pool states from database
     async fn load_pools(&mut self) -> Result<()> {
         let pools = self.db.get_all_liquidity_pools().await?;
+        let holds = self.db.get_liquidity_holds().await?;
         
+        let loaded = self.pools.get_mut().unwrap();
         for (chain_id, token, available, locked, target) in pools {
             let key = (chain_id, token.clone());
-            self.pools.insert(
+            loaded.insert(
                 key,
-                LiquidityPool {
+                Arc::new(tokio::sync::Mutex::new(LiquidityPool {
                     chain_id,
                     token,
                     available,
                     locked,
                     target,
                     last_rebalance: 0,
-                },
+                })),
             );
         }
+        let pool_count = loaded.len();
+        
+        let loaded_holds = self.holds.get_mut().unwrap();
+        for hold in holds {
+            loaded_holds.insert(hold.deposit_id.clone(), hold);
+        }
         
-        info!("Loaded {} liquidity pools from database", self.pools.len());
+        info!("Loaded {} liquidity pools ({} holds) from database", pool_count, loaded_holds.len());
         Ok(())
     }
 }
@@ -354,4 +677,267 @@ mod tests {
         let amount = pool.calculate_rebalance_amount(0.5);
         assert_eq!(amount, 50);
     }
-}
\ No newline at end of file
+    
+    #[tokio::test]
+    async fn test_expired_authorization_releases_liquidity() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(&dir.path().join("coordinator.db"), &DatabaseConfig::default())
+            .await
+            .unwrap();
+        let config = LiquidityConfig {
+            rebalance_threshold: 0.8,
+            target_utilization: 0.5,
+            min_liquidity_usd: 10_000,
+            max_rebalance_usd: 100_000,
+        };
+        let manager = LiquidityManager::new(db, config).await.unwrap();
+        
+        manager.add_liquidity(1, "0xtoken", 1_000).await.unwrap();
+        
+        // Deposit locks, authorization consumes the lock
+        manager.lock_liquidity(1, "0xtoken", 400).await.unwrap();
+        manager.release_liquidity(1, "0xtoken", 400).await.unwrap();
+        assert_eq!(manager.get_pool(1, "0xtoken").await.unwrap().available, 600);
+        
+        // Expiry hands it back
+        manager.restore_liquidity(1, "0xtoken", 400).await.unwrap();
+        let pool = manager.get_pool(1, "0xtoken").await.unwrap();
+        assert_eq!(pool.available, 1_000);
+        assert_eq!(pool.locked, 0);
+        
+        // Re-authorization takes it again
+        manager.reserve_liquidity(1, "0xtoken", 400).await.unwrap();
+        assert_eq!(manager.get_pool(1, "0xtoken").await.unwrap().available, 600);
+        assert!(manager.reserve_liquidity(1, "0xtoken", 700).await.is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_route_falls_back_when_preferred_chain_underfunded() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(&dir.path().join("coordinator.db"), &DatabaseConfig::default())
+            .await
+            .unwrap();
+        let config = LiquidityConfig {
+            rebalance_threshold: 0.8,
+            target_utilization: 0.5,
+            min_liquidity_usd: 10_000,
+            max_rebalance_usd: 100_000,
+        };
+        let manager = LiquidityManager::new(db, config).await.unwrap();
+        
+        manager.add_liquidity(8453, "0xbase", 100).await.unwrap();
+        manager.add_liquidity(10, "0xop", 5_000).await.unwrap();
+        
+        let candidate = |chain_id: u64, token: &str| RouteCandidate {
+            chain_id,
+            token: token.to_string(),
+            amount: Amount::from_u64(1_000, 6),
+        };
+        
+        // Base preferred but underfunded, Arbitrum has no pool, Optimism can cover it
+        let candidates = vec![
+            candidate(8453, "0xbase"),
+            candidate(42161, "0xarb"),
+            candidate(10, "0xop"),
+        ];
+        assert_eq!(manager.select_route(&candidates).await.unwrap().chain_id, 10);
+        
+        // Preferred chain wins when funded
+        manager.add_liquidity(8453, "0xbase", 1_000).await.unwrap();
+        assert_eq!(manager.select_route(&candidates).await.unwrap().chain_id, 8453);
+        
+        // No chain can cover it
+        assert!(manager.select_route(&[candidate(42161, "0xarb")]).await.is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_reconcile_pool_adopts_onchain_balance() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(&dir.path().join("coordinator.db"), &DatabaseConfig::default())
+            .await
+            .unwrap();
+        let manager = LiquidityManager::new(db.clone(), LiquidityConfig::mock()).await.unwrap();
+        
+        manager.add_liquidity(1, "0xtoken", 1_000).await.unwrap();
+        manager.lock_liquidity(1, "0xtoken", 200).await.unwrap();
+        
+        // Tokens sent straight to the gateway: 1_500 on-chain, 200 of it locked
+        assert_eq!(manager.reconcile_pool(1, "0xtoken", 1_500).await.unwrap(), 500);
+        let pool = manager.get_pool(1, "0xtoken").await.unwrap();
+        assert_eq!(pool.available, 1_300);
+        assert_eq!(pool.locked, 200);
+        
+        // Already in sync
+        assert_eq!(manager.reconcile_pool(1, "0xtoken", 1_500).await.unwrap(), 0);
+        
+        // Persisted, so a restart sees the reconciled value
+        let reloaded = LiquidityManager::new(db, LiquidityConfig::mock()).await.unwrap();
+        assert_eq!(reloaded.get_pool(1, "0xtoken").await.unwrap().available, 1_300);
+    }
+    
+    #[tokio::test]
+    async fn test_force_unlock_liquidity() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(&dir.path().join("coordinator.db"), &DatabaseConfig::default())
+            .await
+            .unwrap();
+        let manager = LiquidityManager::new(db.clone(), LiquidityConfig::mock()).await.unwrap();
+        
+        manager.add_liquidity(1, "0xtoken", 1_000).await.unwrap();
+        manager.lock_liquidity(1, "0xtoken", 400).await.unwrap();
+        
+        manager.force_unlock_liquidity(1, "0xtoken", 300, "w1 reorged out").await.unwrap();
+        let pool = manager.get_pool(1, "0xtoken").await.unwrap();
+        assert_eq!(pool.available, 900);
+        assert_eq!(pool.locked, 100);
+        
+        let audit = db.get_liquidity_audit(1, "0xtoken").await.unwrap();
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].action, "force_unlock");
+        assert_eq!(audit[0].amount, 300);
+        assert_eq!(audit[0].reason, "w1 reorged out");
+    }
+    
+    #[tokio::test]
+    async fn test_force_unlock_rejects_more_than_locked() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(&dir.path().join("coordinator.db"), &DatabaseConfig::default())
+            .await
+            .unwrap();
+        let manager = LiquidityManager::new(db.clone(), LiquidityConfig::mock()).await.unwrap();
+        
+        manager.add_liquidity(1, "0xtoken", 1_000).await.unwrap();
+        manager.lock_liquidity(1, "0xtoken", 400).await.unwrap();
+        
+        assert!(manager.force_unlock_liquidity(1, "0xtoken", 401, "too much").await.is_err());
+        assert!(manager.force_unlock_liquidity(2, "0xtoken", 1, "no pool").await.is_err());
+        
+        let pool = manager.get_pool(1, "0xtoken").await.unwrap();
+        assert_eq!(pool.available, 600);
+        assert_eq!(pool.locked, 400);
+        assert!(db.get_liquidity_audit(1, "0xtoken").await.unwrap().is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_hold_becomes_lock_once_final() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(&dir.path().join("coordinator.db"), &DatabaseConfig::default())
+            .await
+            .unwrap();
+        let manager = LiquidityManager::new(db.clone(), LiquidityConfig::mock()).await.unwrap();
+        
+        manager.add_liquidity(1, "0xtoken", 1_000).await.unwrap();
+        
+        // Awaiting confirmations: out of `available` but not locked
+        manager.hold_liquidity("d1", 1, "0xtoken", 400).await.unwrap();
+        let pool = manager.get_pool(1, "0xtoken").await.unwrap();
+        assert_eq!((pool.available, pool.locked), (600, 0));
+        assert_eq!(manager.get_hold("d1").unwrap().amount, 400);
+        
+        // Held liquidity can't be promised twice
+        assert!(manager.select_route(&[RouteCandidate {
+            chain_id: 1,
+            token: "0xtoken".to_string(),
+            amount: Amount::from_u64(700, 6),
+        }]).await.is_none());
+        
+        // Repeated passes while waiting don't hold again
+        manager.hold_liquidity("d1", 1, "0xtoken", 400).await.unwrap();
+        assert_eq!(manager.get_pool(1, "0xtoken").await.unwrap().available, 600);
+        
+        // Holds survive a restart and still count against on-chain balance
+        let manager = LiquidityManager::new(db, LiquidityConfig::mock()).await.unwrap();
+        assert_eq!(manager.get_holds().len(), 1);
+        assert_eq!(manager.reconcile_pool(1, "0xtoken", 1_000).await.unwrap(), 0);
+        
+        // Final: the hold turns into a lock
+        let hold = manager.confirm_hold("d1").await.unwrap().unwrap();
+        assert_eq!(hold.amount, 400);
+        let pool = manager.get_pool(1, "0xtoken").await.unwrap();
+        assert_eq!((pool.available, pool.locked), (600, 400));
+        assert!(manager.get_hold("d1").is_none());
+        assert!(manager.confirm_hold("d1").await.unwrap().is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_hold_released_without_finality() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(&dir.path().join("coordinator.db"), &DatabaseConfig::default())
+            .await
+            .unwrap();
+        let manager = LiquidityManager::new(db.clone(), LiquidityConfig::mock()).await.unwrap();
+        
+        manager.add_liquidity(1, "0xtoken", 1_000).await.unwrap();
+        manager.hold_liquidity("d1", 1, "0xtoken", 400).await.unwrap();
+        assert!(manager.hold_liquidity("d2", 1, "0xtoken", 700).await.is_err());
+        
+        // Deposit reorged out or timed out: everything goes back
+        manager.release_hold("d1").await.unwrap().unwrap();
+        let pool = manager.get_pool(1, "0xtoken").await.unwrap();
+        assert_eq!((pool.available, pool.locked), (1_000, 0));
+        assert!(db.get_liquidity_holds().await.unwrap().is_empty());
+        assert!(manager.release_hold("d1").await.unwrap().is_none());
+        
+        manager.hold_liquidity("d2", 1, "0xtoken", 700).await.unwrap();
+    }
+    
+    #[tokio::test]
+    async fn test_pools_do_not_contend() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(&dir.path().join("coordinator.db"), &DatabaseConfig::default())
+            .await
+            .unwrap();
+        let manager = Arc::new(LiquidityManager::new(db, LiquidityConfig::mock()).await.unwrap());
+        manager.add_liquidity(1, "0xusdc", 1_000).await.unwrap();
+        manager.add_liquidity(8453, "0xusdc", 1_000).await.unwrap();
+        
+        // A withdrawal on chain 1 is mid-write
+        let busy = manager.lock_pool(1, "0xusdc").await.unwrap();
+        
+        // Chain 8453 carries on regardless
+        tokio::time::timeout(std::time::Duration::from_secs(1), async {
+            manager.lock_liquidity(8453, "0xusdc", 100).await.unwrap();
+            manager.hold_liquidity("d1", 8453, "0xusdc", 100).await.unwrap();
+            assert_eq!(manager.get_pool(8453, "0xusdc").await.unwrap().available, 800);
+        })
+        .await
+        .expect("chain 8453 waited on chain 1");
+        
+        // Chain 1 waits its turn
+        let waiting = tokio::spawn({
+            let manager = manager.clone();
+            async move { manager.lock_liquidity(1, "0xusdc", 100).await }
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        assert!(!waiting.is_finished());
+        
+        drop(busy);
+        tokio::time::timeout(std::time::Duration::from_secs(1), waiting)
+            .await
+            .expect("chain 1 resumes once free")
+            .unwrap()
+            .unwrap();
+        
+        // Both pools busy at once
+        let workers: Vec<_> = [1, 8453]
+            .into_iter()
+            .map(|chain_id| {
+                let manager = manager.clone();
+                tokio::spawn(async move {
+                    for _ in 0..10 {
+                        manager.lock_liquidity(chain_id, "0xusdc", 10).await.unwrap();
+                        manager.release_liquidity(chain_id, "0xusdc", 10).await.unwrap();
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.await.unwrap();
+        }
+        
+        let pool = manager.get_pool(1, "0xusdc").await.unwrap();
+        assert_eq!((pool.available, pool.locked), (800, 100));
+        let pool = manager.get_pool(8453, "0xusdc").await.unwrap();
+        assert_eq!((pool.available, pool.locked), (700, 100));
+    }
+}
@@ -0,0 +1,238 @@
+// zcash-coordinator/src/liquidity_sync.rs
+//! Reconcile `LiquidityManager` pools with gateway balances on-chain
+//! Pools drift when tokens reach a gateway outside the bridge flow (direct
+//! transfers, accrued fees), so the chain is treated as the source of truth
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tracing::{info, warn};
+
+use crate::config::ChainConfig;
+use crate::liquidity_manager::LiquidityManager;
+
+/// Token address used for a chain's native asset
+const NATIVE_TOKEN: &str = "0x0000000000000000000000000000000000000000";
+
+/// `balanceOf(address)` selector
+const BALANCE_OF_SELECTOR: &str = "70a08231";
+
+/// Where on-chain gateway balances come from
+#[async_trait]
+pub trait GatewayBalanceSource: Send + Sync {
+    /// Balance of `token` held by the gateway on `chain`, or `None` for
+    /// chains this source can't query
+    async fn gateway_balance(&self, chain: &ChainConfig, token: &str) -> Result<Option<u64>>;
+}
+
+/// Reads EVM gateway balances over JSON-RPC
+#[derive(Clone, Default)]
+pub struct EvmBalanceClient {
+    client: Client,
+}
+
+impl EvmBalanceClient {
+    pub fn new() -> Self {
+        Self {
+            client: Client::new(),
+        }
+    }
+
+    async fn call(&self, chain: &ChainConfig, method: &str, params: Value) -> Result<String> {
+        let payload = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params
+        });
+
+        let response: Value = self.client
+            .post(&chain.rpc_url)
+            .json(&payload)
+            .send()
+            .await
+            .with_context(|| format!("{} request to chain {} failed", method, chain.chain_id))?
+            .json()
+            .await?;
+
+        response
+            .get("result")
+            .and_then(|r| r.as_str())
+            .map(str::to_string)
+            .with_context(|| format!("No result in {} response", method))
+    }
+}
+
+#[async_trait]
+impl GatewayBalanceSource for EvmBalanceClient {
+    async fn gateway_balance(&self, chain: &ChainConfig, token: &str) -> Result<Option<u64>> {
+        if !chain.chain_type.is_evm() {
+            return Ok(None);
+        }
+
+        let result = if token.eq_ignore_ascii_case(NATIVE_TOKEN) {
+            self.call(chain, "eth_getBalance", json!([chain.gateway_address, "latest"])).await?
+        } else {
+            let data = format!(
+                "0x{}{:0>64}",
+                BALANCE_OF_SELECTOR,
+                chain.gateway_address.trim_start_matches("0x").to_lowercase()
+            );
+            self.call(chain, "eth_call", json!([{ "to": token, "data": data }, "latest"])).await?
+        };
+
+        parse_balance(&result).map(Some)
+    }
+}
+
+/// Parse a hex quantity or 32-byte word, rejecting balances beyond `u64`
+fn parse_balance(hex: &str) -> Result<u64> {
+    let digits = hex.trim_start_matches("0x").trim_start_matches('0');
+    if digits.is_empty() {
+        return Ok(0);
+    }
+    if digits.len() > 16 {
+        anyhow::bail!("Balance {} exceeds u64", hex);
+    }
+    u64::from_str_radix(digits, 16).context("Invalid balance")
+}
+
+/// Outcome of reconciling one pool
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolSync {
+    pub chain_id: u64,
+    pub token: String,
+    pub onchain_balance: u64,
+    /// Change applied to the pool's available liquidity
+    pub adjustment: i128,
+}
+
+/// Query every known pool's gateway balance and reconcile it. Balances are
+/// fetched without holding any pool lock; pools on unconfigured or
+/// unqueryable chains, or whose query fails, are skipped.
+pub async fn sync_liquidity(
+    liquidity_manager: &LiquidityManager,
+    chains: &[ChainConfig],
+    source: &dyn GatewayBalanceSource,
+) -> Result<Vec<PoolSync>> {
+    let pools: Vec<(u64, String)> = liquidity_manager
+        .get_all_pools()
+        .await
+        .into_iter()
+        .map(|p| (p.chain_id, p.token))
+        .collect();
+
+    let mut balances = Vec::new();
+    for (chain_id, token) in pools {
+        let Some(chain) = chains.iter().find(|c| c.chain_id == chain_id && c.enabled) else {
+            continue;
+        };
+
+        match source.gateway_balance(chain, &token).await {
+            Ok(Some(balance)) => balances.push((chain_id, token, balance)),
+            Ok(None) => {}
+            Err(e) => warn!("Failed to read gateway balance on chain {} for {}: {}", chain_id, token, e),
+        }
+    }
+
+    let mut synced = Vec::with_capacity(balances.len());
+    for (chain_id, token, onchain_balance) in balances {
+        let adjustment = liquidity_manager.reconcile_pool(chain_id, &token, onchain_balance).await?;
+        synced.push(PoolSync {
+            chain_id,
+            token,
+            onchain_balance,
+            adjustment,
+        });
+    }
+
+    let drifted = synced.iter().filter(|s| s.adjustment != 0).count();
+    info!("Liquidity sync: {} pools checked, {} reconciled", synced.len(), drifted);
+
+    Ok(synced)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ChainType, DatabaseConfig, LiquidityConfig};
+    use crate::database::Database;
+    use std::collections::HashMap;
+
+    /// Fixed balances per (chain, token); anything else is an RPC error
+    struct MockBalances(HashMap<(u64, String), u64>);
+
+    #[async_trait]
+    impl GatewayBalanceSource for MockBalances {
+        async fn gateway_balance(&self, chain: &ChainConfig, token: &str) -> Result<Option<u64>> {
+            self.0
+                .get(&(chain.chain_id, token.to_string()))
+                .copied()
+                .map(Some)
+                .context("RPC unavailable")
+        }
+    }
+
+    fn chain(chain_id: u64) -> ChainConfig {
+        ChainConfig {
+            chain_id,
+            name: format!("chain-{}", chain_id),
+            chain_type: ChainType::Ethereum,
+            rpc_url: "http://localhost:8545".to_string(),
+            ws_url: None,
+            gateway_address: "0xgateway".to_string(),
+            start_block: 0,
+            enabled: true,
+            confirmations: 1,
+            accept_unverified_deposits: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_balance() {
+        assert_eq!(parse_balance("0x0").unwrap(), 0);
+        assert_eq!(parse_balance("0x3e8").unwrap(), 1_000);
+        assert_eq!(parse_balance(&format!("0x{:064x}", 1_000)).unwrap(), 1_000);
+        assert!(parse_balance(&format!("0x1{:016x}", 0)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sync_reconciles_drifted_pools() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(&dir.path().join("coordinator.db"), &DatabaseConfig::default())
+            .await
+            .unwrap();
+        let manager = LiquidityManager::new(db, LiquidityConfig::mock()).await.unwrap();
+        manager.add_liquidity(1, "0xusdc", 1_000).await.unwrap();
+        manager.lock_liquidity(1, "0xusdc", 100).await.unwrap();
+        manager.add_liquidity(8453, "0xusdc", 500).await.unwrap();
+        // Configured chain whose RPC fails
+        manager.add_liquidity(10, "0xusdc", 700).await.unwrap();
+        // Chain missing from config
+        manager.add_liquidity(137, "0xusdc", 300).await.unwrap();
+
+        let source = MockBalances(HashMap::from([
+            // Fees accrued on top of the 1_000 held
+            ((1, "0xusdc".to_string()), 1_250),
+            // In sync
+            ((8453, "0xusdc".to_string()), 500),
+        ]));
+        let chains = vec![chain(1), chain(8453), chain(10)];
+
+        let mut synced = sync_liquidity(&manager, &chains, &source).await.unwrap();
+        synced.sort_by_key(|s| s.chain_id);
+
+        assert_eq!(synced.len(), 2);
+        assert_eq!(synced[0].chain_id, 1);
+        assert_eq!(synced[0].adjustment, 350);
+        assert_eq!(synced[1].adjustment, 0);
+
+        let pool = manager.get_pool(1, "0xusdc").await.unwrap();
+        assert_eq!(pool.available, 1_150);
+        assert_eq!(pool.locked, 100);
+        assert_eq!(manager.get_pool(10, "0xusdc").await.unwrap().available, 700);
+        assert_eq!(manager.get_pool(137, "0xusdc").await.unwrap().available, 300);
+    }
+}
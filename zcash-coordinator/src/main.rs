@@ -16,28 +16,58 @@
 
 use anyhow::{Context, Result};
 use clap::Parser;
-use tracing::{error, info, warn};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tracing::{debug, error, info, warn, Instrument};
 use tokio::signal;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+mod amount;
 mod config;
 mod shielded_pool;
 mod token_registry;
 mod liquidity_manager;
+mod liquidity_sync;
+mod deposit_verifier;
+mod deposit_events;
+mod deposit_queue;
 mod database;
+mod db_health;
+mod metrics;
 mod rpc_server;
 mod zcash_client;
-
+mod worker_pool;
+mod chain_head;
+mod signing;
+mod telemetry;
+mod cli;
+mod config_check;
+mod wire;
+
+#[cfg(all(test, feature = "e2e"))]
+mod e2e_tests;
+
+use amount::Amount;
 use config::Config;
 use shielded_pool::ShieldedPoolManager;
 use token_registry::TokenRegistry;
-use liquidity_manager::LiquidityManager;
+use liquidity_manager::{LiquidityManager, RouteCandidate};
 use database::Database;
+use db_health::DbHealth;
 use rpc_server::RpcServer;
+use signing::CoordinatorSigner;
 use zcash_client::ZcashClient;
+use worker_pool::WorkerPool;
+use chain_head::ChainHeadClient;
+use liquidity_sync::EvmBalanceClient;
+use deposit_verifier::{DepositVerification, EvmSourceClient};
+use deposit_events::{DepositEvents, DepositStage};
+use deposit_queue::DepositQueue;
+
+/// Rejection reason for a deposit whose note was claimed but never got a
+/// txid recorded; the note may exist, so it can't simply be sent again
+const NOTE_SEND_INTERRUPTED: &str =
+    "Note send interrupted before its txid was recorded; check the Zcash wallet before re-minting";
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
@@ -51,14 +81,60 @@ struct Args {
     #[clap(short, long, default_value = "8080")]
     port: u16,
 
+    #[clap(short, long, default_value = "9092")]
+    metrics_port: u16,
+
     #[clap(short, long, default_value = "coordinator.db")]
     database: PathBuf,
+
+    /// Check connectivity to every configured node and gateway address
+    /// formats, print a report and exit
+    #[clap(long)]
+    check: bool,
+
+    /// Write a commented template config to PATH and exit
+    #[clap(long, value_name = "PATH")]
+    generate_config: Option<PathBuf>,
+
+    /// Run a one-off operator command instead of the coordinator daemon
+    #[clap(subcommand)]
+    command: Option<cli::Command>,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
-    init_tracing(args.verbose)?;
+    telemetry::init(args.verbose)?;
+
+    if let Some(path) = &args.generate_config {
+        if path.exists() {
+            anyhow::bail!("Refusing to overwrite existing file {:?}", path);
+        }
+        std::fs::write(path, Config::template_toml()?)
+            .with_context(|| format!("Failed to write {:?}", path))?;
+        info!("✓ Template config written to {:?}; fill in its placeholders before starting", path);
+        return Ok(());
+    }
+
+    if args.check {
+        let config = Config::load(&args.config)
+            .context("Failed to load configuration")?;
+        let results = config_check::run_checks(&config).await;
+        print!("{}", config_check::format_report(&results));
+        
+        let failed = results.iter().filter(|r| !r.passed()).count();
+        if failed > 0 {
+            anyhow::bail!("{} of {} checks failed", failed, results.len());
+        }
+        return Ok(());
+    }
+
+    if let Some(command) = &args.command {
+        let config = Config::load(&args.config)
+            .context("Failed to load configuration")?;
+        print!("{}", cli::run(command, &config, &args.database).await?);
+        return Ok(());
+    }
 
     info!("🌉 Starting ZeroBridge Zcash Coordinator v{}", env!("CARGO_PKG_VERSION"));
     info!("Configuration file: {:?}", args.config);
@@ -70,11 +146,17 @@ async fn main() -> Result<()> {
     info!("  Zcash network: {:?}", config.zcash.network);
 
     // Initialize database
-    let db = Database::new(&args.database)
+    let db = Database::new(&args.database, &config.database)
         .await
         .context("Failed to initialize database")?;
     info!("✓ Database initialized");
 
+    // Authorizations are signed with this key; relayers verify them against
+    // GET /coordinator/pubkey
+    let signer = Arc::new(CoordinatorSigner::from_hex(&config.signing_key)?);
+    db.record_signing_key(&signer.public_key(), &signer.address(), config.key_rotation_overlap).await?;
+    info!("✓ Signing authorizations as {}", signer.address());
+
     // Initialize Zcash client
     let zcash_client = ZcashClient::new(config.zcash.clone())
         .await
@@ -86,20 +168,20 @@ async fn main() -> Result<()> {
     zcash_client.wait_for_sync().await?;
     info!("✓ Zcash node synchronized");
 
-    // Initialize token registry
-    let token_registry = Arc::new(
-        TokenRegistry::load(&config.tokens_config)
-            .await
-            .context("Failed to load token registry")?
-    );
+    // Initialize token registry, then re-apply operator removals
+    let mut token_registry = TokenRegistry::load(&config.tokens_config)
+        .await
+        .context("Failed to load token registry")?;
+    token_registry.apply_overrides(db.get_token_overrides().await?);
     info!("✓ Token registry loaded ({} tokens)", token_registry.token_count());
+    let token_registry = Arc::new(RwLock::new(token_registry));
 
     // Initialize liquidity manager
-    let liquidity_manager = Arc::new(RwLock::new(
+    let liquidity_manager = Arc::new(
         LiquidityManager::new(db.clone(), config.liquidity.clone())
             .await
             .context("Failed to initialize liquidity manager")?
-    ));
+    );
 
     // Initialize shielded pool manager
     let shielded_pool = Arc::new(RwLock::new(
@@ -115,31 +197,87 @@ async fn main() -> Result<()> {
     info!("✓ Shielded pool manager initialized");
     info!("✓ Liquidity manager initialized");
 
+    // Ping the database in the background; writes and processing pause
+    // while it's unreachable
+    let db_health = DbHealth::new();
+    let probe_handle = tokio::spawn(db_health.clone().run_probe(db.clone(), db_health::PROBE_INTERVAL));
+
+    // Simulated withdrawals get their own key, so no gateway accepts them
+    let simulation_signer = match &config.simulation_signing_key {
+        Some(key) if config.enable_simulation && cfg!(feature = "simulation") => {
+            let simulation_signer = Arc::new(CoordinatorSigner::from_hex(key)?);
+            warn!(
+                "Withdrawal simulation enabled: POST /simulate/withdrawal signs without proofs as {}",
+                simulation_signer.address()
+            );
+            Some(simulation_signer)
+        }
+        _ => {
+            if config.enable_simulation {
+                warn!("enable_simulation ignored: built without the `simulation` feature");
+            }
+            None
+        }
+    };
+
     // Start RPC server for relayer queries
+    let deposit_events = DepositEvents::new();
+    let (deposit_queue, deposit_writer) = DepositQueue::new(config.deposit_queue_capacity);
+    let writer_handle = tokio::spawn(deposit_writer.run(db.clone(), deposit_events.clone(), db_health.clone()));
     let rpc_server = RpcServer::new(
         args.port,
+        config.authorization_ttl,
+        tokio::time::Duration::from_secs(config.rpc_request_timeout),
+        config.admin_token.clone(),
+        simulation_signer,
+        signer.clone(),
+        config.key_rotation_overlap,
         db.clone(),
         shielded_pool.clone(),
         token_registry.clone(),
         liquidity_manager.clone(),
+        config.chains.clone(),
+        deposit_events.clone(),
+        db_health.clone(),
+        deposit_queue,
     );
     
+    let (stop_rpc, rpc_stopped) = tokio::sync::oneshot::channel::<()>();
     let rpc_handle = tokio::spawn(async move {
-        if let Err(e) = rpc_server.start().await {
+        let shutdown = async {
+            let _ = rpc_stopped.await;
+        };
+        if let Err(e) = rpc_server.start(shutdown).await {
             error!("RPC server error: {}", e);
         }
     });
     info!("✓ RPC server started on port {}", args.port);
 
+    let metrics_port = args.metrics_port;
+    let metrics_handle = tokio::spawn(async move {
+        if let Err(e) = metrics::start_server(metrics_port).await {
+            error!("Metrics server error: {}", e);
+        }
+    });
+    info!("✓ Metrics server started on port {}", metrics_port);
+
     // Create coordinator instance
-    let coordinator = Coordinator {
+    let workers = WorkerPool::new(config.worker_pool_size);
+    let coordinator = Arc::new(Coordinator {
         config,
+        signer,
         db,
         zcash_client,
         shielded_pool,
         token_registry,
         liquidity_manager,
-    };
+        workers,
+        chain_heads: ChainHeadClient::new(),
+        source_chains: EvmSourceClient::new(),
+        balances: EvmBalanceClient::new(),
+        deposit_events,
+        db_health,
+    });
 
     info!("🚀 Coordinator fully initialized and running");
     info!("   Relayers can connect to process deposits and withdrawals");
@@ -157,25 +295,68 @@ async fn main() -> Result<()> {
         }
     }
 
-    rpc_handle.abort();
+    // Stop accepting connections and let in-flight requests finish
+    let _ = stop_rpc.send(());
+    match tokio::time::timeout(tokio::time::Duration::from_secs(30), rpc_handle).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => error!("RPC server task failed: {}", e),
+        Err(_) => warn!("RPC requests still running after 30s, stopping anyway"),
+    }
+    
+    // With the RPC server gone the queue closes; let the writer store what
+    // relayers were already told is queued
+    if tokio::time::timeout(tokio::time::Duration::from_secs(5), writer_handle).await.is_err() {
+        warn!("Deposit queue not drained before shutdown");
+    }
+    probe_handle.abort();
+    metrics_handle.abort();
     
     info!("Coordinator stopped gracefully");
+    telemetry::shutdown();
     Ok(())
 }
 
 struct Coordinator {
     config: Config,
+    signer: Arc<CoordinatorSigner>,
     db: Database,
     zcash_client: ZcashClient,
     shielded_pool: Arc<RwLock<ShieldedPoolManager>>,
-    token_registry: Arc<TokenRegistry>,
-    liquidity_manager: Arc<RwLock<LiquidityManager>>,
+    token_registry: Arc<RwLock<TokenRegistry>>,
+    liquidity_manager: Arc<LiquidityManager>,
+    workers: WorkerPool,
+    chain_heads: ChainHeadClient,
+    source_chains: EvmSourceClient,
+    balances: EvmBalanceClient,
+    deposit_events: DepositEvents,
+    db_health: DbHealth,
+}
+
+/// Whether a hold has outlived `ttl` seconds
+fn hold_expired(hold: &database::LiquidityHold, ttl: u64) -> bool {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    now - hold.created_at >= ttl as i64
+}
+
+/// What a pass over a pending deposit did with it
+enum DepositOutcome {
+    Processed,
+    AwaitingConfirmations,
+    AwaitingLiquidity,
+    /// Note sent, its Zcash transaction not yet confirmed
+    AwaitingNoteConfirmation,
+    /// Gave up waiting for liquidity; the sender can be refunded
+    Refundable,
+    Rejected(String),
 }
 
 impl Coordinator {
     /// Run the coordinator main loop
     /// FOCUSED: Only processes deposits/withdrawals notified by relayers
-    async fn run(self) -> Result<()> {
+    async fn run(self: Arc<Self>) -> Result<()> {
         info!("Starting coordinator main loop");
 
         let mut interval = tokio::time::interval(
@@ -192,6 +373,15 @@ impl Coordinator {
                 info!("Coordinator tick #{}", tick_count);
             }
 
+            // Nothing below can make progress without the database
+            if !self.db_health.is_healthy() {
+                warn!("Database unavailable, pausing processing until it recovers");
+                self.db_health.wait_healthy().await;
+                info!("Resuming processing");
+                // Don't burst through the ticks missed while paused
+                interval.reset();
+            }
+
             // Process pending deposits (create Zcash notes)
             // These are deposits that relayers have notified us about
             if let Err(e) = self.process_deposits().await {
@@ -204,13 +394,26 @@ impl Coordinator {
                 error!("Error processing withdrawals: {}", e);
             }
 
+            // Expire authorizations no relayer picked up
+            if let Err(e) = self.expire_stale_authorizations().await {
+                error!("Error expiring authorizations: {}", e);
+            }
+
             // Update Zcash state
             if let Err(e) = self.sync_zcash_state().await {
                 error!("Error syncing Zcash state: {}", e);
             }
 
-            // Rebalance liquidity if needed
+            // Sync pools with on-chain balances, then rebalance if needed
             if tick_count % 60 == 0 {
+                if let Err(e) = liquidity_sync::sync_liquidity(
+                    &self.liquidity_manager,
+                    &self.config.chains,
+                    &self.balances,
+                ).await {
+                    error!("Error syncing liquidity: {}", e);
+                }
+
                 if let Err(e) = self.rebalance_liquidity().await {
                     error!("Error rebalancing liquidity: {}", e);
                 }
@@ -224,116 +427,444 @@ impl Coordinator {
     }
 
     /// Process pending deposits from database (populated by relayer notifications)
-    async fn process_deposits(&self) -> Result<()> {
+    /// Each deposit runs on the worker pool; ones still in flight from an
+    /// earlier tick are skipped
+    async fn process_deposits(self: &Arc<Self>) -> Result<()> {
         let pending = self.db.get_pending_deposits().await?;
+        metrics::PENDING_DEPOSITS.set(pending.len() as i64);
         
         if !pending.is_empty() {
             info!("Processing {} pending deposits", pending.len());
         }
 
         for deposit in pending {
-            match self.handle_deposit(deposit).await {
-                Ok(_) => {
-                    info!("✓ Processed deposit: {}", deposit.deposit_id);
-                }
-                Err(e) => {
-                    warn!("Failed to process deposit {}: {}", deposit.deposit_id, e);
+            let deposit_id = deposit.deposit_id.clone();
+            let coordinator = self.clone();
+            self.workers
+                .dispatch(format!("deposit:{}", deposit_id), async move {
+                    // Pools of other coordinators on this database see it too
+                    let outcome = coordinator.db
+                        .with_deposit_lock(
+                            &deposit_id,
+                            database::DEPOSIT_LOCK_TTL,
+                            metrics::timed(
+                                &metrics::DEPOSIT_PROCESSING_SECONDS,
+                                coordinator.handle_deposit(deposit),
+                            ),
+                        )
+                        .await;
+                    let outcome = match outcome {
+                        Ok(Some(outcome)) => outcome,
+                        Ok(None) => {
+                            debug!("Deposit {} locked by another worker", deposit_id);
+                            return;
+                        }
+                        Err(e) => Err(e.into()),
+                    };
+                    match outcome {
+                        Ok(DepositOutcome::Processed) => {
+                            info!("✓ Processed deposit: {}", deposit_id);
+                        }
+                        Ok(DepositOutcome::AwaitingConfirmations) => {
+                            debug!("Deposit {} awaiting source confirmations", deposit_id);
+                        }
+                        Ok(DepositOutcome::AwaitingLiquidity) => {
+                            debug!("Deposit {} awaiting destination liquidity", deposit_id);
+                        }
+                        Ok(DepositOutcome::AwaitingNoteConfirmation) => {
+                            debug!("Deposit {} awaiting Zcash note confirmation", deposit_id);
+                        }
+                        Ok(DepositOutcome::Refundable) => {
+                            warn!("Deposit {} made refundable: no destination liquidity before timeout",
+                                deposit_id);
+                        }
+                        Ok(DepositOutcome::Rejected(reason)) => {
+                            error!("Deposit {} rejected: {}", deposit_id, reason);
+                        }
+                        Err(e) => {
+                            coordinator.record_deposit_failure(&deposit_id, &e).await;
+                        }
+                    }
+                })
+                .await;
+        }
+
+        Ok(())
+    }
+
+    /// Back off a failed deposit, dead-lettering it once out of attempts
+    async fn record_deposit_failure(&self, deposit_id: &str, err: &anyhow::Error) {
+        match self.db
+            .record_deposit_failure(deposit_id, &err.to_string(), &self.config.deposit_retry)
+            .await
+        {
+            Ok(database::DepositFailure { attempts, next_retry_at: Some(retry_at) }) => {
+                warn!("Failed to process deposit {} (attempt {}, retrying at {}): {}",
+                    deposit_id, attempts, retry_at, err);
+            }
+            Ok(database::DepositFailure { attempts, next_retry_at: None }) => {
+                error!("Deposit {} failed after {} attempts, giving up: {}",
+                    deposit_id, attempts, err);
+                if let Err(e) = self.liquidity_manager.release_hold(deposit_id).await {
+                    warn!("Failed to release liquidity held by deposit {}: {}", deposit_id, e);
                 }
             }
+            Err(db_err) => {
+                warn!("Failed to process deposit {}: {} (could not record failure: {})",
+                    deposit_id, err, db_err);
+            }
         }
+    }
 
-        Ok(())
+    /// Whether the deposit's verified source block has the chain's
+    /// configured confirmations. Without a chain head to measure it
+    /// against, the deposit isn't final.
+    async fn deposit_is_final(&self, deposit: &database::Deposit, block_number: u64) -> Result<bool> {
+        let chain = self.config.chains
+            .iter()
+            .find(|c| c.chain_id == deposit.source_chain_id)
+            .with_context(|| format!("Source chain {} not configured", deposit.source_chain_id))?;
+        
+        Ok(match self.chain_heads.latest_block(chain).await? {
+            Some(head) => chain_head::has_confirmations(head, block_number, chain.confirmations),
+            None => false,
+        })
     }
 
-    /// Handle a single deposit - create Zcash note
-    async fn handle_deposit(&self, deposit: database::Deposit) -> Result<()> {
-        info!("Handling deposit: {} ({} -> chain {})", 
-            deposit.deposit_id, deposit.amount, deposit.target_chain_id);
+    /// Check the deposit against its source-chain event, so a relayer
+    /// can't mint a note for a deposit that never happened or report a
+    /// different amount, destination or recipient. Deposits a chain can't
+    /// verify are invalid unless it accepts unverified deposits.
+    async fn verify_source_deposit(&self, deposit: &database::Deposit) -> Result<DepositVerification> {
+        let chain = self.config.chains
+            .iter()
+            .find(|c| c.chain_id == deposit.source_chain_id)
+            .with_context(|| format!("Source chain {} not configured", deposit.source_chain_id))?;
+        
+        Ok(match deposit_verifier::verify_deposit(&self.source_chains, chain, deposit).await? {
+            DepositVerification::Unverifiable if chain.accept_unverified_deposits => {
+                debug!("Deposit {} taken as reported (chain {} not verifiable)",
+                    deposit.deposit_id, deposit.source_chain_id);
+                DepositVerification::Unverifiable
+            }
+            DepositVerification::Unverifiable => DepositVerification::Invalid(format!(
+                "deposits from chain {} can't be verified",
+                deposit.source_chain_id
+            )),
+            verification => verification,
+        })
+    }
 
-        // 1. Verify liquidity on destination chain
-        let token_info = self.token_registry
-            .get_token_for_chain(deposit.target_chain_id, &deposit.token)
-            .context("Token not found in registry")?;
+    /// Dead-letter a deposit that can never be processed, handing back any
+    /// liquidity it held
+    async fn reject_deposit(&self, deposit_id: &str, reason: String) -> Result<DepositOutcome> {
+        self.liquidity_manager.release_hold(deposit_id).await?;
+        self.db.mark_deposit_invalid(deposit_id, &reason).await?;
+        self.deposit_events.publish(deposit_id, DepositStage::Rejected, Some(reason.clone()));
+        Ok(DepositOutcome::Rejected(reason))
+    }
 
-        {
-            let liquidity_manager = self.liquidity_manager.read().await;
-            liquidity_manager
-                .ensure_liquidity(
-                    deposit.target_chain_id,
-                    &token_info.address,
-                    deposit.amount,
+    /// No destination can cover the deposit. It keeps waiting without using
+    /// up retry attempts, until the refund timeout makes it refundable on
+    /// its source chain.
+    async fn await_liquidity(&self, deposit: &database::Deposit) -> Result<DepositOutcome> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        
+        if !self.config.refund_due(deposit.created_at, now) {
+            return Ok(DepositOutcome::AwaitingLiquidity);
+        }
+        
+        let signature = self.signer.refund_signature(
+            self.config.authorization_scheme(deposit.source_chain_id)?,
+            &deposit.deposit_id,
+            &deposit.sender,
+            &deposit.token,
+            deposit.amount,
+        )?;
+        self.liquidity_manager.release_hold(&deposit.deposit_id).await?;
+        self.db.mark_deposit_refundable(&deposit.deposit_id, &signature).await?;
+        self.deposit_events.publish(&deposit.deposit_id, DepositStage::Refundable, None);
+        
+        Ok(DepositOutcome::Refundable)
+    }
+
+    /// Handle a single deposit in two phases. Until the source deposit is
+    /// final, destination liquidity is only held (released again if
+    /// finality takes longer than `deposit_hold_ttl`); once final, the hold
+    /// becomes a lock and the Zcash note is created. The deposit is only
+    /// processed once the note's transaction confirms. Deposits that don't
+    /// match the source chain are dead-lettered without retries.
+    #[tracing::instrument(name = "process_deposit", skip_all, fields(deposit_id = %deposit.deposit_id))]
+    async fn handle_deposit(&self, deposit: database::Deposit) -> Result<DepositOutcome> {
+        // Note already sent on an earlier pass (or before a restart)
+        if let (Some(note_commitment), Some(zcash_txid)) = (&deposit.note_commitment, &deposit.zcash_txid) {
+            return self
+                .confirm_note(
+                    &deposit.deposit_id,
+                    note_commitment,
+                    zcash_txid,
+                    deposit.zcash_fee.unwrap_or(0),
                 )
-                .await
-                .context("Insufficient liquidity on destination chain")?;
+                .await;
         }
+        // Claimed on an earlier pass that died before the txid was recorded
+        if self.db.deposit_note_started(&deposit.deposit_id).await? {
+            return self
+                .reject_deposit(&deposit.deposit_id, NOTE_SEND_INTERRUPTED.to_string())
+                .await;
+        }
+        
+        let is_final = match self.verify_source_deposit(&deposit).await? {
+            DepositVerification::Verified { block_number } => self.deposit_is_final(&deposit, block_number).await?,
+            // Relayers on chains we can't read only report finalized events
+            DepositVerification::Unverifiable => true,
+            DepositVerification::Invalid(reason) => {
+                return self.reject_deposit(&deposit.deposit_id, reason).await;
+            }
+        };
+        
+        if is_final {
+            info!("Handling deposit: {} ({} -> chain {})", 
+                deposit.deposit_id, deposit.amount, deposit.target_chain_id);
+        }
+
+        // 1. Resolve the token on the source and each candidate destination
+        // and rescale the amount, net of the Zcash network fee. Notes carry
+        // the canonical precision; liquidity is in destination units.
+        let (candidates, note_amount, zcash_fee) = {
+            let registry = self.token_registry.read().await;
+            let canonical_id = registry
+                .get_canonical_id(deposit.source_chain_id, &deposit.token)
+                .context("Token not found in registry")?;
+            let source_token = registry
+                .get_token_by_id(canonical_id, deposit.source_chain_id)?;
+            let mappings = registry
+                .get_all_representations(canonical_id)
+                .context("Token mappings not found")?;
+            let canonical_decimals = mappings.decimals;
+            let gross_amount = Amount::from_u64(deposit.amount, source_token.decimals)
+                .rescale(canonical_decimals)
+                .and_then(|amount| amount.to_u64())
+                .context("Deposit amount not representable at canonical precision")?;
+
+            let deduction = match self.config.fees.deduct_zcash_fee(&mappings.symbol, gross_amount) {
+                Ok(deduction) => deduction,
+                Err(e) => {
+                    // Dust never gets bigger: dead-letter instead of retrying
+                    drop(registry);
+                    return self.reject_deposit(&deposit.deposit_id, e.to_string()).await;
+                }
+            };
+            let note_amount = deduction.net_amount;
+
+            let preferences = if deposit.target_chain_preferences.is_empty() {
+                vec![deposit.target_chain_id]
+            } else {
+                deposit.target_chain_preferences.clone()
+            };
+
+            let mut candidates = Vec::new();
+            for chain_id in preferences {
+                let candidate = registry
+                    .get_token_by_id(canonical_id, chain_id)
+                    .and_then(|token| {
+                        let amount = Amount::from_u64(note_amount, canonical_decimals)
+                            .rescale(token.decimals)?;
+                        // Destination pools hold u64 base units
+                        amount.to_u64()?;
+                        Ok(RouteCandidate { chain_id, token: token.address, amount })
+                    });
+                match candidate {
+                    Ok(candidate) => candidates.push(candidate),
+                    Err(e) => warn!("Skipping destination chain {}: {}", chain_id, e),
+                }
+            }
 
-        // 2. Create Zcash shielded note
+            if candidates.is_empty() {
+                anyhow::bail!("Token not available on any requested destination chain");
+            }
+            (candidates, note_amount, deduction.fee)
+        };
+
+        if zcash_fee > 0 {
+            debug!("Deducted Zcash network fee of {} from deposit {}", zcash_fee, deposit.deposit_id);
+        }
+
+        // 2. Stick with the destination already held for this deposit, or
+        // pick the first one with sufficient liquidity
+        let held = self.liquidity_manager.get_hold(&deposit.deposit_id);
+        let route = match &held {
+            Some(hold) => {
+                let decimals = self.token_registry
+                    .read()
+                    .await
+                    .get_token_for_chain(hold.chain_id, &hold.token)?
+                    .decimals;
+                RouteCandidate {
+                    chain_id: hold.chain_id,
+                    token: hold.token.clone(),
+                    amount: Amount::from_u64(hold.amount, decimals),
+                }
+            }
+            None => {
+                let route = self.liquidity_manager.select_route(&candidates).await.cloned();
+                match route {
+                    Some(route) => route,
+                    None => return self.await_liquidity(&deposit).await,
+                }
+            }
+        };
+        // Pools and holds are kept in destination base units
+        let route_amount = route.amount.to_u64()?;
+
+        if route.chain_id != deposit.target_chain_id {
+            info!("Routing deposit {} to chain {}", deposit.deposit_id, route.chain_id);
+            self.db
+                .set_deposit_target_chain(&deposit.deposit_id, route.chain_id)
+                .await?;
+        }
+
+        // 3. Not final yet: soft-hold the liquidity and wait
+        if !is_final {
+            match &held {
+                None => {
+                    self.liquidity_manager
+                        .hold_liquidity(&deposit.deposit_id, route.chain_id, &route.token, route_amount)
+                        .await?;
+                }
+                Some(hold) if hold_expired(hold, self.config.deposit_hold_ttl) => {
+                    self.liquidity_manager.release_hold(&deposit.deposit_id).await?;
+                    anyhow::bail!(
+                        "Deposit not final after {}s; released its liquidity hold",
+                        self.config.deposit_hold_ttl
+                    );
+                }
+                Some(_) => {}
+            }
+            return Ok(DepositOutcome::AwaitingConfirmations);
+        }
+
+        // 4. Lock liquidity for this deposit, converting its hold if it had
+        // one, before anything is sent
+        if self.liquidity_manager.confirm_hold(&deposit.deposit_id).await?.is_none() {
+            self.liquidity_manager
+                .lock_liquidity(
+                    route.chain_id,
+                    &route.token,
+                    route_amount,
+                )
+                .await?;
+        }
+        self.deposit_events.publish(&deposit.deposit_id, DepositStage::LiquidityLocked, None);
+
+        // 5. Create Zcash shielded note, at most once. The claim is saved
+        // before the send and the txid right after it, so a crash in between
+        // leaves a claim without a txid: the note may exist, and an operator
+        // has to check the wallet before re-minting.
+        if !self.db.begin_deposit_note(&deposit.deposit_id).await? {
+            // Claimed by a concurrent pass; give back what we just locked
+            self.liquidity_manager
+                .release_liquidity(route.chain_id, &route.token, route_amount)
+                .await?;
+            return self
+                .reject_deposit(&deposit.deposit_id, NOTE_SEND_INTERRUPTED.to_string())
+                .await;
+        }
         let (note_commitment, zcash_txid) = {
             let mut shielded_pool = self.shielded_pool.write().await;
             shielded_pool
                 .create_deposit_note(
+                    &deposit.deposit_id,
                     deposit.source_chain_id,
                     &deposit.token,
-                    deposit.amount,
+                    note_amount,
+                    zcash_fee,
                     &deposit.recipient,
                     &deposit.zcash_address,
                 )
+                .instrument(tracing::info_span!("create_note"))
                 .await
                 .context("Failed to create Zcash shielded note")?
         };
 
         info!("Created Zcash note: commitment={:?}, txid={}", 
             note_commitment, zcash_txid);
+        self.deposit_events.publish(&deposit.deposit_id, DepositStage::NoteCreated, Some(zcash_txid.clone()));
 
-        // 3. Lock liquidity for this deposit
-        {
-            let mut liquidity_manager = self.liquidity_manager.write().await;
-            liquidity_manager
-                .lock_liquidity(
-                    deposit.target_chain_id,
-                    &token_info.address,
-                    deposit.amount,
-                )
-                .await?;
+        // 6. Check whether the note has landed
+        let note_commitment = hex::encode(note_commitment);
+        self.confirm_note(&deposit.deposit_id, &note_commitment, &zcash_txid, zcash_fee).await
+    }
+
+    /// Mark the deposit processed once its note transaction has the
+    /// configured Zcash confirmations. Until then it stays pending with its
+    /// txid recorded; each pass checks once and moves on, so a slow
+    /// confirmation never holds a worker.
+    async fn confirm_note(
+        &self,
+        deposit_id: &str,
+        note_commitment: &str,
+        zcash_txid: &str,
+        zcash_fee: u64,
+    ) -> Result<DepositOutcome> {
+        let confirmed = self.zcash_client
+            .check_confirmation(zcash_txid, self.config.zcash.confirmations)
+            .instrument(tracing::info_span!("confirm_note"))
+            .await?;
+        if confirmed.is_none() {
+            return Ok(DepositOutcome::AwaitingNoteConfirmation);
         }
 
-        // 4. Update database
         self.db
-            .mark_deposit_processed(
-                &deposit.deposit_id,
-                &hex::encode(note_commitment),
-                &zcash_txid,
-            )
+            .mark_deposit_processed(deposit_id, note_commitment, zcash_txid, zcash_fee)
             .await?;
 
+        self.deposit_events.publish(deposit_id, DepositStage::Complete, Some(zcash_txid.to_string()));
         info!("✓ Deposit processed successfully");
-        Ok(())
+        Ok(DepositOutcome::Processed)
     }
 
     /// Process pending withdrawals - verify proofs and authorize
-    async fn process_withdrawals(&self) -> Result<()> {
+    /// Dispatched on the worker pool like deposits
+    async fn process_withdrawals(self: &Arc<Self>) -> Result<()> {
         let pending = self.db.get_pending_withdrawals().await?;
+        metrics::PENDING_WITHDRAWALS.set(pending.len() as i64);
         
         if !pending.is_empty() {
             info!("Processing {} pending withdrawals", pending.len());
         }
 
         for withdrawal in pending {
-            match self.handle_withdrawal(withdrawal).await {
-                Ok(_) => {
-                    info!("✓ Processed withdrawal: {}", withdrawal.withdrawal_id);
-                }
-                Err(e) => {
-                    warn!("Failed to process withdrawal {}: {}", 
-                        withdrawal.withdrawal_id, e);
-                }
-            }
+            let withdrawal_id = withdrawal.withdrawal_id.clone();
+            let coordinator = self.clone();
+            self.workers
+                .dispatch(format!("withdrawal:{}", withdrawal_id), async move {
+                    let outcome = metrics::timed(
+                        &metrics::WITHDRAWAL_PROCESSING_SECONDS,
+                        coordinator.handle_withdrawal(withdrawal),
+                    )
+                    .await;
+                    match outcome {
+                        Ok(_) => {
+                            info!("✓ Processed withdrawal: {}", withdrawal_id);
+                        }
+                        Err(e) => {
+                            warn!("Failed to process withdrawal {}: {}", 
+                                withdrawal_id, e);
+                        }
+                    }
+                })
+                .await;
         }
 
         Ok(())
     }
 
     /// Handle a single withdrawal - verify proof and authorize with signature
+    #[tracing::instrument(name = "authorize_withdrawal", skip_all, fields(withdrawal_id = %withdrawal.withdrawal_id))]
     async fn handle_withdrawal(&self, withdrawal: database::Withdrawal) -> Result<()> {
         info!("Handling withdrawal: {} (amount: {})", 
             withdrawal.withdrawal_id, withdrawal.amount);
@@ -341,13 +872,13 @@ impl Coordinator {
         // 1. Verify Zcash proof and nullifier
         let valid = {
             let shielded_pool = self.shielded_pool.read().await;
-            shielded_pool
-                .verify_withdrawal_proof(
-                    &withdrawal.nullifier,
-                    &withdrawal.zcash_proof,
-                    &withdrawal.merkle_root,
-                    withdrawal.amount,
-                )
+            let verification = shielded_pool.verify_withdrawal_proof(
+                &withdrawal.nullifier,
+                &withdrawal.zcash_proof,
+                &withdrawal.merkle_root,
+                withdrawal.amount,
+            );
+            metrics::timed(&metrics::PROOF_VERIFICATION_SECONDS, verification)
                 .await
                 .context("Proof verification failed")?
         };
@@ -360,78 +891,113 @@ impl Coordinator {
             return Ok(());
         }
 
-        // 2. Mark nullifier as spent in Zcash
-        {
-            let shielded_pool = self.shielded_pool.read().await;
-            shielded_pool
-                .mark_nullifier_spent(&withdrawal.nullifier)
-                .await?;
-        }
-
-        // 3. Get token info for destination chain
-        let token_info = self.token_registry
-            .get_token_for_chain(withdrawal.target_chain_id, &withdrawal.token)
-            .context("Token not found in registry")?;
-
-        // 4. Generate authorization signature
-        let auth_signature = self.generate_withdrawal_signature(
+        // 2. Get token info for destination chain (address was resolved from
+        // the canonical ID when the withdrawal was accepted). Note amounts are
+        // at canonical precision; pay out in destination units.
+        let (token_info, target_amount) = self.token_registry
+            .read()
+            .await
+            .resolve_payout(withdrawal.target_chain_id, &withdrawal.token, withdrawal.amount)?;
+        let target_amount = target_amount.to_u64()?;
+
+        // 3. Generate authorization signature, valid on the gateway for one
+        // TTL; past it the liquidity below goes back to the pool
+        let deadline = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + self.config.authorization_ttl;
+        let auth_signature = self.signer.withdrawal_signature(
+            self.config.authorization_scheme(withdrawal.target_chain_id)?,
             &withdrawal.withdrawal_id,
             &withdrawal.recipient,
             &token_info.address,
-            withdrawal.amount,
+            target_amount,
             &withdrawal.nullifier,
+            deadline,
         )?;
 
-        // 5. Authorize withdrawal in database with signature
-        self.db
-            .authorize_withdrawal(
-                &withdrawal.withdrawal_id,
-                &token_info.address,
-                withdrawal.amount,
-                &auth_signature,
-            )
+        // 4. Authorize withdrawal in database with signature, recording the
+        // executing relayer's share of the bridge fee, and release its locked
+        // liquidity. One transaction also marks the nullifier spent, so a
+        // crash at any point leaves either all of it or none of it.
+        let fee_split = self.config.fees.split(target_amount);
+        // Only this pool is locked, so other pools keep moving meanwhile
+        let mut pool = self.liquidity_manager
+            .lock_pool(withdrawal.target_chain_id, &token_info.address)
             .await?;
-
-        // 6. Release locked liquidity
-        {
-            let mut liquidity_manager = self.liquidity_manager.write().await;
-            liquidity_manager
-                .release_liquidity(
-                    withdrawal.target_chain_id,
-                    &token_info.address,
-                    withdrawal.amount,
+        let released = pool.released_state(target_amount);
+        
+        let chain_id = withdrawal.target_chain_id;
+        let relayer_fee = fee_split.relayer_fee;
+        let withdrawal_id = withdrawal.withdrawal_id.clone();
+        let token = token_info.address.clone();
+        let authorized = self.db
+            .with_transaction(move |conn| Box::pin(async move {
+                let authorized = Database::authorize_withdrawal_in(
+                    conn,
+                    &withdrawal_id,
+                    &token,
+                    target_amount,
+                    relayer_fee,
+                    deadline,
+                    &auth_signature,
                 )
                 .await?;
+                
+                if let (true, Some((available, locked))) = (authorized, released) {
+                    Database::update_liquidity_pool_in(conn, chain_id, &token, available, locked).await?;
+                }
+                Ok(authorized)
+            }))
+            .await?;
+
+        // Another iteration got there first and already released liquidity
+        if !authorized {
+            warn!("Withdrawal {} already authorized, skipping", withdrawal.withdrawal_id);
+            return Ok(());
+        }
+
+        // 5. Mirror the committed release and spend in memory
+        if let Some((available, locked)) = released {
+            pool.available = available;
+            pool.locked = locked;
         }
+        self.db.cache_spent_nullifier(&hex::encode(&withdrawal.nullifier));
 
+        metrics::WITHDRAWALS_AUTHORIZED.inc();
         info!("✓ Withdrawal authorized with signature - relayer can now execute");
         Ok(())
     }
 
-    /// Generate authorization signature for withdrawal
-    /// This proves the coordinator verified the proof and authorizes execution
-    fn generate_withdrawal_signature(
-        &self,
-        withdrawal_id: &str,
-        recipient: &str,
-        token: &str,
-        amount: u64,
-        nullifier: &[u8],
-    ) -> Result<Vec<u8>> {
-        use sha2::{Sha256, Digest};
+    /// Expire authorizations whose signed deadline has passed and return
+    /// their liquidity to the destination pool. The gateway refuses them
+    /// now, so nothing can draw on it; if one did execute in time, the next
+    /// liquidity sync takes the payout back out.
+    async fn expire_stale_authorizations(&self) -> Result<()> {
+        let expired = self.db.expire_authorizations().await?;
+        
+        if expired.is_empty() {
+            return Ok(());
+        }
+        
+        info!("Expired {} stale withdrawal authorizations", expired.len());
         
-        // Create message to sign
-        let mut hasher = Sha256::new();
-        hasher.update(withdrawal_id.as_bytes());
-        hasher.update(recipient.as_bytes());
-        hasher.update(token.as_bytes());
-        hasher.update(&amount.to_le_bytes());
-        hasher.update(nullifier);
-        let message_hash = hasher.finalize();
-
-        // In production, sign with coordinator's private key
-        // For now, return the hash as signature
-        Ok(message_hash.to_vec())
+        for withdrawal in expired {
+            if let Err(e) = self.liquidity_manager
+                .restore_liquidity(
+                    withdrawal.target_chain_id,
+                    &withdrawal.token,
+                    withdrawal.amount,
+                )
+                .await
+            {
+                warn!("Failed to release liquidity for expired withdrawal {}: {}",
+                    withdrawal.withdrawal_id, e);
+            }
+        }
+        
+        Ok(())
     }
 
     /// Sync Zcash blockchain state
@@ -453,19 +1019,15 @@ impl Coordinator {
     async fn rebalance_liquidity(&self) -> Result<()> {
         info!("Checking liquidity rebalancing...");
         
-        let liquidity_manager = self.liquidity_manager.read().await;
-        let rebalance_needed = liquidity_manager
+        let rebalance_needed = self.liquidity_manager
             .check_rebalancing_needed()
             .await?;
 
         if !rebalance_needed.is_empty() {
             info!("Rebalancing needed for {} pools", rebalance_needed.len());
             
-            drop(liquidity_manager);
-            let mut liquidity_manager = self.liquidity_manager.write().await;
-            
             for (chain_id, token) in rebalance_needed {
-                if let Err(e) = liquidity_manager
+                if let Err(e) = self.liquidity_manager
                     .trigger_rebalance(chain_id, &token)
                     .await
                 {
@@ -488,27 +1050,3 @@ impl Coordinator {
         }
     }
 }
-
-fn init_tracing(verbose: bool) -> Result<()> {
-    let log_level = if verbose {
-        tracing::Level::DEBUG
-    } else {
-        tracing::Level::INFO
-    };
-
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| {
-                    format!(
-                        "zcash_coordinator={},tower_http=debug,sqlx=warn",
-                        log_level
-                    )
-                    .into()
-                }),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
-
-    Ok(())
-}
\ No newline at end of file
@@ -0,0 +1,153 @@
+// zcash-coordinator/src/metrics.rs
+//! Prometheus metrics for coordinator internals
+//!
+//! Served on their own port so scrapers never queue behind relayer traffic
+//! on the RPC server.
+
+use anyhow::Result;
+use axum::{http::StatusCode, routing::get, Router};
+use lazy_static::lazy_static;
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, HistogramVec, IntCounter, IntGauge, Registry, TextEncoder,
+};
+use std::future::Future;
+use tracing::info;
+
+lazy_static! {
+    pub static ref PENDING_DEPOSITS: IntGauge = IntGauge::new(
+        "coordinator_pending_deposits",
+        "Deposits stored and awaiting processing"
+    )
+    .unwrap();
+    pub static ref PENDING_WITHDRAWALS: IntGauge = IntGauge::new(
+        "coordinator_pending_withdrawals",
+        "Withdrawals stored and awaiting authorization"
+    )
+    .unwrap();
+    pub static ref DEPOSIT_QUEUE_DEPTH: IntGauge = IntGauge::new(
+        "coordinator_deposit_queue_depth",
+        "Deposit notifications accepted but not yet written to the database"
+    )
+    .unwrap();
+    pub static ref DEPOSIT_PROCESSING_SECONDS: Histogram = Histogram::with_opts(HistogramOpts::new(
+        "coordinator_deposit_processing_seconds",
+        "Time spent on one pass over a pending deposit"
+    ))
+    .unwrap();
+    pub static ref WITHDRAWAL_PROCESSING_SECONDS: Histogram = Histogram::with_opts(HistogramOpts::new(
+        "coordinator_withdrawal_processing_seconds",
+        "Time spent on one pass over a pending withdrawal"
+    ))
+    .unwrap();
+    pub static ref PROOF_VERIFICATION_SECONDS: Histogram = Histogram::with_opts(HistogramOpts::new(
+        "coordinator_proof_verification_seconds",
+        "Time spent verifying a withdrawal's Zcash proof"
+    ))
+    .unwrap();
+    pub static ref ZCASH_RPC_SECONDS: HistogramVec = HistogramVec::new(
+        HistogramOpts::new(
+            "coordinator_zcash_rpc_seconds",
+            "Zcash node RPC call duration, failed calls included"
+        ),
+        &["method"]
+    )
+    .unwrap();
+    pub static ref WITHDRAWALS_AUTHORIZED: IntCounter = IntCounter::new(
+        "coordinator_withdrawals_authorized_total",
+        "Withdrawals authorized for relayers to execute"
+    )
+    .unwrap();
+    static ref REGISTRY: Registry = {
+        let registry = Registry::new();
+        registry.register(Box::new(PENDING_DEPOSITS.clone())).unwrap();
+        registry.register(Box::new(PENDING_WITHDRAWALS.clone())).unwrap();
+        registry.register(Box::new(DEPOSIT_QUEUE_DEPTH.clone())).unwrap();
+        registry.register(Box::new(DEPOSIT_PROCESSING_SECONDS.clone())).unwrap();
+        registry.register(Box::new(WITHDRAWAL_PROCESSING_SECONDS.clone())).unwrap();
+        registry.register(Box::new(PROOF_VERIFICATION_SECONDS.clone())).unwrap();
+        registry.register(Box::new(ZCASH_RPC_SECONDS.clone())).unwrap();
+        registry.register(Box::new(WITHDRAWALS_AUTHORIZED.clone())).unwrap();
+        registry
+    };
+}
+
+/// Run `work`, recording how long it took in `histogram`
+pub async fn timed<F: Future>(histogram: &Histogram, work: F) -> F::Output {
+    let timer = histogram.start_timer();
+    let output = work.await;
+    timer.observe_duration();
+    output
+}
+
+/// Serve `/metrics` (Prometheus)
+pub async fn start_server(port: u16) -> Result<()> {
+    let addr = format!("0.0.0.0:{}", port);
+    let listener = tokio::net::TcpListener::bind(&addr).await?;
+
+    info!("Metrics server listening on {}", addr);
+
+    axum::serve(listener, router()).await?;
+
+    Ok(())
+}
+
+fn router() -> Router {
+    Router::new().route("/metrics", get(metrics_handler))
+}
+
+async fn metrics_handler() -> Result<String, StatusCode> {
+    let mut buffer = Vec::new();
+    TextEncoder::new()
+        .encode(&REGISTRY.gather(), &mut buffer)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    String::from_utf8(buffer).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zcash_client::ZcashClient;
+    use axum::body::Body;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_metrics_after_processing() {
+        // The mock node isn't running, so the call fails but is still timed
+        let zcash_client = ZcashClient::mock();
+        assert!(zcash_client.get_blockchain_info().await.is_err());
+
+        // One deposit pass and one authorized withdrawal, as the processing
+        // loop records them
+        PENDING_DEPOSITS.set(1);
+        timed(&DEPOSIT_PROCESSING_SECONDS, async {}).await;
+        PENDING_WITHDRAWALS.set(1);
+        timed(&WITHDRAWAL_PROCESSING_SECONDS, async {
+            timed(&PROOF_VERIFICATION_SECONDS, async {}).await;
+            WITHDRAWALS_AUTHORIZED.inc();
+        })
+        .await;
+
+        let request = axum::http::Request::builder()
+            .uri("/metrics")
+            .body(Body::empty())
+            .unwrap();
+        let response = router().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        for series in [
+            "coordinator_pending_deposits 1",
+            "coordinator_pending_withdrawals 1",
+            "coordinator_deposit_queue_depth",
+            "coordinator_deposit_processing_seconds_count",
+            "coordinator_withdrawal_processing_seconds_count",
+            "coordinator_proof_verification_seconds_count",
+            "coordinator_zcash_rpc_seconds_count{method=\"getblockchaininfo\"}",
+            "coordinator_withdrawals_authorized_total",
+        ] {
+            assert!(body.contains(series), "missing {} in:\n{}", series, body);
+        }
+    }
+}
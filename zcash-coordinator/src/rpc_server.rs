@@ -3,66 +3,114 @@
 //! Relayers communicate with coordinator via this API
 
 use axum::{
-    extract::Path,
-    routing::{get, post},
+    error_handling::HandleErrorLayer,
+    extract::{ws::{Message, WebSocket, WebSocketUpgrade}, Path},
+    response::Response,
+    routing::{delete, get, post},
+    BoxError,
     Router,
     Json,
-    http::StatusCode,
+    http::{header, HeaderMap, StatusCode},
 };
 use serde::{Deserialize, Serialize};
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
+use subtle::ConstantTimeEq;
+use tokio::net::TcpListener;
 use tokio::sync::RwLock;
-use tracing::{info, warn};
+use tower::{timeout::{error::Elapsed, TimeoutLayer}, ServiceBuilder};
+use tracing::{debug, info, warn, Span};
 
-use crate::database::{Database, Deposit, Withdrawal};
+use crate::config::ChainConfig;
+use crate::database::{Database, DbError, Deposit, Withdrawal};
+use crate::db_health::DbHealth;
+use crate::deposit_queue::{DepositQueue, Enqueued};
+use crate::deposit_events::{DepositEvents, DepositStage, DepositUpdate};
 use crate::shielded_pool::ShieldedPoolManager;
-use crate::token_registry::TokenRegistry;
-use crate::liquidity_manager::LiquidityManager;
+use crate::signing::CoordinatorSigner;
+use crate::telemetry;
+use crate::token_registry::{same_address, CanonicalTokenId, TokenMappings, TokenRegistry};
+use crate::liquidity_manager::{LiquidityManager, LiquidityPool};
+use crate::liquidity_sync::{self, EvmBalanceClient, GatewayBalanceSource, PoolSync};
+use crate::wire::{Negotiated, Wire};
+
+pub use zerobridge_types::{
+    AuthorizedWithdrawal, CoordinatorPubkey, DepositNotification, RefundAuthorization, WithdrawalNotification,
+};
+
+/// Longest a request waits to read a busy liquidity pool before giving up with 503
+const LOCK_TIMEOUT: Duration = Duration::from_secs(2);
 
 pub struct RpcServer {
     port: u16,
+    authorization_ttl: u64,
+    request_timeout: Duration,
+    admin_token: Option<String>,
+    simulation_signer: Option<Arc<CoordinatorSigner>>,
+    signer: Arc<CoordinatorSigner>,
+    key_rotation_overlap: u64,
     db: Database,
     shielded_pool: Arc<RwLock<ShieldedPoolManager>>,
-    token_registry: Arc<TokenRegistry>,
-    liquidity_manager: Arc<RwLock<LiquidityManager>>,
+    token_registry: Arc<RwLock<TokenRegistry>>,
+    liquidity_manager: Arc<LiquidityManager>,
+    chains: Vec<ChainConfig>,
+    deposit_events: DepositEvents,
+    db_health: DbHealth,
+    deposit_queue: DepositQueue,
 }
 
+/// RPC capabilities advertised to relayers via `GET /version`
+pub const CAPABILITIES: &[&str] = &[
+    "deposits.notify",
+    "deposits.status",
+    "withdrawals.notify",
+    "withdrawals.authorized",
+    "withdrawals.reauthorize",
+    "liquidity.check",
+    "liquidity.token",
+    "tokens",
+    "deposits.ws",
+    "encoding.bincode",
+    "coordinator.pubkey",
+];
+
 // ============ Request/Response Types ============
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct DepositNotification {
-    pub deposit_id: String,
-    pub source_chain_id: u64,
-    pub target_chain_id: u64,
-    pub sender: String,
-    pub token: String,
-    pub amount: u64,
-    pub recipient: Vec<u8>,
-    pub zcash_address: Vec<u8>,
-    pub timestamp: u64,
+pub struct DisableRepresentationRequest {
+    pub chain_id: u64,
+    pub address: String,
 }
 
+/// Withdrawal to sign without a proof, for `POST /simulate/withdrawal`
 #[derive(Debug, Serialize, Deserialize)]
-pub struct WithdrawalNotification {
+pub struct SimulateWithdrawalRequest {
     pub withdrawal_id: String,
     pub target_chain_id: u64,
     pub recipient: String,
+    /// Token address on the target chain
     pub token: String,
     pub amount: u64,
+    #[serde(with = "zerobridge_types::base64_bytes")]
     pub nullifier: Vec<u8>,
-    pub zcash_proof: Vec<u8>,
-    pub merkle_root: Vec<u8>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct AuthorizedWithdrawal {
-    pub withdrawal_id: String,
-    pub target_chain_id: u64,
-    pub recipient: String,
+pub struct ForceUnlockRequest {
+    pub chain_id: u64,
     pub token: String,
     pub amount: u64,
-    pub nullifier: Vec<u8>,
-    pub authorization_signature: Vec<u8>,
+    /// Recorded in the liquidity audit log
+    pub reason: String,
+}
+
+/// New coordinator signing key for `POST /admin/signing-key/rotate`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RotateSigningKeyRequest {
+    /// Hex private key, or an `env:`/`file:` reference resolved on the
+    /// coordinator host
+    pub signing_key: String,
 }
 
 #[derive(Serialize)]
@@ -70,20 +118,60 @@ struct StatusResponse {
     status: String,
 }
 
-#[derive(Serialize)]
+#[derive(Debug, Serialize)]
 struct DepositStatusResponse {
     deposit_id: String,
     processed: bool,
     zcash_txid: Option<String>,
     note_commitment: Option<String>,
+    /// Zcash network fee deducted from the note, in canonical token units
+    zcash_fee: Option<u64>,
 }
 
-#[derive(Serialize)]
+#[derive(Debug, Serialize)]
+struct FailedDepositResponse {
+    deposit_id: String,
+    source_chain_id: u64,
+    target_chain_id: u64,
+    amount: u64,
+    attempts: u32,
+    last_error: String,
+}
+
+#[derive(Debug, Serialize)]
+struct LiquidityHoldResponse {
+    deposit_id: String,
+    chain_id: u64,
+    token: String,
+    amount: u64,
+    created_at: i64,
+}
+
+#[derive(Debug, Serialize)]
 struct LiquidityCheckResponse {
+    /// Whether a pool existed for this chain/token before the check
+    exists: bool,
     available: bool,
     current_liquidity: u64,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TokenLiquidityResponse {
+    pub canonical_id: CanonicalTokenId,
+    pub symbol: String,
+    pub chains: Vec<ChainLiquidity>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChainLiquidity {
+    pub chain_id: u64,
+    pub chain_name: String,
+    pub token_address: String,
+    pub available: u64,
+    pub locked: u64,
+    pub target: u64,
+}
+
 #[derive(Deserialize)]
 struct LiquidityCheckRequest {
     chain_id: u64,
@@ -98,6 +186,12 @@ struct HealthResponse {
     zcash_synced: bool,
 }
 
+#[derive(Serialize)]
+struct VersionResponse {
+    version: String,
+    capabilities: Vec<String>,
+}
+
 #[derive(Serialize)]
 struct StatsResponse {
     total_deposits: u64,
@@ -110,68 +204,208 @@ struct StatsResponse {
 
 #[derive(Clone)]
 struct AppState {
+    authorization_ttl: u64,
+    admin_token: Option<String>,
+    /// Signs simulated withdrawals; set only when simulation is enabled
+    #[cfg_attr(not(feature = "simulation"), allow(dead_code))]
+    simulation_signer: Option<Arc<CoordinatorSigner>>,
+    signer: Arc<CoordinatorSigner>,
+    key_rotation_overlap: u64,
     db: Database,
     shielded_pool: Arc<RwLock<ShieldedPoolManager>>,
-    token_registry: Arc<TokenRegistry>,
-    liquidity_manager: Arc<RwLock<LiquidityManager>>,
+    token_registry: Arc<RwLock<TokenRegistry>>,
+    liquidity_manager: Arc<LiquidityManager>,
+    chains: Arc<Vec<ChainConfig>>,
+    balances: Arc<dyn GatewayBalanceSource>,
+    deposit_events: DepositEvents,
+    db_health: DbHealth,
+    deposit_queue: DepositQueue,
 }
 
 impl RpcServer {
     pub fn new(
         port: u16,
+        authorization_ttl: u64,
+        request_timeout: Duration,
+        admin_token: Option<String>,
+        simulation_signer: Option<Arc<CoordinatorSigner>>,
+        signer: Arc<CoordinatorSigner>,
+        key_rotation_overlap: u64,
         db: Database,
         shielded_pool: Arc<RwLock<ShieldedPoolManager>>,
-        token_registry: Arc<TokenRegistry>,
-        liquidity_manager: Arc<RwLock<LiquidityManager>>,
+        token_registry: Arc<RwLock<TokenRegistry>>,
+        liquidity_manager: Arc<LiquidityManager>,
+        chains: Vec<ChainConfig>,
+        deposit_events: DepositEvents,
+        db_health: DbHealth,
+        deposit_queue: DepositQueue,
     ) -> Self {
         Self {
             port,
+            authorization_ttl,
+            request_timeout,
+            admin_token,
+            simulation_signer,
+            signer,
+            key_rotation_overlap,
             db,
             shielded_pool,
             token_registry,
             liquidity_manager,
+            chains,
+            deposit_events,
+            db_health,
+            deposit_queue,
         }
     }
     
-    pub async fn start(self) -> anyhow::Result<()> {
+    /// Serve until `shutdown` resolves, then finish in-flight requests
+    pub async fn start<F>(self, shutdown: F) -> anyhow::Result<()>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
         let state = AppState {
+            authorization_ttl: self.authorization_ttl,
+            admin_token: self.admin_token,
+            simulation_signer: self.simulation_signer,
+            signer: self.signer,
+            key_rotation_overlap: self.key_rotation_overlap,
             db: self.db,
             shielded_pool: self.shielded_pool,
             token_registry: self.token_registry,
             liquidity_manager: self.liquidity_manager,
+            chains: Arc::new(self.chains),
+            balances: Arc::new(EvmBalanceClient::new()),
+            deposit_events: self.deposit_events,
+            db_health: self.db_health,
+            deposit_queue: self.deposit_queue,
         };
         
         let app = Router::new()
             // Health & status
             .route("/health", get(health_handler))
+            .route("/version", get(version_handler))
             .route("/stats", get(stats_handler))
+            .route("/coordinator/pubkey", get(coordinator_pubkey_handler))
+            
+            // Token catalog for frontends
+            .route("/tokens", get(tokens_handler))
             
             // Deposit endpoints (relayers notify us)
             .route("/deposits/notify", post(notify_deposit_handler))
             .route("/deposits/:id/status", get(deposit_status_handler))
+            .route("/deposits/refundable", get(refundable_deposits_handler))
+            .route("/ws/deposits/:id", get(deposit_updates_handler))
             
             // Withdrawal endpoints
             .route("/withdrawals/notify", post(notify_withdrawal_handler))
             .route("/withdrawals/authorized", get(authorized_withdrawals_handler))
+            .route("/withdrawals/:id/reauthorize", post(reauthorize_withdrawal_handler)) // admin
             
             // Liquidity endpoints
             .route("/liquidity/check", post(check_liquidity_handler))
+            .route("/liquidity/:token", get(token_liquidity_handler))
+            .route("/liquidity/sync", post(sync_liquidity_handler))
+            .route("/liquidity/holds", get(liquidity_holds_handler))
             
-            .with_state(state);
+            // Admin endpoints (require `admin_token`)
+            .route("/admin/tokens/:canonical_id", delete(remove_token_handler))
+            .route("/admin/tokens/disable", post(disable_token_representation_handler))
+            .route("/admin/deposits/failed", get(failed_deposits_handler))
+            .route("/admin/liquidity/unlock", post(force_unlock_liquidity_handler))
+            .route("/admin/signing-key/rotate", post(rotate_signing_key_handler));
+        
+        // Integration testing only (`simulation` feature + `enable_simulation`)
+        #[cfg(feature = "simulation")]
+        let app = app.route("/simulate/withdrawal", post(simulate_withdrawal_handler));
+        
+        let app = app
+            .with_state(state)
+            .layer(axum::middleware::from_fn(telemetry::continue_trace));
+        let app = with_request_timeout(app, self.request_timeout);
         
         let addr = format!("0.0.0.0:{}", self.port);
-        let listener = tokio::net::TcpListener::bind(&addr).await?;
+        let listener = TcpListener::bind(&addr).await?;
         
         info!("RPC server listening on {}", addr);
         
-        axum::serve(listener, app).await?;
-        
-        Ok(())
+        serve(listener, app, shutdown).await
+    }
+}
+
+/// Answer requests still running after `timeout` with 504 rather than hold
+/// the connection
+fn with_request_timeout(app: Router, timeout: Duration) -> Router {
+    app.layer(
+        ServiceBuilder::new()
+            .layer(HandleErrorLayer::new(timeout_error_status))
+            .layer(TimeoutLayer::new(timeout)),
+    )
+}
+
+async fn timeout_error_status(err: BoxError) -> StatusCode {
+    if err.is::<Elapsed>() {
+        warn!("Request timed out");
+        StatusCode::GATEWAY_TIMEOUT
+    } else {
+        warn!("Request failed: {}", err);
+        StatusCode::INTERNAL_SERVER_ERROR
     }
 }
 
+/// Serve `app` on `listener`. Once `shutdown` resolves the listener is closed
+/// and this returns after every in-flight request has completed.
+async fn serve<F>(listener: TcpListener, app: Router, shutdown: F) -> anyhow::Result<()>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown)
+        .await?;
+    
+    info!("RPC server stopped");
+    Ok(())
+}
+
 // ============ Handlers ============
 
+/// Endpoints that write refuse with 503 while the database probe is failing,
+/// so relayers retry later instead of losing notifications
+fn require_db(health: &DbHealth) -> Result<(), StatusCode> {
+    if health.is_healthy() {
+        Ok(())
+    } else {
+        Err(StatusCode::SERVICE_UNAVAILABLE)
+    }
+}
+
+/// HTTP status for a database failure: missing rows are 404, key collisions
+/// 409, an unreachable database 503, anything else 500
+fn db_error_status(err: DbError) -> StatusCode {
+    match err {
+        DbError::NotFound(_) => StatusCode::NOT_FOUND,
+        DbError::Conflict(_) => StatusCode::CONFLICT,
+        DbError::Connection(_) => {
+            warn!("Database unavailable: {}", err);
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+        DbError::Query(_) | DbError::Serialization(_) => {
+            warn!("Database error: {}", err);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// Pool snapshot for a request, or 503 if a write has held the pool past
+/// `LOCK_TIMEOUT`. Reads only: a write abandoned midway could leave memory
+/// behind what it already committed.
+async fn read_pools<F: Future>(read: F) -> Result<F::Output, StatusCode> {
+    tokio::time::timeout(LOCK_TIMEOUT, read).await.map_err(|_| {
+        warn!("Timed out waiting for liquidity pool");
+        StatusCode::SERVICE_UNAVAILABLE
+    })
+}
+
 async fn health_handler() -> Json<HealthResponse> {
     Json(HealthResponse {
         status: "ok".to_string(),
@@ -180,11 +414,48 @@ async fn health_handler() -> Json<HealthResponse> {
     })
 }
 
+/// Relayers call this on startup to check RPC compatibility
+async fn version_handler() -> Json<VersionResponse> {
+    Json(VersionResponse {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        capabilities: CAPABILITIES.iter().map(|c| c.to_string()).collect(),
+    })
+}
+
+/// Key authorizations are signed with, and the keys used before it, so
+/// relayers can verify signatures before executing
+async fn coordinator_pubkey_handler(
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> Result<Json<CoordinatorPubkey>, StatusCode> {
+    coordinator_pubkey(&state.db, &state.signer).await.map(Json)
+}
+
+async fn coordinator_pubkey(db: &Database, signer: &CoordinatorSigner) -> Result<CoordinatorPubkey, StatusCode> {
+    let history = db.signing_keys().await.map_err(db_error_status)?;
+    Ok(CoordinatorPubkey {
+        public_key: signer.public_key(),
+        address: signer.address(),
+        history,
+    })
+}
+
+/// Every supported token with its per-chain representations
+async fn tokens_handler(
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> Json<Vec<TokenMappings>> {
+    let registry = state.token_registry.read().await;
+    Json(token_catalog(&registry))
+}
+
+fn token_catalog(registry: &TokenRegistry) -> Vec<TokenMappings> {
+    registry.get_all_tokens().into_iter().cloned().collect()
+}
+
 async fn stats_handler(
     axum::extract::State(state): axum::extract::State<AppState>,
 ) -> Result<Json<StatsResponse>, StatusCode> {
     let stats = state.db.get_stats().await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .map_err(db_error_status)?;
     
     Ok(Json(StatsResponse {
         total_deposits: stats.total_deposits,
@@ -198,13 +469,57 @@ async fn stats_handler(
 /// Coordinator will create the Zcash shielded note
 async fn notify_deposit_handler(
     axum::extract::State(state): axum::extract::State<AppState>,
-    Json(notification): Json<DepositNotification>,
-) -> Result<Json<StatusResponse>, StatusCode> {
+    headers: HeaderMap,
+    Wire(notification): Wire<DepositNotification>,
+) -> Result<Negotiated<StatusResponse>, StatusCode> {
+    require_db(&state.db_health)?;
+    Span::current().record("deposit_id", notification.deposit_id.as_str());
     info!("Received deposit notification from relayer: {}", notification.deposit_id);
     
-    // Store in database for processing
-    let deposit = Deposit {
-        deposit_id: notification.deposit_id.clone(),
+    if notification.tx_hash.trim().is_empty() {
+        warn!("Rejected deposit {}: no source transaction", notification.deposit_id);
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    }
+    
+    // Several relayers watch the same chains; later notifications are expected
+    let known = state.db.get_deposit(&notification.deposit_id).await
+        .map_err(db_error_status)?
+        .is_some();
+    if known {
+        debug!("Deposit already known: {}", notification.deposit_id);
+        return Ok(Negotiated::new(&headers, StatusResponse {
+            status: "duplicate".to_string(),
+        }));
+    }
+    
+    // Written to the database by the deposit writer
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    let deposit = deposit_from_notification(notification, now);
+    
+    let deposit_id = deposit.deposit_id.clone();
+    let status = match state.deposit_queue.enqueue(deposit) {
+        Enqueued::Queued => "queued",
+        Enqueued::Duplicate => "duplicate",
+        Enqueued::Full => {
+            warn!("Deposit queue full, deferring {}", deposit_id);
+            return Err(StatusCode::TOO_MANY_REQUESTS);
+        }
+    };
+    
+    Ok(Negotiated::new(&headers, StatusResponse {
+        status: status.to_string(),
+    }))
+}
+
+/// Deposit record for a notification received at `received_at`. The refund
+/// timeout runs from then: the relayer's own `timestamp` could be back-dated
+/// to make a deposit refundable at once.
+fn deposit_from_notification(notification: DepositNotification, received_at: i64) -> Deposit {
+    Deposit {
+        deposit_id: notification.deposit_id,
         source_chain_id: notification.source_chain_id,
         target_chain_id: notification.target_chain_id,
         sender: notification.sender,
@@ -215,56 +530,127 @@ async fn notify_deposit_handler(
         processed: false,
         zcash_txid: None,
         note_commitment: None,
-        created_at: notification.timestamp as i64,
-    };
-    
-    state.db.store_deposit(&deposit).await
-        .map_err(|e| {
-            warn!("Failed to store deposit: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
-    
-    info!("Deposit queued for processing: {}", notification.deposit_id);
-    
-    Ok(Json(StatusResponse {
-        status: "queued".to_string(),
-    }))
+        created_at: received_at,
+        target_chain_preferences: notification.target_chain_preferences,
+        block_number: notification.block_number,
+        tx_hash: Some(notification.tx_hash),
+        zcash_fee: None,
+    }
 }
 
 async fn deposit_status_handler(
     axum::extract::State(state): axum::extract::State<AppState>,
     Path(deposit_id): Path<String>,
 ) -> Result<Json<DepositStatusResponse>, StatusCode> {
-    let deposits = state.db.get_pending_deposits().await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    deposit_status(&state.db, &deposit_id).await.map(Json)
+}
+
+/// Push a deposit's stage transitions over a WebSocket until it completes,
+/// is rejected or becomes refundable. A deposit that's already processed
+/// gets its final update straight away.
+async fn deposit_updates_handler(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    Path(deposit_id): Path<String>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    ws.on_upgrade(move |socket| stream_deposit_updates(socket, state, deposit_id))
+}
+
+async fn stream_deposit_updates(mut socket: WebSocket, state: AppState, deposit_id: String) {
+    // Subscribe before checking the database so no transition falls between
+    let mut updates = state.deposit_events.subscribe(&deposit_id);
     
-    let deposit = deposits.iter().find(|d| d.deposit_id == deposit_id);
+    if let Ok(deposit) = state.db.get_deposit_by_id(&deposit_id).await {
+        if deposit.processed {
+            let update = json_update(&deposit_id, DepositStage::Complete, deposit.zcash_txid);
+            let _ = socket.send(Message::Text(update)).await;
+            let _ = socket.close().await;
+            return;
+        }
+    }
     
-    match deposit {
-        Some(d) => Ok(Json(DepositStatusResponse {
-            deposit_id: d.deposit_id.clone(),
-            processed: d.processed,
-            zcash_txid: d.zcash_txid.clone(),
-            note_commitment: d.note_commitment.clone(),
-        })),
-        None => Err(StatusCode::NOT_FOUND),
+    loop {
+        tokio::select! {
+            update = updates.recv() => {
+                let update = match update {
+                    Ok(update) => update,
+                    // Slow client: skip what it missed, the next stage is still useful
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                };
+                let done = update.stage.is_final();
+                let text = serde_json::to_string(&update).unwrap_or_default();
+                if socket.send(Message::Text(text)).await.is_err() || done {
+                    break;
+                }
+            }
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
     }
+    let _ = socket.close().await;
 }
 
-/// Relayer notifies coordinator about a withdrawal request
-/// Coordinator will verify the proof and authorize
-async fn notify_withdrawal_handler(
+fn json_update(deposit_id: &str, stage: DepositStage, detail: Option<String>) -> String {
+    serde_json::to_string(&DepositUpdate {
+        deposit_id: deposit_id.to_string(),
+        stage,
+        timestamp: chrono::Utc::now().timestamp(),
+        detail,
+    })
+    .unwrap_or_default()
+}
+
+/// Deposits that timed out waiting for liquidity, with their refund
+/// authorizations
+async fn refundable_deposits_handler(
     axum::extract::State(state): axum::extract::State<AppState>,
-    Json(notification): Json<WithdrawalNotification>,
-) -> Result<Json<StatusResponse>, StatusCode> {
-    info!("Received withdrawal notification from relayer: {}", notification.withdrawal_id);
+) -> Result<Json<Vec<RefundAuthorization>>, StatusCode> {
+    let refundable = state.db.get_refundable_deposits().await
+        .map_err(db_error_status)?;
     
-    // Store in database for verification
-    let withdrawal = Withdrawal {
-        withdrawal_id: notification.withdrawal_id.clone(),
+    Ok(Json(refundable.into_iter().map(|d| RefundAuthorization {
+        deposit_id: d.deposit_id,
+        source_chain_id: d.source_chain_id,
+        sender: d.sender,
+        token: d.token,
+        amount: d.amount,
+        authorization_signature: d.refund_signature,
+    }).collect()))
+}
+
+/// Status of a deposit in any state, pending or processed
+async fn deposit_status(db: &Database, deposit_id: &str) -> Result<DepositStatusResponse, StatusCode> {
+    let deposit = db.get_deposit_by_id(deposit_id).await
+        .map_err(db_error_status)?;
+    
+    Ok(DepositStatusResponse {
+        deposit_id: deposit.deposit_id,
+        processed: deposit.processed,
+        zcash_txid: deposit.zcash_txid,
+        note_commitment: deposit.note_commitment,
+        zcash_fee: deposit.zcash_fee,
+    })
+}
+
+/// Build a withdrawal record, resolving the destination token address from
+/// the registry. Fails if the token has no representation on the target chain.
+fn withdrawal_from_notification(
+    registry: &TokenRegistry,
+    notification: WithdrawalNotification,
+) -> anyhow::Result<Withdrawal> {
+    let token = registry.get_token_by_id(
+        &notification.canonical_token_id,
+        notification.target_chain_id,
+    )?;
+    
+    Ok(Withdrawal {
+        withdrawal_id: notification.withdrawal_id,
         target_chain_id: notification.target_chain_id,
         recipient: notification.recipient,
-        token: notification.token,
+        token: token.address,
         amount: notification.amount,
         nullifier: notification.nullifier,
         zcash_proof: notification.zcash_proof,
@@ -272,17 +658,40 @@ async fn notify_withdrawal_handler(
         authorized: false,
         auth_signature: None,
         created_at: chrono::Utc::now().timestamp(),
-    };
+        authorized_at: None,
+        expired: false,
+        relayer_fee: 0,
+        auth_deadline: None,
+    })
+}
+
+/// Relayer notifies coordinator about a withdrawal request
+/// Coordinator will verify the proof and authorize
+async fn notify_withdrawal_handler(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: HeaderMap,
+    Wire(notification): Wire<WithdrawalNotification>,
+) -> Result<Negotiated<StatusResponse>, StatusCode> {
+    require_db(&state.db_health)?;
+    Span::current().record("withdrawal_id", notification.withdrawal_id.as_str());
+    info!("Received withdrawal notification from relayer: {}", notification.withdrawal_id);
     
-    state.db.store_withdrawal(&withdrawal).await
+    let withdrawal_id = notification.withdrawal_id.clone();
+    let registry = state.token_registry.read().await;
+    let withdrawal = withdrawal_from_notification(&registry, notification)
         .map_err(|e| {
-            warn!("Failed to store withdrawal: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
+            warn!("Rejected withdrawal {}: {}", withdrawal_id, e);
+            StatusCode::UNPROCESSABLE_ENTITY
         })?;
+    drop(registry);
+    
+    // Store in database for verification
+    state.db.store_withdrawal(&withdrawal).await
+        .map_err(db_error_status)?;
     
-    info!("Withdrawal queued for verification: {}", notification.withdrawal_id);
+    info!("Withdrawal queued for verification: {}", withdrawal_id);
     
-    Ok(Json(StatusResponse {
+    Ok(Negotiated::new(&headers, StatusResponse {
         status: "queued".to_string(),
     }))
 }
@@ -291,9 +700,10 @@ async fn notify_withdrawal_handler(
 /// Coordinator has already verified proofs and signed authorization
 async fn authorized_withdrawals_handler(
     axum::extract::State(state): axum::extract::State<AppState>,
-) -> Result<Json<Vec<AuthorizedWithdrawal>>, StatusCode> {
+    headers: HeaderMap,
+) -> Result<Negotiated<Vec<AuthorizedWithdrawal>>, StatusCode> {
     let authorized = state.db.get_authorized_withdrawals().await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .map_err(db_error_status)?;
     
     let results: Vec<AuthorizedWithdrawal> = authorized
         .into_iter()
@@ -306,6 +716,9 @@ async fn authorized_withdrawals_handler(
                 amount: w.amount,
                 nullifier: w.nullifier.clone(),
                 authorization_signature: sig,
+                timestamp: w.authorized_at.unwrap_or(w.created_at) as u64,
+                relayer_fee: w.relayer_fee,
+                deadline: w.auth_deadline.unwrap_or_default(),
             })
         })
         .collect();
@@ -314,37 +727,888 @@ async fn authorized_withdrawals_handler(
         info!("Returning {} authorized withdrawals to relayer", results.len());
     }
     
-    Ok(Json(results))
+    Ok(Negotiated::new(&headers, results))
+}
+
+/// Re-offer an expired authorization to relayers, signed with a fresh
+/// deadline. Liquidity released on expiry is reserved again before it is
+/// re-offered. Admin only: anyone else could keep a stale authorization
+/// alive indefinitely.
+async fn reauthorize_withdrawal_handler(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: HeaderMap,
+    Path(withdrawal_id): Path<String>,
+) -> Result<Json<StatusResponse>, StatusCode> {
+    require_admin(&state, &headers)?;
+    require_db(&state.db_health)?;
+    Span::current().record("withdrawal_id", withdrawal_id.as_str());
+    
+    let withdrawal = state.db.get_withdrawal(&withdrawal_id).await
+        .map_err(db_error_status)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    
+    if !withdrawal.expired {
+        return Err(StatusCode::CONFLICT);
+    }
+    
+    // The stored token and amount are the destination values the first
+    // signature covered; only the deadline changes
+    let deadline = chrono::Utc::now().timestamp() as u64 + state.authorization_ttl;
+    let auth_signature = crate::config::authorization_scheme(&state.chains, withdrawal.target_chain_id)
+        .and_then(|scheme| {
+            state.signer.withdrawal_signature(
+                scheme,
+                &withdrawal.withdrawal_id,
+                &withdrawal.recipient,
+                &withdrawal.token,
+                withdrawal.amount,
+                &withdrawal.nullifier,
+                deadline,
+            )
+        })
+        .map_err(|e| {
+            warn!("Cannot sign reauthorization for {}: {:#}", withdrawal_id, e);
+            StatusCode::UNPROCESSABLE_ENTITY
+        })?;
+    
+    state.liquidity_manager
+        .reserve_liquidity(withdrawal.target_chain_id, &withdrawal.token, withdrawal.amount)
+        .await
+        .map_err(|e| {
+            warn!("Cannot reauthorize withdrawal {}: {}", withdrawal_id, e);
+            StatusCode::CONFLICT
+        })?;
+    
+    let reauthorized = state.db.reauthorize_withdrawal(&withdrawal_id, deadline, &auth_signature).await;
+    
+    if !matches!(reauthorized, Ok(true)) {
+        // Lost a race with another reauthorization, or failed to store the
+        // new signature; give the reservation back
+        let _ = state.liquidity_manager
+            .restore_liquidity(withdrawal.target_chain_id, &withdrawal.token, withdrawal.amount)
+            .await;
+    }
+    if !reauthorized.map_err(db_error_status)? {
+        return Err(StatusCode::CONFLICT);
+    }
+    
+    info!("Withdrawal reauthorized: {}", withdrawal_id);
+    
+    Ok(Json(StatusResponse {
+        status: "reauthorized".to_string(),
+    }))
 }
 
 async fn check_liquidity_handler(
     axum::extract::State(state): axum::extract::State<AppState>,
     Json(request): Json<LiquidityCheckRequest>,
 ) -> Result<Json<LiquidityCheckResponse>, StatusCode> {
-    let liquidity_manager = state.liquidity_manager.read().await;
-    
-    match liquidity_manager.get_pool(request.chain_id, &request.token) {
-        Some(pool) => {
-            let available = pool.available >= request.amount;
-            Ok(Json(LiquidityCheckResponse {
-                available,
-                current_liquidity: pool.available,
-            }))
-        }
-        None => Ok(Json(LiquidityCheckResponse {
-            available: false,
-            current_liquidity: 0,
-        })),
+    let registry = state.token_registry.read().await;
+    
+    check_liquidity(&registry, &state.liquidity_manager, &request)
+        .await
+        .map(Json)
+}
+
+/// Unknown tokens are a 404; a registered token without a pool reports
+/// `exists: false`, so misconfiguration is distinguishable from an
+/// under-funded pool. Read-only: pools are created by deposits and admins,
+/// never by this unauthenticated query.
+async fn check_liquidity(
+    registry: &TokenRegistry,
+    liquidity_manager: &LiquidityManager,
+    request: &LiquidityCheckRequest,
+) -> Result<LiquidityCheckResponse, StatusCode> {
+    if !registry.is_supported(request.chain_id, &request.token) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    
+    if let Some(pool) = read_pools(liquidity_manager.get_pool(request.chain_id, &request.token)).await? {
+        return Ok(LiquidityCheckResponse {
+            exists: true,
+            available: pool.available >= request.amount,
+            current_liquidity: pool.available,
+        });
     }
+    
+    Ok(LiquidityCheckResponse {
+        exists: false,
+        available: false,
+        current_liquidity: 0,
+    })
+}
+
+/// Liquidity held for deposits still awaiting source confirmations, oldest first
+async fn liquidity_holds_handler(
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> Result<Json<Vec<LiquidityHoldResponse>>, StatusCode> {
+    let mut holds: Vec<LiquidityHoldResponse> = state.liquidity_manager
+        .get_holds()
+        .into_iter()
+        .map(|h| LiquidityHoldResponse {
+            deposit_id: h.deposit_id,
+            chain_id: h.chain_id,
+            token: h.token,
+            amount: h.amount,
+            created_at: h.created_at,
+        })
+        .collect();
+    holds.sort_by_key(|h| h.created_at);
+    
+    Ok(Json(holds))
+}
+
+/// Per-chain liquidity for a token, addressed by canonical ID or symbol
+async fn token_liquidity_handler(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    Path(token): Path<String>,
+) -> Result<Json<TokenLiquidityResponse>, StatusCode> {
+    let registry = state.token_registry.read().await;
+    let pools = read_pools(state.liquidity_manager.get_all_pools()).await?;
+    
+    token_liquidity(&registry, &pools, &token)
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// Collect the pools backing each registered representation of `token`
+fn token_liquidity(
+    registry: &TokenRegistry,
+    pools: &[LiquidityPool],
+    token: &str,
+) -> Option<TokenLiquidityResponse> {
+    let canonical_id = CanonicalTokenId(token.to_string());
+    let mappings = registry
+        .get_all_representations(&canonical_id)
+        .or_else(|| {
+            registry
+                .canonical_id_for_symbol(token)
+                .and_then(|id| registry.get_all_representations(id))
+        })?;
+    
+    let mut chains: Vec<ChainLiquidity> = mappings
+        .representations
+        .iter()
+        .filter_map(|repr| {
+            pools
+                .iter()
+                .find(|p| p.chain_id == repr.chain_id && same_address(&p.token, &repr.address))
+                .map(|pool| ChainLiquidity {
+                    chain_id: repr.chain_id,
+                    chain_name: repr.chain_name.clone(),
+                    token_address: repr.address.clone(),
+                    available: pool.available,
+                    locked: pool.locked,
+                    target: pool.target,
+                })
+        })
+        .collect();
+    chains.sort_by_key(|c| c.chain_id);
+    
+    Some(TokenLiquidityResponse {
+        canonical_id: mappings.canonical_id.clone(),
+        symbol: mappings.symbol.clone(),
+        chains,
+    })
+}
+
+// ============ Simulation ============
+
+/// Sign a withdrawal over the given inputs without verifying a proof, so
+/// integration tests can drive relayers and gateways without a Zcash node.
+/// Admin-only, and signed with the simulation key rather than the
+/// coordinator's. Nothing is stored and no liquidity is reserved.
+#[cfg(feature = "simulation")]
+async fn simulate_withdrawal_handler(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<SimulateWithdrawalRequest>,
+) -> Result<Json<AuthorizedWithdrawal>, StatusCode> {
+    require_admin(&state, &headers)?;
+    Span::current().record("withdrawal_id", request.withdrawal_id.as_str());
+    
+    let authorized = simulate_withdrawal(
+        state.simulation_signer.as_deref(),
+        &state.chains,
+        state.authorization_ttl,
+        request,
+    )?;
+    warn!("Simulated withdrawal authorization: {}", authorized.withdrawal_id);
+    
+    Ok(Json(authorized))
+}
+
+#[cfg(feature = "simulation")]
+fn simulate_withdrawal(
+    simulation_signer: Option<&CoordinatorSigner>,
+    chains: &[ChainConfig],
+    authorization_ttl: u64,
+    request: SimulateWithdrawalRequest,
+) -> Result<AuthorizedWithdrawal, StatusCode> {
+    let signer = simulation_signer.ok_or(StatusCode::FORBIDDEN)?;
+    let deadline = chrono::Utc::now().timestamp() as u64 + authorization_ttl;
+    
+    // Signed for the target gateway, so fields it can't encode are the
+    // caller's mistake
+    let authorization_signature = crate::config::authorization_scheme(chains, request.target_chain_id)
+        .and_then(|scheme| {
+            signer.withdrawal_signature(
+                scheme,
+                &request.withdrawal_id,
+                &request.recipient,
+                &request.token,
+                request.amount,
+                &request.nullifier,
+                deadline,
+            )
+        })
+        .map_err(|e| {
+            warn!("{:#}", e);
+            StatusCode::UNPROCESSABLE_ENTITY
+        })?;
+    
+    Ok(AuthorizedWithdrawal {
+        withdrawal_id: request.withdrawal_id,
+        target_chain_id: request.target_chain_id,
+        recipient: request.recipient,
+        token: request.token,
+        amount: request.amount,
+        nullifier: request.nullifier,
+        authorization_signature,
+        timestamp: chrono::Utc::now().timestamp() as u64,
+        relayer_fee: 0,
+        deadline,
+    })
+}
+
+// ============ Admin Handlers ============
+
+/// Admin endpoints are disabled unless `admin_token` is configured, and then
+/// require it as a bearer token
+fn require_admin(state: &AppState, headers: &HeaderMap) -> Result<(), StatusCode> {
+    let expected = state.admin_token.as_deref().ok_or(StatusCode::FORBIDDEN)?;
+    
+    let provided = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    
+    // Constant-time, so response timing doesn't leak a matching prefix
+    match provided {
+        Some(token) if bool::from(token.as_bytes().ct_eq(expected.as_bytes())) => Ok(()),
+        _ => Err(StatusCode::UNAUTHORIZED),
+    }
+}
+
+/// Retire a token on every chain
+async fn remove_token_handler(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: HeaderMap,
+    Path(canonical_id): Path<String>,
+) -> Result<Json<StatusResponse>, StatusCode> {
+    require_admin(&state, &headers)?;
+    require_db(&state.db_health)?;
+    
+    let canonical_id = CanonicalTokenId(canonical_id);
+    let mut registry = state.token_registry.write().await;
+    
+    if registry.get_all_representations(&canonical_id).is_none() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    
+    // Persist first so a restart doesn't resurrect the token
+    state.db.store_token_override(&canonical_id.0, None).await
+        .map_err(db_error_status)?;
+    
+    registry.remove_token(&canonical_id)
+        .map_err(|_| StatusCode::NOT_FOUND)?;
+    
+    Ok(Json(StatusResponse {
+        status: "removed".to_string(),
+    }))
+}
+
+/// Disable a token's representation on a single chain
+async fn disable_token_representation_handler(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<DisableRepresentationRequest>,
+) -> Result<Json<StatusResponse>, StatusCode> {
+    require_admin(&state, &headers)?;
+    require_db(&state.db_health)?;
+    
+    let mut registry = state.token_registry.write().await;
+    
+    let canonical_id = registry
+        .get_canonical_id(request.chain_id, &request.address)
+        .cloned()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    
+    state.db
+        .store_token_override(&canonical_id.0, Some((request.chain_id, &request.address)))
+        .await
+        .map_err(db_error_status)?;
+    
+    registry.disable_representation(request.chain_id, &request.address)
+        .map_err(|_| StatusCode::NOT_FOUND)?;
+    
+    Ok(Json(StatusResponse {
+        status: "disabled".to_string(),
+    }))
+}
+
+/// Reconcile every pool with its gateway's on-chain balance now, rather
+/// than waiting for the periodic sync
+async fn sync_liquidity_handler(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<PoolSync>>, StatusCode> {
+    require_admin(&state, &headers)?;
+    require_db(&state.db_health)?;
+    
+    liquidity_sync::sync_liquidity(&state.liquidity_manager, &state.chains, state.balances.as_ref())
+        .await
+        .map(Json)
+        .map_err(|e| {
+            warn!("Liquidity sync failed: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+/// Move stuck locked liquidity back to available
+async fn force_unlock_liquidity_handler(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<ForceUnlockRequest>,
+) -> Result<Json<StatusResponse>, StatusCode> {
+    require_admin(&state, &headers)?;
+    require_db(&state.db_health)?;
+    
+    if request.reason.trim().is_empty() {
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    }
+    
+    if read_pools(state.liquidity_manager.get_pool(request.chain_id, &request.token)).await?.is_none() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    
+    state.liquidity_manager
+        .force_unlock_liquidity(request.chain_id, &request.token, request.amount, &request.reason)
+        .await
+        .map_err(|e| {
+            warn!("Rejected force unlock on chain {} token {}: {}", request.chain_id, request.token, e);
+            StatusCode::CONFLICT
+        })?;
+    
+    Ok(Json(StatusResponse {
+        status: "unlocked".to_string(),
+    }))
+}
+
+/// Sign new authorizations with another key. The old key's signatures stay
+/// valid for `key_rotation_overlap`, so authorizations already handed out
+/// can still be executed.
+async fn rotate_signing_key_handler(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<RotateSigningKeyRequest>,
+) -> Result<Json<CoordinatorPubkey>, StatusCode> {
+    require_admin(&state, &headers)?;
+    require_db(&state.db_health)?;
+    
+    rotate_signing_key(&state.db, &state.signer, state.key_rotation_overlap, &request.signing_key)
+        .await
+        .map(Json)
+}
+
+async fn rotate_signing_key(
+    db: &Database,
+    signer: &CoordinatorSigner,
+    overlap_secs: u64,
+    signing_key: &str,
+) -> Result<CoordinatorPubkey, StatusCode> {
+    let next = crate::config::resolve_secret(signing_key)
+        .and_then(|key| CoordinatorSigner::from_hex(&key))
+        .map_err(|e| {
+            warn!("Rejected signing key rotation: {:#}", e);
+            StatusCode::UNPROCESSABLE_ENTITY
+        })?;
+    
+    // Recorded before it signs anything, so relayers can always verify
+    if !db.record_signing_key(&next.public_key(), &next.address(), overlap_secs).await.map_err(db_error_status)? {
+        return Err(StatusCode::CONFLICT);
+    }
+    signer.rotate_to(next);
+    warn!(
+        "Signing key rotated to {}; the previous key stays valid for {}s. Update signing_key in the config before restarting.",
+        signer.address(), overlap_secs
+    );
+    
+    coordinator_pubkey(db, signer).await
+}
+
+/// Deposits that ran out of processing attempts, with their last error
+async fn failed_deposits_handler(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<FailedDepositResponse>>, StatusCode> {
+    require_admin(&state, &headers)?;
+    
+    let failed = state.db.get_failed_deposits().await
+        .map_err(db_error_status)?;
+    
+    Ok(Json(failed.into_iter().map(|d| FailedDepositResponse {
+        deposit_id: d.deposit_id,
+        source_chain_id: d.source_chain_id,
+        target_chain_id: d.target_chain_id,
+        amount: d.amount,
+        attempts: d.attempts,
+        last_error: d.last_error,
+    }).collect()))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::{DatabaseConfig, LiquidityConfig};
 
     #[tokio::test]
     async fn test_health_endpoint() {
         let response = health_handler().await;
         assert_eq!(response.status, "ok");
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_writes_refused_while_database_unhealthy() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(&dir.path().join("coordinator.db"), &DatabaseConfig::default())
+            .await
+            .unwrap();
+        let health = DbHealth::new();
+        assert!(health.probe(&db).await);
+        assert_eq!(require_db(&health), Ok(()));
+
+        db.pool().close().await;
+        assert!(!health.probe(&db).await);
+        assert_eq!(require_db(&health), Err(StatusCode::SERVICE_UNAVAILABLE));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_finishes_in_flight_requests() {
+        use tokio::sync::oneshot;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let (entered, handler_entered) = oneshot::channel::<()>();
+        let entered = Arc::new(std::sync::Mutex::new(Some(entered)));
+        let app = Router::new().route("/slow", get(move || async move {
+            if let Some(entered) = entered.lock().unwrap().take() {
+                let _ = entered.send(());
+            }
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            "done"
+        }));
+
+        let (stop, stopped) = oneshot::channel::<()>();
+        let server = tokio::spawn(serve(listener, app, async {
+            let _ = stopped.await;
+        }));
+
+        let request = tokio::spawn(reqwest::get(format!("http://{}/slow", addr)));
+        handler_entered.await.unwrap();
+        stop.send(()).unwrap();
+
+        // The listener closes right away...
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(tokio::net::TcpStream::connect(addr).await.is_err());
+        assert!(!request.is_finished());
+
+        // ...while the request already being served completes
+        let response = request.await.unwrap().unwrap();
+        assert!(response.status().is_success());
+        assert_eq!(response.text().await.unwrap(), "done");
+        server.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_version_endpoint() {
+        let response = version_handler().await;
+        assert_eq!(response.version, env!("CARGO_PKG_VERSION"));
+        assert!(response.capabilities.iter().any(|c| c == "withdrawals.authorized"));
+    }
+
+    fn test_registry() -> TokenRegistry {
+        TokenRegistry::from_toml(r#"
+[[tokens]]
+symbol = "USDC"
+name = "USD Coin"
+decimals = 6
+
+[[tokens.representations]]
+chain_id = 1
+chain_name = "Ethereum"
+address = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"
+
+[[tokens.representations]]
+chain_id = 8453
+chain_name = "Base"
+address = "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"
+"#).unwrap()
+    }
+
+    #[test]
+    fn test_token_catalog_lists_all_tokens() {
+        let registry = TokenRegistry::from_toml(r#"
+[[tokens]]
+symbol = "USDC"
+name = "USD Coin"
+decimals = 6
+
+[[tokens.representations]]
+chain_id = 1
+chain_name = "Ethereum"
+address = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"
+
+[[tokens.representations]]
+chain_id = 8453
+chain_name = "Base"
+address = "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"
+
+[[tokens]]
+symbol = "ETH"
+name = "Ethereum"
+decimals = 18
+
+[[tokens.representations]]
+chain_id = 1
+chain_name = "Ethereum"
+address = "0x0000000000000000000000000000000000000000"
+native = true
+"#).unwrap();
+
+        let catalog = token_catalog(&registry);
+        assert_eq!(catalog.len(), registry.token_count());
+
+        let json = serde_json::to_value(&catalog).unwrap();
+        assert_eq!(json[0]["symbol"], "ETH");
+        assert_eq!(json[0]["representations"][0]["native"], true);
+        assert_eq!(json[1]["symbol"], "USDC");
+        assert_eq!(json[1]["decimals"], 6);
+        let chains: Vec<u64> = json[1]["representations"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["chain_id"].as_u64().unwrap())
+            .collect();
+        assert_eq!(chains, vec![1, 8453]);
+        assert_eq!(json[1]["canonical_id"], registry.canonical_id_for_symbol("USDC").unwrap().0);
+    }
+
+    fn test_notification(registry: &TokenRegistry, target_chain_id: u64) -> WithdrawalNotification {
+        WithdrawalNotification {
+            withdrawal_id: "w1".to_string(),
+            target_chain_id,
+            recipient: "0xrecipient".to_string(),
+            canonical_token_id: registry.canonical_id_for_symbol("USDC").unwrap().clone(),
+            amount: 1_000_000,
+            nullifier: vec![1u8; 32],
+            zcash_proof: vec![],
+            merkle_root: vec![0u8; 32],
+        }
+    }
+
+    #[test]
+    fn test_withdrawal_resolves_target_chain_token() {
+        let registry = test_registry();
+        let notification = test_notification(&registry, 8453);
+
+        let withdrawal = withdrawal_from_notification(&registry, notification).unwrap();
+        assert_eq!(withdrawal.token, "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913");
+        assert_eq!(withdrawal.target_chain_id, 8453);
+    }
+
+    #[test]
+    fn test_withdrawal_rejects_token_absent_on_target_chain() {
+        let registry = test_registry();
+        // USDC has no representation on chain 137
+        let notification = test_notification(&registry, 137);
+
+        assert!(withdrawal_from_notification(&registry, notification).is_err());
+    }
+
+    fn test_pool(chain_id: u64, token: &str, available: u64, locked: u64) -> LiquidityPool {
+        LiquidityPool {
+            chain_id,
+            token: token.to_string(),
+            available,
+            locked,
+            target: 1_000,
+            last_rebalance: 0,
+        }
+    }
+
+    #[test]
+    fn test_token_liquidity_aggregates_pools() {
+        let registry = test_registry();
+        let pools = vec![
+            test_pool(8453, "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913", 500, 100),
+            // Pool keys may differ in address case from the registry
+            test_pool(1, "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48", 2_000, 0),
+            // Unrelated token
+            test_pool(1, "0x0000000000000000000000000000000000000000", 9_999, 0),
+        ];
+
+        let response = token_liquidity(&registry, &pools, "USDC").unwrap();
+        assert_eq!(response.symbol, "USDC");
+        assert_eq!(response.chains.len(), 2);
+        assert_eq!(response.chains[0].chain_id, 1);
+        assert_eq!(response.chains[0].available, 2_000);
+        assert_eq!(response.chains[1].chain_id, 8453);
+        assert_eq!(response.chains[1].available, 500);
+        assert_eq!(response.chains[1].locked, 100);
+
+        // Addressable by canonical ID too
+        let by_id = token_liquidity(&registry, &pools, &response.canonical_id.0).unwrap();
+        assert_eq!(by_id.chains.len(), 2);
+
+        assert!(token_liquidity(&registry, &pools, "DAI").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_check_liquidity_distinguishes_missing_pool() {
+        let registry = test_registry();
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(&dir.path().join("coordinator.db"), &DatabaseConfig::default())
+            .await
+            .unwrap();
+        let manager = LiquidityManager::new(db, LiquidityConfig::mock()).await.unwrap();
+        let request = |chain_id: u64, token: &str| LiquidityCheckRequest {
+            chain_id,
+            token: token.to_string(),
+            amount: 1_000,
+        };
+        let usdc_base = "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913";
+
+        // Unknown token
+        let err = check_liquidity(&registry, &manager, &request(8453, "0xunknown")).await.unwrap_err();
+        assert_eq!(err, StatusCode::NOT_FOUND);
+
+        // Known token, no pool yet: reported, not created
+        let response = check_liquidity(&registry, &manager, &request(8453, usdc_base)).await.unwrap();
+        assert!(!response.exists);
+        assert!(!response.available);
+        assert!(manager.get_pool(8453, usdc_base).await.is_none());
+
+        // Known pool, under-funded
+        manager.add_liquidity(8453, usdc_base, 500).await.unwrap();
+        let response = check_liquidity(&registry, &manager, &request(8453, usdc_base)).await.unwrap();
+        assert!(response.exists);
+        assert!(!response.available);
+        assert_eq!(response.current_liquidity, 500);
+
+        // A writer holding the pool doesn't stall the request forever, and
+        // other pools answer meanwhile
+        let held = manager.lock_pool(8453, usdc_base).await.unwrap();
+        let err = check_liquidity(&registry, &manager, &request(8453, usdc_base)).await.unwrap_err();
+        assert_eq!(err, StatusCode::SERVICE_UNAVAILABLE);
+        let usdc_ethereum = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+        assert!(check_liquidity(&registry, &manager, &request(1, usdc_ethereum)).await.is_ok());
+        drop(held);
+        assert!(check_liquidity(&registry, &manager, &request(8453, usdc_base)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_slow_requests_time_out() {
+        use axum::body::Body;
+        use tower::ServiceExt;
+
+        let app = Router::new()
+            .route("/slow", get(|| async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                "done"
+            }))
+            .route("/fast", get(|| async { "done" }));
+        let app = with_request_timeout(app, Duration::from_millis(50));
+
+        let request = |uri: &str| axum::http::Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+        let started = std::time::Instant::now();
+        let response = app.clone().oneshot(request("/slow")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+        assert!(started.elapsed() < Duration::from_secs(1));
+
+        let response = app.oneshot(request("/fast")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    fn test_deposit(id: &str) -> Deposit {
+        Deposit {
+            deposit_id: id.to_string(),
+            source_chain_id: 1,
+            target_chain_id: 8453,
+            sender: "0xsender".to_string(),
+            recipient: vec![1u8; 32],
+            token: "0xtoken".to_string(),
+            amount: 1_000,
+            zcash_address: vec![2u8; 43],
+            processed: false,
+            zcash_txid: None,
+            note_commitment: None,
+            created_at: 0,
+            target_chain_preferences: vec![],
+            block_number: None,
+            tx_hash: None,
+            zcash_fee: None,
+        }
+    }
+
+    #[test]
+    fn test_deposit_created_at_is_receive_time() {
+        let notification = DepositNotification {
+            deposit_id: "d1".to_string(),
+            source_chain_id: 1,
+            target_chain_id: 8453,
+            sender: "0xsender".to_string(),
+            token: "0xtoken".to_string(),
+            amount: 1_000,
+            recipient: vec![1u8; 32],
+            zcash_address: vec![2u8; 43],
+            // Back-dated past any refund timeout
+            timestamp: 0,
+            target_chain_preferences: vec![],
+            block_number: Some(100),
+            tx_hash: "0xtx".to_string(),
+        };
+        
+        let deposit = deposit_from_notification(notification, 1_700_000_000);
+        assert_eq!(deposit.created_at, 1_700_000_000);
+        assert_eq!(deposit.tx_hash.as_deref(), Some("0xtx"));
+    }
+
+    #[tokio::test]
+    async fn test_deposit_status_covers_processed_deposits() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(&dir.path().join("coordinator.db"), &DatabaseConfig::default())
+            .await
+            .unwrap();
+        db.store_deposit(&test_deposit("d1")).await.unwrap();
+        db.mark_deposit_processed("d1", "cm1", "txid1", 0).await.unwrap();
+
+        let status = deposit_status(&db, "d1").await.unwrap();
+        assert!(status.processed);
+        assert_eq!(status.zcash_txid.as_deref(), Some("txid1"));
+
+        assert_eq!(deposit_status(&db, "unknown").await.unwrap_err(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_signing_key_rotation_keeps_old_key_valid() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(&dir.path().join("coordinator.db"), &DatabaseConfig::default())
+            .await
+            .unwrap();
+        let signer = CoordinatorSigner::test();
+        db.record_signing_key(&signer.public_key(), &signer.address(), 3600).await.unwrap();
+        let old_address = signer.address();
+
+        assert_eq!(
+            rotate_signing_key(&db, &signer, 3600, "not-a-key").await.unwrap_err(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+        // Rotating to the current key is a no-op
+        assert_eq!(
+            rotate_signing_key(&db, &signer, 3600, crate::signing::TEST_SIGNING_KEY).await.unwrap_err(),
+            StatusCode::CONFLICT
+        );
+
+        let pubkey = rotate_signing_key(&db, &signer, 3600, &"01".repeat(32)).await.unwrap();
+        assert_ne!(pubkey.address, old_address);
+        assert_eq!(signer.address(), pubkey.address);
+
+        // Both keys listed; the old one valid for the overlap window
+        assert_eq!(pubkey.history.len(), 2);
+        let old = &pubkey.history[0];
+        assert_eq!(old.address, old_address);
+        assert_eq!(old.valid_until, Some(old.retired_at.unwrap() + 3600));
+        assert_eq!(pubkey.history[1].address, pubkey.address);
+        assert_eq!(pubkey.history[1].valid_until, None);
+    }
+
+    #[test]
+    fn test_db_error_status_codes() {
+        assert_eq!(db_error_status(DbError::NotFound("x".into())), StatusCode::NOT_FOUND);
+        assert_eq!(db_error_status(DbError::Conflict("x".into())), StatusCode::CONFLICT);
+        assert_eq!(
+            db_error_status(DbError::Connection(sqlx::Error::PoolTimedOut)),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(
+            db_error_status(DbError::Query(sqlx::Error::ColumnNotFound("x".into()))),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+
+    #[cfg(feature = "simulation")]
+    #[test]
+    fn test_simulated_withdrawal() {
+        let chains = [ChainConfig {
+            chain_id: 8453,
+            name: "Base".to_string(),
+            chain_type: crate::config::ChainType::Base,
+            rpc_url: "http://localhost:8545".to_string(),
+            ws_url: None,
+            gateway_address: "0x0000000000000000000000000000000000000001".to_string(),
+            start_block: 0,
+            enabled: true,
+            confirmations: 5,
+            accept_unverified_deposits: false,
+        }];
+        let request = || SimulateWithdrawalRequest {
+            withdrawal_id: "51".repeat(32),
+            target_chain_id: 8453,
+            recipient: "0x00000000000000000000000000000000000000bb".to_string(),
+            token: "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913".to_string(),
+            amount: 1_000_000,
+            nullifier: vec![7u8; 32],
+        };
+
+        // Refused unless `enable_simulation` is set
+        assert_eq!(simulate_withdrawal(None, &chains, 3600, request()).unwrap_err(), StatusCode::FORBIDDEN);
+
+        // Signed with the simulation key, never the coordinator's
+        let signer = CoordinatorSigner::from_hex(crate::signing::TEST_SIMULATION_KEY).unwrap();
+        assert_ne!(signer.address(), CoordinatorSigner::test().address());
+        let authorized = simulate_withdrawal(Some(&signer), &chains, 3600, request()).unwrap();
+        let expected = request();
+        assert_eq!(authorized.withdrawal_id, expected.withdrawal_id);
+        assert_eq!(authorized.relayer_fee, 0);
+        assert!(authorized.deadline > authorized.timestamp);
+        assert_eq!(
+            authorized.authorization_signature,
+            signer
+                .withdrawal_signature(
+                    zerobridge_types::AuthorizationScheme::Evm { chain_id: 8453 },
+                    &expected.withdrawal_id,
+                    &expected.recipient,
+                    &expected.token,
+                    expected.amount,
+                    &expected.nullifier,
+                    authorized.deadline,
+                )
+                .unwrap()
+        );
+
+        // Only for configured chains, and fields their gateway can encode
+        assert_eq!(
+            simulate_withdrawal(Some(&signer), &[], 3600, request()).unwrap_err(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+        let mut bad_id = request();
+        bad_id.withdrawal_id = "sim-1".to_string();
+        assert_eq!(
+            simulate_withdrawal(Some(&signer), &chains, 3600, bad_id).unwrap_err(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+    }
+}
@@ -77,12 +77,16 @@ impl ShieldedPoolManager {
         })
     }
     
-    /// Create deposit note using official orchard library
+    /// Create deposit note using official orchard library. The txid is
+    /// recorded against the deposit as soon as the send returns.
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_deposit_note(
         &mut self,
+        deposit_id: &str,
         source_chain_id: u64,
         token: &str,
         amount: u64,
+        zcash_fee: u64,
         recipient: &[u8],
         zcash_address_bytes: &[u8],
     ) -> Result<(Vec<u8>, String)> {
@@ -116,6 +120,9 @@ impl ShieldedPoolManager {
         let txid = self.zcash_client
             .send_shielded_with_note(note, memo)
             .await?;
+        self.db
+            .mark_deposit_note_sent(deposit_id, &hex::encode(commitment), &txid, zcash_fee)
+            .await?;
         
         // Insert commitment into official incrementalmerkletree
         self.commitment_tree
@@ -214,11 +221,6 @@ impl ShieldedPoolManager {
         Ok(root_bytes == root)
     }
     
-    /// Mark nullifier as spent
-    pub async fn mark_nullifier_spent(&self, nullifier: &[u8]) -> Result<()> {
-        self.db.mark_nullifier_spent(&hex::encode(nullifier)).await
-    }
-    
     /// Check if nullifier spent
     pub async fn is_nullifier_spent(&self, nullifier: &[u8]) -> Result<bool> {
         self.db.is_nullifier_spent(&hex::encode(nullifier)).await
@@ -0,0 +1,218 @@
+// zcash-coordinator/src/signing.rs
+//! Coordinator authorizations relayers submit to the gateways
+
+use anyhow::{Context, Result};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::H256;
+use std::sync::RwLock;
+use zerobridge_types::{refund_authorization_hash, withdrawal_authorization_hash, AuthorizationScheme};
+
+/// The coordinator's secp256k1 signing key. Signatures are 65 bytes
+/// (`r || s || v`) over the authorization hash, recoverable to the key's
+/// Ethereum address. The key can be rotated while running.
+#[derive(Debug)]
+pub struct CoordinatorSigner {
+    wallet: RwLock<LocalWallet>,
+}
+
+impl CoordinatorSigner {
+    /// Signer for a hex-encoded 32-byte private key (`0x` prefix optional)
+    pub fn from_hex(private_key: &str) -> Result<Self> {
+        let wallet = private_key
+            .trim_start_matches("0x")
+            .parse::<LocalWallet>()
+            .context("Invalid coordinator signing key: expected a 32-byte hex secp256k1 private key")?;
+        Ok(Self { wallet: RwLock::new(wallet) })
+    }
+
+    /// Compressed SEC1 public key, hex-encoded
+    pub fn public_key(&self) -> String {
+        let wallet = self.wallet.read().unwrap();
+        let point = wallet.signer().verifying_key().to_encoded_point(true);
+        hex::encode(point.as_bytes())
+    }
+
+    /// Ethereum address of the public key, `0x`-prefixed lowercase hex
+    pub fn address(&self) -> String {
+        format!("{:?}", self.wallet.read().unwrap().address())
+    }
+
+    /// Sign everything from now on with `next`'s key
+    pub fn rotate_to(&self, next: CoordinatorSigner) {
+        *self.wallet.write().unwrap() = next.wallet.into_inner().unwrap();
+    }
+
+    /// Authorization signature for a withdrawal. This proves the coordinator
+    /// verified the proof and authorizes execution until `deadline` (unix
+    /// seconds).
+    #[allow(clippy::too_many_arguments)]
+    pub fn withdrawal_signature(
+        &self,
+        scheme: AuthorizationScheme,
+        withdrawal_id: &str,
+        recipient: &str,
+        token: &str,
+        amount: u64,
+        nullifier: &[u8],
+        deadline: u64,
+    ) -> Result<Vec<u8>> {
+        self.sign(
+            scheme,
+            withdrawal_authorization_hash(scheme, withdrawal_id, recipient, token, amount, nullifier, deadline)?,
+        )
+    }
+
+    /// Authorization for the source gateway to return a deposit to its
+    /// sender. Domain-separated from withdrawal authorizations.
+    pub fn refund_signature(
+        &self,
+        scheme: AuthorizationScheme,
+        deposit_id: &str,
+        sender: &str,
+        token: &str,
+        amount: u64,
+    ) -> Result<Vec<u8>> {
+        self.sign(scheme, refund_authorization_hash(scheme, deposit_id, sender, token, amount)?)
+    }
+
+    fn sign(&self, scheme: AuthorizationScheme, message_hash: [u8; 32]) -> Result<Vec<u8>> {
+        let signature = self
+            .wallet
+            .read()
+            .unwrap()
+            .sign_hash(H256::from(message_hash))
+            .context("Failed to sign authorization")?;
+        let mut signature = signature.to_vec();
+        // `secp256k1_recover`, `env::ecrecover` and `secp256k1_recover_pubkey`
+        // take the bare recovery id
+        if matches!(
+            scheme,
+            AuthorizationScheme::Solana | AuthorizationScheme::Near | AuthorizationScheme::Osmosis
+        ) {
+            signature[64] -= 27;
+        }
+        Ok(signature)
+    }
+
+    /// Fixed well-known key for tests
+    #[cfg(test)]
+    pub fn test() -> Self {
+        Self::from_hex(TEST_SIGNING_KEY).unwrap()
+    }
+}
+
+#[cfg(test)]
+pub const TEST_SIGNING_KEY: &str = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+/// Test key for simulated withdrawals, distinct from `TEST_SIGNING_KEY`
+#[cfg(test)]
+pub const TEST_SIMULATION_KEY: &str = "646f1ce2fdad0e6deeeb5c7e8e5543bdde65e86029e2fd9fc169899c440a7913";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::{RecoveryMessage, Signature};
+
+    const EVM: AuthorizationScheme = AuthorizationScheme::Evm { chain_id: 8453 };
+    const RECIPIENT: &str = "0x00000000000000000000000000000000000000aa";
+    const TOKEN: &str = "0x833589fcd6edb6e08f4c7c32d4f71b54bda02913";
+    const DEADLINE: u64 = 1_700_003_600;
+
+    fn withdrawal_id() -> String {
+        "22".repeat(32)
+    }
+
+    fn sign_withdrawal(signer: &CoordinatorSigner, scheme: AuthorizationScheme) -> Vec<u8> {
+        signer
+            .withdrawal_signature(scheme, &withdrawal_id(), RECIPIENT, TOKEN, 1_000, &[7u8; 32], DEADLINE)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_signatures_recover_to_coordinator_address() {
+        let signer = CoordinatorSigner::test();
+        assert_eq!(signer.address(), "0x2c7536e3605d9c16a7a3d7b1898e529396a65c23");
+        assert_eq!(signer.public_key().len(), 66);
+        assert_eq!(CoordinatorSigner::from_hex(&format!("0x{}", TEST_SIGNING_KEY)).unwrap().address(), signer.address());
+
+        let signature = sign_withdrawal(&signer, EVM);
+        assert_eq!(signature.len(), 65);
+        let hash = withdrawal_authorization_hash(EVM, &withdrawal_id(), RECIPIENT, TOKEN, 1_000, &[7u8; 32], DEADLINE).unwrap();
+        let recovered = Signature::try_from(signature.as_slice())
+            .unwrap()
+            .recover(RecoveryMessage::Hash(H256::from(hash)))
+            .unwrap();
+        assert_eq!(format!("{:?}", recovered), signer.address());
+
+        // Deterministic, so `sign-test` reproduces the daemon's signature
+        assert_eq!(sign_withdrawal(&signer, EVM), signature);
+        assert_ne!(
+            signer.refund_signature(EVM, &withdrawal_id(), RECIPIENT, TOKEN, 1_000).unwrap(),
+            signature
+        );
+        // Only valid on the chain it was issued for
+        assert_ne!(sign_withdrawal(&signer, AuthorizationScheme::Evm { chain_id: 1 }), signature);
+        // ...and until the deadline it was issued with
+        assert_ne!(
+            signer
+                .withdrawal_signature(EVM, &withdrawal_id(), RECIPIENT, TOKEN, 1_000, &[7u8; 32], DEADLINE + 1)
+                .unwrap(),
+            signature
+        );
+
+        assert!(CoordinatorSigner::from_hex("not-a-key").is_err());
+    }
+
+    #[test]
+    fn test_refund_signatures_follow_gateway_scheme() {
+        let signer = CoordinatorSigner::test();
+        let deposit_id = "11".repeat(32);
+        let signature = signer.refund_signature(EVM, &deposit_id, RECIPIENT, TOKEN, 1_000).unwrap();
+        let hash = refund_authorization_hash(EVM, &deposit_id, RECIPIENT, TOKEN, 1_000).unwrap();
+        let recovered = Signature::try_from(signature.as_slice())
+            .unwrap()
+            .recover(RecoveryMessage::Hash(H256::from(hash)))
+            .unwrap();
+        assert_eq!(format!("{:?}", recovered), signer.address());
+        assert!(signature[64] == 27 || signature[64] == 28);
+
+        // Solana recovers with a bare 0/1 recovery id
+        let sender = "4Nd1mBQtrMJVYVfKf2PJy9NZUZdTAsp7D4xWLs4gDB4T";
+        let mint = "So11111111111111111111111111111111111111112";
+        let signature = signer
+            .refund_signature(AuthorizationScheme::Solana, &deposit_id, sender, mint, 1_000)
+            .unwrap();
+        assert!(signature[64] <= 1);
+        let hash = refund_authorization_hash(AuthorizationScheme::Solana, &deposit_id, sender, mint, 1_000).unwrap();
+        let mut ethereum_style = signature.clone();
+        ethereum_style[64] += 27;
+        let recovered = Signature::try_from(ethereum_style.as_slice())
+            .unwrap()
+            .recover(RecoveryMessage::Hash(H256::from(hash)))
+            .unwrap();
+        assert_eq!(format!("{:?}", recovered), signer.address());
+
+        // A sender the gateway can't decode can't be signed for
+        assert!(signer.refund_signature(EVM, &deposit_id, mint, TOKEN, 1_000).is_err());
+    }
+
+    #[test]
+    fn test_rotation_switches_signing_key() {
+        let signer = CoordinatorSigner::test();
+        let before = sign_withdrawal(&signer, EVM);
+
+        let next = CoordinatorSigner::from_hex(&"01".repeat(32)).unwrap();
+        let next_address = next.address();
+        signer.rotate_to(next);
+        assert_eq!(signer.address(), next_address);
+
+        let after = sign_withdrawal(&signer, EVM);
+        let hash = withdrawal_authorization_hash(EVM, &withdrawal_id(), RECIPIENT, TOKEN, 1_000, &[7u8; 32], DEADLINE).unwrap();
+        let recovered = Signature::try_from(after.as_slice())
+            .unwrap()
+            .recover(RecoveryMessage::Hash(H256::from(hash)))
+            .unwrap();
+        assert_eq!(format!("{:?}", recovered), next_address);
+        assert_ne!(after, before);
+    }
+}
@@ -0,0 +1,156 @@
+// zcash-coordinator/src/telemetry.rs
+//! Logging, plus optional OpenTelemetry trace export
+//!
+//! Spans always go to the fmt subscriber. With `OTEL_EXPORTER_OTLP_ENDPOINT`
+//! set they are also exported over OTLP, so a transfer can be followed from
+//! the relayer that reported it through to authorization. Relayers send a
+//! W3C `traceparent` header and each RPC request continues their trace.
+
+use anyhow::Result;
+use axum::{extract::Request, http::HeaderMap, middleware::Next, response::Response};
+use opentelemetry::{global, propagation::Extractor, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{propagation::TraceContextPropagator, trace, Resource};
+use tracing::{field, info_span, Instrument};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+/// Name spans are exported under
+const SERVICE_NAME: &str = "zcash-coordinator";
+
+/// OTLP collector endpoint (e.g. `http://localhost:4317`); unset disables export
+pub const OTLP_ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+/// Install the global subscriber
+pub fn init(verbose: bool) -> Result<()> {
+    let log_level = if verbose {
+        tracing::Level::DEBUG
+    } else {
+        tracing::Level::INFO
+    };
+
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    let otlp = match std::env::var(OTLP_ENDPOINT_ENV) {
+        Ok(endpoint) if !endpoint.is_empty() => {
+            Some(tracing_opentelemetry::layer().with_tracer(otlp_tracer(&endpoint)?))
+        }
+        _ => None,
+    };
+
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| {
+                    format!(
+                        "zcash_coordinator={},tower_http=debug,sqlx=warn",
+                        log_level
+                    )
+                    .into()
+                }),
+        )
+        .with(tracing_subscriber::fmt::layer())
+        .with(otlp)
+        .init();
+
+    Ok(())
+}
+
+fn otlp_tracer(endpoint: &str) -> Result<trace::Tracer> {
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(opentelemetry_otlp::new_exporter().tonic().with_endpoint(endpoint))
+        .with_trace_config(
+            trace::config().with_resource(Resource::new([KeyValue::new("service.name", SERVICE_NAME)])),
+        )
+        .install_batch(opentelemetry_sdk::runtime::Tokio)?;
+    Ok(tracer)
+}
+
+/// Export spans still buffered; call before exiting
+pub fn shutdown() {
+    global::shutdown_tracer_provider();
+}
+
+/// Middleware giving each RPC request a span under the caller's trace.
+/// Handlers fill in `deposit_id`/`withdrawal_id` once they know it.
+pub async fn continue_trace(request: Request, next: Next) -> Response {
+    let span = info_span!(
+        "rpc_request",
+        method = %request.method(),
+        path = %request.uri().path(),
+        deposit_id = field::Empty,
+        withdrawal_id = field::Empty,
+    );
+    let parent = global::get_text_map_propagator(|propagator| {
+        propagator.extract(&HeaderExtractor(request.headers()))
+    });
+    span.set_parent(parent);
+
+    next.run(request).instrument(span).await
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|key| key.as_str()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::post, Router};
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_sdk::testing::trace::InMemorySpanExporter;
+    use opentelemetry_sdk::trace::TracerProvider;
+    use tower::ServiceExt;
+    use tracing::Span;
+
+    #[tokio::test]
+    async fn test_rpc_request_continues_relayer_trace() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = TracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+        let _guard = tracing::subscriber::set_default(subscriber);
+        global::set_text_map_propagator(TraceContextPropagator::new());
+
+        let app = Router::new()
+            .route("/deposits/notify", post(|| async {
+                Span::current().record("deposit_id", "d1");
+                "queued"
+            }))
+            .layer(axum::middleware::from_fn(continue_trace));
+
+        // As sent by a relayer mid-trace
+        let trace_id = "4bf92f3577b34da6a3ce929d0e0e4736";
+        let request = axum::http::Request::builder()
+            .method("POST")
+            .uri("/deposits/notify")
+            .header("traceparent", format!("00-{}-00f067aa0ba902b7-01", trace_id))
+            .body(Body::empty())
+            .unwrap();
+        app.oneshot(request).await.unwrap();
+        provider.force_flush();
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let span = spans.iter().find(|s| s.name == "rpc_request").expect("request span exported");
+        assert_eq!(span.span_context.trace_id().to_string(), trace_id);
+        assert_eq!(span.parent_span_id.to_string(), "00f067aa0ba902b7");
+        let attribute = |key: &str| {
+            span.attributes
+                .iter()
+                .find(|kv| kv.key.as_str() == key)
+                .map(|kv| kv.value.to_string())
+        };
+        assert_eq!(attribute("deposit_id").as_deref(), Some("d1"));
+        assert_eq!(attribute("path").as_deref(), Some("/deposits/notify"));
+    }
+}
@@ -5,7 +5,11 @@ use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
-use tracing::info;
+use tracing::{info, warn};
+
+use crate::amount::Amount;
+
+pub use zerobridge_types::CanonicalTokenId;
 
 /// Token registry managing canonical token identifiers
 pub struct TokenRegistry {
@@ -13,10 +17,6 @@ pub struct TokenRegistry {
     reverse_lookup: HashMap<(u64, String), CanonicalTokenId>,
 }
 
-/// Canonical token identifier (chain-agnostic)
-#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
-pub struct CanonicalTokenId(pub String);
-
 /// Token mappings across chains
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenMappings {
@@ -38,9 +38,16 @@ pub struct ChainToken {
     pub wrapped_version: Option<String>,
 }
 
+/// Largest decimals value accepted for any token or representation
+pub const MAX_TOKEN_DECIMALS: u8 = 36;
+
 /// Token registry configuration file format
 #[derive(Debug, Deserialize)]
 struct TokenConfig {
+    /// Reject (rather than warn about) representations whose decimals differ
+    /// from the canonical value without declaring a `wrapped_version`
+    #[serde(default)]
+    strict_decimals: bool,
     tokens: Vec<TokenDefinition>,
 }
 
@@ -49,9 +56,38 @@ struct TokenDefinition {
     symbol: String,
     name: String,
     decimals: u8,
+    /// Distinguishes tokens sharing a symbol; defaults to the Ethereum
+    /// mainnet address, else the first representation's chain and address.
+    /// Tokens with a unique symbol and no key keep the symbol-only id.
+    #[serde(default)]
+    canonical_key: Option<String>,
     representations: Vec<TokenRepresentation>,
 }
 
+impl TokenDefinition {
+    /// Key mixed into the canonical id, if any. `shared_symbol` says
+    /// whether another token in the registry uses the same symbol.
+    fn canonical_key(&self, shared_symbol: bool) -> Result<Option<String>> {
+        if let Some(key) = &self.canonical_key {
+            return Ok(Some(key.to_lowercase()));
+        }
+        if !shared_symbol {
+            return Ok(None);
+        }
+        
+        let repr = self
+            .representations
+            .iter()
+            .find(|r| r.chain_id == ETHEREUM_MAINNET_CHAIN_ID)
+            .or_else(|| self.representations.first())
+            .with_context(|| format!("Token {} has no representations", self.symbol))?;
+        Ok(Some(format!("{}:{}", repr.chain_id, normalize_address(&repr.address))))
+    }
+}
+
+/// Chain whose token address disambiguates a symbol by default
+const ETHEREUM_MAINNET_CHAIN_ID: u64 = 1;
+
 #[derive(Debug, Deserialize)]
 struct TokenRepresentation {
     chain_id: u64,
@@ -74,32 +110,99 @@ impl TokenRegistry {
             .await
             .context("Failed to read token registry file")?;
         
-        let config: TokenConfig = toml::from_str(&content)
+        Self::from_toml(&content)
+    }
+    
+    /// Build a token registry from TOML registry contents
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let config: TokenConfig = toml::from_str(content)
             .context("Failed to parse token registry")?;
         
         let mut mappings = HashMap::new();
         let mut reverse_lookup = HashMap::new();
         
+        let mut symbol_counts: HashMap<String, usize> = HashMap::new();
+        for token_def in &config.tokens {
+            *symbol_counts.entry(token_def.symbol.to_uppercase()).or_default() += 1;
+        }
+        
         for token_def in config.tokens {
-            let canonical_id = Self::compute_canonical_id(&token_def.symbol);
+            let shared_symbol = symbol_counts[&token_def.symbol.to_uppercase()] > 1;
+            let canonical_key = token_def.canonical_key(shared_symbol)?;
+            let canonical_id = Self::compute_canonical_id(&token_def.symbol, canonical_key.as_deref());
+            if mappings.contains_key(&canonical_id) {
+                anyhow::bail!(
+                    "Token {} is defined twice (canonical id {}); set a distinct canonical_key",
+                    token_def.symbol,
+                    canonical_id.0
+                );
+            }
+            
+            if token_def.decimals > MAX_TOKEN_DECIMALS {
+                anyhow::bail!(
+                    "Token {} has {} decimals (max {})",
+                    token_def.symbol,
+                    token_def.decimals,
+                    MAX_TOKEN_DECIMALS
+                );
+            }
             
             let mut representations = Vec::new();
             
             for repr in token_def.representations {
+                let decimals = repr.decimals.unwrap_or(token_def.decimals);
+                
+                if decimals > MAX_TOKEN_DECIMALS {
+                    anyhow::bail!(
+                        "Token {} on {} has {} decimals (max {})",
+                        token_def.symbol,
+                        repr.chain_name,
+                        decimals,
+                        MAX_TOKEN_DECIMALS
+                    );
+                }
+                
+                // A differing precision is expected for wrapped representations;
+                // otherwise it's likely a typo that would mis-credit amounts
+                if decimals != token_def.decimals && repr.wrapped_version.is_none() {
+                    if config.strict_decimals {
+                        anyhow::bail!(
+                            "Token {} on {} has {} decimals but canonical is {} (set wrapped_version if intended)",
+                            token_def.symbol,
+                            repr.chain_name,
+                            decimals,
+                            token_def.decimals
+                        );
+                    }
+                    warn!(
+                        "Token {} on {} has {} decimals but canonical is {}",
+                        token_def.symbol,
+                        repr.chain_name,
+                        decimals,
+                        token_def.decimals
+                    );
+                }
+                
                 let chain_token = ChainToken {
                     chain_id: repr.chain_id,
                     chain_name: repr.chain_name.clone(),
                     address: repr.address.clone(),
-                    decimals: repr.decimals.unwrap_or(token_def.decimals),
+                    decimals,
                     native: repr.native,
                     wrapped_version: repr.wrapped_version,
                 };
                 
-                // Add to reverse lookup
-                reverse_lookup.insert(
-                    (repr.chain_id, repr.address.to_lowercase()),
-                    canonical_id.clone(),
-                );
+                // Add to reverse lookup; one address can't back two tokens
+                let key = (repr.chain_id, normalize_address(&repr.address));
+                if reverse_lookup.contains_key(&key) {
+                    anyhow::bail!(
+                        "Token {} on {}: address {} is already mapped to another token",
+                        token_def.symbol,
+                        repr.chain_name,
+                        repr.address
+                    );
+                }
+                reverse_lookup.insert(key, canonical_id.clone());
                 
                 representations.push(chain_token);
             }
@@ -126,6 +229,23 @@ impl TokenRegistry {
         })
     }
     
+    /// Re-apply persisted operator removals on top of the registry file
+    pub fn apply_overrides(&mut self, overrides: Vec<(String, Option<(u64, String)>)>) {
+        for (canonical_id, representation) in overrides {
+            let result = match representation {
+                Some((chain_id, address)) => {
+                    self.disable_representation(chain_id, &address).map(|_| ())
+                }
+                None => self.remove_token(&CanonicalTokenId(canonical_id)).map(|_| ()),
+            };
+            
+            // Already absent from the registry file - nothing to undo
+            if let Err(e) = result {
+                warn!("Skipping stale token override: {}", e);
+            }
+        }
+    }
+    
     /// Get token for a specific chain
     pub fn get_token_for_chain(
         &self,
@@ -134,7 +254,7 @@ impl TokenRegistry {
     ) -> Result<ChainToken> {
         let canonical_id = self
             .reverse_lookup
-            .get(&(chain_id, token_address.to_lowercase()))
+            .get(&(chain_id, normalize_address(token_address)))
             .context("Token not found in registry")?;
         
         let mappings = self
@@ -169,6 +289,19 @@ impl TokenRegistry {
             .context("Token not available on specified chain")
     }
     
+    /// Look up a canonical ID by token symbol. `None` if the symbol is
+    /// unknown or shared by more than one token.
+    pub fn canonical_id_for_symbol(&self, symbol: &str) -> Option<&CanonicalTokenId> {
+        let mut matches = self
+            .mappings
+            .values()
+            .filter(|m| m.symbol.eq_ignore_ascii_case(symbol));
+        match (matches.next(), matches.next()) {
+            (Some(m), None) => Some(&m.canonical_id),
+            _ => None,
+        }
+    }
+    
     /// Get canonical ID from chain-specific address
     pub fn get_canonical_id(
         &self,
@@ -176,7 +309,7 @@ impl TokenRegistry {
         token_address: &str,
     ) -> Option<&CanonicalTokenId> {
         self.reverse_lookup
-            .get(&(chain_id, token_address.to_lowercase()))
+            .get(&(chain_id, normalize_address(token_address)))
     }
     
     /// Get all representations for a token
@@ -194,7 +327,35 @@ impl TokenRegistry {
         token_address: &str,
     ) -> bool {
         self.reverse_lookup
-            .contains_key(&(chain_id, token_address.to_lowercase()))
+            .contains_key(&(chain_id, normalize_address(token_address)))
+    }
+    
+    /// All registered tokens, ordered by symbol
+    pub fn get_all_tokens(&self) -> Vec<&TokenMappings> {
+        let mut tokens: Vec<&TokenMappings> = self.mappings.values().collect();
+        tokens.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        tokens
+    }
+    
+    /// Destination token and amount paid out for a withdrawal of
+    /// `canonical_amount` (note precision) to `token_address` on `chain_id`
+    pub fn resolve_payout(
+        &self,
+        chain_id: u64,
+        token_address: &str,
+        canonical_amount: u64,
+    ) -> Result<(ChainToken, Amount)> {
+        let token_info = self.get_token_for_chain(chain_id, token_address)?;
+        let canonical_decimals = self
+            .get_canonical_id(chain_id, token_address)
+            .and_then(|id| self.get_all_representations(id))
+            .context("Token mappings not found")?
+            .decimals;
+        
+        let amount = Amount::from_u64(canonical_amount, canonical_decimals)
+            .rescale(token_info.decimals)
+            .context("Withdrawal amount not representable on destination chain")?;
+        Ok((token_info, amount))
     }
     
     /// Get number of tokens
@@ -213,18 +374,102 @@ impl TokenRegistry {
             .unwrap_or_default()
     }
     
-    /// Compute canonical token ID from symbol
-    fn compute_canonical_id(symbol: &str) -> CanonicalTokenId {
+    /// Convert an amount of `canonical_id` between two chains' representations
+    pub fn convert_amount(
+        &self,
+        canonical_id: &CanonicalTokenId,
+        from_chain_id: u64,
+        to_chain_id: u64,
+        amount: u64,
+    ) -> Result<Amount> {
+        let from = self.get_token_by_id(canonical_id, from_chain_id)?;
+        let to = self.get_token_by_id(canonical_id, to_chain_id)?;
+        
+        Amount::from_u64(amount, from.decimals).rescale(to.decimals)
+    }
+    
+    /// Retire a token entirely; none of its representations resolve afterwards
+    pub fn remove_token(&mut self, canonical_id: &CanonicalTokenId) -> Result<TokenMappings> {
+        let mappings = self
+            .mappings
+            .remove(canonical_id)
+            .context("Token not found")?;
+        
+        self.reverse_lookup.retain(|_, id| id != canonical_id);
+        
+        info!("Removed token {} ({:?})", mappings.symbol, canonical_id);
+        
+        Ok(mappings)
+    }
+    
+    /// Disable a single chain representation, leaving the token's other
+    /// representations in place
+    pub fn disable_representation(
+        &mut self,
+        chain_id: u64,
+        token_address: &str,
+    ) -> Result<CanonicalTokenId> {
+        let canonical_id = self
+            .reverse_lookup
+            .remove(&(chain_id, normalize_address(token_address)))
+            .context("Token not found in registry")?;
+        
+        if let Some(mappings) = self.mappings.get_mut(&canonical_id) {
+            mappings.representations.retain(|t| {
+                !(t.chain_id == chain_id && same_address(&t.address, token_address))
+            });
+        }
+        
+        info!("Disabled token representation {} on chain {}", token_address, chain_id);
+        
+        Ok(canonical_id)
+    }
+    
+    /// Compute canonical token ID from symbol and disambiguating key. Without
+    /// a key it's the symbol-only id tokens have always had, so stored
+    /// deposits, pools and notes keep resolving.
+    fn compute_canonical_id(symbol: &str, canonical_key: Option<&str>) -> CanonicalTokenId {
         use blake2::{Blake2b512, Digest};
         
         let mut hasher = Blake2b512::new();
         hasher.update(symbol.to_uppercase().as_bytes());
+        if let Some(canonical_key) = canonical_key {
+            hasher.update([0u8]);
+            hasher.update(canonical_key.as_bytes());
+        }
         let result = hasher.finalize();
         
         CanonicalTokenId(hex::encode(&result[..16]))
     }
 }
 
+/// Key a token address is looked up by. EVM-style `0x` hex addresses are
+/// case-insensitive (checksum casing only) and are lowercased; anything
+/// else, such as Solana base58 or NEAR account ids, is case-sensitive and
+/// kept as is.
+pub fn normalize_address(address: &str) -> String {
+    match address.strip_prefix("0x") {
+        Some(hex) if !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()) => {
+            address.to_lowercase()
+        }
+        _ => address.to_string(),
+    }
+}
+
+/// Whether two token addresses on the same chain refer to the same token
+pub fn same_address(a: &str, b: &str) -> bool {
+    normalize_address(a) == normalize_address(b)
+}
+
+/// Rescale a raw `u64` amount from `from_decimals` to `to_decimals`
+/// precision; see [`Amount::rescale`]. Also fails if the result doesn't fit
+/// in `u64`.
+pub fn scale_amount(amount: u64, from_decimals: u8, to_decimals: u8) -> Result<u64> {
+    Amount::from_u64(amount, from_decimals)
+        .rescale(to_decimals)?
+        .to_u64()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -290,4 +535,253 @@ address = "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"
         // Clean up
         tokio::fs::remove_file(temp_path).await.ok();
     }
-}
\ No newline at end of file
+
+    fn test_registry() -> TokenRegistry {
+        TokenRegistry::from_toml(r#"
+[[tokens]]
+symbol = "ETH"
+name = "Ethereum"
+decimals = 18
+
+[[tokens.representations]]
+chain_id = 1
+chain_name = "Ethereum"
+address = "0x0000000000000000000000000000000000000000"
+native = true
+
+[[tokens]]
+symbol = "USDC"
+name = "USD Coin"
+decimals = 6
+
+[[tokens.representations]]
+chain_id = 1
+chain_name = "Ethereum"
+address = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"
+
+[[tokens.representations]]
+chain_id = 8453
+chain_name = "Base"
+address = "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"
+"#).unwrap()
+    }
+
+    #[test]
+    fn test_decimals_out_of_range_rejected() {
+        let result = TokenRegistry::from_toml(r#"
+[[tokens]]
+symbol = "BAD"
+name = "Bad Token"
+decimals = 18
+
+[[tokens.representations]]
+chain_id = 1
+chain_name = "Ethereum"
+address = "0x01"
+decimals = 77
+"#);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_decimals_mismatch() {
+        let config = r#"
+[[tokens]]
+symbol = "USDC"
+name = "USD Coin"
+decimals = 6
+
+[[tokens.representations]]
+chain_id = 56
+chain_name = "BSC"
+address = "0x02"
+decimals = 18
+"#;
+
+        // Warn-only by default
+        let registry = TokenRegistry::from_toml(config).unwrap();
+        assert_eq!(registry.get_token_for_chain(56, "0x02").unwrap().decimals, 18);
+
+        // Rejected in strict mode
+        let strict = format!("strict_decimals = true\n{}", config);
+        assert!(TokenRegistry::from_toml(&strict).is_err());
+
+        // Accepted in strict mode when declared as a wrapped representation
+        let wrapped = strict.replace("decimals = 18", "decimals = 18\nwrapped_version = \"BSC-USD\"");
+        assert!(TokenRegistry::from_toml(&wrapped).is_ok());
+    }
+
+    #[test]
+    fn test_tokens_sharing_a_symbol_get_distinct_ids() {
+        let registry = TokenRegistry::from_toml(r#"
+[[tokens]]
+symbol = "USDC"
+name = "USD Coin"
+decimals = 6
+
+[[tokens.representations]]
+chain_id = 1
+chain_name = "Ethereum"
+address = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"
+
+[[tokens]]
+symbol = "USDC"
+name = "USDC Fork"
+decimals = 6
+
+[[tokens.representations]]
+chain_id = 1
+chain_name = "Ethereum"
+address = "0x00000000000000000000000000000000000000f0"
+"#).unwrap();
+        
+        assert_eq!(registry.token_count(), 2);
+        let real = registry.get_canonical_id(1, "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48").unwrap();
+        let fork = registry.get_canonical_id(1, "0x00000000000000000000000000000000000000f0").unwrap();
+        assert_ne!(real, fork);
+        assert_eq!(registry.get_all_representations(fork).unwrap().name, "USDC Fork");
+        
+        // Ambiguous symbol lookups resolve to neither
+        assert!(registry.canonical_id_for_symbol("USDC").is_none());
+    }
+
+    #[test]
+    fn test_unique_symbols_keep_symbol_only_ids() {
+        // Ids persisted before keys existed must keep resolving
+        let registry = test_registry();
+        let usdc = registry.canonical_id_for_symbol("USDC").unwrap();
+        assert_eq!(usdc, &TokenRegistry::compute_canonical_id("usdc", None));
+        assert_ne!(usdc, &TokenRegistry::compute_canonical_id("USDC", Some("circle")));
+    }
+
+    #[test]
+    fn test_duplicate_canonical_ids_rejected() {
+        let token = |name: &str, address: &str, key: &str| format!(r#"
+[[tokens]]
+symbol = "USDC"
+name = "{}"
+decimals = 6
+{}
+
+[[tokens.representations]]
+chain_id = 8453
+chain_name = "Base"
+address = "{}"
+"#, name, key, address);
+        
+        // Same symbol and key
+        let config = token("A", "0x01", r#"canonical_key = "circle""#)
+            + &token("B", "0x02", r#"canonical_key = "circle""#);
+        let err = TokenRegistry::from_toml(&config).unwrap_err().to_string();
+        assert!(err.contains("defined twice"), "{}", err);
+        
+        // An explicit key tells them apart
+        let config = token("A", "0x01", r#"canonical_key = "circle""#)
+            + &token("B", "0x02", r#"canonical_key = "bridged""#);
+        assert_eq!(TokenRegistry::from_toml(&config).unwrap().token_count(), 2);
+        
+        // One address can't be mapped to two tokens
+        let config = token("A", "0x01", "") + &token("B", "0x01", r#"canonical_key = "other""#);
+        let err = TokenRegistry::from_toml(&config).unwrap_err().to_string();
+        assert!(err.contains("already mapped"), "{}", err);
+    }
+
+    #[test]
+    fn test_non_evm_addresses_are_case_sensitive() {
+        let registry = TokenRegistry::from_toml(r#"
+[[tokens]]
+symbol = "BONK"
+name = "Bonk"
+decimals = 5
+
+[[tokens.representations]]
+chain_id = 2
+chain_name = "Solana"
+address = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263"
+
+[[tokens]]
+symbol = "FAKE"
+name = "Lookalike"
+decimals = 5
+
+[[tokens.representations]]
+chain_id = 2
+chain_name = "Solana"
+address = "dezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263"
+"#).unwrap();
+        
+        // Mints differing only by case are distinct tokens
+        let bonk = registry.get_token_for_chain(2, "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263").unwrap();
+        assert_eq!(bonk.address, "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263");
+        let fake_id = registry.get_canonical_id(2, "dezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263").unwrap();
+        assert_eq!(registry.get_all_representations(fake_id).unwrap().symbol, "FAKE");
+        assert!(!registry.is_supported(2, "DEZXAZ8Z7PNRNRJJZ3WXBORGIXCA6XJNB7YAB1PPB263"));
+        
+        // EVM addresses still match regardless of checksum casing
+        let registry = test_registry();
+        assert!(registry.is_supported(1, "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"));
+        assert!(registry.is_supported(1, "0xA0B86991C6218B36C1D19D4A2E9EB0CE3606EB48"));
+    }
+
+    #[test]
+    fn test_removed_token_no_longer_resolves() {
+        let mut registry = test_registry();
+        let usdc = registry.canonical_id_for_symbol("USDC").unwrap().clone();
+
+        registry.remove_token(&usdc).unwrap();
+
+        assert_eq!(registry.token_count(), 1);
+        assert!(!registry.is_supported(1, "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"));
+        assert!(registry
+            .get_token_for_chain(8453, "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913")
+            .is_err());
+        assert!(registry.get_token_by_id(&usdc, 1).is_err());
+        assert!(registry.remove_token(&usdc).is_err());
+
+        // Other tokens unaffected
+        assert!(registry.is_supported(1, "0x0000000000000000000000000000000000000000"));
+    }
+
+    #[test]
+    fn test_disabled_representation_rejected() {
+        let mut registry = test_registry();
+        let usdc = registry.canonical_id_for_symbol("USDC").unwrap().clone();
+
+        // Address case shouldn't matter
+        let disabled = registry
+            .disable_representation(8453, "0x833589fcd6edb6e08f4c7c32d4f71b54bda02913")
+            .unwrap();
+        assert_eq!(disabled, usdc);
+
+        assert!(!registry.is_supported(8453, "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"));
+        assert!(registry.get_token_by_id(&usdc, 8453).is_err());
+        assert_eq!(registry.get_supported_chains(&usdc), vec![1]);
+
+        // Remaining representation still resolves
+        let usdc_on_eth = registry
+            .get_token_for_chain(1, "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48")
+            .unwrap();
+        assert_eq!(usdc_on_eth.chain_id, 1);
+    }
+
+    #[test]
+    fn test_scale_amount_up() {
+        // 1 USDC at 6 decimals -> 18 decimals
+        assert_eq!(scale_amount(1_000_000, 6, 18).unwrap(), 1_000_000_000_000_000_000);
+        assert_eq!(scale_amount(42, 8, 8).unwrap(), 42);
+        
+        // 19 extra decimals of 1 overflows u64
+        assert!(scale_amount(1, 0, 20).is_err());
+    }
+
+    #[test]
+    fn test_scale_amount_down() {
+        assert_eq!(scale_amount(1_000_000_000_000_000_000, 18, 6).unwrap(), 1_000_000);
+        assert_eq!(scale_amount(1_000_000_000_000, 18, 6).unwrap(), 1);
+        
+        // Sub-unit dust would be lost at 6 decimals
+        assert!(scale_amount(1_000_000_000_000_001, 18, 6).is_err());
+        assert!(scale_amount(999_999_999_999, 18, 6).is_err());
+    }
+}
@@ -0,0 +1,202 @@
+// zcash-coordinator/src/wire.rs
+//! Body encodings for the relayer RPC
+//!
+//! JSON by default. Relayers that see the `encoding.bincode` capability may
+//! send `Content-Type: application/x-bincode` bodies and ask for bincode
+//! responses with `Accept`, which keeps proofs, nullifiers and signatures as
+//! raw bytes instead of JSON number arrays. The same wire structs are used
+//! for both.
+
+use axum::{
+    async_trait,
+    body::Bytes,
+    extract::{FromRequest, Request},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::warn;
+
+pub const BINCODE_CONTENT_TYPE: &str = "application/x-bincode";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Json,
+    Bincode,
+}
+
+impl Encoding {
+    /// Encoding of a request body, from its `Content-Type`
+    pub fn of_request(headers: &HeaderMap) -> Self {
+        match headers.get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok()) {
+            Some(content_type) if content_type.starts_with(BINCODE_CONTENT_TYPE) => Encoding::Bincode,
+            _ => Encoding::Json,
+        }
+    }
+
+    /// Encoding to answer with, from the request's `Accept`
+    pub fn accepted(headers: &HeaderMap) -> Self {
+        let accepts_bincode = headers
+            .get_all(header::ACCEPT)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .any(|media| media.trim().starts_with(BINCODE_CONTENT_TYPE));
+        if accepts_bincode {
+            Encoding::Bincode
+        } else {
+            Encoding::Json
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Encoding::Json => "application/json",
+            Encoding::Bincode => BINCODE_CONTENT_TYPE,
+        }
+    }
+
+    pub fn encode<T: Serialize>(&self, value: &T) -> anyhow::Result<Vec<u8>> {
+        Ok(match self {
+            Encoding::Json => serde_json::to_vec(value)?,
+            Encoding::Bincode => bincode::serialize(value)?,
+        })
+    }
+
+    pub fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> anyhow::Result<T> {
+        Ok(match self {
+            Encoding::Json => serde_json::from_slice(bytes)?,
+            Encoding::Bincode => bincode::deserialize(bytes)?,
+        })
+    }
+}
+
+/// Request body in whichever encoding its `Content-Type` names. JSON bodies
+/// are rejected exactly as `Json` would reject them.
+pub struct Wire<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for Wire<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        match Encoding::of_request(req.headers()) {
+            Encoding::Json => Json::<T>::from_request(req, state)
+                .await
+                .map(|Json(value)| Wire(value))
+                .map_err(IntoResponse::into_response),
+            Encoding::Bincode => {
+                let bytes = Bytes::from_request(req, state)
+                    .await
+                    .map_err(IntoResponse::into_response)?;
+                Encoding::Bincode
+                    .decode(&bytes)
+                    .map(Wire)
+                    .map_err(|_| StatusCode::UNPROCESSABLE_ENTITY.into_response())
+            }
+        }
+    }
+}
+
+/// Response body in the encoding the client accepts
+pub struct Negotiated<T> {
+    pub encoding: Encoding,
+    pub value: T,
+}
+
+impl<T> Negotiated<T> {
+    pub fn new(headers: &HeaderMap, value: T) -> Self {
+        Self {
+            encoding: Encoding::accepted(headers),
+            value,
+        }
+    }
+}
+
+impl<T: Serialize> IntoResponse for Negotiated<T> {
+    fn into_response(self) -> Response {
+        match self.encoding.encode(&self.value) {
+            Ok(body) => (
+                [(header::CONTENT_TYPE, HeaderValue::from_static(self.encoding.content_type()))],
+                body,
+            )
+                .into_response(),
+            Err(e) => {
+                warn!("Failed to encode response: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc_server::WithdrawalNotification;
+    use zerobridge_types::CanonicalTokenId;
+
+    fn notification() -> WithdrawalNotification {
+        WithdrawalNotification {
+            withdrawal_id: "w1".to_string(),
+            target_chain_id: 8453,
+            recipient: "0x00000000000000000000000000000000000000aa".to_string(),
+            canonical_token_id: CanonicalTokenId("ab".repeat(16)),
+            amount: 1_000_000,
+            nullifier: vec![0xfe; 32],
+            zcash_proof: vec![0xff; 192],
+            merkle_root: vec![0x11; 32],
+        }
+    }
+
+    #[test]
+    fn test_withdrawal_notification_round_trips_both_encodings() {
+        let original = notification();
+
+        let json = Encoding::Json.encode(&original).unwrap();
+        let binary = Encoding::Bincode.encode(&original).unwrap();
+        // Byte fields are raw in bincode, number arrays in JSON
+        assert!(binary.len() * 2 < json.len(), "{} vs {}", binary.len(), json.len());
+
+        for (encoding, bytes) in [(Encoding::Json, json), (Encoding::Bincode, binary)] {
+            let decoded: WithdrawalNotification = encoding.decode(&bytes).unwrap();
+            assert_eq!(decoded.withdrawal_id, original.withdrawal_id);
+            assert_eq!(decoded.target_chain_id, original.target_chain_id);
+            assert_eq!(decoded.recipient, original.recipient);
+            assert_eq!(decoded.canonical_token_id, original.canonical_token_id);
+            assert_eq!(decoded.amount, original.amount);
+            assert_eq!(decoded.nullifier, original.nullifier);
+            assert_eq!(decoded.zcash_proof, original.zcash_proof);
+            assert_eq!(decoded.merkle_root, original.merkle_root);
+        }
+
+        // Byte fields are base64 strings in JSON
+        let value: serde_json::Value = serde_json::from_slice(&Encoding::Json.encode(&original).unwrap()).unwrap();
+        assert_eq!(value["nullifier"], "/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v7+/v4=");
+        assert!(value["zcash_proof"].is_string());
+        assert_eq!(value["merkle_root"], "ERERERERERERERERERERERERERERERERERERERERERE=");
+
+        // Mislabelled bodies don't decode
+        let json = Encoding::Json.encode(&original).unwrap();
+        assert!(Encoding::Bincode.decode::<WithdrawalNotification>(&json).is_err());
+    }
+
+    #[test]
+    fn test_encoding_negotiation_falls_back_to_json() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(Encoding::of_request(&headers), Encoding::Json);
+        assert_eq!(Encoding::accepted(&headers), Encoding::Json);
+
+        headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(BINCODE_CONTENT_TYPE));
+        headers.insert(header::ACCEPT, HeaderValue::from_static("application/x-bincode, application/json;q=0.5"));
+        assert_eq!(Encoding::of_request(&headers), Encoding::Bincode);
+        assert_eq!(Encoding::accepted(&headers), Encoding::Bincode);
+
+        headers.insert(header::ACCEPT, HeaderValue::from_static("application/json"));
+        assert_eq!(Encoding::accepted(&headers), Encoding::Json);
+    }
+}
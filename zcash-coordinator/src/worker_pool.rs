@@ -0,0 +1,141 @@
+// zcash-coordinator/src/worker_pool.rs
+//! Bounded worker pool for deposit/withdrawal processing
+//! A slow item (e.g. a Zcash send) no longer stalls the whole tick, and
+//! an item still in flight is never picked up a second time
+
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+
+#[derive(Clone)]
+pub struct WorkerPool {
+    limiter: Arc<Semaphore>,
+    in_flight: Arc<Mutex<HashSet<String>>>,
+}
+
+/// Removes the item from the in-flight set when the job ends, even on panic
+struct InFlightGuard {
+    id: String,
+    in_flight: Arc<Mutex<HashSet<String>>>,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.in_flight.lock().unwrap().remove(&self.id);
+    }
+}
+
+impl WorkerPool {
+    pub fn new(size: usize) -> Self {
+        Self {
+            limiter: Arc::new(Semaphore::new(size)),
+            in_flight: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// Run `job` for item `id` on the pool. Returns `None` without running it
+    /// if that id is still being processed. Waits for a free worker when the
+    /// pool is full.
+    pub async fn dispatch<F>(&self, id: String, job: F) -> Option<JoinHandle<()>>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        if !self.in_flight.lock().unwrap().insert(id.clone()) {
+            return None;
+        }
+        let guard = InFlightGuard {
+            id,
+            in_flight: self.in_flight.clone(),
+        };
+
+        // The semaphore is never closed
+        let permit = self.limiter.clone().acquire_owned().await.ok()?;
+
+        Some(tokio::spawn(async move {
+            job.await;
+            drop(permit);
+            drop(guard);
+        }))
+    }
+
+    /// Number of items currently queued or running
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.lock().unwrap().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::time::{sleep, Duration, Instant};
+
+    #[tokio::test]
+    async fn test_items_run_in_parallel_up_to_pool_size() {
+        let pool = WorkerPool::new(4);
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_seen = Arc::new(AtomicUsize::new(0));
+
+        let start = Instant::now();
+        let mut handles = Vec::new();
+        for i in 0..8 {
+            let running = running.clone();
+            let max_seen = max_seen.clone();
+            let handle = pool
+                .dispatch(format!("item{}", i), async move {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_seen.fetch_max(now, Ordering::SeqCst);
+                    sleep(Duration::from_millis(50)).await;
+                    running.fetch_sub(1, Ordering::SeqCst);
+                })
+                .await
+                .unwrap();
+            handles.push(handle);
+        }
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        assert_eq!(max_seen.load(Ordering::SeqCst), 4);
+        // Two waves of 50ms rather than eight
+        assert!(start.elapsed() < Duration::from_millis(300));
+        assert_eq!(pool.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_same_item_not_processed_twice() {
+        let pool = WorkerPool::new(4);
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        let job = |runs: Arc<AtomicUsize>| async move {
+            runs.fetch_add(1, Ordering::SeqCst);
+            sleep(Duration::from_millis(50)).await;
+        };
+
+        let first = pool.dispatch("d1".to_string(), job(runs.clone())).await.unwrap();
+        // Next tick sees the item still pending while it's in flight
+        assert!(pool.dispatch("d1".to_string(), job(runs.clone())).await.is_none());
+        first.await.unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+        // Once finished it can be picked up again (e.g. after a failure)
+        pool.dispatch("d1".to_string(), job(runs.clone())).await.unwrap().await.unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_panicking_job_releases_item() {
+        let pool = WorkerPool::new(1);
+
+        let handle = pool
+            .dispatch("w1".to_string(), async { panic!("boom") })
+            .await
+            .unwrap();
+        assert!(handle.await.is_err());
+
+        assert_eq!(pool.in_flight(), 0);
+        assert!(pool.dispatch("w1".to_string(), async {}).await.is_some());
+    }
+}
@@ -5,10 +5,99 @@ use anyhow::{Context, Result};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use tracing::{debug, info};
+use thiserror::Error;
+use tracing::{debug, info, warn};
 use std::time::Duration;
 
 use crate::config::ZcashConfig;
+use crate::metrics;
+
+/// Times a transient node error is retried before giving up
+const TRANSIENT_RETRIES: u32 = 3;
+
+/// Confirmations a note needs before `z_sendmany` spends it (zcashd default)
+const SENDMANY_MINCONF: u32 = 10;
+
+/// Zatoshis to the decimal ZEC amounts zcashd RPCs take
+fn zat_to_zec(zat: u64) -> f64 {
+    zat as f64 / 100_000_000.0
+}
+
+/// zcashd JSON-RPC failures, by error code where the node reports one
+#[derive(Debug, Error)]
+pub enum ZcashRpcError {
+    /// -6: wallet can't cover the amount plus fee
+    #[error("insufficient funds: {0}")]
+    InsufficientFunds(String),
+    
+    /// -28: node is still starting up (loading blocks, verifying wallet)
+    #[error("node warming up: {0}")]
+    WarmingUp(String),
+    
+    /// -10: node is in initial block download
+    #[error("node syncing: {0}")]
+    Syncing(String),
+    
+    /// -32601: method not supported by this node
+    #[error("method not found: {0}")]
+    MethodNotFound(String),
+    
+    /// -5 / -8 / -32602: bad address, key or parameter
+    #[error("invalid parameter: {0}")]
+    InvalidParameter(String),
+    
+    /// Request never got a JSON-RPC answer (connection, HTTP status, bad body)
+    #[error("transport error: {0}")]
+    Transport(String),
+    
+    /// Any other node-reported error
+    #[error("RPC error {code}: {message}")]
+    Other { code: i64, message: String },
+}
+
+impl ZcashRpcError {
+    /// Map a JSON-RPC `error` object to a typed error
+    pub fn from_rpc_error(error: &Value) -> Self {
+        let code = error.get("code").and_then(|c| c.as_i64()).unwrap_or(0);
+        let message = error.get("message")
+            .and_then(|m| m.as_str())
+            .unwrap_or("Unknown error")
+            .to_string();
+        
+        match code {
+            -6 => ZcashRpcError::InsufficientFunds(message),
+            -28 => ZcashRpcError::WarmingUp(message),
+            -10 => ZcashRpcError::Syncing(message),
+            -32601 => ZcashRpcError::MethodNotFound(message),
+            -5 | -8 | -32602 => ZcashRpcError::InvalidParameter(message),
+            _ => ZcashRpcError::Other { code, message },
+        }
+    }
+    
+    /// Worth retrying: the node or the link to it should recover on its own.
+    /// Only safe for read-only calls; a transport error may hide a call the
+    /// node already acted on.
+    pub fn is_transient(&self) -> bool {
+        self.is_not_ready() || matches!(self, ZcashRpcError::Transport(_))
+    }
+    
+    /// The node refused the call without acting on it (still starting up or
+    /// syncing), so even a call that spends, like `z_sendmany`, can be resent
+    pub fn is_not_ready(&self) -> bool {
+        matches!(self, ZcashRpcError::WarmingUp(_) | ZcashRpcError::Syncing(_))
+    }
+}
+
+/// Pull `result` out of a JSON-RPC response body, or the typed error
+fn parse_rpc_response(json: Value) -> std::result::Result<Value, ZcashRpcError> {
+    if let Some(error) = json.get("error").filter(|e| e.is_object()) {
+        return Err(ZcashRpcError::from_rpc_error(error));
+    }
+    
+    json.get("result")
+        .cloned()
+        .ok_or_else(|| ZcashRpcError::Transport("No result in RPC response".to_string()))
+}
 
 /// Zcash RPC client
 #[derive(Clone)]
@@ -27,6 +116,21 @@ pub struct BlockchainInfo {
     pub chainwork: String,
 }
 
+/// Note the node's wallet has seen arrive at an address
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct ReceivedNote {
+    pub txid: String,
+    /// Zatoshis
+    #[serde(rename = "amountZat")]
+    pub amount: u64,
+    /// Hex-encoded memo
+    #[serde(default)]
+    pub memo: Option<String>,
+    /// Change from one of the wallet's own spends
+    #[serde(default)]
+    pub change: bool,
+}
+
 /// Transaction info
 #[derive(Debug, Deserialize)]
 pub struct TransactionInfo {
@@ -47,6 +151,7 @@ impl ZcashClient {
         
         // Test connection
         zcash_client.test_connection().await?;
+        zcash_client.import_viewing_key().await?;
         
         Ok(zcash_client)
     }
@@ -61,6 +166,30 @@ impl ZcashClient {
         Ok(())
     }
     
+    /// Import the configured viewing key so the wallet scans notes received
+    /// at `source_address`; the node rescans only if the key is new to it
+    async fn import_viewing_key(&self) -> Result<()> {
+        let Some(params) = self.import_viewing_key_params() else {
+            return Ok(());
+        };
+        
+        match self.rpc_call("z_importviewingkey", params).await {
+            Ok(_) => info!("Imported viewing key for {}", self.config.source_address),
+            // The wallet already holds the spending key for this address
+            Err(ZcashRpcError::Other { code: -4, message }) => {
+                debug!("Viewing key not imported: {}", message)
+            }
+            Err(e) => return Err(anyhow::Error::new(e).context("Failed to import viewing key")),
+        }
+        Ok(())
+    }
+    
+    /// `z_importviewingkey` arguments, if a viewing key is configured
+    fn import_viewing_key_params(&self) -> Option<Vec<Value>> {
+        let viewing_key = self.config.viewing_key.as_ref()?;
+        Some(vec![json!(viewing_key), json!("whenkeyisnew")])
+    }
+    
     /// Get blockchain info
     pub async fn get_blockchain_info(&self) -> Result<BlockchainInfo> {
         let response: Value = self.rpc_call("getblockchaininfo", vec![]).await?;
@@ -100,26 +229,24 @@ impl ZcashClient {
     ) -> Result<String> {
         debug!("Sending shielded transaction: to={}, amount={}", to_address, amount);
         
-        // Convert amount to ZAT (1 ZEC = 100,000,000 ZAT)
-        let amount_decimal = amount as f64 / 100_000_000.0;
-        
-        let mut params = vec![
-            json!("ANY_TADDR"), // From any transparent or shielded address
-            json!([{
-                "address": to_address,
-                "amount": amount_decimal
-            }])
-        ];
-        
-        // Add memo if provided
-        if let Some(memo_bytes) = memo {
-            let memo_hex = hex::encode(memo_bytes);
-            params.push(json!({
-                "memo": memo_hex
-            }));
-        }
+        let params = self.sendmany_params(to_address, amount, memo);
         
-        let response: Value = self.rpc_call("z_sendmany", params).await?;
+        // Retry only while the node reports it is warming up/syncing. A lost
+        // response may mean the send went through, so transport errors are
+        // not retried; anything else (e.g. insufficient funds) won't fix
+        // itself.
+        let mut attempt = 0;
+        let response: Value = loop {
+            match self.rpc_call("z_sendmany", params.clone()).await {
+                Ok(response) => break response,
+                Err(e) if e.is_not_ready() && attempt < TRANSIENT_RETRIES => {
+                    attempt += 1;
+                    warn!("z_sendmany failed ({}), retry {}/{}", e, attempt, TRANSIENT_RETRIES);
+                    tokio::time::sleep(Duration::from_secs(2 * attempt as u64)).await;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        };
         let opid = response.as_str()
             .context("Invalid operation ID")?;
         
@@ -130,13 +257,42 @@ impl ZcashClient {
         Ok(txid)
     }
     
+    /// `z_sendmany` arguments: spend from the configured shielded address
+    /// with the configured fee and privacy policy. `amount` is in zatoshis.
+    fn sendmany_params(&self, to_address: &str, amount: u64, memo: Option<&[u8]>) -> Vec<Value> {
+        let mut recipient = json!({
+            "address": to_address,
+            "amount": zat_to_zec(amount)
+        });
+        if let Some(memo_bytes) = memo {
+            recipient["memo"] = json!(hex::encode(memo_bytes));
+        }
+        
+        vec![
+            json!(self.config.source_address),
+            json!([recipient]),
+            json!(SENDMANY_MINCONF),
+            // null: node computes the ZIP-317 fee
+            json!(self.config.fee.map(zat_to_zec)),
+            json!(self.config.privacy_policy),
+        ]
+    }
+    
     /// Wait for async operation to complete
     async fn wait_for_operation(&self, opid: &str) -> Result<String> {
         for _ in 0..60 {
-            let response: Value = self.rpc_call(
+            let response: Value = match self.rpc_call(
                 "z_getoperationstatus",
                 vec![json!([opid])]
-            ).await?;
+            ).await {
+                Ok(response) => response,
+                Err(e) if e.is_transient() => {
+                    debug!("Operation status unavailable ({}), polling again", e);
+                    tokio::time::sleep(Duration::from_secs(2)).await;
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
             
             let status = &response[0];
             
@@ -149,10 +305,8 @@ impl ZcashClient {
                         return Ok(txid.to_string());
                     }
                     "failed" => {
-                        let error = status["error"]["message"]
-                            .as_str()
-                            .unwrap_or("Unknown error");
-                        anyhow::bail!("Operation failed: {}", error);
+                        let error = ZcashRpcError::from_rpc_error(&status["error"]);
+                        return Err(anyhow::Error::new(error).context("Operation failed"));
                     }
                     "executing" | "queued" => {
                         // Still processing
@@ -169,35 +323,33 @@ impl ZcashClient {
         anyhow::bail!("Operation timeout")
     }
     
-    /// Wait for transaction confirmation
-    pub async fn wait_for_confirmation(
+    /// The transaction (raw) if it has reached `confirmations`, `None` if
+    /// not yet. Checks once without waiting: callers keep the txid and ask
+    /// again on a later pass.
+    pub async fn check_confirmation(
         &self,
         txid: &str,
         confirmations: u32,
-    ) -> Result<Value> {
-        debug!("Waiting for {} confirmations of {}", confirmations, txid);
-        
-        for _ in 0..120 {
-            match self.get_transaction(txid).await {
-                Ok(tx_info) => {
-                    if tx_info.confirmations >= confirmations {
-                        info!("Transaction {} confirmed with {} confirmations",
-                            txid, tx_info.confirmations);
-                        
-                        // Get raw transaction for details
-                        let raw_tx = self.get_raw_transaction(txid).await?;
-                        return Ok(raw_tx);
-                    }
-                }
-                Err(e) => {
-                    debug!("Transaction not yet in mempool: {}", e);
-                }
+    ) -> Result<Option<Value>> {
+        match self.get_transaction(txid).await {
+            Ok(tx_info) if tx_info.confirmations >= confirmations => {
+                info!("Transaction {} confirmed with {} confirmations",
+                    txid, tx_info.confirmations);
+                
+                // Get raw transaction for details
+                let raw_tx = self.get_raw_transaction(txid).await?;
+                Ok(Some(raw_tx))
+            }
+            Ok(tx_info) => {
+                debug!("Transaction {} has {}/{} confirmations",
+                    txid, tx_info.confirmations, confirmations);
+                Ok(None)
+            }
+            Err(e) => {
+                debug!("Transaction not yet in mempool: {}", e);
+                Ok(None)
             }
-            
-            tokio::time::sleep(Duration::from_secs(5)).await;
         }
-        
-        anyhow::bail!("Transaction confirmation timeout")
     }
     
     /// Get transaction info
@@ -259,7 +411,9 @@ impl ZcashClient {
     }
     
     /// Make RPC call to Zcash node
-    async fn rpc_call(&self, method: &str, params: Vec<Value>) -> Result<Value> {
+    async fn rpc_call(&self, method: &str, params: Vec<Value>) -> std::result::Result<Value, ZcashRpcError> {
+        let _timer = metrics::ZCASH_RPC_SECONDS.with_label_values(&[method]).start_timer();
+        
         let payload = json!({
             "jsonrpc": "2.0",
             "id": "zerobridge",
@@ -273,24 +427,20 @@ impl ZcashClient {
             .json(&payload)
             .send()
             .await
-            .context("RPC request failed")?;
-        
-        if !response.status().is_success() {
-            anyhow::bail!("RPC error: {}", response.status());
-        }
+            .map_err(|e| ZcashRpcError::Transport(format!("RPC request failed: {}", e)))?;
         
-        let json: Value = response.json().await?;
-        
-        if let Some(error) = json.get("error").and_then(|e| e.as_object()) {
-            let message = error.get("message")
-                .and_then(|m| m.as_str())
-                .unwrap_or("Unknown error");
-            anyhow::bail!("RPC error: {}", message);
-        }
+        // zcashd answers errors with a non-2xx status and a JSON-RPC body,
+        // so only treat the status as fatal when there is no body to parse
+        let status = response.status();
+        let json: Value = match response.json().await {
+            Ok(json) => json,
+            Err(_) if !status.is_success() => {
+                return Err(ZcashRpcError::Transport(format!("RPC error: {}", status)));
+            }
+            Err(e) => return Err(ZcashRpcError::Transport(format!("Invalid RPC response: {}", e))),
+        };
         
-        json.get("result")
-            .cloned()
-            .context("No result in RPC response")
+        parse_rpc_response(json)
     }
     
     #[cfg(test)]
@@ -303,6 +453,10 @@ impl ZcashClient {
                 rpc_user: "test".to_string(),
                 rpc_password: "test".to_string(),
                 spending_key: "test".to_string(),
+                source_address: "ztestsapling1coordinator".to_string(),
+                viewing_key: Some("zxviewtestsapling1coordinator".to_string()),
+                fee: None,
+                privacy_policy: crate::config::PrivacyPolicy::FullPrivacy,
                 confirmations: 1,
                 enable_orchard: true,
                 enable_sapling: true,
@@ -320,4 +474,172 @@ mod tests {
         let client = ZcashClient::mock();
         assert_eq!(client.config.network, crate::config::ZcashNetwork::Testnet);
     }
+
+    #[test]
+    fn test_sendmany_params() {
+        let client = ZcashClient::mock();
+        let params = client.sendmany_params("zs1recipient", 150_000_000, Some(&[0xab, 0xcd]));
+
+        assert_eq!(params[0], json!("ztestsapling1coordinator"));
+        assert_eq!(params[1], json!([{ "address": "zs1recipient", "amount": 1.5, "memo": "abcd" }]));
+        assert_eq!(params[2], json!(10));
+        // No fee configured: node applies ZIP-317
+        assert_eq!(params[3], Value::Null);
+        assert_eq!(params[4], json!("FullPrivacy"));
+
+        let mut client = ZcashClient::mock();
+        client.config.fee = Some(15_000);
+        client.config.privacy_policy = crate::config::PrivacyPolicy::AllowRevealedAmounts;
+        let params = client.sendmany_params("zs1recipient", 1, None);
+
+        assert_eq!(params[1], json!([{ "address": "zs1recipient", "amount": 0.00000001 }]));
+        assert_eq!(params[3], json!(0.00015));
+        assert_eq!(params[4], json!("AllowRevealedAmounts"));
+    }
+
+    #[test]
+    fn test_rpc_error_codes_mapped() {
+        let error = |code: i64, message: &str| json!({
+            "result": null,
+            "error": { "code": code, "message": message },
+            "id": "zerobridge"
+        });
+
+        let err = parse_rpc_response(error(-6, "Insufficient funds")).unwrap_err();
+        assert!(matches!(err, ZcashRpcError::InsufficientFunds(_)));
+        assert!(!err.is_transient());
+
+        let err = parse_rpc_response(error(-28, "Loading block index...")).unwrap_err();
+        assert!(matches!(err, ZcashRpcError::WarmingUp(_)));
+        assert!(err.is_transient());
+
+        let err = parse_rpc_response(error(-10, "Zcash is downloading blocks...")).unwrap_err();
+        assert!(matches!(err, ZcashRpcError::Syncing(_)));
+        assert!(err.is_transient());
+        assert!(err.is_not_ready());
+
+        // Reads retry a lost response; sends must not
+        let err = parse_rpc_response(json!({ "id": "zerobridge" })).unwrap_err();
+        assert!(matches!(err, ZcashRpcError::Transport(_)));
+        assert!(err.is_transient());
+        assert!(!err.is_not_ready());
+
+        let err = parse_rpc_response(error(-32601, "Method not found")).unwrap_err();
+        assert!(matches!(err, ZcashRpcError::MethodNotFound(_)));
+        assert!(!err.is_transient());
+
+        let err = parse_rpc_response(error(-5, "Invalid address")).unwrap_err();
+        assert!(matches!(err, ZcashRpcError::InvalidParameter(_)));
+
+        let err = parse_rpc_response(error(-1, "boom")).unwrap_err();
+        assert!(matches!(err, ZcashRpcError::Other { code: -1, .. }));
+        assert_eq!(err.to_string(), "RPC error -1: boom");
+    }
+
+    #[test]
+    fn test_rpc_success_and_missing_result() {
+        let ok = json!({ "result": "opid-123", "error": null, "id": "zerobridge" });
+        assert_eq!(parse_rpc_response(ok).unwrap(), json!("opid-123"));
+
+        let err = parse_rpc_response(json!({ "id": "zerobridge" })).unwrap_err();
+        assert!(matches!(err, ZcashRpcError::Transport(_)));
+    }
+
+    /// Local zcashd stand-in answering each method with `respond` and
+    /// recording every request
+    async fn mock_node<F>(respond: F) -> (String, std::sync::Arc<std::sync::Mutex<Vec<Value>>>)
+    where
+        F: Fn(&str) -> Value + Clone + Send + Sync + 'static,
+    {
+        use axum::{routing::post, Json, Router};
+
+        let requests = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+        let app = Router::new().route(
+            "/",
+            post(move |Json(request): Json<Value>| {
+                recorded.lock().unwrap().push(request.clone());
+                let result = respond(request["method"].as_str().unwrap());
+                async move { Json(json!({ "result": result, "error": null, "id": request["id"] })) }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (url, requests)
+    }
+
+    #[tokio::test]
+    async fn test_viewing_key_and_source_address_reach_node() {
+        let note = |txid: &str, amount: u64| json!({
+            "txid": txid,
+            "amount": zat_to_zec(amount),
+            "amountZat": amount,
+            "memo": "f6",
+            "outindex": 0,
+            "confirmations": 3,
+            "change": false
+        });
+        let (url, requests) = mock_node(move |method| match method {
+            "z_listreceivedbyaddress" => json!([note("tx1", 5_000), note("tx2", 7_000)]),
+            _ => Value::Null,
+        })
+        .await;
+        let mut client = ZcashClient::mock();
+        client.config.rpc_url = url;
+
+        client.import_viewing_key().await.unwrap();
+        let received = client.received_in("tx1", 3).await.unwrap();
+        assert_eq!(received, vec![ReceivedNote {
+            txid: "tx1".to_string(),
+            amount: 5_000,
+            memo: Some("f6".to_string()),
+            change: false,
+        }]);
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests[0]["method"], "z_importviewingkey");
+        assert_eq!(requests[0]["params"], json!(["zxviewtestsapling1coordinator", "whenkeyisnew"]));
+        assert_eq!(requests[1]["method"], "z_listreceivedbyaddress");
+        assert_eq!(requests[1]["params"], json!(["ztestsapling1coordinator", 3]));
+
+        // Nothing to import without a viewing key
+        client.config.viewing_key = None;
+        assert!(client.import_viewing_key_params().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_check_confirmation() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+        use std::sync::Arc;
+
+        // One more confirmation per check
+        let checks = Arc::new(AtomicU32::new(0));
+        let counter = checks.clone();
+        let (url, requests) = mock_node(move |method| match method {
+            "gettransaction" => json!({
+                "txid": "tx1",
+                "confirmations": counter.fetch_add(1, Ordering::SeqCst),
+                "blockhash": null,
+                "blockindex": null,
+            }),
+            "getrawtransaction" => json!({ "txid": "tx1" }),
+            _ => Value::Null,
+        })
+        .await;
+        let mut client = ZcashClient::mock();
+        client.config.rpc_url = url;
+
+        // Not there yet: one request, no waiting, not an error
+        for _ in 0..3 {
+            assert_eq!(client.check_confirmation("tx1", 3).await.unwrap(), None);
+        }
+        assert_eq!(checks.load(Ordering::SeqCst), 3);
+        assert_eq!(requests.lock().unwrap().len(), 3);
+
+        let raw = client.check_confirmation("tx1", 3).await.unwrap();
+        assert_eq!(raw, Some(json!({ "txid": "tx1" })));
+        assert_eq!(checks.load(Ordering::SeqCst), 4);
+        assert_eq!(requests.lock().unwrap().last().unwrap()["method"], "getrawtransaction");
+    }
 }
\ No newline at end of file
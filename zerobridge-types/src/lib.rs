@@ -0,0 +1,629 @@
+// zerobridge-types/src/lib.rs
+//! Messages exchanged between the coordinator and relayers
+//!
+//! Both binaries serialize these exact structs, in JSON or bincode. Bincode
+//! isn't self-describing, so a field added on one side only would corrupt
+//! every body; keeping a single definition here makes that impossible. The
+//! authorization hashes live here for the same reason: the relayer submits
+//! what the coordinator computed, and the gateways check it byte for byte.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+use std::fmt;
+
+/// Canonical token identifier (chain-agnostic)
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CanonicalTokenId(pub String);
+
+/// Deposit seen on a source gateway, reported by a relayer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DepositNotification {
+    pub deposit_id: String,
+    pub source_chain_id: u64,
+    pub target_chain_id: u64,
+    pub sender: String,
+    pub token: String,
+    pub amount: u64,
+    #[serde(with = "base64_bytes")]
+    pub recipient: Vec<u8>,
+    #[serde(with = "base64_bytes")]
+    pub zcash_address: Vec<u8>,
+    pub timestamp: u64,
+    /// Optional ordered destination preferences (e.g. "USDC on any L2");
+    /// overrides `target_chain_id` when non-empty
+    #[serde(default)]
+    pub target_chain_preferences: Vec<u64>,
+    /// Source-chain inclusion block; the coordinator waits for the chain's
+    /// configured confirmations past it before acting
+    #[serde(default)]
+    pub block_number: Option<u64>,
+    /// Source-chain transaction that emitted the deposit. Each transaction
+    /// is accepted once, and checked on-chain before the deposit is processed.
+    pub tx_hash: String,
+}
+
+/// Shielded withdrawal a relayer asks the coordinator to authorize
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WithdrawalNotification {
+    pub withdrawal_id: String,
+    pub target_chain_id: u64,
+    pub recipient: String,
+    /// Chain-agnostic token; the coordinator resolves the target address
+    pub canonical_token_id: CanonicalTokenId,
+    pub amount: u64,
+    #[serde(with = "base64_bytes")]
+    pub nullifier: Vec<u8>,
+    #[serde(with = "base64_bytes")]
+    pub zcash_proof: Vec<u8>,
+    #[serde(with = "base64_bytes")]
+    pub merkle_root: Vec<u8>,
+}
+
+/// Withdrawal the coordinator verified, ready for a relayer to execute
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuthorizedWithdrawal {
+    pub withdrawal_id: String,
+    pub target_chain_id: u64,
+    pub recipient: String,
+    pub token: String,
+    pub amount: u64,
+    #[serde(with = "base64_bytes")]
+    pub nullifier: Vec<u8>,
+    /// `withdrawal_authorization_hash` of the fields above and `deadline`
+    #[serde(with = "base64_bytes")]
+    pub authorization_signature: Vec<u8>,
+    /// When the coordinator authorized the withdrawal (unix seconds)
+    #[serde(default)]
+    pub timestamp: u64,
+    /// Fee the executing relayer earns, in destination token units
+    #[serde(default)]
+    pub relayer_fee: u64,
+    /// Last unix second the target gateway accepts the signature. Once it
+    /// has passed, the coordinator releases the withdrawal's liquidity.
+    pub deadline: u64,
+}
+
+/// Coordinator-authorized refund of a deposit that was never routed,
+/// executed on the deposit's source gateway
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RefundAuthorization {
+    pub deposit_id: String,
+    pub source_chain_id: u64,
+    pub sender: String,
+    pub token: String,
+    pub amount: u64,
+    /// `refund_authorization_hash` of the fields above
+    #[serde(with = "base64_bytes")]
+    pub authorization_signature: Vec<u8>,
+}
+
+/// A coordinator signing key and when it was in use (unix seconds)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoordinatorKey {
+    /// Compressed SEC1 secp256k1 public key, hex-encoded
+    pub public_key: String,
+    /// Ethereum address of `public_key`
+    pub address: String,
+    pub activated_at: i64,
+    /// When the key was replaced; `None` while it is current
+    #[serde(default)]
+    pub retired_at: Option<i64>,
+    /// Until when authorizations signed with a retired key are still
+    /// accepted, so those issued before the rotation can be executed
+    #[serde(default)]
+    pub valid_until: Option<i64>,
+}
+
+/// Key the coordinator currently signs authorizations with, and every key
+/// it used before (`GET /coordinator/pubkey`). Authorizations verify against
+/// the current key or a retired one still within its `valid_until`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoordinatorPubkey {
+    pub public_key: String,
+    pub address: String,
+    /// Oldest first, including the current key
+    pub history: Vec<CoordinatorKey>,
+}
+
+/// How a gateway rebuilds the message a coordinator authorization signs.
+/// The coordinator signs exactly that message, and relayers verify against
+/// it, so a signature is only ever valid on the gateway it was issued for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthorizationScheme {
+    /// `EVMGateway.sol`: `keccak256(abi.encodePacked(..., block.chainid))`
+    /// under the Ethereum signed-message prefix
+    Evm { chain_id: u64 },
+    /// Solana gateway: `keccak256` over the raw fields, amounts
+    /// little-endian, accounts as 32-byte public keys. Signatures carry a
+    /// 0/1 recovery id rather than Ethereum's 27/28.
+    Solana,
+    /// NEAR gateway: `keccak256` over the ID and recipient account as given,
+    /// the amount as a little-endian `u128`, the nullifier as lowercase hex
+    /// and the deadline little-endian. Signatures carry a 0/1 recovery id,
+    /// like Solana's. The gateway
+    /// has no refund path, so refunds use the `Unchecked` message.
+    Near,
+    /// Osmosis gateway: `sha256` over the ID, recipient and token as given,
+    /// the amount and deadline in decimal and the nullifier as lowercase
+    /// hex. Signatures carry a 0/1 recovery id. The gateway has no refund
+    /// path either.
+    Osmosis,
+    /// Gateways that don't check authorizations on-chain yet: `sha256` over
+    /// the fields as given
+    Unchecked,
+}
+
+/// A field that can't be encoded the way its gateway expects, e.g. an EVM
+/// address that isn't 20 bytes of hex
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidAuthorizationField {
+    pub field: &'static str,
+    pub value: String,
+}
+
+impl fmt::Display for InvalidAuthorizationField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid {} for the gateway's authorization: {}", self.field, self.value)
+    }
+}
+
+impl std::error::Error for InvalidAuthorizationField {}
+
+/// Prefix `EVMGateway` signs under (`MessageHashUtils.toEthSignedMessageHash`)
+const ETH_SIGNED_MESSAGE_PREFIX: &[u8] = b"\x19Ethereum Signed Message:\n32";
+
+/// Message the coordinator authorizes a withdrawal with, as the target
+/// gateway rebuilds it. The Solana and NEAR gateways check the token
+/// against what they already hold, so their messages leave `token` out.
+/// Every gateway refuses the authorization after `deadline` (unix seconds).
+pub fn withdrawal_authorization_hash(
+    scheme: AuthorizationScheme,
+    withdrawal_id: &str,
+    recipient: &str,
+    token: &str,
+    amount: u64,
+    nullifier: &[u8],
+    deadline: u64,
+) -> Result<[u8; 32], InvalidAuthorizationField> {
+    Ok(match scheme {
+        AuthorizationScheme::Evm { chain_id } => {
+            let mut hasher = Keccak256::new();
+            hasher.update(bytes32("withdrawal_id", withdrawal_id)?);
+            hasher.update(evm_address("recipient", recipient)?);
+            hasher.update(evm_address("token", token)?);
+            hasher.update(uint256(amount));
+            hasher.update(nullifier32(nullifier)?);
+            hasher.update(uint256(deadline));
+            hasher.update(uint256(chain_id));
+            eth_signed_message(hasher.finalize().into())
+        }
+        AuthorizationScheme::Solana => {
+            let mut hasher = Keccak256::new();
+            hasher.update(bytes32("withdrawal_id", withdrawal_id)?);
+            hasher.update(solana_pubkey("recipient", recipient)?);
+            hasher.update(amount.to_le_bytes());
+            hasher.update(nullifier32(nullifier)?);
+            hasher.update(deadline.to_le_bytes());
+            hasher.finalize().into()
+        }
+        AuthorizationScheme::Near => {
+            let mut hasher = Keccak256::new();
+            hasher.update(withdrawal_id.as_bytes());
+            hasher.update(recipient.as_bytes());
+            hasher.update(u128::from(amount).to_le_bytes());
+            hasher.update(hex::encode(nullifier32(nullifier)?));
+            hasher.update(deadline.to_le_bytes());
+            hasher.finalize().into()
+        }
+        AuthorizationScheme::Osmosis => {
+            let mut hasher = Sha256::new();
+            hasher.update(withdrawal_id.as_bytes());
+            hasher.update(recipient.as_bytes());
+            hasher.update(token.as_bytes());
+            hasher.update(amount.to_string());
+            hasher.update(hex::encode(nullifier32(nullifier)?));
+            hasher.update(deadline.to_string());
+            hasher.finalize().into()
+        }
+        AuthorizationScheme::Unchecked => {
+            let mut hasher = Sha256::new();
+            hasher.update(withdrawal_id.as_bytes());
+            hasher.update(recipient.as_bytes());
+            hasher.update(token.as_bytes());
+            hasher.update(amount.to_le_bytes());
+            hasher.update(nullifier);
+            hasher.update(deadline.to_le_bytes());
+            hasher.finalize().into()
+        }
+    })
+}
+
+/// Message the coordinator authorizes a refund with, as the deposit's
+/// source gateway rebuilds it. Domain-separated from withdrawal
+/// authorizations by a `REFUND` prefix.
+pub fn refund_authorization_hash(
+    scheme: AuthorizationScheme,
+    deposit_id: &str,
+    sender: &str,
+    token: &str,
+    amount: u64,
+) -> Result<[u8; 32], InvalidAuthorizationField> {
+    Ok(match scheme {
+        AuthorizationScheme::Evm { chain_id } => {
+            let mut hasher = Keccak256::new();
+            hasher.update(b"REFUND");
+            hasher.update(bytes32("deposit_id", deposit_id)?);
+            hasher.update(evm_address("sender", sender)?);
+            hasher.update(evm_address("token", token)?);
+            hasher.update(uint256(amount));
+            hasher.update(uint256(chain_id));
+            eth_signed_message(hasher.finalize().into())
+        }
+        AuthorizationScheme::Solana => {
+            let mut hasher = Keccak256::new();
+            hasher.update(b"REFUND");
+            hasher.update(bytes32("deposit_id", deposit_id)?);
+            hasher.update(solana_pubkey("sender", sender)?);
+            hasher.update(solana_pubkey("token", token)?);
+            hasher.update(amount.to_le_bytes());
+            hasher.finalize().into()
+        }
+        AuthorizationScheme::Near | AuthorizationScheme::Osmosis | AuthorizationScheme::Unchecked => {
+            let mut hasher = Sha256::new();
+            hasher.update(b"REFUND");
+            hasher.update(deposit_id.as_bytes());
+            hasher.update(sender.as_bytes());
+            hasher.update(token.as_bytes());
+            hasher.update(amount.to_le_bytes());
+            hasher.finalize().into()
+        }
+    })
+}
+
+fn eth_signed_message(message_hash: [u8; 32]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(ETH_SIGNED_MESSAGE_PREFIX);
+    hasher.update(message_hash);
+    hasher.finalize().into()
+}
+
+/// `uint256` as `abi.encodePacked` lays it out: 32 bytes, big-endian
+fn uint256(value: u64) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+fn invalid(field: &'static str, value: &str) -> InvalidAuthorizationField {
+    InvalidAuthorizationField { field, value: value.to_string() }
+}
+
+fn hex_array<const N: usize>(field: &'static str, value: &str) -> Result<[u8; N], InvalidAuthorizationField> {
+    let hex = value.strip_prefix("0x").unwrap_or(value);
+    hex::decode(hex)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| invalid(field, value))
+}
+
+/// Deposit and withdrawal IDs: 32 bytes of hex, `0x` optional
+fn bytes32(field: &'static str, value: &str) -> Result<[u8; 32], InvalidAuthorizationField> {
+    hex_array(field, value)
+}
+
+fn evm_address(field: &'static str, value: &str) -> Result<[u8; 20], InvalidAuthorizationField> {
+    hex_array(field, value)
+}
+
+fn nullifier32(nullifier: &[u8]) -> Result<[u8; 32], InvalidAuthorizationField> {
+    nullifier
+        .try_into()
+        .map_err(|_| invalid("nullifier", &hex::encode(nullifier)))
+}
+
+/// Base58 Solana public key
+fn solana_pubkey(field: &'static str, value: &str) -> Result<[u8; 32], InvalidAuthorizationField> {
+    bs58::decode(value)
+        .into_vec()
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| invalid(field, value))
+}
+
+/// `#[serde(with = "base64_bytes")]` for byte fields: a base64 string in
+/// JSON, unchanged in binary encodings
+pub mod base64_bytes {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&STANDARD.encode(bytes))
+        } else {
+            bytes.serialize(serializer)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        if deserializer.is_human_readable() {
+            let encoded = String::deserialize(deserializer)?;
+            STANDARD.decode(encoded).map_err(D::Error::custom)
+        } else {
+            Vec::deserialize(deserializer)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // These pin the wire format both binaries depend on. Changing an
+    // expectation changes what deployed coordinators and relayers exchange,
+    // so both must be upgraded together.
+
+    fn deposit() -> DepositNotification {
+        DepositNotification {
+            deposit_id: "d1".to_string(),
+            source_chain_id: 1,
+            target_chain_id: 8453,
+            sender: "0xsender".to_string(),
+            token: "0xtoken".to_string(),
+            amount: 1_000_000,
+            recipient: vec![1, 2, 3],
+            zcash_address: vec![4, 5, 6],
+            timestamp: 1_700_000_000,
+            target_chain_preferences: vec![10, 8453],
+            block_number: Some(42),
+            tx_hash: "0xtx".to_string(),
+        }
+    }
+
+    fn authorized() -> AuthorizedWithdrawal {
+        AuthorizedWithdrawal {
+            withdrawal_id: "w1".to_string(),
+            target_chain_id: 8453,
+            recipient: "0xrecipient".to_string(),
+            token: "0xtoken".to_string(),
+            amount: 1_000_000,
+            nullifier: vec![0xfe; 4],
+            authorization_signature: vec![0xaa; 4],
+            timestamp: 1_700_000_000,
+            relayer_fee: 250,
+            deadline: DEADLINE,
+        }
+    }
+
+    #[test]
+    fn test_deposit_notification_shape() {
+        let deposit = deposit();
+        assert_eq!(
+            serde_json::to_string(&deposit).unwrap(),
+            concat!(
+                r#"{"deposit_id":"d1","source_chain_id":1,"target_chain_id":8453,"#,
+                r#""sender":"0xsender","token":"0xtoken","amount":1000000,"#,
+                r#""recipient":"AQID","zcash_address":"BAUG","timestamp":1700000000,"#,
+                r#""target_chain_preferences":[10,8453],"block_number":42,"tx_hash":"0xtx"}"#,
+            )
+        );
+        assert_eq!(
+            hex::encode(bincode::serialize(&deposit).unwrap()),
+            DEPOSIT_BINCODE
+        );
+    }
+
+    #[test]
+    fn test_withdrawal_shapes() {
+        let notification = WithdrawalNotification {
+            withdrawal_id: "w1".to_string(),
+            target_chain_id: 8453,
+            recipient: "0xrecipient".to_string(),
+            canonical_token_id: CanonicalTokenId("ab".repeat(4)),
+            amount: 1_000_000,
+            nullifier: vec![0xfe; 4],
+            zcash_proof: vec![0xff; 3],
+            merkle_root: vec![0x11; 3],
+        };
+        assert_eq!(
+            serde_json::to_string(&notification).unwrap(),
+            concat!(
+                r#"{"withdrawal_id":"w1","target_chain_id":8453,"recipient":"0xrecipient","#,
+                r#""canonical_token_id":"abababab","amount":1000000,"nullifier":"/v7+/g==","#,
+                r#""zcash_proof":"////","merkle_root":"ERER"}"#,
+            )
+        );
+
+        assert_eq!(
+            serde_json::to_string(&authorized()).unwrap(),
+            concat!(
+                r#"{"withdrawal_id":"w1","target_chain_id":8453,"recipient":"0xrecipient","#,
+                r#""token":"0xtoken","amount":1000000,"nullifier":"/v7+/g==","#,
+                r#""authorization_signature":"qqqqqg==","timestamp":1700000000,"relayer_fee":250,"#,
+                r#""deadline":1700003600}"#,
+            )
+        );
+        assert_eq!(
+            hex::encode(bincode::serialize(&authorized()).unwrap()),
+            AUTHORIZED_BINCODE
+        );
+    }
+
+    #[test]
+    fn test_optional_fields_default() {
+        let json = r#"{"deposit_id":"d1","source_chain_id":1,"target_chain_id":8453,
+            "sender":"s","token":"t","amount":1,"recipient":"","zcash_address":"",
+            "timestamp":0,"tx_hash":"0xtx"}"#;
+        let deposit: DepositNotification = serde_json::from_str(json).unwrap();
+        assert!(deposit.target_chain_preferences.is_empty());
+        assert_eq!(deposit.block_number, None);
+
+        let json = r#"{"withdrawal_id":"w1","target_chain_id":1,"recipient":"r","token":"t",
+            "amount":1,"nullifier":"","authorization_signature":"","deadline":0}"#;
+        let withdrawal: AuthorizedWithdrawal = serde_json::from_str(json).unwrap();
+        assert_eq!(withdrawal.timestamp, 0);
+        assert_eq!(withdrawal.relayer_fee, 0);
+    }
+
+    #[test]
+    fn test_authorization_hashes() {
+        let withdrawal = authorized();
+        assert_eq!(
+            hex::encode(withdrawal_authorization_hash(
+                AuthorizationScheme::Unchecked,
+                &withdrawal.withdrawal_id,
+                &withdrawal.recipient,
+                &withdrawal.token,
+                withdrawal.amount,
+                &withdrawal.nullifier,
+                withdrawal.deadline,
+            ).unwrap()),
+            WITHDRAWAL_HASH
+        );
+        assert_eq!(
+            hex::encode(refund_authorization_hash(
+                AuthorizationScheme::Unchecked, "d1", "0xsender", "0xtoken", 1_000_000
+            ).unwrap()),
+            REFUND_HASH
+        );
+    }
+
+    // Expected hashes below were computed independently of this crate from
+    // each gateway's own encoding (`EVMGateway._executeWithdrawal` and
+    // `refundDeposit`, Solana `verify_coordinator_signature` and
+    // `verify_refund_signature`, NEAR and Osmosis `withdrawal_message_hash`)
+
+    #[test]
+    fn test_evm_withdrawal_hash_matches_gateway() {
+        let evm = AuthorizationScheme::Evm { chain_id: 8453 };
+        let withdrawal_id = "22".repeat(32);
+        assert_eq!(
+            hex::encode(withdrawal_authorization_hash(
+                evm, &withdrawal_id, EVM_SENDER, EVM_USDC, 1_000_000, &[7u8; 32], DEADLINE
+            ).unwrap()),
+            "9bb5d5a58f371b2ca5277ad2a97ac14d5c708b16666b0ae8bb1a39021027a9ed"
+        );
+        assert_ne!(
+            withdrawal_authorization_hash(
+                AuthorizationScheme::Evm { chain_id: 1 }, &withdrawal_id, EVM_SENDER, EVM_USDC, 1_000_000, &[7u8; 32], DEADLINE
+            ),
+            withdrawal_authorization_hash(evm, &withdrawal_id, EVM_SENDER, EVM_USDC, 1_000_000, &[7u8; 32], DEADLINE)
+        );
+        // The gateway stores a bytes32 nullifier
+        let error = withdrawal_authorization_hash(evm, &withdrawal_id, EVM_SENDER, EVM_USDC, 1, &[7u8; 4], DEADLINE)
+            .unwrap_err();
+        assert_eq!(error.field, "nullifier");
+    }
+
+    #[test]
+    fn test_solana_withdrawal_hash_matches_gateway() {
+        let withdrawal_id = format!("0x{}", "22".repeat(32));
+        let hash = withdrawal_authorization_hash(
+            AuthorizationScheme::Solana, &withdrawal_id, SOLANA_SENDER, SOLANA_MINT, 1_000_000, &[7u8; 32], DEADLINE
+        ).unwrap();
+        assert_eq!(
+            hex::encode(hash),
+            "75866f36f94b6c9d4a9c747cd75be427aaffc2af5f0edf67565f9441da82eb2d"
+        );
+        // The mint isn't part of the Solana message
+        assert_eq!(
+            withdrawal_authorization_hash(
+                AuthorizationScheme::Solana, &withdrawal_id, SOLANA_SENDER, "", 1_000_000, &[7u8; 32], DEADLINE
+            ).unwrap(),
+            hash
+        );
+    }
+
+    #[test]
+    fn test_near_withdrawal_hash_matches_gateway() {
+        let withdrawal_id = "22".repeat(32);
+        let hash = withdrawal_authorization_hash(
+            AuthorizationScheme::Near, &withdrawal_id, "alice.near", "usdc.near", 1_000_000, &[7u8; 32], DEADLINE
+        ).unwrap();
+        assert_eq!(
+            hex::encode(hash),
+            "9d0fdaa58f9b955cb00d38853513b98a61823694587a725b730d15223c096597"
+        );
+        // The token isn't part of the NEAR message
+        assert_eq!(
+            withdrawal_authorization_hash(
+                AuthorizationScheme::Near, &withdrawal_id, "alice.near", "", 1_000_000, &[7u8; 32], DEADLINE
+            ).unwrap(),
+            hash
+        );
+    }
+
+    #[test]
+    fn test_osmosis_withdrawal_hash_matches_gateway() {
+        let withdrawal_id = "22".repeat(32);
+        assert_eq!(
+            hex::encode(withdrawal_authorization_hash(
+                AuthorizationScheme::Osmosis, &withdrawal_id, OSMOSIS_RECIPIENT, "uosmo", 1_000_000, &[7u8; 32], DEADLINE
+            ).unwrap()),
+            "462aa81424c5a00bc8b39c4828ea3313cf4a9f2dee4c7489512449af1108b0c3"
+        );
+    }
+
+    #[test]
+    fn test_refund_hashes_match_gateways() {
+        let deposit_id = format!("0x{}", "11".repeat(32));
+        assert_eq!(
+            hex::encode(refund_authorization_hash(
+                AuthorizationScheme::Evm { chain_id: 8453 },
+                &deposit_id,
+                EVM_SENDER,
+                EVM_USDC,
+                1_000_000,
+            ).unwrap()),
+            "982c78ee6293c4f9917bdb4ce96145bc6947af4c667a3e7215a4fea447532bbe"
+        );
+        assert_eq!(
+            hex::encode(refund_authorization_hash(
+                AuthorizationScheme::Solana,
+                &deposit_id[2..],
+                SOLANA_SENDER,
+                SOLANA_MINT,
+                1_000_000,
+            ).unwrap()),
+            "4ea21424405fd2f9cd492a9d39e2ad3467ca336c74f6067e5d975385dd86e3ef"
+        );
+
+        // Bound to the chain it was issued for
+        assert_ne!(
+            refund_authorization_hash(
+                AuthorizationScheme::Evm { chain_id: 1 }, &deposit_id, EVM_SENDER, EVM_USDC, 1_000_000
+            ),
+            refund_authorization_hash(
+                AuthorizationScheme::Evm { chain_id: 8453 }, &deposit_id, EVM_SENDER, EVM_USDC, 1_000_000
+            )
+        );
+    }
+
+    #[test]
+    fn test_fields_the_gateway_cant_encode_rejected() {
+        let evm = AuthorizationScheme::Evm { chain_id: 1 };
+        let deposit_id = "11".repeat(32);
+        let error = refund_authorization_hash(evm, "d1", EVM_SENDER, EVM_USDC, 1).unwrap_err();
+        assert_eq!(error.field, "deposit_id");
+        let error = refund_authorization_hash(evm, &deposit_id, SOLANA_SENDER, EVM_USDC, 1).unwrap_err();
+        assert_eq!(error.field, "sender");
+        let error = refund_authorization_hash(AuthorizationScheme::Solana, &deposit_id, SOLANA_SENDER, EVM_USDC, 1)
+            .unwrap_err();
+        assert_eq!(error.field, "token");
+    }
+
+    const DEADLINE: u64 = 1_700_003_600;
+    const EVM_SENDER: &str = "0x00000000000000000000000000000000000000aa";
+    const EVM_USDC: &str = "0x833589fcd6edb6e08f4c7c32d4f71b54bda02913";
+    const SOLANA_SENDER: &str = "4Nd1mBQtrMJVYVfKf2PJy9NZUZdTAsp7D4xWLs4gDB4T";
+    const SOLANA_MINT: &str = "So11111111111111111111111111111111111111112";
+    const OSMOSIS_RECIPIENT: &str = "osmo1l3e9pgs3mmwuwrh95fecme0s0qtn2880p3ptlt";
+
+    const DEPOSIT_BINCODE: &str =
+        "02000000000000006431010000000000000005210000000000000800000000000000307873656e64657207000000000000003078746f6b656e40420f00000000000300000000000000010203030000000000000004050600f153650000000002000000000000000a000000000000000521000000000000012a00000000000000040000000000000030787478";
+    const AUTHORIZED_BINCODE: &str =
+        "0200000000000000773105210000000000000b000000000000003078726563697069656e7407000000000000003078746f6b656e40420f00000000000400000000000000fefefefe0400000000000000aaaaaaaa00f1536500000000fa0000000000000010ff536500000000";
+    const WITHDRAWAL_HASH: &str =
+        "1eb9ef88ff1fd209ae9055aea9021a55b891d404b4f52bbed5a83b8d6f518250";
+    const REFUND_HASH: &str = "679ab20b79e63c64c009d9d6bf3178bb560c7fd67f0d69c2c5f72ebed3e8633a";
+}